@@ -0,0 +1,241 @@
+//! In-place editing of mutable syntax trees.
+//!
+//! Trees have to be made mutable with `SyntaxNode::clone_for_update` before
+//! they can be edited. Nodes passed in as replacements or insertions can come
+//! from anywhere (usually [`crate::make`]); they are copied into the tree.
+//!
+//! The parser attaches trivia to the end of the node preceding it, so the
+//! whitespace separating a node from whatever follows lives inside that node.
+//! The functions here move that trailing trivia around so edits keep the
+//! surrounding layout intact.
+
+use crate::make;
+use drip_syntax::{SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken};
+
+/// Replaces `old` with `new`, keeping the trivia that followed `old`.
+pub fn replace(old: &SyntaxNode, new: &SyntaxNode) -> SyntaxNode {
+    let parent = old.parent().expect("cannot replace the root node");
+    let new = prepare(new);
+    let trivia = detach_trailing_trivia(old);
+
+    let index = old.index();
+    parent.splice_children(index..index + 1, vec![new.clone().into()]);
+    append_tokens(&new, trivia);
+
+    new
+}
+
+/// Inserts `new` as the next sibling of `anchor`.
+///
+/// `new` is separated from `anchor` and from the element that follows it the
+/// same way `anchor` was separated from its successor.
+pub fn insert_after(anchor: &SyntaxNode, new: &SyntaxNode) -> SyntaxNode {
+    let parent = anchor
+        .parent()
+        .expect("cannot insert next to the root node");
+    let new = prepare(new);
+    let index = anchor.index() + 1;
+
+    match trailing_whitespace(anchor) {
+        Some(ws) => append_tokens(&new, vec![make::tokens::whitespace(&ws)]),
+        None => {
+            let ws = make::tokens::whitespace(default_separator(&parent));
+            append_tokens(anchor, vec![ws]);
+        }
+    }
+    parent.splice_children(index..index, vec![new.clone().into()]);
+
+    new
+}
+
+/// Inserts `new` as the previous sibling of `anchor`.
+pub fn insert_before(anchor: &SyntaxNode, new: &SyntaxNode) -> SyntaxNode {
+    let parent = anchor
+        .parent()
+        .expect("cannot insert next to the root node");
+    let new = prepare(new);
+    let index = anchor.index();
+
+    let ws = prev_sibling(anchor)
+        .and_then(|prev| trailing_whitespace(&prev))
+        .or_else(|| trailing_whitespace(anchor))
+        .unwrap_or_else(|| default_separator(&parent).to_string());
+
+    append_tokens(&new, vec![make::tokens::whitespace(&ws)]);
+    parent.splice_children(index..index, vec![new.clone().into()]);
+
+    new
+}
+
+/// Removes `node` along with its trailing trivia.
+///
+/// When the last node of a list is removed, its trailing trivia is given to
+/// the node before it, so e.g. the indentation of a closing `}` survives.
+pub fn remove(node: &SyntaxNode) {
+    if node.next_sibling().is_none() {
+        if let Some(prev) = prev_sibling(node) {
+            let trivia = detach_trailing_trivia(node);
+            if !trivia.is_empty() {
+                detach_trailing_trivia(&prev);
+                append_tokens(&prev, trivia);
+            }
+        }
+    }
+
+    node.detach();
+}
+
+fn prepare(node: &SyntaxNode) -> SyntaxNode {
+    node.clone_subtree().clone_for_update()
+}
+
+// `SyntaxNode::prev_sibling` doesn't find siblings in mutable trees
+fn prev_sibling(node: &SyntaxNode) -> Option<SyntaxNode> {
+    node.parent()?
+        .children()
+        .take_while(|sibling| sibling != node)
+        .last()
+}
+
+fn append_tokens(node: &SyntaxNode, tokens: Vec<SyntaxToken>) {
+    let tokens: Vec<SyntaxElement> = tokens.into_iter().map(Into::into).collect();
+    let end = node.children_with_tokens().count();
+    node.splice_children(end..end, tokens);
+}
+
+fn trailing_trivia(node: &SyntaxNode) -> Vec<SyntaxToken> {
+    let mut trivia = Vec::new();
+    let mut token = node.last_token();
+
+    while let Some(t) = token {
+        if !is_trivia(t.kind()) || t.text_range().start() < node.text_range().start() {
+            break;
+        }
+        token = t.prev_token();
+        trivia.push(t);
+    }
+
+    trivia.reverse();
+    trivia
+}
+
+fn detach_trailing_trivia(node: &SyntaxNode) -> Vec<SyntaxToken> {
+    let trivia = trailing_trivia(node);
+    for token in &trivia {
+        token.detach();
+    }
+    trivia
+}
+
+/// The last piece of whitespace in `node`'s trailing trivia, if any.
+fn trailing_whitespace(node: &SyntaxNode) -> Option<String> {
+    trailing_trivia(node)
+        .into_iter()
+        .rev()
+        .find(|token| token.kind() == SyntaxKind::Whitespace)
+        .map(|token| token.text().to_string())
+}
+
+fn default_separator(parent: &SyntaxNode) -> &'static str {
+    match parent.kind() {
+        SyntaxKind::Root
+        | SyntaxKind::FnBodyDef
+        | SyntaxKind::TraitFnListDef
+        | SyntaxKind::ImplItemListDef => "\n",
+        _ => " ",
+    }
+}
+
+fn is_trivia(kind: SyntaxKind) -> bool {
+    matches!(kind, SyntaxKind::Whitespace | SyntaxKind::Comment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{make, AstNode, Root, Stmt};
+
+    fn root(input: &str) -> Root {
+        let syntax = drip_parser::parse(input).syntax().clone_for_update();
+        Root::cast(syntax).unwrap()
+    }
+
+    fn nth_stmt(root: &Root, nth: usize) -> SyntaxNode {
+        root.stmts().nth(nth).unwrap().syntax().clone()
+    }
+
+    #[test]
+    fn replace_keeps_trailing_trivia() {
+        let root = root("a :: 1 + 2 // two\nb :: 3");
+        let two = root
+            .syntax()
+            .descendants()
+            .filter(|node| node.kind() == SyntaxKind::Literal)
+            .nth(1)
+            .unwrap();
+
+        replace(&two, make::variable_ref("x").syntax());
+
+        assert_eq!(root.syntax().to_string(), "a :: 1 + x // two\nb :: 3");
+    }
+
+    #[test]
+    fn insert_statement_after() {
+        let root = root("a :: 1\nc :: 3\n");
+        insert_after(
+            &nth_stmt(&root, 0),
+            make::const_def("b", make::literal(2).into()).syntax(),
+        );
+
+        assert_eq!(root.syntax().to_string(), "a :: 1\nb :: 2\nc :: 3\n");
+        assert_eq!(root.stmts().count(), 3);
+    }
+
+    #[test]
+    fn insert_statement_after_last_statement_without_newline() {
+        let root = root("a :: 1");
+        insert_after(
+            &nth_stmt(&root, 0),
+            make::const_def("b", make::literal(2).into()).syntax(),
+        );
+
+        assert_eq!(root.syntax().to_string(), "a :: 1\nb :: 2");
+    }
+
+    #[test]
+    fn insert_statement_before() {
+        let root = root("f :: () {\n    a\n    c\n}");
+        let body: Vec<_> = match root.stmts().next() {
+            Some(Stmt::FnDef(f)) => f.body().collect(),
+            _ => unreachable!(),
+        };
+
+        insert_before(body[1].syntax(), make::variable_ref("b").syntax());
+
+        assert_eq!(
+            root.syntax().to_string(),
+            "f :: () {\n    a\n    b\n    c\n}"
+        );
+    }
+
+    #[test]
+    fn remove_statement() {
+        let root = root("a :: 1\nb :: 2\nc :: 3");
+        remove(&nth_stmt(&root, 1));
+
+        assert_eq!(root.syntax().to_string(), "a :: 1\nc :: 3");
+    }
+
+    #[test]
+    fn remove_last_statement_keeps_closing_indentation() {
+        let root = root("f :: () {\n    a\n    b\n}");
+        let b = match root.stmts().next() {
+            Some(Stmt::FnDef(f)) => f.body().nth(1).unwrap(),
+            _ => unreachable!(),
+        };
+
+        remove(b.syntax());
+
+        assert_eq!(root.syntax().to_string(), "f :: () {\n    a\n}");
+    }
+}
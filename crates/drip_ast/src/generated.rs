@@ -0,0 +1,2747 @@
+//! Generated by `cargo run -p drip_codegen`, do not edit by hand.
+
+use crate::{support, AstChildren, AstNode, AstToken};
+use drip_syntax::{SyntaxKind, SyntaxNode, SyntaxToken};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Comment(pub(crate) SyntaxToken);
+
+impl AstToken for Comment {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Comment
+    }
+
+    fn cast(syntax: SyntaxToken) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxToken {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Whitespace(pub(crate) SyntaxToken);
+
+impl AstToken for Whitespace {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Whitespace
+    }
+
+    fn cast(syntax: SyntaxToken) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxToken {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Ident(pub(crate) SyntaxToken);
+
+impl AstToken for Ident {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Ident
+    }
+
+    fn cast(syntax: SyntaxToken) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxToken {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Number(pub(crate) SyntaxToken);
+
+impl AstToken for Number {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Number
+    }
+
+    fn cast(syntax: SyntaxToken) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxToken {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Str(pub(crate) SyntaxToken);
+
+impl AstToken for Str {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Str
+    }
+
+    fn cast(syntax: SyntaxToken) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxToken {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Root(pub(crate) SyntaxNode);
+
+impl Root {
+    pub fn stmts(&self) -> AstChildren<Stmt> {
+        support::children(&self.0)
+    }
+}
+
+impl AstNode for Root {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Root
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VariableDef(pub(crate) SyntaxNode);
+
+impl VariableDef {
+    pub fn name(&self) -> Option<Ident> {
+        support::token(&self.0, SyntaxKind::Ident).and_then(Ident::cast)
+    }
+
+    pub fn name_list(&self) -> Option<NameList> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn variable_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::VariableKw)
+    }
+
+    pub fn value(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+}
+
+impl AstNode for VariableDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::VariableDef
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConstDef(pub(crate) SyntaxNode);
+
+impl ConstDef {
+    pub fn name(&self) -> Option<Ident> {
+        support::token(&self.0, SyntaxKind::Ident).and_then(Ident::cast)
+    }
+
+    pub fn name_list(&self) -> Option<NameList> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn const_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::ConstKw)
+    }
+
+    pub fn value(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+}
+
+impl AstNode for ConstDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::ConstDef
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AssignDef(pub(crate) SyntaxNode);
+
+impl AssignDef {
+    pub fn target(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn op(&self) -> Option<SyntaxToken> {
+        support::token_in(
+            &self.0,
+            &[
+                SyntaxKind::Equals,
+                SyntaxKind::PlusEquals,
+                SyntaxKind::MinusEquals,
+                SyntaxKind::StarEquals,
+                SyntaxKind::SlashEquals,
+            ],
+        )
+    }
+
+    pub fn value(&self) -> Option<Expr> {
+        support::child(&self.0, 1)
+    }
+}
+
+impl AstNode for AssignDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::AssignDef
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FnDef(pub(crate) SyntaxNode);
+
+impl FnDef {
+    pub fn name(&self) -> Option<Ident> {
+        support::token(&self.0, SyntaxKind::Ident).and_then(Ident::cast)
+    }
+
+    pub fn const_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::ConstKw)
+    }
+
+    pub fn fn_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::FnKw)
+    }
+
+    pub fn l_round_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::LRoundBracket)
+    }
+
+    pub fn fn_param_list_def(&self) -> Option<FnParamListDef> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn r_round_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::RRoundBracket)
+    }
+
+    pub fn arrow_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Arrow)
+    }
+
+    pub fn fn_return_def(&self) -> Option<FnReturnDef> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn l_curly_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::LCurlyBracket)
+    }
+
+    pub fn fn_body_def(&self) -> Option<FnBodyDef> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn r_curly_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::RCurlyBracket)
+    }
+}
+
+impl AstNode for FnDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::FnDef
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StructDef(pub(crate) SyntaxNode);
+
+impl StructDef {
+    pub fn name(&self) -> Option<Ident> {
+        support::token(&self.0, SyntaxKind::Ident).and_then(Ident::cast)
+    }
+
+    pub fn const_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::ConstKw)
+    }
+
+    pub fn struct_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::StructKw)
+    }
+
+    pub fn inherit_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Inherit)
+    }
+
+    pub fn parent(&self) -> Option<Type> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn struct_field_list_def(&self) -> Option<StructFieldListDef> {
+        support::child(&self.0, 0)
+    }
+}
+
+impl AstNode for StructDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::StructDef
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TraitDef(pub(crate) SyntaxNode);
+
+impl TraitDef {
+    pub fn name(&self) -> Option<Ident> {
+        support::token(&self.0, SyntaxKind::Ident).and_then(Ident::cast)
+    }
+
+    pub fn const_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::ConstKw)
+    }
+
+    pub fn trait_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::TraitKw)
+    }
+
+    pub fn trait_lists_def(&self) -> Option<TraitListsDef> {
+        support::child(&self.0, 0)
+    }
+}
+
+impl AstNode for TraitDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::TraitDef
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ImplDef(pub(crate) SyntaxNode);
+
+impl ImplDef {
+    pub fn self_ty(&self) -> Option<Type> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn inherit_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Inherit)
+    }
+
+    pub fn trait_ty(&self) -> Option<Type> {
+        support::child(&self.0, 1)
+    }
+
+    pub fn const_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::ConstKw)
+    }
+
+    pub fn impl_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::ImplKw)
+    }
+
+    pub fn l_curly_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::LCurlyBracket)
+    }
+
+    pub fn impl_item_list_def(&self) -> Option<ImplItemListDef> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn r_curly_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::RCurlyBracket)
+    }
+}
+
+impl AstNode for ImplDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::ImplDef
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InfixExpr(pub(crate) SyntaxNode);
+
+impl InfixExpr {
+    pub fn lhs(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn op(&self) -> Option<SyntaxToken> {
+        support::token_in(
+            &self.0,
+            &[
+                SyntaxKind::Plus,
+                SyntaxKind::Minus,
+                SyntaxKind::Star,
+                SyntaxKind::Slash,
+                SyntaxKind::Equals2,
+                SyntaxKind::NotEquals,
+                SyntaxKind::LAngledBracket,
+                SyntaxKind::LessEquals,
+                SyntaxKind::RAngledBracket,
+                SyntaxKind::GreaterEquals,
+                SyntaxKind::Dot2,
+            ],
+        )
+    }
+
+    pub fn rhs(&self) -> Option<Expr> {
+        support::child(&self.0, 1)
+    }
+
+    pub fn equals_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Equals)
+    }
+
+    pub fn step(&self) -> Option<Expr> {
+        support::child(&self.0, 2)
+    }
+}
+
+impl AstNode for InfixExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::InfixExpr
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PrefixExpr(pub(crate) SyntaxNode);
+
+impl PrefixExpr {
+    pub fn op(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Minus)
+    }
+
+    pub fn expr(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+}
+
+impl AstNode for PrefixExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::PrefixExpr
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TernaryExpr(pub(crate) SyntaxNode);
+
+impl TernaryExpr {
+    pub fn condition(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn quest_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Quest)
+    }
+
+    pub fn then_branch(&self) -> Option<Expr> {
+        support::child(&self.0, 1)
+    }
+
+    pub fn colon_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Colon)
+    }
+
+    pub fn else_branch(&self) -> Option<Expr> {
+        support::child(&self.0, 2)
+    }
+}
+
+impl AstNode for TernaryExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::TernaryExpr
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Literal(pub(crate) SyntaxNode);
+
+impl Literal {
+    pub fn number(&self) -> Option<Number> {
+        support::token(&self.0, SyntaxKind::Number).and_then(Number::cast)
+    }
+
+    pub fn str(&self) -> Option<Str> {
+        support::token(&self.0, SyntaxKind::Str).and_then(Str::cast)
+    }
+
+    pub fn true_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::TrueKw)
+    }
+
+    pub fn false_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::FalseKw)
+    }
+}
+
+impl AstNode for Literal {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Literal
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RoundBracketExpr(pub(crate) SyntaxNode);
+
+impl RoundBracketExpr {
+    pub fn l_round_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::LRoundBracket)
+    }
+
+    pub fn expr(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn r_round_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::RRoundBracket)
+    }
+}
+
+impl AstNode for RoundBracketExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::RoundBracketExpr
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TupleExpr(pub(crate) SyntaxNode);
+
+impl TupleExpr {
+    pub fn l_round_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::LRoundBracket)
+    }
+
+    pub fn exprs(&self) -> AstChildren<Expr> {
+        support::children(&self.0)
+    }
+
+    pub fn comma_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Comma)
+    }
+
+    pub fn r_round_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::RRoundBracket)
+    }
+}
+
+impl AstNode for TupleExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::TupleExpr
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ArrayExpr(pub(crate) SyntaxNode);
+
+impl ArrayExpr {
+    pub fn l_square_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::LSquareBracket)
+    }
+
+    pub fn exprs(&self) -> AstChildren<Expr> {
+        support::children(&self.0)
+    }
+
+    pub fn comma_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Comma)
+    }
+
+    pub fn r_square_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::RSquareBracket)
+    }
+}
+
+impl AstNode for ArrayExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::ArrayExpr
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CallExpr(pub(crate) SyntaxNode);
+
+impl CallExpr {
+    pub fn expr(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn arg_list(&self) -> Option<ArgList> {
+        support::child(&self.0, 0)
+    }
+}
+
+impl AstNode for CallExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::CallExpr
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MethodCallExpr(pub(crate) SyntaxNode);
+
+impl MethodCallExpr {
+    pub fn receiver(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn dot_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Dot)
+    }
+
+    pub fn name(&self) -> Option<Ident> {
+        support::token(&self.0, SyntaxKind::Ident).and_then(Ident::cast)
+    }
+
+    pub fn arg_list(&self) -> Option<ArgList> {
+        support::child(&self.0, 0)
+    }
+}
+
+impl AstNode for MethodCallExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::MethodCallExpr
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FieldExpr(pub(crate) SyntaxNode);
+
+impl FieldExpr {
+    pub fn expr(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn dot_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Dot)
+    }
+
+    pub fn name(&self) -> Option<Ident> {
+        support::token(&self.0, SyntaxKind::Ident).and_then(Ident::cast)
+    }
+}
+
+impl AstNode for FieldExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::FieldExpr
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IndexExpr(pub(crate) SyntaxNode);
+
+impl IndexExpr {
+    pub fn base(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn l_square_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::LSquareBracket)
+    }
+
+    pub fn index(&self) -> Option<Expr> {
+        support::child(&self.0, 1)
+    }
+
+    pub fn r_square_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::RSquareBracket)
+    }
+}
+
+impl AstNode for IndexExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::IndexExpr
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StructExpr(pub(crate) SyntaxNode);
+
+impl StructExpr {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        support::token_in(&self.0, &[SyntaxKind::Ident, SyntaxKind::SelfTypeKw])
+    }
+
+    pub fn l_curly_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::LCurlyBracket)
+    }
+
+    pub fn struct_expr_fields(&self) -> AstChildren<StructExprField> {
+        support::children(&self.0)
+    }
+
+    pub fn comma_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Comma)
+    }
+
+    pub fn r_curly_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::RCurlyBracket)
+    }
+}
+
+impl AstNode for StructExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::StructExpr
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ClosureExpr(pub(crate) SyntaxNode);
+
+impl ClosureExpr {
+    pub fn pipe_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Pipe)
+    }
+
+    pub fn fn_param_list_def(&self) -> Option<FnParamListDef> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn pipe2_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Pipe2)
+    }
+
+    pub fn body(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+}
+
+impl AstNode for ClosureExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::ClosureExpr
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PlaceholderExpr(pub(crate) SyntaxNode);
+
+impl PlaceholderExpr {
+    pub fn dot3_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Dot3)
+    }
+
+    pub fn dot2_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Dot2)
+    }
+}
+
+impl AstNode for PlaceholderExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::PlaceholderExpr
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VariableRef(pub(crate) SyntaxNode);
+
+impl VariableRef {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        support::token_in(&self.0, &[SyntaxKind::Ident, SyntaxKind::SelfVarKw])
+    }
+}
+
+impl AstNode for VariableRef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::VariableRef
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PathExpr(pub(crate) SyntaxNode);
+
+impl PathExpr {
+    pub fn ty(&self) -> Option<Type> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn const_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::ConstKw)
+    }
+
+    pub fn name(&self) -> Option<Ident> {
+        support::token(&self.0, SyntaxKind::Ident).and_then(Ident::cast)
+    }
+}
+
+impl AstNode for PathExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::PathExpr
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BlockExpr(pub(crate) SyntaxNode);
+
+impl BlockExpr {
+    pub fn l_curly_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::LCurlyBracket)
+    }
+
+    pub fn stmts(&self) -> AstChildren<Stmt> {
+        support::children(&self.0)
+    }
+
+    pub fn r_curly_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::RCurlyBracket)
+    }
+}
+
+impl AstNode for BlockExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::BlockExpr
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IfExpr(pub(crate) SyntaxNode);
+
+impl IfExpr {
+    pub fn if_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::IfKw)
+    }
+
+    pub fn condition(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn then_branch(&self) -> Option<Expr> {
+        support::child(&self.0, 1)
+    }
+
+    pub fn else_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::ElseKw)
+    }
+
+    pub fn else_branch(&self) -> Option<Expr> {
+        support::child(&self.0, 2)
+    }
+}
+
+impl AstNode for IfExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::IfExpr
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LoopExpr(pub(crate) SyntaxNode);
+
+impl LoopExpr {
+    pub fn loop_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::LoopKw)
+    }
+
+    pub fn body(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+}
+
+impl AstNode for LoopExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::LoopExpr
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WhileExpr(pub(crate) SyntaxNode);
+
+impl WhileExpr {
+    pub fn while_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::WhileKw)
+    }
+
+    pub fn condition(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn body(&self) -> Option<Expr> {
+        support::child(&self.0, 1)
+    }
+}
+
+impl AstNode for WhileExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::WhileExpr
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ForExpr(pub(crate) SyntaxNode);
+
+impl ForExpr {
+    pub fn for_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::ForKw)
+    }
+
+    pub fn pat(&self) -> Option<Pat> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn comma_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Comma)
+    }
+
+    pub fn index(&self) -> Option<Pat> {
+        support::child(&self.0, 1)
+    }
+
+    pub fn in_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::InKw)
+    }
+
+    pub fn iterable(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn body(&self) -> Option<Expr> {
+        support::child(&self.0, 1)
+    }
+}
+
+impl AstNode for ForExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::ForExpr
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BreakExpr(pub(crate) SyntaxNode);
+
+impl BreakExpr {
+    pub fn break_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::BreakKw)
+    }
+
+    pub fn expr(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+}
+
+impl AstNode for BreakExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::BreakExpr
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ContinueExpr(pub(crate) SyntaxNode);
+
+impl ContinueExpr {
+    pub fn continue_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::ContinueKw)
+    }
+}
+
+impl AstNode for ContinueExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::ContinueExpr
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MatchExpr(pub(crate) SyntaxNode);
+
+impl MatchExpr {
+    pub fn match_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::MatchKw)
+    }
+
+    pub fn expr(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn match_arm_list(&self) -> Option<MatchArmList> {
+        support::child(&self.0, 0)
+    }
+}
+
+impl AstNode for MatchExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::MatchExpr
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NameList(pub(crate) SyntaxNode);
+
+impl NameList {
+    pub fn ident_pats(&self) -> AstChildren<IdentPat> {
+        support::children(&self.0)
+    }
+
+    pub fn comma_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Comma)
+    }
+}
+
+impl AstNode for NameList {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::NameList
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IdentPat(pub(crate) SyntaxNode);
+
+impl IdentPat {
+    pub fn name(&self) -> Option<Ident> {
+        support::token(&self.0, SyntaxKind::Ident).and_then(Ident::cast)
+    }
+}
+
+impl AstNode for IdentPat {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::IdentPat
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ArgList(pub(crate) SyntaxNode);
+
+impl ArgList {
+    pub fn l_round_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::LRoundBracket)
+    }
+
+    pub fn args(&self) -> AstChildren<Arg> {
+        support::children(&self.0)
+    }
+
+    pub fn comma_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Comma)
+    }
+
+    pub fn r_round_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::RRoundBracket)
+    }
+}
+
+impl AstNode for ArgList {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::ArgList
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StructExprField(pub(crate) SyntaxNode);
+
+impl StructExprField {
+    pub fn name(&self) -> Option<Ident> {
+        support::token(&self.0, SyntaxKind::Ident).and_then(Ident::cast)
+    }
+
+    pub fn colon_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Colon)
+    }
+
+    pub fn expr(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+}
+
+impl AstNode for StructExprField {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::StructExprField
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FnParamListDef(pub(crate) SyntaxNode);
+
+impl FnParamListDef {
+    pub fn fn_param_defs(&self) -> AstChildren<FnParamDef> {
+        support::children(&self.0)
+    }
+}
+
+impl AstNode for FnParamListDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::FnParamListDef
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NamedArg(pub(crate) SyntaxNode);
+
+impl NamedArg {
+    pub fn name(&self) -> Option<Ident> {
+        support::token(&self.0, SyntaxKind::Ident).and_then(Ident::cast)
+    }
+
+    pub fn colon_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Colon)
+    }
+
+    pub fn expr(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+}
+
+impl AstNode for NamedArg {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::NamedArg
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Type(pub(crate) SyntaxNode);
+
+impl Type {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        support::token_in(&self.0, &[SyntaxKind::Ident, SyntaxKind::SelfTypeKw])
+    }
+
+    pub fn l_round_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::LRoundBracket)
+    }
+
+    pub fn r_round_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::RRoundBracket)
+    }
+}
+
+impl AstNode for Type {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Type
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MatchArmList(pub(crate) SyntaxNode);
+
+impl MatchArmList {
+    pub fn l_curly_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::LCurlyBracket)
+    }
+
+    pub fn match_arms(&self) -> AstChildren<MatchArm> {
+        support::children(&self.0)
+    }
+
+    pub fn r_curly_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::RCurlyBracket)
+    }
+}
+
+impl AstNode for MatchArmList {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::MatchArmList
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MatchArm(pub(crate) SyntaxNode);
+
+impl MatchArm {
+    pub fn pats(&self) -> AstChildren<Pat> {
+        support::children(&self.0)
+    }
+
+    pub fn comma_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Comma)
+    }
+
+    pub fn fat_arrow_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::FatArrow)
+    }
+
+    pub fn expr(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+}
+
+impl AstNode for MatchArm {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::MatchArm
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WildcardPat(pub(crate) SyntaxNode);
+
+impl WildcardPat {
+    pub fn underscore_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Underscore)
+    }
+}
+
+impl AstNode for WildcardPat {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::WildcardPat
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LiteralPat(pub(crate) SyntaxNode);
+
+impl LiteralPat {
+    pub fn expr(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+}
+
+impl AstNode for LiteralPat {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::LiteralPat
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangePat(pub(crate) SyntaxNode);
+
+impl RangePat {
+    pub fn start(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn dot2_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Dot2)
+    }
+
+    pub fn end(&self) -> Option<Expr> {
+        support::child(&self.0, 1)
+    }
+}
+
+impl AstNode for RangePat {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::RangePat
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TuplePat(pub(crate) SyntaxNode);
+
+impl TuplePat {
+    pub fn l_round_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::LRoundBracket)
+    }
+
+    pub fn pats(&self) -> AstChildren<Pat> {
+        support::children(&self.0)
+    }
+
+    pub fn comma_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Comma)
+    }
+
+    pub fn r_round_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::RRoundBracket)
+    }
+}
+
+impl AstNode for TuplePat {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::TuplePat
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StructPat(pub(crate) SyntaxNode);
+
+impl StructPat {
+    pub fn name(&self) -> Option<Ident> {
+        support::token(&self.0, SyntaxKind::Ident).and_then(Ident::cast)
+    }
+
+    pub fn l_curly_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::LCurlyBracket)
+    }
+
+    pub fn field_pats(&self) -> AstChildren<FieldPat> {
+        support::children(&self.0)
+    }
+
+    pub fn comma_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Comma)
+    }
+
+    pub fn r_curly_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::RCurlyBracket)
+    }
+}
+
+impl AstNode for StructPat {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::StructPat
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FieldPat(pub(crate) SyntaxNode);
+
+impl FieldPat {
+    pub fn name(&self) -> Option<Ident> {
+        support::token(&self.0, SyntaxKind::Ident).and_then(Ident::cast)
+    }
+
+    pub fn colon_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Colon)
+    }
+
+    pub fn pat(&self) -> Option<Pat> {
+        support::child(&self.0, 0)
+    }
+}
+
+impl AstNode for FieldPat {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::FieldPat
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FnReturnDef(pub(crate) SyntaxNode);
+
+impl FnReturnDef {
+    pub fn l_round_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::LRoundBracket)
+    }
+
+    pub fn fn_return_type_list_def(&self) -> Option<FnReturnTypeListDef> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn r_round_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::RRoundBracket)
+    }
+
+    pub fn fn_return_type_def(&self) -> Option<FnReturnTypeDef> {
+        support::child(&self.0, 0)
+    }
+}
+
+impl AstNode for FnReturnDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::FnReturnDef
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FnBodyDef(pub(crate) SyntaxNode);
+
+impl FnBodyDef {
+    pub fn stmts(&self) -> AstChildren<Stmt> {
+        support::children(&self.0)
+    }
+}
+
+impl AstNode for FnBodyDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::FnBodyDef
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FnParamDef(pub(crate) SyntaxNode);
+
+impl FnParamDef {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        support::token_in(&self.0, &[SyntaxKind::Ident, SyntaxKind::SelfVarKw])
+    }
+
+    pub fn colon_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Colon)
+    }
+
+    pub fn ty(&self) -> Option<Type> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn comma_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Comma)
+    }
+}
+
+impl AstNode for FnParamDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::FnParamDef
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FnReturnTypeListDef(pub(crate) SyntaxNode);
+
+impl FnReturnTypeListDef {
+    pub fn fn_return_type_defs(&self) -> AstChildren<FnReturnTypeDef> {
+        support::children(&self.0)
+    }
+
+    pub fn comma_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Comma)
+    }
+}
+
+impl AstNode for FnReturnTypeListDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::FnReturnTypeListDef
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FnReturnTypeDef(pub(crate) SyntaxNode);
+
+impl FnReturnTypeDef {
+    pub fn ty(&self) -> Option<Type> {
+        support::child(&self.0, 0)
+    }
+}
+
+impl AstNode for FnReturnTypeDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::FnReturnTypeDef
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StructFieldListDef(pub(crate) SyntaxNode);
+
+impl StructFieldListDef {
+    pub fn l_curly_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::LCurlyBracket)
+    }
+
+    pub fn struct_field_defs(&self) -> AstChildren<StructFieldDef> {
+        support::children(&self.0)
+    }
+
+    pub fn r_curly_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::RCurlyBracket)
+    }
+}
+
+impl AstNode for StructFieldListDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::StructFieldListDef
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StructFieldDef(pub(crate) SyntaxNode);
+
+impl StructFieldDef {
+    pub fn name(&self) -> Option<Ident> {
+        support::token(&self.0, SyntaxKind::Ident).and_then(Ident::cast)
+    }
+
+    pub fn colon_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Colon)
+    }
+
+    pub fn ty(&self) -> Option<Type> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn comma_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Comma)
+    }
+}
+
+impl AstNode for StructFieldDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::StructFieldDef
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TraitListsDef(pub(crate) SyntaxNode);
+
+impl TraitListsDef {
+    pub fn l_curly_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::LCurlyBracket)
+    }
+
+    pub fn trait_type_list_def(&self) -> Option<TraitTypeListDef> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn trait_fn_list_def(&self) -> Option<TraitFnListDef> {
+        support::child(&self.0, 0)
+    }
+
+    pub fn r_curly_bracket_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::RCurlyBracket)
+    }
+}
+
+impl AstNode for TraitListsDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::TraitListsDef
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TraitTypeListDef(pub(crate) SyntaxNode);
+
+impl TraitTypeListDef {
+    pub fn trait_type_defs(&self) -> AstChildren<TraitTypeDef> {
+        support::children(&self.0)
+    }
+}
+
+impl AstNode for TraitTypeListDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::TraitTypeListDef
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TraitFnListDef(pub(crate) SyntaxNode);
+
+impl TraitFnListDef {
+    pub fn stmts(&self) -> AstChildren<Stmt> {
+        support::children(&self.0)
+    }
+}
+
+impl AstNode for TraitFnListDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::TraitFnListDef
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TraitTypeDef(pub(crate) SyntaxNode);
+
+impl TraitTypeDef {
+    pub fn type_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::TypeKw)
+    }
+
+    pub fn name(&self) -> Option<Ident> {
+        support::token(&self.0, SyntaxKind::Ident).and_then(Ident::cast)
+    }
+}
+
+impl AstNode for TraitTypeDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::TraitTypeDef
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ImplItemListDef(pub(crate) SyntaxNode);
+
+impl ImplItemListDef {
+    pub fn stmts(&self) -> AstChildren<Stmt> {
+        support::children(&self.0)
+    }
+}
+
+impl AstNode for ImplItemListDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::ImplItemListDef
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self(syntax))
+        } else {
+            None
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Stmt {
+    VariableDef(VariableDef),
+    ConstDef(ConstDef),
+    AssignDef(AssignDef),
+    FnDef(FnDef),
+    StructDef(StructDef),
+    TraitDef(TraitDef),
+    ImplDef(ImplDef),
+    Expr(Expr),
+}
+
+impl AstNode for Stmt {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(
+            kind,
+            SyntaxKind::VariableDef
+                | SyntaxKind::ConstDef
+                | SyntaxKind::AssignDef
+                | SyntaxKind::FnDef
+                | SyntaxKind::StructDef
+                | SyntaxKind::TraitDef
+                | SyntaxKind::ImplDef
+        ) || Expr::can_cast(kind)
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        let result = match syntax.kind() {
+            SyntaxKind::VariableDef => Self::VariableDef(VariableDef(syntax)),
+            SyntaxKind::ConstDef => Self::ConstDef(ConstDef(syntax)),
+            SyntaxKind::AssignDef => Self::AssignDef(AssignDef(syntax)),
+            SyntaxKind::FnDef => Self::FnDef(FnDef(syntax)),
+            SyntaxKind::StructDef => Self::StructDef(StructDef(syntax)),
+            SyntaxKind::TraitDef => Self::TraitDef(TraitDef(syntax)),
+            SyntaxKind::ImplDef => Self::ImplDef(ImplDef(syntax)),
+            _ => return Expr::cast(syntax).map(Self::Expr),
+        };
+
+        Some(result)
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Self::VariableDef(it) => it.syntax(),
+            Self::ConstDef(it) => it.syntax(),
+            Self::AssignDef(it) => it.syntax(),
+            Self::FnDef(it) => it.syntax(),
+            Self::StructDef(it) => it.syntax(),
+            Self::TraitDef(it) => it.syntax(),
+            Self::ImplDef(it) => it.syntax(),
+            Self::Expr(it) => it.syntax(),
+        }
+    }
+}
+
+impl From<VariableDef> for Stmt {
+    fn from(node: VariableDef) -> Self {
+        Self::VariableDef(node)
+    }
+}
+
+impl From<ConstDef> for Stmt {
+    fn from(node: ConstDef) -> Self {
+        Self::ConstDef(node)
+    }
+}
+
+impl From<AssignDef> for Stmt {
+    fn from(node: AssignDef) -> Self {
+        Self::AssignDef(node)
+    }
+}
+
+impl From<FnDef> for Stmt {
+    fn from(node: FnDef) -> Self {
+        Self::FnDef(node)
+    }
+}
+
+impl From<StructDef> for Stmt {
+    fn from(node: StructDef) -> Self {
+        Self::StructDef(node)
+    }
+}
+
+impl From<TraitDef> for Stmt {
+    fn from(node: TraitDef) -> Self {
+        Self::TraitDef(node)
+    }
+}
+
+impl From<ImplDef> for Stmt {
+    fn from(node: ImplDef) -> Self {
+        Self::ImplDef(node)
+    }
+}
+
+impl From<Expr> for Stmt {
+    fn from(node: Expr) -> Self {
+        Self::Expr(node)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Expr {
+    InfixExpr(InfixExpr),
+    PrefixExpr(PrefixExpr),
+    TernaryExpr(TernaryExpr),
+    Literal(Literal),
+    RoundBracketExpr(RoundBracketExpr),
+    TupleExpr(TupleExpr),
+    ArrayExpr(ArrayExpr),
+    CallExpr(CallExpr),
+    MethodCallExpr(MethodCallExpr),
+    FieldExpr(FieldExpr),
+    IndexExpr(IndexExpr),
+    StructExpr(StructExpr),
+    ClosureExpr(ClosureExpr),
+    PlaceholderExpr(PlaceholderExpr),
+    VariableRef(VariableRef),
+    PathExpr(PathExpr),
+    BlockExpr(BlockExpr),
+    IfExpr(IfExpr),
+    LoopExpr(LoopExpr),
+    WhileExpr(WhileExpr),
+    ForExpr(ForExpr),
+    BreakExpr(BreakExpr),
+    ContinueExpr(ContinueExpr),
+    MatchExpr(MatchExpr),
+}
+
+impl AstNode for Expr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(
+            kind,
+            SyntaxKind::InfixExpr
+                | SyntaxKind::PrefixExpr
+                | SyntaxKind::TernaryExpr
+                | SyntaxKind::Literal
+                | SyntaxKind::RoundBracketExpr
+                | SyntaxKind::TupleExpr
+                | SyntaxKind::ArrayExpr
+                | SyntaxKind::CallExpr
+                | SyntaxKind::MethodCallExpr
+                | SyntaxKind::FieldExpr
+                | SyntaxKind::IndexExpr
+                | SyntaxKind::StructExpr
+                | SyntaxKind::ClosureExpr
+                | SyntaxKind::PlaceholderExpr
+                | SyntaxKind::VariableRef
+                | SyntaxKind::PathExpr
+                | SyntaxKind::BlockExpr
+                | SyntaxKind::IfExpr
+                | SyntaxKind::LoopExpr
+                | SyntaxKind::WhileExpr
+                | SyntaxKind::ForExpr
+                | SyntaxKind::BreakExpr
+                | SyntaxKind::ContinueExpr
+                | SyntaxKind::MatchExpr
+        )
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        let result = match syntax.kind() {
+            SyntaxKind::InfixExpr => Self::InfixExpr(InfixExpr(syntax)),
+            SyntaxKind::PrefixExpr => Self::PrefixExpr(PrefixExpr(syntax)),
+            SyntaxKind::TernaryExpr => Self::TernaryExpr(TernaryExpr(syntax)),
+            SyntaxKind::Literal => Self::Literal(Literal(syntax)),
+            SyntaxKind::RoundBracketExpr => Self::RoundBracketExpr(RoundBracketExpr(syntax)),
+            SyntaxKind::TupleExpr => Self::TupleExpr(TupleExpr(syntax)),
+            SyntaxKind::ArrayExpr => Self::ArrayExpr(ArrayExpr(syntax)),
+            SyntaxKind::CallExpr => Self::CallExpr(CallExpr(syntax)),
+            SyntaxKind::MethodCallExpr => Self::MethodCallExpr(MethodCallExpr(syntax)),
+            SyntaxKind::FieldExpr => Self::FieldExpr(FieldExpr(syntax)),
+            SyntaxKind::IndexExpr => Self::IndexExpr(IndexExpr(syntax)),
+            SyntaxKind::StructExpr => Self::StructExpr(StructExpr(syntax)),
+            SyntaxKind::ClosureExpr => Self::ClosureExpr(ClosureExpr(syntax)),
+            SyntaxKind::PlaceholderExpr => Self::PlaceholderExpr(PlaceholderExpr(syntax)),
+            SyntaxKind::VariableRef => Self::VariableRef(VariableRef(syntax)),
+            SyntaxKind::PathExpr => Self::PathExpr(PathExpr(syntax)),
+            SyntaxKind::BlockExpr => Self::BlockExpr(BlockExpr(syntax)),
+            SyntaxKind::IfExpr => Self::IfExpr(IfExpr(syntax)),
+            SyntaxKind::LoopExpr => Self::LoopExpr(LoopExpr(syntax)),
+            SyntaxKind::WhileExpr => Self::WhileExpr(WhileExpr(syntax)),
+            SyntaxKind::ForExpr => Self::ForExpr(ForExpr(syntax)),
+            SyntaxKind::BreakExpr => Self::BreakExpr(BreakExpr(syntax)),
+            SyntaxKind::ContinueExpr => Self::ContinueExpr(ContinueExpr(syntax)),
+            SyntaxKind::MatchExpr => Self::MatchExpr(MatchExpr(syntax)),
+            _ => return None,
+        };
+
+        Some(result)
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Self::InfixExpr(it) => it.syntax(),
+            Self::PrefixExpr(it) => it.syntax(),
+            Self::TernaryExpr(it) => it.syntax(),
+            Self::Literal(it) => it.syntax(),
+            Self::RoundBracketExpr(it) => it.syntax(),
+            Self::TupleExpr(it) => it.syntax(),
+            Self::ArrayExpr(it) => it.syntax(),
+            Self::CallExpr(it) => it.syntax(),
+            Self::MethodCallExpr(it) => it.syntax(),
+            Self::FieldExpr(it) => it.syntax(),
+            Self::IndexExpr(it) => it.syntax(),
+            Self::StructExpr(it) => it.syntax(),
+            Self::ClosureExpr(it) => it.syntax(),
+            Self::PlaceholderExpr(it) => it.syntax(),
+            Self::VariableRef(it) => it.syntax(),
+            Self::PathExpr(it) => it.syntax(),
+            Self::BlockExpr(it) => it.syntax(),
+            Self::IfExpr(it) => it.syntax(),
+            Self::LoopExpr(it) => it.syntax(),
+            Self::WhileExpr(it) => it.syntax(),
+            Self::ForExpr(it) => it.syntax(),
+            Self::BreakExpr(it) => it.syntax(),
+            Self::ContinueExpr(it) => it.syntax(),
+            Self::MatchExpr(it) => it.syntax(),
+        }
+    }
+}
+
+impl From<InfixExpr> for Expr {
+    fn from(node: InfixExpr) -> Self {
+        Self::InfixExpr(node)
+    }
+}
+
+impl From<PrefixExpr> for Expr {
+    fn from(node: PrefixExpr) -> Self {
+        Self::PrefixExpr(node)
+    }
+}
+
+impl From<TernaryExpr> for Expr {
+    fn from(node: TernaryExpr) -> Self {
+        Self::TernaryExpr(node)
+    }
+}
+
+impl From<Literal> for Expr {
+    fn from(node: Literal) -> Self {
+        Self::Literal(node)
+    }
+}
+
+impl From<RoundBracketExpr> for Expr {
+    fn from(node: RoundBracketExpr) -> Self {
+        Self::RoundBracketExpr(node)
+    }
+}
+
+impl From<TupleExpr> for Expr {
+    fn from(node: TupleExpr) -> Self {
+        Self::TupleExpr(node)
+    }
+}
+
+impl From<ArrayExpr> for Expr {
+    fn from(node: ArrayExpr) -> Self {
+        Self::ArrayExpr(node)
+    }
+}
+
+impl From<CallExpr> for Expr {
+    fn from(node: CallExpr) -> Self {
+        Self::CallExpr(node)
+    }
+}
+
+impl From<MethodCallExpr> for Expr {
+    fn from(node: MethodCallExpr) -> Self {
+        Self::MethodCallExpr(node)
+    }
+}
+
+impl From<FieldExpr> for Expr {
+    fn from(node: FieldExpr) -> Self {
+        Self::FieldExpr(node)
+    }
+}
+
+impl From<IndexExpr> for Expr {
+    fn from(node: IndexExpr) -> Self {
+        Self::IndexExpr(node)
+    }
+}
+
+impl From<StructExpr> for Expr {
+    fn from(node: StructExpr) -> Self {
+        Self::StructExpr(node)
+    }
+}
+
+impl From<ClosureExpr> for Expr {
+    fn from(node: ClosureExpr) -> Self {
+        Self::ClosureExpr(node)
+    }
+}
+
+impl From<PlaceholderExpr> for Expr {
+    fn from(node: PlaceholderExpr) -> Self {
+        Self::PlaceholderExpr(node)
+    }
+}
+
+impl From<VariableRef> for Expr {
+    fn from(node: VariableRef) -> Self {
+        Self::VariableRef(node)
+    }
+}
+
+impl From<PathExpr> for Expr {
+    fn from(node: PathExpr) -> Self {
+        Self::PathExpr(node)
+    }
+}
+
+impl From<BlockExpr> for Expr {
+    fn from(node: BlockExpr) -> Self {
+        Self::BlockExpr(node)
+    }
+}
+
+impl From<IfExpr> for Expr {
+    fn from(node: IfExpr) -> Self {
+        Self::IfExpr(node)
+    }
+}
+
+impl From<LoopExpr> for Expr {
+    fn from(node: LoopExpr) -> Self {
+        Self::LoopExpr(node)
+    }
+}
+
+impl From<WhileExpr> for Expr {
+    fn from(node: WhileExpr) -> Self {
+        Self::WhileExpr(node)
+    }
+}
+
+impl From<ForExpr> for Expr {
+    fn from(node: ForExpr) -> Self {
+        Self::ForExpr(node)
+    }
+}
+
+impl From<BreakExpr> for Expr {
+    fn from(node: BreakExpr) -> Self {
+        Self::BreakExpr(node)
+    }
+}
+
+impl From<ContinueExpr> for Expr {
+    fn from(node: ContinueExpr) -> Self {
+        Self::ContinueExpr(node)
+    }
+}
+
+impl From<MatchExpr> for Expr {
+    fn from(node: MatchExpr) -> Self {
+        Self::MatchExpr(node)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Arg {
+    NamedArg(NamedArg),
+    Expr(Expr),
+}
+
+impl AstNode for Arg {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(kind, SyntaxKind::NamedArg) || Expr::can_cast(kind)
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        let result = match syntax.kind() {
+            SyntaxKind::NamedArg => Self::NamedArg(NamedArg(syntax)),
+            _ => return Expr::cast(syntax).map(Self::Expr),
+        };
+
+        Some(result)
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Self::NamedArg(it) => it.syntax(),
+            Self::Expr(it) => it.syntax(),
+        }
+    }
+}
+
+impl From<NamedArg> for Arg {
+    fn from(node: NamedArg) -> Self {
+        Self::NamedArg(node)
+    }
+}
+
+impl From<Expr> for Arg {
+    fn from(node: Expr) -> Self {
+        Self::Expr(node)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Pat {
+    WildcardPat(WildcardPat),
+    IdentPat(IdentPat),
+    LiteralPat(LiteralPat),
+    RangePat(RangePat),
+    TuplePat(TuplePat),
+    StructPat(StructPat),
+}
+
+impl AstNode for Pat {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(
+            kind,
+            SyntaxKind::WildcardPat
+                | SyntaxKind::IdentPat
+                | SyntaxKind::LiteralPat
+                | SyntaxKind::RangePat
+                | SyntaxKind::TuplePat
+                | SyntaxKind::StructPat
+        )
+    }
+
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        let result = match syntax.kind() {
+            SyntaxKind::WildcardPat => Self::WildcardPat(WildcardPat(syntax)),
+            SyntaxKind::IdentPat => Self::IdentPat(IdentPat(syntax)),
+            SyntaxKind::LiteralPat => Self::LiteralPat(LiteralPat(syntax)),
+            SyntaxKind::RangePat => Self::RangePat(RangePat(syntax)),
+            SyntaxKind::TuplePat => Self::TuplePat(TuplePat(syntax)),
+            SyntaxKind::StructPat => Self::StructPat(StructPat(syntax)),
+            _ => return None,
+        };
+
+        Some(result)
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Self::WildcardPat(it) => it.syntax(),
+            Self::IdentPat(it) => it.syntax(),
+            Self::LiteralPat(it) => it.syntax(),
+            Self::RangePat(it) => it.syntax(),
+            Self::TuplePat(it) => it.syntax(),
+            Self::StructPat(it) => it.syntax(),
+        }
+    }
+}
+
+impl From<WildcardPat> for Pat {
+    fn from(node: WildcardPat) -> Self {
+        Self::WildcardPat(node)
+    }
+}
+
+impl From<IdentPat> for Pat {
+    fn from(node: IdentPat) -> Self {
+        Self::IdentPat(node)
+    }
+}
+
+impl From<LiteralPat> for Pat {
+    fn from(node: LiteralPat) -> Self {
+        Self::LiteralPat(node)
+    }
+}
+
+impl From<RangePat> for Pat {
+    fn from(node: RangePat) -> Self {
+        Self::RangePat(node)
+    }
+}
+
+impl From<TuplePat> for Pat {
+    fn from(node: TuplePat) -> Self {
+        Self::TuplePat(node)
+    }
+}
+
+impl From<StructPat> for Pat {
+    fn from(node: StructPat) -> Self {
+        Self::StructPat(node)
+    }
+}
@@ -1,172 +1,298 @@
-use drip_syntax::SyntaxKind::PrefixExpr;
-use drip_syntax::{SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken};
-
+pub mod edit;
+mod generated;
+pub mod make;
+mod ptr;
 pub mod validation;
+pub mod visit;
 
-#[derive(Debug)]
-pub struct Root(SyntaxNode);
+pub use generated::*;
+pub use ptr::{AstPtr, SyntaxNodePtr};
 
-impl Root {
-    pub fn cast(node: SyntaxNode) -> Option<Self> {
-        if node.kind() == SyntaxKind::Root {
-            Some(Self(node))
-        } else {
-            None
-        }
-    }
+use drip_syntax::{SyntaxKind, SyntaxNode, SyntaxToken};
+use std::marker::PhantomData;
 
-    pub fn stmts(&self) -> impl Iterator<Item = Stmt> {
-        self.0.children().filter_map(Stmt::cast)
-    }
-}
+/// A typed view of a `SyntaxNode`.
+pub trait AstNode {
+    fn can_cast(kind: SyntaxKind) -> bool
+    where
+        Self: Sized;
 
-#[derive(Debug)]
-pub enum Stmt {
-    VariableDef(VariableDef),
-    ConstDef(ConstDef),
-    Expr(Expr),
+    fn cast(syntax: SyntaxNode) -> Option<Self>
+    where
+        Self: Sized;
+
+    fn syntax(&self) -> &SyntaxNode;
 }
 
-impl Stmt {
-    pub fn cast(node: SyntaxNode) -> Option<Self> {
-        let result = match node.kind() {
-            SyntaxKind::VariableDef => Self::VariableDef(VariableDef(node)),
-            SyntaxKind::ConstDef => Self::ConstDef(ConstDef(node)),
-            _ => Self::Expr(Expr::cast(node)?),
-        };
+/// A typed view of a `SyntaxToken`.
+pub trait AstToken {
+    fn can_cast(kind: SyntaxKind) -> bool
+    where
+        Self: Sized;
+
+    fn cast(syntax: SyntaxToken) -> Option<Self>
+    where
+        Self: Sized;
+
+    fn syntax(&self) -> &SyntaxToken;
 
-        Some(result)
+    fn text(&self) -> &str {
+        self.syntax().text()
     }
 }
 
-#[derive(Debug)]
-pub enum Expr {
-    BinaryExpr(BinaryExpr),
-    UnaryExpr(UnaryExpr),
-    Literal(Literal),
-    RoundBracketExpr(RoundBracketExpr),
-    VariableRef(VariableRef),
+/// Iterator over the children of a node that can be cast to `N`.
+#[derive(Debug, Clone)]
+pub struct AstChildren<N> {
+    inner: rowan::SyntaxNodeChildren<drip_syntax::Drip>,
+    ph: PhantomData<N>,
 }
 
-impl Expr {
-    pub fn cast(node: SyntaxNode) -> Option<Self> {
-        let result = match node.kind() {
-            SyntaxKind::InfixExpr => Self::BinaryExpr(BinaryExpr(node)),
-            SyntaxKind::PrefixExpr => Self::UnaryExpr(UnaryExpr(node)),
-            SyntaxKind::Literal => Self::Literal(Literal(node)),
-            SyntaxKind::RoundBracketExpr => Self::RoundBracketExpr(RoundBracketExpr(node)),
-            SyntaxKind::VariableRef => Self::VariableRef(VariableRef(node)),
-            _ => return None,
-        };
+impl<N> AstChildren<N> {
+    fn new(parent: &SyntaxNode) -> Self {
+        Self {
+            inner: parent.children(),
+            ph: PhantomData,
+        }
+    }
+}
 
-        Some(result)
+impl<N: AstNode> Iterator for AstChildren<N> {
+    type Item = N;
+
+    fn next(&mut self) -> Option<N> {
+        self.inner.find_map(N::cast)
     }
 }
 
-#[derive(Debug)]
-pub struct BinaryExpr(SyntaxNode);
+mod support {
+    use super::{AstChildren, AstNode};
+    use drip_syntax::{SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken};
+
+    pub(super) fn child<N: AstNode>(parent: &SyntaxNode, nth: usize) -> Option<N> {
+        parent.children().filter_map(N::cast).nth(nth)
+    }
 
-impl BinaryExpr {
-    pub fn lhs(&self) -> Option<Expr> {
-        self.0.children().find_map(Expr::cast)
+    pub(super) fn children<N: AstNode>(parent: &SyntaxNode) -> AstChildren<N> {
+        AstChildren::new(parent)
     }
 
-    pub fn rhs(&self) -> Option<Expr> {
-        self.0.children().filter_map(Expr::cast).nth(1)
+    pub(super) fn token(parent: &SyntaxNode, kind: SyntaxKind) -> Option<SyntaxToken> {
+        token_in(parent, &[kind])
     }
 
-    pub fn op(&self) -> Option<SyntaxToken> {
-        self.0
+    pub(super) fn token_in(parent: &SyntaxNode, kinds: &[SyntaxKind]) -> Option<SyntaxToken> {
+        parent
             .children_with_tokens()
             .filter_map(SyntaxElement::into_token)
-            .find(|token| {
-                matches!(
-                    token.kind(),
-                    SyntaxKind::Plus | SyntaxKind::Minus | SyntaxKind::Star | SyntaxKind::Slash
-                )
-            })
+            .find(|token| kinds.contains(&token.kind()))
     }
 }
 
-#[derive(Debug)]
-pub struct UnaryExpr(SyntaxNode);
+impl Literal {
+    pub fn parse(&self) -> Option<u64> {
+        self.number()?.value()
+    }
+}
 
-impl UnaryExpr {
-    pub fn expr(&self) -> Option<Expr> {
-        self.0.children().find_map(Expr::cast)
+impl Number {
+    pub fn value(&self) -> Option<u64> {
+        self.text().replace('_', "").parse().ok()
     }
 
-    pub fn op(&self) -> Option<SyntaxToken> {
-        self.0
-            .children_with_tokens()
-            .filter_map(SyntaxElement::into_token)
-            .find(|token| token.kind() == SyntaxKind::Minus)
+    /// Whether the number has a fractional part or an exponent, like `3.14`.
+    pub fn is_float(&self) -> bool {
+        self.text().contains(['.', 'e', 'E'])
+    }
+
+    pub fn float_value(&self) -> Option<f64> {
+        self.text().replace('_', "").parse().ok()
     }
 }
 
-#[derive(Debug)]
-pub struct Literal(SyntaxNode);
+impl Str {
+    /// The string's contents without the surrounding quotes.
+    pub fn value(&self) -> &str {
+        let text = self.text();
+        &text[1..text.len() - 1]
+    }
+}
 
-impl Literal {
-    pub fn cast(node: SyntaxNode) -> Option<Self> {
-        if node.kind() == SyntaxKind::Literal {
-            Some(Self(node))
-        } else {
-            None
-        }
+impl Comment {
+    /// The comment's text without the leading `//`.
+    pub fn body(&self) -> &str {
+        &self.text()[2..]
     }
+}
 
-    pub fn parse(&self) -> Option<u64> {
-        self.0.first_token().unwrap().text().parse().ok()
+impl FnDef {
+    pub fn params(&self) -> impl Iterator<Item = FnParamDef> {
+        self.fn_param_list_def()
+            .into_iter()
+            .flat_map(|list| list.fn_param_defs())
+    }
+
+    pub fn return_types(&self) -> impl Iterator<Item = Type> {
+        let ret = self.fn_return_def();
+        let single = ret.as_ref().and_then(FnReturnDef::fn_return_type_def);
+        let list = ret.and_then(|ret| ret.fn_return_type_list_def());
+
+        single
+            .into_iter()
+            .chain(list.into_iter().flat_map(|list| list.fn_return_type_defs()))
+            .filter_map(|ret| ret.ty())
+    }
+
+    pub fn body(&self) -> impl Iterator<Item = Stmt> {
+        self.fn_body_def().into_iter().flat_map(|body| body.stmts())
     }
 }
 
-#[derive(Debug)]
-pub struct RoundBracketExpr(SyntaxNode);
+impl ClosureExpr {
+    pub fn params(&self) -> impl Iterator<Item = FnParamDef> {
+        self.fn_param_list_def()
+            .into_iter()
+            .flat_map(|list| list.fn_param_defs())
+    }
+}
 
-impl RoundBracketExpr {
-    pub fn expr(&self) -> Option<Expr> {
-        self.0.children().find_map(Expr::cast)
+impl StructDef {
+    pub fn fields(&self) -> impl Iterator<Item = StructFieldDef> {
+        self.struct_field_list_def()
+            .into_iter()
+            .flat_map(|list| list.struct_field_defs())
     }
 }
 
-#[derive(Debug)]
-pub struct VariableDef(SyntaxNode);
+impl TraitDef {
+    pub fn types(&self) -> impl Iterator<Item = TraitTypeDef> {
+        self.trait_lists_def()
+            .and_then(|lists| lists.trait_type_list_def())
+            .into_iter()
+            .flat_map(|list| list.trait_type_defs())
+    }
 
-impl VariableDef {
-    pub fn name(&self) -> Option<SyntaxToken> {
-        self.0
-            .children_with_tokens()
-            .filter_map(SyntaxElement::into_token)
-            .find(|token| token.kind() == SyntaxKind::Ident)
+    pub fn members(&self) -> impl Iterator<Item = Stmt> {
+        self.trait_lists_def()
+            .and_then(|lists| lists.trait_fn_list_def())
+            .into_iter()
+            .flat_map(|list| list.stmts())
     }
+}
 
-    pub fn value(&self) -> Option<Expr> {
-        self.0.children().find_map(Expr::cast)
+impl ImplDef {
+    pub fn items(&self) -> impl Iterator<Item = Stmt> {
+        self.impl_item_list_def()
+            .into_iter()
+            .flat_map(|list| list.stmts())
     }
 }
 
-#[derive(Debug)]
-pub struct ConstDef(SyntaxNode);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-impl ConstDef {
-    pub fn name(&self) -> Option<SyntaxToken> {
-        self.0
-            .children_with_tokens()
-            .filter_map(SyntaxElement::into_token)
-            .find(|token| token.kind() == SyntaxKind::Ident)
+    fn parse(input: &str) -> Root {
+        Root::cast(drip_parser::parse(input).syntax()).unwrap()
     }
 
-    pub fn value(&self) -> Option<Expr> {
-        self.0.children().find_map(Expr::cast)
+    #[test]
+    fn cast_definitions_to_their_own_stmt() {
+        let root = parse("f :: () { }");
+        assert!(matches!(root.stmts().next(), Some(Stmt::FnDef(_))));
+
+        let root = parse("S :: struct { }");
+        assert!(matches!(root.stmts().next(), Some(Stmt::StructDef(_))));
     }
-}
 
-#[derive(Debug)]
-pub struct VariableRef(SyntaxNode);
+    #[test]
+    fn access_both_operands_of_the_same_type() {
+        let root = parse("1 + a");
+        let infix = match root.stmts().next() {
+            Some(Stmt::Expr(Expr::InfixExpr(infix))) => infix,
+            _ => unreachable!(),
+        };
+
+        assert!(matches!(infix.lhs(), Some(Expr::Literal(_))));
+        assert!(matches!(infix.rhs(), Some(Expr::VariableRef(_))));
+        assert_eq!(infix.op().unwrap().kind(), SyntaxKind::Plus);
+    }
+
+    #[test]
+    fn skip_assignment_target_when_accessing_value() {
+        let root = parse("a = b");
+        let assign = match root.stmts().next() {
+            Some(Stmt::AssignDef(assign)) => assign,
+            _ => unreachable!(),
+        };
+
+        assert!(
+            matches!(assign.target(), Some(Expr::VariableRef(var)) if var.name().unwrap().text() == "a")
+        );
+        assert!(
+            matches!(assign.value(), Some(Expr::VariableRef(var)) if var.name().unwrap().text() == "b")
+        );
+    }
+
+    #[test]
+    fn access_function_signature() {
+        let root = parse("f :: (a: i32, b: Self) -> (i32, string) { a + b }");
+        let f = match root.stmts().next() {
+            Some(Stmt::FnDef(f)) => f,
+            _ => unreachable!(),
+        };
+
+        let params: Vec<_> = f
+            .params()
+            .map(|param| {
+                let ty = param.ty().unwrap().name().unwrap();
+                format!("{}: {}", param.name().unwrap().text(), ty.text())
+            })
+            .collect();
+        assert_eq!(params, ["a: i32", "b: Self"]);
+
+        let return_types: Vec<_> = f
+            .return_types()
+            .map(|ty| ty.name().unwrap().text().to_string())
+            .collect();
+        assert_eq!(return_types, ["i32", "string"]);
+
+        assert!(matches!(
+            f.body().next(),
+            Some(Stmt::Expr(Expr::InfixExpr(_)))
+        ));
+    }
+
+    #[test]
+    fn access_struct_fields() {
+        let root = parse("Vec2 :: struct { x: f32, y: f32 }");
+        let s = match root.stmts().next() {
+            Some(Stmt::StructDef(s)) => s,
+            _ => unreachable!(),
+        };
+
+        assert_eq!(s.name().unwrap().text(), "Vec2");
+        let fields: Vec<_> = s
+            .fields()
+            .map(|field| field.name().unwrap().text().to_string())
+            .collect();
+        assert_eq!(fields, ["x", "y"]);
+    }
+
+    #[test]
+    fn cast_tokens() {
+        let root = parse("// one\n1");
+        let tokens: Vec<_> = root
+            .syntax()
+            .descendants_with_tokens()
+            .filter_map(|element| element.into_token())
+            .collect();
+
+        let comment = Comment::cast(tokens[0].clone()).unwrap();
+        assert_eq!(comment.body(), " one");
+        assert!(Comment::cast(tokens[1].clone()).is_none());
 
-impl VariableRef {
-    pub fn name(&self) -> Option<SyntaxToken> {
-        self.0.first_token()
+        let number = Number::cast(tokens[2].clone()).unwrap();
+        assert_eq!(number.value(), Some(1));
     }
 }
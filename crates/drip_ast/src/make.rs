@@ -0,0 +1,467 @@
+//! Constructors for well-formed AST nodes.
+//!
+//! Nodes are assembled from green tokens in exactly the shape the parser
+//! gives them, with each piece of whitespace in the innermost node that holds
+//! the token before it, as the parser puts it. The returned nodes are
+//! immutable roots; the functions in [`crate::edit`] take care of making them
+//! mutable when they are inserted into a tree.
+
+use crate::{AssignDef, ConstDef, InfixExpr, Literal, PrefixExpr, RoundBracketExpr};
+use crate::{AstNode, Expr, FnDef, FnParamDef, Stmt, StructDef, StructFieldDef, Type};
+use crate::{VariableDef, VariableRef};
+use drip_syntax::{Drip, SyntaxKind, SyntaxNode, SyntaxToken};
+use rowan::{GreenNode, GreenToken, Language, NodeOrToken};
+
+type Element = NodeOrToken<GreenNode, GreenToken>;
+
+pub fn variable_ref(name: &str) -> VariableRef {
+    build(node(
+        SyntaxKind::VariableRef,
+        vec![token(SyntaxKind::Ident, name)],
+    ))
+}
+
+pub fn literal(value: u64) -> Literal {
+    let number = token(SyntaxKind::Number, &value.to_string());
+    build(node(SyntaxKind::Literal, vec![number]))
+}
+
+/// Builds `lhs op rhs`, putting brackets around an operand that would
+/// otherwise bind differently, like the `1 + a` in `(1 + a) * b`.
+pub fn infix_expr(lhs: Expr, op: &str, rhs: Expr) -> InfixExpr {
+    let precedence = precedence(operator(op));
+    // operators group to the left, so only the right operand needs brackets
+    // when its operator binds as tightly
+    let lhs = bracketed_if(lhs, |operand| operand < precedence);
+    let rhs = bracketed_if(rhs, |operand| operand <= precedence);
+
+    let mut children = vec![part(&lhs)];
+    push_whitespace(&mut children, " ");
+    children.push(token(operator(op), op));
+    push_whitespace(&mut children, " ");
+    children.push(part(&rhs));
+    build(node(SyntaxKind::InfixExpr, children))
+}
+
+pub fn prefix_expr(op: &str, expr: Expr) -> PrefixExpr {
+    let expr = bracketed_if(expr, |operand| operand < PREFIX_PRECEDENCE);
+    let children = vec![token(operator(op), op), part(&expr)];
+    build(node(SyntaxKind::PrefixExpr, children))
+}
+
+pub fn round_bracket_expr(expr: Expr) -> RoundBracketExpr {
+    let children = vec![
+        token(SyntaxKind::LRoundBracket, "("),
+        part(&expr),
+        token(SyntaxKind::RRoundBracket, ")"),
+    ];
+    build(node(SyntaxKind::RoundBracketExpr, children))
+}
+
+pub fn variable_def(name: &str, value: Expr) -> VariableDef {
+    build(binding(SyntaxKind::VariableDef, name, ":=", value))
+}
+
+pub fn const_def(name: &str, value: Expr) -> ConstDef {
+    build(binding(SyntaxKind::ConstDef, name, "::", value))
+}
+
+pub fn assign_def(name: &str, value: Expr) -> AssignDef {
+    let mut children = vec![part(&variable_ref(name))];
+    push_whitespace(&mut children, " ");
+    children.push(token(SyntaxKind::Equals, "="));
+    push_whitespace(&mut children, " ");
+    children.push(part(&value));
+    build(node(SyntaxKind::AssignDef, children))
+}
+
+pub fn ty(name: &str) -> Type {
+    build(node(SyntaxKind::Type, vec![token(SyntaxKind::Ident, name)]))
+}
+
+pub fn fn_param(name: &str, ty: &str) -> FnParamDef {
+    build(typed_name(SyntaxKind::FnParamDef, name, ty))
+}
+
+/// Builds `name :: (params) -> ret { body }`.
+///
+/// The return type is left out if `ret` is empty and wrapped in brackets if
+/// it has more than one entry. Each statement of `body` goes on its own line.
+pub fn fn_def(
+    name: &str,
+    params: impl IntoIterator<Item = FnParamDef>,
+    ret: impl IntoIterator<Item = Type>,
+    body: impl IntoIterator<Item = Stmt>,
+) -> FnDef {
+    let mut children = name_and_kw(name, SyntaxKind::ConstKw, "::");
+    children.push(token(SyntaxKind::LRoundBracket, "("));
+    // the parser keeps the comma after a parameter in the parameter
+    let mut params: Vec<_> = params.into_iter().map(|param| part(&param)).collect();
+    if let Some((_, init)) = params.split_last_mut() {
+        for param in init {
+            let mut inner = vec![param.clone()];
+            push_into_last(&mut inner, token(SyntaxKind::Comma, ","));
+            push_whitespace(&mut inner, " ");
+            *param = inner.pop().unwrap();
+        }
+        children.push(node(SyntaxKind::FnParamListDef, params));
+    }
+    children.push(token(SyntaxKind::RRoundBracket, ")"));
+    push_whitespace(&mut children, " ");
+
+    let ret: Vec<_> = ret
+        .into_iter()
+        .map(|ty| node(SyntaxKind::FnReturnTypeDef, vec![part(&ty)]))
+        .collect();
+    let ret = match ret.len() {
+        0 => None,
+        1 => Some(ret),
+        _ => Some(vec![
+            token(SyntaxKind::LRoundBracket, "("),
+            node(SyntaxKind::FnReturnTypeListDef, comma_separated(ret)),
+            token(SyntaxKind::RRoundBracket, ")"),
+        ]),
+    };
+    if let Some(ret) = ret {
+        children.push(token(SyntaxKind::Arrow, "->"));
+        push_whitespace(&mut children, " ");
+        children.push(node(SyntaxKind::FnReturnDef, ret));
+        push_whitespace(&mut children, " ");
+    }
+
+    children.push(token(SyntaxKind::LCurlyBracket, "{"));
+    let body: Vec<_> = body.into_iter().map(|stmt| part(&stmt)).collect();
+    if body.is_empty() {
+        push_whitespace(&mut children, " ");
+    } else {
+        push_whitespace(&mut children, "\n    ");
+        children.push(node(SyntaxKind::FnBodyDef, lines(body)));
+    }
+    children.push(token(SyntaxKind::RCurlyBracket, "}"));
+    build(node(SyntaxKind::FnDef, children))
+}
+
+pub fn struct_field(name: &str, ty: &str) -> StructFieldDef {
+    build(typed_name(SyntaxKind::StructFieldDef, name, ty))
+}
+
+/// Builds `name :: struct { fields }` with one field per line.
+pub fn struct_def(name: &str, fields: impl IntoIterator<Item = StructFieldDef>) -> StructDef {
+    let mut children = name_and_kw(name, SyntaxKind::ConstKw, "::");
+    children.push(token(SyntaxKind::StructKw, "struct"));
+    push_whitespace(&mut children, " ");
+
+    let mut list = vec![token(SyntaxKind::LCurlyBracket, "{")];
+    let fields: Vec<_> = fields.into_iter().map(|field| part(&field)).collect();
+    if fields.is_empty() {
+        push_whitespace(&mut list, " ");
+    } else {
+        push_whitespace(&mut list, "\n    ");
+        list.extend(lines(fields));
+    }
+    list.push(token(SyntaxKind::RCurlyBracket, "}"));
+    children.push(node(SyntaxKind::StructFieldListDef, list));
+    build(node(SyntaxKind::StructDef, children))
+}
+
+pub mod tokens {
+    use super::*;
+
+    /// A detached, mutable whitespace token.
+    pub fn whitespace(text: &str) -> SyntaxToken {
+        assert!(text.trim().is_empty());
+
+        let root = node(SyntaxKind::Root, vec![token(SyntaxKind::Whitespace, text)]);
+        let root = SyntaxNode::new_root(root.into_node().unwrap()).clone_for_update();
+        let token = root.first_token().unwrap();
+        token.detach();
+        token
+    }
+}
+
+fn raw(kind: SyntaxKind) -> rowan::SyntaxKind {
+    Drip::kind_to_raw(kind)
+}
+
+fn token(kind: SyntaxKind, text: &str) -> Element {
+    GreenToken::new(raw(kind), text).into()
+}
+
+fn node(kind: SyntaxKind, children: Vec<Element>) -> Element {
+    GreenNode::new(raw(kind), children).into()
+}
+
+fn build<N: AstNode>(element: Element) -> N {
+    let green = element.into_node().unwrap();
+    N::cast(SyntaxNode::new_root(green)).unwrap()
+}
+
+/// `name :: value` or `name := value`.
+fn binding(kind: SyntaxKind, name: &str, op: &str, value: Expr) -> Element {
+    let op_kind = if op == ":=" {
+        SyntaxKind::VariableKw
+    } else {
+        SyntaxKind::ConstKw
+    };
+    let mut children = name_and_kw(name, op_kind, op);
+    children.push(part(&value));
+    node(kind, children)
+}
+
+fn name_and_kw(name: &str, kind: SyntaxKind, kw: &str) -> Vec<Element> {
+    let mut children = vec![token(SyntaxKind::Ident, name)];
+    push_whitespace(&mut children, " ");
+    children.push(token(kind, kw));
+    push_whitespace(&mut children, " ");
+    children
+}
+
+/// `name: ty`, as a parameter or a field.
+fn typed_name(kind: SyntaxKind, name: &str, ty: &str) -> Element {
+    let mut children = vec![
+        token(SyntaxKind::Ident, name),
+        token(SyntaxKind::Colon, ":"),
+    ];
+    push_whitespace(&mut children, " ");
+    children.push(part(&self::ty(ty)));
+    node(kind, children)
+}
+
+/// `items` with a `, ` between each.
+fn comma_separated(items: Vec<Element>) -> Vec<Element> {
+    let mut children = Vec::new();
+    for item in items {
+        if !children.is_empty() {
+            children.push(token(SyntaxKind::Comma, ","));
+            push_whitespace(&mut children, " ");
+        }
+        children.push(item);
+    }
+    children
+}
+
+/// `items` one per line, indented, for the inside of a `{ }`.
+fn lines(items: Vec<Element>) -> Vec<Element> {
+    let mut children = Vec::new();
+    for item in items {
+        if !children.is_empty() {
+            push_whitespace(&mut children, "\n    ");
+        }
+        children.push(item);
+    }
+    push_whitespace(&mut children, "\n");
+    children
+}
+
+/// Adds `element` as the last child of the last node in `children`.
+fn push_into_last(children: &mut [Element], element: Element) {
+    if let Some(NodeOrToken::Node(last)) = children.last_mut() {
+        let mut inner: Vec<_> = last.children().map(|child| child.to_owned()).collect();
+        inner.push(element);
+        *last = GreenNode::new(last.kind(), inner);
+    }
+}
+
+/// Adds whitespace after `children`, inside the innermost node that ends
+/// with their last token.
+fn push_whitespace(children: &mut Vec<Element>, text: &str) {
+    match children.last_mut() {
+        Some(NodeOrToken::Node(last)) if last.children().len() > 0 => {
+            let mut inner: Vec<_> = last.children().map(|child| child.to_owned()).collect();
+            push_whitespace(&mut inner, text);
+            *last = GreenNode::new(last.kind(), inner);
+        }
+        _ => children.push(token(SyntaxKind::Whitespace, text)),
+    }
+}
+
+/// The green tree of `node` without the trivia the parser attaches to its
+/// end.
+fn part(node: &impl AstNode) -> Element {
+    without_trailing_trivia(&node.syntax().green().into_owned()).into()
+}
+
+fn without_trailing_trivia(node: &GreenNode) -> GreenNode {
+    let mut children: Vec<Element> = node.children().map(|child| child.to_owned()).collect();
+    while let Some(last) = children.pop() {
+        match last {
+            NodeOrToken::Token(token) if is_trivia(token.kind()) => continue,
+            NodeOrToken::Token(token) => children.push(token.into()),
+            NodeOrToken::Node(inner) => {
+                let inner = without_trailing_trivia(&inner);
+                if inner.children().len() == 0 {
+                    continue;
+                }
+                children.push(inner.into());
+            }
+        }
+        break;
+    }
+    GreenNode::new(node.kind(), children)
+}
+
+fn is_trivia(kind: rowan::SyntaxKind) -> bool {
+    kind == raw(SyntaxKind::Whitespace) || kind == raw(SyntaxKind::Comment)
+}
+
+/// How tightly a prefix operator binds, which is tighter than any infix
+/// operator.
+const PREFIX_PRECEDENCE: u8 = 5;
+
+/// How tightly the infix operator `op` binds, as the parser sees it.
+fn precedence(op: SyntaxKind) -> u8 {
+    match op {
+        SyntaxKind::Dot2 => 1,
+        SyntaxKind::Plus | SyntaxKind::Minus => 3,
+        SyntaxKind::Star | SyntaxKind::Slash => 4,
+        _ => 2,
+    }
+}
+
+/// `expr`, in brackets if `needs_brackets` holds for how tightly it binds
+/// as an operand.
+fn bracketed_if(expr: Expr, needs_brackets: impl FnOnce(u8) -> bool) -> Expr {
+    let binds = match &expr {
+        Expr::InfixExpr(infix) => infix.op().map_or(u8::MAX, |op| precedence(op.kind())),
+        Expr::TernaryExpr(_) | Expr::ClosureExpr(_) => 0,
+        _ => u8::MAX,
+    };
+    if needs_brackets(binds) {
+        round_bracket_expr(expr).into()
+    } else {
+        expr
+    }
+}
+
+fn operator(op: &str) -> SyntaxKind {
+    match op {
+        "+" => SyntaxKind::Plus,
+        "-" => SyntaxKind::Minus,
+        "*" => SyntaxKind::Star,
+        "/" => SyntaxKind::Slash,
+        "==" => SyntaxKind::Equals2,
+        "!=" => SyntaxKind::NotEquals,
+        "<" => SyntaxKind::LAngledBracket,
+        "<=" => SyntaxKind::LessEquals,
+        ">" => SyntaxKind::RAngledBracket,
+        ">=" => SyntaxKind::GreaterEquals,
+        ".." => SyntaxKind::Dot2,
+        _ => panic!("`{}` isn't an operator", op),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn make_const_def() {
+        let value = infix_expr(literal(1).into(), "+", variable_ref("a").into());
+
+        assert_eq!(
+            const_def("b", value.into()).syntax().to_string(),
+            "b :: 1 + a"
+        );
+    }
+
+    #[test]
+    fn make_brackets_around_looser_operands() {
+        let sum = || infix_expr(literal(1).into(), "+", variable_ref("a").into());
+        let b = || Expr::from(variable_ref("b"));
+
+        let product = infix_expr(sum().into(), "*", b());
+        assert_eq!(product.syntax().to_string(), "(1 + a) * b");
+        let difference = infix_expr(b(), "-", sum().into());
+        assert_eq!(difference.syntax().to_string(), "b - (1 + a)");
+        let sum_of_sums = infix_expr(sum().into(), "+", b());
+        assert_eq!(sum_of_sums.syntax().to_string(), "1 + a + b");
+        let comparison = infix_expr(sum().into(), "<", product.into());
+        assert_eq!(comparison.syntax().to_string(), "1 + a < (1 + a) * b");
+        let negated = prefix_expr("-", sum().into());
+        assert_eq!(negated.syntax().to_string(), "-(1 + a)");
+    }
+
+    #[test]
+    fn make_fn_def() {
+        let body = infix_expr(variable_ref("a").into(), "*", variable_ref("b").into());
+        let f = fn_def(
+            "mul",
+            vec![fn_param("a", "i32"), fn_param("b", "i32")],
+            vec![ty("i32")],
+            vec![Stmt::Expr(body.into())],
+        );
+
+        assert_eq!(
+            f.syntax().to_string(),
+            "mul :: (a: i32, b: i32) -> i32 {\n    a * b\n}"
+        );
+        assert_eq!(f.params().count(), 2);
+    }
+
+    #[test]
+    fn make_fn_def_with_multiple_return_types() {
+        let f = fn_def("f", None, vec![ty("i32"), ty("string")], None);
+
+        assert_eq!(f.syntax().to_string(), "f :: () -> (i32, string) { }");
+    }
+
+    #[test]
+    fn make_struct_def() {
+        let s = struct_def(
+            "Vec2",
+            vec![struct_field("x", "f32"), struct_field("y", "f32")],
+        );
+
+        assert_eq!(
+            s.syntax().to_string(),
+            "Vec2 :: struct {\n    x: f32\n    y: f32\n}"
+        );
+    }
+
+    #[test]
+    fn make_the_trees_the_parser_makes() {
+        fn check(made: &SyntaxNode) {
+            let text = made.to_string();
+            let parsed = drip_parser::parse(&text).syntax();
+            let parsed = parsed.first_child().unwrap();
+            assert_eq!(format!("{:#?}", made), format!("{:#?}", parsed), "{}", text);
+        }
+
+        let sum = infix_expr(literal(1).into(), "+", variable_ref("a").into());
+        check(sum.syntax());
+        check(prefix_expr("-", variable_ref("x").into()).syntax());
+        check(round_bracket_expr(sum.clone().into()).syntax());
+        check(variable_def("c", sum.clone().into()).syntax());
+        check(const_def("c", literal(2).into()).syntax());
+        check(assign_def("c", sum.clone().into()).syntax());
+        check(
+            fn_def(
+                "f",
+                vec![fn_param("a", "i32"), fn_param("b", "i32")],
+                vec![ty("i32")],
+                vec![Stmt::Expr(sum.into()), Stmt::Expr(literal(3).into())],
+            )
+            .syntax(),
+        );
+        check(fn_def("f", None, vec![ty("i32"), ty("string")], None).syntax());
+        check(
+            struct_def(
+                "S",
+                vec![struct_field("x", "f32"), struct_field("y", "f32")],
+            )
+            .syntax(),
+        );
+        check(struct_def("S", None).syntax());
+    }
+
+    #[test]
+    fn make_strips_trailing_trivia_of_parts() {
+        let root = crate::Root::cast(drip_parser::parse("1 // one\n").syntax()).unwrap();
+        let one = match root.stmts().next() {
+            Some(Stmt::Expr(expr)) => expr,
+            _ => unreachable!(),
+        };
+
+        assert_eq!(round_bracket_expr(one).syntax().to_string(), "(1)");
+    }
+}
@@ -0,0 +1,132 @@
+//! Pointers to nodes that don't keep the tree alive.
+//!
+//! A pointer remembers a node's kind and range, so it can be stored in
+//! long-lived data like HIR source maps and turned back into a node given the
+//! root of the same tree.
+
+use crate::AstNode;
+use drip_syntax::{SyntaxKind, SyntaxNode};
+use rowan::NodeOrToken;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use text_size::TextRange;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SyntaxNodePtr {
+    kind: SyntaxKind,
+    range: TextRange,
+}
+
+impl SyntaxNodePtr {
+    pub fn new(node: &SyntaxNode) -> Self {
+        Self {
+            kind: node.kind(),
+            range: node.text_range(),
+        }
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    pub fn range(&self) -> TextRange {
+        self.range
+    }
+
+    /// Finds the node in the tree rooted at `root`.
+    ///
+    /// Panics if the tree doesn't contain it.
+    pub fn to_node(&self, root: &SyntaxNode) -> SyntaxNode {
+        let node = match root.covering_element(self.range) {
+            NodeOrToken::Node(node) => node,
+            NodeOrToken::Token(token) => token.parent().unwrap(),
+        };
+
+        node.ancestors()
+            .find(|node| node.kind() == self.kind && node.text_range() == self.range)
+            .unwrap_or_else(|| panic!("can't find {:?} in {:?}", self, root))
+    }
+}
+
+/// A [`SyntaxNodePtr`] that remembers which `AstNode` it points to.
+pub struct AstPtr<N> {
+    raw: SyntaxNodePtr,
+    ph: PhantomData<fn() -> N>,
+}
+
+impl<N: AstNode> AstPtr<N> {
+    pub fn new(node: &N) -> Self {
+        Self {
+            raw: SyntaxNodePtr::new(node.syntax()),
+            ph: PhantomData,
+        }
+    }
+
+    pub fn to_node(&self, root: &SyntaxNode) -> N {
+        N::cast(self.raw.to_node(root)).unwrap()
+    }
+}
+
+impl<N> AstPtr<N> {
+    pub fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.raw
+    }
+}
+
+impl<N> Clone for AstPtr<N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<N> Copy for AstPtr<N> {}
+
+impl<N> PartialEq for AstPtr<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw == other.raw
+    }
+}
+
+impl<N> Eq for AstPtr<N> {}
+
+impl<N> Hash for AstPtr<N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.raw.hash(state)
+    }
+}
+
+impl<N> fmt::Debug for AstPtr<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AstPtr").field(&self.raw).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Expr, Root, Stmt};
+
+    #[test]
+    fn find_node_with_same_range_as_its_parent() {
+        let root = drip_parser::parse("(1)").syntax();
+        let inner = root
+            .descendants()
+            .find(|node| node.kind() == SyntaxKind::Literal)
+            .unwrap();
+        let ptr = AstPtr::new(&Expr::cast(inner.clone()).unwrap());
+
+        assert_eq!(ptr.to_node(&root).syntax(), &inner);
+    }
+
+    #[test]
+    fn find_node_in_a_new_tree() {
+        let input = "a :: 1\nb :: a + 2";
+        let root = Root::cast(drip_parser::parse(input).syntax()).unwrap();
+        let b = root.stmts().nth(1).unwrap();
+        let ptr = AstPtr::<Stmt>::new(&b);
+
+        let new_root = drip_parser::parse(input).syntax();
+        assert_eq!(ptr.to_node(&new_root).syntax().to_string(), "b :: a + 2");
+    }
+}
@@ -1,3 +1,4 @@
+use crate::visit::{self, Visitor, Walk};
 use crate::Literal;
 use drip_syntax::SyntaxNode;
 use std::fmt;
@@ -10,6 +11,16 @@ pub struct ValidationError {
     range: TextRange,
 }
 
+impl ValidationError {
+    pub fn kind(&self) -> ValidationErrorKind {
+        self.kind
+    }
+
+    pub fn range(&self) -> TextRange {
+        self.range
+    }
+}
+
 impl fmt::Display for ValidationError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(
@@ -23,7 +34,7 @@ impl fmt::Display for ValidationError {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
-enum ValidationErrorKind {
+pub enum ValidationErrorKind {
     NumberLiteralTooLarge,
 }
 
@@ -40,23 +51,28 @@ impl fmt::Display for ValidationErrorKind {
 }
 
 pub fn validate(node: &SyntaxNode) -> Vec<ValidationError> {
-    let mut errors = Vec::new();
+    let mut validator = Validator::default();
+    visit::walk(node, &mut validator);
 
-    for node in node.descendants() {
-        if let Some(literal) = Literal::cast(node) {
-            validate_literal(literal, &mut errors)
-        }
-    }
+    validator.errors
+}
 
-    errors
+#[derive(Default)]
+struct Validator {
+    errors: Vec<ValidationError>,
 }
 
-fn validate_literal(literal: Literal, errors: &mut Vec<ValidationError>) {
-    if literal.parse().is_none() {
-        errors.push(ValidationError {
-            kind: ValidationErrorKind::NumberLiteralTooLarge,
-            range: literal.0.first_token().unwrap().text_range(),
-        })
+impl Visitor for Validator {
+    fn enter_literal(&mut self, literal: &Literal) -> Walk {
+        let is_int = literal.number().is_some_and(|n| !n.is_float());
+        if is_int && literal.parse().is_none() {
+            self.errors.push(ValidationError {
+                kind: ValidationErrorKind::NumberLiteralTooLarge,
+                range: literal.0.first_token().unwrap().text_range(),
+            })
+        }
+
+        Walk::Continue
     }
 }
 
@@ -88,6 +104,11 @@ mod tests {
         check("123", &[]);
     }
 
+    #[test]
+    fn validate_float_and_string_literals() {
+        check("3.14 + \"hello\"", &[]);
+    }
+
     #[test]
     fn validate_too_large_literal() {
         check(
@@ -95,4 +116,13 @@ mod tests {
             &[(ValidationErrorKind::NumberLiteralTooLarge, (0..20))],
         );
     }
+
+    #[test]
+    fn validate_literals_with_separators() {
+        check("1_000", &[]);
+        check(
+            "99_999_999_999_999_999_999",
+            &[(ValidationErrorKind::NumberLiteralTooLarge, (0..26))],
+        );
+    }
 }
@@ -0,0 +1,271 @@
+//! Traversal of typed AST nodes.
+//!
+//! Implement [`Visitor`] for the node kinds you're interested in and hand it
+//! to [`walk`], which visits the tree in source order.
+
+mod generated;
+
+pub use generated::Visitor;
+
+use crate::{AstNode, BlockExpr, ClosureExpr, FnBodyDef, ForExpr, ImplItemListDef, MatchArm};
+use crate::{Root, TraitFnListDef};
+use drip_syntax::{SyntaxKind, SyntaxNode};
+use rowan::WalkEvent;
+
+/// What [`walk`] should do after entering a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Walk {
+    Continue,
+    /// Don't visit the node's children. Its leave hook is still called.
+    SkipChildren,
+}
+
+/// A node that introduces a new scope for the definitions inside it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Scope {
+    Root(Root),
+    FnBody(FnBodyDef),
+    TraitBody(TraitFnListDef),
+    ImplBody(ImplItemListDef),
+    Block(BlockExpr),
+    /// The parameters of a closure and its body.
+    Closure(ClosureExpr),
+    /// The body of a `for`, where its loop variables are bound. It's only
+    /// entered after the iterable, which is evaluated outside of it, so the
+    /// loop variables are visited before it.
+    For(ForExpr),
+    /// The bindings of an arm's patterns and its body.
+    MatchArm(MatchArm),
+}
+
+impl Scope {
+    pub fn cast(syntax: SyntaxNode) -> Option<Self> {
+        let scope = match syntax.kind() {
+            SyntaxKind::Root => Self::Root(Root(syntax)),
+            SyntaxKind::FnBodyDef => Self::FnBody(FnBodyDef(syntax)),
+            SyntaxKind::TraitFnListDef => Self::TraitBody(TraitFnListDef(syntax)),
+            SyntaxKind::ImplItemListDef => Self::ImplBody(ImplItemListDef(syntax)),
+            SyntaxKind::BlockExpr => Self::Block(BlockExpr(syntax)),
+            SyntaxKind::ClosureExpr => Self::Closure(ClosureExpr(syntax)),
+            SyntaxKind::ForExpr => Self::For(ForExpr(syntax)),
+            SyntaxKind::MatchArm => Self::MatchArm(MatchArm(syntax)),
+            _ => return None,
+        };
+
+        Some(scope)
+    }
+
+    pub fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Self::Root(it) => it.syntax(),
+            Self::FnBody(it) => it.syntax(),
+            Self::TraitBody(it) => it.syntax(),
+            Self::ImplBody(it) => it.syntax(),
+            Self::Block(it) => it.syntax(),
+            Self::Closure(it) => it.syntax(),
+            Self::For(it) => it.syntax(),
+            Self::MatchArm(it) => it.syntax(),
+        }
+    }
+}
+
+/// Walks `node` and all of its descendants, calling `visitor`'s hooks.
+///
+/// A scope is entered right after its node's enter hook and exited right
+/// before its leave hook, except that a `for` is entered right after its
+/// iterable's leave hook. Skipped scopes are neither entered nor exited.
+pub fn walk(root: &SyntaxNode, visitor: &mut (impl Visitor + ?Sized)) {
+    let mut preorder = root.preorder();
+    let mut skipped = None;
+
+    while let Some(event) = preorder.next() {
+        match event {
+            WalkEvent::Enter(node) => {
+                let has_iterable = for_iterable(&node).is_some();
+                if generated::enter(visitor, &node) == Walk::SkipChildren {
+                    preorder.skip_subtree();
+                    skipped = Some(node);
+                } else if let Some(scope) = Scope::cast(node) {
+                    if !has_iterable {
+                        visitor.enter_scope(&scope);
+                    }
+                }
+            }
+            WalkEvent::Leave(node) => {
+                if skipped.as_ref() == Some(&node) {
+                    skipped = None;
+                } else if let Some(scope) = Scope::cast(node.clone()) {
+                    visitor.exit_scope(&scope);
+                }
+                generated::leave(visitor, &node);
+
+                let parent = node.parent().filter(|_| node != *root);
+                if let Some(parent) = parent {
+                    if for_iterable(&parent).as_ref() == Some(&node) {
+                        visitor.enter_scope(&Scope::For(ForExpr(parent)));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The iterable of the `for` `node`, if it has both an iterable and a body.
+/// With only one of them, there's no telling which one it is.
+fn for_iterable(node: &SyntaxNode) -> Option<SyntaxNode> {
+    let for_expr = ForExpr::cast(node.clone())?;
+    for_expr.body()?;
+    Some(for_expr.iterable()?.syntax().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AstToken, FnDef, VariableRef};
+    use expect_test::{expect, Expect};
+
+    /// Records every hook call, indented by scope depth.
+    #[derive(Default)]
+    struct Recorder {
+        depth: usize,
+        events: String,
+        skip_fns: bool,
+    }
+
+    impl Recorder {
+        fn record(&mut self, event: String) {
+            self.events.push_str(&"  ".repeat(self.depth));
+            self.events.push_str(&event);
+            self.events.push('\n');
+        }
+    }
+
+    impl Visitor for Recorder {
+        fn enter_scope(&mut self, scope: &Scope) {
+            self.record(format!("enter scope {:?}", scope.syntax().kind()));
+            self.depth += 1;
+        }
+
+        fn exit_scope(&mut self, scope: &Scope) {
+            self.depth -= 1;
+            self.record(format!("exit scope {:?}", scope.syntax().kind()));
+        }
+
+        fn enter_fn_def(&mut self, node: &FnDef) -> Walk {
+            self.record(format!("enter fn {}", node.name().unwrap().text()));
+            if self.skip_fns {
+                Walk::SkipChildren
+            } else {
+                Walk::Continue
+            }
+        }
+
+        fn leave_fn_def(&mut self, node: &FnDef) {
+            self.record(format!("leave fn {}", node.name().unwrap().text()));
+        }
+
+        fn enter_variable_ref(&mut self, node: &VariableRef) -> Walk {
+            self.record(format!("ref {}", node.name().unwrap().text()));
+            Walk::Continue
+        }
+    }
+
+    fn check(input: &str, skip_fns: bool, expected: Expect) {
+        let mut recorder = Recorder {
+            skip_fns,
+            ..Recorder::default()
+        };
+        walk(&drip_parser::parse(input).syntax(), &mut recorder);
+
+        expected.assert_eq(&recorder.events);
+    }
+
+    #[test]
+    fn walk_in_source_order_with_scopes() {
+        check(
+            "a\nf :: (x: i32) { x\ng :: () { y } }\nb",
+            false,
+            expect![[r#"
+enter scope Root
+  ref a
+  enter fn f
+  enter scope FnBodyDef
+    ref x
+    enter fn g
+    enter scope FnBodyDef
+      ref y
+    exit scope FnBodyDef
+    leave fn g
+  exit scope FnBodyDef
+  leave fn f
+  ref b
+exit scope Root
+"#]],
+        );
+    }
+
+    #[test]
+    fn enter_expression_scopes() {
+        check(
+            "f :: () {\n{ a }\ng :: |x| x\nfor i in r { i }\nmatch v { 1 => w }\n}",
+            false,
+            expect![[r#"
+enter scope Root
+  enter fn f
+  enter scope FnBodyDef
+    enter scope BlockExpr
+      ref a
+    exit scope BlockExpr
+    enter scope ClosureExpr
+      ref x
+    exit scope ClosureExpr
+    ref r
+    enter scope ForExpr
+      enter scope BlockExpr
+        ref i
+      exit scope BlockExpr
+    exit scope ForExpr
+    ref v
+    enter scope MatchArm
+      ref w
+    exit scope MatchArm
+  exit scope FnBodyDef
+  leave fn f
+exit scope Root
+"#]],
+        );
+    }
+
+    #[test]
+    fn walk_for_iterables_outside_of_the_loop() {
+        check(
+            "for x in x { x }",
+            false,
+            expect![[r#"
+enter scope Root
+  ref x
+  enter scope ForExpr
+    enter scope BlockExpr
+      ref x
+    exit scope BlockExpr
+  exit scope ForExpr
+exit scope Root
+"#]],
+        );
+    }
+
+    #[test]
+    fn skip_children() {
+        check(
+            "f :: () { x }\nb",
+            true,
+            expect![[r#"
+enter scope Root
+  enter fn f
+  leave fn f
+  ref b
+exit scope Root
+"#]],
+        );
+    }
+}
@@ -0,0 +1,538 @@
+//! Generated by `cargo run -p drip_codegen`, do not edit by hand.
+
+use super::{Scope, Walk};
+use crate::*;
+use drip_syntax::{SyntaxKind, SyntaxNode};
+
+/// Hooks called by [`super::walk`] when it enters and leaves each kind of node.
+///
+/// Every hook does nothing by default.
+pub trait Visitor {
+    fn enter_scope(&mut self, _scope: &Scope) {}
+
+    fn exit_scope(&mut self, _scope: &Scope) {}
+
+    fn enter_root(&mut self, _node: &Root) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_root(&mut self, _node: &Root) {}
+
+    fn enter_variable_def(&mut self, _node: &VariableDef) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_variable_def(&mut self, _node: &VariableDef) {}
+
+    fn enter_const_def(&mut self, _node: &ConstDef) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_const_def(&mut self, _node: &ConstDef) {}
+
+    fn enter_assign_def(&mut self, _node: &AssignDef) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_assign_def(&mut self, _node: &AssignDef) {}
+
+    fn enter_fn_def(&mut self, _node: &FnDef) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_fn_def(&mut self, _node: &FnDef) {}
+
+    fn enter_struct_def(&mut self, _node: &StructDef) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_struct_def(&mut self, _node: &StructDef) {}
+
+    fn enter_trait_def(&mut self, _node: &TraitDef) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_trait_def(&mut self, _node: &TraitDef) {}
+
+    fn enter_impl_def(&mut self, _node: &ImplDef) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_impl_def(&mut self, _node: &ImplDef) {}
+
+    fn enter_infix_expr(&mut self, _node: &InfixExpr) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_infix_expr(&mut self, _node: &InfixExpr) {}
+
+    fn enter_prefix_expr(&mut self, _node: &PrefixExpr) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_prefix_expr(&mut self, _node: &PrefixExpr) {}
+
+    fn enter_ternary_expr(&mut self, _node: &TernaryExpr) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_ternary_expr(&mut self, _node: &TernaryExpr) {}
+
+    fn enter_literal(&mut self, _node: &Literal) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_literal(&mut self, _node: &Literal) {}
+
+    fn enter_round_bracket_expr(&mut self, _node: &RoundBracketExpr) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_round_bracket_expr(&mut self, _node: &RoundBracketExpr) {}
+
+    fn enter_tuple_expr(&mut self, _node: &TupleExpr) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_tuple_expr(&mut self, _node: &TupleExpr) {}
+
+    fn enter_array_expr(&mut self, _node: &ArrayExpr) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_array_expr(&mut self, _node: &ArrayExpr) {}
+
+    fn enter_call_expr(&mut self, _node: &CallExpr) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_call_expr(&mut self, _node: &CallExpr) {}
+
+    fn enter_method_call_expr(&mut self, _node: &MethodCallExpr) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_method_call_expr(&mut self, _node: &MethodCallExpr) {}
+
+    fn enter_field_expr(&mut self, _node: &FieldExpr) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_field_expr(&mut self, _node: &FieldExpr) {}
+
+    fn enter_index_expr(&mut self, _node: &IndexExpr) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_index_expr(&mut self, _node: &IndexExpr) {}
+
+    fn enter_struct_expr(&mut self, _node: &StructExpr) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_struct_expr(&mut self, _node: &StructExpr) {}
+
+    fn enter_closure_expr(&mut self, _node: &ClosureExpr) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_closure_expr(&mut self, _node: &ClosureExpr) {}
+
+    fn enter_placeholder_expr(&mut self, _node: &PlaceholderExpr) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_placeholder_expr(&mut self, _node: &PlaceholderExpr) {}
+
+    fn enter_variable_ref(&mut self, _node: &VariableRef) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_variable_ref(&mut self, _node: &VariableRef) {}
+
+    fn enter_path_expr(&mut self, _node: &PathExpr) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_path_expr(&mut self, _node: &PathExpr) {}
+
+    fn enter_block_expr(&mut self, _node: &BlockExpr) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_block_expr(&mut self, _node: &BlockExpr) {}
+
+    fn enter_if_expr(&mut self, _node: &IfExpr) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_if_expr(&mut self, _node: &IfExpr) {}
+
+    fn enter_loop_expr(&mut self, _node: &LoopExpr) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_loop_expr(&mut self, _node: &LoopExpr) {}
+
+    fn enter_while_expr(&mut self, _node: &WhileExpr) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_while_expr(&mut self, _node: &WhileExpr) {}
+
+    fn enter_for_expr(&mut self, _node: &ForExpr) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_for_expr(&mut self, _node: &ForExpr) {}
+
+    fn enter_break_expr(&mut self, _node: &BreakExpr) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_break_expr(&mut self, _node: &BreakExpr) {}
+
+    fn enter_continue_expr(&mut self, _node: &ContinueExpr) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_continue_expr(&mut self, _node: &ContinueExpr) {}
+
+    fn enter_match_expr(&mut self, _node: &MatchExpr) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_match_expr(&mut self, _node: &MatchExpr) {}
+
+    fn enter_name_list(&mut self, _node: &NameList) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_name_list(&mut self, _node: &NameList) {}
+
+    fn enter_ident_pat(&mut self, _node: &IdentPat) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_ident_pat(&mut self, _node: &IdentPat) {}
+
+    fn enter_arg_list(&mut self, _node: &ArgList) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_arg_list(&mut self, _node: &ArgList) {}
+
+    fn enter_struct_expr_field(&mut self, _node: &StructExprField) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_struct_expr_field(&mut self, _node: &StructExprField) {}
+
+    fn enter_fn_param_list_def(&mut self, _node: &FnParamListDef) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_fn_param_list_def(&mut self, _node: &FnParamListDef) {}
+
+    fn enter_named_arg(&mut self, _node: &NamedArg) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_named_arg(&mut self, _node: &NamedArg) {}
+
+    fn enter_type(&mut self, _node: &Type) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_type(&mut self, _node: &Type) {}
+
+    fn enter_match_arm_list(&mut self, _node: &MatchArmList) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_match_arm_list(&mut self, _node: &MatchArmList) {}
+
+    fn enter_match_arm(&mut self, _node: &MatchArm) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_match_arm(&mut self, _node: &MatchArm) {}
+
+    fn enter_wildcard_pat(&mut self, _node: &WildcardPat) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_wildcard_pat(&mut self, _node: &WildcardPat) {}
+
+    fn enter_literal_pat(&mut self, _node: &LiteralPat) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_literal_pat(&mut self, _node: &LiteralPat) {}
+
+    fn enter_range_pat(&mut self, _node: &RangePat) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_range_pat(&mut self, _node: &RangePat) {}
+
+    fn enter_tuple_pat(&mut self, _node: &TuplePat) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_tuple_pat(&mut self, _node: &TuplePat) {}
+
+    fn enter_struct_pat(&mut self, _node: &StructPat) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_struct_pat(&mut self, _node: &StructPat) {}
+
+    fn enter_field_pat(&mut self, _node: &FieldPat) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_field_pat(&mut self, _node: &FieldPat) {}
+
+    fn enter_fn_return_def(&mut self, _node: &FnReturnDef) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_fn_return_def(&mut self, _node: &FnReturnDef) {}
+
+    fn enter_fn_body_def(&mut self, _node: &FnBodyDef) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_fn_body_def(&mut self, _node: &FnBodyDef) {}
+
+    fn enter_fn_param_def(&mut self, _node: &FnParamDef) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_fn_param_def(&mut self, _node: &FnParamDef) {}
+
+    fn enter_fn_return_type_list_def(&mut self, _node: &FnReturnTypeListDef) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_fn_return_type_list_def(&mut self, _node: &FnReturnTypeListDef) {}
+
+    fn enter_fn_return_type_def(&mut self, _node: &FnReturnTypeDef) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_fn_return_type_def(&mut self, _node: &FnReturnTypeDef) {}
+
+    fn enter_struct_field_list_def(&mut self, _node: &StructFieldListDef) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_struct_field_list_def(&mut self, _node: &StructFieldListDef) {}
+
+    fn enter_struct_field_def(&mut self, _node: &StructFieldDef) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_struct_field_def(&mut self, _node: &StructFieldDef) {}
+
+    fn enter_trait_lists_def(&mut self, _node: &TraitListsDef) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_trait_lists_def(&mut self, _node: &TraitListsDef) {}
+
+    fn enter_trait_type_list_def(&mut self, _node: &TraitTypeListDef) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_trait_type_list_def(&mut self, _node: &TraitTypeListDef) {}
+
+    fn enter_trait_fn_list_def(&mut self, _node: &TraitFnListDef) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_trait_fn_list_def(&mut self, _node: &TraitFnListDef) {}
+
+    fn enter_trait_type_def(&mut self, _node: &TraitTypeDef) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_trait_type_def(&mut self, _node: &TraitTypeDef) {}
+
+    fn enter_impl_item_list_def(&mut self, _node: &ImplItemListDef) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave_impl_item_list_def(&mut self, _node: &ImplItemListDef) {}
+}
+
+pub(super) fn enter(visitor: &mut (impl Visitor + ?Sized), node: &SyntaxNode) -> Walk {
+    match node.kind() {
+        SyntaxKind::Root => visitor.enter_root(&Root(node.clone())),
+        SyntaxKind::VariableDef => visitor.enter_variable_def(&VariableDef(node.clone())),
+        SyntaxKind::ConstDef => visitor.enter_const_def(&ConstDef(node.clone())),
+        SyntaxKind::AssignDef => visitor.enter_assign_def(&AssignDef(node.clone())),
+        SyntaxKind::FnDef => visitor.enter_fn_def(&FnDef(node.clone())),
+        SyntaxKind::StructDef => visitor.enter_struct_def(&StructDef(node.clone())),
+        SyntaxKind::TraitDef => visitor.enter_trait_def(&TraitDef(node.clone())),
+        SyntaxKind::ImplDef => visitor.enter_impl_def(&ImplDef(node.clone())),
+        SyntaxKind::InfixExpr => visitor.enter_infix_expr(&InfixExpr(node.clone())),
+        SyntaxKind::PrefixExpr => visitor.enter_prefix_expr(&PrefixExpr(node.clone())),
+        SyntaxKind::TernaryExpr => visitor.enter_ternary_expr(&TernaryExpr(node.clone())),
+        SyntaxKind::Literal => visitor.enter_literal(&Literal(node.clone())),
+        SyntaxKind::RoundBracketExpr => {
+            visitor.enter_round_bracket_expr(&RoundBracketExpr(node.clone()))
+        }
+        SyntaxKind::TupleExpr => visitor.enter_tuple_expr(&TupleExpr(node.clone())),
+        SyntaxKind::ArrayExpr => visitor.enter_array_expr(&ArrayExpr(node.clone())),
+        SyntaxKind::CallExpr => visitor.enter_call_expr(&CallExpr(node.clone())),
+        SyntaxKind::MethodCallExpr => visitor.enter_method_call_expr(&MethodCallExpr(node.clone())),
+        SyntaxKind::FieldExpr => visitor.enter_field_expr(&FieldExpr(node.clone())),
+        SyntaxKind::IndexExpr => visitor.enter_index_expr(&IndexExpr(node.clone())),
+        SyntaxKind::StructExpr => visitor.enter_struct_expr(&StructExpr(node.clone())),
+        SyntaxKind::ClosureExpr => visitor.enter_closure_expr(&ClosureExpr(node.clone())),
+        SyntaxKind::PlaceholderExpr => {
+            visitor.enter_placeholder_expr(&PlaceholderExpr(node.clone()))
+        }
+        SyntaxKind::VariableRef => visitor.enter_variable_ref(&VariableRef(node.clone())),
+        SyntaxKind::PathExpr => visitor.enter_path_expr(&PathExpr(node.clone())),
+        SyntaxKind::BlockExpr => visitor.enter_block_expr(&BlockExpr(node.clone())),
+        SyntaxKind::IfExpr => visitor.enter_if_expr(&IfExpr(node.clone())),
+        SyntaxKind::LoopExpr => visitor.enter_loop_expr(&LoopExpr(node.clone())),
+        SyntaxKind::WhileExpr => visitor.enter_while_expr(&WhileExpr(node.clone())),
+        SyntaxKind::ForExpr => visitor.enter_for_expr(&ForExpr(node.clone())),
+        SyntaxKind::BreakExpr => visitor.enter_break_expr(&BreakExpr(node.clone())),
+        SyntaxKind::ContinueExpr => visitor.enter_continue_expr(&ContinueExpr(node.clone())),
+        SyntaxKind::MatchExpr => visitor.enter_match_expr(&MatchExpr(node.clone())),
+        SyntaxKind::NameList => visitor.enter_name_list(&NameList(node.clone())),
+        SyntaxKind::IdentPat => visitor.enter_ident_pat(&IdentPat(node.clone())),
+        SyntaxKind::ArgList => visitor.enter_arg_list(&ArgList(node.clone())),
+        SyntaxKind::StructExprField => {
+            visitor.enter_struct_expr_field(&StructExprField(node.clone()))
+        }
+        SyntaxKind::FnParamListDef => {
+            visitor.enter_fn_param_list_def(&FnParamListDef(node.clone()))
+        }
+        SyntaxKind::NamedArg => visitor.enter_named_arg(&NamedArg(node.clone())),
+        SyntaxKind::Type => visitor.enter_type(&Type(node.clone())),
+        SyntaxKind::MatchArmList => visitor.enter_match_arm_list(&MatchArmList(node.clone())),
+        SyntaxKind::MatchArm => visitor.enter_match_arm(&MatchArm(node.clone())),
+        SyntaxKind::WildcardPat => visitor.enter_wildcard_pat(&WildcardPat(node.clone())),
+        SyntaxKind::LiteralPat => visitor.enter_literal_pat(&LiteralPat(node.clone())),
+        SyntaxKind::RangePat => visitor.enter_range_pat(&RangePat(node.clone())),
+        SyntaxKind::TuplePat => visitor.enter_tuple_pat(&TuplePat(node.clone())),
+        SyntaxKind::StructPat => visitor.enter_struct_pat(&StructPat(node.clone())),
+        SyntaxKind::FieldPat => visitor.enter_field_pat(&FieldPat(node.clone())),
+        SyntaxKind::FnReturnDef => visitor.enter_fn_return_def(&FnReturnDef(node.clone())),
+        SyntaxKind::FnBodyDef => visitor.enter_fn_body_def(&FnBodyDef(node.clone())),
+        SyntaxKind::FnParamDef => visitor.enter_fn_param_def(&FnParamDef(node.clone())),
+        SyntaxKind::FnReturnTypeListDef => {
+            visitor.enter_fn_return_type_list_def(&FnReturnTypeListDef(node.clone()))
+        }
+        SyntaxKind::FnReturnTypeDef => {
+            visitor.enter_fn_return_type_def(&FnReturnTypeDef(node.clone()))
+        }
+        SyntaxKind::StructFieldListDef => {
+            visitor.enter_struct_field_list_def(&StructFieldListDef(node.clone()))
+        }
+        SyntaxKind::StructFieldDef => visitor.enter_struct_field_def(&StructFieldDef(node.clone())),
+        SyntaxKind::TraitListsDef => visitor.enter_trait_lists_def(&TraitListsDef(node.clone())),
+        SyntaxKind::TraitTypeListDef => {
+            visitor.enter_trait_type_list_def(&TraitTypeListDef(node.clone()))
+        }
+        SyntaxKind::TraitFnListDef => {
+            visitor.enter_trait_fn_list_def(&TraitFnListDef(node.clone()))
+        }
+        SyntaxKind::TraitTypeDef => visitor.enter_trait_type_def(&TraitTypeDef(node.clone())),
+        SyntaxKind::ImplItemListDef => {
+            visitor.enter_impl_item_list_def(&ImplItemListDef(node.clone()))
+        }
+        _ => Walk::Continue,
+    }
+}
+
+pub(super) fn leave(visitor: &mut (impl Visitor + ?Sized), node: &SyntaxNode) {
+    match node.kind() {
+        SyntaxKind::Root => visitor.leave_root(&Root(node.clone())),
+        SyntaxKind::VariableDef => visitor.leave_variable_def(&VariableDef(node.clone())),
+        SyntaxKind::ConstDef => visitor.leave_const_def(&ConstDef(node.clone())),
+        SyntaxKind::AssignDef => visitor.leave_assign_def(&AssignDef(node.clone())),
+        SyntaxKind::FnDef => visitor.leave_fn_def(&FnDef(node.clone())),
+        SyntaxKind::StructDef => visitor.leave_struct_def(&StructDef(node.clone())),
+        SyntaxKind::TraitDef => visitor.leave_trait_def(&TraitDef(node.clone())),
+        SyntaxKind::ImplDef => visitor.leave_impl_def(&ImplDef(node.clone())),
+        SyntaxKind::InfixExpr => visitor.leave_infix_expr(&InfixExpr(node.clone())),
+        SyntaxKind::PrefixExpr => visitor.leave_prefix_expr(&PrefixExpr(node.clone())),
+        SyntaxKind::TernaryExpr => visitor.leave_ternary_expr(&TernaryExpr(node.clone())),
+        SyntaxKind::Literal => visitor.leave_literal(&Literal(node.clone())),
+        SyntaxKind::RoundBracketExpr => {
+            visitor.leave_round_bracket_expr(&RoundBracketExpr(node.clone()))
+        }
+        SyntaxKind::TupleExpr => visitor.leave_tuple_expr(&TupleExpr(node.clone())),
+        SyntaxKind::ArrayExpr => visitor.leave_array_expr(&ArrayExpr(node.clone())),
+        SyntaxKind::CallExpr => visitor.leave_call_expr(&CallExpr(node.clone())),
+        SyntaxKind::MethodCallExpr => visitor.leave_method_call_expr(&MethodCallExpr(node.clone())),
+        SyntaxKind::FieldExpr => visitor.leave_field_expr(&FieldExpr(node.clone())),
+        SyntaxKind::IndexExpr => visitor.leave_index_expr(&IndexExpr(node.clone())),
+        SyntaxKind::StructExpr => visitor.leave_struct_expr(&StructExpr(node.clone())),
+        SyntaxKind::ClosureExpr => visitor.leave_closure_expr(&ClosureExpr(node.clone())),
+        SyntaxKind::PlaceholderExpr => {
+            visitor.leave_placeholder_expr(&PlaceholderExpr(node.clone()))
+        }
+        SyntaxKind::VariableRef => visitor.leave_variable_ref(&VariableRef(node.clone())),
+        SyntaxKind::PathExpr => visitor.leave_path_expr(&PathExpr(node.clone())),
+        SyntaxKind::BlockExpr => visitor.leave_block_expr(&BlockExpr(node.clone())),
+        SyntaxKind::IfExpr => visitor.leave_if_expr(&IfExpr(node.clone())),
+        SyntaxKind::LoopExpr => visitor.leave_loop_expr(&LoopExpr(node.clone())),
+        SyntaxKind::WhileExpr => visitor.leave_while_expr(&WhileExpr(node.clone())),
+        SyntaxKind::ForExpr => visitor.leave_for_expr(&ForExpr(node.clone())),
+        SyntaxKind::BreakExpr => visitor.leave_break_expr(&BreakExpr(node.clone())),
+        SyntaxKind::ContinueExpr => visitor.leave_continue_expr(&ContinueExpr(node.clone())),
+        SyntaxKind::MatchExpr => visitor.leave_match_expr(&MatchExpr(node.clone())),
+        SyntaxKind::NameList => visitor.leave_name_list(&NameList(node.clone())),
+        SyntaxKind::IdentPat => visitor.leave_ident_pat(&IdentPat(node.clone())),
+        SyntaxKind::ArgList => visitor.leave_arg_list(&ArgList(node.clone())),
+        SyntaxKind::StructExprField => {
+            visitor.leave_struct_expr_field(&StructExprField(node.clone()))
+        }
+        SyntaxKind::FnParamListDef => {
+            visitor.leave_fn_param_list_def(&FnParamListDef(node.clone()))
+        }
+        SyntaxKind::NamedArg => visitor.leave_named_arg(&NamedArg(node.clone())),
+        SyntaxKind::Type => visitor.leave_type(&Type(node.clone())),
+        SyntaxKind::MatchArmList => visitor.leave_match_arm_list(&MatchArmList(node.clone())),
+        SyntaxKind::MatchArm => visitor.leave_match_arm(&MatchArm(node.clone())),
+        SyntaxKind::WildcardPat => visitor.leave_wildcard_pat(&WildcardPat(node.clone())),
+        SyntaxKind::LiteralPat => visitor.leave_literal_pat(&LiteralPat(node.clone())),
+        SyntaxKind::RangePat => visitor.leave_range_pat(&RangePat(node.clone())),
+        SyntaxKind::TuplePat => visitor.leave_tuple_pat(&TuplePat(node.clone())),
+        SyntaxKind::StructPat => visitor.leave_struct_pat(&StructPat(node.clone())),
+        SyntaxKind::FieldPat => visitor.leave_field_pat(&FieldPat(node.clone())),
+        SyntaxKind::FnReturnDef => visitor.leave_fn_return_def(&FnReturnDef(node.clone())),
+        SyntaxKind::FnBodyDef => visitor.leave_fn_body_def(&FnBodyDef(node.clone())),
+        SyntaxKind::FnParamDef => visitor.leave_fn_param_def(&FnParamDef(node.clone())),
+        SyntaxKind::FnReturnTypeListDef => {
+            visitor.leave_fn_return_type_list_def(&FnReturnTypeListDef(node.clone()))
+        }
+        SyntaxKind::FnReturnTypeDef => {
+            visitor.leave_fn_return_type_def(&FnReturnTypeDef(node.clone()))
+        }
+        SyntaxKind::StructFieldListDef => {
+            visitor.leave_struct_field_list_def(&StructFieldListDef(node.clone()))
+        }
+        SyntaxKind::StructFieldDef => visitor.leave_struct_field_def(&StructFieldDef(node.clone())),
+        SyntaxKind::TraitListsDef => visitor.leave_trait_lists_def(&TraitListsDef(node.clone())),
+        SyntaxKind::TraitTypeListDef => {
+            visitor.leave_trait_type_list_def(&TraitTypeListDef(node.clone()))
+        }
+        SyntaxKind::TraitFnListDef => {
+            visitor.leave_trait_fn_list_def(&TraitFnListDef(node.clone()))
+        }
+        SyntaxKind::TraitTypeDef => visitor.leave_trait_type_def(&TraitTypeDef(node.clone())),
+        SyntaxKind::ImplItemListDef => {
+            visitor.leave_impl_item_list_def(&ImplItemListDef(node.clone()))
+        }
+        _ => {}
+    }
+}
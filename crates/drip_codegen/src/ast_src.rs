@@ -0,0 +1,220 @@
+//! Lowers `drip.ungram` into the nodes and enums that make up the typed AST.
+
+use crate::kinds;
+use ungrammar::{Grammar, Rule};
+
+pub(crate) struct AstSrc {
+    pub(crate) nodes: Vec<AstNodeSrc>,
+    pub(crate) enums: Vec<AstEnumSrc>,
+}
+
+pub(crate) struct AstNodeSrc {
+    pub(crate) name: String,
+    pub(crate) fields: Vec<Field>,
+}
+
+pub(crate) struct AstEnumSrc {
+    pub(crate) name: String,
+    pub(crate) variants: Vec<String>,
+}
+
+pub(crate) enum Field {
+    Token {
+        name: String,
+        kinds: Vec<&'static str>,
+    },
+    Node {
+        name: String,
+        ty: String,
+        many: bool,
+    },
+}
+
+impl AstSrc {
+    pub(crate) fn enum_by_name(&self, name: &str) -> Option<&AstEnumSrc> {
+        self.enums.iter().find(|en| en.name == name)
+    }
+
+    /// Whether a node of type `ty` can also be cast to `other`, in which case
+    /// accessors for `other` have to skip over children of type `ty`.
+    pub(crate) fn overlaps(&self, ty: &str, other: &str) -> bool {
+        ty == other || self.contains(other, ty) || self.contains(ty, other)
+    }
+
+    fn contains(&self, en: &str, ty: &str) -> bool {
+        match self.enum_by_name(en) {
+            Some(en) => en
+                .variants
+                .iter()
+                .any(|variant| variant == ty || self.contains(variant, ty)),
+            None => false,
+        }
+    }
+}
+
+pub(crate) fn lower(grammar: &Grammar) -> AstSrc {
+    let mut src = AstSrc {
+        nodes: Vec::new(),
+        enums: Vec::new(),
+    };
+
+    for node in grammar.iter() {
+        let name = grammar[node].name.clone();
+        let rule = &grammar[node].rule;
+
+        if let Some(variants) = lower_enum(grammar, rule) {
+            src.enums.push(AstEnumSrc { name, variants });
+        } else {
+            let mut fields = Vec::new();
+            lower_rule(grammar, rule, false, &mut fields);
+            src.nodes.push(AstNodeSrc { name, fields });
+        }
+    }
+
+    src
+}
+
+fn lower_enum(grammar: &Grammar, rule: &Rule) -> Option<Vec<String>> {
+    let alternatives = match rule {
+        Rule::Alt(alternatives) => alternatives,
+        _ => return None,
+    };
+
+    alternatives
+        .iter()
+        .map(|alternative| match alternative {
+            Rule::Node(node) => Some(grammar[*node].name.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn lower_rule(grammar: &Grammar, rule: &Rule, many: bool, fields: &mut Vec<Field>) {
+    match rule {
+        Rule::Labeled { label, rule } => {
+            let field = match &**rule {
+                Rule::Token(token) => Field::Token {
+                    name: label.clone(),
+                    kinds: vec![kinds::lookup(&grammar[*token].name).name],
+                },
+                Rule::Alt(alternatives) => Field::Token {
+                    name: label.clone(),
+                    kinds: alternatives
+                        .iter()
+                        .map(|alternative| match alternative {
+                            Rule::Token(token) => kinds::lookup(&grammar[*token].name).name,
+                            _ => panic!("label `{}` must only name tokens", label),
+                        })
+                        .collect(),
+                },
+                Rule::Node(node) => Field::Node {
+                    name: label.clone(),
+                    ty: grammar[*node].name.clone(),
+                    many,
+                },
+                _ => panic!("unsupported labeled rule `{}`", label),
+            };
+            push_field(fields, field);
+        }
+        Rule::Node(node) => {
+            let ty = grammar[*node].name.clone();
+            push_field(
+                fields,
+                Field::Node {
+                    name: field_name(&ty, many),
+                    ty,
+                    many,
+                },
+            );
+        }
+        Rule::Token(token) => {
+            let token = kinds::lookup(&grammar[*token].name);
+            let name = if token.has_wrapper() {
+                to_snake_case(token.name)
+            } else {
+                format!("{}_token", to_snake_case(token.name))
+            };
+            push_field(
+                fields,
+                Field::Token {
+                    name,
+                    kinds: vec![token.name],
+                },
+            );
+        }
+        Rule::Seq(rules) | Rule::Alt(rules) => {
+            for rule in rules {
+                lower_rule(grammar, rule, many, fields);
+            }
+        }
+        Rule::Opt(rule) => lower_rule(grammar, rule, many, fields),
+        Rule::Rep(rule) => lower_rule(grammar, rule, true, fields),
+    }
+}
+
+/// Adds `field`, merging it with an unlabeled field of the same type if the
+/// grammar mentions it more than once (as in `A (',' A)*`).
+fn push_field(fields: &mut Vec<Field>, field: Field) {
+    let existing = fields
+        .iter_mut()
+        .find(|existing| match (&**existing, &field) {
+            (Field::Token { name, .. }, Field::Token { name: other, .. }) => name == other,
+            (
+                Field::Node { name, ty, .. },
+                Field::Node {
+                    name: other,
+                    ty: other_ty,
+                    ..
+                },
+            ) => ty == other_ty && is_unlabeled(name, ty) && is_unlabeled(other, other_ty),
+            _ => false,
+        });
+
+    match existing {
+        Some(Field::Node { name, ty, many }) => {
+            if let Field::Node { many: true, .. } = field {
+                *many = true;
+                *name = field_name(ty, true);
+            }
+        }
+        Some(Field::Token { .. }) => {}
+        None => fields.push(field),
+    }
+}
+
+fn is_unlabeled(name: &str, ty: &str) -> bool {
+    name == field_name(ty, false) || name == field_name(ty, true)
+}
+
+fn field_name(ty: &str, many: bool) -> String {
+    let name = match to_snake_case(ty).as_str() {
+        "type" => "ty".to_string(),
+        name => name.to_string(),
+    };
+
+    if many {
+        format!("{}s", name)
+    } else {
+        name
+    }
+}
+
+pub(crate) fn to_snake_case(s: &str) -> String {
+    let chars: Vec<char> = s.chars().collect();
+    let mut buf = String::with_capacity(s.len());
+
+    for (idx, c) in chars.iter().enumerate() {
+        if idx > 0 && c.is_ascii_uppercase() {
+            let prev_is_lower = !chars[idx - 1].is_ascii_uppercase();
+            let next_is_lower = chars.get(idx + 1).is_some_and(char::is_ascii_lowercase);
+
+            if prev_is_lower || next_is_lower {
+                buf.push('_');
+            }
+        }
+
+        buf.push(c.to_ascii_lowercase());
+    }
+
+    buf
+}
@@ -0,0 +1,157 @@
+//! Every token the lexer knows about.
+//!
+//! `drip.ungram` refers to tokens by their grammar name: the literal text for
+//! keywords and punctuation (`'::'`, `'struct'`), and a lowercase name for
+//! tokens matched by a regex (`'ident'`, `'number'`).
+
+pub(crate) enum Pattern {
+    Error,
+    Token(&'static str),
+    Regex(&'static str),
+}
+
+pub(crate) struct TokenSrc {
+    pub(crate) name: &'static str,
+    pub(crate) grammar_name: &'static str,
+    pub(crate) pattern: Pattern,
+    /// How the token is described in diagnostics: the text in quotes for
+    /// tokens with fixed text, like `'::'`, and a word for the others.
+    pub(crate) display: &'static str,
+}
+
+impl TokenSrc {
+    /// Tokens whose text varies get a typed `AstToken` wrapper in `drip_ast`.
+    pub(crate) fn has_wrapper(&self) -> bool {
+        matches!(self.pattern, Pattern::Regex(_))
+    }
+}
+
+const fn error() -> TokenSrc {
+    TokenSrc {
+        name: "Error",
+        grammar_name: "error",
+        pattern: Pattern::Error,
+        display: "unrecognized token",
+    }
+}
+
+const fn regex(
+    name: &'static str,
+    grammar_name: &'static str,
+    regex: &'static str,
+    display: &'static str,
+) -> TokenSrc {
+    TokenSrc {
+        name,
+        grammar_name,
+        pattern: Pattern::Regex(regex),
+        display,
+    }
+}
+
+const fn token(name: &'static str, text: &'static str, display: &'static str) -> TokenSrc {
+    TokenSrc {
+        name,
+        grammar_name: text,
+        pattern: Pattern::Token(text),
+        display,
+    }
+}
+
+pub(crate) const TOKENS: &[TokenSrc] = &[
+    error(),
+    regex("Comment", "comment", "//.*", "comment"),
+    regex("Whitespace", "whitespace", "[ \n]+", "whitespace"),
+    regex(
+        "Ident",
+        "ident",
+        "_*[/\\p{L}/u][/\\p{L}/u-z0-9_]*",
+        "identifier",
+    ),
+    token("FnKw", "fn", "'fn'"),
+    token("UseKw", "use", "'use'"),
+    token("ExternKw", "extern", "'extern'"),
+    token("StructKw", "struct", "'struct'"),
+    token("TraitKw", "trait", "'trait'"),
+    token("ImplKw", "impl", "'impl'"),
+    token("IfKw", "if", "'if'"),
+    token("ElseKw", "else", "'else'"),
+    token("LoopKw", "loop", "'loop'"),
+    token("WhileKw", "while", "'while'"),
+    token("ForKw", "for", "'for'"),
+    token("InKw", "in", "'in'"),
+    token("BreakKw", "break", "'break'"),
+    token("ContinueKw", "continue", "'continue'"),
+    token("MatchKw", "match", "'match'"),
+    token("TrueKw", "true", "'true'"),
+    token("FalseKw", "false", "'false'"),
+    token("ConstKw", "::", "'::'"),
+    token("VariableKw", ":=", "':='"),
+    token("TypeKw", "Type", "'Type'"),
+    token("SelfVarKw", "self", "'self'"),
+    token("SelfTypeKw", "Self", "'Self'"),
+    regex(
+        "Number",
+        "number",
+        "([0-9][0-9_]*)?\\.?[0-9][0-9_]*([eE][-+]?[0-9_]+)?",
+        "number",
+    ),
+    regex("Str", "string", "\"[^\"\\n]*\"", "string"),
+    token("Bang", "!", "'!'"),
+    token("Quest", "?", "'?'"),
+    token("Plus", "+", "'+'"),
+    token("Minus", "-", "'-'"),
+    token("Star", "*", "'*'"),
+    token("Slash", "/", "'/'"),
+    token("Circumflex", "^", "'^'"),
+    token("Circumflex2", "^^", "'^^'"),
+    token("And", "&", "'&'"),
+    token("And2", "&&", "'&&'"),
+    token("Pipe", "|", "'|'"),
+    token("Pipe2", "||", "'||'"),
+    token("Equals", "=", "'='"),
+    token("Equals2", "==", "'=='"),
+    token("NotEquals", "!=", "'!='"),
+    token("LessEquals", "<=", "'<='"),
+    token("GreaterEquals", ">=", "'>='"),
+    token("PlusEquals", "+=", "'+='"),
+    token("MinusEquals", "-=", "'-='"),
+    token("StarEquals", "*=", "'*='"),
+    token("SlashEquals", "/=", "'/='"),
+    token("Percent", "%", "'%'"),
+    token("Dollar", "$", "'$'"),
+    token("Hashtag", "#", "'#'"),
+    token("At", "@", "'@'"),
+    token("Underscore", "_", "'_'"),
+    token("Dot", ".", "'.'"),
+    token("Dot2", "..", "'..'"),
+    token("Dot3", "...", "'...'"),
+    token("Comma", ",", "','"),
+    token("Colon", ":", "':'"),
+    token("Semicolon", ";", "';'"),
+    token("Quote", "\"", "'\"'"),
+    token("SingleQuote", "'", "'''"),
+    token("Grave", "`", "'`'"),
+    token("Arrow", "->", "'->'"),
+    token("FatArrow", "=>", "'=>'"),
+    token("Inherit", "<|", "'<|'"),
+    token("LCurlyBracket", "{", "'{'"),
+    token("RCurlyBracket", "}", "'}'"),
+    token("LRoundBracket", "(", "'('"),
+    token("RRoundBracket", ")", "')'"),
+    token("LAngledBracket", "<", "'<'"),
+    token("RAngledBracket", ">", "'>'"),
+    token("LSquareBracket", "[", "'['"),
+    token("RSquareBracket", "]", "']'"),
+];
+
+pub(crate) fn by_name(name: &str) -> &'static TokenSrc {
+    TOKENS.iter().find(|token| token.name == name).unwrap()
+}
+
+pub(crate) fn lookup(grammar_name: &str) -> &'static TokenSrc {
+    TOKENS
+        .iter()
+        .find(|token| token.grammar_name == grammar_name)
+        .unwrap_or_else(|| panic!("unknown token '{}' in drip.ungram", grammar_name))
+}
@@ -0,0 +1,408 @@
+//! Generates `TokenKind`, `SyntaxKind`, the typed AST and its `Visitor` from
+//! `drip.ungram` and the token list in [`kinds`], and a TextMate grammar for
+//! editors from the token list.
+//!
+//! Run `cargo run -p drip_codegen` to regenerate; the tests in this crate fail
+//! if the checked-in files are out of date.
+
+mod ast_src;
+mod kinds;
+mod textmate;
+
+use ast_src::{AstSrc, Field};
+use kinds::{Pattern, TOKENS};
+use std::fmt::Write;
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use ungrammar::Grammar;
+
+const HEADER: &str = "//! Generated by `cargo run -p drip_codegen`, do not edit by hand.\n\n";
+
+pub struct GeneratedFile {
+    pub path: PathBuf,
+    pub contents: String,
+}
+
+pub fn project_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .ancestors()
+        .nth(2)
+        .unwrap()
+        .to_path_buf()
+}
+
+pub fn generate() -> io::Result<Vec<GeneratedFile>> {
+    let root = project_root();
+
+    let grammar = std::fs::read_to_string(root.join("crates/drip_syntax/drip.ungram"))?;
+    let grammar: Grammar = grammar
+        .parse()
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, format!("{}", err)))?;
+    let ast = ast_src::lower(&grammar);
+
+    let files = vec![
+        (
+            root.join("crates/drip_lexer/src/generated.rs"),
+            generate_token_kind(),
+        ),
+        (
+            root.join("crates/drip_syntax/src/generated.rs"),
+            generate_syntax_kind(&ast),
+        ),
+        (
+            root.join("crates/drip_ast/src/generated.rs"),
+            generate_nodes(&ast),
+        ),
+        (
+            root.join("crates/drip_ast/src/visit/generated.rs"),
+            generate_visitor(&ast),
+        ),
+        (
+            root.join("editors/drip.tmLanguage.json"),
+            textmate::generate(),
+        ),
+    ];
+
+    files
+        .into_iter()
+        .map(|(path, contents)| {
+            let contents = match path.extension() {
+                Some(extension) if extension == "rs" => reformat(&contents)?,
+                _ => contents,
+            };
+            Ok(GeneratedFile { path, contents })
+        })
+        .collect()
+}
+
+fn generate_token_kind() -> String {
+    let mut buf = String::from(HEADER);
+
+    buf.push_str("use logos::Logos;\nuse std::fmt::{self, Formatter};\n\n");
+    buf.push_str("#[derive(Logos, Debug, Copy, Clone, PartialEq, Eq, Hash)]\n");
+    buf.push_str("pub enum TokenKind {\n");
+    for token in TOKENS {
+        match token.pattern {
+            Pattern::Error => buf.push_str("#[error]\n"),
+            Pattern::Token(text) => writeln!(buf, "#[token({:?})]", text).unwrap(),
+            Pattern::Regex(regex) => writeln!(buf, "#[regex({:?})]", regex).unwrap(),
+        }
+        writeln!(buf, "{},", token.name).unwrap();
+    }
+    buf.push_str("}\n\n");
+
+    buf.push_str("impl fmt::Display for TokenKind {\n");
+    buf.push_str("fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {\n");
+    buf.push_str("f.write_str(match self {\n");
+    for token in TOKENS {
+        writeln!(buf, "TokenKind::{} => {:?},", token.name, token.display).unwrap();
+    }
+    buf.push_str("})\n}\n}\n");
+
+    buf
+}
+
+fn generate_syntax_kind(ast: &AstSrc) -> String {
+    let mut buf = String::from(HEADER);
+
+    buf.push_str("use drip_lexer::TokenKind;\n");
+    buf.push_str("use num_enum::{IntoPrimitive, TryFromPrimitive};\n\n");
+    buf.push_str(
+        "#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]\n",
+    );
+    buf.push_str("#[repr(u16)]\npub enum SyntaxKind {\n");
+    buf.push_str("Tombstone,\n");
+    buf.push_str("Error,\n\n// tokens\n");
+    for token in TOKENS.iter().filter(|token| token.name != "Error") {
+        writeln!(buf, "{},", token.name).unwrap();
+    }
+    buf.push_str("\n// nodes\n");
+    for node in &ast.nodes {
+        writeln!(buf, "{},", node.name).unwrap();
+    }
+    buf.push_str("}\n\n");
+
+    buf.push_str("impl From<TokenKind> for SyntaxKind {\n");
+    buf.push_str("fn from(token: TokenKind) -> Self {\n");
+    buf.push_str("match token {\n");
+    for token in TOKENS {
+        writeln!(buf, "TokenKind::{0} => SyntaxKind::{0},", token.name).unwrap();
+    }
+    buf.push_str("}\n}\n}\n");
+
+    buf
+}
+
+fn generate_nodes(ast: &AstSrc) -> String {
+    let mut buf = String::from(HEADER);
+
+    buf.push_str("use crate::{support, AstChildren, AstNode, AstToken};\n");
+    buf.push_str("use drip_syntax::{SyntaxKind, SyntaxNode, SyntaxToken};\n");
+
+    for token in TOKENS.iter().filter(|token| token.has_wrapper()) {
+        buf.push_str("\n#[derive(Debug, Clone, PartialEq, Eq, Hash)]\n");
+        writeln!(buf, "pub struct {}(pub(crate) SyntaxToken);\n", token.name).unwrap();
+
+        writeln!(buf, "impl AstToken for {} {{", token.name).unwrap();
+        buf.push_str("fn can_cast(kind: SyntaxKind) -> bool {\n");
+        writeln!(buf, "kind == SyntaxKind::{}", token.name).unwrap();
+        buf.push_str("}\n\n");
+        buf.push_str("fn cast(syntax: SyntaxToken) -> Option<Self> {\n");
+        buf.push_str("if Self::can_cast(syntax.kind()) {\nSome(Self(syntax))\n} else {\nNone\n}\n");
+        buf.push_str("}\n\n");
+        buf.push_str("fn syntax(&self) -> &SyntaxToken {\n&self.0\n}\n");
+        buf.push_str("}\n");
+    }
+
+    for node in &ast.nodes {
+        buf.push_str("\n#[derive(Debug, Clone, PartialEq, Eq, Hash)]\n");
+        writeln!(buf, "pub struct {}(pub(crate) SyntaxNode);\n", node.name).unwrap();
+
+        if !node.fields.is_empty() {
+            writeln!(buf, "impl {} {{", node.name).unwrap();
+            for (idx, field) in node.fields.iter().enumerate() {
+                generate_accessor(ast, &node.fields[..idx], field, &mut buf);
+            }
+            buf.push_str("}\n\n");
+        }
+
+        writeln!(buf, "impl AstNode for {} {{", node.name).unwrap();
+        buf.push_str("fn can_cast(kind: SyntaxKind) -> bool {\n");
+        writeln!(buf, "kind == SyntaxKind::{}", node.name).unwrap();
+        buf.push_str("}\n\n");
+        buf.push_str("fn cast(syntax: SyntaxNode) -> Option<Self> {\n");
+        buf.push_str("if Self::can_cast(syntax.kind()) {\nSome(Self(syntax))\n} else {\nNone\n}\n");
+        buf.push_str("}\n\n");
+        buf.push_str("fn syntax(&self) -> &SyntaxNode {\n&self.0\n}\n");
+        buf.push_str("}\n");
+    }
+
+    for en in &ast.enums {
+        let (nested, direct): (Vec<_>, Vec<_>) = en
+            .variants
+            .iter()
+            .partition(|variant| ast.enum_by_name(variant).is_some());
+
+        buf.push_str("\n#[derive(Debug, Clone, PartialEq, Eq, Hash)]\n");
+        writeln!(buf, "pub enum {} {{", en.name).unwrap();
+        for variant in &en.variants {
+            writeln!(buf, "{0}({0}),", variant).unwrap();
+        }
+        buf.push_str("}\n\n");
+
+        writeln!(buf, "impl AstNode for {} {{", en.name).unwrap();
+        buf.push_str("fn can_cast(kind: SyntaxKind) -> bool {\n");
+        let direct_kinds: Vec<_> = direct
+            .iter()
+            .map(|variant| format!("SyntaxKind::{}", variant))
+            .collect();
+        write!(buf, "matches!(kind, {})", direct_kinds.join(" | ")).unwrap();
+        for variant in &nested {
+            write!(buf, " || {}::can_cast(kind)", variant).unwrap();
+        }
+        buf.push_str("\n}\n\n");
+
+        buf.push_str("fn cast(syntax: SyntaxNode) -> Option<Self> {\n");
+        buf.push_str("let result = match syntax.kind() {\n");
+        for variant in &direct {
+            writeln!(buf, "SyntaxKind::{0} => Self::{0}({0}(syntax)),", variant).unwrap();
+        }
+        match nested.as_slice() {
+            [] => buf.push_str("_ => return None,\n"),
+            [variant] => writeln!(
+                buf,
+                "_ => return {0}::cast(syntax).map(Self::{0}),",
+                variant
+            )
+            .unwrap(),
+            _ => panic!("`{}` may only contain one nested enum", en.name),
+        }
+        buf.push_str("};\n\nSome(result)\n}\n\n");
+
+        buf.push_str("fn syntax(&self) -> &SyntaxNode {\nmatch self {\n");
+        for variant in &en.variants {
+            writeln!(buf, "Self::{}(it) => it.syntax(),", variant).unwrap();
+        }
+        buf.push_str("}\n}\n}\n");
+
+        for variant in &en.variants {
+            writeln!(
+                buf,
+                "\nimpl From<{0}> for {1} {{\nfn from(node: {0}) -> Self {{\nSelf::{0}(node)\n}}\n}}",
+                variant, en.name
+            )
+            .unwrap();
+        }
+    }
+
+    buf
+}
+
+fn generate_visitor(ast: &AstSrc) -> String {
+    let mut buf = String::from(HEADER);
+
+    buf.push_str("use super::{Scope, Walk};\n");
+    buf.push_str("use crate::*;\n");
+    buf.push_str("use drip_syntax::{SyntaxKind, SyntaxNode};\n\n");
+
+    buf.push_str(
+        "/// Hooks called by [`super::walk`] when it enters and leaves each kind of node.\n",
+    );
+    buf.push_str("///\n/// Every hook does nothing by default.\n");
+    buf.push_str("pub trait Visitor {\n");
+    buf.push_str("fn enter_scope(&mut self, _scope: &Scope) {}\n\n");
+    buf.push_str("fn exit_scope(&mut self, _scope: &Scope) {}\n");
+    for node in &ast.nodes {
+        let snake = ast_src::to_snake_case(&node.name);
+        writeln!(
+            buf,
+            "\nfn enter_{}(&mut self, _node: &{}) -> Walk {{\nWalk::Continue\n}}",
+            snake, node.name
+        )
+        .unwrap();
+        writeln!(
+            buf,
+            "\nfn leave_{}(&mut self, _node: &{}) {{}}",
+            snake, node.name
+        )
+        .unwrap();
+    }
+    buf.push_str("}\n\n");
+
+    buf.push_str(
+        "pub(super) fn enter(visitor: &mut (impl Visitor + ?Sized), node: &SyntaxNode) -> Walk {\n",
+    );
+    buf.push_str("match node.kind() {\n");
+    for node in &ast.nodes {
+        writeln!(
+            buf,
+            "SyntaxKind::{0} => visitor.enter_{1}(&{0}(node.clone())),",
+            node.name,
+            ast_src::to_snake_case(&node.name)
+        )
+        .unwrap();
+    }
+    buf.push_str("_ => Walk::Continue,\n}\n}\n\n");
+
+    buf.push_str(
+        "pub(super) fn leave(visitor: &mut (impl Visitor + ?Sized), node: &SyntaxNode) {\n",
+    );
+    buf.push_str("match node.kind() {\n");
+    for node in &ast.nodes {
+        writeln!(
+            buf,
+            "SyntaxKind::{0} => visitor.leave_{1}(&{0}(node.clone())),",
+            node.name,
+            ast_src::to_snake_case(&node.name)
+        )
+        .unwrap();
+    }
+    buf.push_str("_ => {}\n}\n}\n");
+
+    buf
+}
+
+fn generate_accessor(ast: &AstSrc, preceding: &[Field], field: &Field, buf: &mut String) {
+    match field {
+        Field::Token { name, kinds } => match kinds.as_slice() {
+            [kind] if kinds::by_name(kind).has_wrapper() => {
+                writeln!(buf, "pub fn {}(&self) -> Option<{}> {{", name, kind).unwrap();
+                writeln!(
+                    buf,
+                    "support::token(&self.0, SyntaxKind::{}).and_then({}::cast)",
+                    kind, kind
+                )
+                .unwrap();
+                buf.push_str("}\n\n");
+            }
+            kinds => {
+                writeln!(buf, "pub fn {}(&self) -> Option<SyntaxToken> {{", name).unwrap();
+                match kinds {
+                    [kind] => {
+                        writeln!(buf, "support::token(&self.0, SyntaxKind::{})", kind).unwrap()
+                    }
+                    kinds => {
+                        let kinds: Vec<_> = kinds
+                            .iter()
+                            .map(|kind| format!("SyntaxKind::{}", kind))
+                            .collect();
+                        writeln!(buf, "support::token_in(&self.0, &[{}])", kinds.join(", "))
+                            .unwrap()
+                    }
+                }
+                buf.push_str("}\n\n");
+            }
+        },
+        Field::Node {
+            name,
+            ty,
+            many: true,
+        } => {
+            writeln!(buf, "pub fn {}(&self) -> AstChildren<{}> {{", name, ty).unwrap();
+            buf.push_str("support::children(&self.0)\n}\n\n");
+        }
+        Field::Node {
+            name,
+            ty,
+            many: false,
+        } => {
+            // skip children that an earlier accessor already claims
+            let nth = preceding
+                .iter()
+                .filter(|preceding| match preceding {
+                    Field::Node {
+                        ty: other,
+                        many: false,
+                        ..
+                    } => ast.overlaps(other, ty),
+                    _ => false,
+                })
+                .count();
+
+            writeln!(buf, "pub fn {}(&self) -> Option<{}> {{", name, ty).unwrap();
+            writeln!(buf, "support::child(&self.0, {})", nth).unwrap();
+            buf.push_str("}\n\n");
+        }
+    }
+}
+
+fn reformat(text: &str) -> io::Result<String> {
+    let mut rustfmt = Command::new("rustfmt")
+        .args(["--edition", "2018"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+
+    rustfmt.stdin.take().unwrap().write_all(text.as_bytes())?;
+    let output = rustfmt.wait_with_output()?;
+
+    if !output.status.success() {
+        return Err(io::Error::other("rustfmt failed on generated code"));
+    }
+
+    Ok(String::from_utf8(output.stdout).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_code_is_fresh() {
+        let stale: Vec<_> = generate()
+            .unwrap()
+            .into_iter()
+            .filter(|file| {
+                std::fs::read_to_string(&file.path).ok().as_ref() != Some(&file.contents)
+            })
+            .map(|file| file.path.display().to_string())
+            .collect();
+
+        assert!(
+            stale.is_empty(),
+            "generated code is out of date, run `cargo run -p drip_codegen`: {:?}",
+            stale,
+        );
+    }
+}
@@ -0,0 +1,12 @@
+use std::fs;
+
+fn main() -> std::io::Result<()> {
+    for file in drip_codegen::generate()? {
+        if fs::read_to_string(&file.path).ok().as_ref() != Some(&file.contents) {
+            eprintln!("updating {}", file.path.display());
+            fs::write(&file.path, file.contents)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,256 @@
+//! A TextMate grammar for editors that highlight Drip without the language
+//! server.
+//!
+//! Every token in [`TOKENS`] gets a pattern of its own, so the keywords and
+//! operators can't drift from the lexer. The lexer takes the longest token
+//! where TextMate takes the first pattern that matches leftmost, so the
+//! patterns are ordered, and guarded where a token could also start a longer
+//! identifier, to make the two agree.
+
+use crate::kinds::{self, Pattern, TokenSrc, TOKENS};
+use regex::Regex;
+use std::fmt::Write;
+
+pub(crate) fn generate() -> String {
+    let (start, cont) = ident_classes();
+    let is_start = class_matcher(&start);
+    let is_cont = class_matcher(&cont);
+
+    let mut literals: Vec<_> = TOKENS
+        .iter()
+        .filter_map(|token| match token.pattern {
+            Pattern::Token(text) => Some((token, text)),
+            _ => None,
+        })
+        .collect();
+    literals.sort_by_key(|(_, text)| std::cmp::Reverse(text.len()));
+
+    let mut patterns = Vec::new();
+    for name in ["Comment", "Str", "Number"] {
+        patterns.push(regex_pattern(kinds::by_name(name)));
+    }
+    for (token, text) in literals {
+        let rest = text.trim_start_matches('_');
+        let guard = match rest.chars().next() {
+            // an identifier only starts after the underscores if something
+            // it can start with follows them
+            None => format!("(?!_*{})", start),
+            // a keyword is only a keyword if no identifier goes on after it
+            Some(first) if is_start(first) && rest.chars().skip(1).all(&is_cont) => {
+                format!("(?!{})", cont)
+            }
+            Some(_) => String::new(),
+        };
+        patterns.push((scope(token).unwrap(), escape_regex(text) + &guard));
+    }
+    patterns.push(regex_pattern(kinds::by_name("Ident")));
+
+    let mut buf = String::from("{\n");
+    buf.push_str(
+        "  \"comment\": \"Generated by `cargo run -p drip_codegen`, do not edit by hand.\",\n",
+    );
+    buf.push_str("  \"name\": \"Drip\",\n");
+    buf.push_str("  \"scopeName\": \"source.drip\",\n");
+    buf.push_str("  \"fileTypes\": [\"drip\"],\n");
+    buf.push_str("  \"patterns\": [\n");
+    for (idx, (name, regex)) in patterns.iter().enumerate() {
+        let separator = if idx + 1 < patterns.len() { "," } else { "" };
+        writeln!(
+            buf,
+            "    {{ \"name\": {}, \"match\": {} }}{}",
+            json_string(name),
+            json_string(regex),
+            separator
+        )
+        .unwrap();
+    }
+    buf.push_str("  ]\n}\n");
+
+    buf
+}
+
+/// The scope `token` is highlighted with, which no other token shares, or
+/// `None` if it isn't highlighted at all.
+pub(crate) fn scope(token: &TokenSrc) -> Option<String> {
+    let text = match token.pattern {
+        Pattern::Error => return None,
+        Pattern::Regex(_) => {
+            let scope = match token.name {
+                "Whitespace" => return None,
+                "Comment" => "comment.line.double-slash",
+                "Str" => "string.quoted.double",
+                "Number" => "constant.numeric",
+                "Ident" => "variable.other",
+                name => panic!("no scope for the `{}` token", name),
+            };
+            return Some(format!("{}.drip", scope));
+        }
+        Pattern::Token(text) => text,
+    };
+
+    let category = if text.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        match text {
+            "if" | "else" | "loop" | "while" | "for" | "in" | "break" | "continue" | "match" => {
+                "keyword.control"
+            }
+            "fn" | "struct" | "trait" | "impl" | "Type" | "Self" => "storage.type",
+            "true" | "false" => "constant.language",
+            "self" => "variable.language",
+            _ => "keyword.other",
+        }
+    } else {
+        match token.name {
+            "Comma" | "Semicolon" | "Colon" | "Dot" => "punctuation.separator",
+            "LCurlyBracket" | "RCurlyBracket" | "LRoundBracket" | "RRoundBracket"
+            | "LSquareBracket" | "RSquareBracket" => "punctuation.section",
+            "Quote" | "SingleQuote" | "Grave" | "Dollar" | "Hashtag" | "At" => "punctuation.other",
+            _ => "keyword.operator",
+        }
+    };
+
+    let name = token.name.strip_suffix("Kw").unwrap_or(token.name);
+    let mut kebab = String::new();
+    for c in name.chars() {
+        if c.is_uppercase() && !kebab.is_empty() {
+            kebab.push('-');
+        }
+        kebab.push(c.to_ascii_lowercase());
+    }
+    Some(format!("{}.{}.drip", category, kebab))
+}
+
+fn regex_pattern(token: &TokenSrc) -> (String, String) {
+    match token.pattern {
+        Pattern::Regex(regex) => (scope(token).unwrap(), regex.to_string()),
+        _ => panic!("`{}` isn't matched by a regex", token.name),
+    }
+}
+
+/// The character classes an identifier starts with, after any underscores,
+/// and goes on with.
+fn ident_classes() -> (String, String) {
+    let regex = match kinds::by_name("Ident").pattern {
+        Pattern::Regex(regex) => regex,
+        _ => unreachable!(),
+    };
+    let (start, cont) = regex
+        .strip_prefix("_*")
+        .and_then(|regex| regex.strip_suffix('*'))
+        .and_then(|regex| regex.split_once("]["))
+        .expect("identifiers to look like `_*[start][continue]*`");
+    (format!("{}]", start), format!("[{}", cont))
+}
+
+fn class_matcher(class: &str) -> impl Fn(char) -> bool {
+    let regex = Regex::new(&format!("^{}$", class)).unwrap();
+    move |c| regex.is_match(c.encode_utf8(&mut [0; 4]))
+}
+
+fn escape_regex(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        if "\\.^$|?*+()[]{}".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn json_string(text: &str) -> String {
+    let mut buf = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            c if c.is_control() => write!(buf, "\\u{:04x}", c as u32).unwrap(),
+            c => buf.push(c),
+        }
+    }
+    buf.push('"');
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project_root;
+    use drip_lexer::{Lexer, TokenKind};
+    use onig::{Region, SearchOptions};
+    use std::collections::HashMap;
+
+    /// The tokens TextMate finds in `line` with `patterns`: the leftmost
+    /// match wins, and the first pattern breaks ties.
+    fn tokenize<'a>(
+        patterns: &'a [(onig::Regex, String)],
+        line: &str,
+    ) -> Vec<(usize, usize, &'a str)> {
+        let mut tokens = Vec::new();
+        let mut pos = 0;
+        let mut region = Region::new();
+        loop {
+            let mut first: Option<(usize, usize, &str)> = None;
+            for (regex, scope) in patterns {
+                let found = regex.search_with_options(
+                    line,
+                    pos,
+                    line.len(),
+                    SearchOptions::SEARCH_OPTION_NONE,
+                    Some(&mut region),
+                );
+                if found.is_none() {
+                    continue;
+                }
+                let (start, end) = region.pos(0).unwrap();
+                if first.is_none_or(|(first, _, _)| start < first) {
+                    first = Some((start, end, scope));
+                }
+            }
+            match first {
+                Some(token) => {
+                    tokens.push(token);
+                    pos = token.1;
+                }
+                None => return tokens,
+            }
+        }
+    }
+
+    #[test]
+    fn tokenize_like_the_lexer() {
+        let grammar: serde_json::Value = serde_json::from_str(&generate()).unwrap();
+        let patterns: Vec<_> = grammar["patterns"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|pattern| {
+                let regex = onig::Regex::new(pattern["match"].as_str().unwrap()).unwrap();
+                (regex, pattern["name"].as_str().unwrap().to_string())
+            })
+            .collect();
+        let names: HashMap<_, _> = TOKENS
+            .iter()
+            .filter_map(|token| Some((scope(token)?, token.name)))
+            .collect();
+
+        let path = project_root().join("examples/drip/basic/walkthrough.drip");
+        let text = std::fs::read_to_string(path).unwrap();
+
+        let expected: Vec<_> = Lexer::new(&text)
+            .filter(|token| !matches!(token.kind, TokenKind::Whitespace | TokenKind::Error))
+            .map(|token| format!("{:?} {:?} {:?}", token.range, token.kind, token.text))
+            .collect();
+        let mut actual = Vec::new();
+        let mut offset = 0;
+        for line in text.split('\n') {
+            for (start, end, scope) in tokenize(&patterns, line) {
+                let text = &line[start..end];
+                let (start, end) = (offset + start, offset + end);
+                actual.push(format!("{}..{} {} {:?}", start, end, names[scope], text));
+            }
+            offset += line.len() + 1;
+        }
+
+        assert_eq!(actual, expected);
+    }
+}
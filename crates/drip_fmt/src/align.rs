@@ -0,0 +1,116 @@
+//! Lining up the columns of consecutive lines.
+//!
+//! The printer leaves the parts of a line that are aligned with the lines
+//! around it as cells: the names of struct fields, and the elements of the
+//! rows of multi-line arrays. Consecutive lines with cells from the same
+//! struct or array form a run, and each column of a run is padded to its
+//! widest cell. Columns of numbers are aligned to the right, so that their
+//! digits line up.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use text_size::TextSize;
+
+const INDENT: &str = "    ";
+
+#[derive(Debug, Default)]
+pub(crate) struct Line {
+    pub(crate) indent: usize,
+    pub(crate) pieces: Vec<Piece>,
+}
+
+#[derive(Debug)]
+pub(crate) enum Piece {
+    Text(String),
+    Cell(Cell),
+}
+
+#[derive(Debug)]
+pub(crate) struct Cell {
+    /// Where the struct or array the cell belongs to starts, which cells
+    /// are only aligned with the cells of.
+    pub(crate) group: TextSize,
+    /// Where the row the cell is in starts.
+    pub(crate) row: TextSize,
+    pub(crate) column: usize,
+    pub(crate) text: String,
+    pub(crate) numeric: bool,
+    /// Whether the cell ends its row, and so isn't padded after.
+    pub(crate) last: bool,
+}
+
+/// Aligns the cells in `lines`, and joins them into a file.
+pub(crate) fn render(lines: &[Line]) -> String {
+    let mut output = String::new();
+    let mut start = 0;
+    while start < lines.len() {
+        let group = group(&lines[start]);
+        let mut end = start + 1;
+        if group.is_some() {
+            while end < lines.len() && self::group(&lines[end]) == group {
+                end += 1;
+            }
+        }
+        render_run(&lines[start..end], &mut output);
+        start = end;
+    }
+    output
+}
+
+/// The struct or array that the cells on `line` belong to, if it has any,
+/// and they all belong to the same one and are in the same row.
+fn group(line: &Line) -> Option<TextSize> {
+    let mut cells = line.pieces.iter().filter_map(|piece| match piece {
+        Piece::Cell(cell) => Some(cell),
+        Piece::Text(_) => None,
+    });
+    let first = cells.next()?;
+    if cells.all(|cell| cell.group == first.group && cell.row == first.row) {
+        Some(first.group)
+    } else {
+        None
+    }
+}
+
+fn render_run(lines: &[Line], output: &mut String) {
+    // the width of every column, and whether all of it is numbers
+    let mut columns: HashMap<usize, (usize, bool)> = HashMap::new();
+    for line in lines.iter().filter(|line| group(line).is_some()) {
+        for piece in &line.pieces {
+            if let Piece::Cell(cell) = piece {
+                let column = columns.entry(cell.column).or_insert((0, true));
+                column.0 = column.0.max(cell.text.chars().count());
+                column.1 &= cell.numeric;
+            }
+        }
+    }
+
+    for line in lines {
+        let mut text = String::new();
+        for piece in &line.pieces {
+            match piece {
+                Piece::Text(piece) => text.push_str(piece),
+                Piece::Cell(cell) if group(line).is_none() => text.push_str(&cell.text),
+                Piece::Cell(cell) => {
+                    let (width, numeric) = columns[&cell.column];
+                    if numeric {
+                        write!(text, "{:>1$}", cell.text, width).unwrap();
+                    } else if cell.last {
+                        text.push_str(&cell.text);
+                    } else {
+                        write!(text, "{:<1$}", cell.text, width).unwrap();
+                    }
+                }
+            }
+        }
+
+        let text = text.trim_end();
+        if !text.is_empty() {
+            for _ in 0..line.indent {
+                output.push_str(INDENT);
+            }
+            output.push_str(text);
+        }
+        output.push('\n');
+    }
+}
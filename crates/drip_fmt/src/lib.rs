@@ -0,0 +1,241 @@
+//! Formatting of Drip source code.
+//!
+//! The formatter walks the tokens of the lossless syntax tree, so comments
+//! are kept where they were, and decides the whitespace between them:
+//!
+//! - Operators, `::` and `:=` have a space on each side. Brackets, commas,
+//!   colons, `.`, `..`, unary minus and the pipes of closures don't.
+//! - Brackets that had a line break directly inside them put their contents
+//!   on lines of their own, indented by four spaces. Brackets that didn't
+//!   stay on one line, and braces get a space inside them unless empty.
+//! - Line breaks between tokens are kept, with at most one blank line in a
+//!   row, and lines that continue an expression are indented once more.
+//! - The types of the fields of a struct, and the rows of multi-line arrays
+//!   of arrays, are aligned into columns. See [`align`].
+
+mod align;
+mod printer;
+
+/// Formats `input`, or returns `None` if it has syntax errors, since there's
+/// no telling what the code was meant to look like.
+pub fn format(input: &str) -> Option<String> {
+    let parse = drip_parser::parse(input);
+    if !parse.errors().is_empty() {
+        return None;
+    }
+
+    let lines = printer::print(&parse.syntax());
+    let output = align::render(&lines);
+    let output = output.trim();
+    if output.is_empty() {
+        return Some(String::new());
+    }
+    Some(format!("{}\n", output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use drip_lexer::{Lexer, TokenKind};
+    use expect_test::{expect, Expect};
+
+    fn check(input: &str, expect: Expect) {
+        let once = format(input).unwrap();
+        expect.assert_eq(&once);
+        assert_eq!(
+            format(&once).as_ref(),
+            Some(&once),
+            "formatting isn't idempotent"
+        );
+    }
+
+    #[test]
+    fn remove_trailing_whitespace() {
+        assert_eq!(
+            format("a :: 1   \n\n// note  \nb := \"x  \"  \n\n\n").as_deref(),
+            Some("a :: 1\n\n// note\nb := \"x  \"\n")
+        );
+        assert_eq!(format("a :: 1").as_deref(), Some("a :: 1\n"));
+        assert_eq!(format("  \n").as_deref(), Some(""));
+    }
+
+    #[test]
+    fn refuse_to_format_syntax_errors() {
+        assert_eq!(format("a :: (1 +  \n"), None);
+    }
+
+    #[test]
+    fn space_operators() {
+        check(
+            "a::1\nb:=a+2*-a\nc :=  ( a , b )\nd:=(a<=b)!=(c.x ==  [1,2][0])\nr := 0 .. 10\nq := 0..10 = 2\nv :: Vec3 :: new(1)\nf :: fn (x) { x }\ng :: (h: Fn ( )) { h }\nm :: a>b?a:b",
+            expect![[r#"
+                a :: 1
+                b := a + 2 * -a
+                c := (a, b)
+                d := (a <= b) != (c.x == [1, 2][0])
+                r := 0..10
+                q := 0..10=2
+                v :: Vec3::new(1)
+                f :: fn(x) { x }
+                g :: (h: Fn()) { h }
+                m :: a > b ? a : b
+            "#]],
+        );
+    }
+
+    #[test]
+    fn indent_blocks() {
+        check(
+            "add::(a,b:i32)->i32{\na+b}\nmain :: () {\n  if add(1, 2) > 2 {\n\n\n println(\"big\")\n  }\n  else { }\nloop {break}\n}",
+            expect![[r#"
+                add :: (a, b: i32) -> i32 {
+                    a + b
+                }
+                main :: () {
+                    if add(1, 2) > 2 {
+                        println("big")
+                    } else {}
+                    loop { break }
+                }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn indent_continued_lines() {
+        check(
+            "total :: 1 +\n2 +\n     3\nnumbers :: [\n1,\n2 +\n3,\n]\ndouble :: |x|\nx * 2",
+            expect![[r#"
+                total :: 1 +
+                    2 +
+                    3
+                numbers :: [
+                    1,
+                    2 +
+                        3,
+                ]
+                double :: |x|
+                    x * 2
+            "#]],
+        );
+    }
+
+    #[test]
+    fn keep_comments() {
+        check(
+            "// adds\nadd :: (a, b: i32) {   // two numbers\n        // returns\n  a+b    // the sum\n// done\n}\nif a { 1 }\n  // or\nelse { 2 }\n\n\n\n// end",
+            expect![[r#"
+                // adds
+                add :: (a, b: i32) { // two numbers
+                    // returns
+                    a + b // the sum
+                    // done
+                }
+                if a { 1 }
+                // or
+                else { 2 }
+
+                // end
+            "#]],
+        );
+    }
+
+    #[test]
+    fn align_struct_fields() {
+        check(
+            "Animal :: struct {\nname: string\n  health :i32\n\n x, y,z:   i32\n}\nPoint :: struct { x: i32, y: i32 }",
+            expect![[r#"
+                Animal :: struct {
+                    name:   string
+                    health: i32
+
+                    x, y, z: i32
+                }
+                Point :: struct { x: i32, y: i32 }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn align_rows_of_arrays() {
+        check(
+            "grid :: [\n[1, 20, -3],\n[400,5,6],\n[7, 8, 9000],\n]\nnames :: [\n(\"ann\", 31),\n(\"bob\",4)\n]",
+            expect![[r#"
+                grid :: [
+                    [  1, 20,   -3],
+                    [400,  5,    6],
+                    [  7,  8, 9000],
+                ]
+                names :: [
+                    ("ann", 31),
+                    ("bob",  4)
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn align_three_dimensional_arrays() {
+        check(
+            "arr :: [[\n[1, 3, 4],\n[3, 4, 5],\n[3, 8, 10],\n], [\n[2, 3, 4],\n[0, 8, 2],\n]]",
+            expect![[r#"
+                arr :: [[
+                    [1, 3,  4],
+                    [3, 4,  5],
+                    [3, 8, 10],
+                ], [
+                    [2, 3, 4],
+                    [0, 8, 2],
+                ]]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn align_the_walkthrough_array_without_commas() {
+        check(
+            "arr :: [[\n            [1, 3, 4]\n            [3, 4, 5]\n            [3, 8, 0]\n        ][\n            [2, 3, 4]\n            [0, 8, 2]\n            [5, 9, 3]\n        ][\n            [4, 8, 6]\n            [2, 8, 3]\n            [1, 0, 0]\n        ]]\nrows :: [\n[1, 20]\n[300, 4]\n]",
+            expect![[r#"
+                arr :: [[
+                    [1, 3, 4]
+                    [3, 4, 5]
+                    [3, 8, 0]
+                ][
+                    [2, 3, 4]
+                    [0, 8, 2]
+                    [5, 9, 3]
+                ][
+                    [4, 8, 6]
+                    [2, 8, 3]
+                    [1, 0, 0]
+                ]]
+                rows :: [
+                    [  1, 20]
+                    [300,  4]
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn formatting_twice_changes_nothing() {
+        let input =
+            "\n\nadd :: (a, b: i32) -> i32 {  \n    a + b // sum \n  }\n\n  \nprintln(add(1, 2))  ";
+        let once = format(input).unwrap();
+        assert_eq!(format(&once).unwrap(), once);
+    }
+
+    #[test]
+    fn only_change_whitespace() {
+        let input = "\n\nadd :: (a, b: i32) -> i32 {  \n    a + b // sum \n  }\n\n  \nprintln(add(1, 2))  \nV :: struct {\nx: i32\n}\nv :: V {x:1}\nf :: |a| a.x\n";
+        let output = format(input).unwrap();
+        let tokens = |text| {
+            Lexer::new(text)
+                .filter(|token| token.kind != TokenKind::Whitespace)
+                .map(|token| (token.kind, token.text.trim_end().to_string()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(tokens(&output), tokens(input));
+        assert_eq!(format(&output).unwrap(), output);
+    }
+}
@@ -0,0 +1,411 @@
+//! Deciding what goes between the tokens of a file.
+//!
+//! The tokens are printed in order, and each gets a line break, a space or
+//! nothing before it, depending on the tokens around it and the nodes they
+//! belong to. Lines are indented by how many brackets that span several
+//! lines are open, and once more if they continue an expression.
+
+use crate::align::{Cell, Line, Piece};
+use drip_syntax::{SyntaxKind, SyntaxNode, SyntaxToken};
+use std::collections::{HashMap, HashSet};
+use text_size::TextSize;
+
+/// Formats the file `root`, which has no syntax errors, as lines that still
+/// have to be aligned.
+pub(crate) fn print(root: &SyntaxNode) -> Vec<Line> {
+    // every token other than whitespace, with how many line breaks there were
+    // before it
+    let mut tokens = Vec::new();
+    let mut newlines = 0;
+    for token in root
+        .descendants_with_tokens()
+        .filter_map(|e| e.into_token())
+    {
+        if token.kind() == SyntaxKind::Whitespace {
+            newlines += token.text().matches('\n').count();
+        } else {
+            tokens.push((newlines, token));
+            newlines = 0;
+        }
+    }
+
+    let multi_line = multi_line_openers(&tokens);
+    let mut printer = Printer {
+        rows: rows(root, &multi_line),
+        multi_line,
+        lines: Vec::new(),
+        line: Line::default(),
+        groups: Vec::new(),
+        cell: None,
+    };
+    for i in 0..tokens.len() {
+        printer.token(&tokens, i);
+    }
+    printer.lines.push(printer.line);
+    printer.lines
+}
+
+/// A bracket that's been opened.
+struct Group {
+    /// The indentation of the line the bracket is on, and so of the line
+    /// that closes it if it spans several.
+    outer: usize,
+    /// The indentation of the lines inside the brackets.
+    inner: usize,
+    multi_line: bool,
+}
+
+/// How an element of a row of a multi-line array is aligned.
+#[derive(Clone, Copy)]
+struct RowCell {
+    /// The array the row is in.
+    group: TextSize,
+    row: TextSize,
+    column: usize,
+    numeric: bool,
+    last: bool,
+}
+
+struct Printer {
+    /// The opening brackets that have a line break directly inside them.
+    multi_line: HashSet<TextSize>,
+    /// The cells that start at a token, and the tokens after which they end.
+    rows: (HashMap<TextSize, RowCell>, HashSet<TextSize>),
+    lines: Vec<Line>,
+    line: Line,
+    groups: Vec<Group>,
+    /// The cell being printed, if any.
+    cell: Option<Cell>,
+}
+
+impl Printer {
+    fn token(&mut self, tokens: &[(usize, SyntaxToken)], i: usize) {
+        let token = &tokens[i].1;
+        if i > 0 {
+            match self.separator(tokens, i) {
+                Separator::Newline { blank } => {
+                    let indent = self.indent(tokens, i);
+                    self.new_line(blank, indent);
+                }
+                Separator::Space => self.push(" "),
+                Separator::Nothing => {}
+            }
+        }
+
+        let start = token.text_range().start();
+        if let Some(cell) = self.rows.0.get(&start) {
+            self.cell = Some(Cell {
+                group: cell.group,
+                row: cell.row,
+                column: cell.column,
+                text: String::new(),
+                numeric: cell.numeric,
+                last: cell.last,
+            });
+        }
+        self.push(token.text());
+        if self.rows.1.contains(&start) {
+            if let Some(cell) = self.cell.take() {
+                self.line.pieces.push(Piece::Cell(cell));
+            }
+        }
+
+        let kind = token.kind();
+        if is_opener(kind) {
+            let multi_line = self.multi_line.contains(&start);
+            self.groups.push(Group {
+                outer: self.line.indent,
+                inner: self.line.indent + multi_line as usize,
+                multi_line,
+            });
+        } else if is_closer(kind) {
+            self.groups.pop();
+        } else if kind == SyntaxKind::Colon {
+            self.field_cell(token);
+        }
+    }
+
+    fn separator(&self, tokens: &[(usize, SyntaxToken)], i: usize) -> Separator {
+        let (newlines, next) = &tokens[i];
+        let prev = &tokens[i - 1].1;
+        let (prev_kind, next_kind) = (prev.kind(), next.kind());
+        let after_opener = is_opener(prev_kind) && self.is_multi_line(prev);
+        let before_closer =
+            is_closer(next_kind) && self.groups.last().is_some_and(|group| group.multi_line);
+
+        if prev_kind == SyntaxKind::Comment || after_opener || before_closer || *newlines > 0 {
+            // a comment stays at the end of the line it's on
+            if next_kind == SyntaxKind::Comment && *newlines == 0 {
+                return Separator::Space;
+            }
+            if next_kind == SyntaxKind::ElseKw && prev_kind == SyntaxKind::RCurlyBracket {
+                return Separator::Space;
+            }
+            let blank = if after_opener || before_closer {
+                0
+            } else {
+                newlines.saturating_sub(1).min(1)
+            };
+            return Separator::Newline { blank };
+        }
+
+        if space_between(prev, next) {
+            Separator::Space
+        } else {
+            Separator::Nothing
+        }
+    }
+
+    /// The indentation of the line that starts with the `i`th token.
+    fn indent(&self, tokens: &[(usize, SyntaxToken)], i: usize) -> usize {
+        let group = self.groups.last();
+        if is_closer(tokens[i].1.kind()) {
+            return group.map_or(0, |group| group.outer);
+        }
+        let indent = group.map_or(0, |group| group.inner);
+
+        // comments are indented like the code after them, and an `else` that
+        // a comment kept off the line of the `}` like its `if`
+        let code = |token: &&SyntaxToken| token.kind() != SyntaxKind::Comment;
+        let prev = tokens[..i].iter().rev().map(|(_, token)| token).find(code);
+        let next = tokens[i..].iter().map(|(_, token)| token).find(code);
+        match (prev, next) {
+            (Some(prev), Some(next))
+                if !is_opener(prev.kind())
+                    && !is_closer(next.kind())
+                    && next.kind() != SyntaxKind::ElseKw
+                    && !continues_list(prev, next) =>
+            {
+                indent + 1
+            }
+            _ => indent,
+        }
+    }
+
+    fn is_multi_line(&self, opener: &SyntaxToken) -> bool {
+        self.multi_line.contains(&opener.text_range().start())
+    }
+
+    fn new_line(&mut self, blank: usize, indent: usize) {
+        let line = std::mem::take(&mut self.line);
+        self.lines.push(line);
+        for _ in 0..blank {
+            self.lines.push(Line::default());
+        }
+        self.line.indent = indent;
+    }
+
+    fn push(&mut self, text: &str) {
+        if let Some(cell) = &mut self.cell {
+            cell.text.push_str(text);
+            return;
+        }
+        match self.line.pieces.last_mut() {
+            Some(Piece::Text(last)) => last.push_str(text),
+            _ => self.line.pieces.push(Piece::Text(text.to_string())),
+        }
+    }
+
+    /// Makes what's on the line up to the colon of a struct field into a
+    /// cell, so the types of the fields on the lines around it line up.
+    fn field_cell(&mut self, colon: &SyntaxToken) {
+        let list = match colon.parent().and_then(|field| field.parent()) {
+            Some(list) if list.kind() == SyntaxKind::StructFieldListDef => list,
+            _ => return,
+        };
+        let multi_line = list
+            .first_token()
+            .is_some_and(|brace| self.is_multi_line(&brace));
+        if !multi_line || self.line.pieces.iter().any(|p| matches!(p, Piece::Cell(_))) {
+            return;
+        }
+
+        let text = self
+            .line
+            .pieces
+            .drain(..)
+            .map(|piece| match piece {
+                Piece::Text(text) => text,
+                Piece::Cell(cell) => cell.text,
+            })
+            .collect();
+        self.line.pieces.push(Piece::Cell(Cell {
+            group: list.text_range().start(),
+            row: colon.text_range().start(),
+            column: 0,
+            text,
+            numeric: false,
+            last: false,
+        }));
+    }
+}
+
+enum Separator {
+    Newline { blank: usize },
+    Space,
+    Nothing,
+}
+
+/// The opening brackets that have a line break directly inside them, rather
+/// than inside brackets nested in them.
+fn multi_line_openers(tokens: &[(usize, SyntaxToken)]) -> HashSet<TextSize> {
+    let mut open = Vec::new();
+    let mut multi_line = HashSet::new();
+    for (newlines, token) in tokens {
+        if *newlines > 0 {
+            if let Some(opener) = open.last() {
+                multi_line.insert(*opener);
+            }
+        }
+        if is_opener(token.kind()) {
+            open.push(token.text_range().start());
+        } else if is_closer(token.kind()) {
+            open.pop();
+        }
+    }
+    multi_line
+}
+
+/// The cells of the rows of multi-line arrays: every element of an array or
+/// tuple on one line that's an element of a multi-line array. Each cell
+/// starts at the element's first token, and ends after the comma after it
+/// if there is one.
+fn rows(
+    root: &SyntaxNode,
+    multi_line: &HashSet<TextSize>,
+) -> (HashMap<TextSize, RowCell>, HashSet<TextSize>) {
+    let is_multi_line = |node: &SyntaxNode| {
+        node.first_token()
+            .is_some_and(|opener| multi_line.contains(&opener.text_range().start()))
+    };
+    let mut starts = HashMap::new();
+    let mut ends = HashSet::new();
+
+    let arrays = root
+        .descendants()
+        .filter(|node| node.kind() == SyntaxKind::ArrayExpr && is_multi_line(node));
+    for array in arrays {
+        let rows = array.children().filter(|row| {
+            matches!(row.kind(), SyntaxKind::ArrayExpr | SyntaxKind::TupleExpr)
+                && !is_multi_line(row)
+        });
+        for row in rows {
+            let elements: Vec<_> = row.children().collect();
+            for (column, element) in elements.iter().enumerate() {
+                let (first, last) = match (element.first_token(), element.last_token()) {
+                    (Some(first), Some(last)) => (first, last),
+                    _ => continue,
+                };
+                let end = match element.next_sibling_or_token() {
+                    Some(comma) if comma.kind() == SyntaxKind::Comma => comma.text_range().start(),
+                    _ => last.text_range().start(),
+                };
+                starts.insert(
+                    first.text_range().start(),
+                    RowCell {
+                        group: array.text_range().start(),
+                        row: row.text_range().start(),
+                        column,
+                        numeric: is_number(element),
+                        last: column + 1 == elements.len(),
+                    },
+                );
+                ends.insert(end);
+            }
+        }
+    }
+    (starts, ends)
+}
+
+fn is_number(expr: &SyntaxNode) -> bool {
+    match expr.kind() {
+        SyntaxKind::Literal => expr
+            .first_token()
+            .is_some_and(|token| token.kind() == SyntaxKind::Number),
+        SyntaxKind::PrefixExpr => expr.children().next().is_some_and(|e| is_number(&e)),
+        _ => false,
+    }
+}
+
+/// Whether a line break between `prev` and `next` separates two elements of
+/// a list, like statements or array elements, rather than splitting one.
+fn continues_list(prev: &SyntaxToken, next: &SyntaxToken) -> bool {
+    let ancestors: HashSet<_> = prev.ancestors().collect();
+    let common = next.ancestors().find(|node| ancestors.contains(node));
+    common.is_none_or(|node| is_list(node.kind()))
+}
+
+fn is_list(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::Root
+            | SyntaxKind::FnBodyDef
+            | SyntaxKind::BlockExpr
+            | SyntaxKind::ImplItemListDef
+            | SyntaxKind::TraitListsDef
+            | SyntaxKind::TraitFnListDef
+            | SyntaxKind::StructFieldListDef
+            | SyntaxKind::MatchArmList
+            | SyntaxKind::ArrayExpr
+            | SyntaxKind::TupleExpr
+            | SyntaxKind::ArgList
+            | SyntaxKind::StructExpr
+            | SyntaxKind::FnParamListDef
+            | SyntaxKind::FnReturnTypeListDef
+            | SyntaxKind::TuplePat
+            | SyntaxKind::StructPat
+    )
+}
+
+fn is_opener(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::LCurlyBracket | SyntaxKind::LRoundBracket | SyntaxKind::LSquareBracket
+    )
+}
+
+fn is_closer(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::RCurlyBracket | SyntaxKind::RRoundBracket | SyntaxKind::RSquareBracket
+    )
+}
+
+/// Whether there's a space between two tokens on the same line.
+fn space_between(prev: &SyntaxToken, next: &SyntaxToken) -> bool {
+    use SyntaxKind::*;
+
+    let parent = |token: &SyntaxToken| token.parent().map(|node| node.kind());
+    match (prev.kind(), next.kind()) {
+        (LCurlyBracket, RCurlyBracket) => false,
+        (LCurlyBracket, _) | (_, RCurlyBracket) => true,
+        (_, Colon) if parent(next) == Some(TernaryExpr) => true,
+        (LRoundBracket | LSquareBracket | Dot | Dot2, _) => false,
+        (_, RRoundBracket | RSquareBracket | Comma | Colon | Dot | Dot2) => false,
+        (Minus, _) if parent(prev) == Some(PrefixExpr) => false,
+        // the step of `a..b=step`, written like the range itself
+        (Equals, _) if parent(prev) == Some(InfixExpr) => false,
+        (_, Equals) if parent(next) == Some(InfixExpr) => false,
+        // arrays right after each other in an array, like `[[1, 2][3, 4]]`
+        (RSquareBracket, LSquareBracket) if parent(prev) == Some(ArrayExpr) => false,
+        // `Type::name`
+        (ConstKw, _) if parent(prev) == Some(PathExpr) => false,
+        (_, ConstKw) if parent(next) == Some(PathExpr) => false,
+        // calls and indexing, `fn(...)` and `Fn()`
+        (_, LRoundBracket) if parent(next) == Some(ArgList) => false,
+        (FnKw, LRoundBracket) => false,
+        (Ident, LRoundBracket) if parent(next) == Some(Type) => false,
+        (_, LSquareBracket) if parent(next) == Some(IndexExpr) => false,
+        // the parameters of closures
+        (Pipe, _) if is_first_pipe(prev) => false,
+        (_, Pipe) if parent(next) == Some(ClosureExpr) && !is_first_pipe(next) => false,
+        _ => true,
+    }
+}
+
+/// Whether `pipe` opens the parameters of a closure.
+fn is_first_pipe(pipe: &SyntaxToken) -> bool {
+    pipe.parent().is_some_and(|closure| {
+        closure.kind() == SyntaxKind::ClosureExpr && closure.first_token().as_ref() == Some(pipe)
+    })
+}
@@ -0,0 +1,738 @@
+use crate::item_tree::{lower_params, lower_type};
+use crate::source_map::{BodySourceMap, ExprSource};
+use crate::{BinaryOp, Expr, ExprIdx, FormatPart, MatchArm, Pat, PatIdx, Stmt, UnaryOp};
+use drip_ast::{self as ast, AstNode, AstPtr, AstToken, SyntaxNodePtr};
+use drip_syntax::{SyntaxKind, SyntaxNode};
+use la_arena::Arena;
+use smol_str::SmolStr;
+use std::ops::Index;
+use text_size::{TextRange, TextSize};
+
+/// The expressions and patterns of a body, along with where they came from.
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct BodyArenas {
+    pub(crate) exprs: Arena<Expr>,
+    pub(crate) pats: Arena<Pat>,
+    /// The statements lowered so far, moved into [`crate::Body`] at the end.
+    pub(crate) stmts: Vec<Stmt>,
+    pub(crate) source_map: BodySourceMap,
+}
+
+impl Index<ExprIdx> for BodyArenas {
+    type Output = Expr;
+
+    fn index(&self, idx: ExprIdx) -> &Expr {
+        &self.exprs[idx]
+    }
+}
+
+impl Index<PatIdx> for BodyArenas {
+    type Output = Pat;
+
+    fn index(&self, idx: PatIdx) -> &Pat {
+        &self.pats[idx]
+    }
+}
+
+impl BodyArenas {
+    pub fn pats(&self) -> impl Iterator<Item = (PatIdx, &Pat)> {
+        self.pats.iter()
+    }
+
+    pub fn source_map(&self) -> &BodySourceMap {
+        &self.source_map
+    }
+
+    pub(crate) fn lower_stmt(&mut self, ast: ast::Stmt) -> Option<Stmt> {
+        let stmt = ast.clone();
+        let result = match ast {
+            ast::Stmt::VariableDef(ast) if ast.name_list().is_some() => Stmt::TupleDef {
+                pat: self.alloc_name_list(ast.name_list()?),
+                value: self.alloc_expr(ast.value(), ast.syntax()),
+                mutable: true,
+            },
+            ast::Stmt::ConstDef(ast) if ast.name_list().is_some() => Stmt::TupleDef {
+                pat: self.alloc_name_list(ast.name_list()?),
+                value: self.alloc_expr(ast.value(), ast.syntax()),
+                mutable: false,
+            },
+            ast::Stmt::VariableDef(ast) => Stmt::VariableDef {
+                name: ast.name()?.text().into(),
+                value: self.alloc_expr(ast.value(), ast.syntax()),
+            },
+            ast::Stmt::ConstDef(ast) => Stmt::ConstDef {
+                name: ast.name()?.text().into(),
+                value: self.alloc_expr(ast.value(), ast.syntax()),
+            },
+            ast::Stmt::AssignDef(ast) => {
+                let op = match ast.op()?.kind() {
+                    SyntaxKind::PlusEquals => Some(BinaryOp::Add),
+                    SyntaxKind::MinusEquals => Some(BinaryOp::Sub),
+                    SyntaxKind::StarEquals => Some(BinaryOp::Mul),
+                    SyntaxKind::SlashEquals => Some(BinaryOp::Div),
+                    _ => None,
+                };
+                match ast.target() {
+                    // `a, b += 1` becomes a block of `a += 1` and `b += 1`
+                    Some(ast::Expr::TupleExpr(targets))
+                        if targets.l_round_bracket_token().is_none() =>
+                    {
+                        let stmts = targets
+                            .exprs()
+                            .map(|target| {
+                                let assign = Stmt::Assign {
+                                    target: self.alloc_expr(Some(target), targets.syntax()),
+                                    op,
+                                    value: self.alloc_expr(ast.value(), ast.syntax()),
+                                };
+                                self.push_stmt(assign, &stmt)
+                            })
+                            .collect();
+                        let range = ast.syntax().text_range();
+                        Stmt::Expr(self.alloc_implicit(Expr::Block { stmts }, ast.syntax(), range))
+                    }
+                    target => Stmt::Assign {
+                        target: self.alloc_expr(target, ast.syntax()),
+                        op,
+                        value: self.alloc_expr(ast.value(), ast.syntax()),
+                    },
+                }
+            }
+            ast::Stmt::Expr(ast) => {
+                let parent = ast.syntax().clone();
+                Stmt::Expr(self.alloc_expr(Some(ast), &parent))
+            }
+            // items are lowered by the item tree, and not at all in blocks yet
+            _ => return None,
+        };
+
+        Some(result)
+    }
+
+    /// Adds `stmt`, lowered from `ast`, to the body and returns its index.
+    pub(crate) fn push_stmt(&mut self, stmt: Stmt, ast: &ast::Stmt) -> usize {
+        self.stmts.push(stmt);
+        self.source_map.push_stmt(ast);
+        self.stmts.len() - 1
+    }
+
+    fn alloc_expr(&mut self, ast: Option<ast::Expr>, parent: &SyntaxNode) -> ExprIdx {
+        let expr = self.lower_expr(ast.clone());
+        self.alloc(expr, ast, parent)
+    }
+
+    /// Allocates `expr`, lowered from `ast`, which is a child of `parent`.
+    fn alloc(&mut self, expr: Expr, ast: Option<ast::Expr>, parent: &SyntaxNode) -> ExprIdx {
+        let idx = self.exprs.alloc(expr);
+
+        let mut ast = match ast {
+            Some(ast) => ast,
+            None => {
+                let source = ExprSource::Missing(SyntaxNodePtr::new(parent));
+                self.source_map.insert_expr(idx, source);
+                return idx;
+            }
+        };
+        self.source_map
+            .insert_expr(idx, ExprSource::Expr(AstPtr::new(&ast)));
+
+        // brackets are lowered to the expression inside them
+        while let ast::Expr::RoundBracketExpr(bracket) = ast {
+            match bracket.expr() {
+                Some(inner) => {
+                    self.source_map.insert_expr_alias(&inner, idx);
+                    ast = inner;
+                }
+                None => break,
+            }
+        }
+
+        idx
+    }
+
+    pub(crate) fn lower_expr(&mut self, ast: Option<ast::Expr>) -> Expr {
+        if let Some(ast) = ast {
+            match ast {
+                ast::Expr::InfixExpr(ast) => self.lower_binary(ast),
+                ast::Expr::PrefixExpr(ast) => self.lower_unary(ast),
+                ast::Expr::Literal(ast) => self.lower_literal(ast),
+                ast::Expr::RoundBracketExpr(ast) => self.lower_expr(ast.expr()),
+                ast::Expr::VariableRef(ast) => self.lower_variable_ref(ast),
+                ast::Expr::PathExpr(ast) => Expr::Path {
+                    ty: lower_type(ast.ty()),
+                    name: ast
+                        .name()
+                        .map_or_else(SmolStr::default, |name| name.text().into()),
+                },
+                ast::Expr::CallExpr(ast) => self.lower_call(ast),
+                ast::Expr::MethodCallExpr(ast) => self.lower_method_call(ast),
+                ast::Expr::FieldExpr(ast) => Expr::Field {
+                    expr: self.alloc_expr(ast.expr(), ast.syntax()),
+                    name: ast
+                        .name()
+                        .map_or_else(SmolStr::default, |name| name.text().into()),
+                },
+                ast::Expr::IndexExpr(ast) => Expr::Index {
+                    base: self.alloc_expr(ast.base(), ast.syntax()),
+                    index: self.alloc_expr(ast.index(), ast.syntax()),
+                },
+                ast::Expr::StructExpr(ast) => self.lower_struct(ast),
+                ast::Expr::ClosureExpr(ast) => Expr::Closure {
+                    params: lower_params(ast.params()).0,
+                    body: self.alloc_expr(ast.body(), ast.syntax()),
+                },
+                ast::Expr::PlaceholderExpr(_) => Expr::Placeholder,
+                ast::Expr::TupleExpr(ast) => Expr::Tuple {
+                    exprs: self.lower_exprs(ast.exprs(), ast.syntax()),
+                },
+                ast::Expr::ArrayExpr(ast) => Expr::Array {
+                    exprs: self.lower_exprs(ast.exprs(), ast.syntax()),
+                },
+                ast::Expr::BlockExpr(ast) => self.lower_block(ast),
+                ast::Expr::IfExpr(ast) => Expr::If {
+                    condition: self.alloc_expr(ast.condition(), ast.syntax()),
+                    then_branch: self.alloc_expr(ast.then_branch(), ast.syntax()),
+                    else_branch: ast
+                        .else_branch()
+                        .map(|branch| self.alloc_expr(Some(branch), ast.syntax())),
+                },
+                ast::Expr::TernaryExpr(ast) => Expr::If {
+                    condition: self.alloc_expr(ast.condition(), ast.syntax()),
+                    then_branch: self.alloc_expr(ast.then_branch(), ast.syntax()),
+                    else_branch: Some(self.alloc_expr(ast.else_branch(), ast.syntax())),
+                },
+                ast::Expr::LoopExpr(ast) => Expr::Loop {
+                    body: self.alloc_expr(ast.body(), ast.syntax()),
+                },
+                ast::Expr::WhileExpr(ast) => Expr::While {
+                    condition: self.alloc_expr(ast.condition(), ast.syntax()),
+                    body: self.alloc_expr(ast.body(), ast.syntax()),
+                },
+                ast::Expr::ForExpr(ast) => Expr::For {
+                    pat: match ast.pat() {
+                        Some(pat) => self.alloc_pat(pat),
+                        None => self.alloc_pat_with_source(Pat::Missing, ast.syntax()),
+                    },
+                    index: ast.index().map(|pat| self.alloc_pat(pat)),
+                    iterable: self.alloc_expr(ast.iterable(), ast.syntax()),
+                    body: self.alloc_expr(ast.body(), ast.syntax()),
+                },
+                ast::Expr::BreakExpr(ast) => Expr::Break {
+                    value: ast
+                        .expr()
+                        .map(|value| self.alloc_expr(Some(value), ast.syntax())),
+                },
+                ast::Expr::ContinueExpr(_) => Expr::Continue,
+                ast::Expr::MatchExpr(ast) => Expr::Match {
+                    scrutinee: self.alloc_expr(ast.expr(), ast.syntax()),
+                    arms: ast
+                        .match_arm_list()
+                        .into_iter()
+                        .flat_map(|list| list.match_arms())
+                        .map(|arm| MatchArm {
+                            pats: arm.pats().map(|pat| self.alloc_pat(pat)).collect(),
+                            body: self.alloc_expr(arm.expr(), arm.syntax()),
+                        })
+                        .collect(),
+                },
+            }
+        } else {
+            Expr::Missing
+        }
+    }
+
+    fn lower_binary(&mut self, ast: ast::InfixExpr) -> Expr {
+        let op = match ast.op().unwrap().kind() {
+            SyntaxKind::Plus => BinaryOp::Add,
+            SyntaxKind::Minus => BinaryOp::Sub,
+            SyntaxKind::Star => BinaryOp::Mul,
+            SyntaxKind::Slash => BinaryOp::Div,
+            SyntaxKind::Equals2 => BinaryOp::Eq,
+            SyntaxKind::NotEquals => BinaryOp::NotEq,
+            SyntaxKind::LAngledBracket => BinaryOp::Less,
+            SyntaxKind::LessEquals => BinaryOp::LessEq,
+            SyntaxKind::RAngledBracket => BinaryOp::Greater,
+            SyntaxKind::GreaterEquals => BinaryOp::GreaterEq,
+            SyntaxKind::Dot2 => {
+                return Expr::Range {
+                    start: self.alloc_expr(ast.lhs(), ast.syntax()),
+                    end: self.alloc_expr(ast.rhs(), ast.syntax()),
+                    step: ast
+                        .step()
+                        .map(|step| self.alloc_expr(Some(step), ast.syntax())),
+                }
+            }
+            _ => unreachable!(),
+        };
+
+        let lhs = self.lower_expr(ast.lhs());
+        let rhs = self.lower_expr(ast.rhs());
+
+        Expr::Binary {
+            op,
+            lhs: self.alloc(lhs, ast.lhs(), ast.syntax()),
+            rhs: self.alloc(rhs, ast.rhs(), ast.syntax()),
+        }
+    }
+
+    fn lower_unary(&mut self, ast: ast::PrefixExpr) -> Expr {
+        let op = match ast.op().unwrap().kind() {
+            SyntaxKind::Minus => UnaryOp::Neg,
+            _ => unreachable!(),
+        };
+
+        let expr = self.lower_expr(ast.expr());
+
+        Expr::Unary {
+            op,
+            expr: self.alloc(expr, ast.expr(), ast.syntax()),
+        }
+    }
+
+    fn alloc_pat(&mut self, ast: ast::Pat) -> PatIdx {
+        let pat = match &ast {
+            ast::Pat::WildcardPat(_) => Pat::Wildcard,
+            ast::Pat::IdentPat(ast) => match ast.name() {
+                Some(name) => Pat::Ident {
+                    name: name.text().into(),
+                },
+                None => Pat::Missing,
+            },
+            ast::Pat::LiteralPat(ast) => Pat::Literal {
+                expr: self.alloc_expr(ast.expr(), ast.syntax()),
+            },
+            ast::Pat::RangePat(ast) => Pat::Range {
+                start: self.alloc_expr(ast.start(), ast.syntax()),
+                end: self.alloc_expr(ast.end(), ast.syntax()),
+            },
+            ast::Pat::TuplePat(ast) => Pat::Tuple {
+                pats: ast.pats().map(|pat| self.alloc_pat(pat)).collect(),
+            },
+            ast::Pat::StructPat(ast) => Pat::Struct {
+                name: ast
+                    .name()
+                    .map_or_else(SmolStr::default, |name| name.text().into()),
+                fields: ast
+                    .field_pats()
+                    .filter_map(|field| {
+                        let name: SmolStr = field.name()?.text().into();
+                        let pat = match field.pat() {
+                            Some(pat) => self.alloc_pat(pat),
+                            // `x` is short for `x: x`
+                            None => {
+                                let pat = Pat::Ident { name: name.clone() };
+                                self.alloc_pat_with_source(pat, field.syntax())
+                            }
+                        };
+                        Some((name, pat))
+                    })
+                    .collect(),
+            },
+        };
+
+        self.alloc_pat_with_source(pat, ast.syntax())
+    }
+
+    /// The names in `a, b :: value`, as a tuple pattern.
+    fn alloc_name_list(&mut self, ast: ast::NameList) -> PatIdx {
+        let pats = ast
+            .ident_pats()
+            .map(|pat| self.alloc_pat(ast::Pat::IdentPat(pat)))
+            .collect();
+        self.alloc_pat_with_source(Pat::Tuple { pats }, ast.syntax())
+    }
+
+    fn alloc_pat_with_source(&mut self, pat: Pat, syntax: &SyntaxNode) -> PatIdx {
+        let idx = self.pats.alloc(pat);
+        self.source_map.insert_pat(idx, SyntaxNodePtr::new(syntax));
+        idx
+    }
+
+    fn lower_block(&mut self, ast: ast::BlockExpr) -> Expr {
+        let stmts = ast
+            .stmts()
+            .filter_map(|stmt| {
+                let lowered = self.lower_stmt(stmt.clone())?;
+                Some(self.push_stmt(lowered, &stmt))
+            })
+            .collect();
+
+        Expr::Block { stmts }
+    }
+
+    fn lower_struct(&mut self, ast: ast::StructExpr) -> Expr {
+        let name = ast
+            .name()
+            .map_or_else(SmolStr::default, |name| name.text().into());
+        let fields = ast
+            .struct_expr_fields()
+            .map(|field| {
+                let name: Option<SmolStr> = field.name().map(|name| name.text().into());
+                let value = match (field.name(), field.expr()) {
+                    // `x` is short for `x: x`
+                    (Some(token), None) => {
+                        let var = Expr::VariableRef {
+                            var: token.text().into(),
+                        };
+                        let range = token.syntax().text_range();
+                        self.alloc_implicit(var, field.syntax(), range)
+                    }
+                    (_, expr) => self.alloc_expr(expr, field.syntax()),
+                };
+                (name, value)
+            })
+            .collect();
+
+        Expr::Struct { name, fields }
+    }
+
+    fn lower_literal(&mut self, ast: ast::Literal) -> Expr {
+        match ast.str() {
+            Some(s) if s.value().contains('{') => self.lower_format_string(&ast, s),
+            _ => lower_literal(ast),
+        }
+    }
+
+    /// Splits a string like `"a = {a}"` into its text and the names in braces.
+    /// Braces that don't hold a name are kept as they are.
+    fn lower_format_string(&mut self, ast: &ast::Literal, s: ast::Str) -> Expr {
+        let value = s.value();
+        // the contents start after the opening quote
+        let start = s.syntax().text_range().start() + TextSize::from(1);
+
+        let mut parts = Vec::new();
+        let mut text_start = 0;
+        let mut rest = 0;
+        while let Some(open) = value[rest..].find('{').map(|open| rest + open) {
+            let name_len = value[open + 1..]
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(value.len() - open - 1);
+            let close = open + 1 + name_len;
+            let is_name = name_len > 0
+                && !value[open + 1..].starts_with(|c: char| c.is_ascii_digit())
+                && value[close..].starts_with('}');
+            if !is_name {
+                rest = open + 1;
+                continue;
+            }
+
+            if text_start < open {
+                parts.push(FormatPart::Text(value[text_start..open].into()));
+            }
+            let var = Expr::VariableRef {
+                var: value[open + 1..close].into(),
+            };
+            let range = TextRange::new(
+                start + TextSize::from(open as u32),
+                start + TextSize::from(close as u32 + 1),
+            );
+            parts.push(FormatPart::Expr(self.alloc_implicit(
+                var,
+                ast.syntax(),
+                range,
+            )));
+            text_start = close + 1;
+            rest = text_start;
+        }
+
+        if parts.is_empty() {
+            return Expr::StringLiteral {
+                value: value.into(),
+            };
+        }
+        if text_start < value.len() {
+            parts.push(FormatPart::Text(value[text_start..].into()));
+        }
+
+        Expr::FormatString { parts }
+    }
+
+    /// Allocates `expr`, which has no syntax of its own but covers `range`
+    /// inside `node`.
+    fn alloc_implicit(&mut self, expr: Expr, node: &SyntaxNode, range: TextRange) -> ExprIdx {
+        let idx = self.exprs.alloc(expr);
+        let source = ExprSource::Implicit {
+            node: SyntaxNodePtr::new(node),
+            range,
+        };
+        self.source_map.insert_expr(idx, source);
+        idx
+    }
+
+    fn lower_variable_ref(&mut self, ast: ast::VariableRef) -> Expr {
+        Expr::VariableRef {
+            var: ast.name().unwrap().text().into(),
+        }
+    }
+
+    fn lower_call(&mut self, ast: ast::CallExpr) -> Expr {
+        let callee = self.alloc_expr(ast.expr(), ast.syntax());
+        let (args, arg_names) = self.lower_args(ast.arg_list());
+
+        Expr::Call {
+            callee,
+            args,
+            arg_names,
+        }
+    }
+
+    fn lower_method_call(&mut self, ast: ast::MethodCallExpr) -> Expr {
+        let receiver = self.alloc_expr(ast.receiver(), ast.syntax());
+        let name = ast
+            .name()
+            .map_or_else(SmolStr::default, |name| name.text().into());
+        let (args, arg_names) = self.lower_args(ast.arg_list());
+
+        Expr::MethodCall {
+            receiver,
+            name,
+            args,
+            arg_names,
+        }
+    }
+
+    fn lower_args(&mut self, list: Option<ast::ArgList>) -> (Vec<ExprIdx>, Vec<Option<SmolStr>>) {
+        let list = match list {
+            Some(list) => list,
+            None => return (Vec::new(), Vec::new()),
+        };
+        list.args()
+            .map(|arg| match arg {
+                ast::Arg::NamedArg(arg) => {
+                    let name = arg.name().map(|name| name.text().into());
+                    (self.alloc_expr(arg.expr(), arg.syntax()), name)
+                }
+                ast::Arg::Expr(expr) => (self.alloc_expr(Some(expr), list.syntax()), None),
+            })
+            .unzip()
+    }
+
+    fn lower_exprs(
+        &mut self,
+        exprs: impl Iterator<Item = ast::Expr>,
+        parent: &SyntaxNode,
+    ) -> Vec<ExprIdx> {
+        exprs
+            .map(|expr| self.alloc_expr(Some(expr), parent))
+            .collect()
+    }
+}
+
+fn lower_literal(ast: ast::Literal) -> Expr {
+    if ast.true_kw_token().is_some() || ast.false_kw_token().is_some() {
+        return Expr::BoolLiteral {
+            value: ast.true_kw_token().is_some(),
+        };
+    }
+
+    if let Some(s) = ast.str() {
+        return Expr::StringLiteral {
+            value: s.value().into(),
+        };
+    }
+
+    match ast.number() {
+        Some(n) if n.is_float() => Expr::FloatLiteral { n: n.float_value() },
+        _ => Expr::Literal { n: ast.parse() },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use drip_parser as parser;
+
+    fn parse(input: &str) -> ast::Root {
+        ast::Root::cast(parser::parse(input).syntax()).unwrap()
+    }
+
+    fn check_stmt(input: &str, expected_hir: Stmt, expected_exprs: Arena<Expr>) {
+        let root = parse(input);
+        let ast = root.stmts().next().unwrap();
+        let mut database = BodyArenas::default();
+        let hir = database.lower_stmt(ast).unwrap();
+
+        assert_eq!(hir, expected_hir);
+        assert_eq!(database.exprs, expected_exprs);
+    }
+
+    fn check_expr(input: &str, expected_hir: Expr, expected_exprs: Arena<Expr>) {
+        let root = parse(input);
+        let first_stmt = root.stmts().next().unwrap();
+        let ast = match first_stmt {
+            ast::Stmt::Expr(ast) => ast,
+            _ => unreachable!(),
+        };
+        let mut database = BodyArenas::default();
+        let hir = database.lower_expr(Some(ast));
+
+        assert_eq!(hir, expected_hir);
+        assert_eq!(database.exprs, expected_exprs);
+    }
+
+    #[test]
+    fn lower_variable_def() {
+        let mut exprs = Arena::new();
+        let value = exprs.alloc(Expr::VariableRef { var: "bar".into() });
+
+        check_stmt(
+            "foo := bar",
+            Stmt::VariableDef {
+                name: "foo".into(),
+                value,
+            },
+            exprs,
+        );
+    }
+
+    #[test]
+    fn lower_const_def() {
+        let mut exprs = Arena::new();
+        let value = exprs.alloc(Expr::VariableRef { var: "bar".into() });
+
+        check_stmt(
+            "foo :: bar",
+            Stmt::ConstDef {
+                name: "foo".into(),
+                value,
+            },
+            exprs,
+        );
+    }
+
+    // TODO: make this work
+    // #[test]
+    // fn lower_variable_def_without_name() {
+    //     let root = parse(":= 10");
+    //     let ast = root.stmts().next().unwrap();
+    //     println!("{:?}", ast);
+    //     assert!(BodyArenas::default().lower_stmt(ast).is_none());
+    // }
+
+    #[test]
+    fn lower_variable_def_without_value() {
+        let mut exprs = Arena::new();
+        let value = exprs.alloc(Expr::Missing);
+
+        check_stmt(
+            "a :=",
+            Stmt::VariableDef {
+                name: "a".into(),
+                value,
+            },
+            exprs,
+        );
+    }
+
+    #[test]
+    fn lower_compound_assignment() {
+        let mut exprs = Arena::new();
+        let v = exprs.alloc(Expr::VariableRef { var: "v".into() });
+        let target = exprs.alloc(Expr::Field {
+            expr: v,
+            name: "x".into(),
+        });
+        let value = exprs.alloc(Expr::Literal { n: Some(2) });
+
+        check_stmt(
+            "v.x *= 2",
+            Stmt::Assign {
+                target,
+                op: Some(BinaryOp::Mul),
+                value,
+            },
+            exprs,
+        );
+    }
+
+    #[test]
+    fn lower_expr_stmt() {
+        let mut exprs = Arena::new();
+        let expr = exprs.alloc(Expr::Literal { n: Some(123) });
+
+        check_stmt("123", Stmt::Expr(expr), exprs);
+    }
+
+    #[test]
+    fn lower_binary_expr() {
+        let mut exprs = Arena::new();
+        let lhs = exprs.alloc(Expr::Literal { n: Some(1) });
+        let rhs = exprs.alloc(Expr::Literal { n: Some(2) });
+
+        check_expr(
+            "1 + 2",
+            Expr::Binary {
+                lhs,
+                rhs,
+                op: BinaryOp::Add,
+            },
+            exprs,
+        );
+    }
+
+    #[test]
+    fn lower_binary_expr_without_rhs() {
+        let mut exprs = Arena::new();
+        let lhs = exprs.alloc(Expr::Literal { n: Some(10) });
+        let rhs = exprs.alloc(Expr::Missing);
+
+        check_expr(
+            "10 -",
+            Expr::Binary {
+                lhs,
+                rhs,
+                op: BinaryOp::Sub,
+            },
+            exprs,
+        );
+    }
+
+    #[test]
+    fn lower_literal() {
+        check_expr("999", Expr::Literal { n: Some(999) }, Arena::new());
+    }
+
+    #[test]
+    fn lower_paren_expr() {
+        check_expr(
+            "((((((abc))))))",
+            Expr::VariableRef { var: "abc".into() },
+            Arena::new(),
+        );
+    }
+
+    #[test]
+    fn lower_unary_expr() {
+        let mut exprs = Arena::new();
+        let ten = exprs.alloc(Expr::Literal { n: Some(10) });
+
+        check_expr(
+            "-10",
+            Expr::Unary {
+                expr: ten,
+                op: UnaryOp::Neg,
+            },
+            exprs,
+        );
+    }
+
+    #[test]
+    fn lower_unary_expr_without_expr() {
+        let mut exprs = Arena::new();
+        let expr = exprs.alloc(Expr::Missing);
+
+        check_expr(
+            "-",
+            Expr::Unary {
+                expr,
+                op: UnaryOp::Neg,
+            },
+            exprs,
+        );
+    }
+
+    #[test]
+    fn lower_variable_ref() {
+        check_expr("foo", Expr::VariableRef { var: "foo".into() }, Arena::new());
+    }
+}
@@ -0,0 +1,601 @@
+//! Control-flow graphs of bodies.
+//!
+//! A [`Cfg`] splits a body into basic blocks of [`Instr`]s, the reads and
+//! writes of bindings in the order they happen, each ending in a
+//! [`Terminator`] that says where control goes next. Expressions that don't
+//! touch bindings leave no instructions behind; only their control flow
+//! shows up in the graph.
+//!
+//! Code after a `break` or `continue` is put in a block nothing jumps to, so
+//! it's still in the graph but unreachable from the entry. The body of a
+//! closure is a branch off the point where the closure is created, since it
+//! may run later or not at all.
+
+use crate::resolve::{BodyId, Resolution, Resolutions};
+use crate::{Body, Expr, ExprIdx, FormatPart, ItemTree, Pat, PatIdx, Stmt};
+use la_arena::{Arena, Idx};
+use std::mem;
+
+pub type BlockId = Idx<BasicBlock>;
+
+#[derive(Debug, PartialEq)]
+pub struct Cfg {
+    blocks: Arena<BasicBlock>,
+    entry: BlockId,
+    loops: Vec<Loop>,
+    stray_jumps: Vec<ExprIdx>,
+}
+
+impl Cfg {
+    pub fn blocks(&self) -> impl Iterator<Item = (BlockId, &BasicBlock)> {
+        self.blocks.iter()
+    }
+
+    pub fn block(&self, id: BlockId) -> &BasicBlock {
+        &self.blocks[id]
+    }
+
+    pub fn entry(&self) -> BlockId {
+        self.entry
+    }
+
+    /// Every loop in the body, along with the ways out of it.
+    pub fn loops(&self) -> &[Loop] {
+        &self.loops
+    }
+
+    /// The `break` and `continue` expressions that aren't inside a loop.
+    pub fn stray_jumps(&self) -> &[ExprIdx] {
+        &self.stray_jumps
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BasicBlock {
+    pub instrs: Vec<Instr>,
+    pub terminator: Terminator,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Instr {
+    /// `var` is read by the `VariableRef` at `expr`.
+    Use { var: Resolution, expr: ExprIdx },
+    /// `var` is given a value.
+    Def { var: Resolution },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Terminator {
+    Goto(BlockId),
+    /// Continues at `then_target` or `else_target` depending on `on`, which
+    /// is the condition of an `if` or `while`, a `for` loop that either has
+    /// another element or is done, a `match` whose next arm either matches
+    /// or doesn't, or a closure whose body runs or doesn't.
+    Branch {
+        on: ExprIdx,
+        then_target: BlockId,
+        else_target: BlockId,
+    },
+    /// Leaves the body.
+    Return,
+}
+
+impl Terminator {
+    pub fn successors(&self) -> Vec<BlockId> {
+        match *self {
+            Self::Goto(target) => vec![target],
+            Self::Branch {
+                then_target,
+                else_target,
+                ..
+            } => vec![then_target, else_target],
+            Self::Return => Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Loop {
+    /// The `loop`, `while` or `for` expression.
+    pub expr: ExprIdx,
+    pub exits: Vec<LoopExit>,
+}
+
+/// A jump out of a loop, from the end of `block`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoopExit {
+    pub block: BlockId,
+    /// The `break`, or the loop itself when it ends because its condition is
+    /// false or it ran out of elements.
+    pub expr: ExprIdx,
+    pub has_value: bool,
+}
+
+/// Builds the control-flow graph of `body`, which belongs to `id`.
+pub fn build(tree: &ItemTree, id: BodyId, body: &Body, resolutions: &Resolutions) -> Cfg {
+    let mut blocks = Arena::new();
+    let entry = blocks.alloc(BasicBlock::default());
+    let mut builder = Builder {
+        id,
+        body,
+        resolutions,
+        blocks,
+        current: entry,
+        loops: Vec::new(),
+        finished_loops: Vec::new(),
+        stray_jumps: Vec::new(),
+    };
+
+    // parameters have their values from the start
+    if let BodyId::Function(function) = id {
+        for index in 0..tree[function].params.len() {
+            builder.push(Instr::Def {
+                var: Resolution::Param { function, index },
+            });
+        }
+    }
+    builder.lower_stmts(&body.top_level, true);
+
+    Cfg {
+        blocks: builder.blocks,
+        entry,
+        loops: builder.finished_loops,
+        stray_jumps: builder.stray_jumps,
+    }
+}
+
+impl Default for BasicBlock {
+    fn default() -> Self {
+        Self {
+            instrs: Vec::new(),
+            terminator: Terminator::Return,
+        }
+    }
+}
+
+struct LoopTargets {
+    expr: ExprIdx,
+    continue_target: BlockId,
+    break_target: BlockId,
+    exits: Vec<LoopExit>,
+}
+
+struct Builder<'a> {
+    id: BodyId,
+    body: &'a Body,
+    resolutions: &'a Resolutions,
+    blocks: Arena<BasicBlock>,
+    /// The block instructions are added to.
+    current: BlockId,
+    /// The loops around the expression being lowered, innermost last.
+    loops: Vec<LoopTargets>,
+    finished_loops: Vec<Loop>,
+    stray_jumps: Vec<ExprIdx>,
+}
+
+impl Builder<'_> {
+    fn new_block(&mut self) -> BlockId {
+        self.blocks.alloc(BasicBlock::default())
+    }
+
+    fn push(&mut self, instr: Instr) {
+        self.blocks[self.current].instrs.push(instr);
+    }
+
+    /// Ends the current block with `terminator` and continues in `next`.
+    fn terminate(&mut self, terminator: Terminator, next: BlockId) {
+        self.blocks[self.current].terminator = terminator;
+        self.current = next;
+    }
+
+    fn lower_stmts(&mut self, stmts: &[usize], top_level: bool) {
+        for &idx in stmts {
+            self.lower_stmt(idx, top_level);
+        }
+    }
+
+    fn lower_stmt(&mut self, idx: usize, top_level: bool) {
+        match &self.body.stmts[idx] {
+            Stmt::VariableDef { value, .. } | Stmt::ConstDef { value, .. } => {
+                self.lower_expr(*value);
+                // the same rule resolution uses to tell constants apart
+                let global = top_level
+                    && self.id == BodyId::Root
+                    && matches!(self.body.stmts[idx], Stmt::ConstDef { .. });
+                let var = if global {
+                    Resolution::Global { stmt: idx }
+                } else {
+                    Resolution::Local {
+                        body: self.id,
+                        stmt: idx,
+                    }
+                };
+                self.push(Instr::Def { var });
+            }
+            Stmt::TupleDef { pat, value, .. } => {
+                self.lower_expr(*value);
+                let mut bindings = Vec::new();
+                self.lower_pat(*pat, &mut bindings);
+                for var in bindings {
+                    self.push(Instr::Def { var });
+                }
+            }
+            Stmt::Assign { target, op, value } => {
+                let var = match self.body.arenas[*target] {
+                    // a field used without `self.` is changed like `self.x`
+                    Expr::VariableRef { .. } => match self.resolutions.get(self.id, *target) {
+                        Some(Resolution::SelfField { .. }) => None,
+                        var => var,
+                    },
+                    _ => None,
+                };
+                // a plain assignment to a binding doesn't read it
+                if op.is_some() || var.is_none() {
+                    self.lower_expr(*target);
+                }
+                self.lower_expr(*value);
+                if let Some(var) = var {
+                    self.push(Instr::Def { var });
+                }
+            }
+            Stmt::Expr(expr) => self.lower_expr(*expr),
+            Stmt::Item(_) => {}
+        }
+    }
+
+    fn lower_expr(&mut self, idx: ExprIdx) {
+        match &self.body.arenas[idx] {
+            Expr::VariableRef { .. } => {
+                if let Some(var) = self.resolutions.get(self.id, idx) {
+                    self.push(Instr::Use { var, expr: idx });
+                }
+            }
+            Expr::Binary { lhs, rhs, .. } => {
+                self.lower_expr(*lhs);
+                self.lower_expr(*rhs);
+            }
+            Expr::Range { start, end, step } => {
+                self.lower_expr(*start);
+                self.lower_expr(*end);
+                if let Some(step) = step {
+                    self.lower_expr(*step);
+                }
+            }
+            Expr::Unary { expr, .. } | Expr::Field { expr, .. } => self.lower_expr(*expr),
+            Expr::Index { base, index } => {
+                self.lower_expr(*base);
+                self.lower_expr(*index);
+            }
+            Expr::Struct { fields, .. } => {
+                for (_, value) in fields {
+                    self.lower_expr(*value);
+                }
+            }
+            Expr::FormatString { parts } => {
+                for part in parts {
+                    if let FormatPart::Expr(expr) = part {
+                        self.lower_expr(*expr);
+                    }
+                }
+            }
+            Expr::Closure { params, body } => {
+                let body_block = self.new_block();
+                let after = self.new_block();
+                let branch = Terminator::Branch {
+                    on: idx,
+                    then_target: body_block,
+                    else_target: after,
+                };
+                self.terminate(branch, body_block);
+                for index in 0..params.len() {
+                    self.push(Instr::Def {
+                        var: Resolution::ClosureParam {
+                            body: self.id,
+                            closure: idx,
+                            index,
+                        },
+                    });
+                }
+
+                // the loops around the closure can't be left from inside it
+                let loops = mem::take(&mut self.loops);
+                self.lower_expr(*body);
+                self.loops = loops;
+                self.terminate(Terminator::Goto(after), after);
+            }
+            Expr::Call { callee, args, .. } => {
+                self.lower_expr(*callee);
+                self.lower_exprs(args);
+            }
+            Expr::MethodCall { receiver, args, .. } => {
+                self.lower_expr(*receiver);
+                self.lower_exprs(args);
+            }
+            Expr::Tuple { exprs } | Expr::Array { exprs } => self.lower_exprs(exprs),
+            Expr::Block { stmts } => self.lower_stmts(stmts, false),
+            Expr::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.lower_expr(*condition);
+                let then_block = self.new_block();
+                let join = self.new_block();
+                let else_block = match else_branch {
+                    Some(_) => self.new_block(),
+                    None => join,
+                };
+                let branch = Terminator::Branch {
+                    on: *condition,
+                    then_target: then_block,
+                    else_target: else_block,
+                };
+                self.terminate(branch, then_block);
+
+                self.lower_expr(*then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.terminate(Terminator::Goto(join), else_block);
+                    self.lower_expr(*else_branch);
+                }
+                self.terminate(Terminator::Goto(join), join);
+            }
+            Expr::Loop { body } => {
+                let head = self.new_block();
+                let after = self.new_block();
+                self.terminate(Terminator::Goto(head), head);
+                self.lower_loop_body(idx, *body, head, after);
+            }
+            Expr::While { condition, body } => {
+                let head = self.new_block();
+                let body_block = self.new_block();
+                let after = self.new_block();
+                self.terminate(Terminator::Goto(head), head);
+
+                self.lower_expr(*condition);
+                let exit = self.normal_exit(idx);
+                let branch = Terminator::Branch {
+                    on: *condition,
+                    then_target: body_block,
+                    else_target: after,
+                };
+                self.terminate(branch, body_block);
+                self.lower_loop_body(idx, *body, head, after);
+                self.add_exit(exit);
+            }
+            Expr::For {
+                pat,
+                index,
+                iterable,
+                body,
+            } => {
+                self.lower_expr(*iterable);
+                let mut bindings = Vec::new();
+                for pat in std::iter::once(pat).chain(index) {
+                    self.lower_pat(*pat, &mut bindings);
+                }
+                let head = self.new_block();
+                let body_block = self.new_block();
+                let after = self.new_block();
+                self.terminate(Terminator::Goto(head), head);
+
+                let exit = self.normal_exit(idx);
+                let branch = Terminator::Branch {
+                    on: idx,
+                    then_target: body_block,
+                    else_target: after,
+                };
+                self.terminate(branch, body_block);
+                for var in bindings {
+                    self.push(Instr::Def { var });
+                }
+                self.lower_loop_body(idx, *body, head, after);
+                self.add_exit(exit);
+            }
+            Expr::Break { value } => {
+                if let Some(value) = value {
+                    self.lower_expr(*value);
+                }
+                let unreachable = self.new_block();
+                match self.loops.last_mut() {
+                    Some(targets) => {
+                        targets.exits.push(LoopExit {
+                            block: self.current,
+                            expr: idx,
+                            has_value: value.is_some(),
+                        });
+                        let target = targets.break_target;
+                        self.terminate(Terminator::Goto(target), unreachable);
+                    }
+                    None => {
+                        self.stray_jumps.push(idx);
+                        self.current = unreachable;
+                    }
+                }
+            }
+            Expr::Continue => {
+                let unreachable = self.new_block();
+                match self.loops.last() {
+                    Some(targets) => {
+                        let target = targets.continue_target;
+                        self.terminate(Terminator::Goto(target), unreachable);
+                    }
+                    None => {
+                        self.stray_jumps.push(idx);
+                        self.current = unreachable;
+                    }
+                }
+            }
+            Expr::Match { scrutinee, arms } => {
+                self.lower_expr(*scrutinee);
+                let join = self.new_block();
+                for arm in arms {
+                    let mut bindings = Vec::new();
+                    for pat in &arm.pats {
+                        self.lower_pat(*pat, &mut bindings);
+                    }
+
+                    let arm_block = self.new_block();
+                    let next = self.new_block();
+                    let branch = Terminator::Branch {
+                        on: idx,
+                        then_target: arm_block,
+                        else_target: next,
+                    };
+                    self.terminate(branch, arm_block);
+                    for var in bindings {
+                        self.push(Instr::Def { var });
+                    }
+                    self.lower_expr(arm.body);
+                    self.terminate(Terminator::Goto(join), next);
+                }
+                // whether the arms cover every value is checked separately
+                self.terminate(Terminator::Goto(join), join);
+            }
+            Expr::Missing
+            | Expr::Placeholder
+            | Expr::Path { .. }
+            | Expr::Literal { .. }
+            | Expr::FloatLiteral { .. }
+            | Expr::StringLiteral { .. }
+            | Expr::BoolLiteral { .. } => {}
+        }
+    }
+
+    /// Lowers the expressions in `pat` and collects the bindings it defines.
+    fn lower_pat(&mut self, pat: PatIdx, bindings: &mut Vec<Resolution>) {
+        match &self.body.arenas[pat] {
+            Pat::Ident { .. } => {
+                if self.resolutions.pat(self.id, pat).is_none() {
+                    bindings.push(Resolution::PatBinding { body: self.id, pat });
+                }
+            }
+            Pat::Literal { expr } => self.lower_expr(*expr),
+            Pat::Range { start, end } => {
+                self.lower_expr(*start);
+                self.lower_expr(*end);
+            }
+            Pat::Tuple { pats } => {
+                for pat in pats {
+                    self.lower_pat(*pat, bindings);
+                }
+            }
+            Pat::Struct { fields, .. } => {
+                for (_, pat) in fields {
+                    self.lower_pat(*pat, bindings);
+                }
+            }
+            Pat::Missing | Pat::Wildcard => {}
+        }
+    }
+
+    fn lower_exprs(&mut self, exprs: &[ExprIdx]) {
+        for expr in exprs {
+            self.lower_expr(*expr);
+        }
+    }
+
+    /// Lowers `body` of the loop `expr`, jumping back to `head` at its end,
+    /// and continues in `after`.
+    fn lower_loop_body(&mut self, expr: ExprIdx, body: ExprIdx, head: BlockId, after: BlockId) {
+        self.loops.push(LoopTargets {
+            expr,
+            continue_target: head,
+            break_target: after,
+            exits: Vec::new(),
+        });
+        self.lower_expr(body);
+        self.terminate(Terminator::Goto(head), after);
+
+        let targets = self.loops.pop().unwrap();
+        self.finished_loops.push(Loop {
+            expr: targets.expr,
+            exits: targets.exits,
+        });
+    }
+
+    /// The exit of a `while` or `for` loop when it ends on its own, from the
+    /// current block.
+    fn normal_exit(&self, expr: ExprIdx) -> LoopExit {
+        LoopExit {
+            block: self.current,
+            expr,
+            has_value: false,
+        }
+    }
+
+    /// Adds `exit` to the loop that was finished last.
+    fn add_exit(&mut self, exit: LoopExit) {
+        self.finished_loops.last_mut().unwrap().exits.push(exit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolve::resolve;
+    use drip_ast::AstNode;
+
+    fn build_root(input: &str) -> Cfg {
+        let root = drip_ast::Root::cast(drip_parser::parse(input).syntax()).unwrap();
+        let (tree, body) = crate::lower(root);
+        let resolutions = resolve(&tree, &body);
+        build(&tree, BodyId::Root, &body, &resolutions)
+    }
+
+    fn reachable(cfg: &Cfg) -> Vec<BlockId> {
+        let mut seen = vec![cfg.entry()];
+        let mut stack = vec![cfg.entry()];
+        while let Some(block) = stack.pop() {
+            for next in cfg.block(block).terminator.successors() {
+                if !seen.contains(&next) {
+                    seen.push(next);
+                    stack.push(next);
+                }
+            }
+        }
+        seen
+    }
+
+    #[test]
+    fn branch_and_join_on_if() {
+        let cfg = build_root("a := 1\nb := if a == 1 { a } else { 2 }\nb");
+        let entry = cfg.block(cfg.entry());
+
+        assert!(matches!(
+            entry.instrs[..],
+            [Instr::Def { .. }, Instr::Use { .. }]
+        ));
+        let (then_target, else_target) = match entry.terminator {
+            Terminator::Branch {
+                then_target,
+                else_target,
+                ..
+            } => (then_target, else_target),
+            terminator => panic!("expected a branch, got {:?}", terminator),
+        };
+        assert_eq!(
+            cfg.block(then_target).terminator,
+            cfg.block(else_target).terminator
+        );
+        assert_eq!(reachable(&cfg).len(), 4);
+    }
+
+    #[test]
+    fn record_loop_exits_and_stray_jumps() {
+        let cfg =
+            build_root("x :: for i in 0..8 { if i == 5 { break i }\ncontinue\nbreak }\nbreak");
+
+        let exits: Vec<_> = cfg.loops()[0]
+            .exits
+            .iter()
+            .map(|exit| exit.has_value)
+            .collect();
+        assert_eq!(exits, [true, false, false]);
+        assert_eq!(cfg.stray_jumps().len(), 1);
+
+        // the bare `break` comes after a `continue`
+        let reachable = reachable(&cfg);
+        let bare_break = cfg.loops()[0].exits[1];
+        assert!(!reachable.contains(&bare_break.block));
+    }
+}
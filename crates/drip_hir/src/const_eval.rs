@@ -0,0 +1,531 @@
+//! Compile-time evaluation of `::` constants.
+//!
+//! A `::` binding gets a value if it can be computed without running the
+//! program: literals, arithmetic on them, arrays and tuples, and references
+//! to other constants. Other `::` bindings, like `res :: mul_add(1, 2, 3)`,
+//! are just immutable and have no value here.
+//!
+//! Integer arithmetic is checked against the type inference gave the
+//! expression, so `200 + 100` overflows if it's used as a `u8`. Every
+//! constant is evaluated once and the results are kept in [`ConstValues`],
+//! so later passes can fold constants without evaluating them again. The
+//! literals and range bounds in `match` patterns are evaluated the same way,
+//! since the exhaustiveness checker needs their values.
+
+use crate::infer::{InferenceResults, IntTy, Ty};
+use crate::resolve::{BodyId, Resolution, Resolutions};
+use crate::{BinaryOp, Body, Expr, ExprIdx, ItemTree, Pat, Stmt, UnaryOp};
+use smol_str::SmolStr;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Formatter;
+use text_size::TextRange;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstValue {
+    Int(i128),
+    Float(f64),
+    String(SmolStr),
+    Bool(bool),
+    Array(Vec<ConstValue>),
+    Tuple(Vec<ConstValue>),
+}
+
+// a NaN constant isn't equal to itself, which only means that the query
+// database doesn't reuse what was computed from it
+impl Eq for ConstValue {}
+
+impl fmt::Display for ConstValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Int(n) => write!(f, "{}", n),
+            Self::Float(n) => write!(f, "{:?}", n),
+            Self::String(value) => write!(f, "\"{}\"", value),
+            Self::Bool(value) => write!(f, "{}", value),
+            Self::Array(values) => write!(f, "[{}]", join(values)),
+            Self::Tuple(values) => write!(f, "({})", join(values)),
+        }
+    }
+}
+
+fn join(values: &[ConstValue]) -> String {
+    let values: Vec<_> = values.iter().map(ToString::to_string).collect();
+    values.join(", ")
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ConstError {
+    kind: ConstErrorKind,
+    body: BodyId,
+    expr: ExprIdx,
+    range: TextRange,
+}
+
+impl ConstError {
+    pub fn kind(&self) -> &ConstErrorKind {
+        &self.kind
+    }
+
+    pub fn body(&self) -> BodyId {
+        self.body
+    }
+
+    pub fn expr(&self) -> ExprIdx {
+        self.expr
+    }
+
+    pub fn range(&self) -> TextRange {
+        self.range
+    }
+}
+
+impl fmt::Display for ConstError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "error at {}..{}: {}",
+            u32::from(self.range.start()),
+            u32::from(self.range.end()),
+            self.kind,
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConstErrorKind {
+    Cycle { name: SmolStr },
+    Overflow { ty: IntTy },
+    DivisionByZero,
+}
+
+impl fmt::Display for ConstErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cycle { name } => {
+                write!(f, "cycle detected when evaluating constant `{}`", name)
+            }
+            Self::Overflow { ty } => write!(f, "constant overflows `{}`", ty.name()),
+            Self::DivisionByZero => write!(f, "attempt to divide by zero"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct ConstValues {
+    values: HashMap<(BodyId, usize), ConstValue>,
+    /// The values of the expressions in `match` patterns.
+    exprs: HashMap<(BodyId, ExprIdx), ConstValue>,
+    errors: Vec<ConstError>,
+}
+
+impl ConstValues {
+    /// The value of the `::` binding that is statement `stmt` of `body`.
+    pub fn get(&self, body: BodyId, stmt: usize) -> Option<&ConstValue> {
+        self.values.get(&(body, stmt))
+    }
+
+    /// The value of the pattern literal or range bound `expr` in `body`.
+    pub fn expr(&self, body: BodyId, expr: ExprIdx) -> Option<&ConstValue> {
+        self.exprs.get(&(body, expr))
+    }
+
+    pub fn errors(&self) -> &[ConstError] {
+        &self.errors
+    }
+}
+
+/// Evaluates the `::` bindings of `root` and of the bodies of all items in
+/// `tree`.
+pub fn eval_consts(
+    tree: &ItemTree,
+    root: &Body,
+    resolutions: &Resolutions,
+    inference: &InferenceResults,
+) -> ConstValues {
+    let mut evaluator = Evaluator {
+        tree,
+        root,
+        resolutions,
+        inference,
+        states: HashMap::new(),
+        exprs: HashMap::new(),
+        errors: Vec::new(),
+    };
+
+    let bodies =
+        std::iter::once(BodyId::Root).chain(tree.functions().map(|(id, _)| BodyId::Function(id)));
+    for id in bodies {
+        for stmt in 0..evaluator.body(id).stmts.len() {
+            evaluator.eval_const(id, stmt);
+        }
+
+        let body = evaluator.body(id);
+        for (_, pat) in body.arenas.pats() {
+            let exprs = match pat {
+                Pat::Literal { expr } => vec![*expr],
+                Pat::Range { start, end } => vec![*start, *end],
+                _ => continue,
+            };
+            for expr in exprs {
+                if let Some(value) = evaluator.eval_expr(id, expr) {
+                    evaluator.exprs.insert((id, expr), value);
+                }
+            }
+        }
+    }
+
+    let values = evaluator
+        .states
+        .into_iter()
+        .filter_map(|(key, state)| match state {
+            State::Done(Some(value)) => Some((key, value)),
+            State::Done(None) | State::InProgress => None,
+        })
+        .collect();
+
+    ConstValues {
+        values,
+        exprs: evaluator.exprs,
+        errors: evaluator.errors,
+    }
+}
+
+enum State {
+    InProgress,
+    Done(Option<ConstValue>),
+}
+
+struct Evaluator<'a> {
+    tree: &'a ItemTree,
+    root: &'a Body,
+    resolutions: &'a Resolutions,
+    inference: &'a InferenceResults,
+    states: HashMap<(BodyId, usize), State>,
+    exprs: HashMap<(BodyId, ExprIdx), ConstValue>,
+    errors: Vec<ConstError>,
+}
+
+impl<'a> Evaluator<'a> {
+    fn body(&self, id: BodyId) -> &'a Body {
+        match id {
+            BodyId::Root => self.root,
+            BodyId::Function(function) => &self.tree[function].body,
+        }
+    }
+
+    fn eval_const(&mut self, id: BodyId, stmt: usize) -> Option<ConstValue> {
+        if let Some(State::Done(value)) = self.states.get(&(id, stmt)) {
+            return value.clone();
+        }
+
+        let value = match self.body(id).stmts[stmt] {
+            Stmt::ConstDef { value, .. }
+            | Stmt::TupleDef {
+                value,
+                mutable: false,
+                ..
+            } => value,
+            _ => return None,
+        };
+
+        self.states.insert((id, stmt), State::InProgress);
+        let value = self.eval_expr(id, value);
+        self.states.insert((id, stmt), State::Done(value.clone()));
+
+        value
+    }
+
+    fn eval_expr(&mut self, id: BodyId, idx: ExprIdx) -> Option<ConstValue> {
+        let body = self.body(id);
+
+        match &body.arenas[idx] {
+            Expr::Literal { n } => match self.inference.body(id)[idx] {
+                Ty::Float(_) => Some(ConstValue::Float((*n)? as f64)),
+                _ => self.check_int(id, idx, i128::from((*n)?)),
+            },
+            Expr::FloatLiteral { n } => Some(ConstValue::Float((*n)?)),
+            Expr::StringLiteral { value } => Some(ConstValue::String(value.clone())),
+            Expr::BoolLiteral { value } => Some(ConstValue::Bool(*value)),
+            Expr::Unary {
+                op: UnaryOp::Neg,
+                expr,
+            } => match self.eval_expr(id, *expr)? {
+                ConstValue::Int(n) => self.check_int(id, idx, -n),
+                ConstValue::Float(n) => Some(ConstValue::Float(-n)),
+                _ => None,
+            },
+            Expr::Binary { op, lhs, rhs } => {
+                let lhs = self.eval_expr(id, *lhs);
+                let rhs = self.eval_expr(id, *rhs);
+                self.eval_binary(id, idx, op, lhs?, rhs?)
+            }
+            Expr::Tuple { exprs } => self.eval_all(id, exprs).map(ConstValue::Tuple),
+            Expr::Array { exprs } => self.eval_all(id, exprs).map(ConstValue::Array),
+            Expr::VariableRef { var } => match self.resolutions.get(id, idx)? {
+                Resolution::Local { body, stmt } => self.eval_ref(id, idx, var, body, stmt),
+                Resolution::Global { stmt } => self.eval_ref(id, idx, var, BodyId::Root, stmt),
+                Resolution::PatBinding { body, pat } => {
+                    let (stmt, index) = self.body(body).tuple_def(pat)?;
+                    match self.eval_ref(id, idx, var, body, stmt)? {
+                        ConstValue::Tuple(mut values) if index < values.len() => {
+                            Some(values.swap_remove(index))
+                        }
+                        _ => None,
+                    }
+                }
+                Resolution::Param { .. }
+                | Resolution::SelfField { .. }
+                | Resolution::ClosureParam { .. }
+                | Resolution::Item(_)
+                | Resolution::Builtin(_) => None,
+            },
+            Expr::Missing
+            | Expr::FormatString { .. }
+            | Expr::Path { .. }
+            | Expr::Call { .. }
+            | Expr::MethodCall { .. }
+            | Expr::Field { .. }
+            | Expr::Index { .. }
+            | Expr::Struct { .. }
+            | Expr::Closure { .. }
+            | Expr::Placeholder
+            | Expr::Range { .. }
+            | Expr::Block { .. }
+            | Expr::If { .. }
+            | Expr::Loop { .. }
+            | Expr::While { .. }
+            | Expr::For { .. }
+            | Expr::Break { .. }
+            | Expr::Continue
+            | Expr::Match { .. } => None,
+        }
+    }
+
+    /// Evaluates all of `exprs`, even after one of them turns out not to be
+    /// constant, so errors in later ones are still reported.
+    fn eval_all(&mut self, id: BodyId, exprs: &[ExprIdx]) -> Option<Vec<ConstValue>> {
+        let values: Vec<_> = exprs.iter().map(|expr| self.eval_expr(id, *expr)).collect();
+        values.into_iter().collect()
+    }
+
+    fn eval_ref(
+        &mut self,
+        id: BodyId,
+        idx: ExprIdx,
+        name: &SmolStr,
+        body: BodyId,
+        stmt: usize,
+    ) -> Option<ConstValue> {
+        if let Some(State::InProgress) = self.states.get(&(body, stmt)) {
+            self.report(ConstErrorKind::Cycle { name: name.clone() }, id, idx);
+            return None;
+        }
+
+        self.eval_const(body, stmt)
+    }
+
+    fn eval_binary(
+        &mut self,
+        id: BodyId,
+        idx: ExprIdx,
+        op: &BinaryOp,
+        lhs: ConstValue,
+        rhs: ConstValue,
+    ) -> Option<ConstValue> {
+        if op.is_comparison() {
+            let ordering = compare(&lhs, &rhs)?;
+            return Some(ConstValue::Bool(match op {
+                BinaryOp::Eq => ordering == Ordering::Equal,
+                BinaryOp::NotEq => ordering != Ordering::Equal,
+                BinaryOp::Less => ordering == Ordering::Less,
+                BinaryOp::LessEq => ordering != Ordering::Greater,
+                BinaryOp::Greater => ordering == Ordering::Greater,
+                BinaryOp::GreaterEq => ordering != Ordering::Less,
+                _ => unreachable!(),
+            }));
+        }
+
+        match (lhs, rhs) {
+            (ConstValue::Int(lhs), ConstValue::Int(rhs)) => {
+                let value = match op {
+                    BinaryOp::Add => lhs.checked_add(rhs),
+                    BinaryOp::Sub => lhs.checked_sub(rhs),
+                    BinaryOp::Mul => lhs.checked_mul(rhs),
+                    BinaryOp::Div if rhs == 0 => {
+                        self.report(ConstErrorKind::DivisionByZero, id, idx);
+                        return None;
+                    }
+                    BinaryOp::Div => lhs.checked_div(rhs),
+                    _ => unreachable!(),
+                };
+                match value {
+                    Some(value) => self.check_int(id, idx, value),
+                    None => {
+                        let ty = self.int_ty(id, idx).unwrap_or(IntTy::U64);
+                        self.report(ConstErrorKind::Overflow { ty }, id, idx);
+                        None
+                    }
+                }
+            }
+            (ConstValue::Float(lhs), ConstValue::Float(rhs)) => Some(ConstValue::Float(match op {
+                BinaryOp::Add => lhs + rhs,
+                BinaryOp::Sub => lhs - rhs,
+                BinaryOp::Mul => lhs * rhs,
+                BinaryOp::Div => lhs / rhs,
+                _ => unreachable!(),
+            })),
+            (ConstValue::String(lhs), ConstValue::String(rhs)) if *op == BinaryOp::Add => {
+                Some(ConstValue::String(format!("{}{}", lhs, rhs).into()))
+            }
+            _ => None,
+        }
+    }
+
+    /// `n` as the value of `idx`, if it fits in the expression's type.
+    fn check_int(&mut self, id: BodyId, idx: ExprIdx, n: i128) -> Option<ConstValue> {
+        if let Some(ty) = self.int_ty(id, idx) {
+            let (min, max) = ty.bounds();
+            if n < min || n > max {
+                self.report(ConstErrorKind::Overflow { ty }, id, idx);
+                return None;
+            }
+        }
+
+        Some(ConstValue::Int(n))
+    }
+
+    fn int_ty(&self, id: BodyId, idx: ExprIdx) -> Option<IntTy> {
+        match self.inference.body(id)[idx] {
+            Ty::Int(ty) => Some(ty),
+            _ => None,
+        }
+    }
+
+    fn report(&mut self, kind: ConstErrorKind, id: BodyId, expr: ExprIdx) {
+        let range = self
+            .body(id)
+            .arenas
+            .source_map()
+            .expr_syntax(expr)
+            .map_or_else(TextRange::default, |source| source.range());
+
+        self.errors.push(ConstError {
+            kind,
+            body: id,
+            expr,
+            range,
+        });
+    }
+}
+
+/// How two constants of the same scalar type compare, or `None` for other
+/// values and for comparisons involving NaN.
+fn compare(lhs: &ConstValue, rhs: &ConstValue) -> Option<Ordering> {
+    match (lhs, rhs) {
+        (ConstValue::Int(lhs), ConstValue::Int(rhs)) => Some(lhs.cmp(rhs)),
+        (ConstValue::Float(lhs), ConstValue::Float(rhs)) => lhs.partial_cmp(rhs),
+        (ConstValue::String(lhs), ConstValue::String(rhs)) => Some(lhs.cmp(rhs)),
+        (ConstValue::Bool(lhs), ConstValue::Bool(rhs)) => Some(lhs.cmp(rhs)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infer::infer;
+    use crate::resolve::resolve;
+    use drip_ast::AstNode;
+
+    fn eval_source(input: &str) -> (ItemTree, ConstValues) {
+        let root = drip_ast::Root::cast(drip_parser::parse(input).syntax()).unwrap();
+        let (tree, body) = crate::lower(root);
+        let resolutions = resolve(&tree, &body);
+        let inference = infer(&tree, &body, &resolutions);
+        let values = eval_consts(&tree, &body, &resolutions, &inference);
+        (tree, values)
+    }
+
+    fn values(consts: &ConstValues, body: BodyId, stmts: usize) -> Vec<String> {
+        (0..stmts)
+            .map(|stmt| {
+                consts
+                    .get(body, stmt)
+                    .map_or("-".to_string(), ToString::to_string)
+            })
+            .collect()
+    }
+
+    fn errors(consts: &ConstValues) -> Vec<String> {
+        consts.errors().iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn eval_global_constants() {
+        let (_, consts) = eval_source(
+            "TAU :: PI * 2.0\nPI :: 3.14159\nN :: 2 + 3 * 4\nNEG :: -N / 3\nARR :: [N, 1]\nT :: (\"a\" + \"b\", 1.5)",
+        );
+
+        assert_eq!(errors(&consts), Vec::<String>::new());
+        assert_eq!(
+            values(&consts, BodyId::Root, 6),
+            ["6.28318", "3.14159", "14", "-4", "[14, 1]", "(\"ab\", 1.5)"]
+        );
+    }
+
+    #[test]
+    fn eval_walkthrough_constants() {
+        let (_, consts) = eval_source("PI :: 3.14159265358979323846\nTAU :: PI * 2");
+
+        assert_eq!(errors(&consts), Vec::<String>::new());
+        assert_eq!(
+            values(&consts, BodyId::Root, 2),
+            ["3.141592653589793", "6.283185307179586"]
+        );
+    }
+
+    #[test]
+    fn eval_local_constants() {
+        let (tree, consts) = eval_source(
+            "N :: 4\nf :: (x: i32) {\n    A :: N * 2\n    b := 1\n    C :: A + b\n    D :: x\n    E :: f(1)\n}",
+        );
+        let (f, _) = tree.functions().next().unwrap();
+
+        assert_eq!(errors(&consts), Vec::<String>::new());
+        assert_eq!(
+            values(&consts, BodyId::Function(f), 5),
+            ["8", "-", "-", "-", "-"]
+        );
+    }
+
+    #[test]
+    fn report_cycles() {
+        let (_, consts) = eval_source("A :: B + 1\nB :: A\nC :: C");
+
+        assert_eq!(
+            errors(&consts),
+            [
+                "error at 16..18: cycle detected when evaluating constant `A`",
+                "error at 23..24: cycle detected when evaluating constant `C`",
+            ]
+        );
+        assert_eq!(values(&consts, BodyId::Root, 3), ["-", "-", "-"]);
+    }
+
+    #[test]
+    fn report_overflow_and_division_by_zero() {
+        let (_, consts) = eval_source(
+            "BIG :: 2147483647 + 1\nZERO :: 10 / (2 - 2)\nf :: (x: u8) { x }\nBYTE :: 300\nf(BYTE)",
+        );
+
+        assert_eq!(
+            errors(&consts),
+            [
+                "error at 7..22: constant overflows `i32`",
+                "error at 30..43: attempt to divide by zero",
+                "error at 70..74: constant overflows `u8`",
+            ]
+        );
+    }
+}
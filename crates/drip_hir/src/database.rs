@@ -1,246 +0,0 @@
-use crate::{BinaryOp, Expr, Stmt, UnaryOp};
-use drip_ast as ast;
-use drip_syntax::SyntaxKind;
-use la_arena::Arena;
-
-#[derive(Debug, PartialEq, Default)]
-pub struct Database {
-    exprs: Arena<Expr>,
-}
-
-impl Database {
-    pub(crate) fn lower_stmt(&mut self, ast: ast::Stmt) -> Option<Stmt> {
-        let result = match ast {
-            ast::Stmt::VariableDef(ast) => Stmt::VariableDef {
-                name: ast.name()?.text().into(),
-                value: self.lower_expr(ast.value()),
-            },
-            ast::Stmt::ConstDef(ast) => Stmt::ConstDef {
-                name: ast.name()?.text().into(),
-                value: self.lower_expr(ast.value()),
-            },
-            ast::Stmt::Expr(ast) => Stmt::Expr(self.lower_expr(Some(ast))),
-        };
-
-        Some(result)
-    }
-
-    pub(crate) fn lower_expr(&mut self, ast: Option<ast::Expr>) -> Expr {
-        if let Some(ast) = ast {
-            match ast {
-                ast::Expr::BinaryExpr(ast) => self.lower_binary(ast),
-                ast::Expr::UnaryExpr(ast) => self.lower_unary(ast),
-                ast::Expr::Literal(ast) => Expr::Literal { n: ast.parse() },
-                ast::Expr::RoundBracketExpr(ast) => self.lower_expr(ast.expr()),
-                ast::Expr::VariableRef(ast) => self.lower_variable_ref(ast),
-            }
-        } else {
-            Expr::Missing
-        }
-    }
-
-    fn lower_binary(&mut self, ast: ast::BinaryExpr) -> Expr {
-        let op = match ast.op().unwrap().kind() {
-            SyntaxKind::Plus => BinaryOp::Add,
-            SyntaxKind::Minus => BinaryOp::Sub,
-            SyntaxKind::Star => BinaryOp::Mul,
-            SyntaxKind::Slash => BinaryOp::Div,
-            _ => unreachable!(),
-        };
-
-        let lhs = self.lower_expr(ast.lhs());
-        let rhs = self.lower_expr(ast.rhs());
-
-        Expr::Binary {
-            op,
-            lhs: self.exprs.alloc(lhs),
-            rhs: self.exprs.alloc(rhs),
-        }
-    }
-
-    fn lower_unary(&mut self, ast: ast::UnaryExpr) -> Expr {
-        let op = match ast.op().unwrap().kind() {
-            SyntaxKind::Minus => UnaryOp::Neg,
-            _ => unreachable!(),
-        };
-
-        let expr = self.lower_expr(ast.expr());
-
-        Expr::Unary {
-            op,
-            expr: self.exprs.alloc(expr),
-        }
-    }
-
-    fn lower_variable_ref(&mut self, ast: ast::VariableRef) -> Expr {
-        Expr::VariableRef {
-            var: ast.name().unwrap().text().into(),
-        }
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use drip_parser as parser;
-
-    fn parse(input: &str) -> ast::Root {
-        ast::Root::cast(parser::parse(input).syntax()).unwrap()
-    }
-
-    fn check_stmt(input: &str, expected_hir: Stmt) {
-        let root = parse(input);
-        let ast = root.stmts().next().unwrap();
-        let hir = Database::default().lower_stmt(ast).unwrap();
-
-        assert_eq!(hir, expected_hir);
-    }
-
-    fn check_expr(input: &str, expected_hir: Expr, expected_database: Database) {
-        let root = parse(input);
-        let first_stmt = root.stmts().next().unwrap();
-        let ast = match first_stmt {
-            ast::Stmt::Expr(ast) => ast,
-            _ => unreachable!(),
-        };
-        let mut database = Database::default();
-        let hir = database.lower_expr(Some(ast));
-
-        assert_eq!(hir, expected_hir);
-        assert_eq!(database, expected_database);
-    }
-
-    #[test]
-    fn lower_variable_def() {
-        check_stmt(
-            "foo := bar",
-            Stmt::VariableDef {
-                name: "foo".into(),
-                value: Expr::VariableRef { var: "bar".into() },
-            },
-        );
-    }
-
-    #[test]
-    fn lower_const_def() {
-        check_stmt(
-            "foo :: bar",
-            Stmt::ConstDef {
-                name: "foo".into(),
-                value: Expr::VariableRef { var: "bar".into() },
-            },
-        );
-    }
-
-    // TODO: make this work
-    // #[test]
-    // fn lower_variable_def_without_name() {
-    //     let root = parse(":= 10");
-    //     let ast = root.stmts().next().unwrap();
-    //     println!("{:?}", ast);
-    //     assert!(Database::default().lower_stmt(ast).is_none());
-    // }
-
-    #[test]
-    fn lower_variable_def_without_value() {
-        check_stmt(
-            "a :=",
-            Stmt::VariableDef {
-                name: "a".into(),
-                value: Expr::Missing,
-            },
-        );
-    }
-
-    #[test]
-    fn lower_expr_stmt() {
-        check_stmt("123", Stmt::Expr(Expr::Literal { n: Some(123) }));
-    }
-
-    #[test]
-    fn lower_binary_expr() {
-        let mut exprs = Arena::new();
-        let lhs = exprs.alloc(Expr::Literal { n: Some(1) });
-        let rhs = exprs.alloc(Expr::Literal { n: Some(2) });
-
-        check_expr(
-            "1 + 2",
-            Expr::Binary {
-                lhs,
-                rhs,
-                op: BinaryOp::Add,
-            },
-            Database { exprs },
-        );
-    }
-
-    #[test]
-    fn lower_binary_expr_without_rhs() {
-        let mut exprs = Arena::new();
-        let lhs = exprs.alloc(Expr::Literal { n: Some(10) });
-        let rhs = exprs.alloc(Expr::Missing);
-
-        check_expr(
-            "10 -",
-            Expr::Binary {
-                lhs,
-                rhs,
-                op: BinaryOp::Sub,
-            },
-            Database { exprs },
-        );
-    }
-
-    #[test]
-    fn lower_literal() {
-        check_expr("999", Expr::Literal { n: Some(999) }, Database::default());
-    }
-
-    #[test]
-    fn lower_paren_expr() {
-        check_expr(
-            "((((((abc))))))",
-            Expr::VariableRef { var: "abc".into() },
-            Database::default(),
-        );
-    }
-
-    #[test]
-    fn lower_unary_expr() {
-        let mut exprs = Arena::new();
-        let ten = exprs.alloc(Expr::Literal { n: Some(10) });
-
-        check_expr(
-            "-10",
-            Expr::Unary {
-                expr: ten,
-                op: UnaryOp::Neg,
-            },
-            Database { exprs },
-        );
-    }
-
-    #[test]
-    fn lower_unary_expr_without_expr() {
-        let mut exprs = Arena::new();
-        let expr = exprs.alloc(Expr::Missing);
-
-        check_expr(
-            "-",
-            Expr::Unary {
-                expr,
-                op: UnaryOp::Neg,
-            },
-            Database { exprs },
-        );
-    }
-
-    #[test]
-    fn lower_variable_ref() {
-        check_expr(
-            "foo",
-            Expr::VariableRef { var: "foo".into() },
-            Database::default(),
-        );
-    }
-}
@@ -0,0 +1,460 @@
+//! The incremental query database.
+//!
+//! Everything the compiler knows about a program is the result of a query on
+//! a [`Database`]. The only inputs are the text of every file and which file
+//! the program starts in; everything else is derived from them on demand:
+//!
+//! ```text
+//! file_text -> parse -> lower_file -> resolutions -> inference -> const_values
+//!                                                                   -> diagnostics
+//! ```
+//!
+//! `lower_file` produces the file's item tree together with its top-level
+//! body, since both come out of the same walk over the syntax tree. The
+//! results of queries are memoized along with the queries they read. After an
+//! input changes, a result is only recomputed if something it depends on
+//! actually changed, so editing one file leaves the results for every other
+//! file alone.
+//!
+//! Queries check whether they have been cancelled before they start, which
+//! happens when an input is changed while a [`salsa::Snapshot`] of the
+//! database is computing something on another thread. A cancelled query
+//! unwinds with [`Cancelled`], which [`Cancelled::catch`] turns back into an
+//! error.
+
+use crate::const_eval::{self, ConstValues};
+use crate::flow::{self, Severity};
+use crate::infer::{self, InferenceResults};
+use crate::resolve::{self, BodyId, Resolutions};
+use crate::{exhaustiveness, method_resolution, mutability, Body, Item, ItemTree, Stmt};
+use drip_ast::AstNode;
+use drip_parser::Parse;
+use drip_syntax::{SyntaxKind, SyntaxNode};
+use std::fmt;
+use std::fmt::Formatter;
+use std::panic::{self, UnwindSafe};
+use std::sync::Arc;
+use text_size::TextRange;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FileId(pub u32);
+
+#[salsa::query_group(SourceDatabaseStorage)]
+pub trait SourceDatabase: salsa::Database {
+    #[salsa::input]
+    fn file_text(&self, file: FileId) -> Arc<str>;
+
+    /// The file the program starts in.
+    #[salsa::input]
+    fn root_file(&self) -> FileId;
+
+    fn parse(&self, file: FileId) -> Parse;
+}
+
+fn parse(db: &dyn SourceDatabase, file: FileId) -> Parse {
+    db.unwind_if_cancelled();
+    drip_parser::parse(&db.file_text(file))
+}
+
+/// The items of a file and its top-level statements.
+#[derive(Debug, PartialEq, Eq)]
+pub struct LoweredFile {
+    pub tree: ItemTree,
+    pub root: Body,
+}
+
+#[salsa::query_group(HirDatabaseStorage)]
+pub trait HirDatabase: SourceDatabase {
+    fn lower_file(&self, file: FileId) -> Arc<LoweredFile>;
+
+    fn resolutions(&self, file: FileId) -> Arc<Resolutions>;
+
+    fn inference(&self, file: FileId) -> Arc<InferenceResults>;
+
+    fn const_values(&self, file: FileId) -> Arc<ConstValues>;
+
+    /// Every error and warning in the file, in the order they appear.
+    fn diagnostics(&self, file: FileId) -> Arc<[Diagnostic]>;
+}
+
+fn lower_file(db: &dyn HirDatabase, file: FileId) -> Arc<LoweredFile> {
+    db.unwind_if_cancelled();
+    let root = drip_ast::Root::cast(db.parse(file).syntax()).unwrap();
+    let (tree, root) = crate::lower(root);
+    Arc::new(LoweredFile { tree, root })
+}
+
+fn resolutions(db: &dyn HirDatabase, file: FileId) -> Arc<Resolutions> {
+    db.unwind_if_cancelled();
+    let lowered = db.lower_file(file);
+    Arc::new(resolve::resolve(&lowered.tree, &lowered.root))
+}
+
+fn inference(db: &dyn HirDatabase, file: FileId) -> Arc<InferenceResults> {
+    db.unwind_if_cancelled();
+    let lowered = db.lower_file(file);
+    let resolutions = db.resolutions(file);
+    Arc::new(infer::infer(&lowered.tree, &lowered.root, &resolutions))
+}
+
+fn const_values(db: &dyn HirDatabase, file: FileId) -> Arc<ConstValues> {
+    db.unwind_if_cancelled();
+    let lowered = db.lower_file(file);
+    let resolutions = db.resolutions(file);
+    let inference = db.inference(file);
+    Arc::new(const_eval::eval_consts(
+        &lowered.tree,
+        &lowered.root,
+        &resolutions,
+        &inference,
+    ))
+}
+
+fn diagnostics(db: &dyn HirDatabase, file: FileId) -> Arc<[Diagnostic]> {
+    db.unwind_if_cancelled();
+    let parse = db.parse(file);
+    let lowered = db.lower_file(file);
+    let resolutions = db.resolutions(file);
+    let inference = db.inference(file);
+    let consts = db.const_values(file);
+    let (tree, root) = (&lowered.tree, &lowered.root);
+
+    let mut diagnostics = Vec::new();
+    let mut error = |range, message: String| {
+        diagnostics.push(Diagnostic {
+            severity: Severity::Error,
+            range,
+            message,
+            related: Vec::new(),
+        })
+    };
+
+    for e in parse.errors() {
+        error(e.range, e.message());
+    }
+    for e in drip_ast::validation::validate(&parse.syntax()) {
+        error(e.range(), e.kind().to_string());
+    }
+    for e in resolutions.errors() {
+        let range = body(tree, root, e.body())
+            .arenas
+            .source_map()
+            .expr_syntax(e.expr())
+            .map_or_else(TextRange::default, |source| source.range());
+        error(range, e.to_string());
+    }
+    for e in method_resolution::check_impls(tree) {
+        error(
+            item_range(tree, root, Item::Impl(e.impl_id())),
+            e.to_string(),
+        );
+    }
+    for e in inference.errors() {
+        error(e.range(), e.kind().to_string());
+    }
+    for e in consts.errors() {
+        error(e.range(), e.kind().to_string());
+    }
+    for e in mutability::check_mutability(tree, root, &resolutions, &inference) {
+        let definition = without_trailing_trivia(&parse.syntax(), e.definition());
+        diagnostics.push(Diagnostic {
+            severity: Severity::Error,
+            range: e.range(),
+            message: e.kind().to_string(),
+            related: vec![(definition, "defined with `::` here".to_string())],
+        });
+    }
+
+    for d in flow::check_flow(tree, root, &resolutions, &consts) {
+        diagnostics.push(Diagnostic {
+            severity: d.severity(),
+            range: d.range(),
+            message: d.kind().to_string(),
+            related: Vec::new(),
+        });
+    }
+    for d in exhaustiveness::check_matches(tree, root, &resolutions, &inference, &consts) {
+        diagnostics.push(Diagnostic {
+            severity: d.severity(),
+            range: d.range(),
+            message: d.kind().to_string(),
+            related: Vec::new(),
+        });
+    }
+
+    diagnostics.sort_by_key(|d| d.range.start());
+    diagnostics.into()
+}
+
+fn body<'a>(tree: &'a ItemTree, root: &'a Body, id: BodyId) -> &'a Body {
+    match id {
+        BodyId::Root => root,
+        BodyId::Function(function) => &tree[function].body,
+    }
+}
+
+/// `range` without the whitespace and comments at the end of the syntax it
+/// covers, which statements take along up to the next line.
+fn without_trailing_trivia(root: &SyntaxNode, range: TextRange) -> TextRange {
+    let end = root
+        .covering_element(range)
+        .into_node()
+        .and_then(|node| {
+            node.descendants_with_tokens()
+                .filter_map(|element| element.into_token())
+                .filter(|token| {
+                    !matches!(token.kind(), SyntaxKind::Whitespace | SyntaxKind::Comment)
+                })
+                .last()
+        })
+        .map_or(range.end(), |token| token.text_range().end());
+    TextRange::new(range.start(), end.min(range.end()))
+}
+
+/// The range of the statement that defines `item`.
+fn item_range(tree: &ItemTree, root: &Body, item: Item) -> TextRange {
+    let bodies = std::iter::once(root).chain(tree.functions().map(|(_, f)| &f.body));
+    for body in bodies {
+        let stmt = body.stmts.iter().position(|stmt| *stmt == Stmt::Item(item));
+        if let Some(stmt) = stmt {
+            return body
+                .arenas
+                .source_map()
+                .stmt_syntax(stmt)
+                .map_or_else(TextRange::default, |ptr| ptr.syntax_node_ptr().range());
+        }
+    }
+
+    TextRange::default()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub range: TextRange,
+    pub message: String,
+    /// Other places the message is about, each with a note saying how.
+    pub related: Vec<(TextRange, String)>,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(
+            f,
+            "{} at {}..{}: {}",
+            severity,
+            u32::from(self.range.start()),
+            u32::from(self.range.end()),
+            self.message,
+        )?;
+        for (range, note) in &self.related {
+            write!(
+                f,
+                "\n    note at {}..{}: {}",
+                u32::from(range.start()),
+                u32::from(range.end()),
+                note,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The payload a query unwinds with when an input changed while it ran.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl Cancelled {
+    fn throw() -> ! {
+        // `resume_unwind` skips the panic hook, so nothing is printed
+        panic::resume_unwind(Box::new(Cancelled))
+    }
+
+    /// Runs `f`, returning `Err` if a query it ran was cancelled. Other
+    /// panics keep unwinding.
+    pub fn catch<T>(f: impl FnOnce() -> T + UnwindSafe) -> Result<T, Cancelled> {
+        panic::catch_unwind(f).map_err(|payload| match payload.downcast::<Cancelled>() {
+            Ok(cancelled) => *cancelled,
+            Err(payload) => panic::resume_unwind(payload),
+        })
+    }
+}
+
+pub trait CheckCancelled {
+    /// Unwinds with [`Cancelled`] if an input has been changed since the
+    /// current revision started.
+    fn unwind_if_cancelled(&self);
+}
+
+impl<T: salsa::Database + ?Sized> CheckCancelled for T {
+    fn unwind_if_cancelled(&self) {
+        if self.salsa_runtime().is_current_revision_canceled() {
+            Cancelled::throw();
+        }
+    }
+}
+
+#[salsa::database(SourceDatabaseStorage, HirDatabaseStorage)]
+#[derive(Default)]
+pub struct Database {
+    storage: salsa::Storage<Self>,
+}
+
+impl salsa::Database for Database {}
+
+impl salsa::ParallelDatabase for Database {
+    fn snapshot(&self) -> salsa::Snapshot<Self> {
+        salsa::Snapshot::new(Self {
+            storage: self.storage.snapshot(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use salsa::ParallelDatabase;
+    use std::sync::mpsc::{self, Sender};
+    use std::sync::Mutex;
+    use std::thread;
+
+    /// A [`Database`] that records every query it runs.
+    #[salsa::database(SourceDatabaseStorage, HirDatabaseStorage)]
+    #[derive(Default)]
+    struct TestDatabase {
+        storage: salsa::Storage<Self>,
+        executed: Arc<Mutex<Vec<String>>>,
+        /// If set, the next query to run says so here and then waits until
+        /// an input change cancels it.
+        pause: Arc<Mutex<Option<Sender<()>>>>,
+    }
+
+    impl salsa::Database for TestDatabase {
+        fn salsa_event(&self, event: salsa::Event) {
+            if let salsa::EventKind::WillExecute { database_key } = event.kind {
+                let query = format!("{:?}", database_key.debug(self));
+                self.executed.lock().unwrap().push(query);
+
+                let paused = self.pause.lock().unwrap().take();
+                if let Some(paused) = paused {
+                    paused.send(()).unwrap();
+                    while !self.salsa_runtime().is_current_revision_canceled() {
+                        thread::yield_now();
+                    }
+                }
+            }
+        }
+    }
+
+    impl salsa::ParallelDatabase for TestDatabase {
+        fn snapshot(&self) -> salsa::Snapshot<Self> {
+            salsa::Snapshot::new(Self {
+                storage: self.storage.snapshot(),
+                executed: Arc::clone(&self.executed),
+                pause: Arc::clone(&self.pause),
+            })
+        }
+    }
+
+    impl TestDatabase {
+        fn take_executed(&self) -> Vec<String> {
+            std::mem::take(&mut self.executed.lock().unwrap())
+        }
+    }
+
+    fn diagnostics(db: &dyn HirDatabase, file: FileId) -> Vec<String> {
+        db.diagnostics(file)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn recompute_only_what_an_edit_affects() {
+        let mut db = TestDatabase::default();
+        let (a, b) = (FileId(0), FileId(1));
+        db.set_file_text(a, "x := 1\nx + 1".into());
+        db.set_file_text(b, "y :: 2 // two".into());
+
+        db.diagnostics(a);
+        db.diagnostics(b);
+        db.take_executed();
+
+        db.set_file_text(b, "y :: 3 // two".into());
+        db.diagnostics(a);
+        db.diagnostics(b);
+        assert_eq!(
+            db.take_executed(),
+            [
+                "parse(FileId(1))",
+                "diagnostics(FileId(1))",
+                "lower_file(FileId(1))",
+                "resolutions(FileId(1))",
+                "inference(FileId(1))",
+                "const_values(FileId(1))",
+            ]
+        );
+
+        // editing the comment leaves the lowered file as it was, so nothing
+        // that only depends on it runs again
+        db.set_file_text(b, "y :: 3 // hi!".into());
+        db.diagnostics(b);
+        assert_eq!(
+            db.take_executed(),
+            [
+                "parse(FileId(1))",
+                "diagnostics(FileId(1))",
+                "lower_file(FileId(1))"
+            ]
+        );
+    }
+
+    #[test]
+    fn collect_diagnostics_from_every_pass() {
+        let mut db = Database::default();
+        let file = FileId(0);
+        db.set_file_text(
+            file,
+            "a :: 1\na = 2\nb := c\nd :: 1 + \"one\"\nm := match true { true => 1 }\nf :: (x: i32) { 1 }".into(),
+        );
+
+        assert_eq!(
+            diagnostics(&db, file),
+            [
+                "error at 7..9: cannot assign to immutable binding `a`
+    note at 0..6: defined with `::` here",
+                "error at 18..20: cannot find `c` in this scope",
+                "error at 29..35: expected `i32`, found `string`",
+                "error at 40..65: non-exhaustive patterns: `false` not covered",
+                "warning at 71..77: unused parameter `x`",
+            ]
+        );
+    }
+
+    #[test]
+    fn cancel_queries_when_an_input_changes() {
+        let mut db = TestDatabase::default();
+        let file = FileId(0);
+        db.set_file_text(file, "a :: 1".into());
+
+        let (paused, wait) = mpsc::channel();
+        *db.pause.lock().unwrap() = Some(paused);
+        let snapshot = db.snapshot();
+        let handle = thread::spawn(move || Cancelled::catch(|| snapshot.diagnostics(file)));
+
+        // setting the input while the snapshot is in the middle of a query
+        // cancels it, and waits for the snapshot to be dropped
+        wait.recv().unwrap();
+        db.set_file_text(file, "a :: 1\na = 2".into());
+        assert!(matches!(handle.join().unwrap(), Err(Cancelled)));
+        assert_eq!(
+            diagnostics(&db, file),
+            ["error at 7..9: cannot assign to immutable binding `a`
+    note at 0..6: defined with `::` here"]
+        );
+    }
+}
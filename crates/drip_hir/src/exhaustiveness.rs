@@ -0,0 +1,765 @@
+//! Exhaustiveness and reachability checking of `match` arms.
+//!
+//! Patterns are lowered into constructors applied to sub-patterns, and the
+//! arms are checked with the usefulness algorithm from Maranget's "Warnings
+//! for pattern matching": an arm is unreachable if none of its alternatives
+//! matches a value the arms above it don't, and a `match` is exhaustive if a
+//! wildcard after the last arm would be unreachable. When it isn't, the
+//! algorithm also builds an example of a value no arm matches.
+//!
+//! Integers and floats are handled as ranges. An integer type covers the
+//! values between its bounds, and floats are mapped to integer keys that
+//! sort the same way, with every value from negative to positive infinity in
+//! between. NaN is never matched by a pattern. Booleans have two
+//! constructors, tuples and structs have one, and strings and all other
+//! types have too many to list, so matching them exhaustively takes a
+//! wildcard or a binding.
+//!
+//! Patterns have to be constants: literals, ranges of constant bounds, and
+//! names of `::` constants that are evaluated at compile time.
+
+use crate::const_eval::{ConstValue, ConstValues};
+use crate::flow::Severity;
+use crate::infer::{self, InferenceResults, Ty};
+use crate::method_resolution;
+use crate::resolve::{BodyId, Resolution, Resolutions};
+use crate::{Body, Expr, ExprIdx, ItemTree, MatchArm, Pat, PatIdx, TypeRef};
+use smol_str::SmolStr;
+use std::fmt;
+use std::fmt::Formatter;
+use text_size::TextRange;
+
+#[derive(Debug, PartialEq)]
+pub struct MatchDiagnostic {
+    kind: MatchDiagnosticKind,
+    body: BodyId,
+    range: TextRange,
+}
+
+impl MatchDiagnostic {
+    pub fn kind(&self) -> &MatchDiagnosticKind {
+        &self.kind
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.kind.severity()
+    }
+
+    pub fn body(&self) -> BodyId {
+        self.body
+    }
+
+    pub fn range(&self) -> TextRange {
+        self.range
+    }
+}
+
+impl fmt::Display for MatchDiagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity() {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(
+            f,
+            "{} at {}..{}: {}",
+            severity,
+            u32::from(self.range.start()),
+            u32::from(self.range.end()),
+            self.kind,
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MatchDiagnosticKind {
+    /// `witness` is a value none of the arms match.
+    NonExhaustive {
+        witness: String,
+    },
+    UnreachableArm,
+    NonConstantPattern,
+}
+
+impl MatchDiagnosticKind {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::UnreachableArm => Severity::Warning,
+            Self::NonExhaustive { .. } | Self::NonConstantPattern => Severity::Error,
+        }
+    }
+}
+
+impl fmt::Display for MatchDiagnosticKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NonExhaustive { witness } => {
+                write!(f, "non-exhaustive patterns: `{}` not covered", witness)
+            }
+            Self::UnreachableArm => write!(f, "unreachable match arm"),
+            Self::NonConstantPattern => write!(f, "pattern is not a constant"),
+        }
+    }
+}
+
+/// Checks every `match` in `root` and in the bodies of all items in `tree`.
+pub fn check_matches(
+    tree: &ItemTree,
+    root: &Body,
+    resolutions: &Resolutions,
+    inference: &InferenceResults,
+    consts: &ConstValues,
+) -> Vec<MatchDiagnostic> {
+    let bodies = std::iter::once((BodyId::Root, root)).chain(
+        tree.functions()
+            .map(|(id, function)| (BodyId::Function(id), &function.body)),
+    );
+
+    let mut diagnostics = Vec::new();
+    for (id, body) in bodies {
+        let mut checker = Checker {
+            tree,
+            id,
+            body,
+            resolutions,
+            inference,
+            consts,
+            diagnostics: Vec::new(),
+        };
+        for (idx, expr) in body.arenas.exprs.iter() {
+            if let Expr::Match { scrutinee, arms } = expr {
+                checker.check_match(idx, *scrutinee, arms);
+            }
+        }
+
+        checker.diagnostics.sort_by_key(|d| d.range.start());
+        diagnostics.extend(checker.diagnostics);
+    }
+
+    diagnostics
+}
+
+/// A pattern as a constructor applied to sub-patterns.
+#[derive(Debug, Clone, PartialEq)]
+enum DPat {
+    Wild,
+    Ctor(Ctor, Vec<DPat>),
+    /// A pattern whose value isn't known, which is treated as matching
+    /// nothing.
+    Opaque,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Ctor {
+    Bool(bool),
+    /// The integers, or float keys, from `.0` to `.1`, both included.
+    Range(i128, i128),
+    Str(SmolStr),
+    /// The only constructor of a tuple or struct.
+    Single,
+}
+
+/// A value that shows how a `match` isn't exhaustive.
+#[derive(Debug, Clone, PartialEq)]
+enum Witness {
+    Wild,
+    Int(i128),
+    Float(f64),
+    Bool(bool),
+    Str(SmolStr),
+    Tuple(Vec<Witness>),
+    Struct {
+        name: SmolStr,
+        fields: Vec<(SmolStr, Witness)>,
+    },
+}
+
+impl fmt::Display for Witness {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Wild => write!(f, "_"),
+            Self::Int(n) => write!(f, "{}", n),
+            Self::Float(n) => write!(f, "{:?}", n),
+            Self::Bool(value) => write!(f, "{}", value),
+            Self::Str(value) => write!(f, "\"{}\"", value),
+            Self::Tuple(elems) if elems.len() == 1 => write!(f, "({},)", elems[0]),
+            Self::Tuple(elems) => {
+                let elems: Vec<_> = elems.iter().map(ToString::to_string).collect();
+                write!(f, "({})", elems.join(", "))
+            }
+            Self::Struct { name, fields } if fields.is_empty() => write!(f, "{} {{}}", name),
+            Self::Struct { name, fields } => {
+                let fields: Vec<_> = fields
+                    .iter()
+                    .map(|(name, value)| format!("{}: {}", name, value))
+                    .collect();
+                write!(f, "{} {{ {} }}", name, fields.join(", "))
+            }
+        }
+    }
+}
+
+/// The values of a type, as far as patterns can tell them apart.
+enum Domain {
+    Bool,
+    Int(i128, i128),
+    Float,
+    Single,
+    /// Too many values to list, like strings.
+    Infinite,
+}
+
+impl Domain {
+    fn of(ty: &Ty) -> Self {
+        match ty {
+            Ty::Bool => Self::Bool,
+            Ty::Int(ty) => {
+                let (min, max) = ty.bounds();
+                Self::Int(min, max)
+            }
+            Ty::Float(_) => Self::Float,
+            Ty::Tuple(_) | Ty::Struct { .. } => Self::Single,
+            _ => Self::Infinite,
+        }
+    }
+
+    fn range(&self) -> Option<(i128, i128)> {
+        match *self {
+            Self::Int(min, max) => Some((min, max)),
+            Self::Float => Some((float_key(f64::NEG_INFINITY), float_key(f64::INFINITY))),
+            _ => None,
+        }
+    }
+}
+
+/// An integer that orders floats the way they compare, with `-0.0` right
+/// below `0.0`.
+fn float_key(n: f64) -> i128 {
+    let bits = n.to_bits() as i64;
+    i128::from(bits ^ (((bits >> 63) as u64) >> 1) as i64)
+}
+
+fn key_to_float(key: i128) -> f64 {
+    let key = key as i64;
+    f64::from_bits((key ^ (((key >> 63) as u64) >> 1) as i64) as u64)
+}
+
+struct Checker<'a> {
+    tree: &'a ItemTree,
+    id: BodyId,
+    body: &'a Body,
+    resolutions: &'a Resolutions,
+    inference: &'a InferenceResults,
+    consts: &'a ConstValues,
+    diagnostics: Vec<MatchDiagnostic>,
+}
+
+impl Checker<'_> {
+    fn check_match(&mut self, idx: ExprIdx, scrutinee: ExprIdx, arms: &[MatchArm]) {
+        let ty = self.inference.body(self.id)[scrutinee].clone();
+        // the type error is reported already
+        if ty == Ty::Unknown {
+            return;
+        }
+
+        let mut rows: Vec<Vec<DPat>> = Vec::new();
+        for arm in arms {
+            let mut reachable = false;
+            for pat in &arm.pats {
+                let row = vec![self.lower_pat(*pat, &ty)];
+                reachable |= self
+                    .is_useful(&rows, &row, std::slice::from_ref(&ty))
+                    .is_some();
+                rows.push(row);
+            }
+
+            if let (Some(first), Some(last), false) = (arm.pats.first(), arm.pats.last(), reachable)
+            {
+                let range = self.pat_range(*first).cover(self.pat_range(*last));
+                self.report(MatchDiagnosticKind::UnreachableArm, range);
+            }
+        }
+
+        if let Some(witness) = self.is_useful(&rows, &[DPat::Wild], &[ty]) {
+            let witness = witness[0].to_string();
+            let range = self.expr_range(idx);
+            self.report(MatchDiagnosticKind::NonExhaustive { witness }, range);
+        }
+    }
+
+    fn lower_pat(&mut self, pat: PatIdx, ty: &Ty) -> DPat {
+        match &self.body.arenas[pat] {
+            Pat::Missing | Pat::Wildcard => DPat::Wild,
+            Pat::Ident { .. } => {
+                let value = match self.resolutions.pat(self.id, pat) {
+                    Some(Resolution::Global { stmt }) => self.consts.get(BodyId::Root, stmt),
+                    Some(Resolution::Local { body, stmt }) => self.consts.get(body, stmt),
+                    // a binding
+                    _ => return DPat::Wild,
+                };
+                match value {
+                    Some(value) => lower_value(value, ty),
+                    None => self.non_constant(pat),
+                }
+            }
+            Pat::Literal { expr } => match self.consts.expr(self.id, *expr) {
+                Some(value) => lower_value(value, ty),
+                None => self.non_constant(pat),
+            },
+            Pat::Range { start, end } => {
+                let start = self.consts.expr(self.id, *start);
+                let end = self.consts.expr(self.id, *end);
+                let (start, end) = match (start, end) {
+                    (Some(start), Some(end)) => (start, end),
+                    _ => return self.non_constant(pat),
+                };
+                let range = match (start, end, Domain::of(ty)) {
+                    (ConstValue::Int(start), ConstValue::Int(end), Domain::Int(..)) => {
+                        Some((*start, end - 1))
+                    }
+                    (ConstValue::Float(start), ConstValue::Float(end), Domain::Float)
+                        if !start.is_nan() && !end.is_nan() =>
+                    {
+                        // `0.0..` includes `-0.0`, and `..0.0` leaves it out
+                        let start = if *start == 0.0 { -0.0 } else { *start };
+                        let end = if *end == 0.0 { -0.0 } else { *end };
+                        Some((float_key(start), float_key(end) - 1))
+                    }
+                    _ => None,
+                };
+                range.map_or(DPat::Opaque, |(start, end)| range_pat(start, end, ty))
+            }
+            Pat::Tuple { pats } => match ty {
+                Ty::Tuple(tys) if tys.len() == pats.len() => {
+                    let pats = pats
+                        .iter()
+                        .zip(tys)
+                        .map(|(pat, ty)| self.lower_pat(*pat, ty))
+                        .collect();
+                    DPat::Ctor(Ctor::Single, pats)
+                }
+                _ => DPat::Opaque,
+            },
+            Pat::Struct { name, fields } => match ty {
+                Ty::Struct { id, name: ty_name } if ty_name == name => {
+                    let pats = method_resolution::all_fields(self.tree, *id)
+                        .iter()
+                        .map(|field| {
+                            let field_ty = field_ty(self.tree, ty, &field.ty);
+                            fields
+                                .iter()
+                                .find(|(name, _)| *name == field.name)
+                                .map_or(DPat::Wild, |(_, pat)| self.lower_pat(*pat, &field_ty))
+                        })
+                        .collect();
+                    DPat::Ctor(Ctor::Single, pats)
+                }
+                _ => DPat::Opaque,
+            },
+        }
+    }
+
+    fn non_constant(&mut self, pat: PatIdx) -> DPat {
+        let range = self.pat_range(pat);
+        self.report(MatchDiagnosticKind::NonConstantPattern, range);
+        DPat::Opaque
+    }
+
+    /// Whether the row `v` of patterns of types `tys` matches a value none of
+    /// `rows` do. If it does, returns such a value, which is only fully known
+    /// when `v` is made of wildcards.
+    fn is_useful(&self, rows: &[Vec<DPat>], v: &[DPat], tys: &[Ty]) -> Option<Vec<Witness>> {
+        let (head, ty) = match (v.first(), tys.first()) {
+            (Some(head), Some(ty)) => (head, ty),
+            _ => return rows.is_empty().then(Vec::new),
+        };
+        let column: Vec<&Ctor> = rows
+            .iter()
+            .filter_map(|row| match &row[0] {
+                DPat::Ctor(ctor, _) => Some(ctor),
+                DPat::Wild | DPat::Opaque => None,
+            })
+            .collect();
+
+        match head {
+            DPat::Opaque => Some(vec![Witness::Wild; v.len()]),
+            DPat::Ctor(ctor, _) => split(ctor, &column)
+                .into_iter()
+                .find_map(|ctor| self.is_useful_ctor(rows, v, tys, &ctor)),
+            DPat::Wild => {
+                let domain = Domain::of(ty);
+                let ctors = match (&domain, column.is_empty()) {
+                    (_, true) => None,
+                    (Domain::Bool, false) => Some(vec![Ctor::Bool(false), Ctor::Bool(true)]),
+                    (Domain::Single, false) => Some(vec![Ctor::Single]),
+                    (Domain::Int(..) | Domain::Float, false) => {
+                        let (min, max) = domain.range().unwrap();
+                        let mut ctors = split(&Ctor::Range(min, max), &column);
+                        // so witnesses are built from values near zero
+                        ctors.sort_by_key(|ctor| match *ctor {
+                            Ctor::Range(start, end) => {
+                                let n = closest_to_zero(start, end);
+                                (n.abs(), n < 0)
+                            }
+                            _ => (0, false),
+                        });
+                        Some(ctors)
+                    }
+                    (Domain::Infinite, false) => None,
+                };
+
+                // with every constructor in the column, the wildcard is
+                // useful if it is for one of them
+                if let Some(ctors) = &ctors {
+                    if ctors
+                        .iter()
+                        .all(|ctor| column.iter().any(|c| covers(c, ctor)))
+                    {
+                        return ctors
+                            .iter()
+                            .find_map(|ctor| self.is_useful_ctor(rows, v, tys, ctor));
+                    }
+                }
+
+                // otherwise only the rows starting with a wildcard can match
+                // the missing constructors
+                let rest: Vec<_> = rows
+                    .iter()
+                    .filter(|row| row[0] == DPat::Wild)
+                    .map(|row| row[1..].to_vec())
+                    .collect();
+                let mut witness = self.is_useful(&rest, &v[1..], &tys[1..])?;
+                let head = match ctors {
+                    Some(ctors) => missing(&domain, ty, &column, &ctors),
+                    None if column.is_empty() => Witness::Wild,
+                    None => missing(&domain, ty, &column, &[]),
+                };
+                witness.insert(0, head);
+                Some(witness)
+            }
+        }
+    }
+
+    /// [`Self::is_useful`] for the rows and `v` specialized to values built
+    /// with `ctor`.
+    fn is_useful_ctor(
+        &self,
+        rows: &[Vec<DPat>],
+        v: &[DPat],
+        tys: &[Ty],
+        ctor: &Ctor,
+    ) -> Option<Vec<Witness>> {
+        let ty = &tys[0];
+        let sub_tys = self.sub_tys(ty, ctor);
+        let arity = sub_tys.len();
+        let specialized: Vec<_> = rows
+            .iter()
+            .filter_map(|row| specialize(row, ctor, arity))
+            .collect();
+        let v = specialize(v, ctor, arity)?;
+        let sub_tys: Vec<_> = sub_tys
+            .into_iter()
+            .chain(tys[1..].iter().cloned())
+            .collect();
+
+        let mut witness = self.is_useful(&specialized, &v, &sub_tys)?;
+        let fields: Vec<_> = witness.drain(..arity).collect();
+        witness.insert(0, self.build_witness(ty, ctor, fields));
+        Some(witness)
+    }
+
+    fn build_witness(&self, ty: &Ty, ctor: &Ctor, fields: Vec<Witness>) -> Witness {
+        match ctor {
+            Ctor::Bool(value) => Witness::Bool(*value),
+            Ctor::Str(value) => Witness::Str(value.clone()),
+            Ctor::Range(start, end) => range_witness(ty, *start, *end),
+            Ctor::Single => match ty {
+                Ty::Struct { id, name } => Witness::Struct {
+                    name: name.clone(),
+                    fields: method_resolution::all_fields(self.tree, *id)
+                        .iter()
+                        .map(|field| field.name.clone())
+                        .zip(fields)
+                        .collect(),
+                },
+                _ => Witness::Tuple(fields),
+            },
+        }
+    }
+
+    fn sub_tys(&self, ty: &Ty, ctor: &Ctor) -> Vec<Ty> {
+        match (ctor, ty) {
+            (Ctor::Single, Ty::Tuple(tys)) => tys.clone(),
+            (Ctor::Single, Ty::Struct { id, .. }) => method_resolution::all_fields(self.tree, *id)
+                .iter()
+                .map(|field| field_ty(self.tree, ty, &field.ty))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn pat_range(&self, pat: PatIdx) -> TextRange {
+        self.body
+            .arenas
+            .source_map()
+            .pat_syntax(pat)
+            .map_or_else(TextRange::default, |ptr| ptr.range())
+    }
+
+    fn expr_range(&self, expr: ExprIdx) -> TextRange {
+        self.body
+            .arenas
+            .source_map()
+            .expr_syntax(expr)
+            .map_or_else(TextRange::default, |source| source.range())
+    }
+
+    fn report(&mut self, kind: MatchDiagnosticKind, range: TextRange) {
+        self.diagnostics.push(MatchDiagnostic {
+            kind,
+            body: self.id,
+            range,
+        });
+    }
+}
+
+fn field_ty(tree: &ItemTree, struct_ty: &Ty, ty: &TypeRef) -> Ty {
+    match ty {
+        TypeRef::SelfType => struct_ty.clone(),
+        ty => infer::lower_type_ref(tree, ty),
+    }
+}
+
+fn lower_value(value: &ConstValue, ty: &Ty) -> DPat {
+    match (value, ty) {
+        (ConstValue::Int(n), Ty::Int(_)) => range_pat(*n, *n, ty),
+        (ConstValue::Float(n), Ty::Float(_)) if !n.is_nan() => {
+            // `0.0` and `-0.0` are equal, so either matches both
+            if *n == 0.0 {
+                range_pat(float_key(-0.0), float_key(0.0), ty)
+            } else {
+                range_pat(float_key(*n), float_key(*n), ty)
+            }
+        }
+        (ConstValue::Bool(value), Ty::Bool) => DPat::Ctor(Ctor::Bool(*value), Vec::new()),
+        (ConstValue::String(value), Ty::String) => DPat::Ctor(Ctor::Str(value.clone()), Vec::new()),
+        (ConstValue::Tuple(values), Ty::Tuple(tys)) if values.len() == tys.len() => DPat::Ctor(
+            Ctor::Single,
+            values
+                .iter()
+                .zip(tys)
+                .map(|(value, ty)| lower_value(value, ty))
+                .collect(),
+        ),
+        _ => DPat::Opaque,
+    }
+}
+
+/// The values from `start` to `end` that `ty` has, or a pattern that
+/// matches nothing if there are none.
+fn range_pat(start: i128, end: i128, ty: &Ty) -> DPat {
+    let (min, max) = match Domain::of(ty).range() {
+        Some(range) => range,
+        None => return DPat::Opaque,
+    };
+    let (start, end) = (start.max(min), end.min(max));
+    if start > end {
+        return DPat::Opaque;
+    }
+
+    DPat::Ctor(Ctor::Range(start, end), Vec::new())
+}
+
+/// Splits a range constructor into pieces that each lie either completely
+/// inside or completely outside every range in `column`. Other constructors
+/// are returned as they are.
+fn split(ctor: &Ctor, column: &[&Ctor]) -> Vec<Ctor> {
+    let (start, end) = match *ctor {
+        Ctor::Range(start, end) => (start, end),
+        _ => return vec![ctor.clone()],
+    };
+
+    let mut cuts: Vec<i128> = column
+        .iter()
+        .filter_map(|ctor| match **ctor {
+            Ctor::Range(start, end) => Some([start, end + 1]),
+            _ => None,
+        })
+        .flatten()
+        .filter(|cut| *cut > start && *cut <= end)
+        .collect();
+    cuts.sort_unstable();
+    cuts.dedup();
+
+    let mut pieces = Vec::new();
+    let mut piece_start = start;
+    for cut in cuts {
+        pieces.push(Ctor::Range(piece_start, cut - 1));
+        piece_start = cut;
+    }
+    pieces.push(Ctor::Range(piece_start, end));
+
+    pieces
+}
+
+/// Whether every value built with `inner` is built with `outer` too.
+fn covers(outer: &Ctor, inner: &Ctor) -> bool {
+    match (outer, inner) {
+        (Ctor::Range(start, end), Ctor::Range(inner_start, inner_end)) => {
+            start <= inner_start && inner_end <= end
+        }
+        (outer, inner) => outer == inner,
+    }
+}
+
+/// The rest of `row` after its first pattern, with the sub-patterns of that
+/// pattern in front, if the pattern matches values built with `ctor`.
+fn specialize(row: &[DPat], ctor: &Ctor, arity: usize) -> Option<Vec<DPat>> {
+    let mut specialized = match &row[0] {
+        DPat::Wild => vec![DPat::Wild; arity],
+        DPat::Ctor(head, pats) if covers(head, ctor) => pats.clone(),
+        DPat::Ctor(..) | DPat::Opaque => return None,
+    };
+    specialized.extend_from_slice(&row[1..]);
+
+    Some(specialized)
+}
+
+/// A value of `ty` that none of the constructors in `column` build, which
+/// `ctors` lists all constructors of if there aren't too many.
+fn missing(domain: &Domain, ty: &Ty, column: &[&Ctor], ctors: &[Ctor]) -> Witness {
+    let uncovered = |ctor: &&Ctor| !column.iter().any(|c| covers(c, ctor));
+
+    match domain {
+        Domain::Bool => match ctors.iter().find(uncovered) {
+            Some(Ctor::Bool(value)) => Witness::Bool(*value),
+            _ => Witness::Wild,
+        },
+        Domain::Int(..) | Domain::Float => ctors
+            .iter()
+            .filter(uncovered)
+            .filter_map(|ctor| match *ctor {
+                Ctor::Range(start, end) => Some(closest_to_zero(start, end)),
+                _ => None,
+            })
+            .min_by_key(|n| n.abs())
+            .map_or(Witness::Wild, |n| range_witness(ty, n, n)),
+        Domain::Infinite if *ty == Ty::String => std::iter::once(String::new())
+            .chain(('a'..='z').map(String::from))
+            .find(|s| !column.contains(&&Ctor::Str(s.into())))
+            .map_or(Witness::Wild, |s| Witness::Str(s.into())),
+        Domain::Single | Domain::Infinite => Witness::Wild,
+    }
+}
+
+fn closest_to_zero(start: i128, end: i128) -> i128 {
+    if start > 0 {
+        start
+    } else if end < 0 {
+        end
+    } else {
+        0
+    }
+}
+
+/// The value of the range from `start` to `end` that is closest to zero.
+fn range_witness(ty: &Ty, start: i128, end: i128) -> Witness {
+    let n = closest_to_zero(start, end);
+    match ty {
+        Ty::Float(_) => Witness::Float(key_to_float(n)),
+        _ => Witness::Int(n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::const_eval::eval_consts;
+    use crate::infer::infer;
+    use crate::resolve::resolve;
+    use drip_ast::AstNode;
+
+    fn check(input: &str) -> Vec<String> {
+        let root = drip_ast::Root::cast(drip_parser::parse(input).syntax()).unwrap();
+        let (tree, body) = crate::lower(root);
+        let resolutions = resolve(&tree, &body);
+        let inference = infer(&tree, &body, &resolutions);
+        let consts = eval_consts(&tree, &body, &resolutions, &inference);
+
+        check_matches(&tree, &body, &resolutions, &inference, &consts)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn check_float_ranges_and_constants() {
+        let input = "PI :: 3.14159\nTAU :: PI * 2.0\nx := 5.0\nmatch x {\n    PI, TAU => 1\n    PI..TAU => 2\n    PI..TAU + 1.0 => 3\n}";
+        assert_eq!(
+            check(input),
+            ["error at 39..107: non-exhaustive patterns: `0.0` not covered"]
+        );
+
+        let input = "PI :: 3.14159\nx := 5.0\nmatch x {\n    0.0..PI => 1\n    PI => 2\n    _ => 3\n    1.0 => 4\n}";
+        assert_eq!(check(input), ["warning at 77..81: unreachable match arm"]);
+    }
+
+    #[test]
+    fn check_ranges_between_walkthrough_constants() {
+        let input = "PI :: 3.14159265358979323846\nTAU :: PI * 2\nx := 7.0\nmatch x {\n    PI => 1\n    PI, TAU => 2\n    PI..TAU => 3\n    PI..TAU + 1 => 4\n}";
+        assert_eq!(
+            check(input),
+            ["error at 52..130: non-exhaustive patterns: `0.0` not covered"]
+        );
+
+        let input = "PI :: 3.14159265358979323846\nTAU :: PI * 2\nx := 7.0\nmatch x {\n    PI..TAU + 1 => 1\n    TAU => 2\n    _ => 3\n}";
+        assert_eq!(check(input), ["warning at 87..91: unreachable match arm"]);
+    }
+
+    #[test]
+    fn check_integer_ranges() {
+        assert_eq!(
+            check(
+                "f :: (x: u8) {\n    match x {\n        0..10 => 1\n        10..255 => 2\n    }\n}"
+            ),
+            ["error at 19..75: non-exhaustive patterns: `255` not covered"]
+        );
+        assert_eq!(
+            check("x := -4\nmatch x {\n    -10..0, 0 => 1\n    n => n\n}"),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn check_bools_and_tuples() {
+        assert_eq!(
+            check("t := (true, 1)\nmatch t {\n    (true, _) => 1\n    (false, 0) => 2\n}"),
+            ["error at 15..65: non-exhaustive patterns: `(false, 1)` not covered"]
+        );
+        assert_eq!(
+            check("b := true\nmatch b {\n    true => 1\n    false => 2\n    _ => 3\n}"),
+            ["warning at 53..55: unreachable match arm"]
+        );
+    }
+
+    #[test]
+    fn check_struct_and_string_patterns() {
+        let input = "Vec2 :: struct { x: i32, y: i32 }\nv := Vec2 { x: 1, y: 2 }\nmatch v {\n    Vec2 { x: 0, y } => y\n    Vec2 { x, y: 0 } => x\n}";
+        assert_eq!(
+            check(input),
+            ["error at 59..122: non-exhaustive patterns: `Vec2 { x: 1, y: 1 }` not covered"]
+        );
+        assert_eq!(
+            check("s := \"a\"\nmatch s {\n    \"\" => 1\n    \"a\" => 2\n}"),
+            ["error at 9..45: non-exhaustive patterns: `\"b\"` not covered"]
+        );
+    }
+
+    #[test]
+    fn report_non_constant_patterns() {
+        assert_eq!(
+            check("y := 2\nx := 1\nmatch x {\n    y..10 => 1\n    _ => 2\n}"),
+            ["error at 28..34: pattern is not a constant"]
+        );
+    }
+}
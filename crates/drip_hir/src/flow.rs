@@ -0,0 +1,513 @@
+//! Dataflow checks over the control-flow graphs of bodies.
+//!
+//! - A binding must have a value on every path to a read of it. Lexical
+//!   scoping already rules out most reads before a definition, but top-level
+//!   constants can refer to later ones, which only works if those are
+//!   evaluated at compile time.
+//! - If a loop breaks with a value anywhere, every way out of it must have
+//!   one, including a `while` or `for` loop ending on its own.
+//! - `break` and `continue` must be inside a loop.
+//! - Bindings, including the ones `match` patterns introduce, and parameters
+//!   that are never read get a warning, unless their name starts with `_`. Top-level constants are exempt, since they can be
+//!   used from outside the file, and so are the variables of `for` loops,
+//!   which have to be named even when only the repetition matters.
+//!
+//! Only paths reachable from the start of a body are checked.
+
+use crate::cfg::{self, BlockId, Cfg, Instr};
+use crate::const_eval::ConstValues;
+use crate::resolve::{BodyId, Resolution, Resolutions};
+use crate::{Body, Expr, ExprIdx, ItemTree, Pat, PatIdx, Stmt};
+use la_arena::ArenaMap;
+use smol_str::SmolStr;
+use std::collections::HashSet;
+use std::fmt;
+use std::fmt::Formatter;
+use text_size::TextRange;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct FlowDiagnostic {
+    kind: FlowDiagnosticKind,
+    body: BodyId,
+    range: TextRange,
+}
+
+impl FlowDiagnostic {
+    pub fn kind(&self) -> &FlowDiagnosticKind {
+        &self.kind
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.kind.severity()
+    }
+
+    pub fn body(&self) -> BodyId {
+        self.body
+    }
+
+    pub fn range(&self) -> TextRange {
+        self.range
+    }
+}
+
+impl fmt::Display for FlowDiagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity() {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(
+            f,
+            "{} at {}..{}: {}",
+            severity,
+            u32::from(self.range.start()),
+            u32::from(self.range.end()),
+            self.kind,
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FlowDiagnosticKind {
+    PossiblyUninitialized {
+        name: SmolStr,
+    },
+    /// A bare `break` out of a loop that breaks with a value elsewhere.
+    MissingBreakValue,
+    /// A `while` or `for` loop that breaks with a value, but can also end
+    /// without one.
+    LoopEndsWithoutValue,
+    StrayBreak,
+    StrayContinue,
+    UnusedBinding {
+        name: SmolStr,
+    },
+    UnusedParam {
+        name: SmolStr,
+    },
+}
+
+impl FlowDiagnosticKind {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::UnusedBinding { .. } | Self::UnusedParam { .. } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl fmt::Display for FlowDiagnosticKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PossiblyUninitialized { name } => {
+                write!(f, "`{}` is used before it has a value on some paths", name)
+            }
+            Self::MissingBreakValue => {
+                write!(
+                    f,
+                    "`break` needs a value, as other exits of this loop have one"
+                )
+            }
+            Self::LoopEndsWithoutValue => write!(
+                f,
+                "loop can end without a value, but other exits of it have one"
+            ),
+            Self::StrayBreak => write!(f, "`break` outside of a loop"),
+            Self::StrayContinue => write!(f, "`continue` outside of a loop"),
+            Self::UnusedBinding { name } => write!(f, "unused binding `{}`", name),
+            Self::UnusedParam { name } => write!(f, "unused parameter `{}`", name),
+        }
+    }
+}
+
+/// Runs every check on `root` and on the bodies of all items in `tree`.
+pub fn check_flow(
+    tree: &ItemTree,
+    root: &Body,
+    resolutions: &Resolutions,
+    consts: &ConstValues,
+) -> Vec<FlowDiagnostic> {
+    let bodies: Vec<_> = std::iter::once((BodyId::Root, root))
+        .chain(
+            tree.functions()
+                .map(|(id, function)| (BodyId::Function(id), &function.body)),
+        )
+        .map(|(id, body)| (id, body, cfg::build(tree, id, body, resolutions)))
+        .collect();
+
+    // bindings can be read from other bodies, like top-level ones from
+    // inside functions
+    let mut used: HashSet<Resolution> = bodies
+        .iter()
+        .flat_map(|(_, _, cfg)| cfg.blocks())
+        .flat_map(|(_, block)| &block.instrs)
+        .filter_map(|instr| match instr {
+            Instr::Use { var, .. } => Some(*var),
+            Instr::Def { .. } => None,
+        })
+        .collect();
+    // so are constants matched against in patterns
+    for (id, body, _) in &bodies {
+        used.extend(
+            body.arenas
+                .pats()
+                .filter_map(|(pat, _)| resolutions.pat(*id, pat)),
+        );
+    }
+
+    let mut diagnostics = Vec::new();
+    for (id, body, cfg) in &bodies {
+        let mut checker = Checker {
+            tree,
+            id: *id,
+            body,
+            cfg,
+            reachable: reachable(cfg),
+            diagnostics: Vec::new(),
+        };
+        checker.check_initialization(consts);
+        checker.check_loops();
+        checker.check_unused(&used, resolutions);
+
+        checker.diagnostics.sort_by_key(|d| d.range.start());
+        diagnostics.extend(checker.diagnostics);
+    }
+
+    diagnostics
+}
+
+fn reachable(cfg: &Cfg) -> HashSet<BlockId> {
+    let mut seen = HashSet::new();
+    let mut stack = vec![cfg.entry()];
+
+    while let Some(block) = stack.pop() {
+        if seen.insert(block) {
+            stack.extend(cfg.block(block).terminator.successors());
+        }
+    }
+
+    seen
+}
+
+struct Checker<'a> {
+    tree: &'a ItemTree,
+    id: BodyId,
+    body: &'a Body,
+    cfg: &'a Cfg,
+    reachable: HashSet<BlockId>,
+    diagnostics: Vec<FlowDiagnostic>,
+}
+
+impl Checker<'_> {
+    /// Finds the bindings that are definitely assigned at the start of every
+    /// block, by intersecting what's assigned at the end of the blocks that
+    /// jump to it until nothing changes, then reports reads of bindings that
+    /// aren't assigned yet.
+    fn check_initialization(&mut self, consts: &ConstValues) {
+        let cfg = self.cfg;
+        let tracked: HashSet<Resolution> = cfg
+            .blocks()
+            .flat_map(|(_, block)| &block.instrs)
+            .filter_map(|instr| match instr {
+                Instr::Def { var } => Some(*var),
+                Instr::Use { .. } => None,
+            })
+            .collect();
+
+        // constants evaluated at compile time have their value from the start
+        let entry_state: HashSet<Resolution> = tracked
+            .iter()
+            .filter(|var| match var {
+                Resolution::Global { stmt } => consts.get(BodyId::Root, *stmt).is_some(),
+                _ => false,
+            })
+            .copied()
+            .collect();
+
+        // `None` stands for every binding, so it's the identity of the
+        // intersection
+        let mut outs: ArenaMap<BlockId, Option<HashSet<Resolution>>> = ArenaMap::default();
+        for (block, _) in cfg.blocks() {
+            outs.insert(block, None);
+        }
+        let mut preds: ArenaMap<BlockId, Vec<BlockId>> = ArenaMap::default();
+        for (block, _) in cfg.blocks() {
+            preds.insert(block, Vec::new());
+        }
+        for (block, data) in cfg.blocks() {
+            for next in data.terminator.successors() {
+                preds[next].push(block);
+            }
+        }
+
+        let block_in = |outs: &ArenaMap<BlockId, Option<HashSet<Resolution>>>, block| {
+            if block == cfg.entry() {
+                return Some(entry_state.clone());
+            }
+            preds[block]
+                .iter()
+                .filter_map(|pred| outs[*pred].as_ref())
+                .fold(None, |acc: Option<HashSet<Resolution>>, out| match acc {
+                    None => Some(out.clone()),
+                    Some(acc) => Some(acc.intersection(out).copied().collect()),
+                })
+        };
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (block, data) in cfg.blocks() {
+                if !self.reachable.contains(&block) {
+                    continue;
+                }
+                let mut state = match block_in(&outs, block) {
+                    Some(state) => state,
+                    None => continue,
+                };
+                for instr in &data.instrs {
+                    if let Instr::Def { var } = instr {
+                        state.insert(*var);
+                    }
+                }
+                if outs[block].as_ref() != Some(&state) {
+                    outs.insert(block, Some(state));
+                    changed = true;
+                }
+            }
+        }
+
+        for (block, data) in cfg.blocks() {
+            if !self.reachable.contains(&block) {
+                continue;
+            }
+            let mut state = block_in(&outs, block).unwrap_or_default();
+            for instr in &data.instrs {
+                match instr {
+                    Instr::Def { var } => {
+                        state.insert(*var);
+                    }
+                    Instr::Use { var, expr } if tracked.contains(var) && !state.contains(var) => {
+                        let name = match &self.body.arenas[*expr] {
+                            Expr::VariableRef { var } => var.clone(),
+                            _ => SmolStr::default(),
+                        };
+                        let range = self.expr_range(*expr);
+                        self.report(FlowDiagnosticKind::PossiblyUninitialized { name }, range);
+                    }
+                    Instr::Use { .. } => {}
+                }
+            }
+        }
+    }
+
+    fn check_loops(&mut self) {
+        let cfg = self.cfg;
+
+        for lp in cfg.loops() {
+            if !lp.exits.iter().any(|exit| exit.has_value) {
+                continue;
+            }
+            for exit in &lp.exits {
+                if exit.has_value || !self.reachable.contains(&exit.block) {
+                    continue;
+                }
+                let kind = if exit.expr == lp.expr {
+                    FlowDiagnosticKind::LoopEndsWithoutValue
+                } else {
+                    FlowDiagnosticKind::MissingBreakValue
+                };
+                let range = self.expr_range(exit.expr);
+                self.report(kind, range);
+            }
+        }
+
+        for jump in cfg.stray_jumps() {
+            let kind = match self.body.arenas[*jump] {
+                Expr::Continue => FlowDiagnosticKind::StrayContinue,
+                _ => FlowDiagnosticKind::StrayBreak,
+            };
+            let range = self.expr_range(*jump);
+            self.report(kind, range);
+        }
+    }
+
+    /// Adds the identifier patterns in `pat` to `idents`.
+    fn collect_idents(&self, pat: PatIdx, idents: &mut HashSet<PatIdx>) {
+        match &self.body.arenas[pat] {
+            Pat::Ident { .. } => {
+                idents.insert(pat);
+            }
+            Pat::Tuple { pats } => {
+                for pat in pats {
+                    self.collect_idents(*pat, idents);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_unused(&mut self, used: &HashSet<Resolution>, resolutions: &Resolutions) {
+        let source_map = self.body.arenas.source_map();
+
+        for (idx, stmt) in self.body.stmts.iter().enumerate() {
+            let name = match stmt {
+                Stmt::VariableDef { name, .. } | Stmt::ConstDef { name, .. } => name,
+                _ => continue,
+            };
+            let var = Resolution::Local {
+                body: self.id,
+                stmt: idx,
+            };
+            let is_global = self.id == BodyId::Root && self.body.top_level.contains(&idx);
+            if is_global || name.starts_with('_') || used.contains(&var) {
+                continue;
+            }
+
+            let range = source_map
+                .stmt_syntax(idx)
+                .map_or_else(TextRange::default, |ptr| ptr.syntax_node_ptr().range());
+            let name = name.clone();
+            self.report(FlowDiagnosticKind::UnusedBinding { name }, range);
+        }
+
+        let mut loop_vars = HashSet::new();
+        for (_, expr) in self.body.arenas.exprs.iter() {
+            if let Expr::For { pat, index, .. } = expr {
+                for pat in std::iter::once(*pat).chain(*index) {
+                    self.collect_idents(pat, &mut loop_vars);
+                }
+            }
+        }
+
+        for (pat, data) in self.body.arenas.pats() {
+            let name = match data {
+                Pat::Ident { name } => name,
+                _ => continue,
+            };
+            let var = Resolution::PatBinding { body: self.id, pat };
+            if resolutions.pat(self.id, pat).is_some()
+                || loop_vars.contains(&pat)
+                || name.starts_with('_')
+                || used.contains(&var)
+            {
+                continue;
+            }
+
+            let range = source_map
+                .pat_syntax(pat)
+                .map_or_else(TextRange::default, |ptr| ptr.range());
+            let name = name.clone();
+            self.report(FlowDiagnosticKind::UnusedBinding { name }, range);
+        }
+
+        let function = match self.id {
+            BodyId::Function(function) => function,
+            BodyId::Root => return,
+        };
+        // functions without a body, like in traits, only declare parameters
+        if self.body.top_level.is_empty() {
+            return;
+        }
+        for (index, param) in self.tree[function].params.iter().enumerate() {
+            let var = Resolution::Param { function, index };
+            if param.name == "self" || param.name.starts_with('_') || used.contains(&var) {
+                continue;
+            }
+
+            let range = source_map
+                .param_syntax(index)
+                .map_or_else(TextRange::default, |ptr| ptr.syntax_node_ptr().range());
+            let name = param.name.clone();
+            self.report(FlowDiagnosticKind::UnusedParam { name }, range);
+        }
+    }
+
+    fn expr_range(&self, expr: ExprIdx) -> TextRange {
+        self.body
+            .arenas
+            .source_map()
+            .expr_syntax(expr)
+            .map_or_else(TextRange::default, |source| source.range())
+    }
+
+    fn report(&mut self, kind: FlowDiagnosticKind, range: TextRange) {
+        self.diagnostics.push(FlowDiagnostic {
+            kind,
+            body: self.id,
+            range,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::const_eval::eval_consts;
+    use crate::infer::infer;
+    use crate::resolve::resolve;
+    use drip_ast::AstNode;
+
+    fn check(input: &str) -> Vec<String> {
+        let root = drip_ast::Root::cast(drip_parser::parse(input).syntax()).unwrap();
+        let (tree, body) = crate::lower(root);
+        let resolutions = resolve(&tree, &body);
+        let inference = infer(&tree, &body, &resolutions);
+        let consts = eval_consts(&tree, &body, &resolutions, &inference);
+
+        check_flow(&tree, &body, &resolutions, &consts)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn report_loop_exits_without_a_value() {
+        assert_eq!(
+            check("x :: for i in 0..8 { if i == 5 { break i } }\ny :: loop { if x == 1 { break }\nbreak 2 }"),
+            [
+                "error at 5..45: loop can end without a value, but other exits of it have one",
+                "error at 69..75: `break` needs a value, as other exits of this loop have one",
+            ]
+        );
+        assert_eq!(
+            check("x :: loop { break 1\nbreak }\nwhile x < 3 { break }"),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn report_reads_of_constants_that_have_no_value_yet() {
+        let input = "f :: () -> i32 { 1 }\nA :: B + C\nB :: f()\nC :: 2\nB";
+        assert_eq!(
+            check(input),
+            ["error at 26..28: `B` is used before it has a value on some paths"]
+        );
+    }
+
+    #[test]
+    fn warn_about_unused_bindings_and_params() {
+        let input = "f :: (a, _b, c, d: i32) {
+    x := a
+    y := 0
+    y = 1
+    _z := 2
+    for i in 0..c { continue }
+}
+break";
+        assert_eq!(
+            check(input),
+            [
+                "error at 103..108: `break` outside of a loop",
+                "warning at 16..22: unused parameter `d`",
+                "warning at 30..41: unused binding `x`",
+                "warning at 41..52: unused binding `y`",
+            ]
+        );
+    }
+}
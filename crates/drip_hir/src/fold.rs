@@ -0,0 +1,266 @@
+//! Rewriting of lowered statements and expressions.
+//!
+//! A [`Fold`] takes each statement and expression by value and returns its
+//! replacement. The default methods rebuild the node unchanged after folding
+//! its children, so an implementation only overrides the cases it rewrites
+//! and calls back into [`walk_stmt`] or [`walk_expr`] to keep descending.
+
+use crate::{BodyArenas, Expr, ExprIdx, FormatPart, Pat, PatIdx, Stmt};
+use std::mem;
+
+pub trait Fold {
+    fn fold_stmt(&mut self, arenas: &mut BodyArenas, stmt: Stmt) -> Stmt {
+        walk_stmt(self, arenas, stmt)
+    }
+
+    fn fold_expr(&mut self, arenas: &mut BodyArenas, expr: Expr) -> Expr {
+        walk_expr(self, arenas, expr)
+    }
+}
+
+/// Folds every statement in `stmts`, along with all expressions they contain.
+pub fn fold(
+    folder: &mut (impl Fold + ?Sized),
+    arenas: &mut BodyArenas,
+    stmts: Vec<Stmt>,
+) -> Vec<Stmt> {
+    stmts
+        .into_iter()
+        .map(|stmt| folder.fold_stmt(arenas, stmt))
+        .collect()
+}
+
+/// Folds the expressions of `stmt` in place in `arenas` and returns `stmt` itself.
+///
+/// Items are left alone; their bodies are folded separately.
+pub fn walk_stmt<F: Fold + ?Sized>(folder: &mut F, arenas: &mut BodyArenas, stmt: Stmt) -> Stmt {
+    match &stmt {
+        Stmt::VariableDef { value, .. }
+        | Stmt::ConstDef { value, .. }
+        | Stmt::TupleDef { value, .. } => fold_idx(folder, arenas, *value),
+        Stmt::Assign { target, value, .. } => {
+            fold_idx(folder, arenas, *target);
+            fold_idx(folder, arenas, *value);
+        }
+        Stmt::Expr(expr) => fold_idx(folder, arenas, *expr),
+        Stmt::Item(_) => {}
+    }
+
+    stmt
+}
+
+/// Folds the children of `expr` in place in `arenas` and returns `expr` itself.
+pub fn walk_expr<F: Fold + ?Sized>(folder: &mut F, arenas: &mut BodyArenas, expr: Expr) -> Expr {
+    match &expr {
+        Expr::Binary { lhs, rhs, .. } => {
+            fold_idx(folder, arenas, *lhs);
+            fold_idx(folder, arenas, *rhs);
+        }
+        Expr::Unary { expr, .. } => fold_idx(folder, arenas, *expr),
+        Expr::Call { callee, args, .. } => {
+            fold_idx(folder, arenas, *callee);
+            for arg in args {
+                fold_idx(folder, arenas, *arg);
+            }
+        }
+        Expr::MethodCall { receiver, args, .. } => {
+            fold_idx(folder, arenas, *receiver);
+            for arg in args {
+                fold_idx(folder, arenas, *arg);
+            }
+        }
+        Expr::Field { expr, .. } => fold_idx(folder, arenas, *expr),
+        Expr::Index { base, index } => {
+            fold_idx(folder, arenas, *base);
+            fold_idx(folder, arenas, *index);
+        }
+        Expr::Struct { fields, .. } => {
+            for (_, value) in fields {
+                fold_idx(folder, arenas, *value);
+            }
+        }
+        Expr::FormatString { parts } => {
+            for part in parts {
+                if let FormatPart::Expr(expr) = part {
+                    fold_idx(folder, arenas, *expr);
+                }
+            }
+        }
+        Expr::Closure { body, .. } => fold_idx(folder, arenas, *body),
+        Expr::Tuple { exprs } | Expr::Array { exprs } => {
+            for expr in exprs {
+                fold_idx(folder, arenas, *expr);
+            }
+        }
+        Expr::Range { start, end, step } => {
+            fold_idx(folder, arenas, *start);
+            fold_idx(folder, arenas, *end);
+            if let Some(step) = step {
+                fold_idx(folder, arenas, *step);
+            }
+        }
+        Expr::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            fold_idx(folder, arenas, *condition);
+            fold_idx(folder, arenas, *then_branch);
+            if let Some(else_branch) = else_branch {
+                fold_idx(folder, arenas, *else_branch);
+            }
+        }
+        Expr::Loop { body } => fold_idx(folder, arenas, *body),
+        Expr::While { condition, body } => {
+            fold_idx(folder, arenas, *condition);
+            fold_idx(folder, arenas, *body);
+        }
+        Expr::For { iterable, body, .. } => {
+            fold_idx(folder, arenas, *iterable);
+            fold_idx(folder, arenas, *body);
+        }
+        Expr::Break { value } => {
+            if let Some(value) = value {
+                fold_idx(folder, arenas, *value);
+            }
+        }
+        Expr::Match { scrutinee, arms } => {
+            fold_idx(folder, arenas, *scrutinee);
+            for arm in arms {
+                for pat in &arm.pats {
+                    fold_pat(folder, arenas, *pat);
+                }
+                fold_idx(folder, arenas, arm.body);
+            }
+        }
+        // the statements of a block are folded along with all others
+        Expr::Block { .. }
+        | Expr::Continue
+        | Expr::Missing
+        | Expr::Placeholder
+        | Expr::Literal { .. }
+        | Expr::FloatLiteral { .. }
+        | Expr::StringLiteral { .. }
+        | Expr::BoolLiteral { .. }
+        | Expr::VariableRef { .. }
+        | Expr::Path { .. } => {}
+    }
+
+    expr
+}
+
+/// Folds the expressions inside `pat`; patterns themselves aren't rewritten.
+fn fold_pat<F: Fold + ?Sized>(folder: &mut F, arenas: &mut BodyArenas, pat: PatIdx) {
+    match &arenas.pats[pat] {
+        Pat::Literal { expr } => fold_idx(folder, arenas, *expr),
+        Pat::Range { start, end } => {
+            let (start, end) = (*start, *end);
+            fold_idx(folder, arenas, start);
+            fold_idx(folder, arenas, end);
+        }
+        Pat::Tuple { pats } => {
+            for pat in pats.clone() {
+                fold_pat(folder, arenas, pat);
+            }
+        }
+        Pat::Struct { fields, .. } => {
+            let pats: Vec<_> = fields.iter().map(|(_, pat)| *pat).collect();
+            for pat in pats {
+                fold_pat(folder, arenas, pat);
+            }
+        }
+        Pat::Missing | Pat::Wildcard | Pat::Ident { .. } => {}
+    }
+}
+
+fn fold_idx<F: Fold + ?Sized>(folder: &mut F, arenas: &mut BodyArenas, idx: ExprIdx) {
+    let expr = mem::replace(&mut arenas.exprs[idx], Expr::Missing);
+    arenas.exprs[idx] = folder.fold_expr(arenas, expr);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BinaryOp, UnaryOp};
+    use drip_ast::AstNode;
+    use la_arena::Arena;
+    use smol_str::SmolStr;
+
+    /// Replaces references to `var` with `value`.
+    struct Inline {
+        var: SmolStr,
+        value: u64,
+    }
+
+    impl Fold for Inline {
+        fn fold_expr(&mut self, arenas: &mut BodyArenas, expr: Expr) -> Expr {
+            match expr {
+                Expr::VariableRef { var } if var == self.var => Expr::Literal {
+                    n: Some(self.value),
+                },
+                expr => walk_expr(self, arenas, expr),
+            }
+        }
+    }
+
+    fn lower(input: &str) -> (BodyArenas, Vec<Stmt>) {
+        let (_, body) =
+            crate::lower(drip_ast::Root::cast(drip_parser::parse(input).syntax()).unwrap());
+        (body.arenas, body.stmts)
+    }
+
+    #[test]
+    fn fold_nested_exprs() {
+        let (mut arenas, stmts) = lower("b := a * -(a + c)");
+        let mut inline = Inline {
+            var: "a".into(),
+            value: 2,
+        };
+        let stmts = fold(&mut inline, &mut arenas, stmts);
+
+        let mut exprs = Arena::new();
+        let inner_lhs = exprs.alloc(Expr::Literal { n: Some(2) });
+        let inner_rhs = exprs.alloc(Expr::VariableRef { var: "c".into() });
+        let add = exprs.alloc(Expr::Binary {
+            op: BinaryOp::Add,
+            lhs: inner_lhs,
+            rhs: inner_rhs,
+        });
+        let lhs = exprs.alloc(Expr::Literal { n: Some(2) });
+        let rhs = exprs.alloc(Expr::Unary {
+            op: UnaryOp::Neg,
+            expr: add,
+        });
+        let value = exprs.alloc(Expr::Binary {
+            op: BinaryOp::Mul,
+            lhs,
+            rhs,
+        });
+
+        assert_eq!(
+            stmts,
+            vec![Stmt::VariableDef {
+                name: "b".into(),
+                value,
+            }]
+        );
+        assert_eq!(arenas.exprs, exprs);
+    }
+
+    #[test]
+    fn fold_top_level_expr() {
+        let (mut arenas, stmts) = lower("a");
+        let mut inline = Inline {
+            var: "a".into(),
+            value: 7,
+        };
+
+        let stmts = fold(&mut inline, &mut arenas, stmts);
+
+        let expr = match stmts[..] {
+            [Stmt::Expr(expr)] => expr,
+            _ => unreachable!(),
+        };
+        assert_eq!(arenas[expr], Expr::Literal { n: Some(7) });
+    }
+}
@@ -0,0 +1,2057 @@
+//! Type inference.
+//!
+//! Every expression, binding and function gets a type. Types that aren't
+//! written down start out as type variables and are solved by unification as
+//! the bodies are walked in order. Integer and float literals get their own
+//! kinds of variables, which only unify with integer or float types and fall
+//! back to `i32` and `f64` when nothing else pins them down.
+//!
+//! Expressions whose type can't be worked out, like references to unresolved
+//! names, are typed as [`Ty::Unknown`], which unifies with anything so a
+//! single mistake doesn't cascade into more errors. Expressions that never
+//! produce a value, like `break`, are typed as [`Ty::Never`], which unifies
+//! with anything as well.
+//!
+//! A loop's type is the type of the values it breaks with, or `()` if it
+//! never breaks with a value. Whether every way out of the loop has a value
+//! is checked by [`crate::flow`].
+//!
+//! The patterns of a `match` are checked against the type of the value being
+//! matched, and the names they bind get the type of the part they match.
+
+use crate::method_resolution::{self, MethodError};
+use crate::resolve::{BodyId, Builtin, Resolution, Resolutions};
+use crate::{
+    BinaryOp, Body, Expr, ExprIdx, FormatPart, FunctionId, Item, ItemTree, Pat, PatIdx, Stmt,
+    StructId, TraitId, TypeRef, UnaryOp,
+};
+use drip_operator::OperatorTrait;
+use la_arena::ArenaMap;
+use smol_str::SmolStr;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Formatter;
+use std::mem;
+use std::ops::Index;
+use text_size::TextRange;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Ty {
+    Unknown,
+    Int(IntTy),
+    Float(FloatTy),
+    Bool,
+    String,
+    Array(Box<Ty>),
+    Tuple(Vec<Ty>),
+    /// The type of `start..end`.
+    Range(Box<Ty>),
+    Struct {
+        id: StructId,
+        name: SmolStr,
+    },
+    Fn {
+        params: Vec<Ty>,
+        ret: Box<Ty>,
+    },
+    /// `Self` inside a trait, where it stands for every implementing type.
+    SelfType,
+    /// The type of expressions that never finish, like `break`.
+    Never,
+    Infer(InferTy),
+}
+
+impl Ty {
+    fn unit() -> Self {
+        Self::Tuple(Vec::new())
+    }
+
+    fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            Self::Int(_) | Self::Float(_) | Self::Infer(InferTy::Int(_) | InferTy::Float(_))
+        )
+    }
+}
+
+impl fmt::Display for Ty {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown => write!(f, "{{unknown}}"),
+            Self::Int(ty) => write!(f, "{}", ty.name()),
+            Self::Float(ty) => write!(f, "{}", ty.name()),
+            Self::Bool => write!(f, "bool"),
+            Self::String => write!(f, "string"),
+            Self::Array(ty) => write!(f, "[{}]", ty),
+            Self::Tuple(tys) => write!(f, "({})", join(tys)),
+            Self::Range(ty) => write!(f, "Range<{}>", ty),
+            Self::Struct { name, .. } => write!(f, "{}", name),
+            Self::Fn { params, ret } => write!(f, "fn({}) -> {}", join(params), ret),
+            Self::SelfType => write!(f, "Self"),
+            Self::Never => write!(f, "!"),
+            Self::Infer(InferTy::Var(_)) => write!(f, "_"),
+            Self::Infer(InferTy::Int(_)) => write!(f, "{{integer}}"),
+            Self::Infer(InferTy::Float(_)) => write!(f, "{{float}}"),
+            Self::Infer(InferTy::Fn(_)) => write!(f, "Fn()"),
+        }
+    }
+}
+
+fn join(tys: &[Ty]) -> String {
+    tys.iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IntTy {
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+}
+
+impl IntTy {
+    const ALL: [Self; 8] = [
+        Self::I8,
+        Self::I16,
+        Self::I32,
+        Self::I64,
+        Self::U8,
+        Self::U16,
+        Self::U32,
+        Self::U64,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::I8 => "i8",
+            Self::I16 => "i16",
+            Self::I32 => "i32",
+            Self::I64 => "i64",
+            Self::U8 => "u8",
+            Self::U16 => "u16",
+            Self::U32 => "u32",
+            Self::U64 => "u64",
+        }
+    }
+
+    /// The smallest and largest value of the type.
+    pub fn bounds(self) -> (i128, i128) {
+        match self {
+            Self::I8 => (i8::MIN.into(), i8::MAX.into()),
+            Self::I16 => (i16::MIN.into(), i16::MAX.into()),
+            Self::I32 => (i32::MIN.into(), i32::MAX.into()),
+            Self::I64 => (i64::MIN.into(), i64::MAX.into()),
+            Self::U8 => (0, u8::MAX.into()),
+            Self::U16 => (0, u16::MAX.into()),
+            Self::U32 => (0, u32::MAX.into()),
+            Self::U64 => (0, u64::MAX.into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FloatTy {
+    F32,
+    F64,
+}
+
+impl FloatTy {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::F32 => "f32",
+            Self::F64 => "f64",
+        }
+    }
+}
+
+/// A type variable, only left in a type while inference is still running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InferTy {
+    Var(u32),
+    /// The type of an integer literal.
+    Int(u32),
+    /// The type of a float literal.
+    Float(u32),
+    /// The type of something written as `Fn()`, which can be any function.
+    Fn(u32),
+}
+
+impl InferTy {
+    fn index(self) -> usize {
+        match self {
+            Self::Var(var) | Self::Int(var) | Self::Float(var) | Self::Fn(var) => var as usize,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct TypeError {
+    kind: TypeErrorKind,
+    body: BodyId,
+    expr: ExprIdx,
+    range: TextRange,
+}
+
+impl TypeError {
+    pub fn kind(&self) -> &TypeErrorKind {
+        &self.kind
+    }
+
+    pub fn body(&self) -> BodyId {
+        self.body
+    }
+
+    pub fn expr(&self) -> ExprIdx {
+        self.expr
+    }
+
+    pub fn range(&self) -> TextRange {
+        self.range
+    }
+}
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "error at {}..{}: {}",
+            u32::from(self.range.start()),
+            u32::from(self.range.end()),
+            self.kind,
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeErrorKind {
+    Mismatch {
+        expected: Ty,
+        found: Ty,
+    },
+    NotCallable {
+        ty: Ty,
+    },
+    ArgCount {
+        expected: usize,
+        found: usize,
+    },
+    InvalidOperand {
+        ty: Ty,
+    },
+    NoMethod {
+        ty: Ty,
+        name: SmolStr,
+    },
+    AmbiguousMethod {
+        ty: Ty,
+        name: SmolStr,
+    },
+    NoField {
+        ty: Ty,
+        name: SmolStr,
+    },
+    NotIterable {
+        ty: Ty,
+    },
+    NotIndexable {
+        ty: Ty,
+    },
+    NotAStruct {
+        name: SmolStr,
+    },
+    MissingFields {
+        ty: Ty,
+        names: Vec<SmolStr>,
+    },
+    /// An argument passed by a name no parameter has.
+    NoParam {
+        name: SmolStr,
+    },
+    /// A parameter given more than one argument.
+    ArgTwice {
+        name: SmolStr,
+    },
+    /// A struct literal with more values than the struct has fields.
+    TooManyFields {
+        ty: Ty,
+        count: usize,
+    },
+    /// An operator used on a struct that doesn't implement its trait.
+    MissingOperatorImpl {
+        ty: Ty,
+        trait_name: SmolStr,
+    },
+    /// An assignment to `a[i]` where `a` is indexed through an `Index` impl.
+    IndexAssign {
+        ty: Ty,
+    },
+}
+
+impl TypeErrorKind {
+    fn map_tys(self, mut f: impl FnMut(&Ty) -> Ty) -> Self {
+        match self {
+            Self::Mismatch { expected, found } => Self::Mismatch {
+                expected: f(&expected),
+                found: f(&found),
+            },
+            Self::NotCallable { ty } => Self::NotCallable { ty: f(&ty) },
+            Self::InvalidOperand { ty } => Self::InvalidOperand { ty: f(&ty) },
+            Self::NoMethod { ty, name } => Self::NoMethod { ty: f(&ty), name },
+            Self::AmbiguousMethod { ty, name } => Self::AmbiguousMethod { ty: f(&ty), name },
+            Self::NoField { ty, name } => Self::NoField { ty: f(&ty), name },
+            Self::NotIterable { ty } => Self::NotIterable { ty: f(&ty) },
+            Self::NotIndexable { ty } => Self::NotIndexable { ty: f(&ty) },
+            Self::MissingFields { ty, names } => Self::MissingFields { ty: f(&ty), names },
+            Self::TooManyFields { ty, count } => Self::TooManyFields { ty: f(&ty), count },
+            Self::NoParam { name } => Self::NoParam { name },
+            Self::ArgTwice { name } => Self::ArgTwice { name },
+            Self::MissingOperatorImpl { ty, trait_name } => Self::MissingOperatorImpl {
+                ty: f(&ty),
+                trait_name,
+            },
+            Self::IndexAssign { ty } => Self::IndexAssign { ty: f(&ty) },
+            kind @ (Self::ArgCount { .. } | Self::NotAStruct { .. }) => kind,
+        }
+    }
+}
+
+impl fmt::Display for TypeErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mismatch { expected, found } => {
+                write!(f, "expected `{}`, found `{}`", expected, found)
+            }
+            Self::NotCallable { ty } => write!(f, "`{}` is not a function", ty),
+            Self::ArgCount { expected, found } => {
+                write!(f, "expected {} arguments, found {}", expected, found)
+            }
+            Self::InvalidOperand { ty } => {
+                write!(f, "`{}` can't be used with this operator", ty)
+            }
+            Self::NoMethod { ty, name } => write!(f, "no method `{}` on `{}`", name, ty),
+            Self::AmbiguousMethod { ty, name } => {
+                write!(f, "more than one method `{}` on `{}`", name, ty)
+            }
+            Self::NoField { ty, name } => write!(f, "no field `{}` on `{}`", name, ty),
+            Self::NotIterable { ty } => write!(f, "`{}` can't be iterated over", ty),
+            Self::NotIndexable { ty } => write!(f, "`{}` can't be indexed", ty),
+            Self::NotAStruct { name } => write!(f, "`{}` is not a struct", name),
+            Self::MissingFields { ty, names } => {
+                let names: Vec<_> = names.iter().map(|name| format!("`{}`", name)).collect();
+                let plural = if names.len() == 1 { "" } else { "s" };
+                write!(
+                    f,
+                    "missing field{} {} in `{}`",
+                    plural,
+                    names.join(", "),
+                    ty
+                )
+            }
+            Self::NoParam { name } => write!(f, "no parameter named `{}`", name),
+            Self::ArgTwice { name } => write!(f, "`{}` is given more than one argument", name),
+            Self::TooManyFields { ty, count } => {
+                let plural = if *count == 1 { "" } else { "s" };
+                write!(f, "`{}` only has {} field{}", ty, count, plural)
+            }
+            Self::MissingOperatorImpl { ty, trait_name } => {
+                write!(f, "`{}` doesn't implement `{}`", ty, trait_name)
+            }
+            Self::IndexAssign { ty } => {
+                write!(
+                    f,
+                    "cannot assign to an index of `{}`, which implements `Index`",
+                    ty
+                )
+            }
+        }
+    }
+}
+
+/// The types of one body's expressions and bindings.
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct InferenceResult {
+    exprs: ArenaMap<ExprIdx, Ty>,
+    pats: ArenaMap<PatIdx, Ty>,
+    bindings: HashMap<usize, Ty>,
+    methods: HashMap<ExprIdx, FunctionId>,
+    assignments: HashMap<usize, FunctionId>,
+    arg_orders: HashMap<ExprIdx, Vec<usize>>,
+}
+
+impl InferenceResult {
+    /// The function a `MethodCall` expression calls, or the operator method
+    /// a `Binary`, `Unary` or `Index` expression on a struct calls.
+    pub fn method_resolution(&self, expr: ExprIdx) -> Option<FunctionId> {
+        self.methods.get(&expr).copied()
+    }
+
+    /// The parameter each argument of a call that passes some by name goes
+    /// to, in the order the arguments are written in.
+    pub fn arg_order(&self, call: ExprIdx) -> Option<&[usize]> {
+        self.arg_orders.get(&call).map(Vec::as_slice)
+    }
+
+    /// The operator method a compound assignment to a struct, the body's
+    /// `stmt`th statement, calls.
+    pub fn assign_resolution(&self, stmt: usize) -> Option<FunctionId> {
+        self.assignments.get(&stmt).copied()
+    }
+
+    /// The type of the binding defined by the body's `stmt`th statement.
+    pub fn binding(&self, stmt: usize) -> Option<&Ty> {
+        self.bindings.get(&stmt)
+    }
+}
+
+impl Index<ExprIdx> for InferenceResult {
+    type Output = Ty;
+
+    fn index(&self, expr: ExprIdx) -> &Ty {
+        &self.exprs[expr]
+    }
+}
+
+impl Index<PatIdx> for InferenceResult {
+    type Output = Ty;
+
+    fn index(&self, pat: PatIdx) -> &Ty {
+        &self.pats[pat]
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct InferenceResults {
+    bodies: HashMap<BodyId, InferenceResult>,
+    functions: HashMap<FunctionId, Ty>,
+    errors: Vec<TypeError>,
+}
+
+impl InferenceResults {
+    pub fn body(&self, body: BodyId) -> &InferenceResult {
+        &self.bodies[&body]
+    }
+
+    /// The `Fn` type of `function`.
+    pub fn function(&self, function: FunctionId) -> &Ty {
+        &self.functions[&function]
+    }
+
+    pub fn errors(&self) -> &[TypeError] {
+        &self.errors
+    }
+}
+
+/// Infers the types of `root` and of the bodies of all items in `tree`.
+pub fn infer(tree: &ItemTree, root: &Body, resolutions: &Resolutions) -> InferenceResults {
+    let mut self_tys = HashMap::new();
+    for (_, imp) in tree.impls() {
+        for function in &imp.functions {
+            self_tys.insert(*function, imp.self_ty.clone());
+        }
+    }
+    let mut traits = HashMap::new();
+    for (id, trait_data) in tree.traits() {
+        for function in &trait_data.functions {
+            traits.insert(*function, id);
+        }
+    }
+
+    let mut ctx = InferenceContext {
+        tree,
+        resolutions,
+        self_tys,
+        traits,
+        table: UnificationTable::default(),
+        exprs: HashMap::new(),
+        pats: HashMap::new(),
+        bindings: HashMap::new(),
+        methods: HashMap::new(),
+        assignments: HashMap::new(),
+        arg_orders: HashMap::new(),
+        signatures: HashMap::new(),
+        closure_params: HashMap::new(),
+        loops: Vec::new(),
+        errors: Vec::new(),
+    };
+    ctx.infer_body(BodyId::Root, root);
+
+    ctx.finish()
+}
+
+#[derive(Default)]
+struct UnificationTable {
+    vars: Vec<Option<Ty>>,
+}
+
+impl UnificationTable {
+    fn new_var(&mut self, kind: fn(u32) -> InferTy) -> Ty {
+        self.vars.push(None);
+        Ty::Infer(kind(self.vars.len() as u32 - 1))
+    }
+
+    /// Follows `ty` through bound variables until it reaches a type that
+    /// isn't one.
+    fn shallow_resolve(&self, ty: &Ty) -> Ty {
+        let mut ty = ty.clone();
+        while let Ty::Infer(var) = ty {
+            match &self.vars[var.index()] {
+                Some(bound) => ty = bound.clone(),
+                None => break,
+            }
+        }
+        ty
+    }
+
+    /// Replaces all variables in `ty`, defaulting the ones that are unbound.
+    fn resolve(&self, ty: &Ty) -> Ty {
+        self.resolve_with(ty, false)
+    }
+
+    /// Like [`Self::resolve`], but an unbound `Fn()` stays one, so an error
+    /// can show it as it was written.
+    fn resolve_for_error(&self, ty: &Ty) -> Ty {
+        self.resolve_with(ty, true)
+    }
+
+    fn resolve_with(&self, ty: &Ty, keep_fns: bool) -> Ty {
+        let resolve = |ty: &Ty| self.resolve_with(ty, keep_fns);
+        match self.shallow_resolve(ty) {
+            ty @ Ty::Infer(InferTy::Fn(_)) if keep_fns => ty,
+            Ty::Infer(InferTy::Var(_) | InferTy::Fn(_)) => Ty::Unknown,
+            Ty::Infer(InferTy::Int(_)) => Ty::Int(IntTy::I32),
+            Ty::Infer(InferTy::Float(_)) => Ty::Float(FloatTy::F64),
+            Ty::Array(ty) => Ty::Array(Box::new(resolve(&ty))),
+            Ty::Range(ty) => Ty::Range(Box::new(resolve(&ty))),
+            Ty::Tuple(tys) => Ty::Tuple(tys.iter().map(resolve).collect()),
+            Ty::Fn { params, ret } => Ty::Fn {
+                params: params.iter().map(resolve).collect(),
+                ret: Box::new(resolve(&ret)),
+            },
+            ty => ty,
+        }
+    }
+
+    fn unify(&mut self, a: &Ty, b: &Ty) -> bool {
+        let a = self.shallow_resolve(a);
+        let b = self.shallow_resolve(b);
+
+        match (&a, &b) {
+            (Ty::Unknown, _) | (_, Ty::Unknown) => true,
+            (Ty::Never, _) | (_, Ty::Never) => true,
+            (Ty::Infer(x), Ty::Infer(y)) if x == y => true,
+            (Ty::Infer(InferTy::Var(var)), other) | (other, Ty::Infer(InferTy::Var(var))) => {
+                self.bind(*var, other)
+            }
+            // an integer literal can be a float too, like the `2` in `PI * 2`
+            (
+                Ty::Infer(InferTy::Int(var)),
+                other
+                @ (Ty::Int(_) | Ty::Float(_) | Ty::Infer(InferTy::Int(_) | InferTy::Float(_))),
+            )
+            | (
+                other @ (Ty::Int(_) | Ty::Float(_) | Ty::Infer(InferTy::Float(_))),
+                Ty::Infer(InferTy::Int(var)),
+            ) => self.bind(*var, other),
+            (
+                Ty::Infer(InferTy::Float(var)),
+                other @ (Ty::Float(_) | Ty::Infer(InferTy::Float(_))),
+            )
+            | (other @ Ty::Float(_), Ty::Infer(InferTy::Float(var))) => self.bind(*var, other),
+            (Ty::Infer(InferTy::Fn(var)), other @ (Ty::Fn { .. } | Ty::Infer(InferTy::Fn(_))))
+            | (other @ Ty::Fn { .. }, Ty::Infer(InferTy::Fn(var))) => self.bind(*var, other),
+            (Ty::Array(a), Ty::Array(b)) | (Ty::Range(a), Ty::Range(b)) => self.unify(a, b),
+            (Ty::Tuple(a), Ty::Tuple(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| self.unify(a, b))
+            }
+            (
+                Ty::Fn { params, ret },
+                Ty::Fn {
+                    params: other_params,
+                    ret: other_ret,
+                },
+            ) => {
+                params.len() == other_params.len()
+                    && params
+                        .iter()
+                        .zip(other_params)
+                        .all(|(a, b)| self.unify(a, b))
+                    && self.unify(ret, other_ret)
+            }
+            (a, b) => a == b,
+        }
+    }
+
+    fn bind(&mut self, var: u32, ty: &Ty) -> bool {
+        if self.occurs(var, ty) {
+            return false;
+        }
+        self.vars[var as usize] = Some(ty.clone());
+        true
+    }
+
+    fn occurs(&self, var: u32, ty: &Ty) -> bool {
+        match self.shallow_resolve(ty) {
+            Ty::Infer(other) => other.index() == var as usize,
+            Ty::Array(ty) | Ty::Range(ty) => self.occurs(var, &ty),
+            Ty::Tuple(tys) => tys.iter().any(|ty| self.occurs(var, ty)),
+            Ty::Fn { params, ret } => {
+                params.iter().any(|ty| self.occurs(var, ty)) || self.occurs(var, &ret)
+            }
+            _ => false,
+        }
+    }
+}
+
+struct Signature {
+    params: Vec<Ty>,
+    ret: Ty,
+}
+
+struct InferenceContext<'a> {
+    tree: &'a ItemTree,
+    resolutions: &'a Resolutions,
+    /// The `Self` type of every function defined in an impl.
+    self_tys: HashMap<FunctionId, TypeRef>,
+    /// The trait every trait member belongs to.
+    traits: HashMap<FunctionId, TraitId>,
+    table: UnificationTable,
+    exprs: HashMap<BodyId, ArenaMap<ExprIdx, Ty>>,
+    /// The type of the value every pattern matches, which is also the type
+    /// of the names identifier patterns bind.
+    pats: HashMap<(BodyId, PatIdx), Ty>,
+    /// Created on first use, so bindings can be referred to before they're
+    /// inferred, like top-level constants from inside functions.
+    bindings: HashMap<(BodyId, usize), Ty>,
+    methods: HashMap<(BodyId, ExprIdx), FunctionId>,
+    assignments: HashMap<(BodyId, usize), FunctionId>,
+    arg_orders: HashMap<(BodyId, ExprIdx), Vec<usize>>,
+    signatures: HashMap<FunctionId, Signature>,
+    /// The types of the parameters of every closure.
+    closure_params: HashMap<(BodyId, ExprIdx), Vec<Ty>>,
+    /// The loops around the expression being inferred, innermost last.
+    loops: Vec<LoopCtx>,
+    errors: Vec<TypeError>,
+}
+
+#[derive(Default)]
+struct LoopCtx {
+    /// The type of the values the loop breaks with, once one is seen.
+    value: Option<Ty>,
+    breaks: bool,
+}
+
+impl InferenceContext<'_> {
+    fn infer_body(&mut self, id: BodyId, body: &Body) {
+        self.exprs.entry(id).or_default();
+        let loops = mem::take(&mut self.loops);
+        let last = self.infer_stmts(id, body, &body.top_level);
+        self.loops = loops;
+
+        // the last expression of a function is what it returns
+        if let BodyId::Function(function) = id {
+            let ret = self.signature(function).ret.clone();
+            match body.top_level.last().map(|stmt| &body.stmts[*stmt]) {
+                Some(Stmt::Expr(expr)) => {
+                    self.unify_or_report(&ret, &last, id, body, *expr);
+                }
+                _ => {
+                    self.table.unify(&ret, &Ty::unit());
+                }
+            }
+        }
+    }
+
+    /// Infers `stmts` in order and returns the type of the last one, which
+    /// is `()` unless it's an expression.
+    fn infer_stmts(&mut self, id: BodyId, body: &Body, stmts: &[usize]) -> Ty {
+        let mut last = Ty::unit();
+        for &stmt in stmts {
+            last = self.infer_stmt(id, body, stmt);
+        }
+        last
+    }
+
+    fn infer_stmt(&mut self, id: BodyId, body: &Body, idx: usize) -> Ty {
+        match &body.stmts[idx] {
+            Stmt::VariableDef { value, .. } | Stmt::ConstDef { value, .. } => {
+                let ty = self.infer_expr(id, body, *value);
+                let binding = self.binding(id, idx);
+                self.unify_or_report(&binding, &ty, id, body, *value);
+            }
+            Stmt::TupleDef { pat, value, .. } => {
+                let ty = self.infer_expr(id, body, *value);
+                self.infer_pat(id, body, *pat, &ty, *value);
+            }
+            Stmt::Assign { target, op, value } => {
+                let target_ty = self.infer_expr(id, body, *target);
+                let ty = self.infer_expr(id, body, *value);
+                self.check_index_assign(id, body, *target);
+
+                let assign_trait = op.and_then(|op| op.assign_trait());
+                match (assign_trait, self.table.shallow_resolve(&target_ty)) {
+                    (Some(op), target_ty @ Ty::Struct { .. }) => {
+                        match self.operator_method(id, body, op, &target_ty, &[(*value, ty)]) {
+                            Some((function, _)) => {
+                                self.assignments.insert((id, idx), function);
+                            }
+                            None => self.report_missing_impl(op, target_ty, id, body, *target),
+                        }
+                    }
+                    _ => {
+                        if self.unify_or_report(&target_ty, &ty, id, body, *value) {
+                            if let Some(op) = op {
+                                self.check_operand(op, &target_ty, id, body, *target);
+                            }
+                        }
+                    }
+                }
+            }
+            Stmt::Expr(expr) => return self.infer_expr(id, body, *expr),
+            Stmt::Item(item) => self.infer_item(*item),
+        }
+
+        Ty::unit()
+    }
+
+    fn infer_item(&mut self, item: Item) {
+        let tree = self.tree;
+        let functions = match item {
+            Item::Function(function) => vec![function],
+            Item::Trait(id) => tree[id].functions.clone(),
+            Item::Impl(id) => tree[id].functions.clone(),
+            Item::Struct(_) => Vec::new(),
+        };
+
+        for function in functions {
+            self.signature(function);
+            self.infer_body(BodyId::Function(function), &tree[function].body);
+        }
+    }
+
+    fn infer_expr(&mut self, id: BodyId, body: &Body, idx: ExprIdx) -> Ty {
+        let ty = match &body.arenas[idx] {
+            Expr::Missing => Ty::Unknown,
+            Expr::Literal { .. } => self.table.new_var(InferTy::Int),
+            Expr::FloatLiteral { .. } => self.table.new_var(InferTy::Float),
+            Expr::StringLiteral { .. } => Ty::String,
+            Expr::FormatString { parts } => {
+                for part in parts {
+                    if let FormatPart::Expr(expr) = part {
+                        self.infer_expr(id, body, *expr);
+                    }
+                }
+                Ty::String
+            }
+            Expr::BoolLiteral { .. } => Ty::Bool,
+            Expr::Binary { op, lhs, rhs } => {
+                let lhs_ty = self.infer_expr(id, body, *lhs);
+                let rhs_ty = self.infer_expr(id, body, *rhs);
+                let equality = matches!(op, BinaryOp::Eq | BinaryOp::NotEq);
+
+                let operator = op.operator_trait();
+                match self.table.shallow_resolve(&lhs_ty) {
+                    // structs are compared field by field without an `Eq` impl
+                    ty @ Ty::Struct { .. }
+                        if !equality
+                            || method_resolution::lookup_operator(self.tree, &ty, operator)
+                                .is_some() =>
+                    {
+                        let args = [(*rhs, rhs_ty)];
+                        let ret = self.operator_call(id, body, idx, operator, (*lhs, ty), &args);
+                        if op.is_comparison() {
+                            Ty::Bool
+                        } else {
+                            ret
+                        }
+                    }
+                    _ => {
+                        if self.unify_or_report(&lhs_ty, &rhs_ty, id, body, *rhs) && !equality {
+                            self.check_operand(op, &lhs_ty, id, body, *lhs);
+                        }
+                        if op.is_comparison() {
+                            Ty::Bool
+                        } else {
+                            lhs_ty
+                        }
+                    }
+                }
+            }
+            Expr::Unary { op, expr } => {
+                let ty = self.infer_expr(id, body, *expr);
+                match self.table.shallow_resolve(&ty) {
+                    struct_ty @ Ty::Struct { .. } => {
+                        let op = op.operator_trait();
+                        self.operator_call(id, body, idx, op, (*expr, struct_ty), &[])
+                    }
+                    _ => {
+                        match op {
+                            UnaryOp::Neg => self.check_numeric(&ty, id, body, *expr),
+                        }
+                        ty
+                    }
+                }
+            }
+            Expr::VariableRef { .. } | Expr::Path { .. } => match self.resolutions.get(id, idx) {
+                Some(resolution) => self.resolution_ty(resolution),
+                None => Ty::Unknown,
+            },
+            Expr::Call {
+                callee,
+                args,
+                arg_names,
+            } => {
+                let callee_ty = self.infer_expr(id, body, *callee);
+                let function = match self.resolutions.get(id, *callee) {
+                    Some(Resolution::Item(Item::Function(function))) => Some(function),
+                    _ => None,
+                };
+                match self.order_args(id, body, idx, function, args, arg_names) {
+                    Some(args) => self.call(id, body, idx, *callee, callee_ty, &args),
+                    None => Ty::Unknown,
+                }
+            }
+            Expr::MethodCall {
+                receiver,
+                name,
+                args,
+                ..
+            } => self.infer_method_call(id, body, idx, *receiver, name, args),
+            Expr::Field { expr, name } => {
+                let ty = self.infer_expr(id, body, *expr);
+                let ty = self.table.shallow_resolve(&ty);
+                match self.field_ty(&ty, name) {
+                    Some(field_ty) => field_ty,
+                    None => {
+                        if !matches!(ty, Ty::Unknown | Ty::Infer(_) | Ty::SelfType) {
+                            let name = name.clone();
+                            self.report(TypeErrorKind::NoField { ty, name }, id, body, idx);
+                        }
+                        Ty::Unknown
+                    }
+                }
+            }
+            Expr::Index { base, index } => {
+                let base_ty = self.infer_expr(id, body, *base);
+                let index_ty = self.infer_expr(id, body, *index);
+                match self.table.shallow_resolve(&base_ty) {
+                    ty @ Ty::Struct { .. } => {
+                        let op = &drip_operator::INDEX;
+                        let args = [(*index, index_ty)];
+                        self.operator_call(id, body, idx, op, (*base, ty), &args)
+                    }
+                    ty => {
+                        let int = self.table.new_var(InferTy::Int);
+                        self.unify_or_report(&int, &index_ty, id, body, *index);
+                        match ty {
+                            Ty::Array(elem) => *elem,
+                            Ty::Unknown | Ty::Infer(InferTy::Var(_)) => Ty::Unknown,
+                            ty => {
+                                self.report(TypeErrorKind::NotIndexable { ty }, id, body, *base);
+                                Ty::Unknown
+                            }
+                        }
+                    }
+                }
+            }
+            Expr::Struct { name, fields } => self.infer_struct(id, body, idx, name, fields),
+            Expr::Closure {
+                params,
+                body: closure_body,
+            } => {
+                let params: Vec<_> = params
+                    .iter()
+                    .map(|param| self.lower_type_in(id, &param.ty))
+                    .collect();
+                self.closure_params.insert((id, idx), params.clone());
+
+                // `break` and `continue` can't reach the loops around a closure
+                let loops = mem::take(&mut self.loops);
+                let ret = self.infer_expr(id, body, *closure_body);
+                self.loops = loops;
+
+                Ty::Fn {
+                    params,
+                    ret: Box::new(ret),
+                }
+            }
+            Expr::Placeholder => Ty::Never,
+            Expr::Tuple { exprs } => Ty::Tuple(
+                exprs
+                    .iter()
+                    .map(|expr| self.infer_expr(id, body, *expr))
+                    .collect(),
+            ),
+            Expr::Array { exprs } => {
+                let elem = self.table.new_var(InferTy::Var);
+                for expr in exprs {
+                    let ty = self.infer_expr(id, body, *expr);
+                    self.unify_or_report(&elem, &ty, id, body, *expr);
+                }
+                Ty::Array(Box::new(elem))
+            }
+            Expr::Range { start, end, step } => {
+                let start_ty = self.infer_expr(id, body, *start);
+                let end_ty = self.infer_expr(id, body, *end);
+                let mut unified = self.unify_or_report(&start_ty, &end_ty, id, body, *end);
+                if let Some(step) = step {
+                    let step_ty = self.infer_expr(id, body, *step);
+                    unified &= self.unify_or_report(&start_ty, &step_ty, id, body, *step);
+                }
+                if unified {
+                    self.check_numeric(&start_ty, id, body, *start);
+                }
+                Ty::Range(Box::new(start_ty))
+            }
+            Expr::Block { stmts } => self.infer_stmts(id, body, stmts),
+            Expr::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                let condition_ty = self.infer_expr(id, body, *condition);
+                self.unify_or_report(&Ty::Bool, &condition_ty, id, body, *condition);
+                let then_ty = self.infer_expr(id, body, *then_branch);
+
+                // without an `else`, the value of the branch is thrown away
+                match else_branch {
+                    Some(else_branch) => {
+                        let else_ty = self.infer_expr(id, body, *else_branch);
+                        self.unify_or_report(&then_ty, &else_ty, id, body, *else_branch);
+                        match self.table.shallow_resolve(&then_ty) {
+                            Ty::Never => else_ty,
+                            _ => then_ty,
+                        }
+                    }
+                    None => Ty::unit(),
+                }
+            }
+            Expr::Loop { body: loop_body } => {
+                self.loops.push(LoopCtx::default());
+                self.infer_expr(id, body, *loop_body);
+                let ctx = self.loops.pop().unwrap();
+                match ctx.value {
+                    Some(ty) => ty,
+                    // a `loop` can only be left by breaking out of it
+                    None if !ctx.breaks => Ty::Never,
+                    None => Ty::unit(),
+                }
+            }
+            Expr::While {
+                condition,
+                body: loop_body,
+            } => {
+                let condition_ty = self.infer_expr(id, body, *condition);
+                self.unify_or_report(&Ty::Bool, &condition_ty, id, body, *condition);
+                self.infer_loop_body(id, body, *loop_body)
+            }
+            Expr::For {
+                pat,
+                index,
+                iterable,
+                body: loop_body,
+            } => {
+                let iterable_ty = self.infer_expr(id, body, *iterable);
+                let elem = match self.table.shallow_resolve(&iterable_ty) {
+                    Ty::Array(elem) | Ty::Range(elem) => *elem,
+                    Ty::Unknown | Ty::Infer(InferTy::Var(_)) => Ty::Unknown,
+                    ty => {
+                        self.report(TypeErrorKind::NotIterable { ty }, id, body, *iterable);
+                        Ty::Unknown
+                    }
+                };
+                self.infer_pat(id, body, *pat, &elem, *iterable);
+                if let Some(index) = index {
+                    let index_ty = self.table.new_var(InferTy::Int);
+                    self.infer_pat(id, body, *index, &index_ty, *iterable);
+                }
+                self.infer_loop_body(id, body, *loop_body)
+            }
+            Expr::Break { value } => {
+                let ty = value.map(|value| (self.infer_expr(id, body, value), value));
+                if let Some(ctx) = self.loops.last_mut() {
+                    ctx.breaks = true;
+                    if let Some((ty, value)) = ty {
+                        let table = &mut self.table;
+                        let expected = ctx
+                            .value
+                            .get_or_insert_with(|| table.new_var(InferTy::Var))
+                            .clone();
+                        self.unify_or_report(&expected, &ty, id, body, value);
+                    }
+                }
+                Ty::Never
+            }
+            Expr::Continue => Ty::Never,
+            Expr::Match { scrutinee, arms } => {
+                let scrutinee_ty = self.infer_expr(id, body, *scrutinee);
+
+                // a `match` without arms never produces a value
+                let mut ty = Ty::Never;
+                for arm in arms {
+                    for pat in &arm.pats {
+                        self.infer_pat(id, body, *pat, &scrutinee_ty, *scrutinee);
+                    }
+                    let arm_ty = self.infer_expr(id, body, arm.body);
+                    self.unify_or_report(&ty, &arm_ty, id, body, arm.body);
+                    if self.table.shallow_resolve(&ty) == Ty::Never {
+                        ty = arm_ty;
+                    }
+                }
+                ty
+            }
+        };
+
+        self.exprs.get_mut(&id).unwrap().insert(idx, ty.clone());
+        ty
+    }
+
+    /// Checks that `pat` can match a value of type `expected`. Patterns have
+    /// no expressions of their own, so mismatches that aren't about a literal
+    /// or range bound are reported at `scrutinee`.
+    fn infer_pat(
+        &mut self,
+        id: BodyId,
+        body: &Body,
+        pat: PatIdx,
+        expected: &Ty,
+        scrutinee: ExprIdx,
+    ) {
+        self.pats.insert((id, pat), expected.clone());
+
+        match &body.arenas[pat] {
+            Pat::Missing | Pat::Wildcard => {}
+            Pat::Ident { .. } => {
+                if let Some(resolution) = self.resolutions.pat(id, pat) {
+                    let ty = self.resolution_ty(resolution);
+                    self.unify_or_report(expected, &ty, id, body, scrutinee);
+                }
+            }
+            Pat::Literal { expr } => {
+                let ty = self.infer_expr(id, body, *expr);
+                self.unify_or_report(expected, &ty, id, body, *expr);
+            }
+            Pat::Range { start, end } => {
+                let start_ty = self.infer_expr(id, body, *start);
+                let end_ty = self.infer_expr(id, body, *end);
+                if self.unify_or_report(&start_ty, &end_ty, id, body, *end) {
+                    self.check_numeric(&start_ty, id, body, *start);
+                }
+                self.unify_or_report(expected, &start_ty, id, body, *start);
+            }
+            Pat::Tuple { pats } => {
+                let elems: Vec<_> = pats
+                    .iter()
+                    .map(|_| self.table.new_var(InferTy::Var))
+                    .collect();
+                let tuple = Ty::Tuple(elems.clone());
+                self.unify_or_report(expected, &tuple, id, body, scrutinee);
+                for (pat, elem) in pats.iter().zip(&elems) {
+                    self.infer_pat(id, body, *pat, elem, scrutinee);
+                }
+            }
+            Pat::Struct { name, fields } => {
+                let ty = lower_named_type(self.tree, name);
+                if matches!(ty, Ty::Struct { .. }) {
+                    self.unify_or_report(expected, &ty, id, body, scrutinee);
+                } else {
+                    let name = name.clone();
+                    self.report(TypeErrorKind::NotAStruct { name }, id, body, scrutinee);
+                }
+
+                for (name, pat) in fields {
+                    let field_ty = match self.field_ty(&ty, name) {
+                        Some(field_ty) => field_ty,
+                        None => {
+                            if matches!(ty, Ty::Struct { .. }) {
+                                let kind = TypeErrorKind::NoField {
+                                    ty: ty.clone(),
+                                    name: name.clone(),
+                                };
+                                self.report(kind, id, body, scrutinee);
+                            }
+                            Ty::Unknown
+                        }
+                    };
+                    self.infer_pat(id, body, *pat, &field_ty, scrutinee);
+                }
+            }
+        }
+    }
+
+    fn infer_struct(
+        &mut self,
+        id: BodyId,
+        body: &Body,
+        idx: ExprIdx,
+        name: &SmolStr,
+        fields: &[(Option<SmolStr>, ExprIdx)],
+    ) -> Ty {
+        let ty = if name == "Self" {
+            self.lower_type_in(id, &TypeRef::SelfType)
+        } else {
+            lower_named_type(self.tree, name)
+        };
+        let struct_id = match ty {
+            Ty::Struct { id, .. } => Some(id),
+            _ => {
+                let name = name.clone();
+                self.report(TypeErrorKind::NotAStruct { name }, id, body, idx);
+                None
+            }
+        };
+
+        let fields: Vec<_> = match struct_id {
+            Some(struct_id) => method_resolution::literal_fields(self.tree, struct_id, fields),
+            None => fields
+                .iter()
+                .map(|(name, value)| (name.as_ref(), *value))
+                .collect(),
+        };
+        for &(name, value) in &fields {
+            let value_ty = self.infer_expr(id, body, value);
+            let name = match name {
+                Some(name) => name,
+                None => {
+                    if let Some(struct_id) = struct_id {
+                        let count = method_resolution::all_fields(self.tree, struct_id).len();
+                        let kind = TypeErrorKind::TooManyFields {
+                            ty: ty.clone(),
+                            count,
+                        };
+                        self.report(kind, id, body, value);
+                    }
+                    continue;
+                }
+            };
+            match self.field_ty(&ty, name) {
+                Some(field_ty) => {
+                    self.unify_or_report(&field_ty, &value_ty, id, body, value);
+                }
+                None if struct_id.is_some() => {
+                    let kind = TypeErrorKind::NoField {
+                        ty: ty.clone(),
+                        name: name.clone(),
+                    };
+                    self.report(kind, id, body, value);
+                }
+                None => {}
+            }
+        }
+
+        if let Some(struct_id) = struct_id {
+            let names: Vec<_> = method_resolution::all_fields(self.tree, struct_id)
+                .into_iter()
+                .filter(|field| fields.iter().all(|(name, _)| *name != Some(&field.name)))
+                .map(|field| field.name.clone())
+                .collect();
+            if !names.is_empty() {
+                let kind = TypeErrorKind::MissingFields {
+                    ty: ty.clone(),
+                    names,
+                };
+                self.report(kind, id, body, idx);
+            }
+        }
+
+        ty
+    }
+
+    /// The type of a `while` or `for` loop with `loop_body`, which can also
+    /// be left without breaking.
+    fn infer_loop_body(&mut self, id: BodyId, body: &Body, loop_body: ExprIdx) -> Ty {
+        self.loops.push(LoopCtx::default());
+        self.infer_expr(id, body, loop_body);
+        let ctx = self.loops.pop().unwrap();
+        ctx.value.unwrap_or_else(Ty::unit)
+    }
+
+    fn infer_exprs(&mut self, id: BodyId, body: &Body, exprs: &[ExprIdx]) -> Vec<Ty> {
+        exprs
+            .iter()
+            .map(|expr| self.infer_expr(id, body, *expr))
+            .collect()
+    }
+
+    /// The type of calling `callee`, a value of type `callee_ty`, with `args`.
+    fn call(
+        &mut self,
+        id: BodyId,
+        body: &Body,
+        idx: ExprIdx,
+        callee: ExprIdx,
+        callee_ty: Ty,
+        args: &[ExprIdx],
+    ) -> Ty {
+        let arg_tys = self.infer_exprs(id, body, args);
+
+        match self.table.shallow_resolve(&callee_ty) {
+            Ty::Fn { params, ret } => {
+                self.check_args(&params, &arg_tys, id, body, idx, args);
+                *ret
+            }
+            ty @ Ty::Infer(InferTy::Var(_) | InferTy::Fn(_)) => {
+                let ret = self.table.new_var(InferTy::Var);
+                let fn_ty = Ty::Fn {
+                    params: arg_tys,
+                    ret: Box::new(ret.clone()),
+                };
+                self.table.unify(&ty, &fn_ty);
+                ret
+            }
+            Ty::Unknown => Ty::Unknown,
+            ty => {
+                self.report(TypeErrorKind::NotCallable { ty }, id, body, callee);
+                Ty::Unknown
+            }
+        }
+    }
+
+    fn infer_method_call(
+        &mut self,
+        id: BodyId,
+        body: &Body,
+        idx: ExprIdx,
+        receiver: ExprIdx,
+        name: &SmolStr,
+        args: &[ExprIdx],
+    ) -> Ty {
+        let receiver_ty = self.infer_expr(id, body, receiver);
+        let arg_names = match &body.arenas[idx] {
+            Expr::MethodCall { arg_names, .. } => arg_names.as_slice(),
+            _ => &[],
+        };
+
+        let ty = self.table.shallow_resolve(&receiver_ty);
+        let method = match &ty {
+            Ty::Unknown | Ty::Infer(_) => None,
+            Ty::SelfType => Some(match self.current_trait(id) {
+                Some(trait_id) => method_resolution::lookup_trait_method(self.tree, trait_id, name),
+                None => Err(MethodError::NotFound),
+            }),
+            _ => Some(method_resolution::lookup_method(self.tree, &ty, name)),
+        };
+
+        let function = match method {
+            Some(Ok(function)) => function,
+            None => {
+                self.infer_exprs(id, body, args);
+                return Ty::Unknown;
+            }
+            Some(Err(MethodError::NotFound)) => {
+                // a field holding a function, like `self.sound()`
+                if let Some(field_ty) = self.field_ty(&ty, name) {
+                    return match self.order_args(id, body, idx, None, args, arg_names) {
+                        Some(args) => self.call(id, body, idx, idx, field_ty, &args),
+                        None => Ty::Unknown,
+                    };
+                }
+                self.infer_exprs(id, body, args);
+                let name = name.clone();
+                self.report(TypeErrorKind::NoMethod { ty, name }, id, body, idx);
+                return Ty::Unknown;
+            }
+            Some(Err(MethodError::Ambiguous(_))) => {
+                self.infer_exprs(id, body, args);
+                let name = name.clone();
+                self.report(TypeErrorKind::AmbiguousMethod { ty, name }, id, body, idx);
+                return Ty::Unknown;
+            }
+        };
+        self.methods.insert((id, idx), function);
+
+        // the receiver is passed as `self`, which lookup made sure fits
+        let args = match self.order_args(id, body, idx, Some(function), args, arg_names) {
+            Some(args) => args,
+            None => return Ty::Unknown,
+        };
+        let arg_tys = self.infer_exprs(id, body, &args);
+        let signature = self.signature(function);
+        let params = signature.params[1..].to_vec();
+        let ret = signature.ret.clone();
+        self.check_args(&params, &arg_tys, id, body, idx, &args);
+        ret
+    }
+
+    /// `args` in the order of the parameters of `function` they're passed
+    /// to. An argument passed by name goes to the parameter of that name,
+    /// and any other one to the parameter at its position. If that fails,
+    /// the error is reported, and the arguments are only inferred.
+    fn order_args(
+        &mut self,
+        id: BodyId,
+        body: &Body,
+        idx: ExprIdx,
+        function: Option<FunctionId>,
+        args: &[ExprIdx],
+        arg_names: &[Option<SmolStr>],
+    ) -> Option<Vec<ExprIdx>> {
+        if arg_names.iter().all(Option::is_none) {
+            return Some(args.to_vec());
+        }
+
+        // a method's `self` is passed as the receiver
+        let params: Vec<_> = match function {
+            Some(function) => {
+                let params = &self.tree[function].params;
+                let skip = params.first().is_some_and(|param| param.name == "self");
+                params[skip as usize..]
+                    .iter()
+                    .map(|param| param.name.clone())
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        let mut order = Vec::new();
+        for (i, (arg, name)) in args.iter().zip(arg_names).enumerate() {
+            let param = match name {
+                Some(name) => match params.iter().position(|param| param == name) {
+                    Some(param) => param,
+                    None => {
+                        let name = name.clone();
+                        self.report(TypeErrorKind::NoParam { name }, id, body, *arg);
+                        self.infer_exprs(id, body, args);
+                        return None;
+                    }
+                },
+                None => i,
+            };
+            if order.contains(&param) {
+                let name = params.get(param).cloned().unwrap_or_default();
+                self.report(TypeErrorKind::ArgTwice { name }, id, body, *arg);
+                self.infer_exprs(id, body, args);
+                return None;
+            }
+            order.push(param);
+        }
+
+        let mut ordered: Vec<_> = order.iter().copied().zip(args.iter().copied()).collect();
+        ordered.sort_by_key(|(param, _)| *param);
+        self.arg_orders.insert((id, idx), order);
+        Some(ordered.into_iter().map(|(_, arg)| arg).collect())
+    }
+
+    fn check_args(
+        &mut self,
+        params: &[Ty],
+        arg_tys: &[Ty],
+        id: BodyId,
+        body: &Body,
+        idx: ExprIdx,
+        args: &[ExprIdx],
+    ) {
+        if params.len() != args.len() {
+            let kind = TypeErrorKind::ArgCount {
+                expected: params.len(),
+                found: args.len(),
+            };
+            self.report(kind, id, body, idx);
+        }
+        for ((param, arg_ty), arg) in params.iter().zip(arg_tys).zip(args) {
+            self.unify_or_report(param, arg_ty, id, body, *arg);
+        }
+    }
+
+    /// The type of the operator expression `idx`, whose operand `receiver`
+    /// is a struct. The operator calls the member of `op` the struct
+    /// implements, with the other operands as `args`.
+    fn operator_call(
+        &mut self,
+        id: BodyId,
+        body: &Body,
+        idx: ExprIdx,
+        op: &OperatorTrait,
+        receiver: (ExprIdx, Ty),
+        args: &[(ExprIdx, Ty)],
+    ) -> Ty {
+        let (operand, ty) = receiver;
+        match self.operator_method(id, body, op, &ty, args) {
+            Some((function, ret)) => {
+                self.methods.insert((id, idx), function);
+                ret
+            }
+            None => {
+                self.report_missing_impl(op, ty, id, body, operand);
+                Ty::Unknown
+            }
+        }
+    }
+
+    /// The member of `op` that `ty` implements and its return type, after
+    /// checking `args` against its parameters after `self`.
+    fn operator_method(
+        &mut self,
+        id: BodyId,
+        body: &Body,
+        op: &OperatorTrait,
+        ty: &Ty,
+        args: &[(ExprIdx, Ty)],
+    ) -> Option<(FunctionId, Ty)> {
+        let function = method_resolution::lookup_operator(self.tree, ty, op)?;
+        let signature = self.signature(function);
+        let params = signature.params[1..].to_vec();
+        let ret = signature.ret.clone();
+        let (exprs, tys): (Vec<_>, Vec<_>) = args.iter().cloned().unzip();
+        for ((param, ty), expr) in params.iter().zip(&tys).zip(&exprs) {
+            self.unify_or_report(param, ty, id, body, *expr);
+        }
+        Some((function, ret))
+    }
+
+    fn report_missing_impl(
+        &mut self,
+        op: &OperatorTrait,
+        ty: Ty,
+        id: BodyId,
+        body: &Body,
+        expr: ExprIdx,
+    ) {
+        let trait_name = op.name.into();
+        self.report(
+            TypeErrorKind::MissingOperatorImpl { ty, trait_name },
+            id,
+            body,
+            expr,
+        );
+    }
+
+    /// Reports an assignment to `target` that goes through an `Index`
+    /// impl, like `grid[0] = 1` or `grid[0].x = 1`, which only reads.
+    fn check_index_assign(&mut self, id: BodyId, body: &Body, target: ExprIdx) {
+        let mut place = target;
+        loop {
+            place = match &body.arenas[place] {
+                Expr::Field { expr, .. } => *expr,
+                Expr::Index { base, .. } if self.methods.contains_key(&(id, place)) => {
+                    let ty = self.table.resolve(&self.exprs[&id][*base]);
+                    self.report(TypeErrorKind::IndexAssign { ty }, id, body, place);
+                    return;
+                }
+                Expr::Index { base, .. } => *base,
+                _ => return,
+            };
+        }
+    }
+
+    fn field_ty(&mut self, ty: &Ty, name: &str) -> Option<Ty> {
+        let id = match ty {
+            Ty::Struct { id, .. } => *id,
+            _ => return None,
+        };
+        let field = method_resolution::lookup_field(self.tree, id, name)?;
+
+        Some(match &field.ty {
+            TypeRef::SelfType => ty.clone(),
+            // the signature isn't kept with the struct, so every use infers its own
+            TypeRef::Fn => self.table.new_var(InferTy::Fn),
+            field_ty => lower_type_ref(self.tree, field_ty),
+        })
+    }
+
+    fn current_trait(&self, id: BodyId) -> Option<TraitId> {
+        match id {
+            BodyId::Function(function) => self.traits.get(&function).copied(),
+            BodyId::Root => None,
+        }
+    }
+
+    fn check_operand(&mut self, op: &BinaryOp, ty: &Ty, id: BodyId, body: &Body, expr: ExprIdx) {
+        let ty = self.table.shallow_resolve(ty);
+        let valid = match ty {
+            Ty::String => *op == BinaryOp::Add,
+            Ty::Unknown | Ty::Infer(InferTy::Var(_)) => true,
+            ref ty => ty.is_numeric(),
+        };
+
+        if !valid {
+            self.report(TypeErrorKind::InvalidOperand { ty }, id, body, expr);
+        }
+    }
+
+    fn check_numeric(&mut self, ty: &Ty, id: BodyId, body: &Body, expr: ExprIdx) {
+        let ty = self.table.shallow_resolve(ty);
+        if !matches!(ty, Ty::Unknown | Ty::Infer(InferTy::Var(_))) && !ty.is_numeric() {
+            self.report(TypeErrorKind::InvalidOperand { ty }, id, body, expr);
+        }
+    }
+
+    fn resolution_ty(&mut self, resolution: Resolution) -> Ty {
+        match resolution {
+            Resolution::Local { body, stmt } => self.binding(body, stmt),
+            Resolution::Global { stmt } => self.binding(BodyId::Root, stmt),
+            Resolution::Param { function, index } => self.signature(function).params[index].clone(),
+            Resolution::SelfField {
+                function,
+                strukt,
+                index,
+            } => {
+                let self_ty = self.signature(function).params[0].clone();
+                let name = &self.tree[strukt].fields[index].name;
+                self.field_ty(&self_ty, name).unwrap_or(Ty::Unknown)
+            }
+            Resolution::PatBinding { body, pat } => {
+                self.pats.get(&(body, pat)).cloned().unwrap_or(Ty::Unknown)
+            }
+            Resolution::ClosureParam {
+                body,
+                closure,
+                index,
+            } => self
+                .closure_params
+                .get(&(body, closure))
+                .map_or(Ty::Unknown, |params| params[index].clone()),
+            Resolution::Item(Item::Function(function)) => self.fn_ty(function),
+            Resolution::Item(_) => Ty::Unknown,
+            // both builtins take a value of any type
+            Resolution::Builtin(Builtin::Print | Builtin::Println) => Ty::Fn {
+                params: vec![Ty::Unknown],
+                ret: Box::new(Ty::unit()),
+            },
+        }
+    }
+
+    fn binding(&mut self, body: BodyId, stmt: usize) -> Ty {
+        let table = &mut self.table;
+        self.bindings
+            .entry((body, stmt))
+            .or_insert_with(|| table.new_var(InferTy::Var))
+            .clone()
+    }
+
+    fn fn_ty(&mut self, function: FunctionId) -> Ty {
+        let signature = self.signature(function);
+        Ty::Fn {
+            params: signature.params.clone(),
+            ret: Box::new(signature.ret.clone()),
+        }
+    }
+
+    fn signature(&mut self, function: FunctionId) -> &Signature {
+        if !self.signatures.contains_key(&function) {
+            let tree = self.tree;
+            let data = &tree[function];
+            let params = data
+                .params
+                .iter()
+                .map(|param| self.lower_type(function, &param.ty))
+                .collect();
+            // an undeclared return type is inferred from the body
+            let ret = match &data.ret[..] {
+                [] => self.table.new_var(InferTy::Var),
+                [ty] => self.lower_type(function, ty),
+                tys => Ty::Tuple(tys.iter().map(|ty| self.lower_type(function, ty)).collect()),
+            };
+            self.signatures.insert(function, Signature { params, ret });
+        }
+
+        &self.signatures[&function]
+    }
+
+    /// The type `ty` names inside the body `id`.
+    fn lower_type_in(&mut self, id: BodyId, ty: &TypeRef) -> Ty {
+        match id {
+            BodyId::Function(function) => self.lower_type(function, ty),
+            BodyId::Root => match ty {
+                TypeRef::Missing => self.table.new_var(InferTy::Var),
+                TypeRef::Fn => self.table.new_var(InferTy::Fn),
+                ty => lower_type_ref(self.tree, ty),
+            },
+        }
+    }
+
+    fn lower_type(&mut self, function: FunctionId, ty: &TypeRef) -> Ty {
+        match ty {
+            TypeRef::Missing => self.table.new_var(InferTy::Var),
+            TypeRef::Fn => self.table.new_var(InferTy::Fn),
+            TypeRef::SelfType => match self.self_tys.get(&function).cloned() {
+                Some(TypeRef::SelfType) | None => Ty::SelfType,
+                Some(self_ty) => self.lower_type(function, &self_ty),
+            },
+            TypeRef::Named(name) => lower_named_type(self.tree, name),
+        }
+    }
+
+    /// Unifies `expected` with the type `found` of `expr`, and reports a
+    /// mismatch if they don't fit.
+    fn unify_or_report(
+        &mut self,
+        expected: &Ty,
+        found: &Ty,
+        id: BodyId,
+        body: &Body,
+        expr: ExprIdx,
+    ) -> bool {
+        if self.table.unify(expected, found) {
+            return true;
+        }
+
+        let kind = TypeErrorKind::Mismatch {
+            expected: expected.clone(),
+            found: found.clone(),
+        };
+        self.report(kind, id, body, expr);
+        false
+    }
+
+    fn report(&mut self, kind: TypeErrorKind, id: BodyId, body: &Body, expr: ExprIdx) {
+        let range = body
+            .arenas
+            .source_map()
+            .expr_syntax(expr)
+            .map_or_else(TextRange::default, |source| source.range());
+
+        self.errors.push(TypeError {
+            kind,
+            body: id,
+            expr,
+            range,
+        });
+    }
+
+    fn finish(mut self) -> InferenceResults {
+        let table = &self.table;
+        let mut results = InferenceResults::default();
+
+        for (id, exprs) in &self.exprs {
+            let mut result = InferenceResult::default();
+            for (idx, ty) in exprs.iter() {
+                result.exprs.insert(idx, table.resolve(ty));
+            }
+            results.bodies.insert(*id, result);
+        }
+
+        for ((body, pat), ty) in &self.pats {
+            if let Some(result) = results.bodies.get_mut(body) {
+                result.pats.insert(*pat, table.resolve(ty));
+            }
+        }
+
+        for ((body, expr), function) in &self.methods {
+            if let Some(result) = results.bodies.get_mut(body) {
+                result.methods.insert(*expr, *function);
+            }
+        }
+
+        for ((body, stmt), function) in &self.assignments {
+            if let Some(result) = results.bodies.get_mut(body) {
+                result.assignments.insert(*stmt, *function);
+            }
+        }
+
+        for ((body, call), order) in &self.arg_orders {
+            if let Some(result) = results.bodies.get_mut(body) {
+                result.arg_orders.insert(*call, order.clone());
+            }
+        }
+
+        for ((body, stmt), ty) in &self.bindings {
+            if let Some(result) = results.bodies.get_mut(body) {
+                result.bindings.insert(*stmt, table.resolve(ty));
+            }
+        }
+
+        let functions: Vec<_> = self.signatures.keys().copied().collect();
+        for function in functions {
+            let ty = self.fn_ty(function);
+            results.functions.insert(function, self.table.resolve(&ty));
+        }
+
+        let table = &self.table;
+        results.errors = mem::take(&mut self.errors)
+            .into_iter()
+            .map(|mut error| {
+                error.kind = error.kind.map_tys(|ty| table.resolve_for_error(ty));
+                error
+            })
+            .collect();
+
+        results
+    }
+}
+
+/// The type `ty` names outside of any impl, where `Self` stays
+/// [`Ty::SelfType`]. What `Fn()` stands for is only known to inference.
+pub(crate) fn lower_type_ref(tree: &ItemTree, ty: &TypeRef) -> Ty {
+    match ty {
+        TypeRef::Missing | TypeRef::Fn => Ty::Unknown,
+        TypeRef::SelfType => Ty::SelfType,
+        TypeRef::Named(name) => lower_named_type(tree, name),
+    }
+}
+
+pub fn struct_ty(tree: &ItemTree, id: StructId) -> Ty {
+    Ty::Struct {
+        id,
+        name: tree[id].name.clone(),
+    }
+}
+
+fn lower_named_type(tree: &ItemTree, name: &str) -> Ty {
+    if let Some(ty) = IntTy::ALL.iter().find(|ty| ty.name() == name) {
+        return Ty::Int(*ty);
+    }
+
+    match name {
+        "f32" => Ty::Float(FloatTy::F32),
+        "f64" => Ty::Float(FloatTy::F64),
+        "bool" => Ty::Bool,
+        "string" | "String" => Ty::String,
+        _ => tree
+            .structs()
+            .find(|(_, s)| s.name == name)
+            .map_or(Ty::Unknown, |(id, _)| struct_ty(tree, id)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolve::resolve;
+    use drip_ast::AstNode;
+    use text_size::TextSize;
+
+    fn infer_source(input: &str) -> (ItemTree, Body, InferenceResults) {
+        let root = drip_ast::Root::cast(drip_parser::parse(input).syntax()).unwrap();
+        let (tree, body) = crate::lower(root);
+        let resolutions = resolve(&tree, &body);
+        let results = infer(&tree, &body, &resolutions);
+        (tree, body, results)
+    }
+
+    /// The type of every root binding, in order.
+    fn binding_tys(input: &str) -> Vec<String> {
+        let (_, body, results) = infer_source(input);
+        assert_eq!(errors(&results), Vec::<String>::new());
+
+        let root = results.body(BodyId::Root);
+        (0..body.stmts.len())
+            .filter_map(|stmt| root.binding(stmt))
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    fn errors(results: &InferenceResults) -> Vec<String> {
+        results.errors().iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn infer_literals_and_default_number_types() {
+        assert_eq!(
+            binding_tys("a :: 1\nb :: 3.14\nhello := \"hello\"\nt :: (a, b, ())\narr :: [4, 5, 6]"),
+            ["i32", "f64", "string", "(i32, f64, ())", "[i32]"]
+        );
+    }
+
+    #[test]
+    fn infer_integer_literals_as_floats() {
+        assert_eq!(
+            binding_tys("PI :: 3.14159265358979323846\nTAU :: PI * 2"),
+            ["f64", "f64"]
+        );
+    }
+
+    #[test]
+    fn infer_several_names_defined_at_once() {
+        let (_, body, results) = infer_source("a, b, c :: 10, 3, 5");
+        assert_eq!(errors(&results), Vec::<String>::new());
+
+        let root = results.body(BodyId::Root);
+        let tys: Vec<_> = body
+            .arenas
+            .pats()
+            .map(|(pat, _)| root[pat].to_string())
+            .collect();
+        assert_eq!(tys, ["i32", "i32", "i32", "(i32, i32, i32)"]);
+    }
+
+    #[test]
+    fn infer_literal_type_from_use() {
+        let input = "f :: (x: u8) -> u8 { x }\na :: 1\nb :: f(a)";
+        assert_eq!(binding_tys(input), ["u8", "u8"]);
+    }
+
+    #[test]
+    fn infer_grouped_params_and_return_type() {
+        let (tree, _, results) = infer_source(
+            "mul_add :: (a, b, c: i32) -> i32 {\n    a + b * c\n}\nsquare :: (x: f32) { x * x }",
+        );
+        assert_eq!(errors(&results), Vec::<String>::new());
+
+        let tys: Vec<_> = tree
+            .functions()
+            .map(|(id, _)| results.function(id).to_string())
+            .collect();
+        assert_eq!(tys, ["fn(i32, i32, i32) -> i32", "fn(f32) -> f32"]);
+    }
+
+    #[test]
+    fn infer_self_and_struct_types() {
+        let (tree, _, results) = infer_source(
+            "Vec2 :: struct { x: f32 }\nVec2 :: impl { id :: (v: Self) -> Vec2 { v }\nget :: () -> f32 { self.x } }\nShow :: trait { show :: (s: Self) { s } }",
+        );
+        assert_eq!(errors(&results), Vec::<String>::new());
+
+        let tys: Vec<_> = tree
+            .functions()
+            .map(|(id, _)| results.function(id).to_string())
+            .collect();
+        // `get` uses `self` and `show` is a trait member, so both take it
+        assert_eq!(
+            tys,
+            [
+                "fn(Vec2) -> Vec2",
+                "fn(Vec2) -> f32",
+                "fn(Self, Self) -> Self"
+            ]
+        );
+    }
+
+    #[test]
+    fn type_every_expression() {
+        let (_, body, results) = infer_source(
+            "add_and_sub :: (a, b: i32) -> (i32, i32) { (a + b, a - b) }\nx :: add_and_sub(10, 5)",
+        );
+        let root = results.body(BodyId::Root);
+
+        let tys: Vec<_> = body
+            .arenas
+            .exprs
+            .iter()
+            .map(|(idx, _)| root[idx].to_string())
+            .collect();
+        assert_eq!(
+            tys,
+            ["fn(i32, i32) -> (i32, i32)", "i32", "i32", "(i32, i32)"]
+        );
+    }
+
+    #[test]
+    fn infer_method_calls_and_fields() {
+        let input = "Vec3 :: struct { x: i32 }
+Vec3 :: impl {
+    plus :: (self, n: i32) -> Self { self }
+}
+f :: (v: Vec3) { v.plus(1).x }
+g :: (v: Vec3) { v.minus() + v.y + v.plus(\"a\").x }";
+        let (tree, _, results) = infer_source(input);
+
+        let (f, function) = tree.functions().find(|(_, f)| f.name == "f").unwrap();
+        assert_eq!(results.function(f).to_string(), "fn(Vec3) -> i32");
+
+        let (plus, _) = tree.functions().find(|(_, f)| f.name == "plus").unwrap();
+        let call = function
+            .body
+            .arenas
+            .exprs
+            .iter()
+            .find(|(_, expr)| matches!(expr, Expr::MethodCall { .. }))
+            .map(|(idx, _)| idx)
+            .unwrap();
+        let body = results.body(BodyId::Function(f));
+        assert_eq!(body.method_resolution(call), Some(plus));
+        assert_eq!(body[call].to_string(), "Vec3");
+
+        assert_eq!(
+            errors(&results),
+            [
+                "error at 135..145: no method `minus` on `Vec3`",
+                "error at 147..151: no field `y` on `Vec3`",
+                "error at 160..163: expected `i32`, found `string`",
+            ]
+        );
+    }
+
+    #[test]
+    fn infer_control_flow() {
+        let input = "x :: for i in 0..8 { if i == 5 { break i } }
+y :: if x < 3 { 1.5 } else { 2.0 }
+z :: loop { break \"done\" }
+n := 3
+w := while n > 0 { n -= 1 }";
+        assert_eq!(binding_tys(input), ["i32", "f64", "string", "i32", "()"]);
+    }
+
+    #[test]
+    fn infer_match_arms_and_bindings() {
+        let input = "Vec2 :: struct { x: f32, y: f32 }
+v := Vec2 { x: 1.0, y: 2.0 }
+a := match v { Vec2 { x: 0.0, y } => y\n_ => 1.0 }
+b := match (1, true) { (n, true) => n\n(_, false) => break }";
+        assert_eq!(binding_tys(input), ["Vec2", "f32", "i32"]);
+
+        let (_, _, results) = infer_source("x := 1\ny := match x { \"a\" => 1\nPoint { z } => 2 }");
+        assert_eq!(
+            errors(&results),
+            [
+                "error at 22..26: expected `i32`, found `string`",
+                "error at 18..20: `Point` is not a struct",
+            ]
+        );
+    }
+
+    #[test]
+    fn infer_closures_struct_literals_and_indexing() {
+        let input = "Vec2 :: struct { x, y: f32 }
+x :: 1.0
+v :: Vec2 { x, y: 2.0 }
+add :: |a: i32, b: i32| a + b
+arr :: [add(1, 2), 4]
+first :: arr[0]
+s :: \"x = {x}\"";
+        assert_eq!(
+            binding_tys(input),
+            [
+                "f32",
+                "Vec2",
+                "fn(i32, i32) -> i32",
+                "[i32]",
+                "i32",
+                "string"
+            ]
+        );
+
+        let (_, _, results) = infer_source("P :: struct { x, y: i32 }\np :: P { x: 1 }\nq :: 1[0]");
+        assert_eq!(
+            errors(&results),
+            [
+                "error at 31..42: missing field `y` in `P`",
+                "error at 47..48: `i32` can't be indexed",
+            ]
+        );
+
+        let (_, _, results) = infer_source("P :: struct { x, y: i32 }\np :: P { 1, 2.0, 3 }");
+        assert_eq!(
+            errors(&results),
+            [
+                "error at 38..41: expected `i32`, found `f64`",
+                "error at 43..45: `P` only has 2 fields",
+            ]
+        );
+    }
+
+    #[test]
+    fn report_mismatches_with_source_ranges() {
+        let (_, _, results) = infer_source(
+            "a :: 1 + \"one\"\nf :: (x: i32) -> i32 { x }\nb :: f(1.5, 2)\nc :: [1, \"a\"]\nd :: a(1)\ne :: \"a\" * \"b\"",
+        );
+
+        assert_eq!(
+            errors(&results),
+            [
+                "error at 9..15: expected `i32`, found `string`",
+                "error at 47..57: expected 1 arguments, found 2",
+                "error at 49..52: expected `i32`, found `f64`",
+                "error at 66..69: expected `i32`, found `string`",
+                "error at 76..77: `i32` is not a function",
+                "error at 86..90: `string` can't be used with this operator",
+            ]
+        );
+    }
+
+    #[test]
+    fn check_named_args_against_params() {
+        let input = "f :: (x: i32, y: string) {}
+a :: f(y: \"a\", x: 1)
+b :: f(1, z: 2)
+c :: f(1, x: 2)
+d :: f(y: 1, x: 2)";
+        let (_, body, results) = infer_source(input);
+        let (call, _) = body
+            .arenas
+            .exprs
+            .iter()
+            .find(|(_, expr)| matches!(expr, Expr::Call { .. }))
+            .unwrap();
+        assert_eq!(
+            results.body(BodyId::Root).arg_order(call),
+            Some(&[1, 0][..])
+        );
+
+        assert_eq!(
+            errors(&results),
+            [
+                "error at 62..63: no parameter named `z`",
+                "error at 78..79: `x` is given more than one argument",
+                "error at 91..92: expected `string`, found `i32`",
+            ]
+        );
+    }
+
+    #[test]
+    fn infer_fn_types_from_use() {
+        assert_eq!(
+            binding_tys(
+                "id :: (f: Fn()) -> Fn() { f }\nadd :: (a, b: i32) -> i32 { a + b }\ng :: id(add)\nx :: g(1, 2)"
+            ),
+            ["fn(i32, i32) -> i32", "i32"]
+        );
+
+        let (_, _, results) = infer_source(
+            "A :: struct { f: Fn() }\na :: A { f: 5 }\nb :: A { f: |x: i32| x }\nc :: b.f(1)",
+        );
+        assert_eq!(
+            errors(&results),
+            ["error at 36..38: expected `Fn()`, found `i32`"]
+        );
+    }
+
+    const VEC2: &str = "Vec2 :: struct { x, y: f32 }
+Vec2 <| Add :: impl { add :: (self, other: Vec2) -> Vec2 { Vec2 { x: self.x + other.x, y: self.y + other.y } } }
+Vec2 <| Mul :: impl { mul :: (self, by: f32) -> Vec2 { Vec2 { x: self.x * by, y: self.y * by } } }
+Vec2 <| Neg :: impl { neg :: (self) -> Vec2 { Vec2 { x: -self.x, y: -self.y } } }
+Vec2 <| Ord :: impl { cmp :: (self, other: Self) -> i32 { 0 } }
+Vec2 <| Index :: impl { index :: (self, i: i32) -> f32 { if i == 0 { self.x } else { self.y } } }
+Vec2 <| AddAssign :: impl { add_assign :: (self, other: Vec2) { self.x += other.x } }
+";
+
+    #[test]
+    fn infer_operators_on_structs() {
+        let input = format!(
+            "{}a :: Vec2 {{ x: 1.0, y: 2.0 }}\nb :: a + a\nc :: a * 2.0\nd :: -a\ne :: a < b\nf :: a[1]\ng :: a == b\nh := a\nh += b",
+            VEC2
+        );
+        assert_eq!(
+            binding_tys(&input),
+            ["Vec2", "Vec2", "Vec2", "Vec2", "bool", "f32", "bool", "Vec2"]
+        );
+
+        let (_, body, results) = infer_source(&input);
+        let root = results.body(BodyId::Root);
+        let stmt = body.top_level[body.top_level.len() - 1];
+        assert!(root.assign_resolution(stmt).is_some());
+    }
+
+    #[test]
+    fn report_operators_without_impls() {
+        let input = format!(
+            "{}a :: Vec2 {{ x: 1.0, y: 2.0 }}\nb :: a - a\nc :: a * a\nd :: a[\"x\"]\nh := a\nh -= a\nh[0] = 1.0\nP :: struct {{ n: i32 }}\nq :: P {{ n: 1 }} == P {{ n: 2 }}",
+            VEC2
+        );
+        let (_, _, results) = infer_source(&input);
+        let offset = VEC2.len();
+        let errors: Vec<_> = results
+            .errors()
+            .iter()
+            .map(|error| {
+                let range = error.range() - TextSize::from(offset as u32);
+                format!("{:?}: {}", range, error.kind())
+            })
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                "34..36: `Vec2` doesn't implement `Sub`",
+                "49..51: expected `f32`, found `Vec2`",
+                "58..61: expected `i32`, found `string`",
+                "70..72: `Vec2` doesn't implement `SubAssign`",
+                "77..82: cannot assign to an index of `Vec2`, which implements `Index`",
+            ]
+        );
+    }
+}
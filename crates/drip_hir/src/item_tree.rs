@@ -0,0 +1,535 @@
+//! Functions, structs, traits and impls, lowered out of the statements they're
+//! defined in.
+//!
+//! Items are stored in one arena per kind inside an [`ItemTree`]; the body
+//! they were defined in refers to them with a [`Stmt::Item`]. Items can be
+//! nested, e.g. a function defined inside another function's body.
+
+use crate::{BodyArenas, Expr, ExprIdx, Pat, PatIdx, Stmt};
+use drip_ast::{self as ast, AstPtr, AstToken};
+use la_arena::{Arena, Idx};
+use smol_str::SmolStr;
+use std::mem;
+use std::ops::Index;
+
+pub type FunctionId = Idx<Function>;
+pub type StructId = Idx<Struct>;
+pub type TraitId = Idx<Trait>;
+pub type ImplId = Idx<Impl>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Item {
+    Function(FunctionId),
+    Struct(StructId),
+    Trait(TraitId),
+    Impl(ImplId),
+}
+
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct ItemTree {
+    functions: Arena<Function>,
+    structs: Arena<Struct>,
+    traits: Arena<Trait>,
+    impls: Arena<Impl>,
+}
+
+impl ItemTree {
+    pub fn functions(&self) -> impl Iterator<Item = (FunctionId, &Function)> {
+        self.functions.iter()
+    }
+
+    pub fn structs(&self) -> impl Iterator<Item = (StructId, &Struct)> {
+        self.structs.iter()
+    }
+
+    pub fn traits(&self) -> impl Iterator<Item = (TraitId, &Trait)> {
+        self.traits.iter()
+    }
+
+    pub fn impls(&self) -> impl Iterator<Item = (ImplId, &Impl)> {
+        self.impls.iter()
+    }
+}
+
+impl Index<FunctionId> for ItemTree {
+    type Output = Function;
+
+    fn index(&self, id: FunctionId) -> &Function {
+        &self.functions[id]
+    }
+}
+
+impl Index<StructId> for ItemTree {
+    type Output = Struct;
+
+    fn index(&self, id: StructId) -> &Struct {
+        &self.structs[id]
+    }
+}
+
+impl Index<TraitId> for ItemTree {
+    type Output = Trait;
+
+    fn index(&self, id: TraitId) -> &Trait {
+        &self.traits[id]
+    }
+}
+
+impl Index<ImplId> for ItemTree {
+    type Output = Impl;
+
+    fn index(&self, id: ImplId) -> &Impl {
+        &self.impls[id]
+    }
+}
+
+/// A reference to a type by name, as written in a signature.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TypeRef {
+    Missing,
+    SelfType,
+    /// `Fn()`, a function of any signature.
+    Fn,
+    Named(SmolStr),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Function {
+    pub name: SmolStr,
+    pub params: Vec<Param>,
+    pub ret: Vec<TypeRef>,
+    pub body: Body,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Param {
+    pub name: SmolStr,
+    pub ty: TypeRef,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Struct {
+    pub name: SmolStr,
+    /// The struct after `<|`, whose fields and impls this one inherits.
+    pub parent: Option<TypeRef>,
+    pub fields: Vec<Field>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Field {
+    pub name: SmolStr,
+    pub ty: TypeRef,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Trait {
+    pub name: SmolStr,
+    pub types: Vec<SmolStr>,
+    pub functions: Vec<FunctionId>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Impl {
+    pub self_ty: TypeRef,
+    pub trait_ty: Option<TypeRef>,
+    pub functions: Vec<FunctionId>,
+}
+
+/// The statements of a function (or of the whole file) and the expressions
+/// they refer to.
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct Body {
+    pub arenas: BodyArenas,
+    /// Every statement of the body, including the ones inside blocks.
+    /// Bindings are referred to by the index of their statement here.
+    pub stmts: Vec<Stmt>,
+    /// The statements directly in the body, in order.
+    pub top_level: Vec<usize>,
+}
+
+impl Body {
+    /// The `a, b :: value` statement that binds the name `pat`, and which
+    /// element of the value it's bound to.
+    pub fn tuple_def(&self, pat: PatIdx) -> Option<(usize, usize)> {
+        self.stmts
+            .iter()
+            .enumerate()
+            .find_map(|(idx, stmt)| match stmt {
+                Stmt::TupleDef { pat: names, .. } => match &self.arenas[*names] {
+                    Pat::Tuple { pats } => {
+                        let index = pats.iter().position(|name| *name == pat)?;
+                        Some((idx, index))
+                    }
+                    _ => None,
+                },
+                _ => None,
+            })
+    }
+
+    /// Whether the value of `expr` is thrown away where it's written: it's a
+    /// statement of its own, or all of a block whose value isn't used, like
+    /// the body of a loop. A `...` there can be skipped when it's run.
+    /// `returns_value` is whether the body's last statement is the value of
+    /// its function.
+    pub fn value_unused(&self, expr: ExprIdx, returns_value: bool) -> bool {
+        let stmt = self.stmts.iter().position(|stmt| *stmt == Stmt::Expr(expr));
+        if let Some(stmt) = stmt {
+            if self.top_level.contains(&stmt) {
+                return self.top_level.last() != Some(&stmt) || !returns_value;
+            }
+            return self.arenas.exprs.iter().any(|(idx, parent)| match parent {
+                Expr::Block { stmts } if stmts.contains(&stmt) => {
+                    stmts.last() != Some(&stmt) || self.value_unused(idx, returns_value)
+                }
+                _ => false,
+            });
+        }
+
+        self.arenas.exprs.iter().any(|(idx, parent)| match parent {
+            Expr::Loop { body } | Expr::While { body, .. } | Expr::For { body, .. } => {
+                *body == expr
+            }
+            Expr::If {
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                (*then_branch == expr && else_branch.is_none())
+                    || ((*then_branch == expr || *else_branch == Some(expr))
+                        && self.value_unused(idx, returns_value))
+            }
+            Expr::Match { arms, .. } => {
+                arms.iter().any(|arm| arm.body == expr) && self.value_unused(idx, returns_value)
+            }
+            _ => false,
+        })
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct ItemTreeBuilder {
+    tree: ItemTree,
+}
+
+impl ItemTreeBuilder {
+    pub(crate) fn finish(self) -> ItemTree {
+        self.tree
+    }
+
+    pub(crate) fn lower_body(&mut self, stmts: impl Iterator<Item = ast::Stmt>) -> Body {
+        let mut arenas = BodyArenas::default();
+        let top_level = stmts
+            .filter_map(|stmt| {
+                let lowered = match self.lower_item(&stmt) {
+                    Some(item) => Stmt::Item(item),
+                    None => arenas.lower_stmt(stmt.clone())?,
+                };
+                Some(arenas.push_stmt(lowered, &stmt))
+            })
+            .collect();
+
+        Body {
+            stmts: mem::take(&mut arenas.stmts),
+            top_level,
+            arenas,
+        }
+    }
+
+    fn lower_item(&mut self, stmt: &ast::Stmt) -> Option<Item> {
+        let item = match stmt {
+            ast::Stmt::FnDef(ast) => Item::Function(self.lower_function(ast, ImplicitSelf::Never)?),
+            ast::Stmt::StructDef(ast) => Item::Struct(self.lower_struct(ast)?),
+            ast::Stmt::TraitDef(ast) => Item::Trait(self.lower_trait(ast)?),
+            ast::Stmt::ImplDef(ast) => Item::Impl(self.lower_impl(ast)),
+            _ => return None,
+        };
+
+        Some(item)
+    }
+
+    fn lower_function(
+        &mut self,
+        ast: &ast::FnDef,
+        implicit_self: ImplicitSelf,
+    ) -> Option<FunctionId> {
+        let name = ast.name()?.text().into();
+        let (mut params, param_ptrs) = lower_params(ast.params());
+        let ret = ast.return_types().map(|ty| lower_type(Some(ty))).collect();
+        let mut body = self.lower_body(ast.body());
+        body.arenas.source_map.params = param_ptrs.into_iter().map(Some).collect();
+
+        let takes_self = match implicit_self {
+            ImplicitSelf::Never => false,
+            ImplicitSelf::IfUsed => body
+                .arenas
+                .exprs
+                .iter()
+                .any(|(_, expr)| matches!(expr, Expr::VariableRef { var } if var == "self")),
+            ImplicitSelf::Always => true,
+        };
+        if takes_self && params.first().is_none_or(|param| param.name != "self") {
+            params.insert(
+                0,
+                Param {
+                    name: "self".into(),
+                    ty: TypeRef::SelfType,
+                },
+            );
+            body.arenas.source_map.params.insert(0, None);
+        }
+
+        Some(self.tree.functions.alloc(Function {
+            name,
+            params,
+            ret,
+            body,
+        }))
+    }
+
+    fn lower_struct(&mut self, ast: &ast::StructDef) -> Option<StructId> {
+        let name = ast.name()?.text().into();
+        let mut fields: Vec<_> = ast
+            .fields()
+            .filter_map(|field| {
+                Some(Field {
+                    name: field.name()?.text().into(),
+                    ty: lower_type(field.ty()),
+                })
+            })
+            .collect();
+
+        // in `x, y, z: i32` all three fields are `i32`
+        let mut ty = TypeRef::Missing;
+        for field in fields.iter_mut().rev() {
+            if field.ty == TypeRef::Missing {
+                field.ty = ty.clone();
+            } else {
+                ty = field.ty.clone();
+            }
+        }
+
+        let parent = ast.parent().map(|ty| lower_type(Some(ty)));
+
+        Some(self.tree.structs.alloc(Struct {
+            name,
+            parent,
+            fields,
+        }))
+    }
+
+    fn lower_trait(&mut self, ast: &ast::TraitDef) -> Option<TraitId> {
+        let name = ast.name()?.text().into();
+        let types = ast
+            .types()
+            .filter_map(|ty| Some(ty.name()?.text().into()))
+            .collect();
+        let functions = self.lower_functions(ast.members(), ImplicitSelf::Always);
+
+        Some(self.tree.traits.alloc(Trait {
+            name,
+            types,
+            functions,
+        }))
+    }
+
+    fn lower_impl(&mut self, ast: &ast::ImplDef) -> ImplId {
+        let self_ty = lower_type(ast.self_ty());
+        let trait_ty = ast.trait_ty().map(|ty| lower_type(Some(ty)));
+        // a trait's members are all methods, so the impl's have to be too
+        let implicit_self = match trait_ty {
+            Some(_) => ImplicitSelf::Always,
+            None => ImplicitSelf::IfUsed,
+        };
+        let functions = self.lower_functions(ast.items(), implicit_self);
+
+        self.tree.impls.alloc(Impl {
+            self_ty,
+            trait_ty,
+            functions,
+        })
+    }
+
+    fn lower_functions(
+        &mut self,
+        stmts: impl Iterator<Item = ast::Stmt>,
+        implicit_self: ImplicitSelf,
+    ) -> Vec<FunctionId> {
+        stmts
+            .filter_map(|stmt| match stmt {
+                ast::Stmt::FnDef(ast) => self.lower_function(&ast, implicit_self),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// When a function that doesn't list `self` takes it anyway. Members of
+/// traits and of impls of them always do, while the other functions of an
+/// impl only do if they use `self`, so that `Vec3::new` stays a plain
+/// function.
+#[derive(Clone, Copy)]
+enum ImplicitSelf {
+    Never,
+    IfUsed,
+    Always,
+}
+
+pub(crate) fn lower_params(
+    ast: impl Iterator<Item = ast::FnParamDef>,
+) -> (Vec<Param>, Vec<AstPtr<ast::FnParamDef>>) {
+    let (mut params, ptrs): (Vec<_>, Vec<_>) = ast
+        .filter_map(|param| {
+            let lowered = Param {
+                name: param.name()?.text().into(),
+                ty: lower_type(param.ty()),
+            };
+            Some((lowered, AstPtr::new(&param)))
+        })
+        .unzip();
+
+    // in `(a, b, c: i32)` all three parameters are `i32`, while `self`
+    // is `Self` unless it says otherwise
+    let mut ty = TypeRef::Missing;
+    for param in params.iter_mut().rev() {
+        if param.name == "self" && param.ty == TypeRef::Missing {
+            param.ty = TypeRef::SelfType;
+        } else if param.ty == TypeRef::Missing {
+            param.ty = ty.clone();
+        } else {
+            ty = param.ty.clone();
+        }
+    }
+
+    (params, ptrs)
+}
+
+pub(crate) fn lower_type(ast: Option<ast::Type>) -> TypeRef {
+    match ast.and_then(|ast| ast.name()) {
+        Some(name) if name.kind() == drip_syntax::SyntaxKind::SelfTypeKw => TypeRef::SelfType,
+        Some(name) if name.text() == "Fn" => TypeRef::Fn,
+        Some(name) => TypeRef::Named(name.text().into()),
+        None => TypeRef::Missing,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Expr;
+    use drip_ast::AstNode;
+
+    fn lower(input: &str) -> (ItemTree, Body) {
+        crate::lower(ast::Root::cast(drip_parser::parse(input).syntax()).unwrap())
+    }
+
+    #[test]
+    fn lower_function_signature_and_body() {
+        let (tree, body) = lower("mul :: (a: i32, b: Self) -> (i32, i32) { a }");
+
+        let (id, function) = tree.functions().next().unwrap();
+        assert_eq!(body.stmts, vec![Stmt::Item(Item::Function(id))]);
+
+        assert_eq!(function.name, "mul");
+        assert_eq!(
+            function.params,
+            vec![
+                Param {
+                    name: "a".into(),
+                    ty: TypeRef::Named("i32".into()),
+                },
+                Param {
+                    name: "b".into(),
+                    ty: TypeRef::SelfType,
+                },
+            ]
+        );
+        assert_eq!(
+            function.ret,
+            vec![TypeRef::Named("i32".into()), TypeRef::Named("i32".into())]
+        );
+        let a = match function.body.stmts[..] {
+            [Stmt::Expr(a)] => a,
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            function.body.arenas[a],
+            Expr::VariableRef { var: "a".into() }
+        );
+    }
+
+    #[test]
+    fn share_type_between_grouped_params() {
+        let (tree, _) = lower("mul_add :: (a, b, c: i32, d: f32) { a }");
+        let (_, function) = tree.functions().next().unwrap();
+
+        let tys: Vec<_> = function.params.iter().map(|param| &param.ty).collect();
+        assert_eq!(
+            tys,
+            [
+                &TypeRef::Named("i32".into()),
+                &TypeRef::Named("i32".into()),
+                &TypeRef::Named("i32".into()),
+                &TypeRef::Named("f32".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn lower_nested_function() {
+        let (tree, _) = lower("outer :: () { inner :: () { 1 }\n2 }");
+
+        let names: Vec<_> = tree.functions().map(|(_, f)| f.name.as_str()).collect();
+        assert_eq!(names, ["inner", "outer"]);
+
+        let (inner, _) = tree.functions().next().unwrap();
+        let (_, outer) = tree.functions().nth(1).unwrap();
+        assert_eq!(outer.body.stmts[0], Stmt::Item(Item::Function(inner)));
+        assert!(matches!(outer.body.stmts[1], Stmt::Expr(_)));
+    }
+
+    #[test]
+    fn lower_struct_fields() {
+        let (tree, _) = lower("Vec2 :: struct { x: f32, y: f32 }");
+        let (_, s) = tree.structs().next().unwrap();
+
+        assert_eq!(s.name, "Vec2");
+        assert_eq!(
+            s.fields,
+            vec![
+                Field {
+                    name: "x".into(),
+                    ty: TypeRef::Named("f32".into()),
+                },
+                Field {
+                    name: "y".into(),
+                    ty: TypeRef::Named("f32".into()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn lower_trait_functions() {
+        let (tree, _) = lower("Show :: trait { show :: () { 1 } }");
+        let (_, t) = tree.traits().next().unwrap();
+
+        assert_eq!(t.name, "Show");
+        assert_eq!(tree[t.functions[0]].name, "show");
+    }
+
+    #[test]
+    fn lower_impls() {
+        let (tree, body) =
+            lower("Vec2 :: impl { len :: () { 1 } }\nVec2 <| Show :: impl { show :: () { 2 } }");
+
+        let impls: Vec<_> = tree.impls().map(|(_, i)| i).collect();
+        assert_eq!(impls[0].self_ty, TypeRef::Named("Vec2".into()));
+        assert_eq!(impls[0].trait_ty, None);
+        assert_eq!(tree[impls[0].functions[0]].name, "len");
+
+        assert_eq!(impls[1].trait_ty, Some(TypeRef::Named("Show".into())));
+        assert_eq!(tree[impls[1].functions[0]].name, "show");
+
+        assert_eq!(body.stmts.len(), 2);
+    }
+}
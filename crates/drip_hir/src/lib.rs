@@ -1,16 +1,58 @@
-mod database;
-pub use database::Database;
+mod arenas;
+pub mod cfg;
+pub mod const_eval;
+pub mod db;
+pub mod exhaustiveness;
+pub mod flow;
+pub mod fold;
+pub mod infer;
+mod item_tree;
+pub mod method_resolution;
+pub mod mutability;
+pub mod pretty;
+pub mod resolve;
+pub mod semantics;
+mod source_map;
+pub use arenas::BodyArenas;
+pub use db::Database;
+pub use item_tree::{
+    Body, Field, Function, FunctionId, Impl, ImplId, Item, ItemTree, Param, Struct, StructId,
+    Trait, TraitId, TypeRef,
+};
+pub use source_map::{BodySourceMap, ExprSource};
 
+use drip_operator::OperatorTrait;
 use la_arena::Idx;
 use smol_str::SmolStr;
 
-type ExprIdx = Idx<Expr>;
+pub type ExprIdx = Idx<Expr>;
+pub type PatIdx = Idx<Pat>;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Eq)]
 pub enum Stmt {
-    VariableDef { name: SmolStr, value: Expr },
-    ConstDef { name: SmolStr, value: Expr },
-    Expr(Expr),
+    VariableDef {
+        name: SmolStr,
+        value: ExprIdx,
+    },
+    ConstDef {
+        name: SmolStr,
+        value: ExprIdx,
+    },
+    /// `a, b :: value`, or `a, b := value` if `mutable`, which binds the
+    /// names in the tuple pattern `pat` to the elements of `value`.
+    TupleDef {
+        pat: PatIdx,
+        value: ExprIdx,
+        mutable: bool,
+    },
+    /// `target = value`, or `target op= value` if `op` is set.
+    Assign {
+        target: ExprIdx,
+        op: Option<BinaryOp>,
+        value: ExprIdx,
+    },
+    Expr(ExprIdx),
+    Item(Item),
 }
 
 #[derive(Debug, PartialEq)]
@@ -28,27 +70,216 @@ pub enum Expr {
     Literal {
         n: Option<u64>,
     },
+    FloatLiteral {
+        n: Option<f64>,
+    },
+    StringLiteral {
+        value: SmolStr,
+    },
+    /// A string literal with `{name}` in it, which is replaced by the value
+    /// of `name`.
+    FormatString {
+        parts: Vec<FormatPart>,
+    },
+    BoolLiteral {
+        value: bool,
+    },
     VariableRef {
         var: SmolStr,
     },
+    /// `Type::name`, a function of one of the type's impls.
+    Path {
+        ty: TypeRef,
+        name: SmolStr,
+    },
+    /// `arg_names[i]` is the parameter `args[i]` is passed to by name, as
+    /// in `f(y: 1)`.
+    Call {
+        callee: ExprIdx,
+        args: Vec<ExprIdx>,
+        arg_names: Vec<Option<SmolStr>>,
+    },
+    MethodCall {
+        receiver: ExprIdx,
+        name: SmolStr,
+        args: Vec<ExprIdx>,
+        arg_names: Vec<Option<SmolStr>>,
+    },
+    Field {
+        expr: ExprIdx,
+        name: SmolStr,
+    },
+    /// `base[index]`.
+    Index {
+        base: ExprIdx,
+        index: ExprIdx,
+    },
+    /// `Name { field: value }`, where a field written on its own is lowered
+    /// to a reference to the binding of the same name. A value without a
+    /// name goes to the field at its position.
+    Struct {
+        name: SmolStr,
+        fields: Vec<(Option<SmolStr>, ExprIdx)>,
+    },
+    /// `|params| body`. The closure's body belongs to the body it's written
+    /// in, so it can refer to the bindings around it.
+    Closure {
+        params: Vec<Param>,
+        body: ExprIdx,
+    },
+    /// `...`, which fails when it's evaluated.
+    Placeholder,
+    Tuple {
+        exprs: Vec<ExprIdx>,
+    },
+    Array {
+        exprs: Vec<ExprIdx>,
+    },
+    /// `start..end`, or `start..end=step`.
+    Range {
+        start: ExprIdx,
+        end: ExprIdx,
+        step: Option<ExprIdx>,
+    },
+    /// `{ stmts }`, whose statements are indices into [`Body::stmts`].
+    Block {
+        stmts: Vec<usize>,
+    },
+    If {
+        condition: ExprIdx,
+        then_branch: ExprIdx,
+        else_branch: Option<ExprIdx>,
+    },
+    Loop {
+        body: ExprIdx,
+    },
+    While {
+        condition: ExprIdx,
+        body: ExprIdx,
+    },
+    /// `for pat, index in iterable { .. }`, which binds the names in `pat`
+    /// to each element, and the ones in `index` to its index, inside `body`.
+    For {
+        pat: PatIdx,
+        index: Option<PatIdx>,
+        iterable: ExprIdx,
+        body: ExprIdx,
+    },
+    Break {
+        value: Option<ExprIdx>,
+    },
+    Continue,
+    Match {
+        scrutinee: ExprIdx,
+        arms: Vec<MatchArm>,
+    },
 }
 
-#[derive(Debug, PartialEq)]
+// float literals are never NaN, so every expression is equal to itself
+impl Eq for Expr {}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum FormatPart {
+    Text(SmolStr),
+    /// A `{name}`, which is always a `VariableRef`.
+    Expr(ExprIdx),
+}
+
+/// One arm of a `match`, taken if any of `pats` matches.
+#[derive(Debug, PartialEq, Eq)]
+pub struct MatchArm {
+    pub pats: Vec<PatIdx>,
+    pub body: ExprIdx,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Pat {
+    Missing,
+    Wildcard,
+    /// A name, which refers to a constant if one is in scope and binds the
+    /// matched value otherwise.
+    Ident {
+        name: SmolStr,
+    },
+    Literal {
+        expr: ExprIdx,
+    },
+    /// `start..end`, with `end` excluded.
+    Range {
+        start: ExprIdx,
+        end: ExprIdx,
+    },
+    Tuple {
+        pats: Vec<PatIdx>,
+    },
+    /// Fields that aren't listed can have any value.
+    Struct {
+        name: SmolStr,
+        fields: Vec<(SmolStr, PatIdx)>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BinaryOp {
     Add,
     Sub,
     Mul,
     Div,
+    Eq,
+    NotEq,
+    Less,
+    LessEq,
+    Greater,
+    GreaterEq,
 }
 
-#[derive(Debug, PartialEq)]
+impl BinaryOp {
+    pub fn is_comparison(&self) -> bool {
+        !matches!(self, Self::Add | Self::Sub | Self::Mul | Self::Div)
+    }
+
+    /// The trait that lets the operator be used on structs.
+    pub fn operator_trait(&self) -> &'static OperatorTrait {
+        match self {
+            Self::Add => &drip_operator::ADD,
+            Self::Sub => &drip_operator::SUB,
+            Self::Mul => &drip_operator::MUL,
+            Self::Div => &drip_operator::DIV,
+            Self::Eq | Self::NotEq => &drip_operator::EQ,
+            Self::Less | Self::LessEq | Self::Greater | Self::GreaterEq => &drip_operator::ORD,
+        }
+    }
+
+    /// The trait that lets `op=` be used on structs, if there's an `op=`.
+    pub fn assign_trait(&self) -> Option<&'static OperatorTrait> {
+        match self {
+            Self::Add => Some(&drip_operator::ADD_ASSIGN),
+            Self::Sub => Some(&drip_operator::SUB_ASSIGN),
+            Self::Mul => Some(&drip_operator::MUL_ASSIGN),
+            Self::Div => Some(&drip_operator::DIV_ASSIGN),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
 pub enum UnaryOp {
     Neg,
 }
 
-pub fn lower(ast: drip_ast::Root) -> (Database, Vec<Stmt>) {
-    let mut db = Database::default();
-    let stmts = ast.stmts().filter_map(|stmt| db.lower_stmt(stmt)).collect();
+impl UnaryOp {
+    /// The trait that lets the operator be used on structs.
+    pub fn operator_trait(&self) -> &'static OperatorTrait {
+        match self {
+            Self::Neg => &drip_operator::NEG,
+        }
+    }
+}
+
+/// Lowers a file into its items and the body of top-level statements.
+pub fn lower(ast: drip_ast::Root) -> (ItemTree, Body) {
+    let mut builder = item_tree::ItemTreeBuilder::default();
+    let body = builder.lower_body(ast.stmts());
 
-    (db, stmts)
+    (builder.finish(), body)
 }
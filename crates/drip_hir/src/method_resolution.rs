@@ -0,0 +1,589 @@
+//! Lookup of methods and fields on a type, and checking that trait impls
+//! match their traits.
+//!
+//! A method is a function in an impl whose first parameter is `self`. The
+//! impls of a type are searched in override order: the type's own impls
+//! first, then its trait impls, then the same two steps for the struct it
+//! inherits from with `<|`, and so on up the chain. The first step that has a
+//! method of the right name wins, so a struct can override the methods it
+//! inherits; two methods of that name in the same step are ambiguous.
+//! `Type::name` is looked up the same way, but finds functions without
+//! `self` too.
+//!
+//! Operators on structs call the member of a built-in operator trait from
+//! `drip_operator`, which impls can implement without the program declaring
+//! the trait.
+
+use crate::infer::{self, Ty};
+use crate::{Field, Function, FunctionId, Impl, ImplId, ItemTree, StructId, TraitId, TypeRef};
+use drip_operator::{Operand, OperatorTrait};
+use smol_str::SmolStr;
+use std::fmt;
+use std::fmt::Formatter;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MethodError {
+    NotFound,
+    Ambiguous(Vec<FunctionId>),
+}
+
+/// Finds the method `name` that `receiver.name(..)` calls on a `ty`.
+pub fn lookup_method(tree: &ItemTree, ty: &Ty, name: &str) -> Result<FunctionId, MethodError> {
+    lookup(tree, ty, |function| is_method(function, name))
+}
+
+/// Finds the function `name` that the path `Type::name` refers to on a `ty`.
+pub fn lookup_path(tree: &ItemTree, ty: &Ty, name: &str) -> Result<FunctionId, MethodError> {
+    lookup(tree, ty, |function| function.name == name)
+}
+
+fn lookup(
+    tree: &ItemTree,
+    ty: &Ty,
+    wanted: impl Fn(&Function) -> bool,
+) -> Result<FunctionId, MethodError> {
+    for ty in inheritance_chain(tree, ty) {
+        for inherent in [true, false] {
+            let candidates: Vec<_> = tree
+                .impls()
+                .filter(|(_, imp)| imp.trait_ty.is_none() == inherent)
+                .filter(|(_, imp)| infer::lower_type_ref(tree, &imp.self_ty) == ty)
+                .flat_map(|(_, imp)| imp.functions.iter().copied())
+                .filter(|function| wanted(&tree[*function]))
+                .collect();
+
+            match candidates[..] {
+                [] => {}
+                [function] => return Ok(function),
+                _ => return Err(MethodError::Ambiguous(candidates)),
+            }
+        }
+    }
+
+    Err(MethodError::NotFound)
+}
+
+/// Finds the method `name` among the members of a trait, for calls on
+/// `self` inside the trait's own functions.
+pub fn lookup_trait_method(
+    tree: &ItemTree,
+    id: TraitId,
+    name: &str,
+) -> Result<FunctionId, MethodError> {
+    tree[id]
+        .functions
+        .iter()
+        .copied()
+        .find(|function| is_method(&tree[*function], name))
+        .ok_or(MethodError::NotFound)
+}
+
+/// Finds the method an operator calls on a `ty`: the member of `op` in an
+/// impl of `op` for `ty`, or for a struct it inherits from.
+pub fn lookup_operator(tree: &ItemTree, ty: &Ty, op: &OperatorTrait) -> Option<FunctionId> {
+    let trait_ty = TypeRef::Named(op.name.into());
+    inheritance_chain(tree, ty).iter().find_map(|ty| {
+        tree.impls()
+            .filter(|(_, imp)| imp.trait_ty.as_ref() == Some(&trait_ty))
+            .filter(|(_, imp)| infer::lower_type_ref(tree, &imp.self_ty) == *ty)
+            .flat_map(|(_, imp)| imp.functions.iter().copied())
+            .find(|function| is_method(&tree[*function], op.method))
+    })
+}
+
+/// Finds the field `name` of a struct, including the fields it inherits.
+pub fn lookup_field<'a>(tree: &'a ItemTree, id: StructId, name: &str) -> Option<&'a Field> {
+    let ty = infer::struct_ty(tree, id);
+    inheritance_chain(tree, &ty)
+        .into_iter()
+        .find_map(|ty| match ty {
+            Ty::Struct { id, .. } => tree[id].fields.iter().find(|field| field.name == name),
+            _ => None,
+        })
+}
+
+/// All fields of a struct, including the ones it inherits, nearest first.
+/// An inherited field is left out if a nearer struct has one of that name.
+pub fn all_fields(tree: &ItemTree, id: StructId) -> Vec<&Field> {
+    let ty = infer::struct_ty(tree, id);
+    let mut fields: Vec<&Field> = Vec::new();
+    for ty in inheritance_chain(tree, &ty) {
+        if let Ty::Struct { id, .. } = ty {
+            for field in &tree[id].fields {
+                if !fields.iter().any(|f| f.name == field.name) {
+                    fields.push(field);
+                }
+            }
+        }
+    }
+
+    fields
+}
+
+/// The field each value of a struct literal goes to, in the order they're
+/// written in. A value without a name goes to the field at its position, or
+/// to `None` if the struct doesn't have that many fields.
+pub fn literal_fields<'a, T: Copy>(
+    tree: &'a ItemTree,
+    id: StructId,
+    fields: &'a [(Option<SmolStr>, T)],
+) -> Vec<(Option<&'a SmolStr>, T)> {
+    let all = all_fields(tree, id);
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, (name, value))| {
+            let name = name
+                .as_ref()
+                .or_else(|| all.get(i).map(|field| &field.name));
+            (name, *value)
+        })
+        .collect()
+}
+
+/// The functions of the impls of `ty` and of the structs it inherits from,
+/// in override order. A function is left out if one that overrides it has
+/// the same name.
+pub fn all_functions(tree: &ItemTree, ty: &Ty) -> Vec<FunctionId> {
+    let mut functions: Vec<FunctionId> = Vec::new();
+    for ty in inheritance_chain(tree, ty) {
+        for inherent in [true, false] {
+            let candidates = tree
+                .impls()
+                .filter(|(_, imp)| imp.trait_ty.is_none() == inherent)
+                .filter(|(_, imp)| infer::lower_type_ref(tree, &imp.self_ty) == ty)
+                .flat_map(|(_, imp)| imp.functions.iter().copied());
+            for function in candidates {
+                let name = &tree[function].name;
+                if !functions.iter().any(|f| tree[*f].name == *name) {
+                    functions.push(function);
+                }
+            }
+        }
+    }
+
+    functions
+}
+
+/// The methods that can be called on a `ty`, in override order.
+pub fn all_methods(tree: &ItemTree, ty: &Ty) -> Vec<FunctionId> {
+    all_functions(tree, ty)
+        .into_iter()
+        .filter(|function| is_method(&tree[*function], &tree[*function].name))
+        .collect()
+}
+
+fn is_method(function: &Function, name: &str) -> bool {
+    function.name == name
+        && function
+            .params
+            .first()
+            .is_some_and(|param| param.name == "self")
+}
+
+/// `ty`, followed by the structs it inherits from, nearest first.
+fn inheritance_chain(tree: &ItemTree, ty: &Ty) -> Vec<Ty> {
+    let mut chain = vec![ty.clone()];
+
+    while let Some(Ty::Struct { id, .. }) = chain.last() {
+        let parent = match &tree[*id].parent {
+            Some(parent) => infer::lower_type_ref(tree, parent),
+            None => break,
+        };
+        // stop at cycles like `A :: struct <| A`
+        if !matches!(parent, Ty::Struct { .. }) || chain.contains(&parent) {
+            break;
+        }
+        chain.push(parent);
+    }
+
+    chain
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ImplError {
+    kind: ImplErrorKind,
+    impl_id: ImplId,
+    trait_name: SmolStr,
+}
+
+impl ImplError {
+    pub fn kind(&self) -> &ImplErrorKind {
+        &self.kind
+    }
+
+    pub fn impl_id(&self) -> ImplId {
+        self.impl_id
+    }
+
+    pub fn trait_name(&self) -> &str {
+        &self.trait_name
+    }
+}
+
+impl fmt::Display for ImplError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ImplErrorKind::UnknownTrait => write!(f, "cannot find trait `{}`", self.trait_name),
+            ImplErrorKind::MissingMember { name } => write!(
+                f,
+                "not all members of `{}` are implemented, missing `{}`",
+                self.trait_name, name
+            ),
+            ImplErrorKind::NotAMember { name } => {
+                write!(f, "`{}` is not a member of `{}`", name, self.trait_name)
+            }
+            ImplErrorKind::SignatureMismatch { name } => write!(
+                f,
+                "`{}` doesn't match its signature in `{}`",
+                name, self.trait_name
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImplErrorKind {
+    UnknownTrait,
+    MissingMember { name: SmolStr },
+    NotAMember { name: SmolStr },
+    SignatureMismatch { name: SmolStr },
+}
+
+/// Checks that every trait impl provides all members of its trait, and
+/// nothing else, with the signatures the trait gives them.
+pub fn check_impls(tree: &ItemTree) -> Vec<ImplError> {
+    let mut errors = Vec::new();
+
+    for (impl_id, imp) in tree.impls() {
+        let trait_name = match &imp.trait_ty {
+            Some(TypeRef::Named(name)) => name.clone(),
+            Some(_) | None => continue,
+        };
+        let mut report = |kind| {
+            errors.push(ImplError {
+                kind,
+                impl_id,
+                trait_name: trait_name.clone(),
+            })
+        };
+
+        // the operator traits are built in, unless the program declares its own
+        let trait_data = match tree.traits().find(|(_, t)| t.name == trait_name) {
+            Some((_, trait_data)) => trait_data,
+            None => {
+                match drip_operator::lookup(&trait_name) {
+                    Some(op) => check_operator_impl(tree, imp, op, &mut report),
+                    None => report(ImplErrorKind::UnknownTrait),
+                }
+                continue;
+            }
+        };
+
+        for member in &trait_data.functions {
+            let member = &tree[*member];
+            let provided = imp
+                .functions
+                .iter()
+                .find(|function| tree[**function].name == member.name);
+
+            match provided {
+                None => report(ImplErrorKind::MissingMember {
+                    name: member.name.clone(),
+                }),
+                Some(function) if !signatures_match(member, &tree[*function], &imp.self_ty) => {
+                    report(ImplErrorKind::SignatureMismatch {
+                        name: member.name.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        for function in &imp.functions {
+            let name = &tree[*function].name;
+            if !trait_data.functions.iter().any(|f| &tree[*f].name == name) {
+                report(ImplErrorKind::NotAMember { name: name.clone() });
+            }
+        }
+    }
+
+    errors
+}
+
+/// Checks an impl of a built-in operator trait, which has just the one
+/// member.
+fn check_operator_impl(
+    tree: &ItemTree,
+    imp: &Impl,
+    op: &OperatorTrait,
+    report: &mut impl FnMut(ImplErrorKind),
+) {
+    let provided = imp
+        .functions
+        .iter()
+        .find(|function| tree[**function].name == op.method);
+    match provided {
+        None => report(ImplErrorKind::MissingMember {
+            name: op.method.into(),
+        }),
+        Some(function) if !operator_signature_matches(op, &tree[*function], &imp.self_ty) => {
+            report(ImplErrorKind::SignatureMismatch {
+                name: op.method.into(),
+            })
+        }
+        Some(_) => {}
+    }
+
+    for function in &imp.functions {
+        let name = &tree[*function].name;
+        if name != op.method {
+            report(ImplErrorKind::NotAMember { name: name.clone() });
+        }
+    }
+}
+
+fn operator_signature_matches(op: &OperatorTrait, function: &Function, self_ty: &TypeRef) -> bool {
+    let fits = |expected: &Operand, found: &TypeRef| match expected {
+        Operand::SelfType => found == &TypeRef::SelfType || found == self_ty,
+        Operand::Named(name) => found == &TypeRef::Named((*name).into()),
+        Operand::Any => true,
+    };
+
+    is_method(function, op.method)
+        && function.params.len() == op.params.len() + 1
+        && op
+            .params
+            .iter()
+            .zip(&function.params[1..])
+            .all(|(expected, found)| found.name != "self" && fits(expected, &found.ty))
+        && match (&op.ret, &function.ret[..]) {
+            (None, ret) => ret.is_empty(),
+            (Some(Operand::Any), _) => true,
+            (Some(expected), [found]) => fits(expected, found),
+            (Some(_), _) => false,
+        }
+}
+
+/// Whether `function` implements the trait member `member` for `self_ty`,
+/// where `Self` in the trait stands for `self_ty`.
+fn signatures_match(member: &Function, function: &Function, self_ty: &TypeRef) -> bool {
+    let same = |expected: &TypeRef, found: &TypeRef| match expected {
+        TypeRef::SelfType => found == &TypeRef::SelfType || found == self_ty,
+        _ => expected == found,
+    };
+
+    member.params.len() == function.params.len()
+        && member
+            .params
+            .iter()
+            .zip(&function.params)
+            .all(|(expected, found)| {
+                (expected.name == "self") == (found.name == "self") && same(&expected.ty, &found.ty)
+            })
+        && member.ret.len() == function.ret.len()
+        && member
+            .ret
+            .iter()
+            .zip(&function.ret)
+            .all(|(expected, found)| same(expected, found))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use drip_ast::AstNode;
+
+    fn lower(input: &str) -> ItemTree {
+        let root = drip_ast::Root::cast(drip_parser::parse(input).syntax()).unwrap();
+        crate::lower(root).0
+    }
+
+    fn struct_named(tree: &ItemTree, name: &str) -> Ty {
+        let (id, _) = tree.structs().find(|(_, s)| s.name == name).unwrap();
+        infer::struct_ty(tree, id)
+    }
+
+    /// The name of the impl's trait, or of its type for inherent impls.
+    fn owner(tree: &ItemTree, function: FunctionId) -> String {
+        let (_, imp) = tree
+            .impls()
+            .find(|(_, imp)| imp.functions.contains(&function))
+            .unwrap();
+        match (&imp.trait_ty, &imp.self_ty) {
+            (Some(TypeRef::Named(name)), TypeRef::Named(self_ty)) => {
+                format!("{} <| {}", self_ty, name)
+            }
+            (_, TypeRef::Named(self_ty)) => self_ty.to_string(),
+            _ => unreachable!(),
+        }
+    }
+
+    const ANIMALS: &str = "
+Sound :: trait {
+    make_sound :: (self)
+}
+Animal :: struct { name: string }
+Animal <| Sound :: impl {
+    make_sound :: (self) { 1 }
+}
+Dog :: struct <| Animal { toy: string }
+Cat :: struct <| Animal { }
+Cat <| Sound :: impl {
+    make_sound :: (self) { 2 }
+}
+Cat :: impl {
+    make_sound :: (self) { 3 }
+    new :: () { 4 }
+}
+";
+
+    #[test]
+    fn search_impls_in_override_order() {
+        let tree = lower(ANIMALS);
+
+        let dog = lookup_method(&tree, &struct_named(&tree, "Dog"), "make_sound").unwrap();
+        assert_eq!(owner(&tree, dog), "Animal <| Sound");
+
+        let cat = lookup_method(&tree, &struct_named(&tree, "Cat"), "make_sound").unwrap();
+        assert_eq!(owner(&tree, cat), "Cat");
+    }
+
+    #[test]
+    fn only_functions_taking_self_are_methods() {
+        let tree = lower(ANIMALS);
+
+        assert_eq!(
+            lookup_method(&tree, &struct_named(&tree, "Cat"), "new"),
+            Err(MethodError::NotFound)
+        );
+    }
+
+    #[test]
+    fn find_functions_of_paths_with_or_without_self() {
+        let tree = lower(ANIMALS);
+        let cat = struct_named(&tree, "Cat");
+
+        let new = lookup_path(&tree, &cat, "new").unwrap();
+        assert_eq!(owner(&tree, new), "Cat");
+        let make_sound = lookup_path(&tree, &cat, "make_sound").unwrap();
+        assert_eq!(owner(&tree, make_sound), "Cat");
+        assert_eq!(
+            lookup_path(&tree, &struct_named(&tree, "Dog"), "new"),
+            Err(MethodError::NotFound)
+        );
+    }
+
+    #[test]
+    fn take_self_without_listing_it() {
+        let tree = lower(
+            "Sound :: trait {\n    make_sound :: ()\n}\nAnimal :: struct { name: string }\nAnimal <| Sound :: impl {\n    make_sound :: () { println(self.name) }\n}\nAnimal :: impl {\n    name :: () -> string { self.name }\n    new :: () -> Self { Animal { name: \"\" } }\n}",
+        );
+        let animal = struct_named(&tree, "Animal");
+
+        assert_eq!(check_impls(&tree), []);
+        let make_sound = lookup_method(&tree, &animal, "make_sound").unwrap();
+        assert_eq!(owner(&tree, make_sound), "Animal <| Sound");
+        let name = lookup_method(&tree, &animal, "name").unwrap();
+        assert_eq!(owner(&tree, name), "Animal");
+        assert_eq!(
+            lookup_method(&tree, &animal, "new"),
+            Err(MethodError::NotFound)
+        );
+    }
+
+    #[test]
+    fn list_functions_in_override_order() {
+        let tree = lower(ANIMALS);
+        let list = |functions: Vec<FunctionId>| -> Vec<_> {
+            functions
+                .into_iter()
+                .map(|f| format!("{}::{}", owner(&tree, f), tree[f].name))
+                .collect()
+        };
+
+        let cat = struct_named(&tree, "Cat");
+        assert_eq!(
+            list(all_functions(&tree, &cat)),
+            ["Cat::make_sound", "Cat::new"]
+        );
+        assert_eq!(list(all_methods(&tree, &cat)), ["Cat::make_sound"]);
+        let dog = struct_named(&tree, "Dog");
+        assert_eq!(
+            list(all_methods(&tree, &dog)),
+            ["Animal <| Sound::make_sound"]
+        );
+    }
+
+    #[test]
+    fn report_ambiguous_methods() {
+        let tree = lower(
+            "A :: trait { f :: (self) }\nB :: trait { f :: (self) }\nS :: struct { }\nS <| A :: impl { f :: (self) { 1 } }\nS <| B :: impl { f :: (self) { 2 } }",
+        );
+
+        assert!(matches!(
+            lookup_method(&tree, &struct_named(&tree, "S"), "f"),
+            Err(MethodError::Ambiguous(candidates)) if candidates.len() == 2
+        ));
+    }
+
+    #[test]
+    fn find_inherited_fields() {
+        let tree = lower(ANIMALS);
+        let (dog, _) = tree.structs().find(|(_, s)| s.name == "Dog").unwrap();
+
+        assert_eq!(lookup_field(&tree, dog, "name").unwrap().name, "name");
+        assert_eq!(lookup_field(&tree, dog, "toy").unwrap().name, "toy");
+        assert!(lookup_field(&tree, dog, "age").is_none());
+    }
+
+    #[test]
+    fn check_trait_impls() {
+        let tree = lower(
+            "Shape :: trait {\n    area :: (self) -> f32\n    name :: (self) -> string\n}\nSquare :: struct { side: f32 }\nSquare <| Shape :: impl {\n    area :: (self) -> i32 { 1 }\n    sides :: (self) { 4 }\n}\nSquare <| Debug :: impl { }",
+        );
+
+        let errors: Vec<_> = check_impls(&tree).iter().map(ToString::to_string).collect();
+        assert_eq!(
+            errors,
+            [
+                "`area` doesn't match its signature in `Shape`",
+                "not all members of `Shape` are implemented, missing `name`",
+                "`sides` is not a member of `Shape`",
+                "cannot find trait `Debug`",
+            ]
+        );
+    }
+
+    #[test]
+    fn accept_self_type_in_impl_signatures() {
+        let tree = lower(
+            "Twice :: trait { twice :: (self) -> Self }\nN :: struct { }\nN <| Twice :: impl { twice :: (self: N) -> Self { self } }",
+        );
+
+        assert_eq!(check_impls(&tree), []);
+    }
+
+    #[test]
+    fn check_operator_impls_against_the_built_in_traits() {
+        let tree = lower(
+            "V :: struct { }\nV <| Add :: impl { add :: (self, other: V) -> V { self } }\nV <| Eq :: impl { eq :: (self, other: V) -> i32 { 1 } }\nV <| Neg :: impl { negate :: (self) { self } }\nV <| AddAssign :: impl { add_assign :: (self, other: V) -> V { self } }",
+        );
+
+        let errors: Vec<_> = check_impls(&tree).iter().map(ToString::to_string).collect();
+        assert_eq!(
+            errors,
+            [
+                "`eq` doesn't match its signature in `Eq`",
+                "not all members of `Neg` are implemented, missing `neg`",
+                "`negate` is not a member of `Neg`",
+                "`add_assign` doesn't match its signature in `AddAssign`",
+            ]
+        );
+
+        let v = struct_named(&tree, "V");
+        let add = lookup_operator(&tree, &v, &drip_operator::ADD).unwrap();
+        assert_eq!(owner(&tree, add), "V <| Add");
+        assert_eq!(lookup_operator(&tree, &v, &drip_operator::SUB), None);
+    }
+}
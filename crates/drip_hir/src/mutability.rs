@@ -0,0 +1,394 @@
+//! Checking that `::` bindings are never changed.
+//!
+//! `::` bindings are immutable and `:=` bindings are mutable. A binding is
+//! changed by assigning to it or to one of its fields, with `=` or a compound
+//! assignment like `+=`, or by calling a mutating method on it. A method is
+//! mutating if it changes `self`, either directly or by calling another
+//! mutating method on it. Parameters aren't `::` bindings and can be changed
+//! freely.
+
+use crate::infer::InferenceResults;
+use crate::resolve::{BodyId, Resolution, Resolutions};
+use crate::{Body, BodyArenas, Expr, ExprIdx, FunctionId, ItemTree, Pat, Stmt};
+use smol_str::SmolStr;
+use std::collections::HashSet;
+use std::fmt;
+use std::fmt::Formatter;
+use text_size::TextRange;
+
+#[derive(Debug, PartialEq)]
+pub struct MutabilityError {
+    kind: MutabilityErrorKind,
+    body: BodyId,
+    expr: ExprIdx,
+    range: TextRange,
+    definition: TextRange,
+}
+
+impl MutabilityError {
+    pub fn kind(&self) -> &MutabilityErrorKind {
+        &self.kind
+    }
+
+    pub fn body(&self) -> BodyId {
+        self.body
+    }
+
+    /// The assignment target or method call that changes the binding.
+    pub fn expr(&self) -> ExprIdx {
+        self.expr
+    }
+
+    pub fn range(&self) -> TextRange {
+        self.range
+    }
+
+    /// The range of the `::` definition of the binding.
+    pub fn definition(&self) -> TextRange {
+        self.definition
+    }
+}
+
+impl fmt::Display for MutabilityError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "error at {}..{}: {} (defined with `::` at {}..{})",
+            u32::from(self.range.start()),
+            u32::from(self.range.end()),
+            self.kind,
+            u32::from(self.definition.start()),
+            u32::from(self.definition.end()),
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MutabilityErrorKind {
+    Assign { name: SmolStr },
+    AssignField { name: SmolStr },
+    AssignElement { name: SmolStr },
+    MutatingCall { name: SmolStr, method: SmolStr },
+}
+
+impl fmt::Display for MutabilityErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Assign { name } => write!(f, "cannot assign to immutable binding `{}`", name),
+            Self::AssignField { name } => {
+                write!(
+                    f,
+                    "cannot assign to a field of immutable binding `{}`",
+                    name
+                )
+            }
+            Self::AssignElement { name } => write!(
+                f,
+                "cannot assign to an element of immutable binding `{}`",
+                name
+            ),
+            Self::MutatingCall { name, method } => write!(
+                f,
+                "cannot call mutating method `{}` on immutable binding `{}`",
+                method, name
+            ),
+        }
+    }
+}
+
+/// Finds every change to a `::` binding in `root` and in the bodies of all
+/// items in `tree`.
+pub fn check_mutability(
+    tree: &ItemTree,
+    root: &Body,
+    resolutions: &Resolutions,
+    inference: &InferenceResults,
+) -> Vec<MutabilityError> {
+    let mut checker = Checker {
+        tree,
+        root,
+        resolutions,
+        inference,
+        mutating: HashSet::new(),
+        errors: Vec::new(),
+    };
+    checker.find_mutating_methods();
+
+    let bodies =
+        std::iter::once(BodyId::Root).chain(tree.functions().map(|(id, _)| BodyId::Function(id)));
+    for id in bodies {
+        checker.check_body(id);
+    }
+
+    checker.errors
+}
+
+struct Checker<'a> {
+    tree: &'a ItemTree,
+    root: &'a Body,
+    resolutions: &'a Resolutions,
+    inference: &'a InferenceResults,
+    mutating: HashSet<FunctionId>,
+    errors: Vec<MutabilityError>,
+}
+
+impl<'a> Checker<'a> {
+    fn body(&self, id: BodyId) -> &'a Body {
+        match id {
+            BodyId::Root => self.root,
+            BodyId::Function(function) => &self.tree[function].body,
+        }
+    }
+
+    /// Marks methods as mutating until nothing changes, since a method that
+    /// calls a mutating method on `self` is mutating too.
+    fn find_mutating_methods(&mut self) {
+        let tree = self.tree;
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+            for (function, data) in tree.functions() {
+                let takes_self = data.params.first().is_some_and(|p| p.name == "self");
+                if takes_self && !self.mutating.contains(&function) && self.mutates_self(function) {
+                    self.mutating.insert(function);
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    fn mutates_self(&self, function: FunctionId) -> bool {
+        let id = BodyId::Function(function);
+        let body = self.body(id);
+        let is_self = |expr| {
+            place_root(&body.arenas, expr).is_some_and(|root| {
+                // a field used without `self.` is part of `self` too
+                matches!(
+                    self.resolutions.get(id, root),
+                    Some(
+                        Resolution::Param { function: f, index: 0 }
+                        | Resolution::SelfField { function: f, .. }
+                    ) if f == function
+                )
+            })
+        };
+
+        let assigns = body.stmts.iter().any(|stmt| match stmt {
+            Stmt::Assign { target, .. } => is_self(*target),
+            _ => false,
+        });
+        let calls = body.arenas.exprs.iter().any(|(idx, expr)| match expr {
+            Expr::MethodCall { receiver, .. } => {
+                is_self(*receiver) && self.is_mutating_call(id, idx)
+            }
+            _ => false,
+        });
+
+        assigns || calls
+    }
+
+    fn is_mutating_call(&self, id: BodyId, call: ExprIdx) -> bool {
+        self.inference
+            .body(id)
+            .method_resolution(call)
+            .is_some_and(|method| self.mutating.contains(&method))
+    }
+
+    fn check_body(&mut self, id: BodyId) {
+        let body = self.body(id);
+
+        for stmt in &body.stmts {
+            if let Stmt::Assign { target, .. } = stmt {
+                if let Some(root) = place_root(&body.arenas, *target) {
+                    let element = matches!(body.arenas[*target], Expr::Index { .. });
+                    self.check_place(id, *target, root, |name| {
+                        if element {
+                            MutabilityErrorKind::AssignElement { name }
+                        } else if root != *target {
+                            MutabilityErrorKind::AssignField { name }
+                        } else {
+                            MutabilityErrorKind::Assign { name }
+                        }
+                    });
+                }
+            }
+        }
+
+        for (idx, expr) in body.arenas.exprs.iter() {
+            if let Expr::MethodCall { receiver, name, .. } = expr {
+                if !self.is_mutating_call(id, idx) {
+                    continue;
+                }
+                if let Some(root) = place_root(&body.arenas, *receiver) {
+                    self.check_place(id, idx, root, |binding| MutabilityErrorKind::MutatingCall {
+                        name: binding,
+                        method: name.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Reports `expr`, which changes the binding `root` refers to, if that
+    /// binding is immutable.
+    fn check_place(
+        &mut self,
+        id: BodyId,
+        expr: ExprIdx,
+        root: ExprIdx,
+        kind: impl FnOnce(SmolStr) -> MutabilityErrorKind,
+    ) {
+        let (def_body, stmt, name) = match self.resolutions.get(id, root) {
+            Some(Resolution::Local { body, stmt }) => (self.body(body), stmt, None),
+            Some(Resolution::Global { stmt }) => (self.body(BodyId::Root), stmt, None),
+            // one of the names of `a, b :: value`
+            Some(Resolution::PatBinding { body, pat }) => {
+                let def_body = self.body(body);
+                let (stmt, _) = match def_body.tuple_def(pat) {
+                    Some(def) => def,
+                    None => return,
+                };
+                match &def_body.arenas[pat] {
+                    Pat::Ident { name } => (def_body, stmt, Some(name.clone())),
+                    _ => return,
+                }
+            }
+            _ => return,
+        };
+        let name = match (&def_body.stmts[stmt], name) {
+            (Stmt::ConstDef { name, .. }, _) => name.clone(),
+            (Stmt::TupleDef { mutable: false, .. }, Some(name)) => name,
+            _ => return,
+        };
+
+        let body = self.body(id);
+        let range = body
+            .arenas
+            .source_map()
+            .expr_syntax(expr)
+            .map_or_else(TextRange::default, |source| source.range());
+        let definition = def_body
+            .arenas
+            .source_map()
+            .stmt_syntax(stmt)
+            .map_or_else(TextRange::default, |ptr| ptr.syntax_node_ptr().range());
+
+        self.errors.push(MutabilityError {
+            kind: kind(name),
+            body: id,
+            expr,
+            range,
+            definition,
+        });
+    }
+}
+
+/// The variable `expr` is a place in, like `v` in `v.a[i].b`. Other
+/// expressions, like `f().a`, aren't places.
+fn place_root(arenas: &BodyArenas, mut expr: ExprIdx) -> Option<ExprIdx> {
+    loop {
+        match &arenas[expr] {
+            Expr::Field { expr: inner, .. } => {
+                expr = *inner;
+            }
+            Expr::Index { base, .. } => {
+                expr = *base;
+            }
+            Expr::VariableRef { .. } => return Some(expr),
+            _ => return None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infer::infer;
+    use crate::resolve::resolve;
+    use drip_ast::AstNode;
+
+    fn check(input: &str) -> Vec<String> {
+        let root = drip_ast::Root::cast(drip_parser::parse(input).syntax()).unwrap();
+        let (tree, body) = crate::lower(root);
+        let resolutions = resolve(&tree, &body);
+        let inference = infer(&tree, &body, &resolutions);
+
+        check_mutability(&tree, &body, &resolutions, &inference)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn reject_assignment_to_constants() {
+        assert_eq!(
+            check("hello :: \"hello\"\nworld :: \"world\"\nworld = hello + world\ncount := 0\ncount += 1\nworld += hello"),
+            [
+                "error at 34..40: cannot assign to immutable binding `world` (defined with `::` at 17..34)",
+                "error at 78..84: cannot assign to immutable binding `world` (defined with `::` at 17..34)",
+            ]
+        );
+    }
+
+    #[test]
+    fn reject_changes_through_fields_and_mutating_methods() {
+        let input = "Vec3 :: struct { x: i32 }
+Vec3 :: impl {
+    plus_one :: (self) { self.x += 1 }
+    bump :: (self) { self.plus_one() }
+    get :: (self) -> i32 { self.x }
+}
+f :: (p: Vec3) {
+    v :: p
+    v.x = 2
+    v.bump()
+    v.get()
+    w := p
+    w.x += 1
+    w.plus_one()
+}";
+
+        assert_eq!(
+            check(input),
+            [
+                "error at 189..193: cannot assign to a field of immutable binding `v` (defined with `::` at 178..189)",
+                "error at 201..214: cannot call mutating method `bump` on immutable binding `v` (defined with `::` at 178..189)",
+            ]
+        );
+    }
+
+    #[test]
+    fn reject_assignment_to_elements() {
+        assert_eq!(
+            check("a :: [1, 2]\na[0] = 3\nb := [1, 2]\nb[0] = 3"),
+            ["error at 12..17: cannot assign to an element of immutable binding `a` (defined with `::` at 0..12)"]
+        );
+    }
+
+    #[test]
+    fn reject_assignment_to_several_constants() {
+        assert_eq!(
+            check("a, b, c :: 10, 3, 5
+b = 4
+x, y := 1, 2
+y = 3"),
+            ["error at 20..22: cannot assign to immutable binding `b` (defined with `::` at 0..20)"]
+        );
+    }
+    #[test]
+    fn treat_changes_to_fields_without_self_as_mutating() {
+        let input = "Vec3 :: struct { x, y: i32 }
+Vec3 :: impl {
+    plus_one :: (self) { x, y += 1 }
+    get :: (self) -> i32 { x + y }
+}
+v :: Vec3 { 1, 2 }
+v.get()
+v.plus_one()";
+        assert_eq!(
+            check(input),
+            ["error at 145..157: cannot call mutating method `plus_one` on immutable binding `v` (defined with `::` at 118..137)"]
+        );
+    }
+}
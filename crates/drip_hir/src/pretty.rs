@@ -0,0 +1,319 @@
+//! A readable rendering of lowered code, for the REPL's `:hir` and the
+//! driver's `--emit=hir`.
+//!
+//! Every statement, expression and pattern is printed on its own line,
+//! indented below the one it belongs to. Items are printed where they're
+//! defined, together with their bodies.
+
+use crate::{Body, Expr, ExprIdx, FormatPart, Item, ItemTree, Param, Pat, PatIdx, Stmt, TypeRef};
+use smol_str::SmolStr;
+use std::fmt::Write;
+
+/// Renders the top-level statements of `body`, which was lowered along with
+/// `tree`.
+pub fn render(tree: &ItemTree, body: &Body) -> String {
+    let mut printer = Printer {
+        tree,
+        out: String::new(),
+        indent: 0,
+    };
+    printer.stmts(body, &body.top_level);
+    printer.out
+}
+
+struct Printer<'a> {
+    tree: &'a ItemTree,
+    out: String,
+    indent: usize,
+}
+
+impl Printer<'_> {
+    fn line(&mut self, text: &str) {
+        for _ in 0..self.indent {
+            self.out.push_str("  ");
+        }
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    /// Prints `text`, then whatever `f` prints one level further in.
+    fn nested(&mut self, text: &str, f: impl FnOnce(&mut Self)) {
+        self.line(text);
+        self.indent += 1;
+        f(self);
+        self.indent -= 1;
+    }
+
+    fn stmts(&mut self, body: &Body, stmts: &[usize]) {
+        for &stmt in stmts {
+            self.stmt(body, stmt);
+        }
+    }
+
+    fn stmt(&mut self, body: &Body, stmt: usize) {
+        match &body.stmts[stmt] {
+            Stmt::VariableDef { name, value } => {
+                self.nested(&format!("{} :=", name), |p| p.expr(body, *value))
+            }
+            Stmt::ConstDef { name, value } => {
+                self.nested(&format!("{} ::", name), |p| p.expr(body, *value))
+            }
+            Stmt::TupleDef {
+                pat,
+                value,
+                mutable,
+            } => {
+                let names = match &body.arenas[*pat] {
+                    Pat::Tuple { pats } => pats
+                        .iter()
+                        .map(|pat| match &body.arenas[*pat] {
+                            Pat::Ident { name } => name.as_str(),
+                            _ => "_",
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    _ => String::new(),
+                };
+                let op = if *mutable { ":=" } else { "::" };
+                self.nested(&format!("{} {}", names, op), |p| p.expr(body, *value))
+            }
+            Stmt::Assign { target, op, value } => {
+                let text = match op {
+                    Some(op) => format!("assign {:?}", op),
+                    None => "assign".to_string(),
+                };
+                self.nested(&text, |p| {
+                    p.expr(body, *target);
+                    p.expr(body, *value);
+                })
+            }
+            Stmt::Expr(expr) => self.expr(body, *expr),
+            Stmt::Item(item) => self.item(*item),
+        }
+    }
+
+    fn item(&mut self, item: Item) {
+        let tree = self.tree;
+        match item {
+            Item::Function(id) => {
+                let function = &tree[id];
+                let mut text = format!("fn {}({})", function.name, params(&function.params));
+                if !function.ret.is_empty() {
+                    let ret: Vec<_> = function.ret.iter().map(type_ref).collect();
+                    write!(text, " -> {}", ret.join(", ")).unwrap();
+                }
+                self.nested(&text, |p| p.stmts(&function.body, &function.body.top_level))
+            }
+            Item::Struct(id) => {
+                let s = &tree[id];
+                let mut text = format!("struct {}", s.name);
+                if let Some(parent) = &s.parent {
+                    write!(text, " <| {}", type_ref(parent)).unwrap();
+                }
+                self.nested(&text, |p| {
+                    for field in &s.fields {
+                        p.line(&format!("{}: {}", field.name, type_ref(&field.ty)));
+                    }
+                })
+            }
+            Item::Trait(id) => {
+                let t = &tree[id];
+                self.nested(&format!("trait {}", t.name), |p| {
+                    for ty in &t.types {
+                        p.line(&format!("type {}", ty));
+                    }
+                    for function in &t.functions {
+                        p.item(Item::Function(*function));
+                    }
+                })
+            }
+            Item::Impl(id) => {
+                let i = &tree[id];
+                let mut text = format!("impl {}", type_ref(&i.self_ty));
+                if let Some(trait_ty) = &i.trait_ty {
+                    write!(text, " <| {}", type_ref(trait_ty)).unwrap();
+                }
+                self.nested(&text, |p| {
+                    for function in &i.functions {
+                        p.item(Item::Function(*function));
+                    }
+                })
+            }
+        }
+    }
+
+    fn expr(&mut self, body: &Body, expr: ExprIdx) {
+        let exprs = |p: &mut Self, exprs: &[ExprIdx]| {
+            for expr in exprs {
+                p.expr(body, *expr);
+            }
+        };
+        let args = |p: &mut Self, args: &[ExprIdx], names: &[Option<SmolStr>]| {
+            for (arg, name) in args.iter().zip(names) {
+                match name {
+                    Some(name) => p.nested(&format!("{}:", name), |p| p.expr(body, *arg)),
+                    None => p.expr(body, *arg),
+                }
+            }
+        };
+
+        match &body.arenas[expr] {
+            Expr::Missing => self.line("missing"),
+            Expr::Binary { op, lhs, rhs } => self.nested(&format!("{:?}", op), |p| {
+                p.expr(body, *lhs);
+                p.expr(body, *rhs);
+            }),
+            Expr::Unary { op, expr } => self.nested(&format!("{:?}", op), |p| p.expr(body, *expr)),
+            Expr::Literal { n: Some(n) } => self.line(&n.to_string()),
+            Expr::FloatLiteral { n: Some(n) } => self.line(&format!("{:?}", n)),
+            Expr::Literal { n: None } | Expr::FloatLiteral { n: None } => {
+                self.line("invalid number")
+            }
+            Expr::StringLiteral { value } => self.line(&format!("{:?}", value)),
+            Expr::FormatString { parts } => self.nested("format", |p| {
+                for part in parts {
+                    match part {
+                        FormatPart::Text(text) => p.line(&format!("{:?}", text)),
+                        FormatPart::Expr(expr) => p.expr(body, *expr),
+                    }
+                }
+            }),
+            Expr::BoolLiteral { value } => self.line(&value.to_string()),
+            Expr::VariableRef { var } => self.line(&format!("ref {}", var)),
+            Expr::Path { ty, name } => self.line(&format!("path {}::{}", type_ref(ty), name)),
+            Expr::Call {
+                callee,
+                args: a,
+                arg_names,
+            } => self.nested("call", |p| {
+                p.expr(body, *callee);
+                args(p, a, arg_names);
+            }),
+            Expr::MethodCall {
+                receiver,
+                name,
+                args: a,
+                arg_names,
+            } => self.nested(&format!("method call .{}", name), |p| {
+                p.expr(body, *receiver);
+                args(p, a, arg_names);
+            }),
+            Expr::Field { expr, name } => {
+                self.nested(&format!("field .{}", name), |p| p.expr(body, *expr))
+            }
+            Expr::Index { base, index } => self.nested("index", |p| {
+                p.expr(body, *base);
+                p.expr(body, *index);
+            }),
+            Expr::Struct { name, fields } => self.nested(&format!("struct {}", name), |p| {
+                for (name, value) in fields {
+                    match name {
+                        Some(name) => p.nested(&format!("{}:", name), |p| p.expr(body, *value)),
+                        None => p.expr(body, *value),
+                    }
+                }
+            }),
+            Expr::Closure {
+                params: ps,
+                body: b,
+            } => self.nested(&format!("closure |{}|", params(ps)), |p| p.expr(body, *b)),
+            Expr::Placeholder => self.line("..."),
+            Expr::Tuple { exprs: es } => self.nested("tuple", |p| exprs(p, es)),
+            Expr::Array { exprs: es } => self.nested("array", |p| exprs(p, es)),
+            Expr::Range { start, end, step } => self.nested("range", |p| {
+                p.expr(body, *start);
+                p.expr(body, *end);
+                if let Some(step) = step {
+                    p.expr(body, *step);
+                }
+            }),
+            Expr::Block { stmts } => self.nested("block", |p| p.stmts(body, stmts)),
+            Expr::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => self.nested("if", |p| {
+                p.expr(body, *condition);
+                p.expr(body, *then_branch);
+                if let Some(else_branch) = else_branch {
+                    p.expr(body, *else_branch);
+                }
+            }),
+            Expr::Loop { body: b } => self.nested("loop", |p| p.expr(body, *b)),
+            Expr::While { condition, body: b } => self.nested("while", |p| {
+                p.expr(body, *condition);
+                p.expr(body, *b);
+            }),
+            Expr::For {
+                pat,
+                index,
+                iterable,
+                body: b,
+            } => self.nested("for", |p| {
+                p.pat(body, *pat);
+                if let Some(index) = index {
+                    p.pat(body, *index);
+                }
+                p.expr(body, *iterable);
+                p.expr(body, *b);
+            }),
+            Expr::Break { value: None } => self.line("break"),
+            Expr::Break { value: Some(value) } => self.nested("break", |p| p.expr(body, *value)),
+            Expr::Continue => self.line("continue"),
+            Expr::Match { scrutinee, arms } => self.nested("match", |p| {
+                p.expr(body, *scrutinee);
+                for arm in arms {
+                    p.nested("arm", |p| {
+                        for pat in &arm.pats {
+                            p.pat(body, *pat);
+                        }
+                        p.nested("=>", |p| p.expr(body, arm.body));
+                    });
+                }
+            }),
+        }
+    }
+
+    fn pat(&mut self, body: &Body, pat: PatIdx) {
+        match &body.arenas[pat] {
+            Pat::Missing => self.line("missing"),
+            Pat::Wildcard => self.line("_"),
+            Pat::Ident { name } => self.line(&format!("ident {}", name)),
+            Pat::Literal { expr } => self.expr(body, *expr),
+            Pat::Range { start, end } => self.nested("range", |p| {
+                p.expr(body, *start);
+                p.expr(body, *end);
+            }),
+            Pat::Tuple { pats } => self.nested("tuple", |p| {
+                for pat in pats {
+                    p.pat(body, *pat);
+                }
+            }),
+            Pat::Struct { name, fields } => self.nested(&format!("struct {}", name), |p| {
+                for (name, pat) in fields {
+                    p.nested(&format!("{}:", name), |p| p.pat(body, *pat));
+                }
+            }),
+        }
+    }
+}
+
+fn params(params: &[Param]) -> String {
+    let params: Vec<_> = params
+        .iter()
+        .map(|param| match &param.ty {
+            TypeRef::Missing => param.name.to_string(),
+            ty => format!("{}: {}", param.name, type_ref(ty)),
+        })
+        .collect();
+    params.join(", ")
+}
+
+fn type_ref(ty: &TypeRef) -> String {
+    match ty {
+        TypeRef::Missing => "?".to_string(),
+        TypeRef::SelfType => "Self".to_string(),
+        TypeRef::Fn => "Fn()".to_string(),
+        TypeRef::Named(name) => name.to_string(),
+    }
+}
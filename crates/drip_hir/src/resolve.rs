@@ -0,0 +1,1036 @@
+//! Resolution of variable references to the definitions they refer to.
+//!
+//! Every body gets a scope, and scopes are chained lexically: a function's
+//! scope sits on top of the scope of the body it's defined in, all the way
+//! down to the root. Blocks and `for` loops get a scope of their own inside
+//! the body's. Within a scope, `:=` and `::` bindings are only visible after
+//! their definition and shadow earlier bindings of the same name, while items,
+//! parameters and loop variables are visible throughout.
+//!
+//! A function can't see the bindings of the functions it's nested in, only
+//! their items. Top-level `::` constants are the exception to the ordering
+//! rule: function bodies and other top-level constants can refer to them even
+//! if they're defined further down in the file.
+//!
+//! A method that takes `self` can use the fields of its struct on their own,
+//! as in `x += 1` for `self.x += 1`. They're visible like parameters, which
+//! shadow them.
+//!
+//! A name in a `match` pattern refers to a constant if one of that name is in
+//! scope, and otherwise binds the matched value for the arm's body.
+//!
+//! Closures are part of the body they're written in, so unlike functions they
+//! can see the bindings around them. Names that aren't defined anywhere can
+//! still refer to a [`Builtin`].
+
+use crate::infer;
+use crate::method_resolution;
+use crate::{
+    Body, Expr, ExprIdx, FormatPart, FunctionId, Item, ItemTree, Pat, PatIdx, Stmt, StructId,
+    TypeRef,
+};
+use smol_str::SmolStr;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Formatter;
+use std::ptr;
+
+/// The body an expression belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BodyId {
+    Root,
+    Function(FunctionId),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Resolution {
+    /// A `:=` or `::` binding; `stmt` is its index in the body's statements.
+    Local {
+        body: BodyId,
+        stmt: usize,
+    },
+    /// A `::` constant at the top level of the file.
+    Global {
+        stmt: usize,
+    },
+    Param {
+        function: FunctionId,
+        index: usize,
+    },
+    /// The value bound by the identifier pattern `pat`.
+    PatBinding {
+        body: BodyId,
+        pat: PatIdx,
+    },
+    /// A field of `self` in the method `function` used without `self.`,
+    /// which is the `index`th field of `strukt`, the struct declaring it.
+    /// Parameters and bindings of the same name shadow it.
+    SelfField {
+        function: FunctionId,
+        strukt: StructId,
+        index: usize,
+    },
+    /// The `index`th parameter of the closure at `closure`.
+    ClosureParam {
+        body: BodyId,
+        closure: ExprIdx,
+        index: usize,
+    },
+    Item(Item),
+    Builtin(Builtin),
+}
+
+/// A function that's always in scope, unless something of the same name
+/// shadows it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Builtin {
+    /// `print(value)` writes `value` to the standard output.
+    Print,
+    /// `println(value)` writes `value` and a newline to the standard output.
+    Println,
+}
+
+impl Builtin {
+    pub const ALL: [Builtin; 2] = [Builtin::Print, Builtin::Println];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Print => "print",
+            Self::Println => "println",
+        }
+    }
+
+    pub fn lookup(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|builtin| builtin.name() == name)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ResolveError {
+    kind: ResolveErrorKind,
+    name: SmolStr,
+    body: BodyId,
+    expr: ExprIdx,
+}
+
+impl ResolveError {
+    pub fn kind(&self) -> ResolveErrorKind {
+        self.kind
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn body(&self) -> BodyId {
+        self.body
+    }
+
+    pub fn expr(&self) -> ExprIdx {
+        self.expr
+    }
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ResolveErrorKind::Unresolved => {
+                write!(f, "cannot find `{}` in this scope", self.name)
+            }
+            ResolveErrorKind::UseBeforeDefinition => {
+                write!(f, "`{}` is used before it is defined", self.name)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolveErrorKind {
+    Unresolved,
+    UseBeforeDefinition,
+}
+
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct Resolutions {
+    resolutions: HashMap<(BodyId, ExprIdx), Resolution>,
+    pats: HashMap<(BodyId, PatIdx), Resolution>,
+    errors: Vec<ResolveError>,
+}
+
+impl Resolutions {
+    /// What the `VariableRef` at `expr` in `body` refers to.
+    pub fn get(&self, body: BodyId, expr: ExprIdx) -> Option<Resolution> {
+        self.resolutions.get(&(body, expr)).copied()
+    }
+
+    /// The constant the identifier pattern `pat` in `body` refers to, or
+    /// `None` if it's a binding.
+    pub fn pat(&self, body: BodyId, pat: PatIdx) -> Option<Resolution> {
+        self.pats.get(&(body, pat)).copied()
+    }
+
+    pub fn errors(&self) -> &[ResolveError] {
+        &self.errors
+    }
+}
+
+/// Resolves every reference in `root` and in the bodies of all items in `tree`.
+pub fn resolve(tree: &ItemTree, root: &Body) -> Resolutions {
+    let mut resolver = Resolver {
+        tree,
+        scopes: Vec::new(),
+        in_global: false,
+        resolutions: Resolutions::default(),
+        target: None,
+        visible: Vec::new(),
+    };
+    resolver.resolve_body(BodyId::Root, root);
+
+    resolver.resolutions
+}
+
+/// Every name that a variable reference at `expr` in `body` could refer to,
+/// innermost first, along with what it would resolve to.
+pub fn scope_at(
+    tree: &ItemTree,
+    root: &Body,
+    body: BodyId,
+    expr: ExprIdx,
+) -> Vec<(SmolStr, Resolution)> {
+    let mut resolver = Resolver {
+        tree,
+        scopes: Vec::new(),
+        in_global: false,
+        resolutions: Resolutions::default(),
+        target: Some((body, expr)),
+        visible: Vec::new(),
+    };
+    resolver.resolve_body(BodyId::Root, root);
+
+    resolver.visible
+}
+
+struct Scope {
+    body: BodyId,
+    /// Whether this is the scope of a whole body, rather than of a block or
+    /// loop inside one.
+    boundary: bool,
+    params: Vec<(SmolStr, Resolution)>,
+    items: Vec<(SmolStr, Resolution)>,
+    /// All `:=` and `::` bindings of the body, in order.
+    bindings: Vec<(SmolStr, Resolution)>,
+    /// How many of `bindings` have been defined so far.
+    defined: usize,
+}
+
+impl Scope {
+    fn defined(&self) -> &[(SmolStr, Resolution)] {
+        &self.bindings[..self.defined]
+    }
+
+    fn pending(&self) -> &[(SmolStr, Resolution)] {
+        &self.bindings[self.defined..]
+    }
+}
+
+struct Resolver<'a> {
+    tree: &'a ItemTree,
+    scopes: Vec<Scope>,
+    /// Whether we're inside the value of a top-level constant.
+    in_global: bool,
+    resolutions: Resolutions,
+    /// The expression to collect the names visible from into `visible`.
+    target: Option<(BodyId, ExprIdx)>,
+    visible: Vec<(SmolStr, Resolution)>,
+}
+
+impl Resolver<'_> {
+    fn resolve_body(&mut self, id: BodyId, body: &Body) {
+        let params: Vec<_> = match id {
+            BodyId::Root => Vec::new(),
+            BodyId::Function(function) => self.tree[function]
+                .params
+                .iter()
+                .enumerate()
+                .map(|(index, param)| (param.name.clone(), Resolution::Param { function, index }))
+                .chain(self.self_fields(function))
+                .collect(),
+        };
+
+        self.push_scope(id, body, &body.top_level, params, true);
+        self.resolve_stmts(body, &body.top_level);
+        self.scopes.pop();
+    }
+
+    fn push_scope(
+        &mut self,
+        id: BodyId,
+        body: &Body,
+        stmts: &[usize],
+        params: Vec<(SmolStr, Resolution)>,
+        boundary: bool,
+    ) {
+        let mut items = Vec::new();
+        let mut bindings = Vec::new();
+        for &idx in stmts {
+            match &body.stmts[idx] {
+                Stmt::ConstDef { name, .. } if id == BodyId::Root && boundary => {
+                    bindings.push((name.clone(), Resolution::Global { stmt: idx }))
+                }
+                Stmt::VariableDef { name, .. } | Stmt::ConstDef { name, .. } => {
+                    let resolution = Resolution::Local {
+                        body: id,
+                        stmt: idx,
+                    };
+                    bindings.push((name.clone(), resolution))
+                }
+                Stmt::TupleDef { pat, .. } => {
+                    if let Pat::Tuple { pats } = &body.arenas[*pat] {
+                        for &pat in pats {
+                            if let Pat::Ident { name } = &body.arenas[pat] {
+                                let resolution = Resolution::PatBinding { body: id, pat };
+                                bindings.push((name.clone(), resolution));
+                            }
+                        }
+                    }
+                }
+                Stmt::Item(item) => {
+                    if let Some(name) = self.item_name(*item) {
+                        items.push((name, Resolution::Item(*item)));
+                    }
+                }
+                Stmt::Assign { .. } | Stmt::Expr(_) => {}
+            }
+        }
+
+        self.scopes.push(Scope {
+            body: id,
+            boundary,
+            params,
+            items,
+            bindings,
+            defined: 0,
+        });
+    }
+
+    /// Resolves `stmts` in the innermost scope, which they were collected into.
+    fn resolve_stmts(&mut self, body: &Body, stmts: &[usize]) {
+        let scope = self.scopes.last().unwrap();
+        let is_global = scope.body == BodyId::Root && scope.boundary;
+
+        for &idx in stmts {
+            match &body.stmts[idx] {
+                Stmt::VariableDef { value, .. } => {
+                    self.resolve_expr(body, *value);
+                    self.scopes.last_mut().unwrap().defined += 1;
+                }
+                Stmt::ConstDef { value, .. } => {
+                    let in_global = self.in_global;
+                    self.in_global |= is_global;
+                    self.resolve_expr(body, *value);
+                    self.in_global = in_global;
+                    self.scopes.last_mut().unwrap().defined += 1;
+                }
+                Stmt::TupleDef { pat, value, .. } => {
+                    self.resolve_expr(body, *value);
+                    let names = match &body.arenas[*pat] {
+                        Pat::Tuple { pats } => pats
+                            .iter()
+                            .filter(|pat| matches!(body.arenas[**pat], Pat::Ident { .. }))
+                            .count(),
+                        _ => 0,
+                    };
+                    self.scopes.last_mut().unwrap().defined += names;
+                }
+                Stmt::Assign { target, value, .. } => {
+                    self.resolve_expr(body, *target);
+                    self.resolve_expr(body, *value);
+                }
+                Stmt::Expr(expr) => self.resolve_expr(body, *expr),
+                Stmt::Item(item) => self.resolve_item(*item),
+            }
+        }
+    }
+
+    fn resolve_item(&mut self, item: Item) {
+        let tree = self.tree;
+        let functions = match item {
+            Item::Function(function) => vec![function],
+            Item::Trait(id) => tree[id].functions.clone(),
+            Item::Impl(id) => tree[id].functions.clone(),
+            Item::Struct(_) => Vec::new(),
+        };
+
+        for function in functions {
+            self.resolve_body(BodyId::Function(function), &tree[function].body);
+        }
+    }
+
+    fn resolve_expr(&mut self, body: &Body, idx: ExprIdx) {
+        if self.target == Some((self.scopes.last().unwrap().body, idx)) {
+            self.visible = self.visible_names();
+        }
+
+        match &body.arenas[idx] {
+            Expr::Binary { lhs, rhs, .. } => {
+                self.resolve_expr(body, *lhs);
+                self.resolve_expr(body, *rhs);
+            }
+            Expr::Unary { expr, .. } => self.resolve_expr(body, *expr),
+            Expr::VariableRef { var } => {
+                let id = self.scopes.last().unwrap().body;
+                match self.lookup(var) {
+                    Ok(resolution) => {
+                        self.resolutions.resolutions.insert((id, idx), resolution);
+                    }
+                    Err(kind) => self.resolutions.errors.push(ResolveError {
+                        kind,
+                        name: var.clone(),
+                        body: id,
+                        expr: idx,
+                    }),
+                }
+            }
+            Expr::Path { ty, name } => {
+                let id = self.scopes.last().unwrap().body;
+                match self.lookup_path(id, ty, name) {
+                    Some(function) => {
+                        let resolution = Resolution::Item(Item::Function(function));
+                        self.resolutions.resolutions.insert((id, idx), resolution);
+                    }
+                    None => self.resolutions.errors.push(ResolveError {
+                        kind: ResolveErrorKind::Unresolved,
+                        // a path always starts with a type
+                        name: match ty {
+                            TypeRef::Named(ty) => format!("{}::{}", ty, name).into(),
+                            _ => format!("Self::{}", name).into(),
+                        },
+                        body: id,
+                        expr: idx,
+                    }),
+                }
+            }
+            Expr::Call { callee, args, .. } => {
+                self.resolve_expr(body, *callee);
+                for arg in args {
+                    self.resolve_expr(body, *arg);
+                }
+            }
+            Expr::MethodCall { receiver, args, .. } => {
+                self.resolve_expr(body, *receiver);
+                for arg in args {
+                    self.resolve_expr(body, *arg);
+                }
+            }
+            Expr::Field { expr, .. } => self.resolve_expr(body, *expr),
+            Expr::Index { base, index } => {
+                self.resolve_expr(body, *base);
+                self.resolve_expr(body, *index);
+            }
+            Expr::Struct { fields, .. } => {
+                for (_, value) in fields {
+                    self.resolve_expr(body, *value);
+                }
+            }
+            Expr::FormatString { parts } => {
+                for part in parts {
+                    if let FormatPart::Expr(expr) = part {
+                        self.resolve_expr(body, *expr);
+                    }
+                }
+            }
+            Expr::Closure {
+                params,
+                body: closure_body,
+            } => {
+                let id = self.scopes.last().unwrap().body;
+                let params = params
+                    .iter()
+                    .enumerate()
+                    .map(|(index, param)| {
+                        let resolution = Resolution::ClosureParam {
+                            body: id,
+                            closure: idx,
+                            index,
+                        };
+                        (param.name.clone(), resolution)
+                    })
+                    .collect();
+                self.push_scope(id, body, &[], params, false);
+                self.resolve_expr(body, *closure_body);
+                self.scopes.pop();
+            }
+            Expr::Tuple { exprs } | Expr::Array { exprs } => {
+                for expr in exprs {
+                    self.resolve_expr(body, *expr);
+                }
+            }
+            Expr::Range { start, end, step } => {
+                self.resolve_expr(body, *start);
+                self.resolve_expr(body, *end);
+                if let Some(step) = step {
+                    self.resolve_expr(body, *step);
+                }
+            }
+            Expr::Block { stmts } => {
+                let id = self.scopes.last().unwrap().body;
+                self.push_scope(id, body, stmts, Vec::new(), false);
+                self.resolve_stmts(body, stmts);
+                self.scopes.pop();
+            }
+            Expr::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.resolve_expr(body, *condition);
+                self.resolve_expr(body, *then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.resolve_expr(body, *else_branch);
+                }
+            }
+            Expr::Loop { body: loop_body } => self.resolve_expr(body, *loop_body),
+            Expr::While {
+                condition,
+                body: loop_body,
+            } => {
+                self.resolve_expr(body, *condition);
+                self.resolve_expr(body, *loop_body);
+            }
+            Expr::For {
+                pat,
+                index,
+                iterable,
+                body: loop_body,
+            } => {
+                self.resolve_expr(body, *iterable);
+
+                let id = self.scopes.last().unwrap().body;
+                let mut bindings = Vec::new();
+                for pat in std::iter::once(pat).chain(index) {
+                    self.bind_pat(body, *pat, &mut bindings);
+                }
+                self.push_scope(id, body, &[], bindings, false);
+                self.resolve_expr(body, *loop_body);
+                self.scopes.pop();
+            }
+            Expr::Break { value } => {
+                if let Some(value) = value {
+                    self.resolve_expr(body, *value);
+                }
+            }
+            Expr::Match { scrutinee, arms } => {
+                self.resolve_expr(body, *scrutinee);
+
+                let id = self.scopes.last().unwrap().body;
+                for arm in arms {
+                    let mut bindings = Vec::new();
+                    for pat in &arm.pats {
+                        self.resolve_pat(body, *pat, &mut bindings);
+                    }
+                    self.push_scope(id, body, &[], bindings, false);
+                    self.resolve_expr(body, arm.body);
+                    self.scopes.pop();
+                }
+            }
+            Expr::Missing
+            | Expr::Continue
+            | Expr::Placeholder
+            | Expr::Literal { .. }
+            | Expr::FloatLiteral { .. }
+            | Expr::StringLiteral { .. }
+            | Expr::BoolLiteral { .. } => {}
+        }
+    }
+
+    /// Resolves the constants and expressions in `pat`, and collects the
+    /// names it binds into `bindings`.
+    fn resolve_pat(&mut self, body: &Body, pat: PatIdx, bindings: &mut Vec<(SmolStr, Resolution)>) {
+        let id = self.scopes.last().unwrap().body;
+        match &body.arenas[pat] {
+            Pat::Ident { name } => {
+                let constant = match self.lookup(name) {
+                    Ok(resolution @ Resolution::Global { .. }) => Some(resolution),
+                    Ok(resolution @ Resolution::Local { body: local, stmt })
+                        if local == id && matches!(body.stmts[stmt], Stmt::ConstDef { .. }) =>
+                    {
+                        Some(resolution)
+                    }
+                    _ => None,
+                };
+
+                match constant {
+                    Some(resolution) => {
+                        self.resolutions.pats.insert((id, pat), resolution);
+                    }
+                    None => bindings.push((name.clone(), Resolution::PatBinding { body: id, pat })),
+                }
+            }
+            Pat::Literal { expr } => self.resolve_expr(body, *expr),
+            Pat::Range { start, end } => {
+                self.resolve_expr(body, *start);
+                self.resolve_expr(body, *end);
+            }
+            Pat::Tuple { pats } => {
+                for pat in pats {
+                    self.resolve_pat(body, *pat, bindings);
+                }
+            }
+            Pat::Struct { fields, .. } => {
+                for (_, pat) in fields {
+                    self.resolve_pat(body, *pat, bindings);
+                }
+            }
+            Pat::Missing | Pat::Wildcard => {}
+        }
+    }
+
+    /// Collects the names a `for` loop's `pat` binds into `bindings`. Unlike
+    /// in a `match`, a name always binds, even if a constant has it too.
+    fn bind_pat(&mut self, body: &Body, pat: PatIdx, bindings: &mut Vec<(SmolStr, Resolution)>) {
+        let id = self.scopes.last().unwrap().body;
+        match &body.arenas[pat] {
+            Pat::Ident { name } => {
+                bindings.push((name.clone(), Resolution::PatBinding { body: id, pat }))
+            }
+            Pat::Tuple { pats } => {
+                for pat in pats {
+                    self.bind_pat(body, *pat, bindings);
+                }
+            }
+            _ => self.resolve_pat(body, pat, bindings),
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Result<Resolution, ResolveErrorKind> {
+        let in_function = self
+            .scopes
+            .iter()
+            .any(|scope| scope.boundary && scope.body != BodyId::Root);
+        let mut locals_visible = true;
+        let mut defined_later = false;
+
+        for scope in self.scopes.iter().rev() {
+            let is_root = scope.body == BodyId::Root;
+
+            if locals_visible || is_root {
+                if let Some(resolution) = find(scope.defined().iter().rev(), name) {
+                    return Ok(resolution);
+                }
+                if let Some(resolution) = find(scope.params.iter(), name) {
+                    return Ok(resolution);
+                }
+            }
+
+            if let Some(resolution) = find(scope.items.iter(), name) {
+                return Ok(resolution);
+            }
+
+            if let Some(resolution) = find(scope.pending().iter(), name) {
+                let global = matches!(resolution, Resolution::Global { .. });
+                if is_root && (in_function || self.in_global) && global {
+                    return Ok(resolution);
+                }
+                defined_later |= locals_visible || is_root;
+            }
+
+            if scope.boundary && !is_root {
+                locals_visible = false;
+            }
+        }
+
+        if defined_later {
+            Err(ResolveErrorKind::UseBeforeDefinition)
+        } else if let Some(builtin) = Builtin::lookup(name) {
+            Ok(Resolution::Builtin(builtin))
+        } else {
+            Err(ResolveErrorKind::Unresolved)
+        }
+    }
+
+    /// Every name that `lookup` resolves in the current scope, innermost
+    /// first.
+    fn visible_names(&self) -> Vec<(SmolStr, Resolution)> {
+        let mut names: Vec<&str> = Vec::new();
+        for scope in self.scopes.iter().rev() {
+            let entries = (scope.defined().iter().rev())
+                .chain(&scope.params)
+                .chain(&scope.items)
+                .chain(scope.pending());
+            for (name, _) in entries {
+                if !names.contains(&name.as_str()) {
+                    names.push(name);
+                }
+            }
+        }
+        for builtin in Builtin::ALL.iter() {
+            if !names.contains(&builtin.name()) {
+                names.push(builtin.name());
+            }
+        }
+
+        names
+            .into_iter()
+            .filter_map(|name| Some((name.into(), self.lookup(name).ok()?)))
+            .collect()
+    }
+
+    /// The function `ty::name` refers to in the body `id`, where `Self` is
+    /// the type of the impl the body's function is in.
+    fn lookup_path(&self, id: BodyId, ty: &TypeRef, name: &str) -> Option<FunctionId> {
+        let ty = match (ty, id) {
+            (TypeRef::SelfType, BodyId::Function(function)) => {
+                let (_, imp) = self
+                    .tree
+                    .impls()
+                    .find(|(_, imp)| imp.functions.contains(&function))?;
+                &imp.self_ty
+            }
+            _ => ty,
+        };
+        let ty = infer::lower_type_ref(self.tree, ty);
+        method_resolution::lookup_path(self.tree, &ty, name).ok()
+    }
+
+    /// The fields of `self` that a method of a struct can use on their own.
+    fn self_fields(&self, function: FunctionId) -> Vec<(SmolStr, Resolution)> {
+        let takes_self =
+            (self.tree[function].params.first()).is_some_and(|param| param.name == "self");
+        let imp = self
+            .tree
+            .impls()
+            .find(|(_, imp)| imp.functions.contains(&function));
+        let strukt = match imp {
+            Some((_, imp)) if takes_self => match infer::lower_type_ref(self.tree, &imp.self_ty) {
+                infer::Ty::Struct { id, .. } => id,
+                _ => return Vec::new(),
+            },
+            _ => return Vec::new(),
+        };
+
+        method_resolution::all_fields(self.tree, strukt)
+            .into_iter()
+            .filter_map(|field| {
+                // inherited fields are declared by another struct
+                let (strukt, index) = self.tree.structs().find_map(|(id, s)| {
+                    let index = s.fields.iter().position(|f| ptr::eq(f, field))?;
+                    Some((id, index))
+                })?;
+                let resolution = Resolution::SelfField {
+                    function,
+                    strukt,
+                    index,
+                };
+                Some((field.name.clone(), resolution))
+            })
+            .collect()
+    }
+
+    fn item_name(&self, item: Item) -> Option<SmolStr> {
+        match item {
+            Item::Function(id) => Some(self.tree[id].name.clone()),
+            Item::Struct(id) => Some(self.tree[id].name.clone()),
+            Item::Trait(id) => Some(self.tree[id].name.clone()),
+            Item::Impl(_) => None,
+        }
+    }
+}
+
+fn find<'a>(
+    mut entries: impl Iterator<Item = &'a (SmolStr, Resolution)>,
+    name: &str,
+) -> Option<Resolution> {
+    entries
+        .find(|(entry, _)| entry == name)
+        .map(|(_, resolution)| *resolution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use drip_ast::AstNode;
+
+    fn lower(input: &str) -> (ItemTree, Body) {
+        crate::lower(drip_ast::Root::cast(drip_parser::parse(input).syntax()).unwrap())
+    }
+
+    /// Every reference to `name` in `body`, in the order they were lowered.
+    fn refs(body: &Body, name: &str) -> Vec<ExprIdx> {
+        body.arenas
+            .exprs
+            .iter()
+            .filter(|(_, expr)| matches!(expr, Expr::VariableRef { var } if var == name))
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    fn function<'a>(tree: &'a ItemTree, name: &str) -> (FunctionId, &'a Body) {
+        tree.functions()
+            .find(|(_, function)| function.name == name)
+            .map(|(id, function)| (id, &function.body))
+            .unwrap()
+    }
+
+    #[test]
+    fn resolve_shadowed_globals() {
+        let (tree, root) = lower("arr :: 1\narr :: arr + 1\narr");
+        let resolutions = resolve(&tree, &root);
+        let arr = refs(&root, "arr");
+
+        assert_eq!(
+            resolutions.get(BodyId::Root, arr[0]),
+            Some(Resolution::Global { stmt: 0 })
+        );
+        assert_eq!(
+            resolutions.get(BodyId::Root, arr[1]),
+            Some(Resolution::Global { stmt: 1 })
+        );
+        assert!(resolutions.errors().is_empty());
+    }
+
+    #[test]
+    fn resolve_params_and_locals() {
+        let (tree, root) = lower("f :: (a: i32) { b := a\nb }");
+        let resolutions = resolve(&tree, &root);
+        let (f, body) = function(&tree, "f");
+
+        assert_eq!(
+            resolutions.get(BodyId::Function(f), refs(body, "a")[0]),
+            Some(Resolution::Param {
+                function: f,
+                index: 0,
+            })
+        );
+        assert_eq!(
+            resolutions.get(BodyId::Function(f), refs(body, "b")[0]),
+            Some(Resolution::Local {
+                body: BodyId::Function(f),
+                stmt: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn resolve_forward_references_to_items() {
+        let (tree, root) = lower("main :: () { variables }\nvariables :: () { 1 }");
+        let resolutions = resolve(&tree, &root);
+        let (main, body) = function(&tree, "main");
+        let (variables, _) = function(&tree, "variables");
+
+        assert_eq!(
+            resolutions.get(BodyId::Function(main), refs(body, "variables")[0]),
+            Some(Resolution::Item(Item::Function(variables)))
+        );
+    }
+
+    #[test]
+    fn functions_see_later_globals_but_not_enclosing_locals() {
+        let (tree, root) = lower("f :: () { x := 1\ng :: () { x + PI } }\nPI :: 3");
+        let resolutions = resolve(&tree, &root);
+        let (g, body) = function(&tree, "g");
+
+        assert_eq!(
+            resolutions.get(BodyId::Function(g), refs(body, "PI")[0]),
+            Some(Resolution::Global { stmt: 1 })
+        );
+
+        let errors: Vec<_> = resolutions.errors().iter().map(|e| e.to_string()).collect();
+        assert_eq!(errors, ["cannot find `x` in this scope"]);
+    }
+
+    #[test]
+    fn globals_see_later_globals() {
+        let (tree, root) = lower("TAU :: PI * 2\nPI :: 3\nx := PI\nPI");
+        let resolutions = resolve(&tree, &root);
+        let pi = refs(&root, "PI");
+
+        assert_eq!(
+            resolutions.get(BodyId::Root, pi[0]),
+            Some(Resolution::Global { stmt: 1 })
+        );
+        assert_eq!(
+            resolutions.get(BodyId::Root, pi[1]),
+            Some(Resolution::Global { stmt: 1 })
+        );
+        assert!(resolutions.errors().is_empty());
+    }
+
+    #[test]
+    fn resolve_in_blocks_and_loops() {
+        let (tree, root) = lower("x := 1\ny := { x := 2\nx }\nfor i in 0..x { i }\nx + i");
+        let resolutions = resolve(&tree, &root);
+        let x = refs(&root, "x");
+        let i = refs(&root, "i");
+
+        // the inner `x` is lowered, and so numbered, before `y`
+        assert_eq!(
+            resolutions.get(BodyId::Root, x[0]),
+            Some(Resolution::Local {
+                body: BodyId::Root,
+                stmt: 1,
+            })
+        );
+        assert_eq!(
+            resolutions.get(BodyId::Root, x[2]),
+            Some(Resolution::Local {
+                body: BodyId::Root,
+                stmt: 0,
+            })
+        );
+        assert!(matches!(
+            resolutions.get(BodyId::Root, i[0]),
+            Some(Resolution::PatBinding { .. })
+        ));
+
+        let errors: Vec<_> = resolutions.errors().iter().map(|e| e.to_string()).collect();
+        assert_eq!(errors, ["cannot find `i` in this scope"]);
+    }
+
+    #[test]
+    fn resolve_constants_and_bindings_in_patterns() {
+        let (tree, root) = lower("N :: 3\nx := 1\nmatch x { N => 0\nn => n\n_ => n }");
+        let resolutions = resolve(&tree, &root);
+        let pats: Vec<_> = root.arenas.pats().map(|(pat, _)| pat).collect();
+
+        assert_eq!(
+            resolutions.pat(BodyId::Root, pats[0]),
+            Some(Resolution::Global { stmt: 0 })
+        );
+        assert_eq!(resolutions.pat(BodyId::Root, pats[1]), None);
+        assert_eq!(
+            resolutions.get(BodyId::Root, refs(&root, "n")[0]),
+            Some(Resolution::PatBinding {
+                body: BodyId::Root,
+                pat: pats[1],
+            })
+        );
+
+        let errors: Vec<_> = resolutions.errors().iter().map(|e| e.to_string()).collect();
+        assert_eq!(errors, ["cannot find `n` in this scope"]);
+    }
+
+    #[test]
+    fn resolve_several_names_defined_at_once() {
+        let (tree, root) = lower("a, b, c :: 10, 3, 5\nc + a");
+        let resolutions = resolve(&tree, &root);
+        let pats: Vec<_> = root.arenas.pats().map(|(pat, _)| pat).collect();
+
+        assert_eq!(
+            resolutions.get(BodyId::Root, refs(&root, "c")[0]),
+            Some(Resolution::PatBinding {
+                body: BodyId::Root,
+                pat: pats[2],
+            })
+        );
+        assert_eq!(
+            resolutions.get(BodyId::Root, refs(&root, "a")[0]),
+            Some(Resolution::PatBinding {
+                body: BodyId::Root,
+                pat: pats[0],
+            })
+        );
+        assert!(resolutions.errors().is_empty());
+    }
+
+    #[test]
+    fn resolve_paths_to_functions_of_impls() {
+        let (tree, root) =
+            lower("V :: struct { }\nV :: impl {\n    new :: () -> Self { Self::zero() }\n    zero :: () -> V { V { } }\n}\nv :: V::new()\nw :: V::old()");
+        let resolutions = resolve(&tree, &root);
+        let (new, new_body) = function(&tree, "new");
+        let (zero, _) = function(&tree, "zero");
+        let paths = |body: &Body| -> Vec<ExprIdx> {
+            body.arenas
+                .exprs
+                .iter()
+                .filter(|(_, expr)| matches!(expr, Expr::Path { .. }))
+                .map(|(idx, _)| idx)
+                .collect()
+        };
+
+        assert_eq!(
+            resolutions.get(BodyId::Function(new), paths(new_body)[0]),
+            Some(Resolution::Item(Item::Function(zero)))
+        );
+        assert_eq!(
+            resolutions.get(BodyId::Root, paths(&root)[0]),
+            Some(Resolution::Item(Item::Function(new)))
+        );
+        let errors: Vec<_> = resolutions.errors().iter().map(|e| e.to_string()).collect();
+        assert_eq!(errors, ["cannot find `V::old` in this scope"]);
+    }
+
+    #[test]
+    fn resolve_closure_params_captures_and_builtins() {
+        let (tree, root) = lower("n := 1\nadd :: |x| x + n\nprintln(add(2))");
+        let resolutions = resolve(&tree, &root);
+        let closure = root
+            .arenas
+            .exprs
+            .iter()
+            .find(|(_, expr)| matches!(expr, Expr::Closure { .. }))
+            .map(|(idx, _)| idx)
+            .unwrap();
+
+        assert_eq!(
+            resolutions.get(BodyId::Root, refs(&root, "x")[0]),
+            Some(Resolution::ClosureParam {
+                body: BodyId::Root,
+                closure,
+                index: 0,
+            })
+        );
+        assert_eq!(
+            resolutions.get(BodyId::Root, refs(&root, "n")[0]),
+            Some(Resolution::Local {
+                body: BodyId::Root,
+                stmt: 0,
+            })
+        );
+        assert_eq!(
+            resolutions.get(BodyId::Root, refs(&root, "println")[0]),
+            Some(Resolution::Builtin(Builtin::Println))
+        );
+        assert!(resolutions.errors().is_empty());
+    }
+
+    #[test]
+    fn collect_the_names_in_scope() {
+        let (tree, root) =
+            lower("a :: 1\nf :: (p: i32) { b := p\nfor i in 0..b { x }\nc := 2 }\nd := 3");
+        let (f, body) = function(&tree, "f");
+        let x = refs(body, "x")[0];
+
+        let names: Vec<_> = scope_at(&tree, &root, BodyId::Function(f), x)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, ["i", "b", "p", "a", "f", "print", "println"]);
+    }
+
+    #[test]
+    fn report_unresolved_and_use_before_definition() {
+        let (tree, root) = lower("a\nb\na :: 1");
+        let resolutions = resolve(&tree, &root);
+
+        let errors: Vec<_> = resolutions
+            .errors()
+            .iter()
+            .map(|error| (error.kind(), error.name(), error.expr()))
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                (
+                    ResolveErrorKind::UseBeforeDefinition,
+                    "a",
+                    refs(&root, "a")[0]
+                ),
+                (ResolveErrorKind::Unresolved, "b", refs(&root, "b")[0]),
+            ]
+        );
+    }
+}
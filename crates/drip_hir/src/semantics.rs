@@ -0,0 +1,698 @@
+//! What the names in a file refer to, for tools that start from a position in
+//! the source rather than from the HIR.
+//!
+//! Every identifier that defines or refers to something is a [`Name`]: the
+//! names bound by `:=`, `::`, parameters, loop variables and patterns, the
+//! names of items and struct fields, and the references to all of them, be it
+//! as variables, fields, methods, struct literals or types. Two names with
+//! the same [`Definition`] are the same thing, so renaming one means renaming
+//! all of them.
+
+use crate::db::{FileId, HirDatabase, LoweredFile};
+use crate::infer::{self, InferenceResults, Ty};
+use crate::method_resolution;
+use crate::resolve::{BodyId, Resolution, Resolutions};
+use crate::{
+    Body, Expr, ExprIdx, ExprSource, Item, ItemTree, Pat, PatIdx, Stmt, StructId, TypeRef,
+};
+use drip_ast::{self as ast, AstNode, AstToken};
+use drip_syntax::{SyntaxKind, SyntaxNode, SyntaxToken};
+use smol_str::SmolStr;
+use std::collections::HashMap;
+use std::ptr;
+use text_size::{TextRange, TextSize};
+
+/// Something a name can refer to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Definition {
+    /// Anything a variable can refer to, which includes items and builtins.
+    Resolved(Resolution),
+    /// The `index`th field of `strukt`.
+    Field { strukt: StructId, index: usize },
+}
+
+/// An identifier that defines or refers to a [`Definition`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Name {
+    pub range: TextRange,
+    pub definition: Definition,
+    /// Whether this is where the definition is, rather than a reference to
+    /// it.
+    pub is_definition: bool,
+}
+
+/// Every name in `file`, in the order they appear.
+pub fn names(db: &dyn HirDatabase, file: FileId) -> Vec<Name> {
+    let syntax = db.parse(file).syntax();
+    let lowered = db.lower_file(file);
+    let resolutions = db.resolutions(file);
+    let inference = db.inference(file);
+    let mut collector = Collector {
+        syntax: &syntax,
+        tree: &lowered.tree,
+        resolutions: &resolutions,
+        inference: &inference,
+        names: Vec::new(),
+    };
+
+    let items = item_syntax(&syntax, &lowered.tree, &lowered.root);
+    for (item, node) in &items {
+        collector.item(*item, node);
+    }
+    for (id, body) in bodies(&lowered.tree, &lowered.root) {
+        collector.body(id, body);
+    }
+    collector.types();
+
+    let mut names = collector.names;
+    // the field and the variable in a shorthand like `Vec2 { x }` share a
+    // range, and the field comes first
+    names.sort_by_key(|name| {
+        let is_field = matches!(name.definition, Definition::Field { .. });
+        (name.range.start(), !is_field)
+    });
+    names
+}
+
+/// Whether `name` is half of a shorthand like `Vec2 { x }`, which names a
+/// field and a variable at once.
+pub fn is_shorthand(names: &[Name], name: &Name) -> bool {
+    names
+        .iter()
+        .any(|other| other.range == name.range && other.definition != name.definition)
+}
+
+/// The name at `offset`, which can be at either end of it.
+pub fn name_at(db: &dyn HirDatabase, file: FileId, offset: TextSize) -> Option<Name> {
+    let names = names(db, file);
+    let at = |name: &&Name| name.range.contains_inclusive(offset);
+    // prefer the name that starts at `offset` to one that ends there, and
+    // the variable of a shorthand to its field
+    names
+        .iter()
+        .filter(at)
+        .max_by_key(|name| name.range.start())
+        .copied()
+}
+
+/// Where `definition` is defined, if it's defined in the file at all.
+pub fn definition_range(
+    db: &dyn HirDatabase,
+    file: FileId,
+    definition: Definition,
+) -> Option<TextRange> {
+    names(db, file)
+        .into_iter()
+        .find(|name| name.is_definition && name.definition == definition)
+        .map(|name| name.range)
+}
+
+/// The definition and every reference to `definition`, in order.
+pub fn references(db: &dyn HirDatabase, file: FileId, definition: Definition) -> Vec<Name> {
+    names(db, file)
+        .into_iter()
+        .filter(|name| name.definition == definition)
+        .collect()
+}
+
+/// The type of what `definition` stands for, if it's a value.
+pub fn definition_ty(db: &dyn HirDatabase, file: FileId, definition: Definition) -> Option<Ty> {
+    let lowered = db.lower_file(file);
+    let inference = db.inference(file);
+    let tree = &lowered.tree;
+    let resolution = match definition {
+        Definition::Field { strukt, index } => {
+            let ty = &tree[strukt].fields[index].ty;
+            return Some(match ty {
+                TypeRef::SelfType => infer::struct_ty(tree, strukt),
+                ty => infer::lower_type_ref(tree, ty),
+            });
+        }
+        Definition::Resolved(resolution) => resolution,
+    };
+
+    let ty = match resolution {
+        Resolution::Local { body, stmt } => inference.body(body).binding(stmt)?.clone(),
+        Resolution::Global { stmt } => inference.body(BodyId::Root).binding(stmt)?.clone(),
+        Resolution::Param { function, index } => match inference.function(function) {
+            Ty::Fn { params, .. } => params.get(index)?.clone(),
+            _ => return None,
+        },
+        Resolution::SelfField { strukt, index, .. } => {
+            return definition_ty(db, file, Definition::Field { strukt, index });
+        }
+        Resolution::PatBinding { body, pat } => inference.body(body)[pat].clone(),
+        Resolution::ClosureParam {
+            body,
+            closure,
+            index,
+        } => match &inference.body(body)[closure] {
+            Ty::Fn { params, .. } => params.get(index)?.clone(),
+            _ => return None,
+        },
+        Resolution::Item(Item::Function(function)) => inference.function(function).clone(),
+        Resolution::Item(_) => return None,
+        Resolution::Builtin(_) => Ty::Fn {
+            params: vec![Ty::Unknown],
+            ret: Box::new(Ty::Tuple(Vec::new())),
+        },
+    };
+    Some(ty)
+}
+
+/// The innermost expression around `offset`, and the body it's in.
+pub fn expr_at(db: &dyn HirDatabase, file: FileId, offset: TextSize) -> Option<(BodyId, ExprIdx)> {
+    let syntax = db.parse(file).syntax();
+    let lowered = db.lower_file(file);
+    // between two tokens, the one after `offset` unless it's whitespace
+    let token = syntax.token_at_offset(offset).max_by_key(|token| {
+        !matches!(token.kind(), SyntaxKind::Whitespace | SyntaxKind::Comment)
+    })?;
+    let mut exprs = token.ancestors().filter_map(ast::Expr::cast);
+    exprs.find_map(|expr| lowered_expr(&lowered, &expr))
+}
+
+/// The expression that `expr` was lowered to, and the body it's in.
+pub fn expr_of(db: &dyn HirDatabase, file: FileId, expr: &ast::Expr) -> Option<(BodyId, ExprIdx)> {
+    lowered_expr(&db.lower_file(file), expr)
+}
+
+fn lowered_expr(lowered: &LoweredFile, expr: &ast::Expr) -> Option<(BodyId, ExprIdx)> {
+    bodies(&lowered.tree, &lowered.root).find_map(|(id, body)| {
+        let idx = body.arenas.source_map().node_expr(expr)?;
+        Some((id, idx))
+    })
+}
+
+/// Every field of `strukt`, including the ones it inherits, nearest first.
+pub fn fields(db: &dyn HirDatabase, file: FileId, strukt: StructId) -> Vec<Definition> {
+    let tree = &db.lower_file(file).tree;
+    method_resolution::all_fields(tree, strukt)
+        .into_iter()
+        .filter_map(|field| {
+            tree.structs().find_map(|(id, s)| {
+                let index = s.fields.iter().position(|f| ptr::eq(f, field))?;
+                Some(Definition::Field { strukt: id, index })
+            })
+        })
+        .collect()
+}
+
+/// The type of the expression `expr` in `body`.
+pub fn expr_ty(db: &dyn HirDatabase, file: FileId, body: BodyId, expr: ExprIdx) -> Ty {
+    db.inference(file).body(body)[expr].clone()
+}
+
+/// The name of what `definition` refers to.
+pub fn definition_name(db: &dyn HirDatabase, file: FileId, definition: Definition) -> SmolStr {
+    let lowered = db.lower_file(file);
+    let (tree, root) = (&lowered.tree, &lowered.root);
+    let resolution = match definition {
+        Definition::Field { strukt, index } => return tree[strukt].fields[index].name.clone(),
+        Definition::Resolved(resolution) => resolution,
+    };
+
+    match resolution {
+        Resolution::Local { body, stmt } => stmt_name(&body_of(tree, root, body).stmts[stmt]),
+        Resolution::Global { stmt } => stmt_name(&root.stmts[stmt]),
+        Resolution::Param { function, index } => tree[function].params[index].name.clone(),
+        Resolution::SelfField { strukt, index, .. } => tree[strukt].fields[index].name.clone(),
+        Resolution::PatBinding { body, pat } => match &body_of(tree, root, body).arenas[pat] {
+            Pat::Ident { name } => name.clone(),
+            _ => SmolStr::default(),
+        },
+        Resolution::ClosureParam {
+            body,
+            closure,
+            index,
+        } => match &body_of(tree, root, body).arenas[closure] {
+            Expr::Closure { params, .. } => params[index].name.clone(),
+            _ => SmolStr::default(),
+        },
+        Resolution::Item(Item::Function(id)) => tree[id].name.clone(),
+        Resolution::Item(Item::Struct(id)) => tree[id].name.clone(),
+        Resolution::Item(Item::Trait(id)) => tree[id].name.clone(),
+        Resolution::Item(Item::Impl(_)) => SmolStr::default(),
+        Resolution::Builtin(builtin) => builtin.name().into(),
+    }
+}
+
+fn stmt_name(stmt: &Stmt) -> SmolStr {
+    match stmt {
+        Stmt::VariableDef { name, .. } | Stmt::ConstDef { name, .. } => name.clone(),
+        _ => SmolStr::default(),
+    }
+}
+
+fn body_of<'a>(tree: &'a ItemTree, root: &'a Body, id: BodyId) -> &'a Body {
+    match id {
+        BodyId::Root => root,
+        BodyId::Function(function) => &tree[function].body,
+    }
+}
+
+fn bodies<'a>(tree: &'a ItemTree, root: &'a Body) -> impl Iterator<Item = (BodyId, &'a Body)> {
+    let functions = tree
+        .functions()
+        .map(|(id, function)| (BodyId::Function(id), &function.body));
+    std::iter::once((BodyId::Root, root)).chain(functions)
+}
+
+/// The syntax of every item. Most items are statements of a body, while the
+/// functions of impls and traits are found in the order they were lowered.
+fn item_syntax(syntax: &SyntaxNode, tree: &ItemTree, root: &Body) -> HashMap<Item, SyntaxNode> {
+    let mut items = HashMap::new();
+    for (_, body) in bodies(tree, root) {
+        for (idx, stmt) in body.stmts.iter().enumerate() {
+            if let (Stmt::Item(item), Some(ptr)) = (stmt, body.arenas.source_map().stmt_syntax(idx))
+            {
+                items.insert(*item, ptr.to_node(syntax).syntax().clone());
+            }
+        }
+    }
+
+    let members = |node: Option<&SyntaxNode>| -> Vec<ast::FnDef> {
+        let stmts: Vec<_> = match node.cloned() {
+            Some(node) => match ast::Stmt::cast(node) {
+                Some(ast::Stmt::ImplDef(ast)) => ast.items().collect(),
+                Some(ast::Stmt::TraitDef(ast)) => ast.members().collect(),
+                _ => Vec::new(),
+            },
+            None => Vec::new(),
+        };
+        stmts
+            .into_iter()
+            .filter_map(|stmt| match stmt {
+                ast::Stmt::FnDef(ast) if ast.name().is_some() => Some(ast),
+                _ => None,
+            })
+            .collect()
+    };
+    let owners = tree
+        .impls()
+        .map(|(id, imp)| (Item::Impl(id), &imp.functions))
+        .chain(tree.traits().map(|(id, t)| (Item::Trait(id), &t.functions)));
+    for (owner, functions) in owners {
+        let syntax = members(items.get(&owner));
+        for (function, ast) in functions.iter().zip(syntax) {
+            items.insert(Item::Function(*function), ast.syntax().clone());
+        }
+    }
+
+    items
+}
+
+struct Collector<'a> {
+    syntax: &'a SyntaxNode,
+    tree: &'a ItemTree,
+    resolutions: &'a Resolutions,
+    inference: &'a InferenceResults,
+    names: Vec<Name>,
+}
+
+impl Collector<'_> {
+    fn push(&mut self, range: TextRange, definition: Definition, is_definition: bool) {
+        self.names.push(Name {
+            range,
+            definition,
+            is_definition,
+        });
+    }
+
+    fn push_token(&mut self, token: Option<SyntaxToken>, definition: Definition, is_def: bool) {
+        if let Some(token) = token {
+            self.push(token.text_range(), definition, is_def);
+        }
+    }
+
+    fn item(&mut self, item: Item, node: &SyntaxNode) {
+        let definition = Definition::Resolved(Resolution::Item(item));
+        let name = match ast::Stmt::cast(node.clone()) {
+            Some(ast::Stmt::FnDef(ast)) => ast.name(),
+            Some(ast::Stmt::TraitDef(ast)) => ast.name(),
+            Some(ast::Stmt::StructDef(ast)) => {
+                if let Item::Struct(strukt) = item {
+                    let fields = ast.fields().filter_map(|field| field.name());
+                    for (index, field) in fields.enumerate() {
+                        let definition = Definition::Field { strukt, index };
+                        self.push(field.syntax().text_range(), definition, true);
+                    }
+                }
+                ast.name()
+            }
+            _ => None,
+        };
+        self.push_token(name.map(|name| name.syntax().clone()), definition, true);
+
+        if let Some(ast::Stmt::FnDef(ast)) = ast::Stmt::cast(node.clone()) {
+            if let Item::Function(function) = item {
+                let params: Vec<_> = ast.params().filter_map(|param| param.name()).collect();
+                // a `self` that isn't listed comes first
+                let implicit = self.tree[function].params.len() - params.len();
+                for (index, param) in params.into_iter().enumerate() {
+                    let index = index + implicit;
+                    let definition = Definition::Resolved(Resolution::Param { function, index });
+                    self.push(param.text_range(), definition, true);
+                }
+            }
+        }
+    }
+
+    fn body(&mut self, id: BodyId, body: &Body) {
+        let source_map = body.arenas.source_map();
+        for (idx, stmt) in body.stmts.iter().enumerate() {
+            let resolution = match stmt {
+                Stmt::ConstDef { .. } if id == BodyId::Root && body.top_level.contains(&idx) => {
+                    Resolution::Global { stmt: idx }
+                }
+                Stmt::VariableDef { .. } | Stmt::ConstDef { .. } => Resolution::Local {
+                    body: id,
+                    stmt: idx,
+                },
+                _ => continue,
+            };
+            let name = source_map
+                .stmt_syntax(idx)
+                .and_then(|ptr| match ptr.to_node(self.syntax) {
+                    ast::Stmt::VariableDef(ast) => ast.name(),
+                    ast::Stmt::ConstDef(ast) => ast.name(),
+                    _ => None,
+                });
+            let token = name.map(|name| name.syntax().clone());
+            self.push_token(token, Definition::Resolved(resolution), true);
+        }
+
+        for (idx, expr) in body.arenas.exprs.iter() {
+            let source = match source_map.expr_syntax(idx) {
+                Some(source) => source,
+                None => continue,
+            };
+            self.expr(id, idx, expr, source);
+        }
+
+        for (pat, _) in body.arenas.pats() {
+            self.pat(id, body, pat);
+        }
+    }
+
+    fn expr(&mut self, id: BodyId, idx: ExprIdx, expr: &Expr, source: ExprSource) {
+        let node = source.syntax_node_ptr().to_node(self.syntax);
+        match expr {
+            Expr::VariableRef { var } => {
+                let resolution = match self.resolutions.get(id, idx) {
+                    Some(resolution) => resolution,
+                    None => return,
+                };
+                let range = match source {
+                    ExprSource::Expr(_) => {
+                        match ast::VariableRef::cast(node).and_then(|ast| ast.name()) {
+                            Some(name) => name.text_range(),
+                            None => return,
+                        }
+                    }
+                    // a `{name}` in a string covers the braces around the name
+                    ExprSource::Implicit { range, .. }
+                        if range.len() > TextSize::of(var.as_str()) =>
+                    {
+                        TextRange::at(
+                            range.start() + TextSize::of('{'),
+                            TextSize::of(var.as_str()),
+                        )
+                    }
+                    source => source.range(),
+                };
+                let definition = match resolution {
+                    // a field used without `self.` is the field itself
+                    Resolution::SelfField { strukt, index, .. } => {
+                        Definition::Field { strukt, index }
+                    }
+                    resolution => Definition::Resolved(resolution),
+                };
+                self.push(range, definition, false);
+            }
+            Expr::Path { .. } => {
+                let resolution = match self.resolutions.get(id, idx) {
+                    Some(resolution) => resolution,
+                    None => return,
+                };
+                let token = ast::PathExpr::cast(node).and_then(|ast| ast.name());
+                let definition = Definition::Resolved(resolution);
+                self.push_token(token.map(|name| name.syntax().clone()), definition, false);
+            }
+            Expr::Field { expr, name } => {
+                let field = self.field(&self.inference.body(id)[*expr], name);
+                let token = ast::FieldExpr::cast(node).and_then(|ast| ast.name());
+                if let Some(field) = field {
+                    self.push_token(token.map(|name| name.syntax().clone()), field, false);
+                }
+            }
+            Expr::MethodCall { .. } => {
+                let function = match self.inference.body(id).method_resolution(idx) {
+                    Some(function) => function,
+                    None => return,
+                };
+                let token = ast::MethodCallExpr::cast(node).and_then(|ast| ast.name());
+                let definition = Definition::Resolved(Resolution::Item(Item::Function(function)));
+                self.push_token(token.map(|name| name.syntax().clone()), definition, false);
+            }
+            Expr::Struct { .. } => {
+                let ast = match ast::StructExpr::cast(node) {
+                    Some(ast) => ast,
+                    None => return,
+                };
+                // `Self { .. }` names its struct through the impl
+                let strukt = match &self.inference.body(id)[idx] {
+                    Ty::Struct { id, .. } => *id,
+                    _ => return,
+                };
+                let definition = Definition::Resolved(Resolution::Item(Item::Struct(strukt)));
+                self.push_token(ast.name(), definition, false);
+
+                for field in ast.struct_expr_fields() {
+                    let name = match field.name() {
+                        Some(name) => name,
+                        None => continue,
+                    };
+                    if let Some(definition) = self.field_of(strukt, name.text()) {
+                        self.push(name.syntax().text_range(), definition, false);
+                    }
+                }
+            }
+            Expr::Closure { .. } => {
+                let ast = match ast::ClosureExpr::cast(node) {
+                    Some(ast) => ast,
+                    None => return,
+                };
+                let params = ast.params().filter_map(|param| param.name());
+                for (index, param) in params.enumerate() {
+                    let definition = Definition::Resolved(Resolution::ClosureParam {
+                        body: id,
+                        closure: idx,
+                        index,
+                    });
+                    self.push(param.text_range(), definition, true);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn pat(&mut self, id: BodyId, body: &Body, pat: PatIdx) {
+        let node = match body.arenas.source_map().pat_syntax(pat) {
+            Some(ptr) => ptr.to_node(self.syntax),
+            None => return,
+        };
+        let first_ident = |node: &SyntaxNode| {
+            node.children_with_tokens()
+                .filter_map(|element| element.into_token())
+                .find(|token| token.kind() == SyntaxKind::Ident)
+        };
+
+        match &body.arenas[pat] {
+            Pat::Ident { .. } => {
+                let (definition, is_definition) = match self.resolutions.pat(id, pat) {
+                    Some(constant) => (constant, false),
+                    None => (Resolution::PatBinding { body: id, pat }, true),
+                };
+                let definition = Definition::Resolved(definition);
+                self.push_token(first_ident(&node), definition, is_definition);
+            }
+            Pat::Struct { name, .. } => {
+                let strukt = match self.strukt(name) {
+                    Some(strukt) => strukt,
+                    None => return,
+                };
+                let definition = Definition::Resolved(Resolution::Item(Item::Struct(strukt)));
+                self.push_token(first_ident(&node), definition, false);
+
+                // shorthand fields like the `y` in `Vec2 { y }` are bindings
+                // as well
+                let fields = node
+                    .children()
+                    .filter(|field| field.kind() == SyntaxKind::FieldPat);
+                for field in fields {
+                    let name = match first_ident(&field) {
+                        Some(name) => name,
+                        None => continue,
+                    };
+                    if let Some(definition) = self.field_of(strukt, name.text()) {
+                        self.push(name.text_range(), definition, false);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The structs and traits named in signatures, struct definitions and
+    /// impls.
+    fn types(&mut self) {
+        let types = self.syntax.descendants().filter_map(ast::Type::cast);
+        for ty in types {
+            let name = match ty.name() {
+                Some(name) if name.kind() == SyntaxKind::Ident => name,
+                _ => continue,
+            };
+            let is_trait = ty
+                .syntax()
+                .parent()
+                .and_then(ast::ImplDef::cast)
+                .and_then(|imp| imp.trait_ty())
+                .is_some_and(|trait_ty| trait_ty.syntax() == ty.syntax());
+
+            let strukt = self.strukt(name.text()).map(Item::Struct);
+            let tr = self
+                .tree
+                .traits()
+                .find(|(_, t)| t.name == name.text())
+                .map(|(id, _)| Item::Trait(id));
+            let item = if is_trait { tr } else { strukt.or(tr) };
+            if let Some(item) = item {
+                let definition = Definition::Resolved(Resolution::Item(item));
+                self.push(name.text_range(), definition, false);
+            }
+        }
+    }
+
+    fn strukt(&self, name: &str) -> Option<StructId> {
+        self.tree
+            .structs()
+            .find(|(_, s)| s.name == name)
+            .map(|(id, _)| id)
+    }
+
+    fn field(&self, ty: &Ty, name: &str) -> Option<Definition> {
+        match ty {
+            Ty::Struct { id, .. } => self.field_of(*id, name),
+            _ => None,
+        }
+    }
+
+    /// The field `name` of `strukt`, which can be inherited.
+    fn field_of(&self, strukt: StructId, name: &str) -> Option<Definition> {
+        let field = method_resolution::lookup_field(self.tree, strukt, name)?;
+        self.tree.structs().find_map(|(id, s)| {
+            let index = s.fields.iter().position(|f| ptr::eq(f, field))?;
+            Some(Definition::Field { strukt: id, index })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Database, SourceDatabase};
+
+    /// Every name in `input` that refers to the same thing as the one at
+    /// the `$0` in it, as `text@start` with the definition in brackets.
+    fn check_references(input: &str, expected: &[&str]) {
+        let offset = TextSize::from(input.find("$0").unwrap() as u32);
+        let input = input.replace("$0", "");
+        let mut db = Database::default();
+        let file = FileId(0);
+        db.set_file_text(file, input.as_str().into());
+
+        let name = name_at(&db, file, offset).expect("no name at $0");
+        let found: Vec<_> = references(&db, file, name.definition)
+            .into_iter()
+            .map(|name| {
+                let text = format!("{}@{}", &input[name.range], u32::from(name.range.start()));
+                if name.is_definition {
+                    format!("[{}]", text)
+                } else {
+                    text
+                }
+            })
+            .collect();
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn find_references_to_bindings() {
+        check_references(
+            "x := 1\ny :: x$0 + 1\n\"{x}\"\nx := 2\nx",
+            &["[x@0]", "x@12", "x@20"],
+        );
+        check_references(
+            "f :: (a: i32) -> i32 { b :: a$0\n|a| a + b }",
+            &["[a@6]", "a@28"],
+        );
+        check_references("for i in 0..3 { println(i$0) }", &["[i@4]", "i@24"]);
+    }
+
+    #[test]
+    fn find_references_to_items() {
+        check_references(
+            "P :: struct { x: i32 }\nP :: impl { get :: (self) -> i32 { self.x } }\nf :: (p: P$0) { P { x: p.get() } }\nmatch P { x: 1 } { P { x: 1 } => 1\n_ => 2 }",
+            &["[P@0]", "P@23", "P@78", "P@83", "P@108", "P@121"],
+        );
+        check_references(
+            "P :: struct { x: i32 }\nP :: impl { get :: (self) -> i32 { self.x } }\nf :: (p: P) { P { x: p.get$0() } }",
+            &["[get@35]", "get@92"],
+        );
+    }
+
+    #[test]
+    fn find_references_to_fields() {
+        check_references(
+            "A :: struct { x$0: i32 }\nB :: struct <| A { y: i32 }\nb :: B { x: 1, y: 2 }\nb.x + b.y\nmatch b { B { x: 1, y } => y\n_ => 0 }",
+            &["[x@14]", "x@60", "x@75", "x@97"],
+        );
+    }
+
+    #[test]
+    fn know_the_types_of_definitions() {
+        let input = "P :: struct { x: i32 }\nf :: (p: P, n: i8) -> i8 { n }\nfor i in [1.5] { i }\nv := P { x: 1 }\n|a| a + 1";
+        let mut db = Database::default();
+        let file = FileId(0);
+        db.set_file_text(file, input.into());
+
+        let types: Vec<_> = names(&db, file)
+            .into_iter()
+            .filter(|name| name.is_definition)
+            .map(|name| {
+                let ty = definition_ty(&db, file, name.definition)
+                    .map_or_else(|| "-".to_string(), |ty| ty.to_string());
+                let name = definition_name(&db, file, name.definition);
+                format!("{}: {}", name, ty)
+            })
+            .collect();
+        assert_eq!(
+            types,
+            [
+                "P: -",
+                "x: i32",
+                "f: fn(P, i8) -> i8",
+                "p: P",
+                "n: i8",
+                "i: f64",
+                "v: P",
+                "a: i32",
+            ]
+        );
+
+        let offset = TextSize::from(input.find("1.5").unwrap() as u32);
+        let (body, expr) = expr_at(&db, file, offset).unwrap();
+        assert_eq!(expr_ty(&db, file, body, expr).to_string(), "f64");
+    }
+}
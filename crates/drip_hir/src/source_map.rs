@@ -0,0 +1,204 @@
+//! The way back from HIR to the syntax it was lowered from, so diagnostics
+//! about expressions can point at their source.
+
+use crate::{ExprIdx, PatIdx};
+use drip_ast::{self as ast, AstPtr, SyntaxNodePtr};
+use la_arena::ArenaMap;
+use std::collections::HashMap;
+use text_size::TextRange;
+
+/// Where a lowered expression came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExprSource {
+    Expr(AstPtr<ast::Expr>),
+    /// An `Expr::Missing`, pointing at the node the expression is missing
+    /// from, like the `InfixExpr` in `1 +`.
+    Missing(SyntaxNodePtr),
+    /// An expression without a node of its own that covers `range` inside
+    /// `node`, like the `x` in `Vec2 { x }` or the `{x}` in `"x = {x}"`.
+    Implicit {
+        node: SyntaxNodePtr,
+        range: TextRange,
+    },
+}
+
+impl ExprSource {
+    pub fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        match self {
+            Self::Expr(ptr) => ptr.syntax_node_ptr(),
+            Self::Missing(ptr) | Self::Implicit { node: ptr, .. } => *ptr,
+        }
+    }
+
+    pub fn range(&self) -> TextRange {
+        match self {
+            Self::Implicit { range, .. } => *range,
+            _ => self.syntax_node_ptr().range(),
+        }
+    }
+}
+
+/// Maps the expressions and statements of a body to the syntax they were
+/// lowered from, and back.
+///
+/// The statement map also covers the bindings a statement introduces, since a
+/// `:=` or `::` is the only way to bind a name.
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct BodySourceMap {
+    expr_map: HashMap<AstPtr<ast::Expr>, ExprIdx>,
+    expr_map_back: ArenaMap<ExprIdx, ExprSource>,
+    stmt_map: HashMap<AstPtr<ast::Stmt>, usize>,
+    stmt_map_back: Vec<AstPtr<ast::Stmt>>,
+    /// Patterns point at a `FieldPat` for shorthand fields like `Vec2 { x }`,
+    /// so they're stored as plain pointers.
+    pat_map_back: ArenaMap<PatIdx, SyntaxNodePtr>,
+    /// The parameters of the function the body belongs to, with `None` for
+    /// a `self` it takes without listing it.
+    pub(crate) params: Vec<Option<AstPtr<ast::FnParamDef>>>,
+}
+
+impl BodySourceMap {
+    pub fn expr_syntax(&self, expr: ExprIdx) -> Option<ExprSource> {
+        self.expr_map_back.get(expr).copied()
+    }
+
+    pub fn node_expr(&self, node: &ast::Expr) -> Option<ExprIdx> {
+        self.expr_map.get(&AstPtr::new(node)).copied()
+    }
+
+    /// The syntax of the body's `stmt`th statement.
+    pub fn stmt_syntax(&self, stmt: usize) -> Option<AstPtr<ast::Stmt>> {
+        self.stmt_map_back.get(stmt).copied()
+    }
+
+    pub fn node_stmt(&self, node: &ast::Stmt) -> Option<usize> {
+        self.stmt_map.get(&AstPtr::new(node)).copied()
+    }
+
+    pub fn pat_syntax(&self, pat: PatIdx) -> Option<SyntaxNodePtr> {
+        self.pat_map_back.get(pat).copied()
+    }
+
+    /// The syntax of the `index`th parameter of the body's function.
+    pub fn param_syntax(&self, index: usize) -> Option<AstPtr<ast::FnParamDef>> {
+        self.params.get(index).copied().flatten()
+    }
+
+    pub(crate) fn insert_expr(&mut self, expr: ExprIdx, source: ExprSource) {
+        if let ExprSource::Expr(ptr) = source {
+            self.expr_map.insert(ptr, expr);
+        }
+        self.expr_map_back.insert(expr, source);
+    }
+
+    /// Also maps `node` to `expr`, without changing where `expr` came from.
+    pub(crate) fn insert_expr_alias(&mut self, node: &ast::Expr, expr: ExprIdx) {
+        self.expr_map.insert(AstPtr::new(node), expr);
+    }
+
+    pub(crate) fn insert_pat(&mut self, pat: PatIdx, source: SyntaxNodePtr) {
+        self.pat_map_back.insert(pat, source);
+    }
+
+    pub(crate) fn push_stmt(&mut self, node: &ast::Stmt) {
+        self.stmt_map
+            .insert(AstPtr::new(node), self.stmt_map_back.len());
+        self.stmt_map_back.push(AstPtr::new(node));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Body, Stmt};
+    use drip_ast::AstNode;
+    use drip_syntax::SyntaxNode;
+
+    fn lower(input: &str) -> (SyntaxNode, Body) {
+        let syntax = drip_parser::parse(input).syntax();
+        let (_, body) = crate::lower(ast::Root::cast(syntax.clone()).unwrap());
+        (syntax, body)
+    }
+
+    #[test]
+    fn map_every_expr_back_to_its_source() {
+        let input = "a := -b\nc :: 1 +";
+        let (_, body) = lower(input);
+        let source_map = body.arenas.source_map();
+
+        let sources: Vec<_> = body
+            .arenas
+            .exprs
+            .iter()
+            .map(|(idx, expr)| {
+                let source = source_map.expr_syntax(idx).unwrap();
+                let text = &input[source.range()];
+                match source {
+                    ExprSource::Expr(_) => format!("{:?}: {:?}", expr, text.trim_end()),
+                    ExprSource::Missing(_) => format!("missing in {:?}", text.trim_end()),
+                    ExprSource::Implicit { .. } => format!("{:?} implied by {:?}", expr, text),
+                }
+            })
+            .collect();
+
+        assert_eq!(
+            sources,
+            [
+                r#"VariableRef { var: "b" }: "b""#,
+                r#"Unary { op: Neg, expr: Idx::<Expr>(0) }: "-b""#,
+                r#"Literal { n: Some(1) }: "1""#,
+                r#"missing in "1 +""#,
+                r#"Binary { op: Add, lhs: Idx::<Expr>(2), rhs: Idx::<Expr>(3) }: "1 +""#,
+            ]
+        );
+    }
+
+    #[test]
+    fn map_syntax_to_hir() {
+        let (syntax, body) = lower("x :: 1\n((x))");
+        let source_map = body.arenas.source_map();
+
+        let x = syntax
+            .descendants()
+            .find(|node| node.kind() == drip_syntax::SyntaxKind::VariableRef)
+            .and_then(ast::Expr::cast)
+            .unwrap();
+        let idx = source_map.node_expr(&x).unwrap();
+        assert_eq!(
+            body.stmts[1],
+            Stmt::Expr(idx),
+            "brackets map to the expression inside them"
+        );
+
+        let stmt = source_map.stmt_syntax(0).unwrap().to_node(&syntax);
+        assert_eq!(stmt.syntax().to_string(), "x :: 1\n");
+        assert_eq!(source_map.node_stmt(&stmt), Some(0));
+    }
+
+    #[test]
+    fn map_implicit_exprs_into_their_source() {
+        let input = "n :: 1\nP :: struct { n: i32 }\n\"n = {n}\"\nP { n }";
+        let (_, body) = lower(input);
+        let source_map = body.arenas.source_map();
+
+        let implicit: Vec<_> = body
+            .arenas
+            .exprs
+            .iter()
+            .filter_map(|(idx, expr)| match source_map.expr_syntax(idx).unwrap() {
+                source @ ExprSource::Implicit { .. } => {
+                    Some(format!("{:?}: {:?}", expr, &input[source.range()]))
+                }
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(
+            implicit,
+            [
+                r#"VariableRef { var: "n" }: "{n}""#,
+                r#"VariableRef { var: "n" }: "n""#,
+            ]
+        );
+    }
+}
@@ -0,0 +1,400 @@
+//! Completing what's being typed at an offset.
+//!
+//! A file that's being edited rarely parses, so completion leans on the
+//! parser's error recovery. A marker identifier is inserted at the offset as
+//! though it had already been typed, and where it ends up in the tree says
+//! what can go there:
+//!
+//! - after `.`, the fields and methods of the receiver's type, including the
+//!   ones it inherits through `<|`
+//! - after `Type::`, which the parser recovers from as a variable followed by
+//!   a stray `::`, the functions of the impls of `Type`
+//! - after `Name ::`, the keywords that start a definition and a snippet for
+//!   a function, along with everything an expression can start with
+//! - anywhere else an expression can go, the names in scope and the keywords
+//!   that start an expression
+//!
+//! Functions are inserted as snippets, with their parameters as the places to
+//! fill in.
+
+use drip_ast::{self as ast, AstNode, AstToken};
+use drip_hir::db::{FileId, HirDatabase, SourceDatabase};
+use drip_hir::infer::{self, Ty};
+use drip_hir::resolve::{self, Resolution};
+use drip_hir::semantics::{self, Definition};
+use drip_hir::{method_resolution, Database, FunctionId, Item, StructId};
+use drip_syntax::{SyntaxKind, SyntaxNode};
+use smol_str::SmolStr;
+use text_size::TextSize;
+
+/// What's typed in at the offset being completed.
+const MARKER: &str = "__complete";
+
+const EXPR_KEYWORDS: [&str; 7] = ["if", "match", "loop", "while", "for", "true", "false"];
+
+const LOOP_KEYWORDS: [&str; 2] = ["break", "continue"];
+
+const DEFINITION_KEYWORDS: [&str; 3] = ["struct", "trait", "impl"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    pub label: SmolStr,
+    pub kind: CompletionKind,
+    /// The type of what's completed, if it has one.
+    pub detail: Option<String>,
+    /// The text to insert, as a snippet: `${1:name}`, `${2:name}` and so on
+    /// are the places to fill in, in order, and `$0` is where the cursor ends
+    /// up.
+    pub insert: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionKind {
+    Variable,
+    Function,
+    Method,
+    Field,
+    Struct,
+    Trait,
+    Keyword,
+    Snippet,
+}
+
+/// Everything that can be typed at `offset` in `file`.
+pub fn completions(db: &dyn HirDatabase, file: FileId, offset: TextSize) -> Vec<Completion> {
+    let text = db.file_text(file);
+    let at = usize::from(offset);
+    if at > text.len() || !text.is_char_boundary(at) {
+        return Vec::new();
+    }
+
+    // the file with the marker typed in is a file of its own
+    let mut db = Database::default();
+    let file = FileId(0);
+    let text = format!("{}{}{}", &text[..at], MARKER, &text[at..]);
+    db.set_file_text(file, text.as_str().into());
+    db.set_root_file(file);
+
+    let mut completer = Completer {
+        db: &db,
+        file,
+        completions: Vec::new(),
+    };
+    completer.complete(offset);
+    completer.completions
+}
+
+struct Completer<'a> {
+    db: &'a dyn HirDatabase,
+    file: FileId,
+    completions: Vec<Completion>,
+}
+
+impl Completer<'_> {
+    fn complete(&mut self, offset: TextSize) -> Option<()> {
+        let root = self.db.parse(self.file).syntax();
+        let marker = root.token_at_offset(offset).right_biased()?;
+        if marker.kind() != SyntaxKind::Ident || !marker.text().contains(MARKER) {
+            return None;
+        }
+
+        let parent = marker.parent()?;
+        match parent.kind() {
+            SyntaxKind::FieldExpr => self.members(ast::FieldExpr::cast(parent)?.expr()?),
+            SyntaxKind::MethodCallExpr => {
+                self.members(ast::MethodCallExpr::cast(parent)?.receiver()?)
+            }
+            SyntaxKind::PathExpr => {
+                let strukt = self.path(ast::PathExpr::cast(parent)?)?;
+                self.associated_functions(strukt)
+            }
+            SyntaxKind::VariableRef => {
+                if parent.parent()?.kind() == SyntaxKind::ConstDef {
+                    self.definition_keywords();
+                }
+                self.scope(&parent)?;
+                self.expr_keywords(&parent);
+                Some(())
+            }
+            _ => None,
+        }
+    }
+
+    /// The struct whose functions `Type::name` is looking for.
+    fn path(&self, path: ast::PathExpr) -> Option<StructId> {
+        let name = path.ty()?.name()?;
+        let lowered = self.db.lower_file(self.file);
+        let strukt = lowered.tree.structs().find(|(_, s)| s.name == name.text());
+        strukt.map(|(id, _)| id)
+    }
+
+    /// The fields and methods of `receiver`.
+    fn members(&mut self, receiver: ast::Expr) -> Option<()> {
+        let (db, file) = (self.db, self.file);
+        let (body, expr) = semantics::expr_of(db, file, &receiver)?;
+        let ty = semantics::expr_ty(db, file, body, expr);
+
+        if let Ty::Struct { id, .. } = ty {
+            for field in semantics::fields(db, file, id) {
+                let label = semantics::definition_name(db, file, field);
+                self.completions.push(Completion {
+                    insert: label.to_string(),
+                    label,
+                    kind: CompletionKind::Field,
+                    detail: semantics::definition_ty(db, file, field).map(|ty| ty.to_string()),
+                });
+            }
+        }
+        let lowered = db.lower_file(file);
+        for function in method_resolution::all_methods(&lowered.tree, &ty) {
+            self.function(function, CompletionKind::Method);
+        }
+        Some(())
+    }
+
+    fn associated_functions(&mut self, strukt: StructId) -> Option<()> {
+        let lowered = self.db.lower_file(self.file);
+        let ty = infer::struct_ty(&lowered.tree, strukt);
+        for function in method_resolution::all_functions(&lowered.tree, &ty) {
+            self.function(function, CompletionKind::Function);
+        }
+        Some(())
+    }
+
+    /// The names that a variable at `node` can refer to.
+    fn scope(&mut self, node: &SyntaxNode) -> Option<()> {
+        let (db, file) = (self.db, self.file);
+        let expr = ast::Expr::cast(node.clone())?;
+        let (body, expr) = semantics::expr_of(db, file, &expr)?;
+        let lowered = db.lower_file(file);
+
+        // a top-level constant can see itself, but never usefully
+        let defining: Vec<SmolStr> = node
+            .ancestors()
+            .filter_map(ast::ConstDef::cast)
+            .filter_map(|def| Some(def.name()?.text().into()))
+            .collect();
+
+        let scope = resolve::scope_at(&lowered.tree, &lowered.root, body, expr);
+        for (name, resolution) in scope {
+            let is_defining =
+                matches!(resolution, Resolution::Global { .. }) && defining.contains(&name);
+            if is_defining || name.contains(MARKER) {
+                continue;
+            }
+            let kind = match resolution {
+                Resolution::Item(Item::Function(function)) => {
+                    self.function(function, CompletionKind::Function);
+                    continue;
+                }
+                Resolution::Builtin(_) => {
+                    let ty = semantics::definition_ty(db, file, Definition::Resolved(resolution));
+                    self.push_call(name, CompletionKind::Function, ty, &["value".into()]);
+                    continue;
+                }
+                Resolution::Item(Item::Struct(_)) => CompletionKind::Struct,
+                Resolution::Item(Item::Trait(_)) => CompletionKind::Trait,
+                _ => CompletionKind::Variable,
+            };
+            let ty = semantics::definition_ty(db, file, Definition::Resolved(resolution));
+            self.completions.push(Completion {
+                insert: name.to_string(),
+                label: name,
+                kind,
+                detail: ty.map(|ty| ty.to_string()),
+            });
+        }
+        Some(())
+    }
+
+    fn expr_keywords(&mut self, node: &SyntaxNode) {
+        for keyword in EXPR_KEYWORDS.iter() {
+            self.keyword(keyword, CompletionKind::Keyword, keyword);
+        }
+
+        // `break` and `continue` don't reach out of functions and closures
+        let in_loop = node
+            .ancestors()
+            .take_while(|node| !matches!(node.kind(), SyntaxKind::FnDef | SyntaxKind::ClosureExpr))
+            .any(|node| {
+                matches!(
+                    node.kind(),
+                    SyntaxKind::LoopExpr | SyntaxKind::WhileExpr | SyntaxKind::ForExpr
+                )
+            });
+        if in_loop {
+            for keyword in LOOP_KEYWORDS.iter() {
+                self.keyword(keyword, CompletionKind::Keyword, keyword);
+            }
+        }
+    }
+
+    fn definition_keywords(&mut self) {
+        for keyword in DEFINITION_KEYWORDS.iter() {
+            self.keyword(keyword, CompletionKind::Keyword, keyword);
+        }
+        self.keyword("fn", CompletionKind::Snippet, "(${1}) {\n    $0\n}");
+    }
+
+    fn keyword(&mut self, label: &str, kind: CompletionKind, insert: &str) {
+        self.completions.push(Completion {
+            label: label.into(),
+            kind,
+            detail: None,
+            insert: insert.to_string(),
+        });
+    }
+
+    /// A call to `function`, leaving out `self` from the arguments of
+    /// methods.
+    fn function(&mut self, function: FunctionId, kind: CompletionKind) {
+        let lowered = self.db.lower_file(self.file);
+        let data = &lowered.tree[function];
+        let skip = (kind == CompletionKind::Method) as usize;
+        let params: Vec<_> = data
+            .params
+            .iter()
+            .skip(skip)
+            .map(|p| p.name.clone())
+            .collect();
+        let ty = self.db.inference(self.file).function(function).clone();
+        self.push_call(data.name.clone(), kind, Some(ty), &params);
+    }
+
+    fn push_call(
+        &mut self,
+        label: SmolStr,
+        kind: CompletionKind,
+        ty: Option<Ty>,
+        params: &[SmolStr],
+    ) {
+        let args: Vec<_> = params
+            .iter()
+            .enumerate()
+            .map(|(i, param)| format!("${{{}:{}}}", i + 1, param))
+            .collect();
+        self.completions.push(Completion {
+            insert: format!("{}({})", label, args.join(", ")),
+            label,
+            kind,
+            detail: ty.map(|ty| ty.to_string()),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use expect_test::{expect, Expect};
+
+    /// Completes at the `$0` in `input`.
+    fn check(input: &str, expect: Expect) {
+        let offset = input.find("$0").unwrap();
+        let text = input.replace("$0", "");
+        let mut db = Database::default();
+        db.set_file_text(FileId(0), text.as_str().into());
+        db.set_root_file(FileId(0));
+
+        let completions = completions(&db, FileId(0), TextSize::from(offset as u32));
+        let lines: Vec<_> = completions
+            .iter()
+            .map(|completion| {
+                let mut line = format!("{:?} {}", completion.kind, completion.label);
+                if let Some(detail) = &completion.detail {
+                    line += &format!(": {}", detail);
+                }
+                if completion.insert != completion.label {
+                    line += &format!(" => {:?}", completion.insert);
+                }
+                line
+            })
+            .collect();
+        expect.assert_eq(&lines.join("\n"));
+    }
+
+    #[test]
+    fn complete_bindings_in_scope() {
+        check(
+            "limit :: 10\nadd :: (a: i32, b: i32) -> i32 { a + b }\nf :: (n: i32) {\n    total := 0\n    for i in 0..n {\n        total += $0\n    }\n    later := 1\n}",
+            expect![[r#"
+                Variable i: i32
+                Variable total: i32
+                Variable n: i32
+                Variable limit: i32
+                Function add: fn(i32, i32) -> i32 => "add(${1:a}, ${2:b})"
+                Function f: fn(i32) -> () => "f(${1:n})"
+                Function print: fn({unknown}) -> () => "print(${1:value})"
+                Function println: fn({unknown}) -> () => "println(${1:value})"
+                Keyword if
+                Keyword match
+                Keyword loop
+                Keyword while
+                Keyword for
+                Keyword true
+                Keyword false
+                Keyword break
+                Keyword continue"#]],
+        );
+    }
+
+    #[test]
+    fn complete_fields_and_methods() {
+        check(
+            "Vec2 :: struct { x: f32, y: f32 }\nVec2 :: impl {\n    len :: (self) -> f32 { self.x }\n    scale :: (self, by: f32) -> Vec2 { self }\n}\nv := Vec2 { x: 1.0, y: 2.0 }\nv.$0",
+            expect![[r#"
+                Field x: f32
+                Field y: f32
+                Method len: fn(Vec2) -> f32 => "len()"
+                Method scale: fn(Vec2, f32) -> Vec2 => "scale(${1:by})""#]],
+        );
+    }
+
+    #[test]
+    fn complete_inherited_fields_and_trait_methods() {
+        check(
+            "Sound :: trait {\n    make_sound :: (self)\n}\nAnimal :: struct { name: string }\nAnimal <| Sound :: impl {\n    make_sound :: (self) { 1 }\n}\nDog :: struct <| Animal { toy: string }\nd := Dog { name: \"rex\", toy: \"ball\" }\nd.ma$0ke_sound()",
+            expect![[r#"
+                Field toy: string
+                Field name: string
+                Method make_sound: fn(Animal) -> i32 => "make_sound()""#]],
+        );
+    }
+
+    #[test]
+    fn complete_associated_functions() {
+        check(
+            "Vec3 :: struct { x, y, z: i32 }\nVec3 :: impl {\n    new :: (x: i32, y: i32, z: i32) -> Self { Self { x, y, z } }\n    plus_one :: (self) { 1 }\n}\nv :: Vec3::$0",
+            expect![[r#"
+                Function new: fn(i32, i32, i32) -> Vec3 => "new(${1:x}, ${2:y}, ${3:z})"
+                Function plus_one: fn(Vec3) -> i32 => "plus_one(${1:self})""#]],
+        );
+    }
+
+    #[test]
+    fn complete_definitions() {
+        check(
+            "x := 1\nPoint :: $0",
+            expect![[r#"
+                Keyword struct
+                Keyword trait
+                Keyword impl
+                Snippet fn => "(${1}) {\n    $0\n}"
+                Variable x: i32
+                Function print: fn({unknown}) -> () => "print(${1:value})"
+                Function println: fn({unknown}) -> () => "println(${1:value})"
+                Keyword if
+                Keyword match
+                Keyword loop
+                Keyword while
+                Keyword for
+                Keyword true
+                Keyword false"#]],
+        );
+    }
+
+    #[test]
+    fn complete_nothing_outside_expressions() {
+        check("P :: struct { $0 }", expect![[""]]);
+        check("x := \"$0\"", expect![[""]]);
+    }
+}
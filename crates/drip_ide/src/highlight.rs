@@ -0,0 +1,336 @@
+//! Classifying the tokens of a file for highlighting, and rendering them as
+//! HTML or as text coloured for a terminal.
+//!
+//! Tokens are classified by their kind, and identifiers by the node they're
+//! in, so a file that doesn't parse still gets highlighted. With semantic
+//! information, the names that name resolution knows about are classified by
+//! what they refer to instead: a `::` binding is a constant and a `:=`
+//! binding is mutable wherever it's used, and a name in a type is a struct or
+//! a trait rather than just a type.
+
+use drip_hir::db::{FileId, HirDatabase};
+use drip_hir::resolve::{BodyId, Resolution};
+use drip_hir::semantics::{self, Definition};
+use drip_hir::{Item, Stmt};
+use drip_syntax::{SyntaxKind, SyntaxToken};
+use std::collections::HashMap;
+use std::fmt::Write;
+use text_size::TextRange;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HighlightTag {
+    Keyword,
+    Number,
+    String,
+    Comment,
+    Operator,
+    /// A binding that isn't known to be constant or mutable.
+    Variable,
+    Constant,
+    Mutable,
+    Parameter,
+    Field,
+    Function,
+    Method,
+    Struct,
+    Trait,
+    Type,
+}
+
+impl HighlightTag {
+    /// The class of the `<span>`s of this tag in HTML.
+    pub fn css_class(self) -> &'static str {
+        match self {
+            Self::Keyword => "keyword",
+            Self::Number => "number",
+            Self::String => "string",
+            Self::Comment => "comment",
+            Self::Operator => "operator",
+            Self::Variable => "variable",
+            Self::Constant => "constant",
+            Self::Mutable => "mutable",
+            Self::Parameter => "parameter",
+            Self::Field => "field",
+            Self::Function => "function",
+            Self::Method => "method",
+            Self::Struct => "struct",
+            Self::Trait => "trait",
+            Self::Type => "type",
+        }
+    }
+
+    /// The SGR parameters of this tag in a terminal, if it's coloured there.
+    fn ansi_style(self) -> Option<&'static str> {
+        match self {
+            Self::Keyword => Some("35"),
+            Self::Number => Some("36"),
+            Self::String => Some("32"),
+            Self::Comment => Some("90"),
+            Self::Constant => Some("33"),
+            Self::Mutable => Some("4"),
+            Self::Parameter => Some("3"),
+            Self::Field => Some("94"),
+            Self::Function | Self::Method => Some("34"),
+            Self::Struct | Self::Trait | Self::Type => Some("93"),
+            Self::Operator | Self::Variable => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Highlight {
+    pub range: TextRange,
+    pub tag: HighlightTag,
+}
+
+/// The highlighted tokens of `file`, in order. With `semantic`, names are
+/// classified by what they refer to.
+pub fn highlight(db: &dyn HirDatabase, file: FileId, semantic: bool) -> Vec<Highlight> {
+    let root = db.parse(file).syntax();
+    let names = if semantic {
+        semantic_tags(db, file)
+    } else {
+        HashMap::new()
+    };
+
+    let tokens = root
+        .descendants_with_tokens()
+        .filter_map(|element| element.into_token());
+    tokens
+        .filter_map(|token| {
+            let range = token.text_range();
+            let tag = names.get(&range).copied().or_else(|| classify(&token))?;
+            Some(Highlight { range, tag })
+        })
+        .collect()
+}
+
+/// `file` as a `<pre>` block, with every highlighted token in a `<span>`
+/// whose class is [`HighlightTag::css_class`].
+pub fn html(db: &dyn HirDatabase, file: FileId, semantic: bool) -> String {
+    let text = db.file_text(file);
+    let mut output = String::from("<pre class=\"drip\"><code>");
+    render(&text, &highlight(db, file, semantic), |tag, text| {
+        let text = escape(text);
+        match tag {
+            Some(tag) => write!(
+                output,
+                "<span class=\"{}\">{}</span>",
+                tag.css_class(),
+                text
+            ),
+            None => write!(output, "{}", text),
+        }
+        .unwrap()
+    });
+    output.push_str("</code></pre>\n");
+    output
+}
+
+/// `file` coloured with ANSI escape codes.
+pub fn ansi(db: &dyn HirDatabase, file: FileId, semantic: bool) -> String {
+    let text = db.file_text(file);
+    let mut output = String::new();
+    render(&text, &highlight(db, file, semantic), |tag, text| {
+        let style = tag.and_then(HighlightTag::ansi_style);
+        match style {
+            Some(style) => write!(output, "\x1b[{}m{}\x1b[0m", style, text).unwrap(),
+            None => output.push_str(text),
+        }
+    });
+    output
+}
+
+/// Calls `piece` with every highlighted token of `text` and the text
+/// between them, in order.
+fn render(text: &str, highlights: &[Highlight], mut piece: impl FnMut(Option<HighlightTag>, &str)) {
+    let mut end = 0;
+    for highlight in highlights {
+        let range = highlight.range;
+        piece(None, &text[end..range.start().into()]);
+        piece(Some(highlight.tag), &text[range]);
+        end = range.end().into();
+    }
+    piece(None, &text[end..]);
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The tags of the names in `file`, by their ranges.
+fn semantic_tags(db: &dyn HirDatabase, file: FileId) -> HashMap<TextRange, HighlightTag> {
+    let lowered = db.lower_file(file);
+    let (tree, root) = (&lowered.tree, &lowered.root);
+    let binding = |stmt: &Stmt| match stmt {
+        Stmt::ConstDef { .. } => HighlightTag::Constant,
+        Stmt::VariableDef { .. } => HighlightTag::Mutable,
+        _ => HighlightTag::Variable,
+    };
+
+    semantics::names(db, file)
+        .into_iter()
+        .map(|name| {
+            let resolution = match name.definition {
+                Definition::Field { .. } => return (name.range, HighlightTag::Field),
+                Definition::Resolved(resolution) => resolution,
+            };
+            let tag = match resolution {
+                Resolution::Local { body, stmt } => {
+                    let body = match body {
+                        BodyId::Root => root,
+                        BodyId::Function(function) => &tree[function].body,
+                    };
+                    binding(&body.stmts[stmt])
+                }
+                Resolution::Global { .. } => HighlightTag::Constant,
+                Resolution::Param { .. } | Resolution::ClosureParam { .. } => {
+                    HighlightTag::Parameter
+                }
+                Resolution::PatBinding { .. } => HighlightTag::Variable,
+                Resolution::SelfField { .. } => HighlightTag::Field,
+                Resolution::Item(Item::Function(function)) => {
+                    let params = &tree[function].params;
+                    if params.first().is_some_and(|param| param.name == "self") {
+                        HighlightTag::Method
+                    } else {
+                        HighlightTag::Function
+                    }
+                }
+                Resolution::Item(Item::Struct(_)) => HighlightTag::Struct,
+                Resolution::Item(Item::Trait(_)) => HighlightTag::Trait,
+                Resolution::Item(Item::Impl(_)) => HighlightTag::Type,
+                Resolution::Builtin(_) => HighlightTag::Function,
+            };
+            (name.range, tag)
+        })
+        .collect()
+}
+
+/// The tag of `token` going by its kind and the node it's in.
+fn classify(token: &SyntaxToken) -> Option<HighlightTag> {
+    use SyntaxKind::*;
+
+    let tag = match token.kind() {
+        FnKw | UseKw | ExternKw | StructKw | TraitKw | ImplKw | IfKw | ElseKw | LoopKw
+        | WhileKw | ForKw | InKw | BreakKw | ContinueKw | MatchKw | TrueKw | FalseKw | TypeKw
+        | SelfVarKw | SelfTypeKw => HighlightTag::Keyword,
+        Number => HighlightTag::Number,
+        Str => HighlightTag::String,
+        Comment => HighlightTag::Comment,
+        ConstKw | VariableKw | Plus | Minus | Star | Slash | Equals | Equals2 | NotEquals
+        | LAngledBracket | RAngledBracket | LessEquals | GreaterEquals | PlusEquals
+        | MinusEquals | StarEquals | SlashEquals | Dot2 | Arrow | FatArrow | Inherit => {
+            HighlightTag::Operator
+        }
+        Ident => match token.parent()?.kind() {
+            FnDef => HighlightTag::Function,
+            StructDef | StructExpr | StructPat => HighlightTag::Struct,
+            TraitDef => HighlightTag::Trait,
+            Type | TraitTypeDef => HighlightTag::Type,
+            FnParamDef => HighlightTag::Parameter,
+            StructFieldDef | FieldExpr | StructExprField | FieldPat => HighlightTag::Field,
+            MethodCallExpr => HighlightTag::Method,
+            _ => HighlightTag::Variable,
+        },
+        _ => return None,
+    };
+    Some(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use drip_hir::db::SourceDatabase;
+    use drip_hir::Database;
+    use expect_test::{expect, Expect};
+
+    fn check(input: &str, semantic: bool, expect: Expect) {
+        let mut db = Database::default();
+        db.set_file_text(FileId(0), input.into());
+
+        let highlights: Vec<_> = highlight(&db, FileId(0), semantic)
+            .into_iter()
+            .map(|highlight| format!("{:?} {:?}", &input[highlight.range], highlight.tag))
+            .collect();
+        expect.assert_eq(&highlights.join("\n"));
+    }
+
+    const INPUT: &str =
+        "// sum\nadd :: (a: i32) { a + 1 }\nP :: struct { x: f32 }\np := P { x: 1.5 }\np.x";
+
+    #[test]
+    fn classify_tokens_by_syntax() {
+        check(
+            INPUT,
+            false,
+            expect![[r#"
+                "// sum" Comment
+                "add" Function
+                "::" Operator
+                "a" Parameter
+                "i32" Type
+                "a" Variable
+                "+" Operator
+                "1" Number
+                "P" Struct
+                "::" Operator
+                "struct" Keyword
+                "x" Field
+                "f32" Type
+                "p" Variable
+                ":=" Operator
+                "P" Struct
+                "x" Field
+                "1.5" Number
+                "p" Variable
+                "x" Field"#]],
+        );
+    }
+
+    #[test]
+    fn classify_names_by_what_they_refer_to() {
+        check(
+            "N :: 3\nn := N\nf :: (a: i32) { a + n }\nn = f(N)\nprintln(n)",
+            true,
+            expect![[r#"
+                "N" Constant
+                "::" Operator
+                "3" Number
+                "n" Mutable
+                ":=" Operator
+                "N" Constant
+                "f" Function
+                "::" Operator
+                "a" Parameter
+                "i32" Type
+                "a" Parameter
+                "+" Operator
+                "n" Mutable
+                "n" Mutable
+                "=" Operator
+                "f" Function
+                "N" Constant
+                "println" Function
+                "n" Mutable"#]],
+        );
+    }
+
+    #[test]
+    fn render_html_and_ansi() {
+        let mut db = Database::default();
+        db.set_file_text(FileId(0), "x :: a < 2 // \"<\"\n".into());
+
+        assert_eq!(
+            html(&db, FileId(0), false),
+            "<pre class=\"drip\"><code><span class=\"variable\">x</span> <span class=\"operator\">::</span> <span class=\"variable\">a</span> <span class=\"operator\">&lt;</span> <span class=\"number\">2</span> <span class=\"comment\">// &quot;&lt;&quot;</span>\n</code></pre>\n"
+        );
+        assert_eq!(
+            ansi(&db, FileId(0), true),
+            "\x1b[33mx\x1b[0m :: a < \x1b[36m2\x1b[0m \x1b[90m// \"<\"\x1b[0m\n"
+        );
+    }
+}
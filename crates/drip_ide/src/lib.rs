@@ -0,0 +1,8 @@
+//! Editor features that don't depend on how an editor asks for them, for
+//! the language server and anything else that wants them.
+
+mod completion;
+mod highlight;
+
+pub use completion::{completions, Completion, CompletionKind};
+pub use highlight::{ansi, highlight, html, Highlight, HighlightTag};
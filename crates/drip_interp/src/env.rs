@@ -0,0 +1,83 @@
+//! The bindings of running code.
+//!
+//! Every call gets a fresh [`Env`] for its parameters and locals. Bindings
+//! are keyed by what defines them rather than by name, since name
+//! resolution already decided which definition every name refers to. A
+//! closure's environment is a child of the one it was created in, so it
+//! reads and changes the bindings around it.
+
+use crate::value::Value;
+use drip_hir::{ExprIdx, PatIdx};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Formatter;
+use std::rc::Rc;
+
+/// What defines a binding, within one body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Slot {
+    /// The `:=` or `::` binding of a statement.
+    Stmt(usize),
+    Param(usize),
+    Pat(PatIdx),
+    /// A parameter of the closure at the expression.
+    ClosureParam(ExprIdx, usize),
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct Env(Rc<RefCell<Scope>>);
+
+#[derive(Default)]
+struct Scope {
+    slots: HashMap<Slot, Value>,
+    parent: Option<Env>,
+}
+
+impl Env {
+    pub(crate) fn child(&self) -> Self {
+        Self(Rc::new(RefCell::new(Scope {
+            slots: HashMap::new(),
+            parent: Some(self.clone()),
+        })))
+    }
+
+    /// Binds `slot` in this environment, replacing what it was bound to.
+    pub(crate) fn define(&self, slot: Slot, value: Value) {
+        self.0.borrow_mut().slots.insert(slot, value);
+    }
+
+    pub(crate) fn get(&self, slot: Slot) -> Option<Value> {
+        let scope = self.0.borrow();
+        match scope.slots.get(&slot) {
+            Some(value) => Some(value.clone()),
+            None => scope.parent.as_ref()?.get(slot),
+        }
+    }
+
+    /// Runs `f` on the value of `slot` in the nearest environment that
+    /// binds it, or returns `None` if none does.
+    pub(crate) fn with_mut<T>(&self, slot: Slot, f: impl FnOnce(&mut Value) -> T) -> Option<T> {
+        let mut scope = self.0.borrow_mut();
+        if let Some(value) = scope.slots.get_mut(&slot) {
+            return Some(f(value));
+        }
+        let parent = scope.parent.clone();
+        drop(scope);
+        parent?.with_mut(slot, f)
+    }
+}
+
+// a closure can be stored in the environment it captures, so neither of these
+// looks inside
+impl fmt::Debug for Env {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Env")
+    }
+}
+
+impl PartialEq for Env {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
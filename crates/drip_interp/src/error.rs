@@ -0,0 +1,156 @@
+use smol_str::SmolStr;
+use std::fmt;
+use std::fmt::Formatter;
+use text_size::TextRange;
+
+/// An error that stopped a running program, along with the calls it
+/// happened in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuntimeError {
+    kind: RuntimeErrorKind,
+    range: TextRange,
+    trace: Vec<StackFrame>,
+}
+
+impl RuntimeError {
+    /// An error of `kind` at `range`, which happened while running
+    /// `function`.
+    pub fn new(kind: RuntimeErrorKind, range: TextRange, function: String) -> Self {
+        Self {
+            kind,
+            range,
+            trace: vec![StackFrame { function, range }],
+        }
+    }
+
+    /// Records that the error happened during the call at `range`, which was
+    /// made by `function`.
+    pub fn called_from(mut self, function: String, range: TextRange) -> Self {
+        self.trace.push(StackFrame { function, range });
+        self
+    }
+
+    pub fn kind(&self) -> &RuntimeErrorKind {
+        &self.kind
+    }
+
+    pub fn range(&self) -> TextRange {
+        self.range
+    }
+
+    /// Where the error happened, followed by the calls that led there,
+    /// innermost first.
+    pub fn trace(&self) -> &[StackFrame] {
+        &self.trace
+    }
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "error at {}..{}: {}",
+            u32::from(self.range.start()),
+            u32::from(self.range.end()),
+            self.kind,
+        )?;
+        // runaway recursion repeats the same call many times over
+        let mut frames = self.trace.iter().peekable();
+        while let Some(frame) = frames.next() {
+            write!(f, "\n    {}", frame)?;
+            let mut repeats = 0;
+            while frames.next_if_eq(&frame).is_some() {
+                repeats += 1;
+            }
+            if repeats > 0 {
+                write!(f, "\n    (repeated {} more times)", repeats)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A place in the code that was running when an error happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackFrame {
+    /// What was running, like ``"`main`"``, `"a closure"` or
+    /// `"top-level code"`.
+    pub function: String,
+    pub range: TextRange,
+}
+
+impl fmt::Display for StackFrame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "in {} at {}..{}",
+            self.function,
+            u32::from(self.range.start()),
+            u32::from(self.range.end()),
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuntimeErrorKind {
+    DivisionByZero,
+    IndexOutOfBounds {
+        index: i128,
+        len: usize,
+    },
+    /// The result of integer arithmetic doesn't fit in its type.
+    Overflow {
+        ty: &'static str,
+    },
+    /// A `...` was evaluated.
+    Placeholder,
+    /// A loop went over a range with a step of 0.
+    ZeroStep,
+    StackOverflow,
+    /// A global was read by a function that ran before its definition.
+    Uninitialized {
+        name: SmolStr,
+    },
+    /// A struct doesn't have a field, which the type checker lets through
+    /// for bindings of unknown type.
+    NoField {
+        ty: SmolStr,
+        name: SmolStr,
+    },
+    /// None of the arms of a `match` matched.
+    NoMatch,
+    /// A value of the wrong type, which the type checker lets through for
+    /// bindings of unknown type, like untyped closure parameters.
+    Mismatch {
+        expected: &'static str,
+        found: SmolStr,
+    },
+    /// Writing to the output failed.
+    Output(String),
+}
+
+impl fmt::Display for RuntimeErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DivisionByZero => write!(f, "division by zero"),
+            Self::IndexOutOfBounds { index, len } => write!(
+                f,
+                "index {} is out of bounds for an array of length {}",
+                index, len
+            ),
+            Self::Overflow { ty } => write!(f, "the result doesn't fit in `{}`", ty),
+            Self::Placeholder => write!(f, "reached a `...` that hasn't been filled in"),
+            Self::ZeroStep => write!(f, "a range can't step by 0"),
+            Self::StackOverflow => write!(f, "too many nested calls"),
+            Self::Uninitialized { name } => {
+                write!(f, "`{}` was used before it was defined", name)
+            }
+            Self::NoField { ty, name } => write!(f, "`{}` has no field `{}`", ty, name),
+            Self::NoMatch => write!(f, "no arm of the `match` matched"),
+            Self::Mismatch { expected, found } => {
+                write!(f, "expected {}, found `{}`", expected, found)
+            }
+            Self::Output(error) => write!(f, "couldn't write the output: {}", error),
+        }
+    }
+}
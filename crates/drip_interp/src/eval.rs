@@ -0,0 +1,1053 @@
+//! Evaluation of bodies by walking their expressions.
+//!
+//! The interpreter only runs programs without errors, so it relies on name
+//! resolution and type inference: every name has a resolution, calls only
+//! call functions, and operators only see operands they support. The few
+//! places where a value of unknown type can slip through are reported as
+//! [`RuntimeErrorKind::Mismatch`].
+
+use crate::env::{Env, Slot};
+use crate::error::{RuntimeError, RuntimeErrorKind};
+use crate::value::{Closure, Value};
+use drip_hir::infer::{self, InferenceResults, Ty};
+use drip_hir::method_resolution;
+use drip_hir::resolve::{BodyId, Builtin, Resolution, Resolutions};
+use drip_hir::{
+    BinaryOp, Body, Expr, ExprIdx, FormatPart, FunctionId, Item, ItemTree, Pat, PatIdx, Stmt,
+    UnaryOp,
+};
+use smol_str::SmolStr;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::io::Write;
+use text_size::TextRange;
+
+/// How deep calls can nest before the program is stopped. The interpreter
+/// grows its own stack as needed, so this only catches runaway recursion.
+const MAX_DEPTH: usize = 1000;
+
+/// Why evaluation stopped before producing a value.
+pub(crate) enum Unwind {
+    Break(Value),
+    Continue,
+    Error(RuntimeError),
+}
+
+type Eval<T = Value> = Result<T, Unwind>;
+
+/// The body being run and the bindings it sees.
+struct Frame<'a> {
+    id: BodyId,
+    body: &'a Body,
+    env: Env,
+}
+
+/// A binding, or a part of one reached through fields and indices, that can
+/// be assigned to.
+struct Place {
+    /// The name of the binding, and the expression that names it.
+    name: SmolStr,
+    root: ExprIdx,
+    env: Env,
+    slot: Slot,
+    path: Vec<(Projection, ExprIdx)>,
+}
+
+enum Projection {
+    Field(SmolStr),
+    Index(i128),
+}
+
+pub(crate) struct Interpreter<'a> {
+    tree: &'a ItemTree,
+    root: &'a Body,
+    resolutions: &'a Resolutions,
+    inference: &'a InferenceResults,
+    out: &'a mut dyn Write,
+    globals: Env,
+    /// What's running, innermost last, as it appears in stack traces.
+    stack: Vec<String>,
+}
+
+impl<'a> Interpreter<'a> {
+    pub(crate) fn new(
+        tree: &'a ItemTree,
+        root: &'a Body,
+        resolutions: &'a Resolutions,
+        inference: &'a InferenceResults,
+        globals: Env,
+        out: &'a mut dyn Write,
+    ) -> Self {
+        Self {
+            tree,
+            root,
+            resolutions,
+            inference,
+            out,
+            globals,
+            stack: Vec::new(),
+        }
+    }
+
+    /// Runs the top-level statements, then `main` if there is one.
+    pub(crate) fn run(&mut self) -> Result<(), RuntimeError> {
+        self.eval_top_level(0)?;
+
+        let main = self
+            .root
+            .top_level
+            .iter()
+            .find_map(|stmt| match self.root.stmts[*stmt] {
+                Stmt::Item(Item::Function(function)) if self.tree[function].name == "main" => {
+                    Some(function)
+                }
+                _ => None,
+            });
+        if let Some(main) = main {
+            unwrap_error(self.call_function(main, Vec::new()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Runs the top-level statements from the `from`th on, returning the
+    /// value of the last one.
+    pub(crate) fn eval_top_level(&mut self, from: usize) -> Result<Value, RuntimeError> {
+        let frame = Frame {
+            id: BodyId::Root,
+            body: self.root,
+            env: self.globals.clone(),
+        };
+        self.stack.push("top-level code".to_string());
+        let stmts = self.root.top_level.get(from..).unwrap_or_default();
+        let result = self.eval_stmts(&frame, stmts);
+        self.stack.pop();
+        match result {
+            Ok(value) => Ok(value),
+            Err(Unwind::Error(error)) => Err(error),
+            // checked programs don't break out of the top level
+            Err(_) => Ok(Value::unit()),
+        }
+    }
+
+    fn body(&self, id: BodyId) -> &'a Body {
+        match id {
+            BodyId::Root => self.root,
+            BodyId::Function(function) => &self.tree[function].body,
+        }
+    }
+
+    fn error(&self, kind: RuntimeErrorKind, frame: &Frame, expr: ExprIdx) -> Unwind {
+        let range = range(frame.body, expr);
+        let function = self.stack.last().cloned().unwrap_or_default();
+        Unwind::Error(RuntimeError::new(kind, range, function))
+    }
+
+    fn mismatch(
+        &self,
+        expected: &'static str,
+        found: &Value,
+        frame: &Frame,
+        expr: ExprIdx,
+    ) -> Unwind {
+        let found = found.type_name();
+        self.error(RuntimeErrorKind::Mismatch { expected, found }, frame, expr)
+    }
+
+    /// Runs `stmts` in order and returns the value of the last one, which is
+    /// `()` unless it's an expression.
+    fn eval_stmts(&mut self, frame: &Frame<'a>, stmts: &[usize]) -> Eval {
+        let mut last = Value::unit();
+        for &stmt in stmts {
+            last = self.eval_stmt(frame, stmt)?;
+        }
+        Ok(last)
+    }
+
+    fn eval_stmt(&mut self, frame: &Frame<'a>, idx: usize) -> Eval {
+        match &frame.body.stmts[idx] {
+            Stmt::VariableDef { value, .. } | Stmt::ConstDef { value, .. } => {
+                let value = self.eval_expr(frame, *value)?;
+                frame.env.define(Slot::Stmt(idx), value);
+            }
+            Stmt::TupleDef { pat, value, .. } => {
+                // inference made sure the value is a tuple of the right length
+                let value = self.eval_expr(frame, *value)?;
+                self.matches(frame, *pat, &value)?;
+            }
+            Stmt::Assign { target, op, value } => {
+                if let Some(place) = self.place(frame, *target)? {
+                    let mut value = self.eval_expr(frame, *value)?;
+                    let method = self.inference.body(frame.id).assign_resolution(idx);
+                    if let Some(function) = method {
+                        // the method changes `self`, which is written back to the target
+                        let current = self.load(frame, &place)?;
+                        let args = vec![current, value];
+                        let (_, env) = self.call_operator(frame, *target, function, args)?;
+                        if let Some(receiver) = env.get(Slot::Param(0)) {
+                            self.store(frame, &place, receiver)?;
+                        }
+                        return Ok(Value::unit());
+                    }
+                    if let Some(op) = op {
+                        let current = self.load(frame, &place)?;
+                        value = self.binary(frame, *target, op, current, value)?;
+                    }
+                    self.store(frame, &place, value)?;
+                }
+            }
+            Stmt::Expr(expr) => return self.eval_expr(frame, *expr),
+            Stmt::Item(_) => {}
+        }
+
+        Ok(Value::unit())
+    }
+
+    fn eval_exprs(&mut self, frame: &Frame<'a>, exprs: &[ExprIdx]) -> Eval<Vec<Value>> {
+        exprs
+            .iter()
+            .map(|expr| self.eval_expr(frame, *expr))
+            .collect()
+    }
+
+    /// Evaluates the arguments of the call `idx` in the order they're
+    /// written in, and returns them in the order of their parameters.
+    fn eval_args(&mut self, frame: &Frame<'a>, idx: ExprIdx, args: &[ExprIdx]) -> Eval<Vec<Value>> {
+        let values = self.eval_exprs(frame, args)?;
+        match self.inference.body(frame.id).arg_order(idx) {
+            Some(order) => {
+                let mut values: Vec<_> = order.iter().zip(values).collect();
+                values.sort_by_key(|(param, _)| **param);
+                Ok(values.into_iter().map(|(_, value)| value).collect())
+            }
+            None => Ok(values),
+        }
+    }
+
+    fn eval_expr(&mut self, frame: &Frame<'a>, idx: ExprIdx) -> Eval {
+        let value = match &frame.body.arenas[idx] {
+            Expr::Missing => Value::unit(),
+            Expr::Literal { n } => match self.inference.body(frame.id)[idx] {
+                Ty::Float(_) => Value::Float(n.unwrap_or_default() as f64),
+                _ => Value::Int(n.unwrap_or_default().into()),
+            },
+            Expr::FloatLiteral { n } => Value::Float(n.unwrap_or_default()),
+            Expr::StringLiteral { value } => Value::Str(value.to_string()),
+            Expr::FormatString { parts } => {
+                let mut s = String::new();
+                for part in parts {
+                    match part {
+                        FormatPart::Text(text) => s.push_str(text),
+                        FormatPart::Expr(expr) => {
+                            s.push_str(&self.eval_expr(frame, *expr)?.to_string())
+                        }
+                    }
+                }
+                Value::Str(s)
+            }
+            Expr::BoolLiteral { value } => Value::Bool(*value),
+            Expr::Binary { op, lhs, rhs } => {
+                let lhs = self.eval_expr(frame, *lhs)?;
+                let rhs = self.eval_expr(frame, *rhs)?;
+                match self.inference.body(frame.id).method_resolution(idx) {
+                    Some(function) => {
+                        let (value, _) =
+                            self.call_operator(frame, idx, function, vec![lhs, rhs])?;
+                        self.operator_value(frame, idx, op, value)?
+                    }
+                    None => self.binary(frame, idx, op, lhs, rhs)?,
+                }
+            }
+            Expr::Unary {
+                op: UnaryOp::Neg,
+                expr,
+            } => {
+                let value = self.eval_expr(frame, *expr)?;
+                match (self.inference.body(frame.id).method_resolution(idx), value) {
+                    (Some(function), value) => {
+                        self.call_operator(frame, idx, function, vec![value])?.0
+                    }
+                    (None, Value::Int(n)) => self.int(frame, idx, n.checked_neg())?,
+                    (None, Value::Float(n)) => Value::Float(-n),
+                    (None, value) => return Err(self.mismatch("a number", &value, frame, *expr)),
+                }
+            }
+            Expr::VariableRef { var } => match self.resolutions.get(frame.id, idx) {
+                Some(Resolution::Item(Item::Function(function))) => Value::Function(function),
+                Some(Resolution::Builtin(builtin)) => Value::Builtin(builtin),
+                Some(Resolution::SelfField { .. }) => match self.place(frame, idx)? {
+                    Some(place) => self.load(frame, &place)?,
+                    None => Value::unit(),
+                },
+                Some(resolution) => match self.slot(frame, resolution) {
+                    Some((env, slot)) => match env.get(slot) {
+                        Some(value) => value,
+                        None => {
+                            let name = var.clone();
+                            let kind = RuntimeErrorKind::Uninitialized { name };
+                            return Err(self.error(kind, frame, idx));
+                        }
+                    },
+                    // the type checker doesn't let other items be used as values
+                    None => Value::unit(),
+                },
+                None => Value::unit(),
+            },
+            Expr::Path { .. } => match self.resolutions.get(frame.id, idx) {
+                Some(Resolution::Item(Item::Function(function))) => Value::Function(function),
+                _ => Value::unit(),
+            },
+            Expr::Call { callee, args, .. } => {
+                let callee = self.eval_expr(frame, *callee)?;
+                let args = self.eval_args(frame, idx, args)?;
+                self.call(frame, idx, callee, args)?
+            }
+            Expr::MethodCall {
+                receiver,
+                name,
+                args,
+                ..
+            } => self.method_call(frame, idx, *receiver, name, args)?,
+            Expr::Field { expr, name } => match self.place(frame, idx)? {
+                // reading through the place avoids copying the whole binding
+                Some(place) => self.load(frame, &place)?,
+                None => {
+                    let value = self.eval_expr(frame, *expr)?;
+                    self.field(frame, *expr, value, name)?
+                }
+            },
+            Expr::Index { base, index } => match self.place(frame, idx)? {
+                Some(place) => self.load(frame, &place)?,
+                None => {
+                    let base = self.eval_expr(frame, *base)?;
+                    if let Some(function) = self.inference.body(frame.id).method_resolution(idx) {
+                        let index = self.eval_expr(frame, *index)?;
+                        return Ok(self
+                            .call_operator(frame, idx, function, vec![base, index])?
+                            .0);
+                    }
+                    let index = self.eval_index(frame, *index)?;
+                    match base {
+                        Value::Array(mut values) => {
+                            let i = self.check_bounds(frame, idx, index, values.len())?;
+                            values.swap_remove(i)
+                        }
+                        value => return Err(self.mismatch("an array", &value, frame, idx)),
+                    }
+                }
+            },
+            Expr::Struct { fields, .. } => match &self.inference.body(frame.id)[idx] {
+                Ty::Struct { id, name } => {
+                    let mut values = Vec::new();
+                    for (field, expr) in method_resolution::literal_fields(self.tree, *id, fields) {
+                        let value = self.eval_expr(frame, expr)?;
+                        if let Some(field) = field {
+                            values.push((field.clone(), value));
+                        }
+                    }
+
+                    // fields are kept in the order they're defined in
+                    Value::Struct {
+                        id: *id,
+                        name: name.clone(),
+                        fields: method_resolution::all_fields(self.tree, *id)
+                            .into_iter()
+                            .filter_map(|field| {
+                                let i = values.iter().position(|(name, _)| *name == field.name)?;
+                                Some(values.swap_remove(i))
+                            })
+                            .collect(),
+                    }
+                }
+                _ => {
+                    for (_, expr) in fields {
+                        self.eval_expr(frame, *expr)?;
+                    }
+                    Value::unit()
+                }
+            },
+            Expr::Closure { .. } => Value::Closure(Closure {
+                body: frame.id,
+                expr: idx,
+                env: frame.env.clone(),
+            }),
+            Expr::Placeholder => {
+                let returns_value = matches!(frame.id, BodyId::Function(function) if !self.tree[function].ret.is_empty());
+                if !frame.body.value_unused(idx, returns_value) {
+                    return Err(self.error(RuntimeErrorKind::Placeholder, frame, idx));
+                }
+                Value::unit()
+            }
+            Expr::Tuple { exprs } => Value::Tuple(self.eval_exprs(frame, exprs)?),
+            Expr::Array { exprs } => Value::Array(self.eval_exprs(frame, exprs)?),
+            Expr::Range { start, end, step } => {
+                let start = self.eval_expr(frame, *start)?;
+                let end = self.eval_expr(frame, *end)?;
+                let step = match step {
+                    Some(step) => Some(Box::new(self.eval_expr(frame, *step)?)),
+                    None => None,
+                };
+                Value::Range(Box::new(start), Box::new(end), step)
+            }
+            Expr::Block { stmts } => self.eval_stmts(frame, stmts)?,
+            Expr::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                if self.eval_condition(frame, *condition)? {
+                    let value = self.eval_expr(frame, *then_branch)?;
+                    // without an `else`, the value of the branch is thrown away
+                    match else_branch {
+                        Some(_) => value,
+                        None => Value::unit(),
+                    }
+                } else {
+                    match else_branch {
+                        Some(else_branch) => self.eval_expr(frame, *else_branch)?,
+                        None => Value::unit(),
+                    }
+                }
+            }
+            Expr::Loop { body } => loop {
+                match self.eval_expr(frame, *body) {
+                    Ok(_) | Err(Unwind::Continue) => {}
+                    Err(Unwind::Break(value)) => break value,
+                    Err(error) => return Err(error),
+                }
+            },
+            Expr::While { condition, body } => loop {
+                if !self.eval_condition(frame, *condition)? {
+                    break Value::unit();
+                }
+                match self.eval_expr(frame, *body) {
+                    Ok(_) | Err(Unwind::Continue) => {}
+                    Err(Unwind::Break(value)) => break value,
+                    Err(error) => return Err(error),
+                }
+            },
+            Expr::For {
+                pat,
+                index,
+                iterable,
+                body,
+            } => {
+                let values = match self.eval_expr(frame, *iterable)? {
+                    Value::Array(values) => values,
+                    Value::Range(start, end, step) => range_elements(&start, &end, step.as_deref())
+                        .map_err(|kind| self.error(kind, frame, *iterable))?,
+                    value => {
+                        return Err(self.mismatch("an array or range", &value, frame, *iterable))
+                    }
+                };
+
+                let mut result = Value::unit();
+                for (i, value) in values.into_iter().enumerate() {
+                    // inference made sure the patterns always match
+                    self.matches(frame, *pat, &value)?;
+                    if let Some(index) = index {
+                        self.matches(frame, *index, &Value::Int(i as i128))?;
+                    }
+                    match self.eval_expr(frame, *body) {
+                        Ok(_) | Err(Unwind::Continue) => {}
+                        Err(Unwind::Break(value)) => {
+                            result = value;
+                            break;
+                        }
+                        Err(error) => return Err(error),
+                    }
+                }
+                result
+            }
+            Expr::Break { value } => {
+                let value = match value {
+                    Some(value) => self.eval_expr(frame, *value)?,
+                    None => Value::unit(),
+                };
+                return Err(Unwind::Break(value));
+            }
+            Expr::Continue => return Err(Unwind::Continue),
+            Expr::Match { scrutinee, arms } => {
+                let value = self.eval_expr(frame, *scrutinee)?;
+                for arm in arms {
+                    for pat in &arm.pats {
+                        if self.matches(frame, *pat, &value)? {
+                            return self.eval_expr(frame, arm.body);
+                        }
+                    }
+                }
+                return Err(self.error(RuntimeErrorKind::NoMatch, frame, idx));
+            }
+        };
+
+        Ok(value)
+    }
+
+    fn eval_condition(&mut self, frame: &Frame<'a>, expr: ExprIdx) -> Eval<bool> {
+        match self.eval_expr(frame, expr)? {
+            Value::Bool(b) => Ok(b),
+            value => Err(self.mismatch("a bool", &value, frame, expr)),
+        }
+    }
+
+    fn eval_index(&mut self, frame: &Frame<'a>, expr: ExprIdx) -> Eval<i128> {
+        match self.eval_expr(frame, expr)? {
+            Value::Int(n) => Ok(n),
+            value => Err(self.mismatch("an integer", &value, frame, expr)),
+        }
+    }
+
+    fn check_bounds(&self, frame: &Frame, expr: ExprIdx, index: i128, len: usize) -> Eval<usize> {
+        match usize::try_from(index) {
+            Ok(i) if i < len => Ok(i),
+            _ => {
+                let kind = RuntimeErrorKind::IndexOutOfBounds { index, len };
+                Err(self.error(kind, frame, expr))
+            }
+        }
+    }
+
+    /// The binding `resolution` refers to, or `None` if it refers to an item.
+    fn slot(&self, frame: &Frame, resolution: Resolution) -> Option<(Env, Slot)> {
+        let (env, slot) = match resolution {
+            Resolution::Global { stmt } => return Some((self.globals.clone(), Slot::Stmt(stmt))),
+            Resolution::Local { stmt, .. } => (&frame.env, Slot::Stmt(stmt)),
+            Resolution::Param { index, .. } => (&frame.env, Slot::Param(index)),
+            Resolution::SelfField { .. } => (&frame.env, Slot::Param(0)),
+            Resolution::PatBinding { pat, .. } => (&frame.env, Slot::Pat(pat)),
+            Resolution::ClosureParam { closure, index, .. } => {
+                (&frame.env, Slot::ClosureParam(closure, index))
+            }
+            Resolution::Item(_) | Resolution::Builtin(_) => return None,
+        };
+        Some((env.clone(), slot))
+    }
+
+    /// The place `expr` refers to, like `v.a[i]`, or `None` if it isn't one.
+    /// Indices are evaluated along the way.
+    fn place(&mut self, frame: &Frame<'a>, expr: ExprIdx) -> Eval<Option<Place>> {
+        let place = match &frame.body.arenas[expr] {
+            Expr::VariableRef { var } => {
+                let resolution = self.resolutions.get(frame.id, expr);
+                match resolution.and_then(|resolution| self.slot(frame, resolution)) {
+                    // `x` in a method is `self.x`
+                    Some((env, slot))
+                        if matches!(resolution, Some(Resolution::SelfField { .. })) =>
+                    {
+                        Some(Place {
+                            name: "self".into(),
+                            root: expr,
+                            env,
+                            slot,
+                            path: vec![(Projection::Field(var.clone()), expr)],
+                        })
+                    }
+                    Some((env, slot)) => Some(Place {
+                        name: var.clone(),
+                        root: expr,
+                        env,
+                        slot,
+                        path: Vec::new(),
+                    }),
+                    None => None,
+                }
+            }
+            Expr::Field { expr: inner, name } => self.place(frame, *inner)?.map(|mut place| {
+                place.path.push((Projection::Field(name.clone()), expr));
+                place
+            }),
+            // an `Index` impl returns a new value rather than a place
+            Expr::Index { .. }
+                if self
+                    .inference
+                    .body(frame.id)
+                    .method_resolution(expr)
+                    .is_some() =>
+            {
+                None
+            }
+            Expr::Index { base, index } => match self.place(frame, *base)? {
+                Some(mut place) => {
+                    let index = self.eval_index(frame, *index)?;
+                    place.path.push((Projection::Index(index), expr));
+                    Some(place)
+                }
+                None => None,
+            },
+            _ => None,
+        };
+
+        Ok(place)
+    }
+
+    fn load(&self, frame: &Frame, place: &Place) -> Eval {
+        self.with_place(frame, place, |value| value.clone())
+    }
+
+    fn store(&self, frame: &Frame, place: &Place, value: Value) -> Eval<()> {
+        // assigning to a binding that hasn't been defined yet defines it
+        if place.path.is_empty() && place.env.get(place.slot).is_none() {
+            place.env.define(place.slot, value);
+            return Ok(());
+        }
+        self.with_place(frame, place, |target| *target = value)
+    }
+
+    fn with_place<T>(
+        &self,
+        frame: &Frame,
+        place: &Place,
+        f: impl FnOnce(&mut Value) -> T,
+    ) -> Eval<T> {
+        let result = place.env.with_mut(place.slot, |mut value| {
+            for (projection, expr) in &place.path {
+                value = match projection {
+                    Projection::Field(name) => {
+                        field_mut(value, name).map_err(|kind| (kind, *expr))?
+                    }
+                    Projection::Index(index) => match value {
+                        Value::Array(values) => {
+                            let len = values.len();
+                            match usize::try_from(*index) {
+                                Ok(i) if i < len => &mut values[i],
+                                _ => {
+                                    let kind =
+                                        RuntimeErrorKind::IndexOutOfBounds { index: *index, len };
+                                    return Err((kind, *expr));
+                                }
+                            }
+                        }
+                        value => {
+                            let found = value.type_name();
+                            let kind = RuntimeErrorKind::Mismatch {
+                                expected: "an array",
+                                found,
+                            };
+                            return Err((kind, *expr));
+                        }
+                    },
+                };
+            }
+            Ok(f(value))
+        });
+
+        match result {
+            Some(Ok(value)) => Ok(value),
+            Some(Err((kind, expr))) => Err(self.error(kind, frame, expr)),
+            None => {
+                let name = place.name.clone();
+                let kind = RuntimeErrorKind::Uninitialized { name };
+                Err(self.error(kind, frame, place.root))
+            }
+        }
+    }
+
+    fn field(&self, frame: &Frame, expr: ExprIdx, mut value: Value, name: &str) -> Eval {
+        match field_mut(&mut value, name) {
+            Ok(field) => Ok(field.clone()),
+            Err(kind) => Err(self.error(kind, frame, expr)),
+        }
+    }
+
+    /// Applies `op` to two values, for the expression `expr`.
+    fn binary(&self, frame: &Frame, expr: ExprIdx, op: &BinaryOp, lhs: Value, rhs: Value) -> Eval {
+        if !op.is_comparison() {
+            let value = match (lhs, rhs) {
+                (Value::Int(_), Value::Int(0)) if *op == BinaryOp::Div => {
+                    return Err(self.error(RuntimeErrorKind::DivisionByZero, frame, expr));
+                }
+                (Value::Int(lhs), Value::Int(rhs)) => {
+                    let n = match op {
+                        BinaryOp::Add => lhs.checked_add(rhs),
+                        BinaryOp::Sub => lhs.checked_sub(rhs),
+                        BinaryOp::Mul => lhs.checked_mul(rhs),
+                        _ => lhs.checked_div(rhs),
+                    };
+                    self.int(frame, expr, n)?
+                }
+                (Value::Float(lhs), Value::Float(rhs)) => Value::Float(match op {
+                    BinaryOp::Add => lhs + rhs,
+                    BinaryOp::Sub => lhs - rhs,
+                    BinaryOp::Mul => lhs * rhs,
+                    _ => lhs / rhs,
+                }),
+                (Value::Str(lhs), Value::Str(rhs)) if *op == BinaryOp::Add => {
+                    Value::Str(lhs + &rhs)
+                }
+                (lhs, _) => return Err(self.mismatch("a number", &lhs, frame, expr)),
+            };
+            return Ok(value);
+        }
+
+        let ordering = match op {
+            BinaryOp::Eq => return Ok(Value::Bool(lhs == rhs)),
+            BinaryOp::NotEq => return Ok(Value::Bool(lhs != rhs)),
+            _ => match compare(&lhs, &rhs) {
+                Some(ordering) => ordering,
+                // NaN isn't ordered with anything
+                None if matches!(lhs, Value::Float(_)) => return Ok(Value::Bool(false)),
+                None => return Err(self.mismatch("a number or string", &lhs, frame, expr)),
+            },
+        };
+        Ok(Value::Bool(match op {
+            BinaryOp::Less => ordering == Ordering::Less,
+            BinaryOp::LessEq => ordering != Ordering::Greater,
+            BinaryOp::Greater => ordering == Ordering::Greater,
+            _ => ordering != Ordering::Less,
+        }))
+    }
+
+    /// The value of the operator `op` on structs, given what the method of
+    /// its trait returned: `!=` negates `eq`, and comparisons compare the
+    /// result of `cmp` with zero.
+    fn operator_value(&self, frame: &Frame, expr: ExprIdx, op: &BinaryOp, value: Value) -> Eval {
+        match op {
+            BinaryOp::NotEq => self.binary(frame, expr, &BinaryOp::Eq, value, Value::Bool(false)),
+            op if op.is_comparison() && *op != BinaryOp::Eq => {
+                self.binary(frame, expr, op, value, Value::Int(0))
+            }
+            _ => Ok(value),
+        }
+    }
+
+    /// Checks that the result of integer arithmetic fits in the type of
+    /// `expr`; `None` means it didn't even fit in an `i128`.
+    fn int(&self, frame: &Frame, expr: ExprIdx, n: Option<i128>) -> Eval {
+        let ty = match &self.inference.body(frame.id)[expr] {
+            Ty::Int(ty) => Some(*ty),
+            _ => None,
+        };
+        match (n, ty) {
+            (Some(n), Some(ty)) if (ty.bounds().0..=ty.bounds().1).contains(&n) => {
+                Ok(Value::Int(n))
+            }
+            (Some(n), None) => Ok(Value::Int(n)),
+            (_, ty) => {
+                let ty = ty.map_or("i128", |ty| ty.name());
+                Err(self.error(RuntimeErrorKind::Overflow { ty }, frame, expr))
+            }
+        }
+    }
+
+    fn method_call(
+        &mut self,
+        frame: &Frame<'a>,
+        idx: ExprIdx,
+        receiver: ExprIdx,
+        name: &str,
+        args: &[ExprIdx],
+    ) -> Eval {
+        let place = self.place(frame, receiver)?;
+        let receiver_value = match &place {
+            Some(place) => self.load(frame, place)?,
+            None => self.eval_expr(frame, receiver)?,
+        };
+        let args = self.eval_args(frame, idx, args)?;
+
+        // methods are looked up on the struct the receiver actually is, so
+        // calls on `self` in a trait reach the implementing type's method
+        let method = match &receiver_value {
+            Value::Struct { id, .. } => {
+                let ty = infer::struct_ty(self.tree, *id);
+                method_resolution::lookup_method(self.tree, &ty, name).ok()
+            }
+            _ => None,
+        };
+        let method = method.or_else(|| self.inference.body(frame.id).method_resolution(idx));
+
+        let function = match method {
+            Some(function) => function,
+            // a field that holds a function, like `self.sound()`
+            None => {
+                let callee = self.field(frame, receiver, receiver_value, name)?;
+                return self.call(frame, idx, callee, args);
+            }
+        };
+
+        self.check_depth(frame, idx)?;
+        let mut all_args = vec![receiver_value];
+        all_args.extend(args);
+        let (value, env) = self
+            .call_function(function, all_args)
+            .map_err(|unwind| self.called_from(unwind, frame, idx))?;
+
+        // the method can change `self`, which is written back to the receiver
+        if let (Some(place), Some(receiver)) = (place, env.get(Slot::Param(0))) {
+            self.store(frame, &place, receiver)?;
+        }
+
+        Ok(value)
+    }
+
+    /// Calls `function`, the method behind the operator at `idx`, and
+    /// returns its value along with the environment it ran in.
+    fn call_operator(
+        &mut self,
+        frame: &Frame<'a>,
+        idx: ExprIdx,
+        function: FunctionId,
+        args: Vec<Value>,
+    ) -> Eval<(Value, Env)> {
+        self.check_depth(frame, idx)?;
+        self.call_function(function, args)
+            .map_err(|unwind| self.called_from(unwind, frame, idx))
+    }
+
+    fn check_depth(&self, frame: &Frame, idx: ExprIdx) -> Eval<()> {
+        if self.stack.len() >= MAX_DEPTH {
+            return Err(self.error(RuntimeErrorKind::StackOverflow, frame, idx));
+        }
+        Ok(())
+    }
+
+    /// Adds the call at `idx` to the trace of an error that happened inside
+    /// it.
+    fn called_from(&self, unwind: Unwind, frame: &Frame, idx: ExprIdx) -> Unwind {
+        match unwind {
+            Unwind::Error(error) => {
+                let function = self.stack.last().cloned().unwrap_or_default();
+                Unwind::Error(error.called_from(function, range(frame.body, idx)))
+            }
+            unwind => unwind,
+        }
+    }
+
+    /// Calls `callee` with `args` for the call expression `idx`.
+    fn call(&mut self, frame: &Frame<'a>, idx: ExprIdx, callee: Value, args: Vec<Value>) -> Eval {
+        let result = match callee {
+            Value::Function(function) => {
+                self.check_depth(frame, idx)?;
+                self.call_function(function, args).map(|(value, _)| value)
+            }
+            Value::Closure(closure) => {
+                self.check_depth(frame, idx)?;
+                self.call_closure(&closure, args)
+            }
+            Value::Builtin(builtin) => {
+                return self.call_builtin(frame, idx, builtin, args);
+            }
+            callee => return Err(self.mismatch("a function", &callee, frame, idx)),
+        };
+
+        result.map_err(|unwind| self.called_from(unwind, frame, idx))
+    }
+
+    /// Runs `function` and returns its value along with the environment it
+    /// ran in.
+    fn call_function(&mut self, function: FunctionId, args: Vec<Value>) -> Eval<(Value, Env)> {
+        let tree = self.tree;
+        let frame = Frame {
+            id: BodyId::Function(function),
+            body: &tree[function].body,
+            env: Env::default(),
+        };
+        for (i, arg) in args.into_iter().enumerate() {
+            frame.env.define(Slot::Param(i), arg);
+        }
+
+        self.stack.push(format!("`{}`", tree[function].name));
+        let result = grow(|| self.eval_stmts(&frame, &frame.body.top_level));
+        self.stack.pop();
+
+        Ok((result?, frame.env))
+    }
+
+    fn call_closure(&mut self, closure: &Closure, args: Vec<Value>) -> Eval {
+        let body = self.body(closure.body);
+        let closure_body = match &body.arenas[closure.expr] {
+            Expr::Closure { body, .. } => *body,
+            _ => unreachable!("closures are created from `Closure` expressions"),
+        };
+        let frame = Frame {
+            id: closure.body,
+            body,
+            env: closure.env.child(),
+        };
+        for (i, arg) in args.into_iter().enumerate() {
+            frame.env.define(Slot::ClosureParam(closure.expr, i), arg);
+        }
+
+        self.stack.push("a closure".to_string());
+        let result = grow(|| self.eval_expr(&frame, closure_body));
+        self.stack.pop();
+
+        result
+    }
+
+    fn call_builtin(
+        &mut self,
+        frame: &Frame,
+        idx: ExprIdx,
+        builtin: Builtin,
+        args: Vec<Value>,
+    ) -> Eval {
+        let text = args
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(" ");
+        let result = match builtin {
+            Builtin::Print => write!(self.out, "{}", text),
+            Builtin::Println => writeln!(self.out, "{}", text),
+        };
+
+        match result {
+            Ok(()) => Ok(Value::unit()),
+            Err(error) => {
+                let kind = RuntimeErrorKind::Output(error.to_string());
+                Err(self.error(kind, frame, idx))
+            }
+        }
+    }
+
+    /// Whether `value` matches `pat`, binding the names in it if it does.
+    fn matches(&mut self, frame: &Frame<'a>, pat: PatIdx, value: &Value) -> Eval<bool> {
+        let matches = match &frame.body.arenas[pat] {
+            Pat::Missing => false,
+            Pat::Wildcard => true,
+            Pat::Ident { .. } => match self.resolutions.pat(frame.id, pat) {
+                // a constant, which is compared with the value
+                Some(resolution) => match self.slot(frame, resolution) {
+                    Some((env, slot)) => env.get(slot).as_ref() == Some(value),
+                    None => false,
+                },
+                None => {
+                    frame.env.define(Slot::Pat(pat), value.clone());
+                    true
+                }
+            },
+            Pat::Literal { expr } => self.eval_expr(frame, *expr)? == *value,
+            Pat::Range { start, end } => {
+                let start = self.eval_expr(frame, *start)?;
+                let end = self.eval_expr(frame, *end)?;
+                compare(&start, value).is_some_and(|ordering| ordering != Ordering::Greater)
+                    && compare(value, &end) == Some(Ordering::Less)
+            }
+            Pat::Tuple { pats } => match value {
+                Value::Tuple(values) if values.len() == pats.len() => {
+                    for (pat, value) in pats.iter().zip(values) {
+                        if !self.matches(frame, *pat, value)? {
+                            return Ok(false);
+                        }
+                    }
+                    true
+                }
+                _ => false,
+            },
+            Pat::Struct {
+                name: pat_name,
+                fields: pat_fields,
+            } => match value {
+                Value::Struct { name, fields, .. } if name == pat_name => {
+                    for (field, pat) in pat_fields {
+                        let value = fields.iter().find(|(name, _)| name == field);
+                        match value {
+                            Some((_, value)) if self.matches(frame, *pat, value)? => {}
+                            _ => return Ok(false),
+                        }
+                    }
+                    true
+                }
+                _ => false,
+            },
+        };
+
+        Ok(matches)
+    }
+}
+
+fn field_mut<'v>(value: &'v mut Value, name: &str) -> Result<&'v mut Value, RuntimeErrorKind> {
+    match value {
+        Value::Struct {
+            name: ty, fields, ..
+        } => {
+            let ty = ty.clone();
+            fields
+                .iter_mut()
+                .find(|(field, _)| field == name)
+                .map(|(_, value)| value)
+                .ok_or(RuntimeErrorKind::NoField {
+                    ty,
+                    name: name.into(),
+                })
+        }
+        value => Err(RuntimeErrorKind::Mismatch {
+            expected: "a struct",
+            found: value.type_name(),
+        }),
+    }
+}
+
+fn compare(lhs: &Value, rhs: &Value) -> Option<Ordering> {
+    match (lhs, rhs) {
+        (Value::Int(lhs), Value::Int(rhs)) => Some(lhs.cmp(rhs)),
+        (Value::Float(lhs), Value::Float(rhs)) => lhs.partial_cmp(rhs),
+        (Value::Str(lhs), Value::Str(rhs)) => Some(lhs.cmp(rhs)),
+        _ => None,
+    }
+}
+
+/// Runs `f` with enough stack for another call to be evaluated.
+fn grow<T>(f: impl FnOnce() -> T) -> T {
+    stacker::maybe_grow(128 * 1024, 2 * 1024 * 1024, f)
+}
+
+fn range(body: &Body, expr: ExprIdx) -> TextRange {
+    body.arenas
+        .source_map()
+        .expr_syntax(expr)
+        .map_or_else(TextRange::default, |source| source.range())
+}
+
+/// Turns what's left after running a body into its error, if it had one;
+/// `break` and `continue` can't leave a body.
+fn unwrap_error<T>(result: Eval<T>) -> Result<(), RuntimeError> {
+    match result {
+        Err(Unwind::Error(error)) => Err(error),
+        _ => Ok(()),
+    }
+}
+
+/// The elements of `start..end`, counting in steps of `step`, or of 1 if
+/// there's none.
+fn range_elements(
+    start: &Value,
+    end: &Value,
+    step: Option<&Value>,
+) -> Result<Vec<Value>, RuntimeErrorKind> {
+    match (start, end, step) {
+        (Value::Int(start), Value::Int(end), None | Some(Value::Int(_))) => {
+            let step = match step {
+                Some(Value::Int(step)) => *step,
+                _ => 1,
+            };
+            if step == 0 {
+                return Err(RuntimeErrorKind::ZeroStep);
+            }
+            let mut values = Vec::new();
+            let mut n = *start;
+            while (step > 0 && n < *end) || (step < 0 && n > *end) {
+                values.push(Value::Int(n));
+                n += step;
+            }
+            Ok(values)
+        }
+        (Value::Float(start), Value::Float(end), None | Some(Value::Float(_))) => {
+            let step = match step {
+                Some(Value::Float(step)) => *step,
+                _ => 1.0,
+            };
+            if step == 0.0 {
+                return Err(RuntimeErrorKind::ZeroStep);
+            }
+            let len = ((end - start) / step).ceil().max(0.0) as usize;
+            Ok((0..len)
+                .map(|i| Value::Float(start + i as f64 * step))
+                .collect())
+        }
+        (start, ..) => Err(RuntimeErrorKind::Mismatch {
+            expected: "a number",
+            found: start.type_name(),
+        }),
+    }
+}
@@ -0,0 +1,341 @@
+//! A tree-walking interpreter for checked Drip programs.
+//!
+//! The interpreter runs the HIR of a file directly: it evaluates the file's
+//! top-level statements in order and then calls `main`, if the file defines
+//! one. Programs with errors aren't run at all.
+
+mod env;
+mod error;
+mod eval;
+mod value;
+
+pub use error::{RuntimeError, RuntimeErrorKind, StackFrame};
+pub use value::{Closure, Value};
+
+use crate::env::Env;
+use drip_hir::db::{Diagnostic, FileId, HirDatabase};
+use drip_hir::flow::Severity;
+use std::fmt;
+use std::fmt::Formatter;
+use std::io::Write;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// The program has errors, so it wasn't run.
+    Diagnostics(Vec<Diagnostic>),
+    Runtime(RuntimeError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Diagnostics(diagnostics) => {
+                for (i, diagnostic) in diagnostics.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}", diagnostic)?;
+                }
+                Ok(())
+            }
+            Self::Runtime(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+/// Runs `file`, writing what it prints to `out`.
+pub fn run(db: &dyn HirDatabase, file: FileId, out: &mut dyn Write) -> Result<(), Error> {
+    with_interpreter(db, file, Env::default(), out, |interpreter| {
+        interpreter.run()
+    })
+}
+
+/// The top-level bindings of a file that grows over time, like the inputs
+/// of a REPL, so only the statements added to it have to be run.
+#[derive(Default)]
+pub struct Session {
+    globals: Env,
+    /// How many of the file's top-level statements have run.
+    ran: usize,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs the top-level statements of `file` that haven't run yet, without
+    /// calling `main`, and returns the value of the last one, which is `()`
+    /// unless it's an expression. The statements before them must be the
+    /// ones that already ran. If running fails, the new statements count as
+    /// not having run, but what they changed before the error stays changed.
+    pub fn eval(
+        &mut self,
+        db: &dyn HirDatabase,
+        file: FileId,
+        out: &mut dyn Write,
+    ) -> Result<Value, Error> {
+        let from = self.ran;
+        let value = with_interpreter(db, file, self.globals.clone(), out, |interpreter| {
+            interpreter.eval_top_level(from)
+        })?;
+        self.ran = db.lower_file(file).root.top_level.len();
+        Ok(value)
+    }
+}
+
+fn with_interpreter<T>(
+    db: &dyn HirDatabase,
+    file: FileId,
+    globals: Env,
+    out: &mut dyn Write,
+    f: impl FnOnce(&mut eval::Interpreter<'_>) -> Result<T, RuntimeError>,
+) -> Result<T, Error> {
+    let errors: Vec<_> = db
+        .diagnostics(file)
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == Severity::Error)
+        .cloned()
+        .collect();
+    if !errors.is_empty() {
+        return Err(Error::Diagnostics(errors));
+    }
+
+    let lowered = db.lower_file(file);
+    let resolutions = db.resolutions(file);
+    let inference = db.inference(file);
+    let mut interpreter = eval::Interpreter::new(
+        &lowered.tree,
+        &lowered.root,
+        &resolutions,
+        &inference,
+        globals,
+        out,
+    );
+    f(&mut interpreter).map_err(Error::Runtime)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use drip_hir::db::SourceDatabase;
+    use drip_hir::Database;
+
+    /// Runs `input` and returns what it printed, followed by its error if it
+    /// had one.
+    fn run_source(input: &str) -> String {
+        let mut db = Database::default();
+        let file = FileId(0);
+        db.set_file_text(file, input.into());
+        db.set_root_file(file);
+
+        let mut out = Vec::new();
+        let result = run(&db, file, &mut out);
+        let mut output = String::from_utf8(out).unwrap();
+        if let Err(error) = result {
+            output.push_str(&error.to_string());
+        }
+        output
+    }
+
+    #[test]
+    fn run_walkthrough_examples() {
+        // `loop { }` never ends, so it's left out
+        let input = include_str!("../../../examples/drip/basic/walkthrough.drip");
+        let infinite_loop = "    loop { } // infinite loop, can be breaked\n";
+        assert!(input.contains(infinite_loop));
+        let input = input.replace(infinite_loop, "");
+
+        // the example calls a `traits` function it doesn't define, and gives
+        // `x` the value of a `for` that can run out of items without one
+        assert_eq!(
+            run_source(&input),
+            "error at 151..157: cannot find `traits` in this scope\n\
+             error at 2463..2540: loop can end without a value, but other exits of it have one"
+        );
+    }
+
+    #[test]
+    fn run_structs_methods_and_closures() {
+        let input = r#"Animal :: struct { name: string, legs: i32 }
+Animal :: impl {
+    describe :: (self) -> string {
+        legs :: self.legs
+        self.name + " has {legs} legs"
+    }
+    lose_leg :: (self) { self.legs -= 1 }
+}
+Dog :: struct <| Animal { toy: string }
+Dog :: impl {
+    describe :: (self) -> string { self.name + " likes the " + self.toy }
+}
+
+dog := Dog { name: "Rex", legs: 4, toy: "ball" }
+println(dog)
+println(dog.describe())
+cat := Animal { name: "Tom", legs: 4 }
+cat.lose_leg()
+println(cat.describe())
+
+count := 0
+bump :: |by: i32| { count += by
+count }
+bump(2)
+println(bump(3))
+twice :: |x: i32| x * 2
+println([twice(1), twice(2)][1])
+s :: "a"
+println(("x", s, 1.5))"#;
+
+        assert_eq!(
+            run_source(input),
+            r#"Dog { toy: "ball", name: "Rex", legs: 4 }
+Rex likes the ball
+Tom has 3 legs
+5
+4
+("x", "a", 1.5)
+"#
+        );
+    }
+
+    #[test]
+    fn run_operators_on_structs() {
+        let input = r#"Money :: struct { cents: i32 }
+Money <| Add :: impl { add :: (self, other: Money) -> Money { Money { cents: self.cents + other.cents } } }
+Money <| Mul :: impl { mul :: (self, by: i32) -> Money { Money { cents: self.cents * by } } }
+Money <| Neg :: impl { neg :: (self) -> Money { Money { cents: -self.cents } } }
+Money <| Eq :: impl { eq :: (self, other: Self) -> bool { self.cents / 100 == other.cents / 100 } }
+Money <| Ord :: impl { cmp :: (self, other: Self) -> i32 { self.cents - other.cents } }
+Money <| Index :: impl { index :: (self, i: i32) -> i32 { if i == 0 { self.cents / 100 } else { self.cents - self.cents / 100 * 100 } } }
+Money <| SubAssign :: impl { sub_assign :: (self, other: Money) { self.cents -= other.cents } }
+
+a :: Money { cents: 150 }
+b :: Money { cents: 199 }
+println(a + b * 2)
+println(-a)
+println((a == b, a != b, a < b, a >= b))
+println((b[0], b[1]))
+wallet := [a, b]
+wallet[1] -= a
+println(wallet[1])"#;
+
+        assert_eq!(
+            run_source(input),
+            "Money { cents: 548 }\nMoney { cents: -150 }\n(true, false, true, false)\n(1, 99)\nMoney { cents: 49 }\n"
+        );
+    }
+
+    #[test]
+    fn run_control_flow_and_match() {
+        let input = r#"N :: 3
+Point :: struct { x, y: i32 }
+describe :: (p: Point) -> string {
+    match p {
+        Point { x: 0, y: 0 } => "origin"
+        Point { x: N } => "on x = N"
+        Point { y } => "y = {y}"
+    }
+}
+
+arr := [1, 2, 3, 4, 5, 6]
+sum := 0
+for n in arr {
+    if n == 2 { continue }
+    if n == 5 { break }
+    sum += n
+}
+i := 0
+while i < 3 { arr[i] = arr[i] * 10
+i += 1 }
+println("{sum} {arr}")
+println(describe(Point { x: 0, y: 0 }))
+println(describe(Point { x: 3, y: 7 }))
+println(describe(Point { x: 1, y: 7 }))
+grade :: match 85 {
+    90..101 => "A"
+    80..90 => "B"
+    _ => "C"
+}
+println(grade)"#;
+
+        assert_eq!(
+            run_source(input),
+            "8 [10, 20, 30, 4, 5, 6]\norigin\non x = N\ny = 7\nB\n"
+        );
+    }
+
+    #[test]
+    fn run_number_literals_with_separators() {
+        assert_eq!(
+            run_source("x :: 1_000 + 1\ny :: 1_0.5\nprintln(\"{x} {y}\")"),
+            "1001 10.5\n"
+        );
+    }
+
+    #[test]
+    fn report_runtime_errors_with_stack_traces() {
+        let input = "div :: (a, b: i32) -> i32 { a / b }
+main :: () {
+    f :: |b: i32| div(10, b)
+    println(f(2))
+    f(0)
+}";
+        assert_eq!(
+            run_source(input),
+            "5
+error at 28..34: division by zero
+    in `div` at 28..34
+    in a closure at 67..82
+    in `main` at 100..105"
+        );
+
+        assert_eq!(
+            run_source("arr :: [1, 2, 3]\ni := 3\narr[i]"),
+            "error at 24..30: index 3 is out of bounds for an array of length 3
+    in top-level code at 24..30"
+        );
+        assert_eq!(
+            run_source("f :: (n: u8) -> u8 { n * 200 }\nf(1)\nf(2)"),
+            "error at 21..29: the result doesn't fit in `u8`
+    in `f` at 21..29
+    in top-level code at 36..40"
+        );
+        assert_eq!(
+            run_source("f :: (n: u8) -> u8 { if n == 0 { ... } else { n } }\nf(0)"),
+            "error at 33..37: reached a `...` that hasn't been filled in
+    in `f` at 33..37
+    in top-level code at 52..56"
+        );
+        assert_eq!(
+            run_source("step := 0\nfor i in 0..3=step { }"),
+            "error at 19..29: a range can't step by 0
+    in top-level code at 19..29"
+        );
+        assert_eq!(
+            run_source("f :: (n: u8) { if n == 0 { ... } else { .. } }\nf(0)\nprintln(\"done\")"),
+            "done\n"
+        );
+        assert_eq!(
+            run_source("main :: () {\n    y :: ...\n    println(\"{y}\")\n}"),
+            "error at 22..30: reached a `...` that hasn't been filled in
+    in `main` at 22..30"
+        );
+        assert_eq!(
+            run_source("f :: (n: i32) -> i32 { f(n + 1) }\nf(0)"),
+            "error at 23..32: too many nested calls
+    in `f` at 23..32
+    (repeated 998 more times)
+    in top-level code at 34..38"
+        );
+    }
+
+    #[test]
+    fn refuse_to_run_programs_with_errors() {
+        assert_eq!(
+            run_source("println(\"hi\")\nx :: 1\nx = 2"),
+            "error at 21..23: cannot assign to immutable binding `x`
+    note at 14..20: defined with `::` here"
+        );
+    }
+}
@@ -0,0 +1,116 @@
+//! The values a program computes.
+//!
+//! Values are copied when they're bound or passed around, so changing a
+//! field of one binding never changes another. The only shared state is the
+//! environment a closure captures, which it sees changes to.
+
+use crate::env::Env;
+use drip_hir::resolve::{BodyId, Builtin};
+use drip_hir::{ExprIdx, FunctionId, StructId};
+use smol_str::SmolStr;
+use std::fmt;
+use std::fmt::Formatter;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i128),
+    Float(f64),
+    Bool(bool),
+    Str(String),
+    Array(Vec<Value>),
+    /// A tuple, where the empty tuple is `()`.
+    Tuple(Vec<Value>),
+    /// `start..end`, with `end` excluded, and the step of `start..end=step`.
+    Range(Box<Value>, Box<Value>, Option<Box<Value>>),
+    /// An instance of a struct, with the fields it inherits.
+    Struct {
+        id: StructId,
+        name: SmolStr,
+        fields: Vec<(SmolStr, Value)>,
+    },
+    Function(FunctionId),
+    Closure(Closure),
+    Builtin(Builtin),
+}
+
+impl Value {
+    pub fn unit() -> Self {
+        Self::Tuple(Vec::new())
+    }
+
+    /// The name of the value's type, for error messages.
+    pub fn type_name(&self) -> SmolStr {
+        match self {
+            Self::Int(_) => "integer".into(),
+            Self::Float(_) => "float".into(),
+            Self::Bool(_) => "bool".into(),
+            Self::Str(_) => "string".into(),
+            Self::Array(_) => "array".into(),
+            Self::Tuple(_) => "tuple".into(),
+            Self::Range(..) => "range".into(),
+            Self::Struct { name, .. } => name.clone(),
+            Self::Function(_) | Self::Closure(_) | Self::Builtin(_) => "function".into(),
+        }
+    }
+
+    /// Writes the value the way it appears inside an array, tuple or struct,
+    /// where strings are quoted.
+    fn fmt_nested(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Str(s) => write!(f, "{:?}", s),
+            _ => write!(f, "{}", self),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Int(n) => write!(f, "{}", n),
+            Self::Float(n) => write!(f, "{:?}", n),
+            Self::Bool(b) => write!(f, "{}", b),
+            Self::Str(s) => write!(f, "{}", s),
+            Self::Array(values) => {
+                write!(f, "[")?;
+                fmt_list(f, values)?;
+                write!(f, "]")
+            }
+            Self::Tuple(values) => {
+                write!(f, "(")?;
+                fmt_list(f, values)?;
+                write!(f, ")")
+            }
+            Self::Range(start, end, None) => write!(f, "{}..{}", start, end),
+            Self::Range(start, end, Some(step)) => write!(f, "{}..{}={}", start, end, step),
+            Self::Struct { name, fields, .. } => {
+                write!(f, "{} {{", name)?;
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    write!(f, "{} {}: ", if i == 0 { "" } else { "," }, name)?;
+                    value.fmt_nested(f)?;
+                }
+                write!(f, " }}")
+            }
+            Self::Function(_) | Self::Builtin(_) => write!(f, "<function>"),
+            Self::Closure(_) => write!(f, "<closure>"),
+        }
+    }
+}
+
+fn fmt_list(f: &mut Formatter<'_>, values: &[Value]) -> fmt::Result {
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        value.fmt_nested(f)?;
+    }
+    Ok(())
+}
+
+/// A closure together with the environment it was created in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Closure {
+    pub(crate) body: BodyId,
+    /// The `Closure` expression.
+    pub(crate) expr: ExprIdx,
+    pub(crate) env: Env,
+}
@@ -0,0 +1,271 @@
+use drip_ast::validation::{self, ValidationError};
+use drip_lexer::{Lexer, Token};
+use drip_parser::error::ParseError;
+use drip_syntax::{SyntaxElement, SyntaxNode};
+use serde_json::{json, Value};
+use text_size::TextRange;
+
+/// Version of the JSON schema produced by this crate.
+///
+/// Bump this whenever a field is renamed, removed or changes meaning,
+/// so consumers can detect output they don't understand.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Serializes everything known about `input`: its tokens, its syntax tree
+/// and all parse and validation diagnostics.
+pub fn document(input: &str) -> Value {
+    let parse = drip_parser::parse(input);
+    let syntax = parse.syntax();
+
+    let diagnostics: Vec<_> = parse
+        .errors()
+        .iter()
+        .map(parse_error)
+        .chain(validation::validate(&syntax).iter().map(validation_error))
+        .collect();
+
+    json!({
+        "version": SCHEMA_VERSION,
+        "tokens": tokens(input),
+        "tree": syntax_node(&syntax),
+        "diagnostics": diagnostics,
+    })
+}
+
+pub fn tokens(input: &str) -> Value {
+    Value::Array(Lexer::new(input).map(|token| self::token(&token)).collect())
+}
+
+pub fn token(token: &Token) -> Value {
+    json!({
+        "kind": format!("{:?}", token.kind),
+        "text": token.text,
+        "range": range(token.range),
+    })
+}
+
+pub fn syntax_node(node: &SyntaxNode) -> Value {
+    let children: Vec<_> = node
+        .children_with_tokens()
+        .map(|element| match element {
+            SyntaxElement::Node(node) => syntax_node(&node),
+            SyntaxElement::Token(token) => json!({
+                "kind": format!("{:?}", token.kind()),
+                "text": token.text(),
+                "range": range(token.text_range()),
+            }),
+        })
+        .collect();
+
+    json!({
+        "kind": format!("{:?}", node.kind()),
+        "range": range(node.text_range()),
+        "children": children,
+    })
+}
+
+pub fn parse_error(error: &ParseError) -> Value {
+    let expected: Vec<_> = error
+        .expected
+        .iter()
+        .map(|kind| format!("{:?}", kind))
+        .collect();
+
+    json!({
+        "source": "parser",
+        "message": error.message(),
+        "range": range(error.range),
+        "expected": expected,
+        "found": error.found.map(|kind| format!("{:?}", kind)),
+    })
+}
+
+pub fn validation_error(error: &ValidationError) -> Value {
+    json!({
+        "source": "validation",
+        "code": format!("{:?}", error.kind()),
+        "message": error.kind().to_string(),
+        "range": range(error.range()),
+    })
+}
+
+fn range(range: TextRange) -> Value {
+    json!({
+        "start": u32::from(range.start()),
+        "end": u32::from(range.end()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use expect_test::{expect, Expect};
+
+    fn check(actual: Value, expected: Expect) {
+        expected.assert_eq(&serde_json::to_string_pretty(&actual).unwrap());
+    }
+
+    #[test]
+    fn serialize_tokens() {
+        check(
+            tokens("a :: 1"),
+            expect![[r#"
+                [
+                  {
+                    "kind": "Ident",
+                    "range": {
+                      "end": 1,
+                      "start": 0
+                    },
+                    "text": "a"
+                  },
+                  {
+                    "kind": "Whitespace",
+                    "range": {
+                      "end": 2,
+                      "start": 1
+                    },
+                    "text": " "
+                  },
+                  {
+                    "kind": "ConstKw",
+                    "range": {
+                      "end": 4,
+                      "start": 2
+                    },
+                    "text": "::"
+                  },
+                  {
+                    "kind": "Whitespace",
+                    "range": {
+                      "end": 5,
+                      "start": 4
+                    },
+                    "text": " "
+                  },
+                  {
+                    "kind": "Number",
+                    "range": {
+                      "end": 6,
+                      "start": 5
+                    },
+                    "text": "1"
+                  }
+                ]"#]],
+        );
+    }
+
+    #[test]
+    fn serialize_syntax_tree() {
+        let parse = drip_parser::parse("-1");
+
+        check(
+            syntax_node(&parse.syntax()),
+            expect![[r#"
+                {
+                  "children": [
+                    {
+                      "children": [
+                        {
+                          "kind": "Minus",
+                          "range": {
+                            "end": 1,
+                            "start": 0
+                          },
+                          "text": "-"
+                        },
+                        {
+                          "children": [
+                            {
+                              "kind": "Number",
+                              "range": {
+                                "end": 2,
+                                "start": 1
+                              },
+                              "text": "1"
+                            }
+                          ],
+                          "kind": "Literal",
+                          "range": {
+                            "end": 2,
+                            "start": 1
+                          }
+                        }
+                      ],
+                      "kind": "PrefixExpr",
+                      "range": {
+                        "end": 2,
+                        "start": 0
+                      }
+                    }
+                  ],
+                  "kind": "Root",
+                  "range": {
+                    "end": 2,
+                    "start": 0
+                  }
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn serialize_parse_error() {
+        check(
+            document("(1")["diagnostics"].clone(),
+            expect![[r#"
+                [
+                  {
+                    "expected": [
+                      "LRoundBracket",
+                      "Dot",
+                      "LSquareBracket",
+                      "Plus",
+                      "Minus",
+                      "Star",
+                      "Slash",
+                      "Equals2",
+                      "NotEquals",
+                      "LAngledBracket",
+                      "LessEquals",
+                      "RAngledBracket",
+                      "GreaterEquals",
+                      "Dot2",
+                      "Comma",
+                      "RRoundBracket"
+                    ],
+                    "found": null,
+                    "message": "expected '(', '.', '[', '+', '-', '*', '/', '==', '!=', '<', '<=', '>', '>=', '..', ',' or ')'",
+                    "range": {
+                      "end": 2,
+                      "start": 1
+                    },
+                    "source": "parser"
+                  }
+                ]"#]],
+        );
+    }
+
+    #[test]
+    fn serialize_validation_error() {
+        check(
+            document("99999999999999999999")["diagnostics"].clone(),
+            expect![[r#"
+                [
+                  {
+                    "code": "NumberLiteralTooLarge",
+                    "message": "number literal is larger than an integer's maximum value, 18446744073709551615",
+                    "range": {
+                      "end": 20,
+                      "start": 0
+                    },
+                    "source": "validation"
+                  }
+                ]"#]],
+        );
+    }
+
+    #[test]
+    fn document_is_versioned() {
+        assert_eq!(document("")["version"], SCHEMA_VERSION);
+    }
+}
@@ -0,0 +1,237 @@
+//! Generated by `cargo run -p drip_codegen`, do not edit by hand.
+
+use logos::Logos;
+use std::fmt::{self, Formatter};
+
+#[derive(Logos, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    #[error]
+    Error,
+    #[regex("//.*")]
+    Comment,
+    #[regex("[ \n]+")]
+    Whitespace,
+    #[regex("_*[/\\p{L}/u][/\\p{L}/u-z0-9_]*")]
+    Ident,
+    #[token("fn")]
+    FnKw,
+    #[token("use")]
+    UseKw,
+    #[token("extern")]
+    ExternKw,
+    #[token("struct")]
+    StructKw,
+    #[token("trait")]
+    TraitKw,
+    #[token("impl")]
+    ImplKw,
+    #[token("if")]
+    IfKw,
+    #[token("else")]
+    ElseKw,
+    #[token("loop")]
+    LoopKw,
+    #[token("while")]
+    WhileKw,
+    #[token("for")]
+    ForKw,
+    #[token("in")]
+    InKw,
+    #[token("break")]
+    BreakKw,
+    #[token("continue")]
+    ContinueKw,
+    #[token("match")]
+    MatchKw,
+    #[token("true")]
+    TrueKw,
+    #[token("false")]
+    FalseKw,
+    #[token("::")]
+    ConstKw,
+    #[token(":=")]
+    VariableKw,
+    #[token("Type")]
+    TypeKw,
+    #[token("self")]
+    SelfVarKw,
+    #[token("Self")]
+    SelfTypeKw,
+    #[regex("([0-9][0-9_]*)?\\.?[0-9][0-9_]*([eE][-+]?[0-9_]+)?")]
+    Number,
+    #[regex("\"[^\"\\n]*\"")]
+    Str,
+    #[token("!")]
+    Bang,
+    #[token("?")]
+    Quest,
+    #[token("+")]
+    Plus,
+    #[token("-")]
+    Minus,
+    #[token("*")]
+    Star,
+    #[token("/")]
+    Slash,
+    #[token("^")]
+    Circumflex,
+    #[token("^^")]
+    Circumflex2,
+    #[token("&")]
+    And,
+    #[token("&&")]
+    And2,
+    #[token("|")]
+    Pipe,
+    #[token("||")]
+    Pipe2,
+    #[token("=")]
+    Equals,
+    #[token("==")]
+    Equals2,
+    #[token("!=")]
+    NotEquals,
+    #[token("<=")]
+    LessEquals,
+    #[token(">=")]
+    GreaterEquals,
+    #[token("+=")]
+    PlusEquals,
+    #[token("-=")]
+    MinusEquals,
+    #[token("*=")]
+    StarEquals,
+    #[token("/=")]
+    SlashEquals,
+    #[token("%")]
+    Percent,
+    #[token("$")]
+    Dollar,
+    #[token("#")]
+    Hashtag,
+    #[token("@")]
+    At,
+    #[token("_")]
+    Underscore,
+    #[token(".")]
+    Dot,
+    #[token("..")]
+    Dot2,
+    #[token("...")]
+    Dot3,
+    #[token(",")]
+    Comma,
+    #[token(":")]
+    Colon,
+    #[token(";")]
+    Semicolon,
+    #[token("\"")]
+    Quote,
+    #[token("'")]
+    SingleQuote,
+    #[token("`")]
+    Grave,
+    #[token("->")]
+    Arrow,
+    #[token("=>")]
+    FatArrow,
+    #[token("<|")]
+    Inherit,
+    #[token("{")]
+    LCurlyBracket,
+    #[token("}")]
+    RCurlyBracket,
+    #[token("(")]
+    LRoundBracket,
+    #[token(")")]
+    RRoundBracket,
+    #[token("<")]
+    LAngledBracket,
+    #[token(">")]
+    RAngledBracket,
+    #[token("[")]
+    LSquareBracket,
+    #[token("]")]
+    RSquareBracket,
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TokenKind::Error => "unrecognized token",
+            TokenKind::Comment => "comment",
+            TokenKind::Whitespace => "whitespace",
+            TokenKind::Ident => "identifier",
+            TokenKind::FnKw => "'fn'",
+            TokenKind::UseKw => "'use'",
+            TokenKind::ExternKw => "'extern'",
+            TokenKind::StructKw => "'struct'",
+            TokenKind::TraitKw => "'trait'",
+            TokenKind::ImplKw => "'impl'",
+            TokenKind::IfKw => "'if'",
+            TokenKind::ElseKw => "'else'",
+            TokenKind::LoopKw => "'loop'",
+            TokenKind::WhileKw => "'while'",
+            TokenKind::ForKw => "'for'",
+            TokenKind::InKw => "'in'",
+            TokenKind::BreakKw => "'break'",
+            TokenKind::ContinueKw => "'continue'",
+            TokenKind::MatchKw => "'match'",
+            TokenKind::TrueKw => "'true'",
+            TokenKind::FalseKw => "'false'",
+            TokenKind::ConstKw => "'::'",
+            TokenKind::VariableKw => "':='",
+            TokenKind::TypeKw => "'Type'",
+            TokenKind::SelfVarKw => "'self'",
+            TokenKind::SelfTypeKw => "'Self'",
+            TokenKind::Number => "number",
+            TokenKind::Str => "string",
+            TokenKind::Bang => "'!'",
+            TokenKind::Quest => "'?'",
+            TokenKind::Plus => "'+'",
+            TokenKind::Minus => "'-'",
+            TokenKind::Star => "'*'",
+            TokenKind::Slash => "'/'",
+            TokenKind::Circumflex => "'^'",
+            TokenKind::Circumflex2 => "'^^'",
+            TokenKind::And => "'&'",
+            TokenKind::And2 => "'&&'",
+            TokenKind::Pipe => "'|'",
+            TokenKind::Pipe2 => "'||'",
+            TokenKind::Equals => "'='",
+            TokenKind::Equals2 => "'=='",
+            TokenKind::NotEquals => "'!='",
+            TokenKind::LessEquals => "'<='",
+            TokenKind::GreaterEquals => "'>='",
+            TokenKind::PlusEquals => "'+='",
+            TokenKind::MinusEquals => "'-='",
+            TokenKind::StarEquals => "'*='",
+            TokenKind::SlashEquals => "'/='",
+            TokenKind::Percent => "'%'",
+            TokenKind::Dollar => "'$'",
+            TokenKind::Hashtag => "'#'",
+            TokenKind::At => "'@'",
+            TokenKind::Underscore => "'_'",
+            TokenKind::Dot => "'.'",
+            TokenKind::Dot2 => "'..'",
+            TokenKind::Dot3 => "'...'",
+            TokenKind::Comma => "','",
+            TokenKind::Colon => "':'",
+            TokenKind::Semicolon => "';'",
+            TokenKind::Quote => "'\"'",
+            TokenKind::SingleQuote => "'''",
+            TokenKind::Grave => "'`'",
+            TokenKind::Arrow => "'->'",
+            TokenKind::FatArrow => "'=>'",
+            TokenKind::Inherit => "'<|'",
+            TokenKind::LCurlyBracket => "'{'",
+            TokenKind::RCurlyBracket => "'}'",
+            TokenKind::LRoundBracket => "'('",
+            TokenKind::RRoundBracket => "')'",
+            TokenKind::LAngledBracket => "'<'",
+            TokenKind::RAngledBracket => "'>'",
+            TokenKind::LSquareBracket => "'['",
+            TokenKind::RSquareBracket => "']'",
+        })
+    }
+}
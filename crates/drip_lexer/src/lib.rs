@@ -1,9 +1,10 @@
+mod generated;
 mod token;
+pub use generated::TokenKind;
 use logos::Logos;
 use std::convert::TryFrom;
 use std::ops::Range;
 use text_size::{TextRange, TextSize};
-pub use token::TokenKind;
 
 pub struct Lexer<'a> {
     inner: logos::Lexer<'a, TokenKind>,
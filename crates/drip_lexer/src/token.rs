@@ -1,119 +1,4 @@
-use logos::Logos;
-use std::fmt::{self, Formatter};
-
-#[derive(Logos, Debug, Copy, Clone, PartialEq, Eq)]
-pub enum TokenKind {
-    #[error]
-    Error,
-
-    #[regex("//.*")]
-    Comment,
-    #[regex("[ \n]+")]
-    Whitespace,
-    #[regex("[/\\p{L}/u][/\\p{L}/u-z0-9_]*")]
-    Ident,
-    #[token("fn")]
-    FnKw,
-
-    #[token("use")]
-    UseKw,
-    #[token("extern")]
-    ExternKw,
-
-    #[token("struct")]
-    StructKw,
-    #[token("trait")]
-    TraitKw,
-    #[token("::")]
-    ConstKw,
-    #[token(":=")]
-    VariableKw,
-
-    #[token("Type")]
-    TypeKw,
-    #[token("self")]
-    SelfVarKw,
-    #[token("Self")]
-    SelfTypeKw,
-
-    #[regex("([0-9][0-9_]*)?\\.?[0-9_]+([eE][-+]?[0-9_]+)?")]
-    Number,
-
-    #[token("!")]
-    Bang,
-    #[token("?")]
-    Quest,
-    #[token("+")]
-    Plus,
-    #[token("-")]
-    Minus,
-    #[token("*")]
-    Star,
-    #[token("/")]
-    Slash,
-    #[token("^")]
-    Circumflex,
-    #[token("^^")]
-    Circumflex2,
-
-    #[token("&")]
-    And,
-    #[token("&&")]
-    And2,
-    #[token("|")]
-    Pipe,
-    #[token("||")]
-    Pipe2,
-
-    #[token("=")]
-    Equals,
-    #[token("==")]
-    Equals2,
-
-    #[token("%")]
-    Percent,
-    #[token("$")]
-    Dollar,
-    #[token("#")]
-    Hashtag,
-    #[token("@")]
-    At,
-    #[token("_")]
-    Underscore,
-    #[token(".")]
-    Dot,
-    #[token(",")]
-    Comma,
-    #[token(":")]
-    Colon,
-    #[token(";")]
-    Semicolon,
-    #[token("\"")]
-    Quote,
-    #[token("\'")]
-    SingleQuote,
-    #[token("`")]
-    Grave,
-    #[token("->")]
-    Arrow,
-    // brackets
-    #[token("{")]
-    LCurlyBracket,
-    #[token("}")]
-    RCurlyBracket,
-    #[token("(")]
-    LRoundBracket,
-    #[token(")")]
-    RRoundBracket,
-    #[token("<")]
-    LAngledBracket,
-    #[token(">")]
-    RAngledBracket,
-    #[token("[")]
-    LSquareBracket,
-    #[token("]")]
-    RSquareBracket,
-}
+use crate::TokenKind;
 
 impl TokenKind {
     pub fn is_trivia(self) -> bool {
@@ -121,63 +6,6 @@ impl TokenKind {
     }
 }
 
-impl fmt::Display for TokenKind {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        f.write_str(match self {
-            TokenKind::Error => "unrecognized token",
-            TokenKind::Comment => "comment",
-            TokenKind::Whitespace => "whitespace",
-            TokenKind::FnKw => "fn",
-            TokenKind::StructKw => "struct",
-            TokenKind::TraitKw => "trait",
-            TokenKind::Ident => "identifier",
-            TokenKind::TypeKw => "type",
-            TokenKind::SelfVarKw => "'self'",
-            TokenKind::SelfTypeKw => "'Self'",
-            TokenKind::ConstKw => "::",
-            TokenKind::VariableKw => ":=",
-            TokenKind::Number => "number",
-            TokenKind::Bang => "!",
-            TokenKind::Quest => "'?'",
-            TokenKind::Plus => "'+'",
-            TokenKind::Minus => "'-'",
-            TokenKind::Star => "'*'",
-            TokenKind::Slash => "'/'",
-            TokenKind::Circumflex => "'^'",
-            TokenKind::Circumflex2 => "'^^'",
-            TokenKind::And => "'&'",
-            TokenKind::And2 => "'&&'",
-            TokenKind::Pipe => "'|'",
-            TokenKind::Pipe2 => "'||'",
-            TokenKind::Equals => "'='",
-            TokenKind::Equals2 => "'=='",
-            TokenKind::Percent => "'%'",
-            TokenKind::Dollar => "'$'",
-            TokenKind::Hashtag => "'#'",
-            TokenKind::At => "'@'",
-            TokenKind::Underscore => "'_'",
-            TokenKind::Dot => "'.'",
-            TokenKind::Comma => "','",
-            TokenKind::Colon => "':'",
-            TokenKind::Semicolon => "';'",
-            TokenKind::Quote => "'\"'",
-            TokenKind::SingleQuote => "'''",
-            TokenKind::Grave => "'`'",
-            TokenKind::Arrow => "->",
-            TokenKind::LCurlyBracket => "'{'",
-            TokenKind::RCurlyBracket => "'}'",
-            TokenKind::LRoundBracket => "'('",
-            TokenKind::RRoundBracket => "')'",
-            TokenKind::LAngledBracket => "'<'",
-            TokenKind::RAngledBracket => "'>'",
-            TokenKind::LSquareBracket => "'['",
-            TokenKind::RSquareBracket => "']'",
-            TokenKind::UseKw => "use",
-            TokenKind::ExternKw => "extern",
-        })
-    }
-}
-
 #[cfg(test)]
 mod lexer_tests {
     use crate::*;
@@ -210,6 +38,8 @@ mod lexer_tests {
         assert("안녕하세요", TokenKind::Ident);
         assert("안녕하세요3", TokenKind::Ident);
         assert("Gerät3是", TokenKind::Ident);
+        assert("_private", TokenKind::Ident);
+        assert("__init2", TokenKind::Ident);
     }
 
     #[test]
@@ -242,6 +72,66 @@ mod lexer_tests {
         assert("Self", TokenKind::SelfTypeKw);
     }
 
+    #[test]
+    fn impl_kw() {
+        assert("impl", TokenKind::ImplKw);
+    }
+
+    #[test]
+    fn if_kw() {
+        assert("if", TokenKind::IfKw);
+    }
+
+    #[test]
+    fn else_kw() {
+        assert("else", TokenKind::ElseKw);
+    }
+
+    #[test]
+    fn loop_kw() {
+        assert("loop", TokenKind::LoopKw);
+    }
+
+    #[test]
+    fn while_kw() {
+        assert("while", TokenKind::WhileKw);
+    }
+
+    #[test]
+    fn for_kw() {
+        assert("for", TokenKind::ForKw);
+    }
+
+    #[test]
+    fn in_kw() {
+        assert("in", TokenKind::InKw);
+    }
+
+    #[test]
+    fn break_kw() {
+        assert("break", TokenKind::BreakKw);
+    }
+
+    #[test]
+    fn continue_kw() {
+        assert("continue", TokenKind::ContinueKw);
+    }
+
+    #[test]
+    fn match_kw() {
+        assert("match", TokenKind::MatchKw);
+    }
+
+    #[test]
+    fn true_kw() {
+        assert("true", TokenKind::TrueKw);
+    }
+
+    #[test]
+    fn false_kw() {
+        assert("false", TokenKind::FalseKw);
+    }
+
     #[test]
     fn number() {
         assert("123", TokenKind::Number);
@@ -253,6 +143,13 @@ mod lexer_tests {
         assert("2_____4", TokenKind::Number);
         assert("2_490.0", TokenKind::Number);
         assert("2_490.023_423_7", TokenKind::Number);
+        assert("1_000", TokenKind::Number);
+        assert("0.5_0", TokenKind::Number);
+    }
+
+    #[test]
+    fn str() {
+        assert("\"hello world\"", TokenKind::Str);
     }
 
     #[test]
@@ -325,6 +222,41 @@ mod lexer_tests {
         assert("==", TokenKind::Equals2);
     }
 
+    #[test]
+    fn not_equals() {
+        assert("!=", TokenKind::NotEquals);
+    }
+
+    #[test]
+    fn less_equals() {
+        assert("<=", TokenKind::LessEquals);
+    }
+
+    #[test]
+    fn greater_equals() {
+        assert(">=", TokenKind::GreaterEquals);
+    }
+
+    #[test]
+    fn plus_equals() {
+        assert("+=", TokenKind::PlusEquals);
+    }
+
+    #[test]
+    fn minus_equals() {
+        assert("-=", TokenKind::MinusEquals);
+    }
+
+    #[test]
+    fn star_equals() {
+        assert("*=", TokenKind::StarEquals);
+    }
+
+    #[test]
+    fn slash_equals() {
+        assert("/=", TokenKind::SlashEquals);
+    }
+
     #[test]
     fn percent() {
         assert("%", TokenKind::Percent);
@@ -355,6 +287,16 @@ mod lexer_tests {
         assert(".", TokenKind::Dot);
     }
 
+    #[test]
+    fn dot2() {
+        assert("..", TokenKind::Dot2);
+    }
+
+    #[test]
+    fn dot3() {
+        assert("...", TokenKind::Dot3);
+    }
+
     #[test]
     fn comma() {
         assert(",", TokenKind::Comma);
@@ -385,6 +327,16 @@ mod lexer_tests {
         assert("`", TokenKind::Grave);
     }
 
+    #[test]
+    fn fat_arrow() {
+        assert("=>", TokenKind::FatArrow);
+    }
+
+    #[test]
+    fn inherit() {
+        assert("<|", TokenKind::Inherit);
+    }
+
     #[test]
     fn l_round_bracket() {
         assert("(", TokenKind::LRoundBracket);
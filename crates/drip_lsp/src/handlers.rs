@@ -0,0 +1,274 @@
+//! Answering requests about open documents.
+
+use crate::line_index::LineIndex;
+use crate::{RequestError, Server};
+use drip_hir::db::{FileId, SourceDatabase};
+use drip_hir::resolve::Resolution;
+use drip_hir::semantics::{self, Definition};
+use drip_hir::Item;
+use drip_ide::CompletionKind;
+use drip_lexer::{Lexer, TokenKind};
+use drip_syntax::{SyntaxKind, SyntaxNode};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionParams, CompletionResponse,
+    DocumentFormattingParams, DocumentSymbol, DocumentSymbolParams, DocumentSymbolResponse,
+    GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents, HoverParams,
+    InsertTextFormat, Location, MarkupContent, MarkupKind, ReferenceParams, RenameParams,
+    SemanticTokens, SemanticTokensParams, SemanticTokensResult, SymbolKind,
+    TextDocumentPositionParams, TextEdit, WorkspaceEdit,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use text_size::TextSize;
+
+type Result<T> = std::result::Result<T, RequestError>;
+
+/// The file, text and offset that `position` refers to.
+fn position(
+    server: &Server,
+    position: &TextDocumentPositionParams,
+) -> Result<(FileId, Arc<str>, TextSize)> {
+    let file = server.file(&position.text_document.uri)?;
+    let text = server.db.file_text(file);
+    let offset = LineIndex::new(&text).offset(position.position);
+    Ok((file, text, offset))
+}
+
+pub(crate) fn hover(server: &Server, params: HoverParams) -> Result<Option<Hover>> {
+    let (file, text, offset) = position(server, &params.text_document_position_params)?;
+    let db = &server.db;
+    let index = LineIndex::new(&text);
+
+    let (value, range) = match semantics::name_at(db, file, offset) {
+        Some(name) => {
+            let definition = name.definition;
+            let ident = semantics::definition_name(db, file, definition);
+            let value = match (definition, semantics::definition_ty(db, file, definition)) {
+                (Definition::Resolved(Resolution::Item(Item::Struct(_))), _) => {
+                    format!("{} :: struct", ident)
+                }
+                (Definition::Resolved(Resolution::Item(Item::Trait(_))), _) => {
+                    format!("{} :: trait", ident)
+                }
+                (Definition::Resolved(Resolution::Item(_) | Resolution::Builtin(_)), Some(ty)) => {
+                    format!("{} :: {}", ident, ty)
+                }
+                (_, Some(ty)) => format!("{}: {}", ident, ty),
+                (_, None) => return Ok(None),
+            };
+            (value, Some(index.range(name.range)))
+        }
+        None => match semantics::expr_at(db, file, offset) {
+            Some((body, expr)) => (semantics::expr_ty(db, file, body, expr).to_string(), None),
+            None => return Ok(None),
+        },
+    };
+
+    Ok(Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: format!("```drip\n{}\n```", value),
+        }),
+        range,
+    }))
+}
+
+pub(crate) fn completion(
+    server: &Server,
+    params: CompletionParams,
+) -> Result<Option<CompletionResponse>> {
+    let (file, _, offset) = position(server, &params.text_document_position)?;
+    let items = drip_ide::completions(&server.db, file, offset)
+        .into_iter()
+        .map(|completion| CompletionItem {
+            label: completion.label.to_string(),
+            kind: Some(match completion.kind {
+                CompletionKind::Variable => CompletionItemKind::VARIABLE,
+                CompletionKind::Function => CompletionItemKind::FUNCTION,
+                CompletionKind::Method => CompletionItemKind::METHOD,
+                CompletionKind::Field => CompletionItemKind::FIELD,
+                CompletionKind::Struct => CompletionItemKind::STRUCT,
+                CompletionKind::Trait => CompletionItemKind::INTERFACE,
+                CompletionKind::Keyword => CompletionItemKind::KEYWORD,
+                CompletionKind::Snippet => CompletionItemKind::SNIPPET,
+            }),
+            detail: completion.detail,
+            insert_text: Some(completion.insert),
+            insert_text_format: Some(InsertTextFormat::SNIPPET),
+            ..CompletionItem::default()
+        })
+        .collect();
+    Ok(Some(CompletionResponse::Array(items)))
+}
+
+pub(crate) fn definition(
+    server: &Server,
+    params: GotoDefinitionParams,
+) -> Result<Option<GotoDefinitionResponse>> {
+    let params = params.text_document_position_params;
+    let (file, text, offset) = position(server, &params)?;
+    let db = &server.db;
+
+    let range = semantics::name_at(db, file, offset)
+        .and_then(|name| semantics::definition_range(db, file, name.definition));
+    Ok(range.map(|range| {
+        GotoDefinitionResponse::Scalar(Location {
+            uri: params.text_document.uri,
+            range: LineIndex::new(&text).range(range),
+        })
+    }))
+}
+
+pub(crate) fn references(
+    server: &Server,
+    params: ReferenceParams,
+) -> Result<Option<Vec<Location>>> {
+    let (file, text, offset) = position(server, &params.text_document_position)?;
+    let uri = params.text_document_position.text_document.uri;
+    let include_declaration = params.context.include_declaration;
+    let db = &server.db;
+    let index = LineIndex::new(&text);
+
+    let name = match semantics::name_at(db, file, offset) {
+        Some(name) => name,
+        None => return Ok(None),
+    };
+    let locations = semantics::references(db, file, name.definition)
+        .into_iter()
+        .filter(|name| include_declaration || !name.is_definition)
+        .map(|name| Location {
+            uri: uri.clone(),
+            range: index.range(name.range),
+        })
+        .collect();
+    Ok(Some(locations))
+}
+
+pub(crate) fn symbols(
+    server: &Server,
+    params: DocumentSymbolParams,
+) -> Result<Option<DocumentSymbolResponse>> {
+    let file = server.file(&params.text_document.uri)?;
+    let text = server.db.file_text(file);
+    let root = server.db.parse(file).syntax();
+    let symbols = document_symbols(&root, &LineIndex::new(&text));
+    Ok(Some(DocumentSymbolResponse::Nested(symbols)))
+}
+
+/// The functions, structs and traits defined in `node`, with the ones
+/// defined inside them as their children.
+fn document_symbols(node: &SyntaxNode, index: &LineIndex) -> Vec<DocumentSymbol> {
+    let mut symbols = Vec::new();
+    for child in node.children() {
+        let kind = match child.kind() {
+            SyntaxKind::FnDef => match node.kind() {
+                SyntaxKind::ImplItemListDef | SyntaxKind::TraitFnListDef => SymbolKind::METHOD,
+                _ => SymbolKind::FUNCTION,
+            },
+            SyntaxKind::StructDef => SymbolKind::STRUCT,
+            SyntaxKind::StructFieldDef => SymbolKind::FIELD,
+            SyntaxKind::TraitDef => SymbolKind::INTERFACE,
+            // the functions of an impl are listed where the impl is
+            _ => {
+                symbols.extend(document_symbols(&child, index));
+                continue;
+            }
+        };
+        let name = child
+            .children_with_tokens()
+            .filter_map(|element| element.into_token())
+            .find(|token| token.kind() == SyntaxKind::Ident);
+        let name = match name {
+            Some(name) => name,
+            None => continue,
+        };
+
+        #[allow(deprecated)]
+        symbols.push(DocumentSymbol {
+            name: name.text().to_string(),
+            detail: None,
+            kind,
+            tags: None,
+            deprecated: None,
+            range: index.range(child.text_range()),
+            selection_range: index.range(name.text_range()),
+            children: Some(document_symbols(&child, index)).filter(|c| !c.is_empty()),
+        });
+    }
+    symbols
+}
+
+pub(crate) fn formatting(
+    server: &Server,
+    params: DocumentFormattingParams,
+) -> Result<Option<Vec<TextEdit>>> {
+    let file = server.file(&params.text_document.uri)?;
+    let text = server.db.file_text(file);
+    let index = LineIndex::new(&text);
+
+    // formatting a file with syntax errors does nothing
+    let formatted = match drip_fmt::format(&text) {
+        Some(formatted) => formatted,
+        None => return Ok(None),
+    };
+    let whole = index.range(text_size::TextRange::up_to(TextSize::of(&*text)));
+    Ok(Some(vec![TextEdit::new(whole, formatted)]))
+}
+
+pub(crate) fn rename(server: &Server, params: RenameParams) -> Result<Option<WorkspaceEdit>> {
+    let (file, text, offset) = position(server, &params.text_document_position)?;
+    let uri = params.text_document_position.text_document.uri;
+    let new_name = params.new_name;
+    let db = &server.db;
+    let index = LineIndex::new(&text);
+
+    let name = match semantics::name_at(db, file, offset) {
+        Some(name) => name,
+        None => return Ok(None),
+    };
+    if let Definition::Resolved(Resolution::Builtin(_)) = name.definition {
+        return Err(RequestError::invalid("builtins can't be renamed"));
+    }
+    let mut tokens = Lexer::new(&new_name);
+    let is_ident = matches!(tokens.next(), Some(token) if token.kind == TokenKind::Ident)
+        && tokens.next().is_none();
+    if !is_ident {
+        return Err(RequestError::invalid(format!(
+            "`{}` isn't a name",
+            new_name
+        )));
+    }
+
+    // a shorthand like `Vec2 { x }` is written out, so that only the half
+    // being renamed changes
+    let names = semantics::names(db, file);
+    let edits = semantics::references(db, file, name.definition)
+        .into_iter()
+        .map(|name| {
+            let old_name = &text[name.range];
+            let new_text = match name.definition {
+                _ if !semantics::is_shorthand(&names, &name) => new_name.clone(),
+                Definition::Field { .. } => format!("{}: {}", new_name, old_name),
+                Definition::Resolved(_) => format!("{}: {}", old_name, new_name),
+            };
+            TextEdit::new(index.range(name.range), new_text)
+        })
+        .collect();
+    let mut changes = HashMap::new();
+    changes.insert(uri, edits);
+    Ok(Some(WorkspaceEdit::new(changes)))
+}
+
+pub(crate) fn semantic_tokens(
+    server: &Server,
+    params: SemanticTokensParams,
+) -> Result<Option<SemanticTokensResult>> {
+    let file = server.file(&params.text_document.uri)?;
+    let text = server.db.file_text(file);
+    let highlights = drip_ide::highlight(&server.db, file, true);
+    let data = crate::semantic_tokens::semantic_tokens(&text, &highlights, &LineIndex::new(&text));
+    Ok(Some(SemanticTokensResult::Tokens(SemanticTokens {
+        result_id: None,
+        data,
+    })))
+}
@@ -0,0 +1,288 @@
+//! A language server for Drip, which speaks the Language Server Protocol over
+//! standard input and output.
+//!
+//! Every open document is a file in a [`Database`], which is updated with the
+//! whole text of the document whenever it changes. Diagnostics are published
+//! after every change, and requests are answered from the database:
+//!
+//! - hovering shows the type of the name or expression under the cursor
+//! - going to the definition, finding references and renaming use
+//!   [`drip_hir::semantics`]
+//! - document symbols are the functions, structs and traits of the file
+//! - completion uses [`drip_ide`]
+//! - formatting uses [`drip_fmt`]
+//! - semantic tokens come from [`drip_ide::highlight`]
+
+mod handlers;
+mod line_index;
+mod semantic_tokens;
+
+use drip_hir::db::{FileId, HirDatabase, SourceDatabase};
+use drip_hir::Database;
+use line_index::LineIndex;
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{self as notifications, Notification as _};
+use lsp_types::request::{self as requests, Request as _};
+use lsp_types::{
+    CompletionOptions, DiagnosticRelatedInformation, DiagnosticSeverity, HoverProviderCapability,
+    Location, OneOf, PublishDiagnosticsParams, SemanticTokensFullOptions, SemanticTokensOptions,
+    SemanticTokensServerCapabilities, ServerCapabilities, TextDocumentSyncCapability,
+    TextDocumentSyncKind, Url,
+};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+
+pub type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync>>;
+
+/// Serves a client over standard input and output until it exits.
+pub fn stdio() -> Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+    run(&connection)?;
+    // the writer thread only stops once the connection is gone
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+/// Serves a client over `connection`, from the initialization to the
+/// shutdown.
+pub fn run(connection: &Connection) -> Result<()> {
+    let capabilities = serde_json::to_value(capabilities())?;
+    connection.initialize(capabilities)?;
+
+    let mut server = Server {
+        db: Database::default(),
+        files: HashMap::new(),
+        next_file: 0,
+    };
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    return Ok(());
+                }
+                let response = server.request(request);
+                connection.sender.send(response.into())?;
+            }
+            Message::Notification(notification) => {
+                for notification in server.notification(notification) {
+                    connection.sender.send(notification.into())?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+    Ok(())
+}
+
+fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec![".".to_string(), ":".to_string()]),
+            ..CompletionOptions::default()
+        }),
+        definition_provider: Some(OneOf::Left(true)),
+        references_provider: Some(OneOf::Left(true)),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        document_formatting_provider: Some(OneOf::Left(true)),
+        rename_provider: Some(OneOf::Left(true)),
+        semantic_tokens_provider: Some(SemanticTokensServerCapabilities::SemanticTokensOptions(
+            SemanticTokensOptions {
+                legend: semantic_tokens::legend(),
+                full: Some(SemanticTokensFullOptions::Bool(true)),
+                ..SemanticTokensOptions::default()
+            },
+        )),
+        ..ServerCapabilities::default()
+    }
+}
+
+pub(crate) struct Server {
+    pub(crate) db: Database,
+    /// The open documents.
+    files: HashMap<Url, FileId>,
+    next_file: u32,
+}
+
+/// The error a request is answered with.
+pub(crate) struct RequestError {
+    code: ErrorCode,
+    message: String,
+}
+
+impl RequestError {
+    pub(crate) fn invalid(message: impl Into<String>) -> Self {
+        Self {
+            code: ErrorCode::InvalidRequest,
+            message: message.into(),
+        }
+    }
+}
+
+impl Server {
+    /// The file of the open document at `url`.
+    pub(crate) fn file(&self, url: &Url) -> std::result::Result<FileId, RequestError> {
+        self.files
+            .get(url)
+            .copied()
+            .ok_or_else(|| RequestError::invalid(format!("`{}` isn't open", url)))
+    }
+
+    fn request(&mut self, request: Request) -> Response {
+        let Request { id, method, params } = request;
+        let result = match method.as_str() {
+            requests::HoverRequest::METHOD => respond(params, handlers::hover, self),
+            requests::Completion::METHOD => respond(params, handlers::completion, self),
+            requests::GotoDefinition::METHOD => respond(params, handlers::definition, self),
+            requests::References::METHOD => respond(params, handlers::references, self),
+            requests::DocumentSymbolRequest::METHOD => respond(params, handlers::symbols, self),
+            requests::Formatting::METHOD => respond(params, handlers::formatting, self),
+            requests::Rename::METHOD => respond(params, handlers::rename, self),
+            requests::SemanticTokensFullRequest::METHOD => {
+                respond(params, handlers::semantic_tokens, self)
+            }
+            _ => Err(RequestError {
+                code: ErrorCode::MethodNotFound,
+                message: format!("unknown request `{}`", method),
+            }),
+        };
+
+        match result {
+            Ok(result) => Response::new_ok(id, result),
+            Err(error) => Response::new_err(id, error.code as i32, error.message),
+        }
+    }
+
+    /// Handles `notification`, returning the notifications to send back.
+    ///
+    /// A notification can't be answered with an error, so one with params
+    /// that don't parse is logged and ignored.
+    fn notification(&mut self, notification: Notification) -> Vec<Notification> {
+        let Notification { method, params } = notification;
+        let (url, text) = match method.as_str() {
+            notifications::DidOpenTextDocument::METHOD => {
+                let params: lsp_types::DidOpenTextDocumentParams =
+                    match parse_params(&method, params) {
+                        Some(params) => params,
+                        None => return Vec::new(),
+                    };
+                (params.text_document.uri, Some(params.text_document.text))
+            }
+            notifications::DidChangeTextDocument::METHOD => {
+                let params: lsp_types::DidChangeTextDocumentParams =
+                    match parse_params(&method, params) {
+                        Some(params) => params,
+                        None => return Vec::new(),
+                    };
+                // the whole text is sent every time, so the last change has it
+                let text = params.content_changes.into_iter().last().map(|c| c.text);
+                (params.text_document.uri, text)
+            }
+            notifications::DidCloseTextDocument::METHOD => {
+                let params: lsp_types::DidCloseTextDocumentParams =
+                    match parse_params(&method, params) {
+                        Some(params) => params,
+                        None => return Vec::new(),
+                    };
+                (params.text_document.uri, None)
+            }
+            _ => return Vec::new(),
+        };
+
+        let file = match (text, self.files.get(&url)) {
+            (Some(text), Some(file)) => {
+                self.db.set_file_text(*file, text.into());
+                *file
+            }
+            (Some(text), None) => {
+                let file = FileId(self.next_file);
+                self.next_file += 1;
+                self.db.set_file_text(file, text.into());
+                self.files.insert(url.clone(), file);
+                file
+            }
+            // a closed document has no diagnostics anymore
+            (None, _) => {
+                self.files.remove(&url);
+                return vec![publish_diagnostics(url, Vec::new())];
+            }
+        };
+
+        let text = self.db.file_text(file);
+        let index = LineIndex::new(&text);
+        let diagnostics = self
+            .db
+            .diagnostics(file)
+            .iter()
+            .map(|diagnostic| lsp_types::Diagnostic {
+                range: index.range(diagnostic.range),
+                severity: Some(match diagnostic.severity {
+                    drip_hir::flow::Severity::Error => DiagnosticSeverity::ERROR,
+                    drip_hir::flow::Severity::Warning => DiagnosticSeverity::WARNING,
+                }),
+                source: Some("drip".to_string()),
+                message: diagnostic.message.clone(),
+                related_information: (!diagnostic.related.is_empty()).then(|| {
+                    diagnostic
+                        .related
+                        .iter()
+                        .map(|(range, note)| DiagnosticRelatedInformation {
+                            location: Location {
+                                uri: url.clone(),
+                                range: index.range(*range),
+                            },
+                            message: note.clone(),
+                        })
+                        .collect()
+                }),
+                ..lsp_types::Diagnostic::default()
+            })
+            .collect();
+        vec![publish_diagnostics(url, diagnostics)]
+    }
+}
+
+/// Parses the `params` of a `method` notification, logging them to standard
+/// error if they don't parse.
+fn parse_params<P: serde::de::DeserializeOwned>(method: &str, params: Value) -> Option<P> {
+    match serde_json::from_value(params) {
+        Ok(params) => Some(params),
+        Err(error) => {
+            eprintln!("ignoring a malformed `{}` notification: {}", method, error);
+            None
+        }
+    }
+}
+
+fn publish_diagnostics(uri: Url, diagnostics: Vec<lsp_types::Diagnostic>) -> Notification {
+    let params = PublishDiagnosticsParams {
+        uri,
+        diagnostics,
+        version: None,
+    };
+    Notification::new(
+        notifications::PublishDiagnostics::METHOD.to_string(),
+        params,
+    )
+}
+
+/// Answers a request with `handler`, after parsing its `params`.
+fn respond<P, R>(
+    params: Value,
+    handler: fn(&Server, P) -> std::result::Result<R, RequestError>,
+    server: &Server,
+) -> std::result::Result<Value, RequestError>
+where
+    P: serde::de::DeserializeOwned,
+    R: serde::Serialize,
+{
+    let params = serde_json::from_value(params).map_err(|error| RequestError {
+        code: ErrorCode::InvalidParams,
+        message: error.to_string(),
+    })?;
+    let result = handler(server, params)?;
+    Ok(serde_json::to_value(result).unwrap())
+}
@@ -0,0 +1,88 @@
+//! Converting between offsets into a file and the line and UTF-16 column
+//! positions the protocol uses.
+
+use lsp_types::{Position, Range};
+use std::convert::TryFrom;
+use text_size::{TextRange, TextSize};
+
+pub(crate) struct LineIndex<'a> {
+    text: &'a str,
+    /// Where every line starts.
+    starts: Vec<TextSize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub(crate) fn new(text: &'a str) -> Self {
+        let newlines = text.match_indices('\n').map(|(i, _)| to_size(i + 1));
+        Self {
+            text,
+            starts: std::iter::once(TextSize::from(0)).chain(newlines).collect(),
+        }
+    }
+
+    pub(crate) fn position(&self, offset: TextSize) -> Position {
+        let offset = offset.min(TextSize::of(self.text));
+        let line = self.starts.partition_point(|start| *start <= offset) - 1;
+        let before = &self.text[TextRange::new(self.starts[line], offset)];
+        Position::new(line as u32, before.encode_utf16().count() as u32)
+    }
+
+    pub(crate) fn range(&self, range: TextRange) -> Range {
+        Range::new(self.position(range.start()), self.position(range.end()))
+    }
+
+    /// The offset of `position`, which is clamped to the end of its line if
+    /// it's past it, and to the end of the file if the line is.
+    pub(crate) fn offset(&self, position: Position) -> TextSize {
+        let start = match self.starts.get(position.line as usize) {
+            Some(start) => *start,
+            None => return TextSize::of(self.text),
+        };
+        let line = self.text[usize::from(start)..]
+            .split('\n')
+            .next()
+            .unwrap_or("");
+
+        let mut column = 0;
+        let mut offset = start;
+        for c in line.chars() {
+            if column >= position.character as usize {
+                break;
+            }
+            column += c.len_utf16();
+            offset += TextSize::of(c);
+        }
+        offset
+    }
+}
+
+fn to_size(offset: usize) -> TextSize {
+    TextSize::try_from(offset).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_offsets_and_positions() {
+        let text = "a :: 1\nb := \"𝄞é\" + a\n";
+        let index = LineIndex::new(text);
+        let position = |offset: u32| {
+            let position = index.position(offset.into());
+            (position.line, position.character)
+        };
+        assert_eq!(position(0), (0, 0));
+        assert_eq!(position(6), (0, 6));
+        assert_eq!(position(7), (1, 0));
+        // `𝄞` is two UTF-16 code units and four bytes
+        assert_eq!(position(17), (1, 8));
+        assert_eq!(position(100), (2, 0));
+
+        let offset = |line, character| u32::from(index.offset(Position::new(line, character)));
+        assert_eq!(offset(1, 8), 17);
+        assert_eq!(offset(1, 7), 17, "the middle of a character rounds up");
+        assert_eq!(offset(0, 99), 6);
+        assert_eq!(offset(9, 0), text.len() as u32);
+    }
+}
@@ -0,0 +1,3 @@
+fn main() -> drip_lsp::Result<()> {
+    drip_lsp::stdio()
+}
@@ -0,0 +1,157 @@
+//! Encoding the highlighted tokens of [`drip_ide::highlight`] as semantic
+//! tokens.
+
+use crate::line_index::LineIndex;
+use drip_ide::{Highlight, HighlightTag};
+use lsp_types::{SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokensLegend};
+
+/// The token types, in the order their indices refer to them.
+const TYPES: [SemanticTokenType; 13] = [
+    SemanticTokenType::KEYWORD,
+    SemanticTokenType::NUMBER,
+    SemanticTokenType::STRING,
+    SemanticTokenType::COMMENT,
+    SemanticTokenType::OPERATOR,
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::PARAMETER,
+    SemanticTokenType::PROPERTY,
+    SemanticTokenType::FUNCTION,
+    SemanticTokenType::METHOD,
+    SemanticTokenType::STRUCT,
+    SemanticTokenType::INTERFACE,
+    SemanticTokenType::TYPE,
+];
+
+/// The token modifiers, in the order of their bits.
+const MODIFIERS: [&str; 2] = ["readonly", "mutable"];
+
+pub(crate) fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: TYPES.to_vec(),
+        token_modifiers: MODIFIERS
+            .iter()
+            .map(|modifier| SemanticTokenModifier::new(modifier))
+            .collect(),
+    }
+}
+
+/// `highlights` in `text`, encoded relative to each other.
+pub(crate) fn semantic_tokens(
+    text: &str,
+    highlights: &[Highlight],
+    index: &LineIndex,
+) -> Vec<SemanticToken> {
+    let mut tokens = Vec::new();
+    let (mut line, mut column) = (0, 0);
+    for highlight in highlights {
+        let (ty, modifier) = token_type(highlight.tag);
+        let start = index.position(highlight.range.start());
+        let length = text[highlight.range].encode_utf16().count() as u32;
+        if start.line != line {
+            column = 0;
+        }
+        tokens.push(SemanticToken {
+            delta_line: start.line - line,
+            delta_start: start.character - column,
+            length,
+            token_type: TYPES.iter().position(|t| *t == ty).unwrap() as u32,
+            token_modifiers_bitset: modifier.map_or(0, |modifier| {
+                let bit = MODIFIERS.iter().position(|m| *m == modifier).unwrap();
+                1 << bit
+            }),
+        });
+        line = start.line;
+        column = start.character;
+    }
+    tokens
+}
+
+fn token_type(tag: HighlightTag) -> (SemanticTokenType, Option<&'static str>) {
+    let ty = match tag {
+        HighlightTag::Keyword => SemanticTokenType::KEYWORD,
+        HighlightTag::Number => SemanticTokenType::NUMBER,
+        HighlightTag::String => SemanticTokenType::STRING,
+        HighlightTag::Comment => SemanticTokenType::COMMENT,
+        HighlightTag::Operator => SemanticTokenType::OPERATOR,
+        HighlightTag::Variable => SemanticTokenType::VARIABLE,
+        HighlightTag::Constant => return (SemanticTokenType::VARIABLE, Some("readonly")),
+        HighlightTag::Mutable => return (SemanticTokenType::VARIABLE, Some("mutable")),
+        HighlightTag::Parameter => SemanticTokenType::PARAMETER,
+        HighlightTag::Field => SemanticTokenType::PROPERTY,
+        HighlightTag::Function => SemanticTokenType::FUNCTION,
+        HighlightTag::Method => SemanticTokenType::METHOD,
+        HighlightTag::Struct => SemanticTokenType::STRUCT,
+        HighlightTag::Trait => SemanticTokenType::INTERFACE,
+        HighlightTag::Type => SemanticTokenType::TYPE,
+    };
+    (ty, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use drip_hir::db::{FileId, SourceDatabase};
+    use drip_hir::Database;
+
+    #[test]
+    fn encode_tokens() {
+        let text =
+            "// sum\nadd :: (a: i32) { a + 1 }\nP :: struct { x: f32 }\np := P { x: 1.5 }\np.x";
+        let mut db = Database::default();
+        db.set_file_text(FileId(0), text.into());
+        let highlights = drip_ide::highlight(&db, FileId(0), true);
+        let index = LineIndex::new(text);
+
+        // undo the relative encoding
+        let (mut line, mut column) = (0, 0);
+        let tokens: Vec<_> = semantic_tokens(text, &highlights, &index)
+            .into_iter()
+            .map(|token| {
+                if token.delta_line > 0 {
+                    column = 0;
+                }
+                line += token.delta_line;
+                column += token.delta_start;
+                let line_text = text.lines().nth(line as usize).unwrap();
+                let start = line_text
+                    .char_indices()
+                    .nth(column as usize)
+                    .map_or(line_text.len(), |(i, _)| i);
+                let token_text = &line_text[start..start + token.length as usize];
+                let mut token_type = TYPES[token.token_type as usize].as_str().to_string();
+                for (bit, modifier) in MODIFIERS.iter().enumerate() {
+                    if token.token_modifiers_bitset & 1 << bit != 0 {
+                        token_type = format!("{} {}", modifier, token_type);
+                    }
+                }
+                format!("{} {}", token_text, token_type)
+            })
+            .collect();
+
+        assert_eq!(
+            tokens,
+            [
+                "// sum comment",
+                "add function",
+                ":: operator",
+                "a parameter",
+                "i32 type",
+                "a parameter",
+                "+ operator",
+                "1 number",
+                "P struct",
+                ":: operator",
+                "struct keyword",
+                "x property",
+                "f32 type",
+                "p mutable variable",
+                ":= operator",
+                "P struct",
+                "x property",
+                "1.5 number",
+                "p mutable variable",
+                "x property",
+            ]
+        );
+    }
+}
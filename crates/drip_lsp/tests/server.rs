@@ -0,0 +1,418 @@
+//! Talks to the server over its standard input and output, the way an editor
+//! would.
+
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+const URI: &str = "file:///main.drip";
+
+struct Client {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: u64,
+}
+
+impl Client {
+    /// Starts the server and initializes it.
+    fn start() -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_drip_lsp"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        let mut client = Self {
+            child,
+            stdin,
+            stdout,
+            next_id: 0,
+        };
+
+        let result = client.request("initialize", json!({ "capabilities": {} }));
+        assert!(result["capabilities"]["hoverProvider"].as_bool().unwrap());
+        client.notify("initialized", json!({}));
+        client
+    }
+
+    fn send(&mut self, message: Value) {
+        let message = message.to_string();
+        write!(
+            self.stdin,
+            "Content-Length: {}\r\n\r\n{}",
+            message.len(),
+            message
+        )
+        .unwrap();
+        self.stdin.flush().unwrap();
+    }
+
+    fn receive(&mut self) -> Value {
+        let mut length = 0;
+        loop {
+            let mut header = String::new();
+            self.stdout.read_line(&mut header).unwrap();
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some(value) = header.strip_prefix("Content-Length: ") {
+                length = value.parse().unwrap();
+            }
+        }
+        let mut content = vec![0; length];
+        self.stdout.read_exact(&mut content).unwrap();
+        serde_json::from_slice(&content).unwrap()
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+    }
+
+    /// Sends a request and waits for its result, skipping notifications.
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        self.next_id += 1;
+        let id = self.next_id;
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }));
+        loop {
+            let message = self.receive();
+            if message["id"] == id {
+                if message.get("error").is_some() {
+                    return message["error"].clone();
+                }
+                return message["result"].clone();
+            }
+        }
+    }
+
+    /// Opens the document at [`URI`], and returns the diagnostics for it.
+    fn open(&mut self, text: &str) -> Value {
+        self.notify(
+            "textDocument/didOpen",
+            json!({
+                "textDocument": { "uri": URI, "languageId": "drip", "version": 0, "text": text }
+            }),
+        );
+        self.diagnostics()
+    }
+
+    fn change(&mut self, text: &str) -> Value {
+        self.notify(
+            "textDocument/didChange",
+            json!({
+                "textDocument": { "uri": URI, "version": 1 },
+                "contentChanges": [{ "text": text }]
+            }),
+        );
+        self.diagnostics()
+    }
+
+    fn diagnostics(&mut self) -> Value {
+        let message = self.receive();
+        assert_eq!(message["method"], "textDocument/publishDiagnostics");
+        assert_eq!(message["params"]["uri"], URI);
+        message["params"]["diagnostics"].clone()
+    }
+
+    fn at(&mut self, method: &str, line: u32, character: u32) -> Value {
+        self.request(
+            method,
+            json!({
+                "textDocument": { "uri": URI },
+                "position": { "line": line, "character": character },
+                "context": { "includeDeclaration": true }
+            }),
+        )
+    }
+
+    fn shutdown(&mut self) {
+        assert_eq!(self.request("shutdown", Value::Null), Value::Null);
+        self.notify("exit", Value::Null);
+        assert!(self.child.wait().unwrap().success());
+    }
+}
+
+impl Drop for Client {
+    // a failed test mustn't leave the server running
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+    }
+}
+
+fn range(start: (u32, u32), end: (u32, u32)) -> Value {
+    json!({
+        "start": { "line": start.0, "character": start.1 },
+        "end": { "line": end.0, "character": end.1 }
+    })
+}
+
+#[test]
+fn publish_diagnostics() {
+    let mut client = Client::start();
+
+    let diagnostics = client.open("x := 1\ny := true + x\n");
+    assert_eq!(diagnostics.as_array().unwrap().len(), 1);
+    assert_eq!(diagnostics[0]["severity"], 1);
+    assert_eq!(diagnostics[0]["range"]["start"]["line"], 1);
+
+    let diagnostics = client.change("x :: 1\nx = 2\n");
+    assert_eq!(diagnostics[0]["range"], range((1, 0), (1, 2)));
+    assert_eq!(
+        diagnostics[0]["relatedInformation"],
+        json!([{
+            "location": { "uri": URI, "range": range((0, 0), (0, 6)) },
+            "message": "defined with `::` here"
+        }])
+    );
+
+    let diagnostics = client.change("x := 1\ny := 2 + x\n");
+    assert_eq!(diagnostics, json!([]));
+
+    client.notify(
+        "textDocument/didClose",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    assert_eq!(client.diagnostics(), json!([]));
+    client.shutdown();
+}
+
+#[test]
+fn ignore_malformed_notifications() {
+    let mut client = Client::start();
+
+    client.notify(
+        "textDocument/didOpen",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    // the server is still there, and the document was never opened
+    let error = client.at("textDocument/hover", 0, 0);
+    assert_eq!(error["message"], format!("`{}` isn't open", URI));
+
+    let diagnostics = client.open("x := 1\n");
+    assert_eq!(diagnostics, json!([]));
+    client.shutdown();
+}
+
+#[test]
+fn hover_names_and_expressions() {
+    let mut client = Client::start();
+    client.open("P :: struct { x: f32 }\nlen :: (p: P) -> f32 { p.x * 2.0 }\n");
+
+    let hover = client.at("textDocument/hover", 0, 0);
+    assert_eq!(hover["contents"]["value"], "```drip\nP :: struct\n```");
+    let hover = client.at("textDocument/hover", 1, 1);
+    assert_eq!(
+        hover["contents"]["value"],
+        "```drip\nlen :: fn(P) -> f32\n```"
+    );
+    assert_eq!(hover["range"], range((1, 0), (1, 3)));
+    let hover = client.at("textDocument/hover", 1, 25);
+    assert_eq!(hover["contents"]["value"], "```drip\nx: f32\n```");
+    let hover = client.at("textDocument/hover", 1, 28);
+    assert_eq!(hover["contents"]["value"], "```drip\nf32\n```");
+    client.shutdown();
+}
+
+#[test]
+fn complete_members() {
+    let mut client = Client::start();
+    client.open("P :: struct { x: f32 }\np := P { x: 1.0 }\np.\n");
+
+    let items = client.request(
+        "textDocument/completion",
+        json!({
+            "textDocument": { "uri": URI },
+            "position": { "line": 2, "character": 2 },
+            "context": { "triggerKind": 2, "triggerCharacter": "." }
+        }),
+    );
+    assert_eq!(items[0]["label"], "x");
+    assert_eq!(items[0]["detail"], "f32");
+    assert_eq!(items.as_array().unwrap().len(), 1);
+    client.shutdown();
+}
+
+#[test]
+fn find_definitions_and_references() {
+    let mut client = Client::start();
+    client.open("x := 1\ny := x + 1\nx = y + x\n");
+
+    let definition = client.at("textDocument/definition", 2, 8);
+    assert_eq!(definition["uri"], URI);
+    assert_eq!(definition["range"], range((0, 0), (0, 1)));
+
+    let references = client.at("textDocument/references", 1, 5);
+    let ranges: Vec<_> = references
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|location| location["range"].clone())
+        .collect();
+    assert_eq!(
+        ranges,
+        [
+            range((0, 0), (0, 1)),
+            range((1, 5), (1, 6)),
+            range((2, 0), (2, 1)),
+            range((2, 8), (2, 9)),
+        ]
+    );
+    client.shutdown();
+}
+
+#[test]
+fn list_document_symbols() {
+    let mut client = Client::start();
+    client.open(
+        "Vec2 :: struct { x: f32, y: f32 }\nVec2 :: impl {\n    len :: (self) -> f32 { self.x }\n}\nmain :: () {}\n",
+    );
+
+    let symbols = client.request(
+        "textDocument/documentSymbol",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    let outline: Vec<_> = symbols
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|symbol| {
+            let children = symbol["children"].as_array().map_or(0, Vec::len);
+            format!("{} {} {}", symbol["name"], symbol["kind"], children)
+        })
+        .collect();
+    assert_eq!(outline, ["\"Vec2\" 23 2", "\"len\" 6 0", "\"main\" 12 0"]);
+    assert_eq!(symbols[0]["children"][1]["name"], "y");
+    assert_eq!(symbols[1]["selectionRange"], range((2, 4), (2, 7)));
+    client.shutdown();
+}
+
+#[test]
+fn format_documents() {
+    let mut client = Client::start();
+    client.open("x:=1+2  \n");
+
+    let edits = client.request(
+        "textDocument/formatting",
+        json!({
+            "textDocument": { "uri": URI },
+            "options": { "tabSize": 4, "insertSpaces": true }
+        }),
+    );
+    assert_eq!(
+        edits,
+        json!([{ "range": range((0, 0), (1, 0)), "newText": "x := 1 + 2\n" }])
+    );
+    client.shutdown();
+}
+
+#[test]
+fn rename_bindings() {
+    let mut client = Client::start();
+    client.open("count := 0\ncount += 1\nprintln(count)\n");
+
+    let edit = client.request(
+        "textDocument/rename",
+        json!({
+            "textDocument": { "uri": URI },
+            "position": { "line": 1, "character": 2 },
+            "newName": "total"
+        }),
+    );
+    let edits = edit["changes"][URI].as_array().unwrap();
+    assert_eq!(edits.len(), 3);
+    assert!(edits.iter().all(|edit| edit["newText"] == "total"));
+    assert_eq!(edits[2]["range"], range((2, 8), (2, 13)));
+
+    let error = client.request(
+        "textDocument/rename",
+        json!({
+            "textDocument": { "uri": URI },
+            "position": { "line": 0, "character": 0 },
+            "newName": "not a name"
+        }),
+    );
+    assert_eq!(error["message"], "`not a name` isn't a name");
+    let error = client.request(
+        "textDocument/rename",
+        json!({
+            "textDocument": { "uri": URI },
+            "position": { "line": 2, "character": 0 },
+            "newName": "print"
+        }),
+    );
+    assert_eq!(error["message"], "builtins can't be renamed");
+    client.shutdown();
+}
+
+#[test]
+fn rename_and_find_shorthand_fields() {
+    let mut client = Client::start();
+    client.open("V :: struct { x: i32 }\nx := 1\nv := V { x }\nv.x\n");
+    let rename = |client: &mut Client, line, character, new_name| {
+        let edit = client.request(
+            "textDocument/rename",
+            json!({
+                "textDocument": { "uri": URI },
+                "position": { "line": line, "character": character },
+                "newName": new_name
+            }),
+        );
+        edit["changes"][URI].clone()
+    };
+
+    assert_eq!(
+        rename(&mut client, 1, 0, "a"),
+        json!([
+            { "range": range((1, 0), (1, 1)), "newText": "a" },
+            { "range": range((2, 9), (2, 10)), "newText": "x: a" },
+        ])
+    );
+    assert_eq!(
+        rename(&mut client, 0, 14, "b"),
+        json!([
+            { "range": range((0, 14), (0, 15)), "newText": "b" },
+            { "range": range((2, 9), (2, 10)), "newText": "b: x" },
+            { "range": range((3, 2), (3, 3)), "newText": "b" },
+        ])
+    );
+
+    let references = client.at("textDocument/references", 0, 14);
+    let ranges: Vec<_> = references
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|location| location["range"].clone())
+        .collect();
+    assert_eq!(
+        ranges,
+        [
+            range((0, 14), (0, 15)),
+            range((2, 9), (2, 10)),
+            range((3, 2), (3, 3)),
+        ]
+    );
+    let references = client.at("textDocument/references", 2, 9);
+    assert_eq!(references.as_array().unwrap().len(), 2);
+    assert_eq!(references[0]["range"], range((1, 0), (1, 1)));
+    client.shutdown();
+}
+
+#[test]
+fn highlight_semantic_tokens() {
+    let mut client = Client::start();
+    client.open("x := 1\n");
+
+    let tokens = client.request(
+        "textDocument/semanticTokens/full",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    // a mutable variable, an operator and a number, each relative to the one
+    // before
+    assert_eq!(
+        tokens["data"],
+        json!([0, 0, 1, 5, 2, 0, 2, 2, 4, 0, 0, 3, 1, 1, 0])
+    );
+    client.shutdown();
+}
@@ -1,7 +1,141 @@
+//! The traits that give operators their meaning on structs.
+//!
+//! Operators work on numbers, and `+` on strings, without any help. A struct
+//! can use an operator by implementing the operator's trait, after which the
+//! operator calls the trait's method:
+//!
+//! ```text
+//! Vec3 <| Add :: impl {
+//!     add :: (self, other: Vec3) -> Vec3 { .. }
+//! }
+//! a + b // a.add(b)
+//! ```
+//!
+//! The traits are built in, so they can be implemented without declaring
+//! them first. Each has a single method, described by an [`OperatorTrait`].
+//! `!=` negates `eq`, and the comparison operators compare the result of
+//! `cmp` with zero, so `a < b` is `a.cmp(b) < 0`.
+
+/// A built-in trait and the signature of its only method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OperatorTrait {
+    pub name: &'static str,
+    pub method: &'static str,
+    /// The types of the method's parameters after `self`.
+    pub params: &'static [Operand],
+    /// The method's return type, or `None` if it doesn't return a value.
+    pub ret: Option<Operand>,
+}
+
+/// A type in the signature of an operator method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    /// The implementing type, written as `Self` or by its name.
+    SelfType,
+    /// A built-in type, like `bool`.
+    Named(&'static str),
+    /// Whatever type the impl declares.
+    Any,
+}
+
+/// `a + b`
+pub const ADD: OperatorTrait = binary("Add", "add");
+/// `a - b`
+pub const SUB: OperatorTrait = binary("Sub", "sub");
+/// `a * b`
+pub const MUL: OperatorTrait = binary("Mul", "mul");
+/// `a / b`
+pub const DIV: OperatorTrait = binary("Div", "div");
+/// `-a`
+pub const NEG: OperatorTrait = OperatorTrait {
+    name: "Neg",
+    method: "neg",
+    params: &[],
+    ret: Some(Operand::Any),
+};
+/// `a == b` and `a != b`. Structs without an impl are compared field by
+/// field instead.
+pub const EQ: OperatorTrait = OperatorTrait {
+    name: "Eq",
+    method: "eq",
+    params: &[Operand::SelfType],
+    ret: Some(Operand::Named("bool")),
+};
+/// `<`, `<=`, `>` and `>=`, with `cmp` returning a negative number, zero or
+/// a positive number if `self` is less than, equal to or greater than
+/// `other`.
+pub const ORD: OperatorTrait = OperatorTrait {
+    name: "Ord",
+    method: "cmp",
+    params: &[Operand::SelfType],
+    ret: Some(Operand::Named("i32")),
+};
+/// `a[i]`, which can only be read, not assigned to.
+pub const INDEX: OperatorTrait = OperatorTrait {
+    name: "Index",
+    method: "index",
+    params: &[Operand::Any],
+    ret: Some(Operand::Any),
+};
+/// `a += b`
+pub const ADD_ASSIGN: OperatorTrait = assign("AddAssign", "add_assign");
+/// `a -= b`
+pub const SUB_ASSIGN: OperatorTrait = assign("SubAssign", "sub_assign");
+/// `a *= b`
+pub const MUL_ASSIGN: OperatorTrait = assign("MulAssign", "mul_assign");
+/// `a /= b`
+pub const DIV_ASSIGN: OperatorTrait = assign("DivAssign", "div_assign");
+
+pub const ALL: [OperatorTrait; 12] = [
+    ADD, SUB, MUL, DIV, NEG, EQ, ORD, INDEX, ADD_ASSIGN, SUB_ASSIGN, MUL_ASSIGN, DIV_ASSIGN,
+];
+
+/// An arithmetic operator, whose right-hand side and result can have any
+/// type, like in `Vec3 * f32 -> Vec3`.
+const fn binary(name: &'static str, method: &'static str) -> OperatorTrait {
+    OperatorTrait {
+        name,
+        method,
+        params: &[Operand::Any],
+        ret: Some(Operand::Any),
+    }
+}
+
+/// A compound assignment, whose method changes `self` instead of returning a
+/// new value.
+const fn assign(name: &'static str, method: &'static str) -> OperatorTrait {
+    OperatorTrait {
+        name,
+        method,
+        params: &[Operand::Any],
+        ret: None,
+    }
+}
+
+/// The built-in trait called `name`.
+pub fn lookup(name: &str) -> Option<&'static OperatorTrait> {
+    ALL.iter().find(|t| t.name == name)
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    #[test]
+    fn look_up_traits_by_name() {
+        assert_eq!(lookup("Add"), Some(&ADD));
+        assert_eq!(lookup("DivAssign").map(|t| t.method), Some("div_assign"));
+        assert_eq!(lookup("add"), None);
+        assert_eq!(lookup("Display"), None);
+    }
+
     #[test]
-    fn it_works() {
-        assert_eq!(2 + 2, 4);
+    fn names_and_methods_are_unique() {
+        for (i, a) in ALL.iter().enumerate() {
+            for b in &ALL[i + 1..] {
+                assert_ne!(a.name, b.name);
+                assert_ne!(a.method, b.method);
+            }
+        }
     }
 }
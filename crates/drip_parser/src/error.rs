@@ -3,21 +3,16 @@ use std::fmt;
 use std::fmt::Formatter;
 use text_size::TextRange;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ParseError {
     pub expected: Vec<TokenKind>,
     pub found: Option<TokenKind>,
     pub range: TextRange,
 }
 
-impl fmt::Display for ParseError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "error at {}..{}: expected ",
-            u32::from(self.range.start()),
-            u32::from(self.range.end()),
-        )?;
+impl ParseError {
+    pub fn message(&self) -> String {
+        let mut message = String::from("expected ");
 
         let num_expected = self.expected.len();
         let is_first = |idx| idx == 0;
@@ -25,18 +20,30 @@ impl fmt::Display for ParseError {
 
         for (idx, expected_kind) in self.expected.iter().enumerate() {
             if is_first(idx) {
-                write!(f, "{}", expected_kind)?;
+                message.push_str(&format!("{}", expected_kind));
             } else if is_last(idx) {
-                write!(f, " or {}", expected_kind)?;
+                message.push_str(&format!(" or {}", expected_kind));
             } else {
-                write!(f, ", {}", expected_kind)?;
+                message.push_str(&format!(", {}", expected_kind));
             }
         }
 
         if let Some(found) = self.found {
-            write!(f, ", but found {}", found)?;
+            message.push_str(&format!(", but found {}", found));
         }
 
-        Ok(())
+        message
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "error at {}..{}: {}",
+            u32::from(self.range.start()),
+            u32::from(self.range.end()),
+            self.message(),
+        )
     }
 }
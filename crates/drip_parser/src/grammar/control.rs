@@ -0,0 +1,166 @@
+use crate::grammar::{decl, expr, pattern};
+use crate::marker::{CompletedMarker, Marker};
+use crate::parser::Parser;
+use drip_lexer::TokenKind;
+use drip_syntax::SyntaxKind;
+
+pub(crate) fn block_expr(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(TokenKind::LCurlyBracket));
+
+    let marker = p.start();
+    p.bump();
+    p.with_struct_literals(true, |p| {
+        while !p.at(TokenKind::RCurlyBracket) && !p.at_end() {
+            decl::decl(p);
+        }
+    });
+    p.expect(TokenKind::RCurlyBracket);
+
+    marker.complete(p, SyntaxKind::BlockExpr)
+}
+
+/// The block a branch or loop body has to be.
+fn body(p: &mut Parser) {
+    if p.at(TokenKind::LCurlyBracket) {
+        block_expr(p);
+    } else {
+        p.error();
+    }
+}
+
+pub(crate) fn if_expr(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(TokenKind::IfKw));
+
+    let marker = p.start();
+    p.bump();
+    if_rest(p, marker)
+}
+
+/// The condition, branches and `else` of an `if`; `else cond { ... }` starts
+/// another `if` without the keyword.
+fn if_rest(p: &mut Parser, marker: Marker) -> CompletedMarker {
+    expr::condition(p);
+    body(p);
+
+    if p.at(TokenKind::ElseKw) {
+        p.bump();
+        if p.at(TokenKind::IfKw) {
+            if_expr(p);
+        } else if p.at(TokenKind::LCurlyBracket) {
+            block_expr(p);
+        } else if !p.at_end() {
+            let marker = p.start();
+            if_rest(p, marker);
+        } else {
+            p.error();
+        }
+    }
+
+    marker.complete(p, SyntaxKind::IfExpr)
+}
+
+pub(crate) fn loop_expr(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(TokenKind::LoopKw));
+
+    let marker = p.start();
+    p.bump();
+    body(p);
+
+    marker.complete(p, SyntaxKind::LoopExpr)
+}
+
+pub(crate) fn while_expr(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(TokenKind::WhileKw));
+
+    let marker = p.start();
+    p.bump();
+    expr::condition(p);
+    body(p);
+
+    marker.complete(p, SyntaxKind::WhileExpr)
+}
+
+pub(crate) fn for_expr(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(TokenKind::ForKw));
+
+    let marker = p.start();
+    p.bump();
+    pattern::binding_pattern(p);
+    if p.at(TokenKind::Comma) {
+        p.bump();
+        pattern::binding_pattern(p);
+    }
+    p.expect(TokenKind::InKw);
+    expr::condition(p);
+    body(p);
+
+    marker.complete(p, SyntaxKind::ForExpr)
+}
+
+pub(crate) fn break_expr(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(TokenKind::BreakKw));
+
+    let marker = p.start();
+    p.bump();
+
+    // the value has to be on the same line as the `break`
+    if !p.at_line_start() && !p.at_end() && !p.at_set(&[TokenKind::RCurlyBracket]) {
+        expr::expr(p);
+    }
+
+    marker.complete(p, SyntaxKind::BreakExpr)
+}
+
+pub(crate) fn continue_expr(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(TokenKind::ContinueKw));
+
+    let marker = p.start();
+    p.bump();
+
+    marker.complete(p, SyntaxKind::ContinueExpr)
+}
+
+pub(crate) fn match_expr(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(TokenKind::MatchKw));
+
+    let marker = p.start();
+    p.bump();
+    expr::condition(p);
+
+    if p.at(TokenKind::LCurlyBracket) {
+        match_arm_list(p);
+    } else {
+        p.error();
+    }
+
+    marker.complete(p, SyntaxKind::MatchExpr)
+}
+
+fn match_arm_list(p: &mut Parser) {
+    assert!(p.at(TokenKind::LCurlyBracket));
+
+    let marker = p.start();
+    p.bump();
+    p.with_struct_literals(true, |p| {
+        while !p.at(TokenKind::RCurlyBracket) && !p.at_end() {
+            match_arm(p);
+        }
+    });
+    p.expect(TokenKind::RCurlyBracket);
+
+    marker.complete(p, SyntaxKind::MatchArmList);
+}
+
+fn match_arm(p: &mut Parser) {
+    let marker = p.start();
+
+    pattern::pattern(p);
+    while p.at(TokenKind::Comma) {
+        p.bump();
+        pattern::pattern(p);
+    }
+    p.expect(TokenKind::FatArrow);
+    expr::expr(p);
+
+    marker.complete(p, SyntaxKind::MatchArm);
+}
@@ -4,6 +4,14 @@ use crate::parser::Parser;
 use drip_lexer::TokenKind;
 use drip_syntax::SyntaxKind;
 
+const ASSIGN_OPS: [TokenKind; 5] = [
+    TokenKind::Equals,
+    TokenKind::PlusEquals,
+    TokenKind::MinusEquals,
+    TokenKind::StarEquals,
+    TokenKind::SlashEquals,
+];
+
 pub(crate) fn decl(p: &mut Parser) {
     if p.at(TokenKind::ExternKw) {
         abi(p);
@@ -12,7 +20,30 @@ pub(crate) fn decl(p: &mut Parser) {
     } else if p.at(TokenKind::Ident) {
         ident_decl(p);
     } else {
-        expr::expr(p);
+        expr_or_assign(p);
+    }
+}
+
+/// Like [`decl`], but `name :: (...)` is always a function, which may not
+/// have a body.
+pub(crate) fn trait_member(p: &mut Parser) {
+    if p.at(TokenKind::Ident)
+        && p.peek_nth(1) == Some(TokenKind::ConstKw)
+        && matches!(
+            p.peek_nth(2),
+            Some(TokenKind::LRoundBracket | TokenKind::FnKw)
+        )
+    {
+        let m = p.start();
+        p.bump();
+        p.bump();
+        if p.at(TokenKind::FnKw) {
+            p.bump();
+        }
+        func::function_def(p);
+        m.complete(p, SyntaxKind::FnDef);
+    } else {
+        decl(p);
     }
 }
 
@@ -21,25 +52,29 @@ fn ident_decl(p: &mut Parser) {
     let m = p.start();
 
     let peek = p.peek_nth(1);
-    if peek.is_some() {
-        let peek = peek.unwrap();
-        if peek == TokenKind::Equals {
-            let m_ref = p.start();
-            p.bump();
-            m_ref.complete(p, SyntaxKind::VariableRef);
-            assign_def(p);
-            m.complete(p, SyntaxKind::AssignDef);
-            return;
-        }
+    if peek == Some(TokenKind::Inherit)
+        || (peek == Some(TokenKind::ConstKw) && p.peek_nth(2) == Some(TokenKind::ImplKw))
+    {
+        structs::impl_def(p);
+        m.complete(p, SyntaxKind::ImplDef);
+        return;
+    }
+
+    if peek == Some(TokenKind::Comma) && at_name_list(p) {
+        name_list_def(p, m);
+        return;
+    }
+
+    if let Some(peek) = peek {
         if peek != TokenKind::ConstKw && peek != TokenKind::VariableKw {
-            expr::expr(p);
+            expr_or_assign(p);
             m.abandon(p);
             return;
         }
     }
 
     if peek.is_none() {
-        expr::expr(p);
+        expr_or_assign(p);
         m.abandon(p);
         return;
     }
@@ -56,6 +91,43 @@ fn ident_decl(p: &mut Parser) {
     }
 }
 
+/// Whether names separated by commas are followed by `::` or `:=`, as in
+/// `a, b :: 1, 2`.
+fn at_name_list(p: &mut Parser) -> bool {
+    let mut nth = 1;
+    while p.peek_nth(nth) == Some(TokenKind::Comma) && p.peek_nth(nth + 1) == Some(TokenKind::Ident)
+    {
+        nth += 2;
+    }
+    matches!(
+        p.peek_nth(nth),
+        Some(TokenKind::ConstKw | TokenKind::VariableKw)
+    )
+}
+
+fn name_list_def(p: &mut Parser, m: Marker) {
+    let list = p.start();
+    loop {
+        let name = p.start();
+        p.bump();
+        name.complete(p, SyntaxKind::IdentPat);
+        if !p.at(TokenKind::Comma) {
+            break;
+        }
+        p.bump();
+    }
+    list.complete(p, SyntaxKind::NameList);
+
+    let kind = if p.at(TokenKind::ConstKw) {
+        SyntaxKind::ConstDef
+    } else {
+        SyntaxKind::VariableDef
+    };
+    p.bump();
+    expr::expr_or_tuple(p);
+    m.complete(p, kind);
+}
+
 fn const_def(p: &mut Parser, m: Marker) {
     assert!(p.at(TokenKind::ConstKw));
     p.bump();
@@ -66,25 +138,40 @@ fn const_def(p: &mut Parser, m: Marker) {
     } else if p.at(TokenKind::TraitKw) {
         structs::trait_def(p);
         m.complete(p, SyntaxKind::TraitDef);
+    } else if p.at(TokenKind::FnKw) {
+        // `name :: fn(...)` is the same as `name :: (...)`
+        p.bump();
+        func::function_def(p);
+        m.complete(p, SyntaxKind::FnDef);
+    } else if at_function_params(p) {
+        func::function_def(p);
+        m.complete(p, SyntaxKind::FnDef);
     } else {
-        if p.current().unwrap() == TokenKind::LRoundBracket {
-            let mut counter = 0;
-            while p.peek_nth(counter).unwrap() != TokenKind::RRoundBracket {
-                counter += 1;
-            }
-            let nth = p.peek_nth(counter + 1).unwrap();
-            if nth == TokenKind::Arrow || nth == TokenKind::LCurlyBracket {
-                func::function_def(p);
-                m.complete(p, SyntaxKind::FnDef);
-            } else {
-                expr::expr(p);
-                m.complete(p, SyntaxKind::ConstDef);
-            }
-        } else {
-            expr::expr(p);
-            m.complete(p, SyntaxKind::ConstDef);
+        expr::expr(p);
+        m.complete(p, SyntaxKind::ConstDef);
+    }
+}
+
+/// Whether the brackets here hold the parameters of a function, which are
+/// followed by `->` or `{`, rather than an expression. Types inside them
+/// can have brackets too, as in `(f: Fn())`.
+fn at_function_params(p: &mut Parser) -> bool {
+    let mut depth = 0;
+    let mut nth = 0;
+    loop {
+        match p.peek_nth(nth) {
+            Some(TokenKind::LRoundBracket) => depth += 1,
+            Some(TokenKind::RRoundBracket) if depth > 1 => depth -= 1,
+            Some(TokenKind::RRoundBracket) if depth == 1 => break,
+            Some(_) if depth > 0 => {}
+            _ => return false,
         }
+        nth += 1;
     }
+    matches!(
+        p.peek_nth(nth + 1),
+        Some(TokenKind::Arrow | TokenKind::LCurlyBracket)
+    )
 }
 
 fn variable_def(p: &mut Parser) {
@@ -93,10 +180,35 @@ fn variable_def(p: &mut Parser) {
     expr::expr(p);
 }
 
-fn assign_def(p: &mut Parser) {
-    assert!(p.at(TokenKind::Equals));
-    p.bump();
-    expr::expr(p);
+/// An expression, or an assignment like `a = b` or `v.x += 1` to one.
+/// Targets separated by commas, as in `x, y += 1`, make a tuple without
+/// brackets, which has to be assigned to.
+fn expr_or_assign(p: &mut Parser) {
+    let mut target = match expr::expr(p) {
+        Some(target) => target,
+        None => return,
+    };
+
+    let targets = p.peek() == Some(TokenKind::Comma);
+    if targets {
+        let m = target.precede(p);
+        while p.at(TokenKind::Comma) {
+            p.bump();
+            if expr::expr(p).is_none() {
+                break;
+            }
+        }
+        target = m.complete(p, SyntaxKind::TupleExpr);
+    }
+
+    if p.at_set(&ASSIGN_OPS) {
+        let m = target.precede(p);
+        p.bump();
+        expr::expr(p);
+        m.complete(p, SyntaxKind::AssignDef);
+    } else if targets {
+        p.expect(TokenKind::Equals);
+    }
 }
 
 fn abi(_p: &mut Parser) {
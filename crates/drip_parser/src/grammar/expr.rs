@@ -1,17 +1,32 @@
 use super::*;
+use crate::marker::Marker;
 
 pub enum BinaryOp {
     Add,
     Sub,
     Mul,
     Div,
+    Eq,
+    NotEq,
+    Less,
+    LessEq,
+    Greater,
+    GreaterEq,
+    Range,
 }
 
 impl BinaryOp {
     pub fn binding_power(&self) -> (u8, u8) {
         match self {
-            Self::Add | Self::Sub => (1, 2),
-            Self::Mul | Self::Div => (3, 4),
+            Self::Range => (1, 2),
+            Self::Eq
+            | Self::NotEq
+            | Self::Less
+            | Self::LessEq
+            | Self::Greater
+            | Self::GreaterEq => (3, 4),
+            Self::Add | Self::Sub => (5, 6),
+            Self::Mul | Self::Div => (7, 8),
         }
     }
 }
@@ -23,7 +38,7 @@ enum UnaryOp {
 impl UnaryOp {
     fn binding_power(&self) -> ((), u8) {
         match self {
-            Self::Neg => ((), 5),
+            Self::Neg => ((), 9),
         }
     }
 }
@@ -32,7 +47,31 @@ pub(crate) fn expr(p: &mut Parser) -> Option<CompletedMarker> {
     expr_binding_power(p, 0)
 }
 
-fn expr_binding_power(p: &mut Parser, min_binding_power: u8) -> Option<CompletedMarker> {
+/// An expression, or several separated by commas that make a tuple without
+/// brackets, like the values in `a, b :: 1, 2`.
+pub(crate) fn expr_or_tuple(p: &mut Parser) -> Option<CompletedMarker> {
+    let first = expr(p)?;
+    if !p.at(TokenKind::Comma) {
+        return Some(first);
+    }
+
+    let marker = first.precede(p);
+    while p.at(TokenKind::Comma) {
+        p.bump();
+        if expr(p).is_none() {
+            break;
+        }
+    }
+    Some(marker.complete(p, SyntaxKind::TupleExpr))
+}
+
+/// The condition of an `if`, `while`, `for` or `match`, which is followed by
+/// a `{` that can't start a struct literal.
+pub(crate) fn condition(p: &mut Parser) -> Option<CompletedMarker> {
+    p.with_struct_literals(false, expr)
+}
+
+pub(crate) fn expr_binding_power(p: &mut Parser, min_binding_power: u8) -> Option<CompletedMarker> {
     let mut lhs = lhs(p)?;
     loop {
         let op = if p.at(TokenKind::Plus) {
@@ -43,6 +82,20 @@ fn expr_binding_power(p: &mut Parser, min_binding_power: u8) -> Option<Completed
             BinaryOp::Mul
         } else if p.at(TokenKind::Slash) {
             BinaryOp::Div
+        } else if p.at(TokenKind::Equals2) {
+            BinaryOp::Eq
+        } else if p.at(TokenKind::NotEquals) {
+            BinaryOp::NotEq
+        } else if p.at(TokenKind::LAngledBracket) {
+            BinaryOp::Less
+        } else if p.at(TokenKind::LessEquals) {
+            BinaryOp::LessEq
+        } else if p.at(TokenKind::RAngledBracket) {
+            BinaryOp::Greater
+        } else if p.at(TokenKind::GreaterEquals) {
+            BinaryOp::GreaterEq
+        } else if p.at(TokenKind::Dot2) {
+            BinaryOp::Range
         } else {
             break;
         };
@@ -57,35 +110,128 @@ fn expr_binding_power(p: &mut Parser, min_binding_power: u8) -> Option<Completed
 
         let marker = lhs.precede(p);
         let parsed_rhs = expr_binding_power(p, right_binding_power).is_some();
+        // the step of `a..b=step`, on the same line
+        if matches!(op, BinaryOp::Range)
+            && parsed_rhs
+            && p.peek() == Some(TokenKind::Equals)
+            && !p.at_line_start()
+        {
+            p.bump();
+            expr_binding_power(p, right_binding_power);
+        }
         lhs = marker.complete(p, SyntaxKind::InfixExpr);
 
         if !parsed_rhs {
-            break;
+            return Some(lhs);
         }
     }
 
+    // `c ? a : b` binds looser than any operator, so it only ends a whole
+    // expression.
+    if min_binding_power == 0 && p.peek() == Some(TokenKind::Quest) {
+        let marker = lhs.precede(p);
+        p.bump();
+        expr(p);
+        p.expect(TokenKind::Colon);
+        expr(p);
+        lhs = marker.complete(p, SyntaxKind::TernaryExpr);
+    }
+
     Some(lhs)
 }
 
 fn lhs(p: &mut Parser) -> Option<CompletedMarker> {
-    let marker = if p.at(TokenKind::Number) {
+    let mut array_element = p.peek() == Some(TokenKind::LSquareBracket) && p.at_array_element();
+    let mut marker = if p.at(TokenKind::Number) {
         literal(p)
-    } else if p.at(TokenKind::Ident) {
-        variable_ref(p)
+    } else if p.at_set(&[TokenKind::Ident, TokenKind::SelfTypeKw]) && at_path(p) {
+        path_expr(p)
+    } else if p.at(TokenKind::Ident) || p.peek() == Some(TokenKind::SelfTypeKw) {
+        variable_ref_or_struct_expr(p)
     } else if p.at(TokenKind::Minus) {
-        prefix_expr(p)
+        return Some(prefix_expr(p));
     } else if p.at(TokenKind::LRoundBracket) {
         round_bracket_expr(p)
+    } else if p.at(TokenKind::Str) {
+        literal(p)
+    } else if p.at(TokenKind::LSquareBracket) {
+        array_expr(p)
+    } else if p.at(TokenKind::SelfVarKw) {
+        variable_ref(p)
+    } else if p.at(TokenKind::LCurlyBracket) {
+        return Some(control::block_expr(p));
+    } else if p.at(TokenKind::IfKw) {
+        return Some(control::if_expr(p));
+    } else if p.at(TokenKind::LoopKw) {
+        return Some(control::loop_expr(p));
+    } else if p.at(TokenKind::WhileKw) {
+        return Some(control::while_expr(p));
+    } else if p.at(TokenKind::ForKw) {
+        return Some(control::for_expr(p));
+    } else if p.at(TokenKind::BreakKw) {
+        return Some(control::break_expr(p));
+    } else if p.at(TokenKind::ContinueKw) {
+        return Some(control::continue_expr(p));
+    } else if p.at(TokenKind::MatchKw) {
+        return Some(control::match_expr(p));
+    } else if p.at(TokenKind::TrueKw) || p.at(TokenKind::FalseKw) {
+        literal(p)
+    } else if p.at(TokenKind::Pipe) || p.at(TokenKind::Pipe2) {
+        return Some(closure_expr(p));
+    } else if p.at(TokenKind::Dot3) || p.peek() == Some(TokenKind::Dot2) {
+        let marker = p.start();
+        p.bump();
+        return Some(marker.complete(p, SyntaxKind::PlaceholderExpr));
     } else {
         p.error();
         return None;
     };
 
+    // a `(` on the next line starts a new statement instead of a call
+    while !p.at_line_start() {
+        if p.at(TokenKind::LRoundBracket) {
+            let call = marker.precede(p);
+            arg_list(p);
+            marker = call.complete(p, SyntaxKind::CallExpr);
+        } else if p.at(TokenKind::Dot) {
+            marker = field_or_method_call(p, marker);
+        } else if p.at(TokenKind::LSquareBracket) && !array_element {
+            let index = marker.precede(p);
+            p.bump();
+            p.with_struct_literals(true, expr);
+            p.expect(TokenKind::RSquareBracket);
+            marker = index.complete(p, SyntaxKind::IndexExpr);
+        } else {
+            break;
+        }
+        array_element = false;
+    }
+
     Some(marker)
 }
 
+fn field_or_method_call(p: &mut Parser, receiver: CompletedMarker) -> CompletedMarker {
+    assert!(p.at(TokenKind::Dot));
+
+    let marker = receiver.precede(p);
+    p.bump();
+    p.expect(TokenKind::Ident);
+
+    if !p.at_line_start() && p.at(TokenKind::LRoundBracket) {
+        arg_list(p);
+        marker.complete(p, SyntaxKind::MethodCallExpr)
+    } else {
+        marker.complete(p, SyntaxKind::FieldExpr)
+    }
+}
+
 fn literal(p: &mut Parser) -> CompletedMarker {
-    assert!(p.at(TokenKind::Number));
+    assert!(p.at_set(&[
+        TokenKind::Number,
+        TokenKind::Str,
+        TokenKind::TrueKw,
+        TokenKind::FalseKw
+    ]));
 
     let marker = p.start();
     p.bump();
@@ -93,13 +239,101 @@ fn literal(p: &mut Parser) -> CompletedMarker {
 }
 
 fn variable_ref(p: &mut Parser) -> CompletedMarker {
-    assert!(p.at(TokenKind::Ident));
+    assert!(p.at(TokenKind::Ident) || p.at(TokenKind::SelfVarKw));
 
     let marker = p.start();
     p.bump();
     marker.complete(p, SyntaxKind::VariableRef)
 }
 
+/// Whether a `Type::name` path starts here. A statement starting with
+/// `Name ::` defines `Name` instead, so paths only appear inside
+/// expressions.
+fn at_path(p: &mut Parser) -> bool {
+    p.peek_nth(1) == Some(TokenKind::ConstKw) && p.peek_nth(2) == Some(TokenKind::Ident)
+}
+
+fn path_expr(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(TokenKind::Ident) || p.at(TokenKind::SelfTypeKw));
+
+    let marker = p.start();
+    let ty = p.start();
+    p.bump();
+    ty.complete(p, SyntaxKind::Type);
+    p.bump();
+    p.expect(TokenKind::Ident);
+    marker.complete(p, SyntaxKind::PathExpr)
+}
+
+/// A name, which starts a struct literal if a `{` follows on the same line.
+/// `Self` can only start a struct literal.
+fn variable_ref_or_struct_expr(p: &mut Parser) -> CompletedMarker {
+    let self_ty = p.peek() == Some(TokenKind::SelfTypeKw);
+    assert!(self_ty || p.at(TokenKind::Ident));
+
+    let marker = p.start();
+    p.bump();
+
+    if !self_ty
+        && (!p.struct_literals_allowed()
+            || p.at_line_start()
+            || p.peek() != Some(TokenKind::LCurlyBracket))
+    {
+        return marker.complete(p, SyntaxKind::VariableRef);
+    }
+
+    p.expect(TokenKind::LCurlyBracket);
+    while !p.at(TokenKind::RCurlyBracket) && !p.at_end() {
+        struct_expr_field(p);
+        if !p.at(TokenKind::Comma) {
+            break;
+        }
+        p.bump();
+    }
+    p.expect(TokenKind::RCurlyBracket);
+
+    marker.complete(p, SyntaxKind::StructExpr)
+}
+
+/// `name: value`, `name` on its own, or a value without a name.
+fn struct_expr_field(p: &mut Parser) {
+    let marker = p.start();
+    let named = p.peek() == Some(TokenKind::Ident)
+        && matches!(
+            p.peek_nth(1),
+            Some(TokenKind::Colon | TokenKind::Comma | TokenKind::RCurlyBracket) | None
+        );
+    if named {
+        p.bump();
+        if p.at(TokenKind::Colon) {
+            p.bump();
+            expr(p);
+        }
+    } else {
+        expr(p);
+    }
+
+    marker.complete(p, SyntaxKind::StructExprField);
+}
+
+fn closure_expr(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(TokenKind::Pipe) || p.at(TokenKind::Pipe2));
+
+    let marker = p.start();
+    if p.at(TokenKind::Pipe) {
+        p.bump();
+        if !p.at(TokenKind::Pipe) {
+            func::function_param_list_def(p, TokenKind::Pipe);
+        }
+        p.expect(TokenKind::Pipe);
+    } else {
+        p.bump();
+    }
+    p.with_struct_literals(true, expr);
+
+    marker.complete(p, SyntaxKind::ClosureExpr)
+}
+
 fn prefix_expr(p: &mut Parser) -> CompletedMarker {
     assert!(p.at(TokenKind::Minus));
 
@@ -120,8 +354,92 @@ fn round_bracket_expr(p: &mut Parser) -> CompletedMarker {
 
     let marker = p.start();
     p.bump();
+    p.with_struct_literals(true, |p| round_bracket_contents(p, marker))
+}
+
+fn round_bracket_contents(p: &mut Parser, marker: Marker) -> CompletedMarker {
+    // `()` is the empty tuple, and a comma after the first element makes a tuple
+    if p.at(TokenKind::RRoundBracket) {
+        p.bump();
+        return marker.complete(p, SyntaxKind::TupleExpr);
+    }
+
     expr_binding_power(p, 0);
+    if p.at(TokenKind::Comma) {
+        p.bump();
+        expr_list(p, TokenKind::RRoundBracket);
+        p.expect(TokenKind::RRoundBracket);
+        return marker.complete(p, SyntaxKind::TupleExpr);
+    }
+
     p.expect(TokenKind::RRoundBracket);
 
     marker.complete(p, SyntaxKind::RoundBracketExpr)
 }
+
+fn array_expr(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(TokenKind::LSquareBracket));
+
+    let marker = p.start();
+    p.bump();
+    p.with_struct_literals(true, array_elements);
+    p.expect(TokenKind::RSquareBracket);
+
+    marker.complete(p, SyntaxKind::ArrayExpr)
+}
+
+/// Like [`expr_list`], but elements on lines of their own, and arrays right
+/// after each other, don't need commas between them.
+fn array_elements(p: &mut Parser) {
+    while !p.at(TokenKind::RSquareBracket) && !p.at_end() {
+        if p.with_array_element(|p| expr_binding_power(p, 0)).is_none() {
+            break;
+        }
+        if p.at(TokenKind::Comma) {
+            p.bump();
+        } else if !p.at_line_start() && !p.at(TokenKind::LSquareBracket) {
+            break;
+        }
+    }
+}
+
+fn arg_list(p: &mut Parser) {
+    assert!(p.at(TokenKind::LRoundBracket));
+
+    let marker = p.start();
+    p.bump();
+    p.with_struct_literals(true, |p| {
+        while !p.at(TokenKind::RRoundBracket) && !p.at_end() {
+            if arg(p).is_none() || !p.at(TokenKind::Comma) {
+                break;
+            }
+            p.bump();
+        }
+    });
+    p.expect(TokenKind::RRoundBracket);
+
+    marker.complete(p, SyntaxKind::ArgList);
+}
+
+/// An argument, which is passed by name if it starts with `name:`.
+fn arg(p: &mut Parser) -> Option<CompletedMarker> {
+    if p.peek() != Some(TokenKind::Ident) || p.peek_nth(1) != Some(TokenKind::Colon) {
+        return expr_binding_power(p, 0);
+    }
+
+    let marker = p.start();
+    p.bump();
+    p.bump();
+    expr(p);
+    Some(marker.complete(p, SyntaxKind::NamedArg))
+}
+
+/// Comma-separated expressions up to `end`, with an optional trailing comma.
+fn expr_list(p: &mut Parser, end: TokenKind) {
+    while !p.at(end) && !p.at_end() {
+        if expr_binding_power(p, 0).is_none() || !p.at(TokenKind::Comma) {
+            break;
+        }
+        p.bump();
+    }
+}
@@ -6,7 +6,7 @@ use drip_syntax::SyntaxKind;
 pub(crate) fn function_def(p: &mut Parser) {
     p.expect(TokenKind::LRoundBracket);
     if !p.at(TokenKind::RRoundBracket) {
-        function_param_list_def(p);
+        function_param_list_def(p, TokenKind::RRoundBracket);
     }
     p.expect(TokenKind::RRoundBracket);
     // return is optional
@@ -14,16 +14,25 @@ pub(crate) fn function_def(p: &mut Parser) {
         p.bump();
         function_return_def(p);
     }
-    p.expect(TokenKind::LCurlyBracket);
-    if !p.at(TokenKind::RCurlyBracket) {
-        function_body_def(p);
+    // trait members can be declared without a body
+    if p.at(TokenKind::LCurlyBracket) {
+        p.bump();
+        if !p.at(TokenKind::RCurlyBracket) {
+            function_body_def(p);
+        }
+        p.expect(TokenKind::RCurlyBracket);
     }
-    p.expect(TokenKind::RCurlyBracket);
 }
 
-fn function_param_list_def(p: &mut Parser) {
+/// The parameters up to `end`, which is `)` for functions and `|` for
+/// closures.
+pub(crate) fn function_param_list_def(p: &mut Parser, end: TokenKind) {
     let m = p.start();
-    while !p.at(TokenKind::RRoundBracket) {
+    while !p.at(end) && !p.at_end() {
+        if !p.at(TokenKind::Ident) && !p.at(TokenKind::SelfVarKw) {
+            p.error();
+            break;
+        }
         function_param_def(p);
     }
     m.complete(p, SyntaxKind::FnParamListDef);
@@ -31,7 +40,9 @@ fn function_param_list_def(p: &mut Parser) {
 
 fn function_body_def(p: &mut Parser) {
     let m = p.start();
-    decl::decl(p);
+    while !p.at(TokenKind::RCurlyBracket) && !p.at_end() {
+        decl::decl(p);
+    }
     m.complete(p, SyntaxKind::FnBodyDef);
 }
 
@@ -48,7 +59,7 @@ fn function_return_def(p: &mut Parser) {
 }
 
 fn function_return_type_list_def(p: &mut Parser) {
-    assert!(p.at(TokenKind::Ident));
+    assert!(p.at(TokenKind::Ident) || p.at(TokenKind::SelfTypeKw));
     let m = p.start();
     while !p.at(TokenKind::RRoundBracket) {
         function_return_type_def(p);
@@ -60,18 +71,21 @@ fn function_return_type_list_def(p: &mut Parser) {
 }
 
 fn function_return_type_def(p: &mut Parser) {
-    assert!(p.at(TokenKind::Ident));
+    assert!(p.at(TokenKind::Ident) || p.at(TokenKind::SelfTypeKw));
     let m = p.start();
     types::types(p);
     m.complete(p, SyntaxKind::FnReturnTypeDef);
 }
 
 fn function_param_def(p: &mut Parser) {
-    assert!(p.at(TokenKind::Ident));
+    assert!(p.at(TokenKind::Ident) || p.at(TokenKind::SelfVarKw));
     let m = p.start();
     p.bump();
-    p.expect(TokenKind::Colon);
-    types::types(p);
+    // the type is optional, `(a, b: i32)` gives both parameters the type `i32`
+    if p.at(TokenKind::Colon) {
+        p.bump();
+        types::types(p);
+    }
     // `,` is optional after parameter
     if p.at(TokenKind::Comma) {
         p.bump()
@@ -3,9 +3,11 @@ use crate::parser::Parser;
 use drip_lexer::TokenKind;
 use drip_syntax::SyntaxKind;
 
+mod control;
 mod decl;
 mod expr;
 mod func;
+mod pattern;
 mod structs;
 mod types;
 
@@ -216,8 +218,8 @@ Root@0..3
       Literal@1..2
         Number@1..2 "1"
       Plus@2..3 "+"
-error at 2..3: expected number, identifier, '-' or '('
-error at 2..3: expected ')'"#]],
+error at 2..3: expected number, identifier, '-', '(', string, '[', 'self', '{', 'if', 'loop', 'while', 'for', 'break', 'continue', 'match', 'true', 'false', '|', '||' or '...'
+error at 2..3: expected ',' or ')'"#]],
         );
     }
 
@@ -312,7 +314,399 @@ Root@0..4
     LRoundBracket@0..1 "("
     VariableRef@1..4
       Ident@1..4 "foo"
-error at 1..4: expected '+', '-', '*', '/' or ')'"#]],
+error at 1..4: expected '(', '.', '[', '+', '-', '*', '/', '==', '!=', '<', '<=', '>', '>=', '..', ',' or ')'"#]],
+        );
+    }
+
+    #[test]
+    fn list_each_expected_token_once() {
+        check(
+            "f :: () { x @ }",
+            expect![[r#"
+Root@0..15
+  FnDef@0..15
+    Ident@0..1 "f"
+    Whitespace@1..2 " "
+    ConstKw@2..4 "::"
+    Whitespace@4..5 " "
+    LRoundBracket@5..6 "("
+    RRoundBracket@6..7 ")"
+    Whitespace@7..8 " "
+    LCurlyBracket@8..9 "{"
+    Whitespace@9..10 " "
+    FnBodyDef@10..14
+      VariableRef@10..12
+        Ident@10..11 "x"
+        Whitespace@11..12 " "
+      Error@12..14
+        At@12..13 "@"
+        Whitespace@13..14 " "
+    RCurlyBracket@14..15 "}"
+error at 12..13: expected '(', '.', '[', '+', '-', '*', '/', '==', '!=', '<', '<=', '>', '>=', '..', '}', 'extern', 'use', identifier, number, string, 'self', '{', 'if', 'loop', 'while', 'for', 'break', 'continue', 'match', 'true', 'false', '|', '||' or '...', but found '@'"#]],
+        );
+    }
+
+    #[test]
+    fn parse_string_literal() {
+        check(
+            r#""drip""#,
+            expect![[r#"
+Root@0..6
+  Literal@0..6
+    Str@0..6 "\"drip\"""#]],
+        );
+    }
+
+    #[test]
+    fn parse_tuple() {
+        check(
+            "(1, a,)",
+            expect![[r#"
+Root@0..7
+  TupleExpr@0..7
+    LRoundBracket@0..1 "("
+    Literal@1..2
+      Number@1..2 "1"
+    Comma@2..3 ","
+    Whitespace@3..4 " "
+    VariableRef@4..5
+      Ident@4..5 "a"
+    Comma@5..6 ","
+    RRoundBracket@6..7 ")""#]],
+        );
+    }
+
+    #[test]
+    fn parse_empty_tuple() {
+        check(
+            "()",
+            expect![[r#"
+Root@0..2
+  TupleExpr@0..2
+    LRoundBracket@0..1 "("
+    RRoundBracket@1..2 ")""#]],
+        );
+    }
+
+    #[test]
+    fn parse_array() {
+        check(
+            "[1, 2]",
+            expect![[r#"
+Root@0..6
+  ArrayExpr@0..6
+    LSquareBracket@0..1 "["
+    Literal@1..2
+      Number@1..2 "1"
+    Comma@2..3 ","
+    Whitespace@3..4 " "
+    Literal@4..5
+      Number@4..5 "2"
+    RSquareBracket@5..6 "]""#]],
+        );
+    }
+
+    #[test]
+    fn parse_array_rows_without_commas() {
+        check(
+            "[[\n  [1]\n  [2]\n][\n  [3]\n], x[0]]",
+            expect![[r#"
+Root@0..32
+  ArrayExpr@0..32
+    LSquareBracket@0..1 "["
+    ArrayExpr@1..16
+      LSquareBracket@1..2 "["
+      Whitespace@2..5 "\n  "
+      ArrayExpr@5..11
+        LSquareBracket@5..6 "["
+        Literal@6..7
+          Number@6..7 "1"
+        RSquareBracket@7..8 "]"
+        Whitespace@8..11 "\n  "
+      ArrayExpr@11..15
+        LSquareBracket@11..12 "["
+        Literal@12..13
+          Number@12..13 "2"
+        RSquareBracket@13..14 "]"
+        Whitespace@14..15 "\n"
+      RSquareBracket@15..16 "]"
+    ArrayExpr@16..25
+      LSquareBracket@16..17 "["
+      Whitespace@17..20 "\n  "
+      ArrayExpr@20..24
+        LSquareBracket@20..21 "["
+        Literal@21..22
+          Number@21..22 "3"
+        RSquareBracket@22..23 "]"
+        Whitespace@23..24 "\n"
+      RSquareBracket@24..25 "]"
+    Comma@25..26 ","
+    Whitespace@26..27 " "
+    IndexExpr@27..31
+      VariableRef@27..28
+        Ident@27..28 "x"
+      LSquareBracket@28..29 "["
+      Literal@29..30
+        Number@29..30 "0"
+      RSquareBracket@30..31 "]"
+    RSquareBracket@31..32 "]""#]],
+        );
+    }
+
+    #[test]
+    fn parse_call() {
+        check(
+            "-f(1, g(x))(2)",
+            expect![[r#"
+Root@0..14
+  PrefixExpr@0..14
+    Minus@0..1 "-"
+    CallExpr@1..14
+      CallExpr@1..11
+        VariableRef@1..2
+          Ident@1..2 "f"
+        ArgList@2..11
+          LRoundBracket@2..3 "("
+          Literal@3..4
+            Number@3..4 "1"
+          Comma@4..5 ","
+          Whitespace@5..6 " "
+          CallExpr@6..10
+            VariableRef@6..7
+              Ident@6..7 "g"
+            ArgList@7..10
+              LRoundBracket@7..8 "("
+              VariableRef@8..9
+                Ident@8..9 "x"
+              RRoundBracket@9..10 ")"
+          RRoundBracket@10..11 ")"
+      ArgList@11..14
+        LRoundBracket@11..12 "("
+        Literal@12..13
+          Number@12..13 "2"
+        RRoundBracket@13..14 ")""#]],
+        );
+    }
+
+    #[test]
+    fn do_not_parse_call_across_lines() {
+        check(
+            "f\n(1)",
+            expect![[r#"
+Root@0..5
+  VariableRef@0..2
+    Ident@0..1 "f"
+    Whitespace@1..2 "\n"
+  RoundBracketExpr@2..5
+    LRoundBracket@2..3 "("
+    Literal@3..4
+      Number@3..4 "1"
+    RRoundBracket@4..5 ")""#]],
+        );
+    }
+
+    #[test]
+    fn parse_path_call() {
+        check(
+            "v :: Vec3::new(5, Self::zero())",
+            expect![[r#"
+Root@0..31
+  ConstDef@0..31
+    Ident@0..1 "v"
+    Whitespace@1..2 " "
+    ConstKw@2..4 "::"
+    Whitespace@4..5 " "
+    CallExpr@5..31
+      PathExpr@5..14
+        Type@5..9
+          Ident@5..9 "Vec3"
+        ConstKw@9..11 "::"
+        Ident@11..14 "new"
+      ArgList@14..31
+        LRoundBracket@14..15 "("
+        Literal@15..16
+          Number@15..16 "5"
+        Comma@16..17 ","
+        Whitespace@17..18 " "
+        CallExpr@18..30
+          PathExpr@18..28
+            Type@18..22
+              SelfTypeKw@18..22 "Self"
+            ConstKw@22..24 "::"
+            Ident@24..28 "zero"
+          ArgList@28..30
+            LRoundBracket@28..29 "("
+            RRoundBracket@29..30 ")"
+        RRoundBracket@30..31 ")""#]],
+        );
+    }
+
+    #[test]
+    fn parse_call_with_named_args() {
+        check(
+            "f(1, y: a)",
+            expect![[r#"
+Root@0..10
+  CallExpr@0..10
+    VariableRef@0..1
+      Ident@0..1 "f"
+    ArgList@1..10
+      LRoundBracket@1..2 "("
+      Literal@2..3
+        Number@2..3 "1"
+      Comma@3..4 ","
+      Whitespace@4..5 " "
+      NamedArg@5..9
+        Ident@5..6 "y"
+        Colon@6..7 ":"
+        Whitespace@7..8 " "
+        VariableRef@8..9
+          Ident@8..9 "a"
+      RRoundBracket@9..10 ")""#]],
+        );
+    }
+
+    #[test]
+    fn parse_assignment_to_several_targets() {
+        check(
+            "x, y += 1",
+            expect![[r#"
+Root@0..9
+  AssignDef@0..9
+    TupleExpr@0..5
+      VariableRef@0..1
+        Ident@0..1 "x"
+      Comma@1..2 ","
+      Whitespace@2..3 " "
+      VariableRef@3..5
+        Ident@3..4 "y"
+        Whitespace@4..5 " "
+    PlusEquals@5..7 "+="
+    Whitespace@7..8 " "
+    Literal@8..9
+      Number@8..9 "1""#]],
+        );
+    }
+
+    #[test]
+    fn parse_method_call_and_field() {
+        check(
+            "self.sound().volume",
+            expect![[r#"
+Root@0..19
+  FieldExpr@0..19
+    MethodCallExpr@0..12
+      VariableRef@0..4
+        SelfVarKw@0..4 "self"
+      Dot@4..5 "."
+      Ident@5..10 "sound"
+      ArgList@10..12
+        LRoundBracket@10..11 "("
+        RRoundBracket@11..12 ")"
+    Dot@12..13 "."
+    Ident@13..19 "volume""#]],
+        );
+    }
+
+    #[test]
+    fn define_function_with_grouped_params() {
+        check(
+            "f :: (a, b: i32) {}",
+            expect![[r#"
+Root@0..19
+  FnDef@0..19
+    Ident@0..1 "f"
+    Whitespace@1..2 " "
+    ConstKw@2..4 "::"
+    Whitespace@4..5 " "
+    LRoundBracket@5..6 "("
+    FnParamListDef@6..15
+      FnParamDef@6..9
+        Ident@6..7 "a"
+        Comma@7..8 ","
+        Whitespace@8..9 " "
+      FnParamDef@9..15
+        Ident@9..10 "b"
+        Colon@10..11 ":"
+        Whitespace@11..12 " "
+        Type@12..15
+          Ident@12..15 "i32"
+    RRoundBracket@15..16 ")"
+    Whitespace@16..17 " "
+    LCurlyBracket@17..18 "{"
+    RCurlyBracket@18..19 "}""#]],
+        );
+    }
+
+    #[test]
+    fn define_function_with_fn_keyword() {
+        check(
+            "new :: fn(x) -> Self {}",
+            expect![[r#"
+Root@0..23
+  FnDef@0..23
+    Ident@0..3 "new"
+    Whitespace@3..4 " "
+    ConstKw@4..6 "::"
+    Whitespace@6..7 " "
+    FnKw@7..9 "fn"
+    LRoundBracket@9..10 "("
+    FnParamListDef@10..11
+      FnParamDef@10..11
+        Ident@10..11 "x"
+    RRoundBracket@11..12 ")"
+    Whitespace@12..13 " "
+    Arrow@13..15 "->"
+    Whitespace@15..16 " "
+    FnReturnDef@16..21
+      FnReturnTypeDef@16..21
+        Type@16..21
+          SelfTypeKw@16..20 "Self"
+          Whitespace@20..21 " "
+    LCurlyBracket@21..22 "{"
+    RCurlyBracket@22..23 "}""#]],
+        );
+    }
+
+    #[test]
+    fn define_function_taking_fn() {
+        check(
+            "call :: (f: Fn()) -> Fn() { f }",
+            expect![[r#"
+Root@0..31
+  FnDef@0..31
+    Ident@0..4 "call"
+    Whitespace@4..5 " "
+    ConstKw@5..7 "::"
+    Whitespace@7..8 " "
+    LRoundBracket@8..9 "("
+    FnParamListDef@9..16
+      FnParamDef@9..16
+        Ident@9..10 "f"
+        Colon@10..11 ":"
+        Whitespace@11..12 " "
+        Type@12..16
+          Ident@12..14 "Fn"
+          LRoundBracket@14..15 "("
+          RRoundBracket@15..16 ")"
+    RRoundBracket@16..17 ")"
+    Whitespace@17..18 " "
+    Arrow@18..20 "->"
+    Whitespace@20..21 " "
+    FnReturnDef@21..26
+      FnReturnTypeDef@21..26
+        Type@21..26
+          Ident@21..23 "Fn"
+          LRoundBracket@23..24 "("
+          RRoundBracket@24..25 ")"
+          Whitespace@25..26 " "
+    LCurlyBracket@26..27 "{"
+    Whitespace@27..28 " "
+    FnBodyDef@28..30
+      VariableRef@28..30
+        Ident@28..29 "f"
+        Whitespace@29..30 " "
+    RCurlyBracket@30..31 "}""#]],
         );
     }
 
@@ -421,6 +815,26 @@ Root@0..13
         )
     }
 
+    #[test]
+    fn compound_assignment_to_field() {
+        check(
+            "v.x += 1",
+            expect![[r#"
+Root@0..8
+  AssignDef@0..8
+    FieldExpr@0..4
+      VariableRef@0..1
+        Ident@0..1 "v"
+      Dot@1..2 "."
+      Ident@2..3 "x"
+      Whitespace@3..4 " "
+    PlusEquals@4..6 "+="
+    Whitespace@6..7 " "
+    Literal@7..8
+      Number@7..8 "1""#]],
+        )
+    }
+
     #[test]
     fn def_constant() {
         check(
@@ -437,6 +851,67 @@ Root@0..7
         )
     }
 
+    #[test]
+    fn def_several_constants() {
+        check(
+            "a, b, c :: 10, 3, 5",
+            expect![[r#"
+Root@0..19
+  ConstDef@0..19
+    NameList@0..8
+      IdentPat@0..1
+        Ident@0..1 "a"
+      Comma@1..2 ","
+      Whitespace@2..3 " "
+      IdentPat@3..4
+        Ident@3..4 "b"
+      Comma@4..5 ","
+      Whitespace@5..6 " "
+      IdentPat@6..8
+        Ident@6..7 "c"
+        Whitespace@7..8 " "
+    ConstKw@8..10 "::"
+    Whitespace@10..11 " "
+    TupleExpr@11..19
+      Literal@11..13
+        Number@11..13 "10"
+      Comma@13..14 ","
+      Whitespace@14..15 " "
+      Literal@15..16
+        Number@15..16 "3"
+      Comma@16..17 ","
+      Whitespace@17..18 " "
+      Literal@18..19
+        Number@18..19 "5""#]],
+        )
+    }
+
+    #[test]
+    fn def_variables_from_a_call() {
+        check(
+            "x, y := f()",
+            expect![[r#"
+Root@0..11
+  VariableDef@0..11
+    NameList@0..5
+      IdentPat@0..1
+        Ident@0..1 "x"
+      Comma@1..2 ","
+      Whitespace@2..3 " "
+      IdentPat@3..5
+        Ident@3..4 "y"
+        Whitespace@4..5 " "
+    VariableKw@5..7 ":="
+    Whitespace@7..8 " "
+    CallExpr@8..11
+      VariableRef@8..9
+        Ident@8..9 "f"
+      ArgList@9..11
+        LRoundBracket@9..10 "("
+        RRoundBracket@10..11 ")""#]],
+        )
+    }
+
     #[test]
     fn define_complete_empty_function() {
         check(
@@ -610,6 +1085,38 @@ Root@0..97
         )
     }
 
+    #[test]
+    fn define_function_with_multiple_statements() {
+        check(
+            "f :: () { a :: 1 a }",
+            expect![[r#"
+Root@0..20
+  FnDef@0..20
+    Ident@0..1 "f"
+    Whitespace@1..2 " "
+    ConstKw@2..4 "::"
+    Whitespace@4..5 " "
+    LRoundBracket@5..6 "("
+    RRoundBracket@6..7 ")"
+    Whitespace@7..8 " "
+    LCurlyBracket@8..9 "{"
+    Whitespace@9..10 " "
+    FnBodyDef@10..19
+      ConstDef@10..17
+        Ident@10..11 "a"
+        Whitespace@11..12 " "
+        ConstKw@12..14 "::"
+        Whitespace@14..15 " "
+        Literal@15..17
+          Number@15..16 "1"
+          Whitespace@16..17 " "
+      VariableRef@17..19
+        Ident@17..18 "a"
+        Whitespace@18..19 " "
+    RCurlyBracket@19..20 "}""#]],
+        )
+    }
+
     #[test]
     fn define_empty_struct() {
         check(
@@ -734,4 +1241,696 @@ Root@0..22
       RCurlyBracket@21..22 "}""#]],
         );
     }
+
+    #[test]
+    fn define_struct_with_parent() {
+        check(
+            "Dog :: struct <| Animal { toy: string }",
+            expect![[r#"
+Root@0..39
+  StructDef@0..39
+    Ident@0..3 "Dog"
+    Whitespace@3..4 " "
+    ConstKw@4..6 "::"
+    Whitespace@6..7 " "
+    StructKw@7..13 "struct"
+    Whitespace@13..14 " "
+    Inherit@14..16 "<|"
+    Whitespace@16..17 " "
+    Type@17..24
+      Ident@17..23 "Animal"
+      Whitespace@23..24 " "
+    StructFieldListDef@24..39
+      LCurlyBracket@24..25 "{"
+      Whitespace@25..26 " "
+      StructFieldDef@26..38
+        Ident@26..29 "toy"
+        Colon@29..30 ":"
+        Whitespace@30..31 " "
+        Type@31..38
+          Ident@31..37 "string"
+          Whitespace@37..38 " "
+      RCurlyBracket@38..39 "}""#]],
+        );
+    }
+
+    #[test]
+    fn define_trait_with_member_declarations() {
+        check(
+            "Sound :: trait {\n    make_sound :: (self)\n    volume :: (self) -> i32\n}",
+            expect![[r#"
+Root@0..71
+  TraitDef@0..71
+    Ident@0..5 "Sound"
+    Whitespace@5..6 " "
+    ConstKw@6..8 "::"
+    Whitespace@8..9 " "
+    TraitKw@9..14 "trait"
+    Whitespace@14..15 " "
+    TraitListsDef@15..71
+      LCurlyBracket@15..16 "{"
+      Whitespace@16..21 "\n    "
+      TraitFnListDef@21..70
+        FnDef@21..46
+          Ident@21..31 "make_sound"
+          Whitespace@31..32 " "
+          ConstKw@32..34 "::"
+          Whitespace@34..35 " "
+          LRoundBracket@35..36 "("
+          FnParamListDef@36..40
+            FnParamDef@36..40
+              SelfVarKw@36..40 "self"
+          RRoundBracket@40..41 ")"
+          Whitespace@41..46 "\n    "
+        FnDef@46..70
+          Ident@46..52 "volume"
+          Whitespace@52..53 " "
+          ConstKw@53..55 "::"
+          Whitespace@55..56 " "
+          LRoundBracket@56..57 "("
+          FnParamListDef@57..61
+            FnParamDef@57..61
+              SelfVarKw@57..61 "self"
+          RRoundBracket@61..62 ")"
+          Whitespace@62..63 " "
+          Arrow@63..65 "->"
+          Whitespace@65..66 " "
+          FnReturnDef@66..70
+            FnReturnTypeDef@66..70
+              Type@66..70
+                Ident@66..69 "i32"
+                Whitespace@69..70 "\n"
+      RCurlyBracket@70..71 "}""#]],
+        );
+    }
+
+    #[test]
+    fn define_impl() {
+        check(
+            "Vec2 :: impl { len :: () { 1 } }",
+            expect![[r#"
+Root@0..32
+  ImplDef@0..32
+    Type@0..5
+      Ident@0..4 "Vec2"
+      Whitespace@4..5 " "
+    ConstKw@5..7 "::"
+    Whitespace@7..8 " "
+    ImplKw@8..12 "impl"
+    Whitespace@12..13 " "
+    LCurlyBracket@13..14 "{"
+    Whitespace@14..15 " "
+    ImplItemListDef@15..31
+      FnDef@15..31
+        Ident@15..18 "len"
+        Whitespace@18..19 " "
+        ConstKw@19..21 "::"
+        Whitespace@21..22 " "
+        LRoundBracket@22..23 "("
+        RRoundBracket@23..24 ")"
+        Whitespace@24..25 " "
+        LCurlyBracket@25..26 "{"
+        Whitespace@26..27 " "
+        FnBodyDef@27..29
+          Literal@27..29
+            Number@27..28 "1"
+            Whitespace@28..29 " "
+        RCurlyBracket@29..30 "}"
+        Whitespace@30..31 " "
+    RCurlyBracket@31..32 "}""#]],
+        );
+    }
+
+    #[test]
+    fn define_trait_impl() {
+        check(
+            "Dog <| Sound :: impl { }",
+            expect![[r#"
+Root@0..24
+  ImplDef@0..24
+    Type@0..4
+      Ident@0..3 "Dog"
+      Whitespace@3..4 " "
+    Inherit@4..6 "<|"
+    Whitespace@6..7 " "
+    Type@7..13
+      Ident@7..12 "Sound"
+      Whitespace@12..13 " "
+    ConstKw@13..15 "::"
+    Whitespace@15..16 " "
+    ImplKw@16..20 "impl"
+    Whitespace@20..21 " "
+    LCurlyBracket@21..22 "{"
+    Whitespace@22..23 " "
+    RCurlyBracket@23..24 "}""#]],
+        );
+    }
+
+    #[test]
+    fn parse_if_else_if_chain() {
+        check(
+            "if a == b { 1 } else if a < b { 2 } else { 3 }",
+            expect![[r#"
+Root@0..46
+  IfExpr@0..46
+    IfKw@0..2 "if"
+    Whitespace@2..3 " "
+    InfixExpr@3..10
+      VariableRef@3..5
+        Ident@3..4 "a"
+        Whitespace@4..5 " "
+      Equals2@5..7 "=="
+      Whitespace@7..8 " "
+      VariableRef@8..10
+        Ident@8..9 "b"
+        Whitespace@9..10 " "
+    BlockExpr@10..16
+      LCurlyBracket@10..11 "{"
+      Whitespace@11..12 " "
+      Literal@12..14
+        Number@12..13 "1"
+        Whitespace@13..14 " "
+      RCurlyBracket@14..15 "}"
+      Whitespace@15..16 " "
+    ElseKw@16..20 "else"
+    Whitespace@20..21 " "
+    IfExpr@21..46
+      IfKw@21..23 "if"
+      Whitespace@23..24 " "
+      InfixExpr@24..30
+        VariableRef@24..26
+          Ident@24..25 "a"
+          Whitespace@25..26 " "
+        LAngledBracket@26..27 "<"
+        Whitespace@27..28 " "
+        VariableRef@28..30
+          Ident@28..29 "b"
+          Whitespace@29..30 " "
+      BlockExpr@30..36
+        LCurlyBracket@30..31 "{"
+        Whitespace@31..32 " "
+        Literal@32..34
+          Number@32..33 "2"
+          Whitespace@33..34 " "
+        RCurlyBracket@34..35 "}"
+        Whitespace@35..36 " "
+      ElseKw@36..40 "else"
+      Whitespace@40..41 " "
+      BlockExpr@41..46
+        LCurlyBracket@41..42 "{"
+        Whitespace@42..43 " "
+        Literal@43..45
+          Number@43..44 "3"
+          Whitespace@44..45 " "
+        RCurlyBracket@45..46 "}""#]],
+        );
+    }
+
+    #[test]
+    fn parse_else_with_condition() {
+        check(
+            "if a { } else b { } else { }",
+            expect![[r#"
+Root@0..28
+  IfExpr@0..28
+    IfKw@0..2 "if"
+    Whitespace@2..3 " "
+    VariableRef@3..5
+      Ident@3..4 "a"
+      Whitespace@4..5 " "
+    BlockExpr@5..9
+      LCurlyBracket@5..6 "{"
+      Whitespace@6..7 " "
+      RCurlyBracket@7..8 "}"
+      Whitespace@8..9 " "
+    ElseKw@9..13 "else"
+    Whitespace@13..14 " "
+    IfExpr@14..28
+      VariableRef@14..16
+        Ident@14..15 "b"
+        Whitespace@15..16 " "
+      BlockExpr@16..20
+        LCurlyBracket@16..17 "{"
+        Whitespace@17..18 " "
+        RCurlyBracket@18..19 "}"
+        Whitespace@19..20 " "
+      ElseKw@20..24 "else"
+      Whitespace@24..25 " "
+      BlockExpr@25..28
+        LCurlyBracket@25..26 "{"
+        Whitespace@26..27 " "
+        RCurlyBracket@27..28 "}""#]],
+        );
+    }
+
+    #[test]
+    fn parse_ternary() {
+        check(
+            "a < b ? a : b + 1",
+            expect![[r#"
+Root@0..17
+  TernaryExpr@0..17
+    InfixExpr@0..6
+      VariableRef@0..2
+        Ident@0..1 "a"
+        Whitespace@1..2 " "
+      LAngledBracket@2..3 "<"
+      Whitespace@3..4 " "
+      VariableRef@4..6
+        Ident@4..5 "b"
+        Whitespace@5..6 " "
+    Quest@6..7 "?"
+    Whitespace@7..8 " "
+    VariableRef@8..10
+      Ident@8..9 "a"
+      Whitespace@9..10 " "
+    Colon@10..11 ":"
+    Whitespace@11..12 " "
+    InfixExpr@12..17
+      VariableRef@12..14
+        Ident@12..13 "b"
+        Whitespace@13..14 " "
+      Plus@14..15 "+"
+      Whitespace@15..16 " "
+      Literal@16..17
+        Number@16..17 "1""#]],
+        );
+    }
+
+    #[test]
+    fn parse_for_loop_over_range_with_break() {
+        check(
+            "x :: for i in 0..8 { break i }",
+            expect![[r#"
+Root@0..30
+  ConstDef@0..30
+    Ident@0..1 "x"
+    Whitespace@1..2 " "
+    ConstKw@2..4 "::"
+    Whitespace@4..5 " "
+    ForExpr@5..30
+      ForKw@5..8 "for"
+      Whitespace@8..9 " "
+      IdentPat@9..11
+        Ident@9..10 "i"
+        Whitespace@10..11 " "
+      InKw@11..13 "in"
+      Whitespace@13..14 " "
+      InfixExpr@14..19
+        Literal@14..15
+          Number@14..15 "0"
+        Dot2@15..17 ".."
+        Literal@17..19
+          Number@17..18 "8"
+          Whitespace@18..19 " "
+      BlockExpr@19..30
+        LCurlyBracket@19..20 "{"
+        Whitespace@20..21 " "
+        BreakExpr@21..29
+          BreakKw@21..26 "break"
+          Whitespace@26..27 " "
+          VariableRef@27..29
+            Ident@27..28 "i"
+            Whitespace@28..29 " "
+        RCurlyBracket@29..30 "}""#]],
+        );
+    }
+
+    #[test]
+    fn parse_for_loop_with_patterns() {
+        check(
+            "for (x, _), i in arr {}",
+            expect![[r#"
+Root@0..23
+  ForExpr@0..23
+    ForKw@0..3 "for"
+    Whitespace@3..4 " "
+    TuplePat@4..10
+      LRoundBracket@4..5 "("
+      IdentPat@5..6
+        Ident@5..6 "x"
+      Comma@6..7 ","
+      Whitespace@7..8 " "
+      WildcardPat@8..9
+        Underscore@8..9 "_"
+      RRoundBracket@9..10 ")"
+    Comma@10..11 ","
+    Whitespace@11..12 " "
+    IdentPat@12..14
+      Ident@12..13 "i"
+      Whitespace@13..14 " "
+    InKw@14..16 "in"
+    Whitespace@16..17 " "
+    VariableRef@17..21
+      Ident@17..20 "arr"
+      Whitespace@20..21 " "
+    BlockExpr@21..23
+      LCurlyBracket@21..22 "{"
+      RCurlyBracket@22..23 "}""#]],
+        );
+    }
+
+    #[test]
+    fn parse_range_with_step() {
+        check(
+            "0..10=2",
+            expect![[r#"
+Root@0..7
+  InfixExpr@0..7
+    Literal@0..1
+      Number@0..1 "0"
+    Dot2@1..3 ".."
+    Literal@3..5
+      Number@3..5 "10"
+    Equals@5..6 "="
+    Literal@6..7
+      Number@6..7 "2""#]],
+        );
+    }
+
+    #[test]
+    fn parse_while_and_loop() {
+        check(
+            "while n >= 1 { continue }\nloop { break }",
+            expect![[r#"
+Root@0..40
+  WhileExpr@0..26
+    WhileKw@0..5 "while"
+    Whitespace@5..6 " "
+    InfixExpr@6..13
+      VariableRef@6..8
+        Ident@6..7 "n"
+        Whitespace@7..8 " "
+      GreaterEquals@8..10 ">="
+      Whitespace@10..11 " "
+      Literal@11..13
+        Number@11..12 "1"
+        Whitespace@12..13 " "
+    BlockExpr@13..26
+      LCurlyBracket@13..14 "{"
+      Whitespace@14..15 " "
+      ContinueExpr@15..24
+        ContinueKw@15..23 "continue"
+        Whitespace@23..24 " "
+      RCurlyBracket@24..25 "}"
+      Whitespace@25..26 "\n"
+  LoopExpr@26..40
+    LoopKw@26..30 "loop"
+    Whitespace@30..31 " "
+    BlockExpr@31..40
+      LCurlyBracket@31..32 "{"
+      Whitespace@32..33 " "
+      BreakExpr@33..39
+        BreakKw@33..38 "break"
+        Whitespace@38..39 " "
+      RCurlyBracket@39..40 "}""#]],
+        );
+    }
+
+    #[test]
+    fn parse_match_with_alternatives_and_ranges() {
+        check(
+            "match x {\n  PI, -1 => a\n  PI..TAU + 1 => b\n  _ => c\n}",
+            expect![[r#"
+Root@0..53
+  MatchExpr@0..53
+    MatchKw@0..5 "match"
+    Whitespace@5..6 " "
+    VariableRef@6..8
+      Ident@6..7 "x"
+      Whitespace@7..8 " "
+    MatchArmList@8..53
+      LCurlyBracket@8..9 "{"
+      Whitespace@9..12 "\n  "
+      MatchArm@12..26
+        IdentPat@12..14
+          Ident@12..14 "PI"
+        Comma@14..15 ","
+        Whitespace@15..16 " "
+        LiteralPat@16..19
+          PrefixExpr@16..19
+            Minus@16..17 "-"
+            Literal@17..19
+              Number@17..18 "1"
+              Whitespace@18..19 " "
+        FatArrow@19..21 "=>"
+        Whitespace@21..22 " "
+        VariableRef@22..26
+          Ident@22..23 "a"
+          Whitespace@23..26 "\n  "
+      MatchArm@26..45
+        RangePat@26..38
+          VariableRef@26..28
+            Ident@26..28 "PI"
+          Dot2@28..30 ".."
+          InfixExpr@30..38
+            VariableRef@30..34
+              Ident@30..33 "TAU"
+              Whitespace@33..34 " "
+            Plus@34..35 "+"
+            Whitespace@35..36 " "
+            Literal@36..38
+              Number@36..37 "1"
+              Whitespace@37..38 " "
+        FatArrow@38..40 "=>"
+        Whitespace@40..41 " "
+        VariableRef@41..45
+          Ident@41..42 "b"
+          Whitespace@42..45 "\n  "
+      MatchArm@45..52
+        WildcardPat@45..47
+          Underscore@45..46 "_"
+          Whitespace@46..47 " "
+        FatArrow@47..49 "=>"
+        Whitespace@49..50 " "
+        VariableRef@50..52
+          Ident@50..51 "c"
+          Whitespace@51..52 "\n"
+      RCurlyBracket@52..53 "}""#]],
+        );
+    }
+
+    #[test]
+    fn parse_tuple_and_struct_patterns() {
+        check(
+            "match v { (true, n) => n\nVec2 { x: 0, y } => y }",
+            expect![[r#"
+Root@0..48
+  MatchExpr@0..48
+    MatchKw@0..5 "match"
+    Whitespace@5..6 " "
+    VariableRef@6..8
+      Ident@6..7 "v"
+      Whitespace@7..8 " "
+    MatchArmList@8..48
+      LCurlyBracket@8..9 "{"
+      Whitespace@9..10 " "
+      MatchArm@10..25
+        TuplePat@10..20
+          LRoundBracket@10..11 "("
+          LiteralPat@11..15
+            Literal@11..15
+              TrueKw@11..15 "true"
+          Comma@15..16 ","
+          Whitespace@16..17 " "
+          IdentPat@17..18
+            Ident@17..18 "n"
+          RRoundBracket@18..19 ")"
+          Whitespace@19..20 " "
+        FatArrow@20..22 "=>"
+        Whitespace@22..23 " "
+        VariableRef@23..25
+          Ident@23..24 "n"
+          Whitespace@24..25 "\n"
+      MatchArm@25..47
+        StructPat@25..42
+          Ident@25..29 "Vec2"
+          Whitespace@29..30 " "
+          LCurlyBracket@30..31 "{"
+          Whitespace@31..32 " "
+          FieldPat@32..36
+            Ident@32..33 "x"
+            Colon@33..34 ":"
+            Whitespace@34..35 " "
+            LiteralPat@35..36
+              Literal@35..36
+                Number@35..36 "0"
+          Comma@36..37 ","
+          Whitespace@37..38 " "
+          FieldPat@38..40
+            Ident@38..39 "y"
+            Whitespace@39..40 " "
+          RCurlyBracket@40..41 "}"
+          Whitespace@41..42 " "
+        FatArrow@42..44 "=>"
+        Whitespace@44..45 " "
+        VariableRef@45..47
+          Ident@45..46 "y"
+          Whitespace@46..47 " "
+      RCurlyBracket@47..48 "}""#]],
+        );
+    }
+
+    #[test]
+    fn parse_struct_literal_with_shorthand_field() {
+        check(
+            "Vec2 { x: 1, y }",
+            expect![[r#"
+Root@0..16
+  StructExpr@0..16
+    Ident@0..4 "Vec2"
+    Whitespace@4..5 " "
+    LCurlyBracket@5..6 "{"
+    Whitespace@6..7 " "
+    StructExprField@7..11
+      Ident@7..8 "x"
+      Colon@8..9 ":"
+      Whitespace@9..10 " "
+      Literal@10..11
+        Number@10..11 "1"
+    Comma@11..12 ","
+    Whitespace@12..13 " "
+    StructExprField@13..15
+      Ident@13..14 "y"
+      Whitespace@14..15 " "
+    RCurlyBracket@15..16 "}""#]],
+        );
+    }
+
+    #[test]
+    fn parse_self_struct_literal_with_values_without_names() {
+        check(
+            "Self { a + 1, b }",
+            expect![[r#"
+Root@0..17
+  StructExpr@0..17
+    SelfTypeKw@0..4 "Self"
+    Whitespace@4..5 " "
+    LCurlyBracket@5..6 "{"
+    Whitespace@6..7 " "
+    StructExprField@7..12
+      InfixExpr@7..12
+        VariableRef@7..9
+          Ident@7..8 "a"
+          Whitespace@8..9 " "
+        Plus@9..10 "+"
+        Whitespace@10..11 " "
+        Literal@11..12
+          Number@11..12 "1"
+    Comma@12..13 ","
+    Whitespace@13..14 " "
+    StructExprField@14..16
+      Ident@14..15 "b"
+      Whitespace@15..16 " "
+    RCurlyBracket@16..17 "}""#]],
+        );
+    }
+
+    #[test]
+    fn do_not_parse_struct_literal_in_condition() {
+        check(
+            "if a { b }",
+            expect![[r#"
+Root@0..10
+  IfExpr@0..10
+    IfKw@0..2 "if"
+    Whitespace@2..3 " "
+    VariableRef@3..5
+      Ident@3..4 "a"
+      Whitespace@4..5 " "
+    BlockExpr@5..10
+      LCurlyBracket@5..6 "{"
+      Whitespace@6..7 " "
+      VariableRef@7..9
+        Ident@7..8 "b"
+        Whitespace@8..9 " "
+      RCurlyBracket@9..10 "}""#]],
+        );
+    }
+
+    #[test]
+    fn define_struct_with_grouped_fields() {
+        check(
+            "V :: struct { x, y: i32 }",
+            expect![[r#"
+Root@0..25
+  StructDef@0..25
+    Ident@0..1 "V"
+    Whitespace@1..2 " "
+    ConstKw@2..4 "::"
+    Whitespace@4..5 " "
+    StructKw@5..11 "struct"
+    Whitespace@11..12 " "
+    StructFieldListDef@12..25
+      LCurlyBracket@12..13 "{"
+      Whitespace@13..14 " "
+      StructFieldDef@14..17
+        Ident@14..15 "x"
+        Comma@15..16 ","
+        Whitespace@16..17 " "
+      StructFieldDef@17..24
+        Ident@17..18 "y"
+        Colon@18..19 ":"
+        Whitespace@19..20 " "
+        Type@20..24
+          Ident@20..23 "i32"
+          Whitespace@23..24 " "
+      RCurlyBracket@24..25 "}""#]],
+        );
+    }
+
+    #[test]
+    fn parse_closure_and_index() {
+        check(
+            "|a, b| a[b]",
+            expect![[r#"
+Root@0..11
+  ClosureExpr@0..11
+    Pipe@0..1 "|"
+    FnParamListDef@1..5
+      FnParamDef@1..4
+        Ident@1..2 "a"
+        Comma@2..3 ","
+        Whitespace@3..4 " "
+      FnParamDef@4..5
+        Ident@4..5 "b"
+    Pipe@5..6 "|"
+    Whitespace@6..7 " "
+    IndexExpr@7..11
+      VariableRef@7..8
+        Ident@7..8 "a"
+      LSquareBracket@8..9 "["
+      VariableRef@9..10
+        Ident@9..10 "b"
+      RSquareBracket@10..11 "]""#]],
+        );
+    }
+
+    #[test]
+    fn parse_short_placeholder() {
+        check(
+            "{..}",
+            expect![[r#"
+Root@0..4
+  BlockExpr@0..4
+    LCurlyBracket@0..1 "{"
+    PlaceholderExpr@1..3
+      Dot2@1..3 ".."
+    RCurlyBracket@3..4 "}""#]],
+        );
+    }
+
+    #[test]
+    fn parse_closure_without_params() {
+        check(
+            "|| ...",
+            expect![[r#"
+Root@0..6
+  ClosureExpr@0..6
+    Pipe2@0..2 "||"
+    Whitespace@2..3 " "
+    PlaceholderExpr@3..6
+      Dot3@3..6 "...""#]],
+        );
+    }
 }
@@ -0,0 +1,111 @@
+use crate::grammar::expr;
+use crate::parser::Parser;
+use drip_lexer::TokenKind;
+use drip_syntax::SyntaxKind;
+
+/// The binding power just above `..`, so a range bound stops before it.
+const RANGE_BOUND_BINDING_POWER: u8 = 3;
+
+pub(crate) fn pattern(p: &mut Parser) {
+    if p.at(TokenKind::Underscore) {
+        let marker = p.start();
+        p.bump();
+        marker.complete(p, SyntaxKind::WildcardPat);
+    } else if p.at(TokenKind::LRoundBracket) {
+        tuple_pat(p, pattern);
+    } else if p.at(TokenKind::Ident) && p.peek_nth(1) == Some(TokenKind::LCurlyBracket) {
+        struct_pat(p);
+    } else if p.at(TokenKind::Ident) && p.peek_nth(1) != Some(TokenKind::Dot2) {
+        let marker = p.start();
+        p.bump();
+        marker.complete(p, SyntaxKind::IdentPat);
+    } else if p.at_set(&[
+        TokenKind::Ident,
+        TokenKind::Number,
+        TokenKind::Str,
+        TokenKind::TrueKw,
+        TokenKind::FalseKw,
+        TokenKind::Minus,
+    ]) {
+        literal_or_range_pat(p);
+    } else {
+        p.error();
+    }
+}
+
+/// A pattern that always matches, like the ones `for` loops take: a name,
+/// `_` or a tuple of these.
+pub(crate) fn binding_pattern(p: &mut Parser) {
+    if p.at(TokenKind::Underscore) {
+        let marker = p.start();
+        p.bump();
+        marker.complete(p, SyntaxKind::WildcardPat);
+    } else if p.at(TokenKind::LRoundBracket) {
+        tuple_pat(p, binding_pattern);
+    } else if p.at(TokenKind::Ident) {
+        let marker = p.start();
+        p.bump();
+        marker.complete(p, SyntaxKind::IdentPat);
+    } else {
+        p.error();
+    }
+}
+
+fn literal_or_range_pat(p: &mut Parser) {
+    let marker = p.start();
+    expr::expr_binding_power(p, RANGE_BOUND_BINDING_POWER);
+
+    if p.at(TokenKind::Dot2) {
+        p.bump();
+        expr::expr_binding_power(p, RANGE_BOUND_BINDING_POWER);
+        marker.complete(p, SyntaxKind::RangePat);
+    } else {
+        marker.complete(p, SyntaxKind::LiteralPat);
+    }
+}
+
+fn tuple_pat(p: &mut Parser, element: fn(&mut Parser)) {
+    assert!(p.at(TokenKind::LRoundBracket));
+
+    let marker = p.start();
+    p.bump();
+    while !p.at(TokenKind::RRoundBracket) && !p.at_end() {
+        element(p);
+        if !p.at(TokenKind::Comma) {
+            break;
+        }
+        p.bump();
+    }
+    p.expect(TokenKind::RRoundBracket);
+
+    marker.complete(p, SyntaxKind::TuplePat);
+}
+
+fn struct_pat(p: &mut Parser) {
+    assert!(p.at(TokenKind::Ident));
+
+    let marker = p.start();
+    p.bump();
+    p.bump();
+    while !p.at(TokenKind::RCurlyBracket) && !p.at_end() {
+        field_pat(p);
+        if !p.at(TokenKind::Comma) {
+            break;
+        }
+        p.bump();
+    }
+    p.expect(TokenKind::RCurlyBracket);
+
+    marker.complete(p, SyntaxKind::StructPat);
+}
+
+fn field_pat(p: &mut Parser) {
+    let marker = p.start();
+    p.expect(TokenKind::Ident);
+    if p.at(TokenKind::Colon) {
+        p.bump();
+        pattern(p);
+    }
+
+    marker.complete(p, SyntaxKind::FieldPat);
+}
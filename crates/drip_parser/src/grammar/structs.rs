@@ -7,6 +7,10 @@ use drip_syntax::SyntaxKind;
 pub(crate) fn struct_def(p: &mut Parser) {
     assert!(p.at(TokenKind::StructKw));
     p.bump();
+    if p.at(TokenKind::Inherit) {
+        p.bump();
+        types::types(p);
+    }
     struct_field_list_def(p);
 }
 
@@ -14,8 +18,11 @@ fn struct_field_list_def(p: &mut Parser) {
     assert!(p.at(TokenKind::LCurlyBracket));
     let m = p.start();
     p.bump();
-    while !p.at(TokenKind::RCurlyBracket) {
-        // TODO: handle "{ a: }", "{ a: {",  "{ {"
+    while !p.at(TokenKind::RCurlyBracket) && !p.at_end() {
+        if !p.at(TokenKind::Ident) {
+            p.error();
+            break;
+        }
         struct_field_def(p);
     }
     p.expect(TokenKind::RCurlyBracket);
@@ -26,8 +33,11 @@ fn struct_field_def(p: &mut Parser) {
     assert!(p.at(TokenKind::Ident));
     let m = p.start();
     p.bump();
-    p.expect(TokenKind::Colon);
-    types::types(p);
+    // the type is optional, `x, y: i32` gives both fields the type `i32`
+    if p.at(TokenKind::Colon) {
+        p.bump();
+        types::types(p);
+    }
     // `,` is optional after fields
     if p.at(TokenKind::Comma) {
         p.bump()
@@ -66,6 +76,34 @@ fn trait_type_lists_def(p: &mut Parser) {
 fn trait_fn_list_def(p: &mut Parser) {
     assert!(p.at(TokenKind::Ident));
     let m = p.start();
-    decl::decl(p);
+    while !p.at(TokenKind::RCurlyBracket) && !p.at_end() {
+        decl::trait_member(p);
+    }
     m.complete(p, SyntaxKind::TraitFnListDef);
 }
+
+pub(crate) fn impl_def(p: &mut Parser) {
+    assert!(p.at(TokenKind::Ident));
+    types::types(p);
+    if p.at(TokenKind::Inherit) {
+        p.bump();
+        if p.at(TokenKind::Ident) || p.at(TokenKind::SelfTypeKw) {
+            types::types(p);
+        }
+    }
+    p.expect(TokenKind::ConstKw);
+    p.expect(TokenKind::ImplKw);
+    p.expect(TokenKind::LCurlyBracket);
+    if !p.at(TokenKind::RCurlyBracket) {
+        impl_item_list_def(p);
+    }
+    p.expect(TokenKind::RCurlyBracket);
+}
+
+fn impl_item_list_def(p: &mut Parser) {
+    let m = p.start();
+    while !p.at(TokenKind::RCurlyBracket) && !p.at_end() {
+        decl::decl(p);
+    }
+    m.complete(p, SyntaxKind::ImplItemListDef);
+}
@@ -9,6 +9,13 @@ pub(super) fn types(p: &mut Parser) {
             if is_path_type_start(token) {
                 let m = p.start();
                 p.bump();
+                // `Fn()`
+                if p.peek() == Some(TokenKind::LRoundBracket)
+                    && p.peek_nth(1) == Some(TokenKind::RRoundBracket)
+                {
+                    p.bump();
+                    p.bump();
+                }
                 m.complete(p, SyntaxKind::Type);
             }
         }
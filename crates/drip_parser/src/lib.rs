@@ -25,6 +25,7 @@ pub fn parse(input: &str) -> Parse {
     sink.finish()
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Parse {
     green_node: GreenNode,
     errors: Vec<ParseError>,
@@ -48,6 +49,10 @@ impl Parse {
     pub fn syntax(&self) -> SyntaxNode {
         SyntaxNode::new_root(self.green_node.clone())
     }
+
+    pub fn errors(&self) -> &[ParseError] {
+        &self.errors
+    }
 }
 
 #[cfg(test)]
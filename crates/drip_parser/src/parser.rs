@@ -6,6 +6,7 @@ use crate::source::Source;
 use drip_lexer::{Token, TokenKind};
 use drip_syntax::SyntaxKind;
 use std::mem;
+use text_size::TextSize;
 
 const RECOVERY_SET: [TokenKind; 1] = [TokenKind::Ident];
 
@@ -13,6 +14,13 @@ pub(crate) struct Parser<'l, 'input> {
     source: Source<'l, 'input>,
     pub(crate) events: Vec<Event>,
     expected_token_kinds: Vec<TokenKind>,
+    /// Whether `Name {` starts a struct literal, which it doesn't in the
+    /// condition of an `if`, where the `{` starts the body instead.
+    struct_literals: bool,
+    /// Where the element of the innermost array literal being parsed
+    /// starts. An array literal right there can't be indexed, since a `[`
+    /// after it starts the next element, as in `[[1, 2][3, 4]]`.
+    array_element: Option<TextSize>,
 }
 
 impl<'l, 'input> Parser<'l, 'input> {
@@ -21,6 +29,8 @@ impl<'l, 'input> Parser<'l, 'input> {
             source,
             events: Vec::new(),
             expected_token_kinds: Vec::new(),
+            struct_literals: true,
+            array_element: None,
         }
     }
 
@@ -72,12 +82,52 @@ impl<'l, 'input> Parser<'l, 'input> {
         }
     }
 
+    /// Runs `f` with struct literals allowed or not.
+    pub(crate) fn with_struct_literals<T>(
+        &mut self,
+        allowed: bool,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        let outer = mem::replace(&mut self.struct_literals, allowed);
+        let result = f(self);
+        self.struct_literals = outer;
+        result
+    }
+
+    pub(crate) fn struct_literals_allowed(&self) -> bool {
+        self.struct_literals
+    }
+
+    /// Runs `f` on an element of an array literal that starts at the next
+    /// token.
+    pub(crate) fn with_array_element<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        let start = self.next_token_start();
+        let outer = mem::replace(&mut self.array_element, start);
+        let result = f(self);
+        self.array_element = outer;
+        result
+    }
+
+    /// Whether an element of the innermost array literal starts at the next
+    /// token.
+    pub(crate) fn at_array_element(&mut self) -> bool {
+        let start = self.next_token_start();
+        start.is_some() && start == self.array_element
+    }
+
+    fn next_token_start(&mut self) -> Option<TextSize> {
+        self.source.peek_token().map(|token| token.range.start())
+    }
+
     pub fn eat(&mut self, kinds: &[TokenKind]) {
         self.source.eat(kinds);
     }
 
     pub fn at(&mut self, kind: TokenKind) -> bool {
-        self.expected_token_kinds.push(kind);
+        // loops check for the same kinds on every iteration
+        if !self.expected_token_kinds.contains(&kind) {
+            self.expected_token_kinds.push(kind);
+        }
         self.peek() == Some(kind)
     }
 
@@ -86,7 +136,12 @@ impl<'l, 'input> Parser<'l, 'input> {
     }
 
     pub fn at_set(&mut self, set: &[TokenKind]) -> bool {
-        self.peek().map_or(false, |k| set.contains(&k))
+        self.peek().is_some_and(|k| set.contains(&k))
+    }
+
+    /// Whether the next token is the first one on its line.
+    pub fn at_line_start(&self) -> bool {
+        self.source.at_line_start()
     }
 
     pub fn at_end(&mut self) -> bool {
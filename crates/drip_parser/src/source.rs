@@ -20,11 +20,8 @@ impl<'l, 'input> Source<'l, 'input> {
         let mut counter = 0;
         let mut nth_counter = 0;
         while nth_counter <= nth {
-            let token = self.tokens.get(self.cursor + counter);
-            if token.is_none() {
-                return None;
-            }
-            if !token.unwrap().is_trivia() {
+            let token = self.tokens.get(self.cursor + counter)?;
+            if !token.is_trivia() {
                 nth_counter += 1;
             }
             counter += 1;
@@ -43,11 +40,19 @@ impl<'l, 'input> Source<'l, 'input> {
         Some(token)
     }
 
-    pub fn peek_token(&mut self) -> Option<&Token> {
+    pub fn peek_token(&mut self) -> Option<&Token<'input>> {
         self.eat_trivia();
         self.peek_token_raw()
     }
 
+    /// Whether the trivia before the next token contains a line break.
+    pub fn at_line_start(&self) -> bool {
+        self.tokens[self.cursor..]
+            .iter()
+            .take_while(|token| token.is_trivia())
+            .any(|token| token.text.contains('\n'))
+    }
+
     pub fn last_token_range(&self) -> Option<TextRange> {
         self.tokens.last().map(|Token { range, .. }| *range)
     }
@@ -56,7 +61,7 @@ impl<'l, 'input> Source<'l, 'input> {
         self.tokens.get(self.cursor).map(|Token { kind, .. }| *kind)
     }
 
-    fn peek_token_raw(&self) -> Option<&Token> {
+    fn peek_token_raw(&self) -> Option<&Token<'input>> {
         self.tokens.get(self.cursor)
     }
 
@@ -87,6 +92,6 @@ impl<'l, 'input> Source<'l, 'input> {
     }
 
     fn at_trivia(&self) -> bool {
-        self.peek_kind_raw().map_or(false, TokenKind::is_trivia)
+        self.peek_kind_raw().is_some_and(TokenKind::is_trivia)
     }
 }
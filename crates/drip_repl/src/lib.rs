@@ -1,7 +1,463 @@
+//! An interactive read-eval-print loop for Drip.
+//!
+//! Every input the REPL accepts is kept, and each new input is checked as if
+//! it was written at the end of everything accepted before it, so
+//! definitions carry over from one input to the next. Only the new input is
+//! run, with the values the earlier ones left their bindings with. An input
+//! with an error is reported and then forgotten, though anything it changed
+//! before a runtime error stays changed.
+//!
+//! When an input ends in an expression, its value and type are printed.
+//! Ranges in errors are relative to the start of the input they point into.
+//! Lines starting with `:` are commands, listed by `:help`.
+
+use drip_ast::AstNode;
+use drip_hir::db::{FileId, HirDatabase, SourceDatabase};
+use drip_hir::flow::Severity;
+use drip_hir::resolve::BodyId;
+use drip_hir::{Database, Stmt};
+use drip_interp::{Error, RuntimeError, Session, Value};
+use drip_lexer::{Lexer, TokenKind};
+use rustyline::error::ReadlineError;
+use rustyline::Editor;
+use std::fs;
+use std::io::{self, Write};
+use text_size::{TextRange, TextSize};
+
+const HELP: &str = "\
+:type <expr>     print the type of an expression without running it
+:ast <input>     print the syntax tree of an input
+:hir <input>     print the lowered form of an input
+:tokens <input>  print the tokens of an input
+:load <file>     run a file as if it was typed in
+:reset           forget every definition
+:quit            leave the REPL
+";
+
+/// Whether the REPL keeps reading after an input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    Continue,
+    Quit,
+}
+
+/// The inputs accepted so far, the database they're checked in and the
+/// bindings running them left behind.
+#[derive(Default)]
+pub struct Repl {
+    db: Database,
+    session: Session,
+    /// Every accepted input, each followed by a newline.
+    source: String,
+    /// Where each accepted input starts in `source`.
+    starts: Vec<usize>,
+}
+
+impl Repl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Handles an input, which is either a command or code, writing what it
+    /// prints to `out`.
+    pub fn eval(&mut self, input: &str, out: &mut dyn Write) -> io::Result<Control> {
+        let input = input.trim_end();
+        let (command, arg) = match input.strip_prefix(':') {
+            Some(command) => {
+                let (command, arg) = command
+                    .split_once(char::is_whitespace)
+                    .unwrap_or((command, ""));
+                (command, arg.trim())
+            }
+            None => return self.eval_code(input, out).map(|()| Control::Continue),
+        };
+
+        match command {
+            "type" | "t" => self.print_type(arg, out)?,
+            "ast" => writeln!(out, "{}", drip_parser::parse(arg).debug_tree())?,
+            "hir" => {
+                let root = drip_ast::Root::cast(drip_parser::parse(arg).syntax()).unwrap();
+                let (tree, body) = drip_hir::lower(root);
+                write!(out, "{}", drip_hir::pretty::render(&tree, &body))?;
+            }
+            "tokens" => {
+                for token in Lexer::new(arg) {
+                    writeln!(out, "{:?}@{:?} {:?}", token.kind, token.range, token.text)?;
+                }
+            }
+            "load" => match fs::read_to_string(arg) {
+                Ok(text) => self.eval_code(&text, out)?,
+                Err(error) => writeln!(out, "can't read `{}`: {}", arg, error)?,
+            },
+            "reset" => *self = Self::default(),
+            "quit" | "q" => return Ok(Control::Quit),
+            "help" | "h" => write!(out, "{}", HELP)?,
+            _ => writeln!(out, "unknown command `:{}`, see `:help`", command)?,
+        }
+
+        Ok(Control::Continue)
+    }
+
+    fn eval_code(&mut self, input: &str, out: &mut dyn Write) -> io::Result<()> {
+        if input.trim().is_empty() {
+            return Ok(());
+        }
+
+        let start = self.source.len();
+        let text = format!("{}{}\n", self.source, input);
+        if !self.check(&text, out)? {
+            return Ok(());
+        }
+
+        let value = match self.session.eval(&self.db, FileId(0), out) {
+            Ok(value) => value,
+            Err(Error::Runtime(error)) => {
+                self.starts.push(start);
+                let error = self.relative_error(&error);
+                self.starts.pop();
+                return writeln!(out, "{}", error);
+            }
+            // checking found no errors
+            Err(Error::Diagnostics(_)) => unreachable!(),
+        };
+
+        if let Some(ty) = self.tail_type(start) {
+            if !matches!(&value, Value::Tuple(values) if values.is_empty()) {
+                match &value {
+                    Value::Str(s) => writeln!(out, "{:?}: {}", s, ty)?,
+                    _ => writeln!(out, "{}: {}", value, ty)?,
+                }
+            }
+        }
+
+        self.source = text;
+        self.starts.push(start);
+        Ok(())
+    }
+
+    fn print_type(&mut self, expr: &str, out: &mut dyn Write) -> io::Result<()> {
+        let start = self.source.len();
+        let text = format!("{}{}\n", self.source, expr);
+        if self.check(&text, out)? {
+            match self.tail_type(start) {
+                Some(ty) => writeln!(out, "{}", ty)?,
+                None => writeln!(out, "`{}` isn't an expression", expr)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Puts `text`, which is the accepted source followed by a new input,
+    /// into the database and reports the input's diagnostics. Returns
+    /// whether it had no errors.
+    fn check(&mut self, text: &str, out: &mut dyn Write) -> io::Result<bool> {
+        self.db.set_file_text(FileId(0), text.into());
+        self.db.set_root_file(FileId(0));
+
+        let start = self.source.len();
+        self.starts.push(start);
+        let mut failed = false;
+        for diagnostic in self.db.diagnostics(FileId(0)).iter() {
+            let is_error = diagnostic.severity == Severity::Error;
+            // warnings about earlier inputs were shown when they were entered
+            if is_error || usize::from(diagnostic.range.start()) >= start {
+                let mut diagnostic = diagnostic.clone();
+                diagnostic.range = self.relative(diagnostic.range);
+                for (range, _) in &mut diagnostic.related {
+                    *range = self.relative(*range);
+                }
+                writeln!(out, "{}", diagnostic)?;
+            }
+            failed |= is_error;
+        }
+        self.starts.pop();
+
+        Ok(!failed)
+    }
+
+    /// The type of the last top-level statement if it's an expression that
+    /// starts at or after `start`.
+    fn tail_type(&self, start: usize) -> Option<String> {
+        let lowered = self.db.lower_file(FileId(0));
+        let root = &lowered.root;
+        let stmt = *root.top_level.last()?;
+        let expr = match root.stmts[stmt] {
+            Stmt::Expr(expr) => expr,
+            _ => return None,
+        };
+        let range = root
+            .arenas
+            .source_map()
+            .stmt_syntax(stmt)?
+            .syntax_node_ptr()
+            .range();
+        if usize::from(range.start()) < start {
+            return None;
+        }
+
+        let inference = self.db.inference(FileId(0));
+        Some(inference.body(BodyId::Root)[expr].to_string())
+    }
+
+    /// `range`, relative to the start of the input it's in.
+    fn relative(&self, range: TextRange) -> TextRange {
+        let start = usize::from(range.start());
+        let input = self
+            .starts
+            .iter()
+            .rev()
+            .find(|input| **input <= start)
+            .copied()
+            .unwrap_or(0);
+        range - TextSize::from(input as u32)
+    }
+
+    fn relative_error(&self, error: &RuntimeError) -> RuntimeError {
+        let (first, rest) = error.trace().split_first().unwrap();
+        let mut relative = RuntimeError::new(
+            error.kind().clone(),
+            self.relative(error.range()),
+            first.function.clone(),
+        );
+        for frame in rest {
+            relative = relative.called_from(frame.function.clone(), self.relative(frame.range));
+        }
+        relative
+    }
+}
+
+/// Whether `input` can be evaluated, or is still missing closing brackets
+/// and should continue on the next line.
+pub fn is_complete(input: &str) -> bool {
+    let mut depth = 0;
+    for token in Lexer::new(input) {
+        match token.kind {
+            TokenKind::LCurlyBracket | TokenKind::LRoundBracket | TokenKind::LSquareBracket => {
+                depth += 1
+            }
+            TokenKind::RCurlyBracket | TokenKind::RRoundBracket | TokenKind::RSquareBracket => {
+                depth -= 1
+            }
+            _ => {}
+        }
+    }
+    depth <= 0
+}
+
+/// Runs the REPL on the terminal until `:quit` or the end of input.
+pub fn run() -> io::Result<()> {
+    let mut repl = Repl::new();
+    let mut editor = Editor::<()>::new();
+    let mut stdout = io::stdout();
+    let mut input = String::new();
+
+    loop {
+        let prompt = if input.is_empty() { "→ " } else { "… " };
+        let line = match editor.readline(prompt) {
+            Ok(line) => line,
+            // ctrl-c throws away the input that's being written
+            Err(ReadlineError::Interrupted) => {
+                input.clear();
+                continue;
+            }
+            Err(ReadlineError::Eof) => return Ok(()),
+            Err(ReadlineError::Io(error)) => return Err(error),
+            Err(error) => return Err(io::Error::other(error)),
+        };
+
+        input.push_str(&line);
+        input.push('\n');
+        if !is_complete(&input) {
+            continue;
+        }
+
+        editor.add_history_entry(input.trim_end());
+        let control = repl.eval(&input, &mut stdout)?;
+        stdout.flush()?;
+        input.clear();
+        if control == Control::Quit {
+            return Ok(());
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use expect_test::{expect, Expect};
+
+    /// Feeds each of `inputs` to a new REPL and checks everything it
+    /// printed, with each input echoed after a prompt.
+    fn check(inputs: &[&str], expect: Expect) {
+        let mut repl = Repl::new();
+        let mut out = Vec::new();
+        for input in inputs {
+            writeln!(out, "→ {}", input).unwrap();
+            if repl.eval(input, &mut out).unwrap() == Control::Quit {
+                break;
+            }
+        }
+        expect.assert_eq(&String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn keep_definitions_and_print_values() {
+        check(
+            &[
+                "x := 40",
+                "x += 2",
+                "x",
+                "double :: (n: i32) -> i32 { n * 2 }",
+                "double(x)",
+                "\"a\" + \"b\"",
+                "(x, 1.5, [true])",
+                "println(\"hi\")",
+                "y := double(1)\nprintln(y)",
+                "y",
+            ],
+            expect![[r#"
+                → x := 40
+                → x += 2
+                → x
+                42: i32
+                → double :: (n: i32) -> i32 { n * 2 }
+                → double(x)
+                84: i32
+                → "a" + "b"
+                "ab": string
+                → (x, 1.5, [true])
+                (42, 1.5, [true]): (i32, f64, [bool])
+                → println("hi")
+                hi
+                → y := double(1)
+                println(y)
+                2
+                → y
+                2: i32
+            "#]],
+        );
+    }
+
+    #[test]
+    fn run_only_the_new_input() {
+        check(
+            &[
+                "count := 0",
+                "count += 1\nprintln(\"count is {count}\")",
+                "count",
+                "count += 1",
+                "count",
+            ],
+            expect![[r#"
+                → count := 0
+                → count += 1
+                println("count is {count}")
+                count is 1
+                → count
+                1: i32
+                → count += 1
+                → count
+                2: i32
+            "#]],
+        );
+    }
+
+    #[test]
+    fn forget_inputs_with_errors() {
+        check(
+            &[
+                "a :: 1",
+                "a = 2",
+                "b := a + true",
+                "b",
+                "arr :: [1, 2]",
+                "println(\"before\")\narr[2]",
+                "f :: (i: i32) -> i32 { arr[i] }",
+                "f(5)",
+                "a",
+            ],
+            expect![[r#"
+                → a :: 1
+                → a = 2
+                error at 0..2: cannot assign to immutable binding `a`
+                    note at 0..6: defined with `::` here
+                → b := a + true
+                error at 9..14: expected `i32`, found `bool`
+                → b
+                error at 0..2: cannot find `b` in this scope
+                → arr :: [1, 2]
+                → println("before")
+                arr[2]
+                before
+                error at 18..25: index 2 is out of bounds for an array of length 2
+                    in top-level code at 18..25
+                → f :: (i: i32) -> i32 { arr[i] }
+                → f(5)
+                error at 23..30: index 5 is out of bounds for an array of length 2
+                    in `f` at 23..30
+                    in top-level code at 0..5
+                → a
+                1: i32
+            "#]],
+        );
+    }
+
+    #[test]
+    fn run_commands() {
+        check(
+            &[
+                "n :: 3",
+                ":type n * 2",
+                ":type n := 1",
+                ":tokens n+1",
+                ":ast n+1",
+                ":hir add :: (a: i32) -> i32 { a + n }",
+                ":reset",
+                "n",
+                ":frobnicate",
+                ":quit",
+                "n",
+            ],
+            expect![[r#"
+                → n :: 3
+                → :type n * 2
+                i32
+                → :type n := 1
+                `n := 1` isn't an expression
+                → :tokens n+1
+                Ident@0..1 "n"
+                Plus@1..2 "+"
+                Number@2..3 "1"
+                → :ast n+1
+                Root@0..3
+                  InfixExpr@0..3
+                    VariableRef@0..1
+                      Ident@0..1 "n"
+                    Plus@1..2 "+"
+                    Literal@2..3
+                      Number@2..3 "1"
+                → :hir add :: (a: i32) -> i32 { a + n }
+                fn add(a: i32) -> i32
+                  Add
+                    ref a
+                    ref n
+                → :reset
+                → n
+                error at 0..2: cannot find `n` in this scope
+                → :frobnicate
+                unknown command `:frobnicate`, see `:help`
+                → :quit
+            "#]],
+        );
+    }
+
     #[test]
-    fn it_works() {
-        assert_eq!(2 + 2, 4);
+    fn continue_unfinished_input() {
+        assert!(is_complete("x := 1"));
+        assert!(!is_complete("f :: () {"));
+        assert!(!is_complete("f :: () {\n    g(1,"));
+        assert!(!is_complete("xs := [1,"));
+        assert!(is_complete("f :: () {\n    g(1, 2)\n}"));
+        assert!(is_complete("s :: \"{\""));
     }
 }
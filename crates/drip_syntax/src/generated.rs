@@ -0,0 +1,228 @@
+//! Generated by `cargo run -p drip_codegen`, do not edit by hand.
+
+use drip_lexer::TokenKind;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[repr(u16)]
+pub enum SyntaxKind {
+    Tombstone,
+    Error,
+
+    // tokens
+    Comment,
+    Whitespace,
+    Ident,
+    FnKw,
+    UseKw,
+    ExternKw,
+    StructKw,
+    TraitKw,
+    ImplKw,
+    IfKw,
+    ElseKw,
+    LoopKw,
+    WhileKw,
+    ForKw,
+    InKw,
+    BreakKw,
+    ContinueKw,
+    MatchKw,
+    TrueKw,
+    FalseKw,
+    ConstKw,
+    VariableKw,
+    TypeKw,
+    SelfVarKw,
+    SelfTypeKw,
+    Number,
+    Str,
+    Bang,
+    Quest,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Circumflex,
+    Circumflex2,
+    And,
+    And2,
+    Pipe,
+    Pipe2,
+    Equals,
+    Equals2,
+    NotEquals,
+    LessEquals,
+    GreaterEquals,
+    PlusEquals,
+    MinusEquals,
+    StarEquals,
+    SlashEquals,
+    Percent,
+    Dollar,
+    Hashtag,
+    At,
+    Underscore,
+    Dot,
+    Dot2,
+    Dot3,
+    Comma,
+    Colon,
+    Semicolon,
+    Quote,
+    SingleQuote,
+    Grave,
+    Arrow,
+    FatArrow,
+    Inherit,
+    LCurlyBracket,
+    RCurlyBracket,
+    LRoundBracket,
+    RRoundBracket,
+    LAngledBracket,
+    RAngledBracket,
+    LSquareBracket,
+    RSquareBracket,
+
+    // nodes
+    Root,
+    VariableDef,
+    ConstDef,
+    AssignDef,
+    FnDef,
+    StructDef,
+    TraitDef,
+    ImplDef,
+    InfixExpr,
+    PrefixExpr,
+    TernaryExpr,
+    Literal,
+    RoundBracketExpr,
+    TupleExpr,
+    ArrayExpr,
+    CallExpr,
+    MethodCallExpr,
+    FieldExpr,
+    IndexExpr,
+    StructExpr,
+    ClosureExpr,
+    PlaceholderExpr,
+    VariableRef,
+    PathExpr,
+    BlockExpr,
+    IfExpr,
+    LoopExpr,
+    WhileExpr,
+    ForExpr,
+    BreakExpr,
+    ContinueExpr,
+    MatchExpr,
+    NameList,
+    IdentPat,
+    ArgList,
+    StructExprField,
+    FnParamListDef,
+    NamedArg,
+    Type,
+    MatchArmList,
+    MatchArm,
+    WildcardPat,
+    LiteralPat,
+    RangePat,
+    TuplePat,
+    StructPat,
+    FieldPat,
+    FnReturnDef,
+    FnBodyDef,
+    FnParamDef,
+    FnReturnTypeListDef,
+    FnReturnTypeDef,
+    StructFieldListDef,
+    StructFieldDef,
+    TraitListsDef,
+    TraitTypeListDef,
+    TraitFnListDef,
+    TraitTypeDef,
+    ImplItemListDef,
+}
+
+impl From<TokenKind> for SyntaxKind {
+    fn from(token: TokenKind) -> Self {
+        match token {
+            TokenKind::Error => SyntaxKind::Error,
+            TokenKind::Comment => SyntaxKind::Comment,
+            TokenKind::Whitespace => SyntaxKind::Whitespace,
+            TokenKind::Ident => SyntaxKind::Ident,
+            TokenKind::FnKw => SyntaxKind::FnKw,
+            TokenKind::UseKw => SyntaxKind::UseKw,
+            TokenKind::ExternKw => SyntaxKind::ExternKw,
+            TokenKind::StructKw => SyntaxKind::StructKw,
+            TokenKind::TraitKw => SyntaxKind::TraitKw,
+            TokenKind::ImplKw => SyntaxKind::ImplKw,
+            TokenKind::IfKw => SyntaxKind::IfKw,
+            TokenKind::ElseKw => SyntaxKind::ElseKw,
+            TokenKind::LoopKw => SyntaxKind::LoopKw,
+            TokenKind::WhileKw => SyntaxKind::WhileKw,
+            TokenKind::ForKw => SyntaxKind::ForKw,
+            TokenKind::InKw => SyntaxKind::InKw,
+            TokenKind::BreakKw => SyntaxKind::BreakKw,
+            TokenKind::ContinueKw => SyntaxKind::ContinueKw,
+            TokenKind::MatchKw => SyntaxKind::MatchKw,
+            TokenKind::TrueKw => SyntaxKind::TrueKw,
+            TokenKind::FalseKw => SyntaxKind::FalseKw,
+            TokenKind::ConstKw => SyntaxKind::ConstKw,
+            TokenKind::VariableKw => SyntaxKind::VariableKw,
+            TokenKind::TypeKw => SyntaxKind::TypeKw,
+            TokenKind::SelfVarKw => SyntaxKind::SelfVarKw,
+            TokenKind::SelfTypeKw => SyntaxKind::SelfTypeKw,
+            TokenKind::Number => SyntaxKind::Number,
+            TokenKind::Str => SyntaxKind::Str,
+            TokenKind::Bang => SyntaxKind::Bang,
+            TokenKind::Quest => SyntaxKind::Quest,
+            TokenKind::Plus => SyntaxKind::Plus,
+            TokenKind::Minus => SyntaxKind::Minus,
+            TokenKind::Star => SyntaxKind::Star,
+            TokenKind::Slash => SyntaxKind::Slash,
+            TokenKind::Circumflex => SyntaxKind::Circumflex,
+            TokenKind::Circumflex2 => SyntaxKind::Circumflex2,
+            TokenKind::And => SyntaxKind::And,
+            TokenKind::And2 => SyntaxKind::And2,
+            TokenKind::Pipe => SyntaxKind::Pipe,
+            TokenKind::Pipe2 => SyntaxKind::Pipe2,
+            TokenKind::Equals => SyntaxKind::Equals,
+            TokenKind::Equals2 => SyntaxKind::Equals2,
+            TokenKind::NotEquals => SyntaxKind::NotEquals,
+            TokenKind::LessEquals => SyntaxKind::LessEquals,
+            TokenKind::GreaterEquals => SyntaxKind::GreaterEquals,
+            TokenKind::PlusEquals => SyntaxKind::PlusEquals,
+            TokenKind::MinusEquals => SyntaxKind::MinusEquals,
+            TokenKind::StarEquals => SyntaxKind::StarEquals,
+            TokenKind::SlashEquals => SyntaxKind::SlashEquals,
+            TokenKind::Percent => SyntaxKind::Percent,
+            TokenKind::Dollar => SyntaxKind::Dollar,
+            TokenKind::Hashtag => SyntaxKind::Hashtag,
+            TokenKind::At => SyntaxKind::At,
+            TokenKind::Underscore => SyntaxKind::Underscore,
+            TokenKind::Dot => SyntaxKind::Dot,
+            TokenKind::Dot2 => SyntaxKind::Dot2,
+            TokenKind::Dot3 => SyntaxKind::Dot3,
+            TokenKind::Comma => SyntaxKind::Comma,
+            TokenKind::Colon => SyntaxKind::Colon,
+            TokenKind::Semicolon => SyntaxKind::Semicolon,
+            TokenKind::Quote => SyntaxKind::Quote,
+            TokenKind::SingleQuote => SyntaxKind::SingleQuote,
+            TokenKind::Grave => SyntaxKind::Grave,
+            TokenKind::Arrow => SyntaxKind::Arrow,
+            TokenKind::FatArrow => SyntaxKind::FatArrow,
+            TokenKind::Inherit => SyntaxKind::Inherit,
+            TokenKind::LCurlyBracket => SyntaxKind::LCurlyBracket,
+            TokenKind::RCurlyBracket => SyntaxKind::RCurlyBracket,
+            TokenKind::LRoundBracket => SyntaxKind::LRoundBracket,
+            TokenKind::RRoundBracket => SyntaxKind::RRoundBracket,
+            TokenKind::LAngledBracket => SyntaxKind::LAngledBracket,
+            TokenKind::RAngledBracket => SyntaxKind::RAngledBracket,
+            TokenKind::LSquareBracket => SyntaxKind::LSquareBracket,
+            TokenKind::RSquareBracket => SyntaxKind::RSquareBracket,
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! Compares the VM with the tree-walking interpreter on programs that spend
+//! their time in loops, arithmetic and calls.
+//!
+//! Run with `cargo bench -p drip_vm`. Each program is checked once up front,
+//! so the measurements only cover running it.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use drip_hir::db::{FileId, SourceDatabase};
+use drip_hir::Database;
+use std::io;
+
+const PROGRAMS: &[(&str, &str)] = &[
+    (
+        "sum_loop",
+        "sum := 0
+i := 0
+while i < 50000 {
+    sum += i
+    i += 1
+}",
+    ),
+    (
+        "nested_arithmetic",
+        "main :: () {
+    total := 0
+    for i in 0..300 {
+        for j in 0..300 {
+            total += (i * 3 + j) / 7 - (i - j) * 2
+        }
+    }
+}",
+    ),
+    (
+        "fib",
+        "fib :: (n: i32) -> i32 {
+    if n < 2 { n } else { fib(n - 1) + fib(n - 2) }
+}
+fib(20)",
+    ),
+    (
+        "array_fill_and_sum",
+        "main :: () {
+    xs := [0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    total := 0
+    for round in 0..5000 {
+        i := 0
+        while i < 10 {
+            xs[i] = xs[i] + i
+            i += 1
+        }
+        for x in xs { total += x }
+    }
+}",
+    ),
+];
+
+fn database(input: &str) -> Database {
+    let mut db = Database::default();
+    db.set_file_text(FileId(0), input.into());
+    db.set_root_file(FileId(0));
+    db
+}
+
+fn vm_vs_interp(c: &mut Criterion) {
+    let mut group = c.benchmark_group("vm_vs_interp");
+    group.sample_size(10);
+
+    for (name, input) in PROGRAMS {
+        let db = database(input);
+        let program = drip_vm::compile(&db, FileId(0)).unwrap();
+
+        group.bench_with_input(BenchmarkId::new("interp", name), &db, |b, db| {
+            b.iter(|| drip_interp::run(db, FileId(0), &mut io::sink()).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("vm", name), &program, |b, program| {
+            b.iter(|| drip_vm::execute(program, &mut io::sink()).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, vm_vs_interp);
+criterion_main!(benches);
@@ -0,0 +1,505 @@
+//! The compiled form of a program, and its disassembly.
+//!
+//! Every function, closure and the file's top-level code is compiled to a
+//! [`Function`] of register-based instructions. A function's registers are
+//! numbered from zero and start with its parameters; the bindings it owns
+//! come next, and the registers above them hold temporaries. Literals live
+//! in the program's constant pool.
+
+use drip_hir::infer::IntTy;
+use drip_hir::resolve::Builtin;
+use drip_hir::{BinaryOp, StructId};
+use smol_str::SmolStr;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Formatter;
+use text_size::TextRange;
+
+pub type Reg = u16;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Instr {
+    LoadConst {
+        dst: Reg,
+        constant: u32,
+    },
+    LoadUnit {
+        dst: Reg,
+    },
+    LoadBool {
+        dst: Reg,
+        value: bool,
+    },
+    LoadFunction {
+        dst: Reg,
+        function: u32,
+    },
+    LoadBuiltin {
+        dst: Reg,
+        builtin: Builtin,
+    },
+    Move {
+        dst: Reg,
+        src: Reg,
+    },
+    GetGlobal {
+        dst: Reg,
+        global: u32,
+    },
+    TakeGlobal {
+        dst: Reg,
+        global: u32,
+    },
+    SetGlobal {
+        global: u32,
+        src: Reg,
+    },
+    /// Moves the value of `reg` into a new cell, for a binding that a
+    /// closure captures.
+    Box {
+        reg: Reg,
+    },
+    GetCell {
+        dst: Reg,
+        cell: Reg,
+    },
+    /// Moves the value out of the cell in `cell`, leaving `()` behind. This
+    /// and the other `Take` instructions start assignments to part of a
+    /// value, so that the value isn't shared while it's changed.
+    TakeCell {
+        dst: Reg,
+        cell: Reg,
+    },
+    SetCell {
+        cell: Reg,
+        src: Reg,
+    },
+    GetUpvalue {
+        dst: Reg,
+        upvalue: u32,
+    },
+    TakeUpvalue {
+        dst: Reg,
+        upvalue: u32,
+    },
+    SetUpvalue {
+        upvalue: u32,
+        src: Reg,
+    },
+    /// Creates a closure of `function`, capturing what its
+    /// [`Function::captures`] lists.
+    Closure {
+        dst: Reg,
+        function: u32,
+    },
+    /// `dst = lhs op rhs`, checking that an integer result fits in `int`.
+    Binary {
+        op: BinaryOp,
+        dst: Reg,
+        lhs: Reg,
+        rhs: Reg,
+        int: Option<IntTy>,
+    },
+    Neg {
+        dst: Reg,
+        src: Reg,
+        int: Option<IntTy>,
+    },
+    Jump {
+        target: u32,
+    },
+    JumpIfFalse {
+        cond: Reg,
+        target: u32,
+    },
+    /// Increments `counter` and puts the `counter`th element of the array or
+    /// range in `iterable` in `dst`, or jumps to `exit` if there are no
+    /// elements left.
+    ForNext {
+        iterable: Reg,
+        counter: Reg,
+        dst: Reg,
+        exit: u32,
+    },
+    /// Calls `callee` with the `argc` registers starting at `args`.
+    Call {
+        dst: Reg,
+        callee: Reg,
+        args: Reg,
+        argc: u8,
+    },
+    /// Calls the method named by the string constant `name` on the receiver
+    /// in `args`, followed by `argc` arguments. The method is looked up on
+    /// the struct the receiver is, then `method` is tried, then a field
+    /// holding a function. A method's final `self` is written back to `args`.
+    CallMethod {
+        dst: Reg,
+        name: u32,
+        method: Option<u32>,
+        args: Reg,
+        argc: u8,
+    },
+    /// Calls `function`, the method behind an operator on a struct, with the
+    /// receiver in `args` followed by `argc` arguments. The method's final
+    /// `self` is written back to `args`.
+    CallOperator {
+        dst: Reg,
+        function: u32,
+        args: Reg,
+        argc: u8,
+    },
+    Return {
+        src: Reg,
+    },
+    MakeArray {
+        dst: Reg,
+        start: Reg,
+        len: u16,
+    },
+    MakeTuple {
+        dst: Reg,
+        start: Reg,
+        len: u16,
+    },
+    MakeRange {
+        dst: Reg,
+        start: Reg,
+        end: Reg,
+        step: Option<Reg>,
+    },
+    /// Creates a struct of `shape` from the fields in the registers starting
+    /// at `start`, in the shape's order.
+    MakeStruct {
+        dst: Reg,
+        shape: u32,
+        start: Reg,
+    },
+    /// Joins the `len` registers starting at `start` into a string.
+    Concat {
+        dst: Reg,
+        start: Reg,
+        len: u16,
+    },
+    GetField {
+        dst: Reg,
+        base: Reg,
+        name: u32,
+    },
+    TakeField {
+        dst: Reg,
+        base: Reg,
+        name: u32,
+    },
+    SetField {
+        base: Reg,
+        name: u32,
+        src: Reg,
+    },
+    GetIndex {
+        dst: Reg,
+        base: Reg,
+        index: Reg,
+    },
+    TakeIndex {
+        dst: Reg,
+        base: Reg,
+        index: Reg,
+    },
+    SetIndex {
+        base: Reg,
+        index: Reg,
+        src: Reg,
+    },
+    /// Reads an element of the tuple in `base`.
+    GetElement {
+        dst: Reg,
+        base: Reg,
+        index: u16,
+    },
+    /// Whether `src` is a tuple of `len` elements.
+    IsTuple {
+        dst: Reg,
+        src: Reg,
+        len: u16,
+    },
+    /// Whether `src` is a struct named by the string constant `name`.
+    IsStruct {
+        dst: Reg,
+        src: Reg,
+        name: u32,
+    },
+    /// Fails with a `...` error.
+    Placeholder,
+    /// Fails because no arm of a `match` matched.
+    NoMatch,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Constant {
+    Int(i128),
+    Float(f64),
+    Str(SmolStr),
+}
+
+/// Where a closure gets one of its upvalues from, in the function that
+/// creates it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capture {
+    /// A register holding a cell.
+    Local(Reg),
+    Upvalue(u32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    /// What the function is called in stack traces, like ``"`main`"``.
+    pub name: String,
+    pub params: usize,
+    pub registers: usize,
+    pub code: Vec<Instr>,
+    /// The source range of each instruction.
+    pub ranges: Vec<TextRange>,
+    /// The upvalues of a closure.
+    pub captures: Vec<Capture>,
+}
+
+/// The fields of a struct, in the order its values store them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shape {
+    pub id: StructId,
+    pub name: SmolStr,
+    pub fields: Vec<SmolStr>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Program {
+    pub functions: Vec<Function>,
+    pub constants: Vec<Constant>,
+    pub shapes: Vec<Shape>,
+    /// The method each struct has for each name that's called, keyed by
+    /// the string constant of the name.
+    pub methods: HashMap<(StructId, u32), u32>,
+    /// The names of the globals, which are the bindings made by the file's
+    /// top-level statements.
+    pub globals: Vec<SmolStr>,
+    /// The top-level code.
+    pub entry: u32,
+    pub main: Option<u32>,
+}
+
+impl fmt::Display for Constant {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Int(n) => write!(f, "{}", n),
+            Self::Float(n) => write!(f, "{:?}", n),
+            Self::Str(s) => write!(f, "{:?}", s),
+        }
+    }
+}
+
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "constants:")?;
+        for (i, constant) in self.constants.iter().enumerate() {
+            writeln!(f, "    #{:<4} {}", i, constant)?;
+        }
+
+        for (i, function) in self.functions.iter().enumerate() {
+            writeln!(
+                f,
+                "\nfunction {} {} ({} params, {} registers):",
+                i, function.name, function.params, function.registers
+            )?;
+            for capture in &function.captures {
+                match capture {
+                    Capture::Local(reg) => writeln!(f, "    capture r{}", reg)?,
+                    Capture::Upvalue(upvalue) => writeln!(f, "    capture u{}", upvalue)?,
+                }
+            }
+            for (ip, (instr, range)) in function.code.iter().zip(&function.ranges).enumerate() {
+                let text = self.disassemble(instr);
+                writeln!(
+                    f,
+                    "    {:04}  {:<36} {}..{}",
+                    ip,
+                    text,
+                    u32::from(range.start()),
+                    u32::from(range.end())
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Program {
+    fn disassemble(&self, instr: &Instr) -> String {
+        let constant = |i: &u32| &self.constants[*i as usize];
+        let int = |int: &Option<IntTy>| int.map_or(String::new(), |ty| format!(" ({})", ty.name()));
+
+        match instr {
+            Instr::LoadConst { dst, constant: i } => {
+                format!("load_const r{}, #{} ; {}", dst, i, constant(i))
+            }
+            Instr::LoadUnit { dst } => format!("load_unit r{}", dst),
+            Instr::LoadBool { dst, value } => format!("load_bool r{}, {}", dst, value),
+            Instr::LoadFunction { dst, function } => format!(
+                "load_function r{}, {} ; {}",
+                dst, function, self.functions[*function as usize].name
+            ),
+            Instr::LoadBuiltin { dst, builtin } => {
+                format!("load_builtin r{}, {}", dst, builtin.name())
+            }
+            Instr::Move { dst, src } => format!("move r{}, r{}", dst, src),
+            Instr::GetGlobal { dst, global } => format!(
+                "get_global r{}, g{} ; {}",
+                dst, global, self.globals[*global as usize]
+            ),
+            Instr::SetGlobal { global, src } => format!(
+                "set_global g{}, r{} ; {}",
+                global, src, self.globals[*global as usize]
+            ),
+            Instr::TakeGlobal { dst, global } => format!(
+                "take_global r{}, g{} ; {}",
+                dst, global, self.globals[*global as usize]
+            ),
+            Instr::TakeCell { dst, cell } => format!("take_cell r{}, r{}", dst, cell),
+            Instr::TakeUpvalue { dst, upvalue } => {
+                format!("take_upvalue r{}, u{}", dst, upvalue)
+            }
+            Instr::TakeField { dst, base, name } => {
+                format!("take_field r{}, r{}, {}", dst, base, constant(name))
+            }
+            Instr::TakeIndex { dst, base, index } => {
+                format!("take_index r{}, r{}, r{}", dst, base, index)
+            }
+            Instr::IsTuple { dst, src, len } => format!("is_tuple r{}, r{}, {}", dst, src, len),
+            Instr::Box { reg } => format!("box r{}", reg),
+            Instr::GetCell { dst, cell } => format!("get_cell r{}, r{}", dst, cell),
+            Instr::SetCell { cell, src } => format!("set_cell r{}, r{}", cell, src),
+            Instr::GetUpvalue { dst, upvalue } => format!("get_upvalue r{}, u{}", dst, upvalue),
+            Instr::SetUpvalue { upvalue, src } => format!("set_upvalue u{}, r{}", upvalue, src),
+            Instr::Closure { dst, function } => format!("closure r{}, {}", dst, function),
+            Instr::Binary {
+                op,
+                dst,
+                lhs,
+                rhs,
+                int: ty,
+            } => format!(
+                "{} r{}, r{}, r{}{}",
+                binary_name(*op),
+                dst,
+                lhs,
+                rhs,
+                int(ty)
+            ),
+            Instr::Neg { dst, src, int: ty } => format!("neg r{}, r{}{}", dst, src, int(ty)),
+            Instr::Jump { target } => format!("jump {:04}", target),
+            Instr::JumpIfFalse { cond, target } => {
+                format!("jump_if_false r{}, {:04}", cond, target)
+            }
+            Instr::ForNext {
+                iterable,
+                counter,
+                dst,
+                exit,
+            } => format!(
+                "for_next r{}, r{}, r{}, {:04}",
+                dst, iterable, counter, exit
+            ),
+            Instr::Call {
+                dst,
+                callee,
+                args,
+                argc,
+            } => format!("call r{}, r{}, r{}, {}", dst, callee, args, argc),
+            Instr::CallMethod {
+                dst,
+                name,
+                method,
+                args,
+                argc,
+            } => {
+                let method = method.map_or(String::new(), |method| format!(" -> {}", method));
+                format!(
+                    "call_method r{}, {}, r{}, {}{}",
+                    dst,
+                    constant(name),
+                    args,
+                    argc,
+                    method
+                )
+            }
+            Instr::CallOperator {
+                dst,
+                function,
+                args,
+                argc,
+            } => format!(
+                "call_operator r{}, {}, r{}, {} ; {}",
+                dst, function, args, argc, self.functions[*function as usize].name
+            ),
+            Instr::Return { src } => format!("return r{}", src),
+            Instr::MakeArray { dst, start, len } => {
+                format!("make_array r{}, r{}, {}", dst, start, len)
+            }
+            Instr::MakeTuple { dst, start, len } => {
+                format!("make_tuple r{}, r{}, {}", dst, start, len)
+            }
+            Instr::MakeRange {
+                dst,
+                start,
+                end,
+                step: None,
+            } => format!("make_range r{}, r{}, r{}", dst, start, end),
+            Instr::MakeRange {
+                dst,
+                start,
+                end,
+                step: Some(step),
+            } => format!("make_range r{}, r{}, r{}, r{}", dst, start, end, step),
+            Instr::MakeStruct { dst, shape, start } => format!(
+                "make_struct r{}, {}, r{}",
+                dst, self.shapes[*shape as usize].name, start
+            ),
+            Instr::Concat { dst, start, len } => format!("concat r{}, r{}, {}", dst, start, len),
+            Instr::GetField { dst, base, name } => {
+                format!("get_field r{}, r{}, {}", dst, base, constant(name))
+            }
+            Instr::SetField { base, name, src } => {
+                format!("set_field r{}, {}, r{}", base, constant(name), src)
+            }
+            Instr::GetIndex { dst, base, index } => {
+                format!("get_index r{}, r{}, r{}", dst, base, index)
+            }
+            Instr::SetIndex { base, index, src } => {
+                format!("set_index r{}, r{}, r{}", base, index, src)
+            }
+            Instr::GetElement { dst, base, index } => {
+                format!("get_element r{}, r{}, {}", dst, base, index)
+            }
+            Instr::IsStruct { dst, src, name } => {
+                format!("is_struct r{}, r{}, {}", dst, src, constant(name))
+            }
+            Instr::Placeholder => "placeholder".to_string(),
+            Instr::NoMatch => "no_match".to_string(),
+        }
+    }
+}
+
+fn binary_name(op: BinaryOp) -> &'static str {
+    match op {
+        BinaryOp::Add => "add",
+        BinaryOp::Sub => "sub",
+        BinaryOp::Mul => "mul",
+        BinaryOp::Div => "div",
+        BinaryOp::Eq => "eq",
+        BinaryOp::NotEq => "not_eq",
+        BinaryOp::Less => "less",
+        BinaryOp::LessEq => "less_eq",
+        BinaryOp::Greater => "greater",
+        BinaryOp::GreaterEq => "greater_eq",
+    }
+}
@@ -0,0 +1,1696 @@
+//! Compilation of checked HIR into bytecode.
+//!
+//! Every body is first scanned to find which closure owns each binding and
+//! which bindings closures capture from around them. A binding lives in a
+//! register of the function or closure that owns it; captured bindings are
+//! boxed into cells when their owner starts, so the closures that capture
+//! them and the owner see the same value. The bindings of the file's
+//! top-level statements are globals instead, since functions can refer to
+//! them.
+//!
+//! Instructions get the source range of the expression they're compiled
+//! for, or of the part of it that an error there is reported at, so the
+//! VM's errors match the interpreter's.
+
+use crate::bytecode::{Capture, Constant, Function, Instr, Program, Reg, Shape};
+use drip_hir::infer::{self, InferenceResults, Ty};
+use drip_hir::method_resolution;
+use drip_hir::resolve::{BodyId, Resolution, Resolutions};
+use drip_hir::{
+    BinaryOp, Body, Expr, ExprIdx, FormatPart, FunctionId, Item, ItemTree, Pat, PatIdx, Stmt,
+    StructId, UnaryOp,
+};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use text_size::TextRange;
+
+/// What defines a binding, within one body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Slot {
+    Stmt(usize),
+    Param(usize),
+    Pat(PatIdx),
+    ClosureParam(ExprIdx, usize),
+}
+
+/// Which closure of a body owns each binding, and what each closure
+/// captures. `None` stands for the body's own function.
+#[derive(Default)]
+struct Scopes {
+    /// Every binding with its owner, in the order they're defined.
+    owners: Vec<(Slot, Option<ExprIdx>)>,
+    owner: HashMap<Slot, Option<ExprIdx>>,
+    /// The closure each closure is written in.
+    parents: HashMap<ExprIdx, Option<ExprIdx>>,
+    captured: HashSet<Slot>,
+    /// The bindings each closure captures, in the order of its upvalues.
+    upvalues: HashMap<ExprIdx, Vec<Slot>>,
+}
+
+impl Scopes {
+    fn new(id: BodyId, body: &Body, params: usize, resolutions: &Resolutions) -> Self {
+        let mut scopes = Scopes::default();
+        for index in 0..params {
+            scopes.define(Slot::Param(index), None);
+        }
+
+        let mut scan = Scan {
+            id,
+            body,
+            resolutions,
+            scopes: &mut scopes,
+            references: Vec::new(),
+        };
+        for &stmt in &body.top_level {
+            scan.stmt(stmt, None);
+        }
+
+        let references = scan.references;
+        for (reference, closure) in references {
+            let resolution = match reference {
+                Reference::Expr(expr) => resolutions.get(id, expr),
+                Reference::Pat(pat) => resolutions.pat(id, pat),
+            };
+            let slot = match resolution.and_then(|resolution| slot(id, resolution)) {
+                Some(slot) => slot,
+                None => continue,
+            };
+            let owner = match scopes.owner.get(&slot) {
+                Some(owner) => *owner,
+                None => continue,
+            };
+            // the file's own top-level bindings are globals
+            if id == BodyId::Root && owner.is_none() && matches!(slot, Slot::Stmt(_)) {
+                continue;
+            }
+            if owner == closure {
+                continue;
+            }
+
+            // every closure between the reference and the binding's owner
+            // captures the binding
+            scopes.captured.insert(slot);
+            let mut closure = closure;
+            while closure != owner {
+                let current = match closure {
+                    Some(current) => current,
+                    None => break,
+                };
+                let upvalues = scopes.upvalues.entry(current).or_default();
+                if !upvalues.contains(&slot) {
+                    upvalues.push(slot);
+                }
+                closure = scopes.parents[&current];
+            }
+        }
+
+        scopes
+    }
+
+    fn define(&mut self, slot: Slot, owner: Option<ExprIdx>) {
+        if self.owner.insert(slot, owner).is_none() {
+            self.owners.push((slot, owner));
+        }
+    }
+}
+
+/// A walk over a body that records who owns each binding and which
+/// closure every reference to a binding is made from.
+struct Scan<'s> {
+    id: BodyId,
+    body: &'s Body,
+    resolutions: &'s Resolutions,
+    scopes: &'s mut Scopes,
+    references: Vec<(Reference, Option<ExprIdx>)>,
+}
+
+#[derive(Clone, Copy)]
+enum Reference {
+    Expr(ExprIdx),
+    Pat(PatIdx),
+}
+
+impl Scan<'_> {
+    fn stmt(&mut self, idx: usize, owner: Option<ExprIdx>) {
+        match &self.body.stmts[idx] {
+            Stmt::VariableDef { value, .. } | Stmt::ConstDef { value, .. } => {
+                self.expr(*value, owner);
+                self.scopes.define(Slot::Stmt(idx), owner);
+            }
+            Stmt::TupleDef { pat, value, .. } => {
+                self.expr(*value, owner);
+                self.pat(*pat, owner);
+            }
+            Stmt::Assign { target, value, .. } => {
+                self.expr(*target, owner);
+                self.expr(*value, owner);
+            }
+            Stmt::Expr(expr) => self.expr(*expr, owner),
+            Stmt::Item(_) => {}
+        }
+    }
+
+    fn exprs(&mut self, exprs: &[ExprIdx], owner: Option<ExprIdx>) {
+        for expr in exprs {
+            self.expr(*expr, owner);
+        }
+    }
+
+    fn expr(&mut self, idx: ExprIdx, owner: Option<ExprIdx>) {
+        match &self.body.arenas[idx] {
+            Expr::Missing
+            | Expr::Literal { .. }
+            | Expr::FloatLiteral { .. }
+            | Expr::StringLiteral { .. }
+            | Expr::BoolLiteral { .. }
+            | Expr::Placeholder
+            | Expr::Path { .. }
+            | Expr::Continue => {}
+            Expr::VariableRef { .. } => self.references.push((Reference::Expr(idx), owner)),
+            Expr::FormatString { parts } => {
+                for part in parts {
+                    if let FormatPart::Expr(expr) = part {
+                        self.expr(*expr, owner);
+                    }
+                }
+            }
+            Expr::Binary { lhs, rhs, .. } => self.exprs(&[*lhs, *rhs], owner),
+            Expr::Unary { expr, .. } | Expr::Field { expr, .. } => self.expr(*expr, owner),
+            Expr::Call { callee, args, .. } => {
+                self.expr(*callee, owner);
+                self.exprs(args, owner);
+            }
+            Expr::MethodCall { receiver, args, .. } => {
+                self.expr(*receiver, owner);
+                self.exprs(args, owner);
+            }
+            Expr::Index { base, index } => self.exprs(&[*base, *index], owner),
+            Expr::Struct { fields, .. } => {
+                for (_, expr) in fields {
+                    self.expr(*expr, owner);
+                }
+            }
+            Expr::Closure { params, body } => {
+                self.scopes.parents.insert(idx, owner);
+                for index in 0..params.len() {
+                    self.scopes
+                        .define(Slot::ClosureParam(idx, index), Some(idx));
+                }
+                self.expr(*body, Some(idx));
+            }
+            Expr::Tuple { exprs } | Expr::Array { exprs } => self.exprs(exprs, owner),
+            Expr::Range { start, end, step } => {
+                self.exprs(&[*start, *end], owner);
+                if let Some(step) = step {
+                    self.expr(*step, owner);
+                }
+            }
+            Expr::Block { stmts } => {
+                for stmt in stmts {
+                    self.stmt(*stmt, owner);
+                }
+            }
+            Expr::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.exprs(&[*condition, *then_branch], owner);
+                if let Some(else_branch) = else_branch {
+                    self.expr(*else_branch, owner);
+                }
+            }
+            Expr::Loop { body } => self.expr(*body, owner),
+            Expr::While { condition, body } => self.exprs(&[*condition, *body], owner),
+            Expr::For {
+                pat,
+                index,
+                iterable,
+                body,
+            } => {
+                self.expr(*iterable, owner);
+                self.pat(*pat, owner);
+                if let Some(index) = index {
+                    self.pat(*index, owner);
+                }
+                self.expr(*body, owner);
+            }
+            Expr::Break { value } => {
+                if let Some(value) = value {
+                    self.expr(*value, owner);
+                }
+            }
+            Expr::Match { scrutinee, arms } => {
+                self.expr(*scrutinee, owner);
+                for arm in arms {
+                    for pat in &arm.pats {
+                        self.pat(*pat, owner);
+                    }
+                    self.expr(arm.body, owner);
+                }
+            }
+        }
+    }
+
+    fn pat(&mut self, idx: PatIdx, owner: Option<ExprIdx>) {
+        match &self.body.arenas[idx] {
+            Pat::Missing | Pat::Wildcard => {}
+            Pat::Ident { .. } => match self.resolutions.pat(self.id, idx) {
+                Some(_) => self.references.push((Reference::Pat(idx), owner)),
+                None => self.scopes.define(Slot::Pat(idx), owner),
+            },
+            Pat::Literal { expr } => self.expr(*expr, owner),
+            Pat::Range { start, end } => {
+                self.expr(*start, owner);
+                self.expr(*end, owner);
+            }
+            Pat::Tuple { pats } => {
+                for pat in pats {
+                    self.pat(*pat, owner);
+                }
+            }
+            Pat::Struct { fields, .. } => {
+                for (_, pat) in fields {
+                    self.pat(*pat, owner);
+                }
+            }
+        }
+    }
+}
+
+/// The binding of the body `id` that `resolution` refers to.
+fn slot(id: BodyId, resolution: Resolution) -> Option<Slot> {
+    match resolution {
+        Resolution::Local { body, stmt } if body == id => Some(Slot::Stmt(stmt)),
+        Resolution::Param { function, index } if id == BodyId::Function(function) => {
+            Some(Slot::Param(index))
+        }
+        // a field used without `self.` is reached through `self`
+        Resolution::SelfField { function, .. } if id == BodyId::Function(function) => {
+            Some(Slot::Param(0))
+        }
+        Resolution::PatBinding { body, pat } if body == id => Some(Slot::Pat(pat)),
+        Resolution::ClosureParam {
+            body,
+            closure,
+            index,
+        } if body == id => Some(Slot::ClosureParam(closure, index)),
+        _ => None,
+    }
+}
+
+/// Where the value of a binding is kept, as seen from the function being
+/// compiled.
+#[derive(Clone, Copy)]
+enum Storage {
+    Register(Reg),
+    Cell(Reg),
+    Upvalue(u32),
+    Global(u32),
+}
+
+/// A binding, or a part of one reached through fields and indices, that can
+/// be assigned to.
+struct Place {
+    storage: Storage,
+    /// The expression that names the binding.
+    root: ExprIdx,
+    path: Vec<(Projection, ExprIdx)>,
+}
+
+#[derive(Clone, Copy)]
+enum Projection {
+    /// A field, by the string constant of its name.
+    Field(u32),
+    /// An index, which has been evaluated into a register.
+    Index(Reg),
+}
+
+/// The loop a `break` or `continue` belongs to.
+struct Loop {
+    dst: Reg,
+    breaks: Vec<usize>,
+    continue_to: usize,
+}
+
+/// A closure that's been given a function index but not compiled yet.
+struct PendingClosure {
+    body: BodyId,
+    expr: ExprIdx,
+    index: u32,
+    captures: Vec<Capture>,
+}
+
+/// The function being compiled.
+struct FunctionState<'a> {
+    id: BodyId,
+    body: &'a Body,
+    scopes: Rc<Scopes>,
+    /// The closure being compiled, or `None` for the body's own function.
+    closure: Option<ExprIdx>,
+    registers: HashMap<Slot, Reg>,
+    code: Vec<Instr>,
+    ranges: Vec<TextRange>,
+    range: TextRange,
+    next: Reg,
+    max: Reg,
+    loops: Vec<Loop>,
+}
+
+pub(crate) struct Compiler<'a> {
+    tree: &'a ItemTree,
+    root: &'a Body,
+    resolutions: &'a Resolutions,
+    inference: &'a InferenceResults,
+    program: Program,
+    scopes: HashMap<BodyId, Rc<Scopes>>,
+    functions: HashMap<FunctionId, u32>,
+    /// The global of each top-level statement's binding.
+    globals: HashMap<usize, u32>,
+    shapes: HashMap<StructId, u32>,
+    /// The globals that stand in for bindings of other bodies, by the body
+    /// that refers to them and the binding.
+    undefined: HashMap<(BodyId, BodyId, Slot), u32>,
+    /// The string constants of the method names whose methods have been
+    /// looked up on every struct.
+    method_names: HashSet<u32>,
+    pending: Vec<PendingClosure>,
+    f: Option<FunctionState<'a>>,
+}
+
+impl<'a> Compiler<'a> {
+    pub(crate) fn new(
+        tree: &'a ItemTree,
+        root: &'a Body,
+        resolutions: &'a Resolutions,
+        inference: &'a InferenceResults,
+    ) -> Self {
+        Self {
+            tree,
+            root,
+            resolutions,
+            inference,
+            program: Program::default(),
+            scopes: HashMap::new(),
+            functions: HashMap::new(),
+            globals: HashMap::new(),
+            shapes: HashMap::new(),
+            undefined: HashMap::new(),
+            method_names: HashSet::new(),
+            pending: Vec::new(),
+            f: None,
+        }
+    }
+
+    pub(crate) fn compile(mut self) -> Program {
+        // functions can refer to each other and to the globals before they're
+        // compiled, so those get their indices up front
+        for (id, _) in self.tree.functions() {
+            let index = self.reserve_function();
+            self.functions.insert(id, index);
+        }
+        let entry = self.reserve_function();
+        self.program.entry = entry;
+
+        let root_scopes = self.scopes(BodyId::Root);
+        for (slot, owner) in &root_scopes.owners {
+            if let (Slot::Stmt(stmt), None) = (slot, owner) {
+                let name = match &self.root.stmts[*stmt] {
+                    Stmt::VariableDef { name, .. } | Stmt::ConstDef { name, .. } => name.clone(),
+                    _ => continue,
+                };
+                self.globals
+                    .insert(*stmt, self.program.globals.len() as u32);
+                self.program.globals.push(name);
+            }
+        }
+
+        let tree = self.tree;
+        for (id, function) in tree.functions() {
+            let name = format!("`{}`", function.name);
+            let params = function.params.len();
+            let compiled = self.compile_body(BodyId::Function(id), name, params, None);
+            self.program.functions[self.functions[&id] as usize] = compiled;
+        }
+        let compiled = self.compile_body(BodyId::Root, "top-level code".to_string(), 0, None);
+        self.program.functions[entry as usize] = compiled;
+
+        while let Some(pending) = self.pending.pop() {
+            let params = match &self.body(pending.body).arenas[pending.expr] {
+                Expr::Closure { params, .. } => params.len(),
+                _ => unreachable!("closures are compiled from `Closure` expressions"),
+            };
+            let mut compiled = self.compile_body(
+                pending.body,
+                "a closure".to_string(),
+                params,
+                Some(pending.expr),
+            );
+            compiled.captures = pending.captures;
+            self.program.functions[pending.index as usize] = compiled;
+        }
+
+        self.program.main =
+            self.root
+                .top_level
+                .iter()
+                .find_map(|stmt| match self.root.stmts[*stmt] {
+                    Stmt::Item(Item::Function(function)) if self.tree[function].name == "main" => {
+                        Some(self.functions[&function])
+                    }
+                    _ => None,
+                });
+
+        self.program
+    }
+
+    fn reserve_function(&mut self) -> u32 {
+        let index = self.program.functions.len() as u32;
+        self.program.functions.push(Function {
+            name: String::new(),
+            params: 0,
+            registers: 0,
+            code: Vec::new(),
+            ranges: Vec::new(),
+            captures: Vec::new(),
+        });
+        index
+    }
+
+    fn body(&self, id: BodyId) -> &'a Body {
+        match id {
+            BodyId::Root => self.root,
+            BodyId::Function(function) => &self.tree[function].body,
+        }
+    }
+
+    fn scopes(&mut self, id: BodyId) -> Rc<Scopes> {
+        if let Some(scopes) = self.scopes.get(&id) {
+            return scopes.clone();
+        }
+        let params = match id {
+            BodyId::Root => 0,
+            BodyId::Function(function) => self.tree[function].params.len(),
+        };
+        let scopes = Rc::new(Scopes::new(id, self.body(id), params, self.resolutions));
+        self.scopes.insert(id, scopes.clone());
+        scopes
+    }
+
+    fn f(&mut self) -> &mut FunctionState<'a> {
+        self.f.as_mut().expect("a function is being compiled")
+    }
+
+    fn state(&self) -> &FunctionState<'a> {
+        self.f.as_ref().expect("a function is being compiled")
+    }
+
+    /// Compiles the function of a body, or one of its closures.
+    fn compile_body(
+        &mut self,
+        id: BodyId,
+        name: String,
+        params: usize,
+        closure: Option<ExprIdx>,
+    ) -> Function {
+        let body = self.body(id);
+        let scopes = self.scopes(id);
+
+        // parameters come first, then the other bindings the function owns
+        let mut registers = HashMap::new();
+        for index in 0..params {
+            let slot = match closure {
+                Some(closure) => Slot::ClosureParam(closure, index),
+                None => Slot::Param(index),
+            };
+            registers.insert(slot, index as Reg);
+        }
+        let mut next = params as Reg;
+        for (slot, owner) in &scopes.owners {
+            let global = id == BodyId::Root && owner.is_none() && matches!(slot, Slot::Stmt(_));
+            if *owner == closure && !registers.contains_key(slot) && !global {
+                registers.insert(*slot, next);
+                next += 1;
+            }
+        }
+
+        let range = match closure {
+            Some(closure) => range(body, closure),
+            None => TextRange::default(),
+        };
+        self.f = Some(FunctionState {
+            id,
+            body,
+            scopes: scopes.clone(),
+            closure,
+            registers,
+            code: Vec::new(),
+            ranges: Vec::new(),
+            range,
+            next,
+            max: next,
+            loops: Vec::new(),
+        });
+
+        let mut boxed: Vec<_> = self
+            .state()
+            .registers
+            .iter()
+            .filter(|(slot, _)| scopes.captured.contains(slot))
+            .map(|(_, reg)| *reg)
+            .collect();
+        boxed.sort_unstable();
+        for reg in boxed {
+            self.emit(Instr::Box { reg });
+        }
+
+        let result = self.temp();
+        match closure {
+            Some(closure) => {
+                let closure_body = match &body.arenas[closure] {
+                    Expr::Closure { body, .. } => *body,
+                    _ => unreachable!("closures are compiled from `Closure` expressions"),
+                };
+                self.compile_expr(closure_body, result);
+            }
+            None => self.compile_stmts(&body.top_level, result),
+        }
+        self.emit(Instr::Return { src: result });
+
+        let f = self.f.take().expect("a function is being compiled");
+        Function {
+            name,
+            params,
+            registers: f.max as usize,
+            code: f.code,
+            ranges: f.ranges,
+            captures: Vec::new(),
+        }
+    }
+
+    fn emit(&mut self, instr: Instr) -> usize {
+        let range = self.state().range;
+        self.emit_at(instr, range)
+    }
+
+    fn emit_at(&mut self, instr: Instr, range: TextRange) -> usize {
+        let f = self.f();
+        f.code.push(instr);
+        f.ranges.push(range);
+        f.code.len() - 1
+    }
+
+    fn here(&self) -> usize {
+        self.state().code.len()
+    }
+
+    /// Points the jump at `at` to the next instruction.
+    fn patch(&mut self, at: usize) {
+        let target = self.here() as u32;
+        match &mut self.f().code[at] {
+            Instr::Jump { target: t }
+            | Instr::JumpIfFalse { target: t, .. }
+            | Instr::ForNext { exit: t, .. } => *t = target,
+            instr => unreachable!("{:?} isn't a jump", instr),
+        }
+    }
+
+    fn temp(&mut self) -> Reg {
+        let f = self.f();
+        let reg = f.next;
+        f.next += 1;
+        f.max = f.max.max(f.next);
+        reg
+    }
+
+    fn constant(&mut self, constant: Constant) -> u32 {
+        match self.program.constants.iter().position(|c| *c == constant) {
+            Some(index) => index as u32,
+            None => {
+                self.program.constants.push(constant);
+                self.program.constants.len() as u32 - 1
+            }
+        }
+    }
+
+    fn string(&mut self, s: &str) -> u32 {
+        self.constant(Constant::Str(s.into()))
+    }
+
+    fn range_of(&self, expr: ExprIdx) -> TextRange {
+        range(self.state().body, expr)
+    }
+
+    fn int_ty(&self, expr: ExprIdx) -> Option<infer::IntTy> {
+        match &self.inference.body(self.state().id)[expr] {
+            Ty::Int(ty) => Some(*ty),
+            _ => None,
+        }
+    }
+
+    /// Where the binding `resolution` refers to is kept, or `None` if it
+    /// refers to an item or a builtin.
+    fn storage(&mut self, resolution: Resolution) -> Option<Storage> {
+        match resolution {
+            Resolution::Global { stmt } => {
+                return self
+                    .globals
+                    .get(&stmt)
+                    .map(|global| Storage::Global(*global));
+            }
+            // the interpreter doesn't let functions see the top-level `:=`
+            // and `a, b ::` bindings, so they get a global that's never
+            // defined instead
+            Resolution::Local { body, .. } | Resolution::PatBinding { body, .. }
+                if body != self.state().id =>
+            {
+                let name = match resolution {
+                    Resolution::Local { stmt, .. } => match &self.body(body).stmts[stmt] {
+                        Stmt::VariableDef { name, .. } | Stmt::ConstDef { name, .. } => {
+                            name.clone()
+                        }
+                        _ => return None,
+                    },
+                    Resolution::PatBinding { pat, .. } => match &self.body(body).arenas[pat] {
+                        Pat::Ident { name } => name.clone(),
+                        _ => return None,
+                    },
+                    _ => return None,
+                };
+                let key = (self.state().id, body, slot(body, resolution)?);
+                let globals = &mut self.program.globals;
+                let global = *self.undefined.entry(key).or_insert_with(|| {
+                    globals.push(name);
+                    globals.len() as u32 - 1
+                });
+                return Some(Storage::Global(global));
+            }
+            _ => {}
+        }
+
+        let f = self.state();
+        let slot = slot(f.id, resolution)?;
+        if let (BodyId::Root, Slot::Stmt(stmt)) = (f.id, slot) {
+            if let Some(global) = self.globals.get(&stmt) {
+                return Some(Storage::Global(*global));
+            }
+        }
+
+        match f.registers.get(&slot) {
+            Some(reg) if f.scopes.captured.contains(&slot) => Some(Storage::Cell(*reg)),
+            Some(reg) => Some(Storage::Register(*reg)),
+            None => {
+                let upvalues = &f.scopes.upvalues[&f.closure?];
+                let index = upvalues.iter().position(|s| *s == slot)?;
+                Some(Storage::Upvalue(index as u32))
+            }
+        }
+    }
+
+    fn load(&mut self, storage: Storage, dst: Reg) {
+        match storage {
+            Storage::Register(src) if src == dst => return,
+            Storage::Register(src) => self.emit(Instr::Move { dst, src }),
+            Storage::Cell(cell) => self.emit(Instr::GetCell { dst, cell }),
+            Storage::Upvalue(upvalue) => self.emit(Instr::GetUpvalue { dst, upvalue }),
+            Storage::Global(global) => self.emit(Instr::GetGlobal { dst, global }),
+        };
+    }
+
+    fn store(&mut self, storage: Storage, src: Reg) {
+        match storage {
+            Storage::Register(dst) if src == dst => return,
+            Storage::Register(dst) => self.emit(Instr::Move { dst, src }),
+            Storage::Cell(cell) => self.emit(Instr::SetCell { cell, src }),
+            Storage::Upvalue(upvalue) => self.emit(Instr::SetUpvalue { upvalue, src }),
+            Storage::Global(global) => self.emit(Instr::SetGlobal { global, src }),
+        };
+    }
+
+    /// Compiles `stmts` in order, putting the value of the last one in `dst`.
+    fn compile_stmts(&mut self, stmts: &[usize], dst: Reg) {
+        let body = self.state().body;
+        for (i, &stmt) in stmts.iter().enumerate() {
+            match &body.stmts[stmt] {
+                Stmt::Expr(expr) if i == stmts.len() - 1 => return self.compile_expr(*expr, dst),
+                _ => self.compile_stmt(stmt),
+            }
+        }
+        self.emit(Instr::LoadUnit { dst });
+    }
+
+    fn compile_stmt(&mut self, idx: usize) {
+        let body = self.state().body;
+        let mark = self.state().next;
+        let range = body
+            .arenas
+            .source_map()
+            .stmt_syntax(idx)
+            .map_or_else(TextRange::default, |ptr| ptr.syntax_node_ptr().range());
+        let outer = std::mem::replace(&mut self.f().range, range);
+        match &body.stmts[idx] {
+            Stmt::VariableDef { value, .. } | Stmt::ConstDef { value, .. } => {
+                let storage = self.storage(Resolution::Local {
+                    body: self.state().id,
+                    stmt: idx,
+                });
+                match storage {
+                    Some(Storage::Register(reg)) => self.compile_expr(*value, reg),
+                    Some(storage) => {
+                        let src = self.temp();
+                        self.compile_expr(*value, src);
+                        self.store(storage, src);
+                    }
+                    None => {}
+                }
+            }
+            Stmt::TupleDef { pat, value, .. } => {
+                let src = self.temp();
+                self.compile_expr(*value, src);
+                // inference made sure the value is a tuple of the right
+                // length, so the pattern always matches
+                let mut fails = Vec::new();
+                self.compile_pat(*pat, src, &mut fails);
+                for fail in fails {
+                    self.patch(fail);
+                }
+            }
+            Stmt::Assign { target, op, value } => {
+                // like the interpreter, assigning to something that isn't a
+                // place does nothing at all
+                if let Some(place) = self.place(*target) {
+                    let method = self.inference.body(self.state().id).assign_resolution(idx);
+                    if let Some(function) = method {
+                        // the method changes `self`, which is written back to
+                        // the target
+                        let start = self.temp();
+                        let src = self.temp();
+                        self.compile_expr(*value, src);
+                        self.load_place(&place, start);
+                        let function = self.functions[&function];
+                        let range = self.range_of(*target);
+                        self.emit_at(
+                            Instr::CallOperator {
+                                dst: src,
+                                function,
+                                args: start,
+                                argc: 1,
+                            },
+                            range,
+                        );
+                        self.store_place(&place, start);
+                    } else {
+                        let src = self.temp();
+                        self.compile_expr(*value, src);
+                        if let Some(op) = op {
+                            let current = self.temp();
+                            self.load_place(&place, current);
+                            let int = self.int_ty(*target);
+                            let range = self.range_of(*target);
+                            self.emit_at(
+                                Instr::Binary {
+                                    op: *op,
+                                    dst: src,
+                                    lhs: current,
+                                    rhs: src,
+                                    int,
+                                },
+                                range,
+                            );
+                        }
+                        self.store_place(&place, src);
+                    }
+                }
+            }
+            Stmt::Expr(expr) => {
+                let dst = self.temp();
+                self.compile_expr(*expr, dst);
+            }
+            Stmt::Item(_) => {}
+        }
+        let f = self.f();
+        f.next = mark;
+        f.range = outer;
+    }
+
+    /// Compiles `exprs` into consecutive registers and returns the first.
+    fn compile_exprs(&mut self, exprs: &[ExprIdx]) -> Reg {
+        let start = self.state().next;
+        for expr in exprs {
+            let dst = self.temp();
+            self.compile_expr(*expr, dst);
+        }
+        start
+    }
+
+    /// Compiles the arguments of the call `idx` into consecutive registers in
+    /// the order of their parameters, and returns the first one.
+    fn compile_args(&mut self, id: BodyId, idx: ExprIdx, args: &[ExprIdx]) -> Reg {
+        let order = match self.inference.body(id).arg_order(idx) {
+            Some(order) => order.to_vec(),
+            None => return self.compile_exprs(args),
+        };
+        let start = self.state().next;
+        for _ in args {
+            self.temp();
+        }
+        // arguments are still evaluated in the order they're written in
+        for (param, arg) in order.into_iter().zip(args) {
+            self.compile_expr(*arg, start + param as Reg);
+        }
+        start
+    }
+
+    /// The register holding the value of `expr`: the binding's own register
+    /// if `expr` names one, or a new temporary it's compiled into.
+    fn operand(&mut self, expr: ExprIdx) -> Reg {
+        if let Some(Storage::Register(reg)) = self.local(expr) {
+            return reg;
+        }
+        let dst = self.temp();
+        self.compile_expr(expr, dst);
+        dst
+    }
+
+    /// Where the binding `expr` names is kept, if it's a name.
+    fn local(&mut self, expr: ExprIdx) -> Option<Storage> {
+        let f = self.state();
+        match &f.body.arenas[expr] {
+            Expr::VariableRef { .. } => match self.resolutions.get(f.id, expr)? {
+                Resolution::SelfField { .. } => None,
+                resolution => self.storage(resolution),
+            },
+            _ => None,
+        }
+    }
+
+    fn compile_expr(&mut self, idx: ExprIdx, dst: Reg) {
+        let range = self.range_of(idx);
+        let outer = std::mem::replace(&mut self.f().range, range);
+        let mark = self.state().next;
+        self.compile_expr_inner(idx, dst);
+        let f = self.f();
+        f.next = mark;
+        f.range = outer;
+    }
+
+    fn compile_expr_inner(&mut self, idx: ExprIdx, dst: Reg) {
+        let body = self.state().body;
+        let id = self.state().id;
+        match &body.arenas[idx] {
+            Expr::Missing => {
+                self.emit(Instr::LoadUnit { dst });
+            }
+            Expr::Literal { n } => {
+                let constant = match self.inference.body(id)[idx] {
+                    Ty::Float(_) => Constant::Float(n.unwrap_or_default() as f64),
+                    _ => Constant::Int(n.unwrap_or_default().into()),
+                };
+                let constant = self.constant(constant);
+                self.emit(Instr::LoadConst { dst, constant });
+            }
+            Expr::FloatLiteral { n } => {
+                let constant = self.constant(Constant::Float(n.unwrap_or_default()));
+                self.emit(Instr::LoadConst { dst, constant });
+            }
+            Expr::StringLiteral { value } => {
+                let constant = self.string(value);
+                self.emit(Instr::LoadConst { dst, constant });
+            }
+            Expr::FormatString { parts } => {
+                let start = self.state().next;
+                for part in parts {
+                    let reg = self.temp();
+                    match part {
+                        FormatPart::Text(text) => {
+                            let constant = self.string(text);
+                            self.emit(Instr::LoadConst { dst: reg, constant });
+                        }
+                        FormatPart::Expr(expr) => self.compile_expr(*expr, reg),
+                    }
+                }
+                let len = parts.len() as u16;
+                self.emit(Instr::Concat { dst, start, len });
+            }
+            Expr::BoolLiteral { value } => {
+                self.emit(Instr::LoadBool { dst, value: *value });
+            }
+            Expr::Binary { op, lhs, rhs } if self.operator(idx).is_some() => {
+                let function = self.operator(idx).unwrap();
+                let start = self.compile_exprs(&[*lhs, *rhs]);
+                self.emit(Instr::CallOperator {
+                    dst,
+                    function,
+                    args: start,
+                    argc: 1,
+                });
+                self.operator_value(*op, dst);
+            }
+            Expr::Binary { op, lhs, rhs } => {
+                // a binding can only be read in place if the right-hand side
+                // can't assign to it first
+                let lhs = if is_simple(&body.arenas[*rhs]) {
+                    self.operand(*lhs)
+                } else {
+                    let reg = self.temp();
+                    self.compile_expr(*lhs, reg);
+                    reg
+                };
+                let rhs = self.operand(*rhs);
+                let int = self.int_ty(idx);
+                self.emit(Instr::Binary {
+                    op: *op,
+                    dst,
+                    lhs,
+                    rhs,
+                    int,
+                });
+            }
+            Expr::Unary { expr, .. } if self.operator(idx).is_some() => {
+                let function = self.operator(idx).unwrap();
+                let start = self.compile_exprs(&[*expr]);
+                self.emit(Instr::CallOperator {
+                    dst,
+                    function,
+                    args: start,
+                    argc: 0,
+                });
+            }
+            Expr::Unary {
+                op: UnaryOp::Neg,
+                expr,
+            } => {
+                let src = self.operand(*expr);
+                let int = self.int_ty(idx);
+                self.emit(Instr::Neg { dst, src, int });
+            }
+            Expr::VariableRef { .. } => match self.resolutions.get(id, idx) {
+                Some(Resolution::Item(Item::Function(function))) => {
+                    let function = self.functions[&function];
+                    self.emit(Instr::LoadFunction { dst, function });
+                }
+                Some(Resolution::Builtin(builtin)) => {
+                    self.emit(Instr::LoadBuiltin { dst, builtin });
+                }
+                Some(Resolution::SelfField { .. }) => match self.place(idx) {
+                    Some(place) => self.load_place(&place, dst),
+                    None => {
+                        self.emit(Instr::LoadUnit { dst });
+                    }
+                },
+                Some(resolution) => match self.storage(resolution) {
+                    Some(storage) => self.load(storage, dst),
+                    // the type checker doesn't let other items be used as values
+                    None => {
+                        self.emit(Instr::LoadUnit { dst });
+                    }
+                },
+                None => {
+                    self.emit(Instr::LoadUnit { dst });
+                }
+            },
+            Expr::Path { .. } => match self.resolutions.get(id, idx) {
+                Some(Resolution::Item(Item::Function(function))) => {
+                    let function = self.functions[&function];
+                    self.emit(Instr::LoadFunction { dst, function });
+                }
+                _ => {
+                    self.emit(Instr::LoadUnit { dst });
+                }
+            },
+            Expr::Call { callee, args, .. } => {
+                let callee_reg = self.temp();
+                self.compile_expr(*callee, callee_reg);
+                let start = self.compile_args(id, idx, args);
+                self.emit(Instr::Call {
+                    dst,
+                    callee: callee_reg,
+                    args: start,
+                    argc: args.len() as u8,
+                });
+            }
+            Expr::MethodCall {
+                receiver,
+                name,
+                args,
+                ..
+            } => {
+                let place = self.place(*receiver);
+                let start = self.temp();
+                match &place {
+                    Some(place) => self.load_place(place, start),
+                    None => self.compile_expr(*receiver, start),
+                }
+                self.compile_args(id, idx, args);
+
+                let name = self.string(name);
+                self.lookup_methods(name);
+                let method = self
+                    .inference
+                    .body(id)
+                    .method_resolution(idx)
+                    .map(|function| self.functions[&function]);
+                self.emit(Instr::CallMethod {
+                    dst,
+                    name,
+                    method,
+                    args: start,
+                    argc: args.len() as u8,
+                });
+
+                // the method can change `self`, which is written back to the
+                // receiver
+                if let Some(place) = place {
+                    self.store_place(&place, start);
+                }
+            }
+            Expr::Field { expr, name } => match self.place(idx) {
+                Some(place) => self.load_place(&place, dst),
+                None => {
+                    let base = self.operand(*expr);
+                    let name = self.string(name);
+                    let range = self.range_of(*expr);
+                    self.emit_at(Instr::GetField { dst, base, name }, range);
+                }
+            },
+            Expr::Index { base, index } if self.operator(idx).is_some() => {
+                let function = self.operator(idx).unwrap();
+                let start = self.compile_exprs(&[*base, *index]);
+                self.emit(Instr::CallOperator {
+                    dst,
+                    function,
+                    args: start,
+                    argc: 1,
+                });
+            }
+            Expr::Index { base, index } => match self.place(idx) {
+                Some(place) => self.load_place(&place, dst),
+                None => {
+                    let base = self.operand(*base);
+                    let index = self.operand(*index);
+                    self.emit(Instr::GetIndex { dst, base, index });
+                }
+            },
+            Expr::Struct { fields, .. } => match &self.inference.body(id)[idx] {
+                Ty::Struct { id: struct_id, .. } => {
+                    let shape = self.shape(*struct_id);
+                    let names = self.program.shapes[shape as usize].fields.clone();
+                    let start = self.state().next;
+                    for _ in &names {
+                        self.temp();
+                    }
+                    // fields are evaluated in the order they're written in,
+                    // but kept in the order they're defined in
+                    for (name, expr) in
+                        method_resolution::literal_fields(self.tree, *struct_id, fields)
+                    {
+                        match names.iter().position(|field| Some(field) == name) {
+                            Some(i) => self.compile_expr(expr, start + i as Reg),
+                            None => {
+                                let reg = self.temp();
+                                self.compile_expr(expr, reg);
+                            }
+                        }
+                    }
+                    self.emit(Instr::MakeStruct { dst, shape, start });
+                }
+                _ => {
+                    for (_, expr) in fields {
+                        let reg = self.temp();
+                        self.compile_expr(*expr, reg);
+                    }
+                    self.emit(Instr::LoadUnit { dst });
+                }
+            },
+            Expr::Closure { .. } => {
+                let index = self.reserve_function();
+                let captures = self.captures(idx);
+                self.pending.push(PendingClosure {
+                    body: id,
+                    expr: idx,
+                    index,
+                    captures,
+                });
+                self.emit(Instr::Closure {
+                    dst,
+                    function: index,
+                });
+            }
+            Expr::Placeholder => {
+                let returns_value =
+                    matches!(id, BodyId::Function(function) if !self.tree[function].ret.is_empty());
+                if body.value_unused(idx, returns_value) {
+                    self.emit(Instr::LoadUnit { dst });
+                } else {
+                    self.emit(Instr::Placeholder);
+                }
+            }
+            Expr::Tuple { exprs } => {
+                let start = self.compile_exprs(exprs);
+                let len = exprs.len() as u16;
+                self.emit(Instr::MakeTuple { dst, start, len });
+            }
+            Expr::Array { exprs } => {
+                let start = self.compile_exprs(exprs);
+                let len = exprs.len() as u16;
+                self.emit(Instr::MakeArray { dst, start, len });
+            }
+            Expr::Range { start, end, step } => {
+                let start = self.operand(*start);
+                let end = self.operand(*end);
+                let step = step.map(|step| self.operand(step));
+                self.emit(Instr::MakeRange {
+                    dst,
+                    start,
+                    end,
+                    step,
+                });
+            }
+            Expr::Block { stmts } => self.compile_stmts(stmts, dst),
+            Expr::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                let jump = self.compile_condition(*condition);
+                match else_branch {
+                    Some(else_branch) => {
+                        self.compile_expr(*then_branch, dst);
+                        let end = self.emit(Instr::Jump { target: 0 });
+                        self.patch(jump);
+                        self.compile_expr(*else_branch, dst);
+                        self.patch(end);
+                    }
+                    // without an `else`, the value of the branch is thrown away
+                    None => {
+                        let reg = self.temp();
+                        self.compile_expr(*then_branch, reg);
+                        self.patch(jump);
+                        self.emit(Instr::LoadUnit { dst });
+                    }
+                }
+            }
+            Expr::Loop { body } => {
+                let start = self.here();
+                self.compile_loop_body(*body, dst, start);
+                self.emit(Instr::Jump {
+                    target: start as u32,
+                });
+                self.finish_loop();
+            }
+            Expr::While { condition, body } => {
+                let start = self.here();
+                let exit = self.compile_condition(*condition);
+                self.compile_loop_body(*body, dst, start);
+                self.emit(Instr::Jump {
+                    target: start as u32,
+                });
+                self.patch(exit);
+                self.emit(Instr::LoadUnit { dst });
+                self.finish_loop();
+            }
+            Expr::For {
+                pat,
+                index,
+                iterable,
+                body: loop_body,
+            } => {
+                let iterable_reg = self.temp();
+                self.compile_expr(*iterable, iterable_reg);
+                // the index of the current element, which is incremented
+                // before each one
+                let counter = self.temp();
+                let before_first = self.constant(Constant::Int(-1));
+                self.emit(Instr::LoadConst {
+                    dst: counter,
+                    constant: before_first,
+                });
+                self.emit(Instr::LoadUnit { dst });
+
+                // a name kept in a register gets each element directly
+                let var = match body.arenas[*pat] {
+                    Pat::Ident { .. } => self.storage(Resolution::PatBinding {
+                        body: id,
+                        pat: *pat,
+                    }),
+                    _ => None,
+                };
+                let element = match var {
+                    Some(Storage::Register(reg)) => reg,
+                    _ => self.temp(),
+                };
+                let range = self.range_of(*iterable);
+                let next = self.emit_at(
+                    Instr::ForNext {
+                        iterable: iterable_reg,
+                        counter,
+                        dst: element,
+                        exit: 0,
+                    },
+                    range,
+                );
+                // inference made sure the patterns always match
+                let mut fails = Vec::new();
+                if !matches!(var, Some(Storage::Register(_))) {
+                    self.compile_pat(*pat, element, &mut fails);
+                }
+                if let Some(index) = index {
+                    self.compile_pat(*index, counter, &mut fails);
+                }
+                for fail in fails {
+                    self.patch(fail);
+                }
+                self.compile_loop_body(*loop_body, dst, next);
+                self.emit(Instr::Jump {
+                    target: next as u32,
+                });
+                self.patch(next);
+                self.finish_loop();
+            }
+            Expr::Break { value } => {
+                let loop_dst = match self.state().loops.last() {
+                    Some(l) => l.dst,
+                    None => return,
+                };
+                match value {
+                    Some(value) => self.compile_expr(*value, loop_dst),
+                    None => {
+                        self.emit(Instr::LoadUnit { dst: loop_dst });
+                    }
+                }
+                let jump = self.emit(Instr::Jump { target: 0 });
+                if let Some(l) = self.f().loops.last_mut() {
+                    l.breaks.push(jump);
+                }
+            }
+            Expr::Continue => {
+                if let Some(target) = self.state().loops.last().map(|l| l.continue_to) {
+                    self.emit(Instr::Jump {
+                        target: target as u32,
+                    });
+                }
+            }
+            Expr::Match { scrutinee, arms } => {
+                let value = self.operand(*scrutinee);
+                let mut ends = Vec::new();
+                for arm in arms {
+                    let mut matched = Vec::new();
+                    for pat in &arm.pats {
+                        let mark = self.state().next;
+                        let mut fails = Vec::new();
+                        self.compile_pat(*pat, value, &mut fails);
+                        self.f().next = mark;
+                        matched.push(self.emit(Instr::Jump { target: 0 }));
+                        for fail in fails {
+                            self.patch(fail);
+                        }
+                    }
+                    let next_arm = self.emit(Instr::Jump { target: 0 });
+                    for jump in matched {
+                        self.patch(jump);
+                    }
+                    self.compile_expr(arm.body, dst);
+                    ends.push(self.emit(Instr::Jump { target: 0 }));
+                    self.patch(next_arm);
+                }
+                self.emit(Instr::NoMatch);
+                for end in ends {
+                    self.patch(end);
+                }
+            }
+        }
+    }
+
+    /// Compiles a condition and a jump past what follows if it's false, and
+    /// returns the jump.
+    fn compile_condition(&mut self, condition: ExprIdx) -> usize {
+        let mark = self.state().next;
+        let cond = self.operand(condition);
+        let range = self.range_of(condition);
+        let jump = self.emit_at(Instr::JumpIfFalse { cond, target: 0 }, range);
+        self.f().next = mark;
+        jump
+    }
+
+    fn compile_loop_body(&mut self, body: ExprIdx, dst: Reg, continue_to: usize) {
+        self.f().loops.push(Loop {
+            dst,
+            breaks: Vec::new(),
+            continue_to,
+        });
+        let reg = self.temp();
+        self.compile_expr(body, reg);
+    }
+
+    /// Points the `break`s of the innermost loop past its end.
+    fn finish_loop(&mut self) {
+        let l = self.f().loops.pop().expect("a loop is being compiled");
+        for jump in l.breaks {
+            self.patch(jump);
+        }
+    }
+
+    /// Compiles a test of whether the value in `value` matches `pat`, which
+    /// binds the names in it on the way. The jumps taken when it doesn't
+    /// match are added to `fails`.
+    fn compile_pat(&mut self, pat: PatIdx, value: Reg, fails: &mut Vec<usize>) {
+        let f = self.state();
+        let (id, body) = (f.id, f.body);
+        match &body.arenas[pat] {
+            Pat::Missing => fails.push(self.emit(Instr::Jump { target: 0 })),
+            Pat::Wildcard => {}
+            Pat::Ident { .. } => match self.resolutions.pat(id, pat) {
+                // a constant, which is compared with the value
+                Some(resolution) => match self.storage(resolution) {
+                    Some(storage) => {
+                        let constant = self.temp();
+                        self.load(storage, constant);
+                        self.compile_test(BinaryOp::Eq, constant, value, fails);
+                    }
+                    None => fails.push(self.emit(Instr::Jump { target: 0 })),
+                },
+                None => {
+                    let storage = self
+                        .storage(Resolution::PatBinding { body: id, pat })
+                        .expect("identifier patterns are bindings");
+                    self.store(storage, value);
+                }
+            },
+            Pat::Literal { expr } => {
+                let literal = self.temp();
+                self.compile_expr(*expr, literal);
+                self.compile_test(BinaryOp::Eq, literal, value, fails);
+            }
+            Pat::Range { start, end } => {
+                let start = self.operand(*start);
+                let end = self.operand(*end);
+                self.compile_test(BinaryOp::LessEq, start, value, fails);
+                self.compile_test(BinaryOp::Less, value, end, fails);
+            }
+            Pat::Tuple { pats } => {
+                let cond = self.temp();
+                let len = pats.len() as u16;
+                self.emit(Instr::IsTuple {
+                    dst: cond,
+                    src: value,
+                    len,
+                });
+                fails.push(self.emit(Instr::JumpIfFalse { cond, target: 0 }));
+                for (index, pat) in pats.iter().enumerate() {
+                    let element = self.temp();
+                    self.emit(Instr::GetElement {
+                        dst: element,
+                        base: value,
+                        index: index as u16,
+                    });
+                    self.compile_pat(*pat, element, fails);
+                }
+            }
+            Pat::Struct { name, fields } => {
+                let cond = self.temp();
+                let name = self.string(name);
+                self.emit(Instr::IsStruct {
+                    dst: cond,
+                    src: value,
+                    name,
+                });
+                fails.push(self.emit(Instr::JumpIfFalse { cond, target: 0 }));
+                for (field, pat) in fields {
+                    let reg = self.temp();
+                    let name = self.string(field);
+                    self.emit(Instr::GetField {
+                        dst: reg,
+                        base: value,
+                        name,
+                    });
+                    self.compile_pat(*pat, reg, fails);
+                }
+            }
+        }
+    }
+
+    fn compile_test(&mut self, op: BinaryOp, lhs: Reg, rhs: Reg, fails: &mut Vec<usize>) {
+        let cond = self.temp();
+        self.emit(Instr::Binary {
+            op,
+            dst: cond,
+            lhs,
+            rhs,
+            int: None,
+        });
+        fails.push(self.emit(Instr::JumpIfFalse { cond, target: 0 }));
+    }
+
+    /// The function behind the operator `expr` if it's used on a struct.
+    fn operator(&self, expr: ExprIdx) -> Option<u32> {
+        let function = self
+            .inference
+            .body(self.state().id)
+            .method_resolution(expr)?;
+        Some(self.functions[&function])
+    }
+
+    /// Turns what the method behind `op` returned in `dst` into the
+    /// operator's value: `!=` negates `eq`, and comparisons compare the
+    /// result of `cmp` with zero.
+    fn operator_value(&mut self, op: BinaryOp, dst: Reg) {
+        let (op, rhs) = match op {
+            BinaryOp::NotEq => {
+                let rhs = self.temp();
+                self.emit(Instr::LoadBool {
+                    dst: rhs,
+                    value: false,
+                });
+                (BinaryOp::Eq, rhs)
+            }
+            op if op.is_comparison() && op != BinaryOp::Eq => {
+                let rhs = self.temp();
+                let constant = self.constant(Constant::Int(0));
+                self.emit(Instr::LoadConst { dst: rhs, constant });
+                (op, rhs)
+            }
+            _ => return,
+        };
+        self.emit(Instr::Binary {
+            op,
+            dst,
+            lhs: dst,
+            rhs,
+            int: None,
+        });
+    }
+
+    /// The place `expr` refers to, like `v.a[i]`, or `None` if it isn't one.
+    /// Indices are evaluated into registers along the way.
+    fn place(&mut self, expr: ExprIdx) -> Option<Place> {
+        let f = self.state();
+        let (id, body) = (f.id, f.body);
+        match &body.arenas[expr] {
+            Expr::VariableRef { var } => {
+                let resolution = self.resolutions.get(id, expr)?;
+                let storage = self.storage(resolution)?;
+                let mut path = Vec::new();
+                // `x` in a method is `self.x`
+                if let Resolution::SelfField { .. } = resolution {
+                    path.push((Projection::Field(self.string(var)), expr));
+                }
+                Some(Place {
+                    storage,
+                    root: expr,
+                    path,
+                })
+            }
+            Expr::Field { expr: inner, name } => {
+                let mut place = self.place(*inner)?;
+                let name = self.string(name);
+                place.path.push((Projection::Field(name), expr));
+                Some(place)
+            }
+            // an `Index` impl returns a new value rather than a place
+            Expr::Index { .. } if self.operator(expr).is_some() => None,
+            Expr::Index { base, index } => {
+                let mut place = self.place(*base)?;
+                let reg = self.temp();
+                self.compile_expr(*index, reg);
+                place.path.push((Projection::Index(reg), expr));
+                Some(place)
+            }
+            _ => None,
+        }
+    }
+
+    fn load_place(&mut self, place: &Place, dst: Reg) {
+        if place.path.is_empty() {
+            let range = self.range_of(place.root);
+            let outer = std::mem::replace(&mut self.f().range, range);
+            self.load(place.storage, dst);
+            self.f().range = outer;
+            return;
+        }
+
+        let mut base = match place.storage {
+            Storage::Register(reg) => reg,
+            storage => {
+                let range = self.range_of(place.root);
+                let outer = std::mem::replace(&mut self.f().range, range);
+                self.load(storage, dst);
+                self.f().range = outer;
+                dst
+            }
+        };
+        for (projection, expr) in &place.path {
+            let range = self.range_of(*expr);
+            let instr = match *projection {
+                Projection::Field(name) => Instr::GetField { dst, base, name },
+                Projection::Index(index) => Instr::GetIndex { dst, base, index },
+            };
+            self.emit_at(instr, range);
+            base = dst;
+        }
+    }
+
+    /// Assigns the value in `src` to a place. The parts of the binding on the
+    /// way are taken out of it and put back afterwards, so only the part
+    /// that's assigned to is copied if it's shared.
+    fn store_place(&mut self, place: &Place, src: Reg) {
+        let root_range = self.range_of(place.root);
+        let outer = std::mem::replace(&mut self.f().range, root_range);
+        if place.path.is_empty() {
+            self.store(place.storage, src);
+            self.f().range = outer;
+            return;
+        }
+
+        let root = match place.storage {
+            Storage::Register(reg) => reg,
+            storage => {
+                let dst = self.temp();
+                self.emit(match storage {
+                    Storage::Cell(cell) => Instr::TakeCell { dst, cell },
+                    Storage::Upvalue(upvalue) => Instr::TakeUpvalue { dst, upvalue },
+                    Storage::Global(global) => Instr::TakeGlobal { dst, global },
+                    Storage::Register(_) => unreachable!(),
+                });
+                dst
+            }
+        };
+
+        let mut bases = vec![root];
+        for (projection, expr) in &place.path[..place.path.len() - 1] {
+            let dst = self.temp();
+            let base = *bases.last().unwrap();
+            let range = self.range_of(*expr);
+            let instr = match *projection {
+                Projection::Field(name) => Instr::TakeField { dst, base, name },
+                Projection::Index(index) => Instr::TakeIndex { dst, base, index },
+            };
+            self.emit_at(instr, range);
+            bases.push(dst);
+        }
+
+        let mut src = src;
+        for (base, (projection, expr)) in bases.iter().rev().zip(place.path.iter().rev()) {
+            let range = self.range_of(*expr);
+            let instr = match *projection {
+                Projection::Field(name) => Instr::SetField {
+                    base: *base,
+                    name,
+                    src,
+                },
+                Projection::Index(index) => Instr::SetIndex {
+                    base: *base,
+                    index,
+                    src,
+                },
+            };
+            self.emit_at(instr, range);
+            src = *base;
+        }
+
+        if !matches!(place.storage, Storage::Register(_)) {
+            self.store(place.storage, root);
+        }
+        self.f().range = outer;
+    }
+
+    /// Where a closure created in the function being compiled gets each of
+    /// its upvalues.
+    fn captures(&self, closure: ExprIdx) -> Vec<Capture> {
+        let f = self.state();
+        let upvalues = match f.scopes.upvalues.get(&closure) {
+            Some(upvalues) => upvalues,
+            None => return Vec::new(),
+        };
+        upvalues
+            .iter()
+            .map(|slot| match f.registers.get(slot) {
+                Some(reg) => Capture::Local(*reg),
+                None => {
+                    let outer = &f.scopes.upvalues[&f.closure.expect("captured from a closure")];
+                    let index = outer
+                        .iter()
+                        .position(|s| s == slot)
+                        .expect("closures capture what their closures capture");
+                    Capture::Upvalue(index as u32)
+                }
+            })
+            .collect()
+    }
+
+    fn shape(&mut self, id: StructId) -> u32 {
+        if let Some(shape) = self.shapes.get(&id) {
+            return *shape;
+        }
+        let shape = Shape {
+            id,
+            name: self.tree[id].name.clone(),
+            fields: method_resolution::all_fields(self.tree, id)
+                .into_iter()
+                .map(|field| field.name.clone())
+                .collect(),
+        };
+        let index = self.program.shapes.len() as u32;
+        self.program.shapes.push(shape);
+        self.shapes.insert(id, index);
+        index
+    }
+
+    /// Looks up the method called `name` on every struct, for calls whose
+    /// receiver turns out to be a struct at runtime.
+    fn lookup_methods(&mut self, name: u32) {
+        if !self.method_names.insert(name) {
+            return;
+        }
+        let method_name = match &self.program.constants[name as usize] {
+            Constant::Str(s) => s.clone(),
+            _ => return,
+        };
+        for (id, _) in self.tree.structs() {
+            let ty = infer::struct_ty(self.tree, id);
+            if let Ok(function) = method_resolution::lookup_method(self.tree, &ty, &method_name) {
+                self.program
+                    .methods
+                    .insert((id, name), self.functions[&function]);
+            }
+        }
+    }
+}
+
+/// Whether evaluating `expr` can't change any binding.
+fn is_simple(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::VariableRef { .. }
+            | Expr::Literal { .. }
+            | Expr::FloatLiteral { .. }
+            | Expr::StringLiteral { .. }
+            | Expr::BoolLiteral { .. }
+    )
+}
+
+fn range(body: &Body, expr: ExprIdx) -> TextRange {
+    body.arenas
+        .source_map()
+        .expr_syntax(expr)
+        .map_or_else(TextRange::default, |source| source.range())
+}
@@ -0,0 +1,538 @@
+//! A register-based bytecode compiler and virtual machine for checked Drip
+//! programs.
+//!
+//! The VM runs the same programs as the tree-walking interpreter in
+//! `drip_interp`, with the same output and the same errors, but compiles
+//! each function to bytecode first instead of walking its HIR every time
+//! it runs.
+
+mod bytecode;
+mod compile;
+mod value;
+mod vm;
+
+pub use bytecode::{Capture, Constant, Function, Instr, Program, Reg, Shape};
+pub use value::{Closure, Value};
+
+use drip_hir::db::{FileId, HirDatabase};
+use drip_hir::flow::Severity;
+use drip_interp::Error;
+use std::io::Write;
+
+/// Compiles `file`, unless it has errors.
+pub fn compile(db: &dyn HirDatabase, file: FileId) -> Result<Program, Error> {
+    let errors: Vec<_> = db
+        .diagnostics(file)
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == Severity::Error)
+        .cloned()
+        .collect();
+    if !errors.is_empty() {
+        return Err(Error::Diagnostics(errors));
+    }
+
+    let lowered = db.lower_file(file);
+    let resolutions = db.resolutions(file);
+    let inference = db.inference(file);
+    Ok(compile::Compiler::new(&lowered.tree, &lowered.root, &resolutions, &inference).compile())
+}
+
+/// Runs a compiled program, writing what it prints to `out`.
+pub fn execute(program: &Program, out: &mut dyn Write) -> Result<(), Error> {
+    vm::Vm::new(program, out).run().map_err(Error::Runtime)
+}
+
+/// Compiles and runs `file`, writing what it prints to `out`.
+pub fn run(db: &dyn HirDatabase, file: FileId, out: &mut dyn Write) -> Result<(), Error> {
+    execute(&compile(db, file)?, out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use drip_hir::db::SourceDatabase;
+    use drip_hir::Database;
+    use expect_test::{expect, Expect};
+
+    fn db(input: &str) -> Database {
+        let mut db = Database::default();
+        db.set_file_text(FileId(0), input.into());
+        db.set_root_file(FileId(0));
+        db
+    }
+
+    /// What running `input` with `run` printed, followed by its error if it
+    /// had one.
+    fn output(
+        input: &str,
+        run: fn(&dyn HirDatabase, FileId, &mut dyn Write) -> Result<(), Error>,
+    ) -> String {
+        let mut out = Vec::new();
+        let result = run(&db(input), FileId(0), &mut out);
+        let mut output = String::from_utf8(out).unwrap();
+        if let Err(error) = result {
+            output.push_str(&error.to_string());
+        }
+        output
+    }
+
+    /// Runs `input` on the VM, checks that the interpreter does the same
+    /// and returns the output.
+    fn run_both(input: &str) -> String {
+        let vm = output(input, run);
+        assert_eq!(vm, output(input, drip_interp::run));
+        vm
+    }
+
+    fn check_bytecode(input: &str, expect: Expect) {
+        let program = compile(&db(input), FileId(0)).unwrap();
+        expect.assert_eq(&program.to_string());
+    }
+
+    #[test]
+    fn run_like_the_interpreter() {
+        let input = r#"main :: () {
+    hello := "hello"
+    world :: "world"
+    hello = hello + " " + world
+    println(hello)
+
+    mul_add :: (a, b, c: i32) -> i32 { a + b * c }
+    a :: 10
+    res := mul_add(a, 3, 5)
+    res /= 2
+    println("({a} + 3 * 5) / 2 = {res}")
+
+    add_and_sub :: (a, b: i32) -> (i32, i32) { (a + b, a - b) }
+    identity :: |f| f
+    match identity(add_and_sub)(10, 5) {
+        (addition, subtraction) => println("{addition}, {subtraction}")
+    }
+
+    i := 0
+    x :: loop {
+        if i == 5 {
+            break i
+        }
+        i += 1
+    }
+    println((x, 1.5, true, [1, 2], 0..3, ()))
+}"#;
+        assert_eq!(
+            run_both(input),
+            "hello world\n(10 + 3 * 5) / 2 = 12\n15, 5\n(5, 1.5, true, [1, 2], 0..3, ())\n"
+        );
+    }
+
+    #[test]
+    fn run_several_names_defined_at_once() {
+        let input = r#"PI :: 3.14159265358979323846
+TAU :: PI * 2
+f :: (a, b, c: i32) {
+    add_and_sub :: (a, b: i32) -> (i32, i32) { (a + b, a - b) }
+    x, y := add_and_sub(a, c)
+    y *= b
+    println((x, y, TAU))
+}
+a, b, c :: 10, 3, 5
+f(a, b, c)
+println("{a}, {b}, {c}")"#;
+        assert_eq!(run_both(input), "(15, 15, 6.283185307179586)\n10, 3, 5\n");
+    }
+
+    #[test]
+    fn run_structs_methods_and_closures() {
+        let input = r#"Animal :: struct { name: string, legs: i32 }
+Animal :: impl {
+    describe :: (self) -> string {
+        legs :: self.legs
+        self.name + " has {legs} legs"
+    }
+    lose_leg :: (self) { self.legs -= 1 }
+}
+Dog :: struct <| Animal { toy: string }
+Dog :: impl {
+    describe :: (self) -> string { self.name + " likes the " + self.toy }
+}
+
+dog := Dog { legs: 4, toy: "ball", name: "Rex" }
+println(dog)
+println(dog.describe())
+cats := [Animal { name: "Tom", legs: 4 }]
+cats[0].lose_leg()
+cats[0].name = "Tim"
+println(cats[0].describe())
+
+count := 0
+bump :: |by: i32| { count += by
+count }
+bump(2)
+println(bump(3))
+
+make_counter :: () -> i32 {
+    n := 0
+    inc :: || { add :: |by: i32| { n += by }
+    add(1) }
+    inc()
+    inc()
+    n
+}
+println(make_counter())
+s :: "a"
+println(("x", s, 1.5))"#;
+        assert_eq!(
+            run_both(input),
+            r#"Dog { toy: "ball", name: "Rex", legs: 4 }
+Rex likes the ball
+Tim has 3 legs
+5
+2
+("x", "a", 1.5)
+"#
+        );
+    }
+
+    #[test]
+    fn run_paths_and_methods_without_self_listed() {
+        let input = r#"Vec3 :: struct { x, y, z: i32 }
+Vec3 :: impl {
+    new :: (x, y, z: i32) -> Self { Vec3 { x, y, z } }
+    sum :: () -> i32 { self.x + self.y + self.z }
+}
+Sound :: trait { make_sound :: () }
+Animal :: struct { name: string }
+Animal <| Sound :: impl {
+    make_sound :: () { println(self.name + " makes a sound") }
+}
+Cat :: struct <| Animal { }
+Cat <| Sound :: impl {
+    make_sound :: () { println("meow") }
+}
+
+v :: Vec3::new(5, 8, 7)
+println(v.sum())
+println(Vec3::sum(v))
+dog :: Animal { name: "Rex" }
+dog.make_sound()
+cat :: Cat { name: "Tom" }
+cat.make_sound()"#;
+        assert_eq!(run_both(input), "20\n20\nRex makes a sound\nmeow\n");
+    }
+
+    #[test]
+    fn run_control_flow_and_match() {
+        let input = r#"N :: 3
+Point :: struct { x, y: i32 }
+describe :: (p: Point) -> string {
+    match p {
+        Point { x: 0, y: 0 } => "origin"
+        Point { x: N } => "on x = N"
+        Point { y } => "y = {y}"
+    }
+}
+
+arr := [1, 2, 3, 4, 5, 6]
+sum := 0
+for n in arr {
+    if n == 2 { continue }
+    if n == 5 { break }
+    sum += n
+}
+i := 0
+while i < 3 { arr[i] = arr[i] * 10
+i += 1 }
+println("{sum} {arr}")
+println(describe(Point { x: 0, y: 0 }))
+println(describe(Point { x: 3, y: 7 }))
+println(describe(Point { x: 1, y: 7 }))
+grade :: match 85 {
+    90..101 => "A"
+    80..90 => "B"
+    _ => "C"
+}
+println(grade)
+total := 0.0
+for f in 0.0..2.5 { total += f }
+println(total)"#;
+        assert_eq!(
+            run_both(input),
+            "8 [10, 20, 30, 4, 5, 6]\norigin\non x = N\ny = 7\nB\n3.0\n"
+        );
+    }
+
+    #[test]
+    fn run_for_loops_with_patterns_and_indices() {
+        let input = r#"N :: 3
+pairs :: [(4, 3), (0, 2)]
+for (x, y), i in pairs { println("{i}: {x} {y}") }
+for N, i in [7, 8] { println((N, i)) }
+count := 0
+for _ in 0..4 { count += 1 }
+f :: || { for (_, y) in pairs { println(y) } }
+f()
+println(count)
+for n, i in 4..8=2 { println((n, i)) }
+for x in 1.0..0.0=-0.5 { println(x) }
+println(0..10=3)"#;
+        assert_eq!(
+            run_both(input),
+            "0: 4 3\n1: 0 2\n(7, 0)\n(8, 1)\n3\n2\n4\n(4, 0)\n(6, 1)\n1.0\n0.5\n0..10=3\n"
+        );
+    }
+
+    #[test]
+    fn report_runtime_errors_like_the_interpreter() {
+        let input = "div :: (a, b: i32) -> i32 { a / b }
+main :: () {
+    f :: |b: i32| div(10, b)
+    println(f(2))
+    f(0)
+}";
+        assert_eq!(
+            run_both(input),
+            "5
+error at 28..34: division by zero
+    in `div` at 28..34
+    in a closure at 67..82
+    in `main` at 100..105"
+        );
+
+        run_both("arr :: [1, 2, 3]\ni := 3\narr[i]");
+        run_both("arr := [[1], [2]]\narr[1][1] = 3");
+        run_both("f :: (n: u8) -> u8 { n * 200 }\nf(1)\nf(2)");
+        run_both("f :: (n: u8) -> u8 { if n == 0 { ... } else { n } }\nf(0)");
+        run_both("step := 0\nfor i in 0..3=step { }");
+        assert_eq!(
+            run_both("f :: (n: u8) { if n == 0 { ... } else { .. } }\nf(0)\nprintln(\"done\")"),
+            "done\n"
+        );
+        assert_eq!(
+            run_both("main :: () {\n    y :: ...\n    println(\"{y}\")\n}"),
+            "error at 22..30: reached a `...` that hasn't been filled in
+    in `main` at 22..30"
+        );
+        assert_eq!(
+            run_both("f :: (n: i32) -> i32 { f(n + 1) }\nf(0)"),
+            "error at 23..32: too many nested calls
+    in `f` at 23..32
+    (repeated 998 more times)
+    in top-level code at 34..38"
+        );
+        run_both("println(\"hi\")\nx :: 1\nx = 2");
+    }
+
+    #[test]
+    fn disassemble_bytecode() {
+        check_bytecode(
+            "scale :: 2
+add :: (n: i32) -> i32 {
+    total := 0
+    bump :: |i: i32| { total += i }
+    for i in 0..n { bump(i) }
+    total
+}
+println(add(3) * scale)",
+            expect![[r##"
+                constants:
+                    #0    0
+                    #1    -1
+                    #2    2
+                    #3    3
+
+                function 0 `add` (1 params, 11 registers):
+                    0000  box r1                               0..0
+                    0001  load_const r5, #0 ; 0                49..55
+                    0002  set_cell r1, r5                      40..55
+                    0003  closure r2, 2                        63..91
+                    0004  load_const r7, #0 ; 0                100..101
+                    0005  make_range r6, r7, r0                100..105
+                    0006  load_const r7, #1 ; -1               91..121
+                    0007  load_unit r5                         91..121
+                    0008  for_next r3, r6, r7, 0013            100..105
+                    0009  move r9, r2                          107..111
+                    0010  move r10, r3                         112..113
+                    0011  call r8, r9, r10, 1                  107..115
+                    0012  jump 0008                            91..121
+                    0013  get_cell r4, r1                      121..127
+                    0014  return r4                            0..0
+
+                function 1 top-level code (0 params, 6 registers):
+                    0000  load_const r1, #2 ; 2                9..11
+                    0001  set_global g0, r1 ; scale            0..11
+                    0002  load_builtin r1, println             129..136
+                    0003  load_function r4, 0 ; `add`          137..140
+                    0004  load_const r5, #3 ; 3                141..142
+                    0005  call r3, r4, r5, 1                   137..144
+                    0006  get_global r4, g0 ; scale            146..151
+                    0007  mul r2, r3, r4 (i32)                 137..151
+                    0008  call r0, r1, r2, 1                   129..152
+                    0009  return r0                            0..0
+
+                function 2 a closure (1 params, 4 registers):
+                    capture r1
+                    0000  move r2, r0                          83..85
+                    0001  get_upvalue r3, u0                   74..80
+                    0002  add r2, r3, r2 (i32)                 74..80
+                    0003  set_upvalue u0, r2                   74..80
+                    0004  load_unit r1                         72..91
+                    0005  return r1                            63..91
+            "##]],
+        );
+    }
+
+    #[test]
+    fn run_assignments_through_places_and_captures() {
+        let input = r#"Q :: struct { v: i32 }
+P :: struct { a: Q, b: i32 }
+C :: struct { n: i32 }
+C :: impl {
+    inc :: (self) { self.n += 1 }
+    add :: (self, by: i32) -> i32 {
+        g :: |x: i32| { self.n += x
+        self.n }
+        g(by)
+    }
+}
+
+p := P { a: Q { v: 1 }, b: 3 }
+ps := [p, p]
+p.a.v = 5
+p.b += 1
+ps[1].a.v = 9
+println((p, ps))
+
+cs := [C { n: 0 }]
+inc :: || { cs[0].inc() }
+inc()
+inc()
+c := cs[0]
+println((cs, c.add(10), c))
+
+for i in 0..2 {
+    get :: || i
+    println(get())
+}"#;
+        assert_eq!(
+            run_both(input),
+            "(P { a: Q { v: 5 }, b: 4 }, [P { a: Q { v: 1 }, b: 3 }, P { a: Q { v: 9 }, b: 3 }])
+([C { n: 2 }], 12, C { n: 12 })
+0
+1
+"
+        );
+
+        // functions don't see the top-level `:=` bindings
+        assert_eq!(
+            run_both("xs := [1]\nf :: () { xs[0] = 2 }\nf()"),
+            "error at 20..22: `xs` was used before it was defined
+    in `f` at 20..22
+    in top-level code at 32..35"
+        );
+    }
+
+    #[test]
+    fn run_operators_on_structs() {
+        let input = r#"V :: struct { x, y: i32 }
+V <| Add :: impl { add :: (self, other: V) -> V { V { x: self.x + other.x, y: self.y + other.y } } }
+V <| Mul :: impl { mul :: (self, by: i32) -> V { V { x: self.x * by, y: self.y * by } } }
+V <| Neg :: impl { neg :: (self) -> V { V { x: -self.x, y: -self.y } } }
+V <| Eq :: impl { eq :: (self, other: Self) -> bool { self.x == other.x } }
+V <| Ord :: impl { cmp :: (self, other: Self) -> i32 { self.x - other.x } }
+V <| Index :: impl { index :: (self, i: i32) -> i32 { if i == 0 { self.x } else { self.y } } }
+V <| AddAssign :: impl { add_assign :: (self, other: V) { self.x += other.x
+self.y += other.y } }
+
+a := V { x: 1, y: 2 }
+b := V { x: 3, y: 4 }
+println((a + b * 2, -a, a))
+println((a == b, a != b, a < b, a >= b, b[1]))
+vs := [a, b]
+vs[0] += b
+shift :: || { a += V { x: 10, y: 0 } }
+shift()
+println((vs, a))"#;
+        assert_eq!(
+            run_both(input),
+            "(V { x: 7, y: 10 }, V { x: -1, y: -2 }, V { x: 1, y: 2 })
+(false, true, true, false, 4)
+([V { x: 4, y: 6 }, V { x: 3, y: 4 }], V { x: 11, y: 2 })
+"
+        );
+
+        let input = "N :: struct { n: i32 }
+N <| Div :: impl { div :: (self, by: i32) -> N { N { n: self.n / by } } }
+main :: () { N { n: 1 } / 0 }";
+        assert_eq!(
+            run_both(input),
+            "error at 79..91: division by zero
+    in `div` at 79..91
+    in `main` at 110..125"
+        );
+    }
+    #[test]
+    fn run_self_struct_literals_and_values_without_names() {
+        let input = r#"Vec3 :: struct { x, y, z: i32 }
+Vec3 :: impl {
+    new :: (x: i32, y: i32, z: i32) -> Self { Self { x, y, z } }
+}
+Named :: struct <| Vec3 { name: string }
+
+println(Vec3::new(1, 2, 3))
+println(Vec3 { 5, 8, 7 })
+println(Named { "a", 1, 2, z: 3 })"#;
+        assert_eq!(
+            run_both(input),
+            "Vec3 { x: 1, y: 2, z: 3 }
+Vec3 { x: 5, y: 8, z: 7 }
+Named { name: \"a\", x: 1, y: 2, z: 3 }
+"
+        );
+    }
+    #[test]
+    fn run_calls_with_named_args() {
+        let input = r#"P :: struct { x: i32 }
+P :: impl {
+    shift :: (self, by, times: i32) -> i32 { self.x + by * times }
+}
+sub :: (a, b: i32) -> i32 { a - b }
+log :: (s: string) -> i32 { println(s)
+0 }
+
+println(sub(b: 1, a: 8))
+println(sub(2, b: 1))
+println(P { x: 1 }.shift(times: 3, by: 2))
+sub(b: log("b"), a: log("a"))"#;
+        assert_eq!(run_both(input), "7\n1\n7\nb\na\n");
+    }
+    #[test]
+    fn run_methods_using_fields_without_self() {
+        let input = r#"Vec3 :: struct { x, y, z: i32 }
+Vec3 :: impl {
+    plus_one :: fn(self) {
+        x, y, z += 1
+    }
+    sum :: (self) -> i32 { x + y + z }
+    scale :: (self, x: i32) -> i32 { x * y }
+    bump_later :: (self) -> i32 {
+        bump :: || {
+            z += 10
+            z
+        }
+        bump()
+    }
+}
+
+v := Vec3 { 1, 2, 3 }
+v.plus_one()
+println(v)
+println((v.sum(), v.scale(10), v.bump_later()))
+println(v)"#;
+        assert_eq!(
+            run_both(input),
+            "Vec3 { x: 2, y: 3, z: 4 }
+(9, 30, 14)
+Vec3 { x: 2, y: 3, z: 14 }
+"
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! The values the VM computes.
+//!
+//! Values behave like the interpreter's, which are copied whenever they're
+//! bound or passed around, but arrays, tuples and structs share their
+//! contents until one of the copies is changed. They're printed exactly the
+//! way the interpreter prints its values.
+
+use crate::bytecode::Shape;
+use drip_hir::resolve::Builtin;
+use std::cell::RefCell;
+use std::fmt;
+use std::fmt::Formatter;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i128),
+    Float(f64),
+    Bool(bool),
+    Str(Rc<str>),
+    Array(Rc<Vec<Value>>),
+    /// A tuple, where the empty tuple is `()`.
+    Tuple(Rc<Vec<Value>>),
+    /// `start..end`, with `end` excluded, and the step of `start..end=step`.
+    Range(Box<Value>, Box<Value>, Option<Box<Value>>),
+    /// An instance of a struct, with its fields in the order of its shape.
+    Struct(Rc<Shape>, Rc<Vec<Value>>),
+    Function(u32),
+    Closure(Rc<Closure>),
+    Builtin(Builtin),
+    /// A binding that closures capture, which only ever appears in the
+    /// registers of the function that owns it.
+    Cell(Rc<RefCell<Value>>),
+}
+
+impl Value {
+    pub fn unit() -> Self {
+        Self::Tuple(Rc::new(Vec::new()))
+    }
+
+    /// The name of the value's type, for error messages.
+    pub fn type_name(&self) -> &str {
+        match self {
+            Self::Int(_) => "integer",
+            Self::Float(_) => "float",
+            Self::Bool(_) => "bool",
+            Self::Str(_) => "string",
+            Self::Array(_) => "array",
+            Self::Tuple(_) => "tuple",
+            Self::Range(..) => "range",
+            Self::Struct(shape, _) => &shape.name,
+            Self::Function(_) | Self::Closure(_) | Self::Builtin(_) => "function",
+            Self::Cell(_) => "cell",
+        }
+    }
+
+    /// Writes the value the way it appears inside an array, tuple or struct,
+    /// where strings are quoted.
+    fn fmt_nested(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Str(s) => write!(f, "{:?}", s),
+            _ => write!(f, "{}", self),
+        }
+    }
+}
+
+impl Default for Value {
+    fn default() -> Self {
+        Self::unit()
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Int(n) => write!(f, "{}", n),
+            Self::Float(n) => write!(f, "{:?}", n),
+            Self::Bool(b) => write!(f, "{}", b),
+            Self::Str(s) => write!(f, "{}", s),
+            Self::Array(values) => {
+                write!(f, "[")?;
+                fmt_list(f, values)?;
+                write!(f, "]")
+            }
+            Self::Tuple(values) => {
+                write!(f, "(")?;
+                fmt_list(f, values)?;
+                write!(f, ")")
+            }
+            Self::Range(start, end, None) => write!(f, "{}..{}", start, end),
+            Self::Range(start, end, Some(step)) => write!(f, "{}..{}={}", start, end, step),
+            Self::Struct(shape, fields) => {
+                write!(f, "{} {{", shape.name)?;
+                for (i, (name, value)) in shape.fields.iter().zip(fields.iter()).enumerate() {
+                    write!(f, "{} {}: ", if i == 0 { "" } else { "," }, name)?;
+                    value.fmt_nested(f)?;
+                }
+                write!(f, " }}")
+            }
+            Self::Function(_) | Self::Builtin(_) => write!(f, "<function>"),
+            Self::Closure(_) => write!(f, "<closure>"),
+            Self::Cell(cell) => write!(f, "{}", cell.borrow()),
+        }
+    }
+}
+
+fn fmt_list(f: &mut Formatter<'_>, values: &[Value]) -> fmt::Result {
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        value.fmt_nested(f)?;
+    }
+    Ok(())
+}
+
+/// A closure together with the cells of the bindings it captures.
+#[derive(Debug)]
+pub struct Closure {
+    pub(crate) function: u32,
+    pub(crate) upvalues: Vec<Rc<RefCell<Value>>>,
+}
+
+// two closures are the same if they run the same code on the same bindings
+impl PartialEq for Closure {
+    fn eq(&self, other: &Self) -> bool {
+        self.function == other.function
+            && self.upvalues.len() == other.upvalues.len()
+            && self
+                .upvalues
+                .iter()
+                .zip(&other.upvalues)
+                .all(|(a, b)| Rc::ptr_eq(a, b))
+    }
+}
@@ -0,0 +1,734 @@
+//! Execution of compiled programs.
+//!
+//! All calls share one stack of registers: a call's registers start where
+//! its caller's end, and are dropped when it returns. The VM reports the
+//! same errors, at the same ranges and with the same stack traces, as the
+//! interpreter.
+
+use crate::bytecode::{Capture, Constant, Function, Instr, Program, Shape};
+use crate::value::{Closure, Value};
+use drip_hir::infer::IntTy;
+use drip_hir::resolve::Builtin;
+use drip_hir::BinaryOp;
+use drip_interp::{RuntimeError, RuntimeErrorKind};
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::io::Write;
+use std::mem;
+use std::rc::Rc;
+
+/// How deep calls can nest before the program is stopped, which is the same
+/// as in the interpreter.
+const MAX_DEPTH: usize = 1000;
+
+struct Frame {
+    function: u32,
+    ip: usize,
+    base: usize,
+    closure: Option<Rc<Closure>>,
+    /// The caller's register the result goes in.
+    dst: usize,
+    /// The caller's register a method's final `self` goes in.
+    write_back: Option<usize>,
+}
+
+pub(crate) struct Vm<'a> {
+    program: &'a Program,
+    constants: Vec<Value>,
+    shapes: Vec<Rc<Shape>>,
+    out: &'a mut dyn Write,
+    globals: Vec<Option<Value>>,
+    registers: Vec<Value>,
+    frames: Vec<Frame>,
+}
+
+type Exec<T = ()> = Result<T, RuntimeErrorKind>;
+
+impl<'a> Vm<'a> {
+    pub(crate) fn new(program: &'a Program, out: &'a mut dyn Write) -> Self {
+        let constants = program
+            .constants
+            .iter()
+            .map(|constant| match constant {
+                Constant::Int(n) => Value::Int(*n),
+                Constant::Float(n) => Value::Float(*n),
+                Constant::Str(s) => Value::Str(Rc::from(s.as_str())),
+            })
+            .collect();
+        Self {
+            program,
+            constants,
+            shapes: program.shapes.iter().cloned().map(Rc::new).collect(),
+            out,
+            globals: vec![None; program.globals.len()],
+            registers: Vec::new(),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Runs the top-level code, then `main` if there is one.
+    pub(crate) fn run(&mut self) -> Result<(), RuntimeError> {
+        self.run_function(self.program.entry)?;
+        if let Some(main) = self.program.main {
+            self.run_function(main)?;
+        }
+        Ok(())
+    }
+
+    fn run_function(&mut self, function: u32) -> Result<(), RuntimeError> {
+        let registers = self.program.functions[function as usize].registers;
+        self.registers.clear();
+        self.registers.resize(registers, Value::unit());
+        self.frames.push(Frame {
+            function,
+            ip: 0,
+            base: 0,
+            closure: None,
+            dst: 0,
+            write_back: None,
+        });
+
+        let result = self.execute();
+        if let Err(kind) = result {
+            let error = self.error(kind);
+            self.frames.clear();
+            return Err(error);
+        }
+        Ok(())
+    }
+
+    /// Turns an error in the innermost call into one with a stack trace.
+    fn error(&self, kind: RuntimeErrorKind) -> RuntimeError {
+        let mut frames = self.frames.iter().rev().map(|frame| {
+            let function = &self.program.functions[frame.function as usize];
+            let range = function.ranges[frame.ip.saturating_sub(1)];
+            (function.name.clone(), range)
+        });
+        let (function, range) = frames.next().expect("errors happen in a call");
+        let mut error = RuntimeError::new(kind, range, function);
+        for (function, range) in frames {
+            error = error.called_from(function, range);
+        }
+        error
+    }
+
+    fn execute(&mut self) -> Exec {
+        let program = self.program;
+        let (mut function, mut base, mut ip) = self.position();
+
+        macro_rules! reg {
+            ($reg:expr) => {
+                self.registers[base + $reg as usize]
+            };
+        }
+        // the frame's position is only written back when something needs it:
+        // before calls, returns and errors
+        macro_rules! sync {
+            () => {
+                self.frames.last_mut().expect("a function is running").ip = ip
+            };
+        }
+        macro_rules! fail {
+            ($kind:expr) => {{
+                sync!();
+                return Err($kind);
+            }};
+        }
+        macro_rules! try_exec {
+            ($result:expr) => {
+                match $result {
+                    Ok(value) => value,
+                    Err(kind) => fail!(kind),
+                }
+            };
+        }
+
+        loop {
+            let instr = function.code[ip];
+            ip += 1;
+            match instr {
+                Instr::LoadConst { dst, constant } => {
+                    reg!(dst) = self.constants[constant as usize].clone();
+                }
+                Instr::LoadUnit { dst } => reg!(dst) = Value::unit(),
+                Instr::LoadBool { dst, value } => reg!(dst) = Value::Bool(value),
+                Instr::LoadFunction { dst, function } => reg!(dst) = Value::Function(function),
+                Instr::LoadBuiltin { dst, builtin } => reg!(dst) = Value::Builtin(builtin),
+                Instr::Move { dst, src } => reg!(dst) = reg!(src).clone(),
+                Instr::GetGlobal { dst, global } => {
+                    reg!(dst) = try_exec!(self.global(global)).clone();
+                }
+                Instr::TakeGlobal { dst, global } => {
+                    let value = mem::take(try_exec!(self.global(global)));
+                    reg!(dst) = value;
+                }
+                Instr::SetGlobal { global, src } => {
+                    self.globals[global as usize] = Some(reg!(src).clone());
+                }
+                Instr::Box { reg } => {
+                    let value = mem::take(&mut reg!(reg));
+                    reg!(reg) = Value::Cell(Rc::new(RefCell::new(value)));
+                }
+                Instr::GetCell { dst, cell } => {
+                    let value = cell_of(&reg!(cell)).borrow().clone();
+                    reg!(dst) = value;
+                }
+                Instr::TakeCell { dst, cell } => {
+                    let value = mem::take(&mut *cell_of(&reg!(cell)).borrow_mut());
+                    reg!(dst) = value;
+                }
+                Instr::SetCell { cell, src } => {
+                    let value = reg!(src).clone();
+                    *cell_of(&reg!(cell)).borrow_mut() = value;
+                }
+                Instr::GetUpvalue { dst, upvalue } => {
+                    let value = self.upvalue(upvalue).borrow().clone();
+                    reg!(dst) = value;
+                }
+                Instr::TakeUpvalue { dst, upvalue } => {
+                    let value = mem::take(&mut *self.upvalue(upvalue).borrow_mut());
+                    reg!(dst) = value;
+                }
+                Instr::SetUpvalue { upvalue, src } => {
+                    let value = reg!(src).clone();
+                    *self.upvalue(upvalue).borrow_mut() = value;
+                }
+                Instr::Closure { dst, function } => {
+                    let upvalues = program.functions[function as usize]
+                        .captures
+                        .iter()
+                        .map(|capture| match capture {
+                            Capture::Local(reg) => cell_of(&reg!(*reg)).clone(),
+                            Capture::Upvalue(upvalue) => self.upvalue(*upvalue).clone(),
+                        })
+                        .collect();
+                    reg!(dst) = Value::Closure(Rc::new(Closure { function, upvalues }));
+                }
+                Instr::Binary {
+                    op,
+                    dst,
+                    lhs,
+                    rhs,
+                    int,
+                } => {
+                    let value = try_exec!(binary(op, &reg!(lhs), &reg!(rhs), int));
+                    reg!(dst) = value;
+                }
+                Instr::Neg { dst, src, int } => {
+                    let value = match &reg!(src) {
+                        Value::Int(n) => try_exec!(check_int(n.checked_neg(), int)),
+                        Value::Float(n) => Value::Float(-n),
+                        value => fail!(mismatch("a number", value)),
+                    };
+                    reg!(dst) = value;
+                }
+                Instr::Jump { target } => ip = target as usize,
+                Instr::JumpIfFalse { cond, target } => match reg!(cond) {
+                    Value::Bool(true) => {}
+                    Value::Bool(false) => ip = target as usize,
+                    ref value => fail!(mismatch("a bool", value)),
+                },
+                Instr::ForNext {
+                    iterable,
+                    counter,
+                    dst,
+                    exit,
+                } => {
+                    let i = match reg!(counter) {
+                        Value::Int(i) => i + 1,
+                        _ => unreachable!("loop counters are integers"),
+                    };
+                    match try_exec!(nth(&reg!(iterable), i)) {
+                        Some(value) => {
+                            reg!(dst) = value;
+                            reg!(counter) = Value::Int(i);
+                        }
+                        None => ip = exit as usize,
+                    }
+                }
+                Instr::Call {
+                    dst,
+                    callee,
+                    args,
+                    argc,
+                } => {
+                    sync!();
+                    let callee = reg!(callee).clone();
+                    try_exec!(self.call(callee, base + dst as usize, base + args as usize, argc));
+                    let position = self.position();
+                    function = position.0;
+                    base = position.1;
+                    ip = position.2;
+                }
+                Instr::CallMethod {
+                    dst,
+                    name,
+                    method,
+                    args,
+                    argc,
+                } => {
+                    sync!();
+                    let receiver = base + args as usize;
+                    let found = match &self.registers[receiver] {
+                        Value::Struct(shape, _) => program.methods.get(&(shape.id, name)).copied(),
+                        _ => None,
+                    };
+                    match found.or(method) {
+                        Some(method) => try_exec!(self.push_frame(
+                            method,
+                            None,
+                            base + dst as usize,
+                            receiver,
+                            argc + 1,
+                            Some(receiver),
+                        )),
+                        // a field that holds a function, like `self.sound()`
+                        None => {
+                            let callee = try_exec!(field(&self.registers[receiver], name, self));
+                            try_exec!(self.call(callee, base + dst as usize, receiver + 1, argc));
+                        }
+                    }
+                    let position = self.position();
+                    function = position.0;
+                    base = position.1;
+                    ip = position.2;
+                }
+                Instr::CallOperator {
+                    dst,
+                    function: callee,
+                    args,
+                    argc,
+                } => {
+                    sync!();
+                    let receiver = base + args as usize;
+                    try_exec!(self.push_frame(
+                        callee,
+                        None,
+                        base + dst as usize,
+                        receiver,
+                        argc + 1,
+                        Some(receiver),
+                    ));
+                    let position = self.position();
+                    function = position.0;
+                    base = position.1;
+                    ip = position.2;
+                }
+                Instr::Return { src } => {
+                    let value = mem::take(&mut reg!(src));
+                    let done = self.frames.pop().expect("a function is running");
+                    if let Some(write_back) = done.write_back {
+                        let receiver = match mem::take(&mut self.registers[done.base]) {
+                            Value::Cell(cell) => cell.borrow().clone(),
+                            value => value,
+                        };
+                        self.registers[write_back] = receiver;
+                    }
+                    self.registers.truncate(done.base);
+
+                    if self.frames.is_empty() {
+                        return Ok(());
+                    }
+                    self.registers[done.dst] = value;
+                    let position = self.position();
+                    function = position.0;
+                    base = position.1;
+                    ip = position.2;
+                }
+                Instr::MakeArray { dst, start, len } => {
+                    let values = self.take_range(base + start as usize, len);
+                    reg!(dst) = Value::Array(Rc::new(values));
+                }
+                Instr::MakeTuple { dst, start, len } => {
+                    let values = self.take_range(base + start as usize, len);
+                    reg!(dst) = Value::Tuple(Rc::new(values));
+                }
+                Instr::MakeRange {
+                    dst,
+                    start,
+                    end,
+                    step,
+                } => {
+                    let start = Box::new(reg!(start).clone());
+                    let end = Box::new(reg!(end).clone());
+                    let step = step.map(|step| Box::new(reg!(step).clone()));
+                    reg!(dst) = Value::Range(start, end, step);
+                }
+                Instr::MakeStruct { dst, shape, start } => {
+                    let shape = self.shapes[shape as usize].clone();
+                    let values = self.take_range(base + start as usize, shape.fields.len() as u16);
+                    reg!(dst) = Value::Struct(shape, Rc::new(values));
+                }
+                Instr::Concat { dst, start, len } => {
+                    let mut s = String::new();
+                    for reg in start..start + len {
+                        s.push_str(&reg!(reg).to_string());
+                    }
+                    reg!(dst) = Value::Str(Rc::from(s));
+                }
+                Instr::GetField {
+                    dst,
+                    base: src,
+                    name,
+                } => {
+                    let value = try_exec!(field(&reg!(src), name, self));
+                    reg!(dst) = value;
+                }
+                Instr::TakeField {
+                    dst,
+                    base: src,
+                    name,
+                } => {
+                    let name = self.name(name);
+                    let value = mem::take(try_exec!(field_mut(&mut reg!(src), name)));
+                    reg!(dst) = value;
+                }
+                Instr::SetField {
+                    base: dst,
+                    name,
+                    src,
+                } => {
+                    let value = reg!(src).clone();
+                    let name = self.name(name);
+                    *try_exec!(field_mut(&mut reg!(dst), name)) = value;
+                }
+                Instr::GetIndex {
+                    dst,
+                    base: src,
+                    index,
+                } => {
+                    let index = try_exec!(int_index(&reg!(index)));
+                    let value = match &reg!(src) {
+                        Value::Array(values) => {
+                            values[try_exec!(check_bounds(index, values.len()))].clone()
+                        }
+                        value => fail!(mismatch("an array", value)),
+                    };
+                    reg!(dst) = value;
+                }
+                Instr::TakeIndex {
+                    dst,
+                    base: src,
+                    index,
+                } => {
+                    let index = try_exec!(int_index(&reg!(index)));
+                    let value = mem::take(try_exec!(element_mut(&mut reg!(src), index)));
+                    reg!(dst) = value;
+                }
+                Instr::SetIndex {
+                    base: dst,
+                    index,
+                    src,
+                } => {
+                    let index = try_exec!(int_index(&reg!(index)));
+                    let value = reg!(src).clone();
+                    *try_exec!(element_mut(&mut reg!(dst), index)) = value;
+                }
+                Instr::GetElement {
+                    dst,
+                    base: src,
+                    index,
+                } => {
+                    let value = match &reg!(src) {
+                        Value::Tuple(values) => values[index as usize].clone(),
+                        value => fail!(mismatch("a tuple", value)),
+                    };
+                    reg!(dst) = value;
+                }
+                Instr::IsTuple { dst, src, len } => {
+                    let is =
+                        matches!(&reg!(src), Value::Tuple(values) if values.len() == len as usize);
+                    reg!(dst) = Value::Bool(is);
+                }
+                Instr::IsStruct { dst, src, name } => {
+                    let name = self.name(name);
+                    let is = matches!(&reg!(src), Value::Struct(shape, _) if shape.name == name);
+                    reg!(dst) = Value::Bool(is);
+                }
+                Instr::Placeholder => fail!(RuntimeErrorKind::Placeholder),
+                Instr::NoMatch => fail!(RuntimeErrorKind::NoMatch),
+            }
+        }
+    }
+
+    /// The function, registers and next instruction of the innermost call.
+    fn position(&self) -> (&'a Function, usize, usize) {
+        let frame = self.frames.last().expect("a function is running");
+        let function = &self.program.functions[frame.function as usize];
+        (function, frame.base, frame.ip)
+    }
+
+    fn global(&mut self, global: u32) -> Exec<&mut Value> {
+        match &mut self.globals[global as usize] {
+            Some(value) => Ok(value),
+            None => {
+                let name = self.program.globals[global as usize].clone();
+                Err(RuntimeErrorKind::Uninitialized { name })
+            }
+        }
+    }
+
+    fn upvalue(&self, upvalue: u32) -> &Rc<RefCell<Value>> {
+        let frame = self.frames.last().expect("a function is running");
+        let closure = frame
+            .closure
+            .as_ref()
+            .expect("upvalues are read by closures");
+        &closure.upvalues[upvalue as usize]
+    }
+
+    /// The string constant `name`.
+    fn name(&self, name: u32) -> &'a str {
+        match &self.program.constants[name as usize] {
+            Constant::Str(s) => s,
+            _ => unreachable!("names are string constants"),
+        }
+    }
+
+    fn take_range(&mut self, start: usize, len: u16) -> Vec<Value> {
+        self.registers[start..start + len as usize]
+            .iter_mut()
+            .map(mem::take)
+            .collect()
+    }
+
+    /// Calls `callee` with the `argc` registers at `args`, for a call whose
+    /// result goes in `dst`.
+    fn call(&mut self, callee: Value, dst: usize, args: usize, argc: u8) -> Exec {
+        match callee {
+            Value::Function(function) => self.push_frame(function, None, dst, args, argc, None),
+            Value::Closure(closure) => {
+                let function = closure.function;
+                self.push_frame(function, Some(closure), dst, args, argc, None)
+            }
+            Value::Builtin(builtin) => {
+                let text = self.registers[args..args + argc as usize]
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let result = match builtin {
+                    Builtin::Print => write!(self.out, "{}", text),
+                    Builtin::Println => writeln!(self.out, "{}", text),
+                };
+                match result {
+                    Ok(()) => {
+                        self.registers[dst] = Value::unit();
+                        Ok(())
+                    }
+                    Err(error) => Err(RuntimeErrorKind::Output(error.to_string())),
+                }
+            }
+            callee => Err(mismatch("a function", &callee)),
+        }
+    }
+
+    fn push_frame(
+        &mut self,
+        function: u32,
+        closure: Option<Rc<Closure>>,
+        dst: usize,
+        args: usize,
+        argc: u8,
+        write_back: Option<usize>,
+    ) -> Exec {
+        if self.frames.len() >= MAX_DEPTH {
+            return Err(RuntimeErrorKind::StackOverflow);
+        }
+
+        let caller = self.frames.last().expect("a function is running");
+        let base = caller.base + self.program.functions[caller.function as usize].registers;
+        let callee: &Function = &self.program.functions[function as usize];
+        self.registers
+            .resize(base + callee.registers.max(argc as usize), Value::unit());
+        for i in 0..argc as usize {
+            self.registers[base + i] = mem::take(&mut self.registers[args + i]);
+        }
+        self.frames.push(Frame {
+            function,
+            ip: 0,
+            base,
+            closure,
+            dst,
+            write_back,
+        });
+        Ok(())
+    }
+}
+
+fn cell_of(value: &Value) -> &Rc<RefCell<Value>> {
+    match value {
+        Value::Cell(cell) => cell,
+        _ => unreachable!("captured bindings are boxed"),
+    }
+}
+
+fn mismatch(expected: &'static str, found: &Value) -> RuntimeErrorKind {
+    RuntimeErrorKind::Mismatch {
+        expected,
+        found: found.type_name().into(),
+    }
+}
+
+fn field(value: &Value, name: u32, vm: &Vm) -> Exec<Value> {
+    let name = vm.name(name);
+    match value {
+        Value::Struct(shape, fields) => match shape.fields.iter().position(|f| f == name) {
+            Some(i) => Ok(fields[i].clone()),
+            None => Err(RuntimeErrorKind::NoField {
+                ty: shape.name.clone(),
+                name: name.into(),
+            }),
+        },
+        value => Err(mismatch("a struct", value)),
+    }
+}
+
+fn field_mut<'v>(value: &'v mut Value, name: &str) -> Exec<&'v mut Value> {
+    match value {
+        Value::Struct(shape, fields) => match shape.fields.iter().position(|f| f == name) {
+            Some(i) => Ok(&mut Rc::make_mut(fields)[i]),
+            None => Err(RuntimeErrorKind::NoField {
+                ty: shape.name.clone(),
+                name: name.into(),
+            }),
+        },
+        value => Err(mismatch("a struct", value)),
+    }
+}
+
+fn element_mut(value: &mut Value, index: i128) -> Exec<&mut Value> {
+    match value {
+        Value::Array(values) => {
+            let i = check_bounds(index, values.len())?;
+            Ok(&mut Rc::make_mut(values)[i])
+        }
+        value => Err(mismatch("an array", value)),
+    }
+}
+
+fn int_index(value: &Value) -> Exec<i128> {
+    match value {
+        Value::Int(n) => Ok(*n),
+        value => Err(mismatch("an integer", value)),
+    }
+}
+
+fn check_bounds(index: i128, len: usize) -> Exec<usize> {
+    match usize::try_from(index) {
+        Ok(i) if i < len => Ok(i),
+        _ => Err(RuntimeErrorKind::IndexOutOfBounds { index, len }),
+    }
+}
+
+/// The `i`th element of an array or range, or `None` if it has no more.
+fn nth(iterable: &Value, i: i128) -> Exec<Option<Value>> {
+    match iterable {
+        Value::Array(values) => Ok(values.get(i as usize).cloned()),
+        Value::Range(start, end, step) => match (&**start, &**end, step.as_deref()) {
+            (Value::Int(start), Value::Int(end), None | Some(Value::Int(_))) => {
+                let step = match step.as_deref() {
+                    Some(Value::Int(step)) => *step,
+                    _ => 1,
+                };
+                if step == 0 {
+                    return Err(RuntimeErrorKind::ZeroStep);
+                }
+                let n = start + i * step;
+                let in_range = if step > 0 { n < *end } else { n > *end };
+                Ok(if in_range { Some(Value::Int(n)) } else { None })
+            }
+            (Value::Float(start), Value::Float(end), None | Some(Value::Float(_))) => {
+                let step = match step.as_deref() {
+                    Some(Value::Float(step)) => *step,
+                    _ => 1.0,
+                };
+                if step == 0.0 {
+                    return Err(RuntimeErrorKind::ZeroStep);
+                }
+                let len = ((end - start) / step).ceil().max(0.0) as i128;
+                Ok(if i < len {
+                    Some(Value::Float(start + i as f64 * step))
+                } else {
+                    None
+                })
+            }
+            (start, ..) => Err(mismatch("a number", start)),
+        },
+        value => Err(mismatch("an array or range", value)),
+    }
+}
+
+/// Applies `op` to two values, checking that an integer result fits in
+/// `int`.
+fn binary(op: BinaryOp, lhs: &Value, rhs: &Value, int: Option<IntTy>) -> Exec<Value> {
+    if !op.is_comparison() {
+        return match (lhs, rhs) {
+            (Value::Int(_), Value::Int(0)) if op == BinaryOp::Div => {
+                Err(RuntimeErrorKind::DivisionByZero)
+            }
+            (Value::Int(lhs), Value::Int(rhs)) => {
+                let n = match op {
+                    BinaryOp::Add => lhs.checked_add(*rhs),
+                    BinaryOp::Sub => lhs.checked_sub(*rhs),
+                    BinaryOp::Mul => lhs.checked_mul(*rhs),
+                    _ => lhs.checked_div(*rhs),
+                };
+                check_int(n, int)
+            }
+            (Value::Float(lhs), Value::Float(rhs)) => Ok(Value::Float(match op {
+                BinaryOp::Add => lhs + rhs,
+                BinaryOp::Sub => lhs - rhs,
+                BinaryOp::Mul => lhs * rhs,
+                _ => lhs / rhs,
+            })),
+            (Value::Str(lhs), Value::Str(rhs)) if op == BinaryOp::Add => {
+                let mut s = String::with_capacity(lhs.len() + rhs.len());
+                s.push_str(lhs);
+                s.push_str(rhs);
+                Ok(Value::Str(Rc::from(s)))
+            }
+            (lhs, _) => Err(mismatch("a number", lhs)),
+        };
+    }
+
+    let ordering = match op {
+        BinaryOp::Eq => return Ok(Value::Bool(lhs == rhs)),
+        BinaryOp::NotEq => return Ok(Value::Bool(lhs != rhs)),
+        _ => match compare(lhs, rhs) {
+            Some(ordering) => ordering,
+            // NaN isn't ordered with anything
+            None if matches!(lhs, Value::Float(_)) => return Ok(Value::Bool(false)),
+            None => return Err(mismatch("a number or string", lhs)),
+        },
+    };
+    Ok(Value::Bool(match op {
+        BinaryOp::Less => ordering == Ordering::Less,
+        BinaryOp::LessEq => ordering != Ordering::Greater,
+        BinaryOp::Greater => ordering == Ordering::Greater,
+        _ => ordering != Ordering::Less,
+    }))
+}
+
+/// Checks that the result of integer arithmetic fits in its type; `None`
+/// means it didn't even fit in an `i128`.
+fn check_int(n: Option<i128>, int: Option<IntTy>) -> Exec<Value> {
+    match (n, int) {
+        (Some(n), Some(ty)) if (ty.bounds().0..=ty.bounds().1).contains(&n) => Ok(Value::Int(n)),
+        (Some(n), None) => Ok(Value::Int(n)),
+        (_, ty) => {
+            let ty = ty.map_or("i128", |ty| ty.name());
+            Err(RuntimeErrorKind::Overflow { ty })
+        }
+    }
+}
+
+fn compare(lhs: &Value, rhs: &Value) -> Option<Ordering> {
+    match (lhs, rhs) {
+        (Value::Int(lhs), Value::Int(rhs)) => Some(lhs.cmp(rhs)),
+        (Value::Float(lhs), Value::Float(rhs)) => lhs.partial_cmp(rhs),
+        (Value::Str(lhs), Value::Str(rhs)) => Some(lhs.cmp(rhs)),
+        _ => None,
+    }
+}
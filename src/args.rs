@@ -0,0 +1,272 @@
+//! The command line.
+
+use std::path::PathBuf;
+
+pub(crate) const USAGE: &str = "\
+usage: drip <command> [<flags>] [<paths>]
+
+commands:
+  run <path>         run a file, or the `main.drip` of a directory
+    --interpret        use the tree-walking interpreter instead of the VM
+    --emit=bytecode    print the compiled bytecode instead of running it
+  check <paths>      report the errors and warnings in every file
+  parse <paths>      print what the compiler makes of every file
+    --emit=<form>      tokens, cst, ast, hir or json (default: cst)
+  fmt <paths>        format every file in place
+    --check            print a diff of the files that would change instead
+  repl               start the REPL, which is also what `drip` alone does
+    --json             print the syntax of every line read as JSON instead
+  highlight <paths>  print every file with its syntax highlighted
+    --html             as HTML, with a class on every token, instead of in
+                       colour
+    --semantic         tell names apart by what they refer to
+  lsp                start the language server, which talks over standard
+                     input and output
+  help               print this message
+
+A directory stands for every `.drip` file in it and `-` for standard input.
+Without paths, the current directory is used.
+
+exit status:
+  0  success
+  1  a file had errors or failed at runtime, or `fmt --check` found a file
+     that isn't formatted
+  2  the command line was wrong, or a file couldn't be read or written
+";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Command {
+    Run {
+        path: PathBuf,
+        interpret: bool,
+        emit_bytecode: bool,
+    },
+    Check {
+        paths: Vec<PathBuf>,
+    },
+    Parse {
+        emit: Emit,
+        paths: Vec<PathBuf>,
+    },
+    Fmt {
+        check: bool,
+        paths: Vec<PathBuf>,
+    },
+    Repl {
+        json: bool,
+    },
+    Highlight {
+        html: bool,
+        semantic: bool,
+        paths: Vec<PathBuf>,
+    },
+    Lsp,
+    Help,
+}
+
+/// A form of the code that `drip parse` can print.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Emit {
+    Tokens,
+    Cst,
+    Ast,
+    Hir,
+    Json,
+}
+
+/// Parses the arguments that follow the program's name.
+pub(crate) fn parse(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let args: Vec<_> = args.into_iter().collect();
+    let (command, args) = match args.first().map(String::as_str) {
+        None => ("repl", &args[..]),
+        Some("-h") | Some("--help") => ("help", &args[1..]),
+        // `drip --json` and `drip program.drip` are short for `drip repl
+        // --json` and `drip run program.drip`
+        Some(arg) if arg.starts_with("--") => ("repl", &args[..]),
+        Some(arg) if arg.ends_with(".drip") => ("run", &args[..]),
+        Some(command) => (command, &args[1..]),
+    };
+
+    let (flags, mut paths): (Vec<_>, Vec<_>) =
+        args.iter().cloned().partition(|arg| arg.starts_with("--"));
+    let mut flags = Flags(flags);
+    let command = match command {
+        "run" => {
+            if paths.len() != 1 {
+                return Err("`run` takes one file or directory".to_string());
+            }
+            let emit_bytecode = match flags.value("--emit").as_deref() {
+                Some("bytecode") => true,
+                Some(form) => return Err(format!("`run` can't emit `{}`", form)),
+                None => false,
+            };
+            Command::Run {
+                path: paths.remove(0).into(),
+                interpret: flags.switch("--interpret"),
+                emit_bytecode,
+            }
+        }
+        "check" => Command::Check {
+            paths: default_paths(paths),
+        },
+        "parse" => {
+            let emit = match flags.value("--emit").as_deref() {
+                Some("tokens") => Emit::Tokens,
+                Some("cst") | None => Emit::Cst,
+                Some("ast") => Emit::Ast,
+                Some("hir") => Emit::Hir,
+                Some("json") => Emit::Json,
+                Some(form) => return Err(format!("`parse` can't emit `{}`", form)),
+            };
+            Command::Parse {
+                emit,
+                paths: default_paths(paths),
+            }
+        }
+        "fmt" => Command::Fmt {
+            check: flags.switch("--check"),
+            paths: default_paths(paths),
+        },
+        "highlight" => Command::Highlight {
+            html: flags.switch("--html"),
+            semantic: flags.switch("--semantic"),
+            paths: default_paths(paths),
+        },
+        "repl" | "lsp" | "help" if !paths.is_empty() => {
+            return Err(format!("`{}` doesn't take paths", command))
+        }
+        "repl" => Command::Repl {
+            json: flags.switch("--json"),
+        },
+        "lsp" => Command::Lsp,
+        "help" => Command::Help,
+        _ => return Err(format!("unknown command `{}`", command)),
+    };
+
+    match flags.0.first() {
+        Some(flag) => Err(format!("unknown flag `{}`", flag)),
+        None => Ok(command),
+    }
+}
+
+fn default_paths(paths: Vec<String>) -> Vec<PathBuf> {
+    if paths.is_empty() {
+        return vec![PathBuf::from(".")];
+    }
+    paths.into_iter().map(PathBuf::from).collect()
+}
+
+/// The flags that haven't been looked at yet.
+struct Flags(Vec<String>);
+
+impl Flags {
+    /// Whether the flag `name` was given.
+    fn switch(&mut self, name: &str) -> bool {
+        let len = self.0.len();
+        self.0.retain(|flag| flag != name);
+        self.0.len() != len
+    }
+
+    /// The value of the flag `name`, given as `name=value`.
+    fn value(&mut self, name: &str) -> Option<String> {
+        let i = self.0.iter().position(|flag| {
+            flag.strip_prefix(name)
+                .is_some_and(|rest| rest.starts_with('='))
+        })?;
+        let flag = self.0.remove(i);
+        Some(flag[name.len() + 1..].to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_str(args: &str) -> Result<Command, String> {
+        parse(args.split_whitespace().map(str::to_string))
+    }
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!(
+            parse_str("run main.drip --interpret"),
+            Ok(Command::Run {
+                path: "main.drip".into(),
+                interpret: true,
+                emit_bytecode: false,
+            })
+        );
+        assert_eq!(
+            parse_str("parse --emit=hir a.drip src"),
+            Ok(Command::Parse {
+                emit: Emit::Hir,
+                paths: vec!["a.drip".into(), "src".into()],
+            })
+        );
+        assert_eq!(
+            parse_str("fmt --check"),
+            Ok(Command::Fmt {
+                check: true,
+                paths: vec![".".into()],
+            })
+        );
+        assert_eq!(
+            parse_str("check -"),
+            Ok(Command::Check {
+                paths: vec!["-".into()],
+            })
+        );
+        assert_eq!(parse_str(""), Ok(Command::Repl { json: false }));
+        assert_eq!(
+            parse_str("highlight --html a.drip"),
+            Ok(Command::Highlight {
+                html: true,
+                semantic: false,
+                paths: vec!["a.drip".into()],
+            })
+        );
+        assert_eq!(parse_str("lsp"), Ok(Command::Lsp));
+        assert_eq!(parse_str("--help"), Ok(Command::Help));
+    }
+
+    #[test]
+    fn parse_shorthands() {
+        assert_eq!(parse_str("--json"), Ok(Command::Repl { json: true }));
+        assert_eq!(
+            parse_str("hello.drip --emit=bytecode"),
+            Ok(Command::Run {
+                path: "hello.drip".into(),
+                interpret: false,
+                emit_bytecode: true,
+            })
+        );
+    }
+
+    #[test]
+    fn reject_bad_command_lines() {
+        let errors: Vec<_> = [
+            "build",
+            "run",
+            "run a.drip b.drip",
+            "run a.drip --emit=ast",
+            "parse --emit=llvm",
+            "check --fix",
+            "repl src",
+        ]
+        .iter()
+        .map(|args| parse_str(args).unwrap_err())
+        .collect();
+        assert_eq!(
+            errors,
+            [
+                "unknown command `build`",
+                "`run` takes one file or directory",
+                "`run` takes one file or directory",
+                "`run` can't emit `ast`",
+                "`parse` can't emit `llvm`",
+                "unknown flag `--fix`",
+                "`repl` doesn't take paths",
+            ]
+        );
+    }
+}
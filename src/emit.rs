@@ -0,0 +1,160 @@
+//! The forms of the code that `drip parse` prints.
+
+use crate::args::Emit;
+use drip_ast::AstNode;
+use drip_hir::db::Diagnostic;
+use drip_hir::flow::Severity;
+use drip_syntax::{SyntaxElement, SyntaxKind, SyntaxNode};
+use std::fmt::Write;
+
+/// `text` in the form `emit`, ending in a newline.
+pub(crate) fn emit(emit: Emit, text: &str) -> String {
+    match emit {
+        Emit::Tokens => drip_lexer::Lexer::new(text)
+            .map(|token| format!("{:?}@{:?} {:?}\n", token.kind, token.range, token.text))
+            .collect(),
+        Emit::Cst => drip_parser::parse(text).debug_tree() + "\n",
+        Emit::Ast => {
+            let mut s = String::new();
+            ast(&drip_parser::parse(text).syntax(), 0, &mut s);
+            s
+        }
+        Emit::Hir => {
+            let root = drip_ast::Root::cast(drip_parser::parse(text).syntax()).unwrap();
+            let (tree, body) = drip_hir::lower(root);
+            drip_hir::pretty::render(&tree, &body)
+        }
+        Emit::Json => drip_json::document(text).to_string() + "\n",
+    }
+}
+
+/// The syntax tree without whitespace and comments, and without ranges.
+fn ast(node: &SyntaxNode, depth: usize, s: &mut String) {
+    writeln!(s, "{:indent$}{:?}", "", node.kind(), indent = depth * 2).unwrap();
+    for child in node.children_with_tokens() {
+        match child {
+            SyntaxElement::Node(node) => ast(&node, depth + 1, s),
+            SyntaxElement::Token(token) if !is_trivia(token.kind()) => writeln!(
+                s,
+                "{:indent$}{:?} {:?}",
+                "",
+                token.kind(),
+                token.text(),
+                indent = (depth + 1) * 2
+            )
+            .unwrap(),
+            SyntaxElement::Token(_) => {}
+        }
+    }
+}
+
+fn is_trivia(kind: SyntaxKind) -> bool {
+    matches!(kind, SyntaxKind::Whitespace | SyntaxKind::Comment)
+}
+
+/// The parse and validation errors in `text`.
+pub(crate) fn syntax_errors(text: &str) -> Vec<Diagnostic> {
+    let parse = drip_parser::parse(text);
+    let validation = drip_ast::validation::validate(&parse.syntax());
+    let parse_errors = parse.errors().iter().map(|e| (e.range, e.message()));
+    let validation_errors = validation.iter().map(|e| (e.range(), e.kind().to_string()));
+    parse_errors
+        .chain(validation_errors)
+        .map(|(range, message)| Diagnostic {
+            severity: Severity::Error,
+            range,
+            message,
+            related: Vec::new(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use expect_test::{expect, Expect};
+
+    fn check(form: Emit, input: &str, expect: Expect) {
+        expect.assert_eq(&emit(form, input));
+    }
+
+    #[test]
+    fn emit_every_form() {
+        let input = "n :: 1 // one\nn+2";
+        check(
+            Emit::Tokens,
+            input,
+            expect![[r#"
+                Ident@0..1 "n"
+                Whitespace@1..2 " "
+                ConstKw@2..4 "::"
+                Whitespace@4..5 " "
+                Number@5..6 "1"
+                Whitespace@6..7 " "
+                Comment@7..13 "// one"
+                Whitespace@13..14 "\n"
+                Ident@14..15 "n"
+                Plus@15..16 "+"
+                Number@16..17 "2"
+            "#]],
+        );
+        check(
+            Emit::Cst,
+            "n+",
+            expect![[r#"
+                Root@0..2
+                  InfixExpr@0..2
+                    VariableRef@0..1
+                      Ident@0..1 "n"
+                    Plus@1..2 "+"
+                error at 1..2: expected number, identifier, '-', '(', string, '[', 'self', '{', 'if', 'loop', 'while', 'for', 'break', 'continue', 'match', 'true', 'false', '|', '||' or '...'
+            "#]],
+        );
+        check(
+            Emit::Ast,
+            input,
+            expect![[r#"
+                Root
+                  ConstDef
+                    Ident "n"
+                    ConstKw "::"
+                    Literal
+                      Number "1"
+                  InfixExpr
+                    VariableRef
+                      Ident "n"
+                    Plus "+"
+                    Literal
+                      Number "2"
+            "#]],
+        );
+        check(
+            Emit::Hir,
+            input,
+            expect![[r#"
+                n ::
+                  1
+                Add
+                  ref n
+                  2
+            "#]],
+        );
+        assert!(emit(Emit::Json, input).starts_with("{\"diagnostics\":[],"));
+    }
+
+    #[test]
+    fn collect_syntax_errors() {
+        let messages: Vec<_> = syntax_errors("a :: (99999999999999999999\nb :: 2")
+            .into_iter()
+            .map(|d| format!("{:?}: {}", d.range, d.message))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "27..28: expected '+', '-', '*', '/', '==', '!=', '<', '<=', '>', '>=', '..', ',' or ')', but found identifier",
+                "6..26: number literal is larger than an integer's maximum value, 18446744073709551615",
+            ]
+        );
+        assert!(syntax_errors("a :: 1").is_empty());
+    }
+}
@@ -1,39 +1,238 @@
-use drip_parser::parse;
-use std::io;
-use std::io::Write;
+//! The `drip` command, which runs, checks, dumps, formats and highlights Drip
+//! code, or starts the REPL or the language server. See [`args::USAGE`] for
+//! what it takes and the exit statuses it uses.
 
-fn main() -> color_eyre::Result<()> {
-    let stdin = io::stdin();
-    let mut stdout = io::stdout();
-
-    let mut input = String::new();
+mod args;
+mod emit;
+mod source;
 
-    loop {
-        write!(stdout, "→ ")?;
-        stdout.flush()?;
+use args::{Command, Emit};
+use drip_hir::db::{Diagnostic, FileId, HirDatabase, SourceDatabase};
+use drip_hir::flow::Severity;
+use drip_interp::Error;
+use source::Source;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::{env, fs, process};
 
-        stdin.read_line(&mut input)?;
+/// A file had errors, failed at runtime or isn't formatted.
+const EXIT_FAILURE: i32 = 1;
+/// The command line was wrong, or a file couldn't be read or written.
+const EXIT_USAGE: i32 = 2;
 
-        let parse = parse(&input);
-        println!("{}", parse.debug_tree());
+fn main() {
+    let command = match args::parse(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, args::USAGE);
+            process::exit(EXIT_USAGE);
+        }
+    };
 
-        let syntax = parse.syntax();
+    let code = match execute(command) {
+        Ok(code) => code,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            EXIT_USAGE
+        }
+    };
+    // exiting doesn't flush what's buffered
+    let _ = io::stdout().flush();
+    process::exit(code);
+}
 
-        for error in drip_ast::validation::validate(&syntax) {
-            println!("{}", error);
+/// Runs `command`, returning the exit status, or the message for a file
+/// that couldn't be read or written.
+fn execute(command: Command) -> Result<i32, String> {
+    match command {
+        Command::Run {
+            path,
+            interpret,
+            emit_bytecode,
+        } => run(&path, interpret, emit_bytecode),
+        Command::Check { paths } => check(&paths),
+        Command::Parse { emit, paths } => parse(emit, &paths),
+        Command::Fmt { check, paths } => fmt(check, &paths),
+        Command::Repl { json } => repl(json).map_err(|error| error.to_string()),
+        Command::Highlight {
+            html,
+            semantic,
+            paths,
+        } => highlight(html, semantic, &paths),
+        Command::Lsp => drip_lsp::stdio()
+            .map(|()| 0)
+            .map_err(|error| error.to_string()),
+        Command::Help => {
+            print!("{}", args::USAGE);
+            Ok(0)
         }
+    }
+}
 
-        let root = drip_ast::Root::cast(syntax).unwrap();
+/// Runs the program at `path` with the VM, or the interpreter if
+/// `interpret` is set. `emit_bytecode` prints the compiled program instead.
+fn run(path: &Path, interpret: bool, emit_bytecode: bool) -> Result<i32, String> {
+    let source = source::read(source::entry(path)?)?;
+    let mut db = drip_hir::Database::default();
+    let file = FileId(0);
+    db.set_file_text(file, source.text.as_str().into());
+    db.set_root_file(file);
+    if report(&source, &db.diagnostics(file)) {
+        return Ok(EXIT_FAILURE);
+    }
 
-        dbg!(root
-            .stmts()
-            .filter_map(|stmt| if let drip_ast::Stmt::VariableDef(var_def) = stmt {
-                Some(var_def.value())
+    let mut out = io::stdout();
+    let result = if interpret {
+        drip_interp::run(&db, file, &mut out)
+    } else {
+        drip_vm::compile(&db, file).and_then(|program| {
+            if emit_bytecode {
+                print!("{}", program);
+                Ok(())
             } else {
-                None
-            })
-            .collect::<Vec<_>>());
+                drip_vm::execute(&program, &mut out)
+            }
+        })
+    };
+    match result {
+        Ok(()) => Ok(0),
+        Err(Error::Diagnostics(diagnostics)) => {
+            report(&source, &diagnostics);
+            Ok(EXIT_FAILURE)
+        }
+        Err(Error::Runtime(error)) => {
+            let _ = out.flush();
+            eprintln!("{}", source.runtime_error(&error));
+            Ok(EXIT_FAILURE)
+        }
+    }
+}
+
+/// Reports every diagnostic in the files at `paths`, followed by how many
+/// there were.
+fn check(paths: &[PathBuf]) -> Result<i32, String> {
+    let sources = source::load(paths)?;
+    let mut db = drip_hir::Database::default();
+    let (mut errors, mut warnings) = (0, 0);
+    for (i, source) in sources.iter().enumerate() {
+        let file = FileId(i as u32);
+        db.set_file_text(file, source.text.as_str().into());
+        for diagnostic in db.diagnostics(file).iter() {
+            match diagnostic.severity {
+                Severity::Error => errors += 1,
+                Severity::Warning => warnings += 1,
+            }
+            eprintln!("{}", source.diagnostic(diagnostic));
+        }
+    }
+
+    if errors + warnings > 0 {
+        eprintln!(
+            "{} and {} in {}",
+            source::plural(errors, "error"),
+            source::plural(warnings, "warning"),
+            source::plural(sources.len(), "file")
+        );
+    }
+    Ok(if errors > 0 { EXIT_FAILURE } else { 0 })
+}
+
+/// Prints every file at `paths` in the form `emit`, with a header naming
+/// the file if there are several. JSON is printed one document per line
+/// instead. Syntax errors are reported as well.
+fn parse(emit: Emit, paths: &[PathBuf]) -> Result<i32, String> {
+    let sources = source::load(paths)?;
+    let mut code = 0;
+    for source in &sources {
+        if sources.len() > 1 && emit != Emit::Json {
+            println!("==> {} <==", source.name());
+        }
+        print!("{}", emit::emit(emit, &source.text));
+        if report(source, &emit::syntax_errors(&source.text)) {
+            code = EXIT_FAILURE;
+        }
+    }
+    Ok(code)
+}
+
+/// Formats every file at `paths` in place, or prints it if it was read from
+/// standard input. With `check`, how the files that would change would
+/// change is printed as a diff instead.
+fn fmt(check: bool, paths: &[PathBuf]) -> Result<i32, String> {
+    let mut code = 0;
+    for source in source::load(paths)? {
+        let formatted = match drip_fmt::format(&source.text) {
+            Some(formatted) => formatted,
+            None => {
+                report(&source, &emit::syntax_errors(&source.text));
+                eprintln!("{} wasn't formatted, since it has errors", source.name());
+                code = EXIT_FAILURE;
+                continue;
+            }
+        };
+
+        if check {
+            if formatted != source.text {
+                let name = source.name();
+                let diff = similar::TextDiff::from_lines(&source.text, &formatted);
+                print!("{}", diff.unified_diff().header(&name, &name));
+                code = EXIT_FAILURE;
+            }
+        } else if source.is_stdin() {
+            print!("{}", formatted);
+        } else if formatted != source.text {
+            fs::write(&source.path, formatted)
+                .map_err(|error| source::cant("write", &source.path, error))?;
+        }
+    }
+    Ok(code)
+}
+
+/// Prints every file at `paths` with its syntax highlighted, in colour or
+/// with `html` as HTML, and with a header naming the file if there are
+/// several. With `semantic`, names are highlighted by what they refer to.
+fn highlight(html: bool, semantic: bool, paths: &[PathBuf]) -> Result<i32, String> {
+    let sources = source::load(paths)?;
+    let mut db = drip_hir::Database::default();
+    for (i, source) in sources.iter().enumerate() {
+        let file = FileId(i as u32);
+        db.set_file_text(file, source.text.as_str().into());
+        if html {
+            print!("{}", drip_ide::html(&db, file, semantic));
+        } else {
+            if sources.len() > 1 {
+                println!("==> {} <==", source.name());
+            }
+            print!("{}", drip_ide::ansi(&db, file, semantic));
+        }
+    }
+    Ok(0)
+}
+
+/// Starts the REPL, or with `json`, prints everything known about the syntax
+/// of each line read as a JSON document, for tools that consume the
+/// compiler's output.
+fn repl(json: bool) -> io::Result<i32> {
+    if !json {
+        drip_repl::run()?;
+        return Ok(0);
+    }
 
+    let stdin = io::stdin();
+    let mut input = String::new();
+    while stdin.read_line(&mut input)? != 0 {
+        println!("{}", drip_json::document(&input));
         input.clear();
     }
+    Ok(0)
+}
+
+/// Prints `diagnostics` in `source`, and returns whether any is an error.
+fn report(source: &Source, diagnostics: &[Diagnostic]) -> bool {
+    for diagnostic in diagnostics {
+        eprintln!("{}", source.diagnostic(diagnostic));
+    }
+    diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity == Severity::Error)
 }
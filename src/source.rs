@@ -0,0 +1,287 @@
+//! Reading source files, and pointing into them in messages.
+
+use drip_hir::db::Diagnostic;
+use drip_hir::flow::Severity;
+use drip_interp::RuntimeError;
+use std::fmt::Write;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use text_size::TextSize;
+
+/// The text of a file, and where it came from.
+pub(crate) struct Source {
+    /// The file's path, or `-` for standard input.
+    pub(crate) path: PathBuf,
+    pub(crate) text: String,
+}
+
+impl Source {
+    pub(crate) fn is_stdin(&self) -> bool {
+        self.path == Path::new("-")
+    }
+
+    pub(crate) fn name(&self) -> String {
+        if self.is_stdin() {
+            return "<stdin>".to_string();
+        }
+        self.path.display().to_string()
+    }
+
+    /// `offset` as `path:line:column`, counting both from one.
+    pub(crate) fn location(&self, offset: TextSize) -> String {
+        let before = &self.text[..usize::from(offset).min(self.text.len())];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        format!(
+            "{}:{}:{}",
+            self.name(),
+            before.matches('\n').count() + 1,
+            before[line_start..].chars().count() + 1
+        )
+    }
+
+    pub(crate) fn diagnostic(&self, diagnostic: &Diagnostic) -> String {
+        let severity = match diagnostic.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        let mut s = format!(
+            "{}: {}: {}",
+            self.location(diagnostic.range.start()),
+            severity,
+            diagnostic.message
+        );
+        for (range, note) in &diagnostic.related {
+            write!(s, "\n    {}: note: {}", self.location(range.start()), note).unwrap();
+        }
+        s
+    }
+
+    /// A runtime error, followed by the calls that led to it.
+    pub(crate) fn runtime_error(&self, error: &RuntimeError) -> String {
+        let mut s = format!(
+            "{}: error: {}",
+            self.location(error.range().start()),
+            error.kind()
+        );
+        // runaway recursion repeats the same call many times over
+        let mut frames = error.trace().iter().peekable();
+        while let Some(frame) = frames.next() {
+            let location = self.location(frame.range.start());
+            write!(s, "\n    in {} at {}", frame.function, location).unwrap();
+            let mut repeats = 0;
+            while frames.next_if_eq(&frame).is_some() {
+                repeats += 1;
+            }
+            if repeats > 0 {
+                write!(s, "\n    (repeated {} more times)", repeats).unwrap();
+            }
+        }
+        s
+    }
+}
+
+/// Reads the files that `paths` stand for, in the order of [`find`].
+pub(crate) fn load(paths: &[PathBuf]) -> Result<Vec<Source>, String> {
+    find(paths)?.into_iter().map(read).collect()
+}
+
+/// The files that `paths` stand for. A directory stands for every `.drip`
+/// file in it and the directories in it, sorted by path, except for hidden
+/// ones and Cargo's `target` directories.
+pub(crate) fn find(paths: &[PathBuf]) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    for path in paths {
+        if !path.is_dir() {
+            files.push(path.clone());
+            continue;
+        }
+
+        let start = files.len();
+        find_in(path, &mut files).map_err(|error| cant("read", path, error))?;
+        if files.len() == start {
+            return Err(format!(
+                "there are no `.drip` files in `{}`",
+                path.display()
+            ));
+        }
+        files[start..].sort();
+    }
+    Ok(files)
+}
+
+fn find_in(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if name.starts_with('.') || name == "target" {
+            continue;
+        }
+        if path.is_dir() {
+            find_in(&path, files)?;
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension == "drip")
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// The file a program at `path` starts in: `path` itself, or the
+/// `main.drip` in it if it's a directory.
+pub(crate) fn entry(path: &Path) -> Result<PathBuf, String> {
+    if !path.is_dir() {
+        return Ok(path.to_path_buf());
+    }
+    let main = path.join("main.drip");
+    if !main.is_file() {
+        return Err(format!("there's no `main.drip` in `{}`", path.display()));
+    }
+    Ok(main)
+}
+
+pub(crate) fn read(path: PathBuf) -> Result<Source, String> {
+    let text = if path == Path::new("-") {
+        let mut text = String::new();
+        io::stdin()
+            .read_to_string(&mut text)
+            .map(|_| text)
+            .map_err(|error| format!("can't read standard input: {}", error))?
+    } else {
+        fs::read_to_string(&path).map_err(|error| cant("read", &path, error))?
+    };
+    Ok(Source { path, text })
+}
+
+/// The message for an I/O `error` while trying to `verb` the file at `path`.
+pub(crate) fn cant(verb: &str, path: &Path, error: io::Error) -> String {
+    format!("can't {} `{}`: {}", verb, path.display(), error)
+}
+
+/// `count` followed by `noun`, which gets an `s` unless there's one.
+pub(crate) fn plural(count: usize, noun: &str) -> String {
+    let s = if count == 1 { "" } else { "s" };
+    format!("{} {}{}", count, noun, s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use drip_interp::RuntimeErrorKind;
+    use text_size::TextRange;
+
+    fn source(text: &str) -> Source {
+        Source {
+            path: "dir/main.drip".into(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn point_at_lines_and_columns() {
+        let source = source("a :: 1\nbé :: a +\n");
+        let at = |offset: u32| source.location(offset.into());
+        assert_eq!(at(0), "dir/main.drip:1:1");
+        assert_eq!(at(5), "dir/main.drip:1:6");
+        assert_eq!(at(7), "dir/main.drip:2:1");
+        assert_eq!(at(11), "dir/main.drip:2:4");
+        assert_eq!(at(100), "dir/main.drip:3:1");
+
+        let diagnostic = Diagnostic {
+            severity: Severity::Warning,
+            range: TextRange::new(11.into(), 12.into()),
+            message: "unused".to_string(),
+            related: Vec::new(),
+        };
+        assert_eq!(
+            source.diagnostic(&diagnostic),
+            "dir/main.drip:2:4: warning: unused"
+        );
+
+        let diagnostic = Diagnostic {
+            severity: Severity::Error,
+            range: TextRange::new(7.into(), 9.into()),
+            message: "cannot assign".to_string(),
+            related: vec![(
+                TextRange::new(0.into(), 6.into()),
+                "defined here".to_string(),
+            )],
+        };
+        assert_eq!(
+            source.diagnostic(&diagnostic),
+            "dir/main.drip:2:1: error: cannot assign
+    dir/main.drip:1:1: note: defined here"
+        );
+    }
+
+    #[test]
+    fn trace_runtime_errors() {
+        let source = source("f :: () { f() }\nf()");
+        let call = TextRange::new(10.into(), 13.into());
+        let mut error = RuntimeError::new(RuntimeErrorKind::StackOverflow, call, "`f`".into());
+        for _ in 0..3 {
+            error = error.called_from("`f`".into(), call);
+        }
+        error = error.called_from(
+            "top-level code".into(),
+            TextRange::new(16.into(), 19.into()),
+        );
+        assert_eq!(
+            source.runtime_error(&error),
+            format!(
+                "dir/main.drip:1:11: error: {}
+    in `f` at dir/main.drip:1:11
+    (repeated 3 more times)
+    in top-level code at dir/main.drip:2:1",
+                RuntimeErrorKind::StackOverflow
+            )
+        );
+    }
+
+    #[test]
+    fn find_files_in_directories() {
+        let root = std::env::temp_dir().join(format!("drip-find-{}", std::process::id()));
+        for file in [
+            "b.drip",
+            "a/z.drip",
+            "a/notes.txt",
+            ".git/x.drip",
+            "target/y.drip",
+        ] {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        fs::create_dir_all(root.join("empty")).unwrap();
+
+        let found = find(&[root.clone(), "-".into()]);
+        let empty = find(&[root.join("empty")]);
+        let main = entry(&root);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            found.unwrap(),
+            [root.join("a/z.drip"), root.join("b.drip"), "-".into()]
+        );
+        assert_eq!(
+            empty.unwrap_err(),
+            format!(
+                "there are no `.drip` files in `{}`",
+                root.join("empty").display()
+            )
+        );
+        assert_eq!(
+            main.unwrap_err(),
+            format!("there's no `main.drip` in `{}`", root.display())
+        );
+    }
+
+    #[test]
+    fn count_things() {
+        assert_eq!(plural(0, "error"), "0 errors");
+        assert_eq!(plural(1, "error"), "1 error");
+        assert_eq!(plural(2, "warning"), "2 warnings");
+    }
+}